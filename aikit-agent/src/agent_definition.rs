@@ -15,4 +15,8 @@ pub struct AgentPersona {
     pub disallowed_tools: Option<Vec<String>>,
     #[serde(default)]
     pub model: Option<String>,
+    /// Backend that runs this persona when it is spawned as a sub-agent
+    /// (`codex`, `claude`, ...). `None` runs it in the built-in loop.
+    #[serde(default)]
+    pub agent: Option<String>,
 }
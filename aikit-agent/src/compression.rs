@@ -1,22 +1,122 @@
-use crate::context::{ContextPacket, TurnRole};
+use crate::config::AgentConfig;
+use crate::context::{estimate_tokens, estimate_turn_tokens, ContextPacket, Turn, TurnRole};
 use crate::errors::AgentError;
+use crate::llm::{LlmGateway, LlmMessage, LlmRequest, LlmUsage};
+
+/// How old turns are condensed once the context exceeds its token budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionStrategy {
+    /// Ask the configured [`LlmGateway`] for a structured running summary
+    /// (goals, decisions, files changed, open questions). Falls back to
+    /// [`CompressionStrategy::Truncate`] when the call fails or comes back
+    /// empty.
+    #[default]
+    Summarize,
+    /// Keep a clipped one-line excerpt of every old turn. No LLM call.
+    Truncate,
+}
+
+impl std::str::FromStr for CompressionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "summarize" | "llm" => Ok(Self::Summarize),
+            "truncate" => Ok(Self::Truncate),
+            other => Err(format!(
+                "unknown compression strategy '{other}' (expected summarize|truncate)"
+            )),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CompressionResult {
     pub original_tokens: u64,
     pub compressed_tokens: u64,
     pub turns_summarized: u64,
+    /// The summary turn that replaced the old turns.
+    pub summary: String,
+    /// The strategy that produced `summary` — `Truncate` when `Summarize`
+    /// fell back.
+    pub strategy: CompressionStrategy,
+    /// Token usage of the summarization call, if one was made and reported.
+    pub usage: Option<LlmUsage>,
 }
 
-/// Compress the context packet if it exceeds the token budget.
+/// Instructions for the summarization call.
+const SUMMARY_INSTRUCTIONS: &str = "You maintain the running memory of a coding agent \
+whose older conversation turns are being dropped to save context. Summarize the \
+transcript below so the agent can continue the task without repeating work. Keep \
+concrete facts: file paths, identifiers, commands and their outcomes, error messages. \
+If the transcript begins with an earlier summary, fold it in. Reply with exactly \
+these sections, as short bullet lists (write \"- none\" when a section is empty):\n\
+## Goals\n## Decisions\n## Files changed\n## Open questions";
+
+/// Caps on each transcript field sent for summarization, so the request
+/// itself stays well inside the model's window.
+const MAX_CONTENT_CHARS: usize = 2000;
+const MAX_TOOL_CHARS: usize = 1000;
+
+/// Compress the context packet if it exceeds the token budget, using the
+/// truncating summary. See [`maybe_compress_with`] for the LLM strategy.
 ///
 /// Compression strategy:
 /// 1. Always preserve system_instructions verbatim
 /// 2. Always preserve the latest user prompt verbatim
 /// 3. Always preserve the latest tool result verbatim
-/// 4. Summarize oldest turns into a single summary turn
-/// 5. Idempotent: same input + budget → same output
+/// 4. Summarize oldest turns into a single summary turn, clipped to the
+///    budget the preserved turns leave
+///
+/// After compression the packet fits its budget unless the preserved turns
+/// alone exceed it, so calling again before new turns arrive is a no-op.
+/// A packet that is still over budget is compressed again, and the earlier
+/// summary is folded into the new one — with
+/// [`CompressionStrategy::Summarize`] that is another LLM call.
 pub fn maybe_compress(packet: &mut ContextPacket) -> Result<Option<CompressionResult>, AgentError> {
+    compress_if_needed(packet, |turns| {
+        (
+            truncated_summary(turns),
+            CompressionStrategy::Truncate,
+            None,
+        )
+    })
+}
+
+/// Like [`maybe_compress`], but honours `config.compression`: with
+/// [`CompressionStrategy::Summarize`] the old turns are summarized by
+/// `gateway` using `config.model`, falling back to truncation on failure.
+pub fn maybe_compress_with(
+    packet: &mut ContextPacket,
+    config: &AgentConfig,
+    gateway: &dyn LlmGateway,
+) -> Result<Option<CompressionResult>, AgentError> {
+    compress_if_needed(packet, |turns| match config.compression {
+        CompressionStrategy::Truncate => (
+            truncated_summary(turns),
+            CompressionStrategy::Truncate,
+            None,
+        ),
+        CompressionStrategy::Summarize => match llm_summary(turns, config, gateway) {
+            Ok((summary, usage)) => (summary, CompressionStrategy::Summarize, usage),
+            Err(reason) => {
+                tracing::warn!(%reason, "LLM context summary failed; truncating instead");
+                (
+                    truncated_summary(turns),
+                    CompressionStrategy::Truncate,
+                    None,
+                )
+            }
+        },
+    })
+}
+
+type Summarized = (String, CompressionStrategy, Option<LlmUsage>);
+
+fn compress_if_needed(
+    packet: &mut ContextPacket,
+    summarize: impl FnOnce(&[Turn]) -> Summarized,
+) -> Result<Option<CompressionResult>, AgentError> {
     let available = packet.token_budget.available();
     let current_tokens = packet.estimated_tokens();
 
@@ -25,20 +125,30 @@ pub fn maybe_compress(packet: &mut ContextPacket) -> Result<Option<CompressionRe
     }
 
     let original_tokens = current_tokens;
-    let result = compress(packet, available)?;
+    let Some((turns_summarized, (summary, strategy, usage))) = compress(packet, summarize)? else {
+        return Ok(None);
+    };
     let compressed_tokens = packet.estimated_tokens();
 
     Ok(Some(CompressionResult {
         original_tokens,
         compressed_tokens,
-        turns_summarized: result,
+        turns_summarized,
+        summary,
+        strategy,
+        usage,
     }))
 }
 
-fn compress(packet: &mut ContextPacket, _target_tokens: u64) -> Result<u64, AgentError> {
+/// Replace every turn before the preserved tail with one summary turn.
+/// `None` when there is nothing old enough to summarize.
+fn compress(
+    packet: &mut ContextPacket,
+    summarize: impl FnOnce(&[Turn]) -> Summarized,
+) -> Result<Option<(u64, Summarized)>, AgentError> {
     let conversation = &mut packet.conversation;
     if conversation.is_empty() {
-        return Ok(0);
+        return Ok(None);
     }
 
     let last_user_idx = conversation.iter().rposition(|t| t.role == TurnRole::User);
@@ -57,30 +167,114 @@ fn compress(packet: &mut ContextPacket, _target_tokens: u64) -> Result<u64, Agen
         .unwrap_or(conversation.len());
 
     if preserve_from == 0 {
-        return Ok(0);
+        return Ok(None);
     }
 
     let turns_to_summarize: Vec<_> = conversation.drain(..preserve_from).collect();
     let turns_summarized = turns_to_summarize.len() as u64;
 
-    let summary = build_summary(&turns_to_summarize);
+    let mut summarized = summarize(&turns_to_summarize);
+
+    // Whatever the preserved tail leaves of the budget is all the summary
+    // may use; without the cap a long summary keeps the packet over budget
+    // and every later turn compresses it again.
+    let kept = estimate_tokens(&packet.system_instructions)
+        + conversation.iter().map(estimate_turn_tokens).sum::<u64>()
+        + estimate_turn_tokens(&Turn::assistant(String::new()));
+    let room = packet.token_budget.available().saturating_sub(kept);
+    summarized.0 = clip_to_tokens(&summarized.0, room).to_string();
 
-    let summary_turn = crate::context::Turn::assistant(summary);
-    conversation.insert(0, summary_turn);
+    conversation.insert(0, Turn::assistant(summarized.0.clone()));
 
-    Ok(turns_summarized)
+    Ok(Some((turns_summarized, summarized)))
 }
 
-fn build_summary(turns: &[crate::context::Turn]) -> String {
+/// Ask `gateway` for a structured summary of `turns`.
+fn llm_summary(
+    turns: &[Turn],
+    config: &AgentConfig,
+    gateway: &dyn LlmGateway,
+) -> Result<(String, Option<LlmUsage>), String> {
+    let req = LlmRequest {
+        model: config.model.clone(),
+        base_url: config.base_url.clone(),
+        api_key: config.api_key.clone(),
+        messages: vec![
+            LlmMessage {
+                role: "system".to_string(),
+                content: Some(SUMMARY_INSTRUCTIONS.to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+            },
+            LlmMessage {
+                role: "user".to_string(),
+                content: Some(render_transcript(turns)),
+                tool_calls: None,
+                tool_call_id: None,
+            },
+        ],
+        tools: vec![],
+        tool_choice: None,
+        temperature: Some(0.0),
+        top_p: None,
+        max_tokens: None,
+        stream: false,
+    };
+    let response = gateway.complete(req).map_err(|e| e.to_string())?;
+    let text = response.content.unwrap_or_default();
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("empty summary".to_string());
+    }
+    Ok((
+        format!("[Summary of {} turns]\n{}", turns.len(), text),
+        response.usage,
+    ))
+}
+
+/// The turns as plain text for the summarization prompt.
+fn render_transcript(turns: &[Turn]) -> String {
+    let mut out = String::new();
+    for turn in turns {
+        if !turn.content.is_empty() {
+            out.push_str(&format!(
+                "[{}]: {}\n",
+                role_label(&turn.role),
+                truncate(&turn.content, MAX_CONTENT_CHARS)
+            ));
+        }
+        for call in turn.tool_calls.iter().flatten() {
+            out.push_str(&format!(
+                "[Tool call: {}({})]\n",
+                call.name,
+                truncate(&call.arguments, MAX_TOOL_CHARS)
+            ));
+        }
+        for result in turn.tool_results.iter().flatten() {
+            let label = if result.is_error {
+                "Tool error"
+            } else {
+                "Tool result"
+            };
+            out.push_str(&format!(
+                "[{}: {}]\n",
+                label,
+                truncate(&result.output, MAX_TOOL_CHARS)
+            ));
+        }
+    }
+    out
+}
+
+fn truncated_summary(turns: &[Turn]) -> String {
     let mut parts = Vec::new();
     for turn in turns {
-        let role_str = match turn.role {
-            TurnRole::User => "User",
-            TurnRole::Assistant => "Assistant",
-            TurnRole::Tool => "Tool",
-        };
         if !turn.content.is_empty() {
-            parts.push(format!("[{}]: {}", role_str, truncate(&turn.content, 80)));
+            parts.push(format!(
+                "[{}]: {}",
+                role_label(&turn.role),
+                truncate(&turn.content, 80)
+            ));
         }
         if let Some(calls) = &turn.tool_calls {
             for call in calls {
@@ -100,6 +294,32 @@ fn build_summary(turns: &[crate::context::Turn]) -> String {
     format!("[Summary of {} turns]\n{}", turns.len(), parts.join("\n"))
 }
 
+fn role_label(role: &TurnRole) -> &'static str {
+    match role {
+        TurnRole::User => "User",
+        TurnRole::Assistant => "Assistant",
+        TurnRole::Tool => "Tool",
+    }
+}
+
+/// The longest prefix of `s`, cut after a whole word, whose
+/// [`estimate_tokens`] is at most `max_tokens`.
+fn clip_to_tokens(s: &str, max_tokens: u64) -> &str {
+    if estimate_tokens(s) <= max_tokens {
+        return s;
+    }
+    let mut words = max_tokens;
+    while words + words / 3 > max_tokens {
+        words -= 1;
+    }
+    let end = s
+        .split_whitespace()
+        .take(words as usize)
+        .last()
+        .map_or(0, |w| w.as_ptr() as usize - s.as_ptr() as usize + w.len());
+    &s[..end]
+}
+
 fn truncate(s: &str, max: usize) -> &str {
     if s.len() <= max {
         s
@@ -116,6 +336,7 @@ fn truncate(s: &str, max: usize) -> &str {
 mod tests {
     use super::*;
     use crate::context::{ContextPacket, TokenBudget, Turn};
+    use crate::llm::mock::{MockGateway, MockResponse};
 
     fn make_packet_over_budget() -> ContextPacket {
         // Use a very small budget to force compression
@@ -179,35 +400,61 @@ mod tests {
     fn test_context_compression_idempotent() {
         let mut packet = make_packet_over_budget();
 
-        // Compress once
-        maybe_compress(&mut packet).unwrap();
+        // Compress once: the clipped summary brings the packet within budget
+        maybe_compress(&mut packet).unwrap().unwrap();
+        assert!(packet.estimated_tokens() <= packet.token_budget.available());
         let state_after_first: Vec<_> = packet
             .conversation
             .iter()
             .map(|t| t.content.clone())
             .collect();
-        let tokens_after_first = packet.estimated_tokens();
 
-        // Compress again with same packet
-        maybe_compress(&mut packet).unwrap();
+        // Compressing again is a no-op
+        assert!(maybe_compress(&mut packet).unwrap().is_none());
         let state_after_second: Vec<_> = packet
             .conversation
             .iter()
             .map(|t| t.content.clone())
             .collect();
-        let tokens_after_second = packet.estimated_tokens();
+        assert_eq!(state_after_first, state_after_second);
+    }
 
-        // Second compression should not further reduce (already within budget)
-        // The state may differ slightly due to re-summarization, but tokens should be stable
-        assert!(
-            tokens_after_second <= tokens_after_first + 10,
-            "second compression should not increase tokens significantly"
-        );
+    #[test]
+    fn test_summarize_caps_summary_and_is_idempotent() {
+        let mut packet = make_packet_over_budget();
+        let long_summary = "- the user asked a numbered question and got an answer\n".repeat(50);
+        let gw = RecordingGateway::new(vec![
+            MockResponse::text(long_summary.clone()),
+            MockResponse::text(long_summary),
+        ]);
+        let config = make_config(CompressionStrategy::Summarize);
+
+        let result = maybe_compress_with(&mut packet, &config, &gw)
+            .unwrap()
+            .expect("compression should have occurred");
+        assert_eq!(result.strategy, CompressionStrategy::Summarize);
+        assert!(result.summary.starts_with("[Summary of 18 turns]"));
+        assert!(result.compressed_tokens <= packet.token_budget.available());
         assert_eq!(
-            state_after_first.len(),
-            state_after_second.len(),
-            "conversation length should be stable after repeated compression"
+            packet.conversation.last().unwrap().content,
+            "Assistant answer number 9"
         );
+
+        let before: Vec<_> = packet
+            .conversation
+            .iter()
+            .map(|t| t.content.clone())
+            .collect();
+        assert!(maybe_compress_with(&mut packet, &config, &gw)
+            .unwrap()
+            .is_none());
+        let after: Vec<_> = packet
+            .conversation
+            .iter()
+            .map(|t| t.content.clone())
+            .collect();
+        assert_eq!(before, after);
+        assert_eq!(gw.requests.lock().unwrap().len(), 1, "no second LLM call");
     }
 
     #[test]
@@ -313,4 +560,139 @@ mod tests {
             }
         }
     }
+
+    fn make_config(compression: CompressionStrategy) -> AgentConfig {
+        AgentConfig {
+            model: "test-model".to_string(),
+            base_url: "http://localhost".to_string(),
+            api_key: "test-key".to_string(),
+            stream: false,
+            max_iterations: 3,
+            max_subagent_depth: 0,
+            context_budget_tokens: 50,
+            compression,
+            max_parallel_tools: 4,
+            workdir: std::env::temp_dir(),
+            allowed_roots: vec![],
+            skills_dirs: vec![],
+            agents_md_path: None,
+            timeout_secs: 30,
+            connect_timeout_secs: 5,
+            session_persona: None,
+            session_agents: std::collections::HashMap::new(),
+            host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: Default::default(),
+        }
+    }
+
+    /// Records every request and answers from a [`MockGateway`].
+    struct RecordingGateway {
+        requests: std::sync::Mutex<Vec<LlmRequest>>,
+        inner: MockGateway,
+    }
+
+    impl RecordingGateway {
+        fn new(responses: Vec<MockResponse>) -> Self {
+            Self {
+                requests: std::sync::Mutex::new(Vec::new()),
+                inner: MockGateway::new(responses),
+            }
+        }
+    }
+
+    impl LlmGateway for RecordingGateway {
+        fn complete(
+            &self,
+            req: LlmRequest,
+        ) -> Result<crate::llm::LlmResponse, crate::llm::LlmError> {
+            self.requests.lock().unwrap().push(req.clone());
+            self.inner.complete(req)
+        }
+
+        fn stream(
+            &self,
+            req: LlmRequest,
+        ) -> Result<crate::llm::LlmStreamHandle, crate::llm::LlmError> {
+            self.requests.lock().unwrap().push(req.clone());
+            self.inner.stream(req)
+        }
+    }
+
+    #[test]
+    fn test_summarize_strategy_uses_llm_summary() {
+        let mut packet = make_packet_over_budget();
+        let gw = RecordingGateway::new(vec![MockResponse::text(
+            "## Goals\n- answer questions\n## Decisions\n- none\n## Files changed\n- none\n## Open questions\n- none",
+        )]);
+        let result = maybe_compress_with(
+            &mut packet,
+            &make_config(CompressionStrategy::Summarize),
+            &gw,
+        )
+        .unwrap()
+        .expect("compression should have occurred");
+
+        assert_eq!(result.strategy, CompressionStrategy::Summarize);
+        assert!(result.summary.contains("## Goals\n- answer questions"));
+        assert_eq!(packet.conversation[0].content, result.summary);
+
+        let requests = gw.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1, "one summarization call");
+        let transcript = requests[0].messages[1].content.as_deref().unwrap();
+        assert!(transcript.contains("[User]: User question number 0"));
+        assert!(requests[0].tools.is_empty());
+    }
+
+    #[test]
+    fn test_summarize_strategy_falls_back_to_truncation_on_error() {
+        let mut packet = make_packet_over_budget();
+        let gw = MockGateway::new(vec![MockResponse::error(
+            crate::llm::LlmError::RequestFailed {
+                message: "boom".to_string(),
+            },
+        )]);
+        let result = maybe_compress_with(
+            &mut packet,
+            &make_config(CompressionStrategy::Summarize),
+            &gw,
+        )
+        .unwrap()
+        .expect("compression should have occurred");
+
+        assert_eq!(result.strategy, CompressionStrategy::Truncate);
+        assert!(result.summary.starts_with("[Summary of "));
+        assert!(result.summary.contains("[User]: User question number 0"));
+        assert!(result.usage.is_none());
+    }
+
+    #[test]
+    fn test_truncate_strategy_makes_no_llm_call() {
+        let mut packet = make_packet_over_budget();
+        let gw = RecordingGateway::new(vec![]);
+        let result = maybe_compress_with(
+            &mut packet,
+            &make_config(CompressionStrategy::Truncate),
+            &gw,
+        )
+        .unwrap()
+        .expect("compression should have occurred");
+
+        assert_eq!(result.strategy, CompressionStrategy::Truncate);
+        assert!(gw.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compression_strategy_from_str() {
+        assert_eq!(
+            "summarize".parse::<CompressionStrategy>(),
+            Ok(CompressionStrategy::Summarize)
+        );
+        assert_eq!(
+            " Truncate ".parse::<CompressionStrategy>(),
+            Ok(CompressionStrategy::Truncate)
+        );
+        assert!("shrink".parse::<CompressionStrategy>().is_err());
+    }
 }
@@ -3,9 +3,13 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::agent_definition::AgentPersona;
+use crate::compression::CompressionStrategy;
 use crate::errors::AgentError;
 use crate::host_tools::HostToolProvider;
 use crate::llm::openai_compat::resolve_api_key;
+use crate::mcp::McpServerConfig;
+use crate::subagents::SubagentDelegate;
+use crate::web::WebConfig;
 
 #[derive(Clone)]
 pub struct AgentConfig {
@@ -16,6 +20,12 @@ pub struct AgentConfig {
     pub max_iterations: u32,
     pub max_subagent_depth: u32,
     pub context_budget_tokens: u64,
+    /// How old turns are condensed when the context exceeds its budget.
+    pub compression: CompressionStrategy,
+    /// Upper bound on tool calls from one assistant turn that run at once.
+    /// Only tools that report [`crate::tools::Tool::parallel_safe`] run
+    /// concurrently; `1` executes every call serially.
+    pub max_parallel_tools: usize,
     pub workdir: PathBuf,
     pub allowed_roots: Vec<PathBuf>,
     pub skills_dirs: Vec<PathBuf>,
@@ -28,6 +38,15 @@ pub struct AgentConfig {
     pub session_agents: HashMap<String, AgentPersona>,
     /// Optional host tool provider injected by the embedder.
     pub host_tool_provider: Option<Arc<dyn HostToolProvider>>,
+    /// Runs `spawn_subagent` tasks addressed to external Backends (Claude,
+    /// Codex, ...). Injected by the embedder; `None` keeps sub-agents
+    /// in-process.
+    pub subagent_delegate: Option<Arc<dyn SubagentDelegate>>,
+    /// MCP servers whose tools join the catalog as `mcp__<server>__<tool>`.
+    /// Set by the embedder after `from_env`.
+    pub mcp_servers: Vec<McpServerConfig>,
+    /// Domain policy and search backend for `web_fetch` / `web_search`.
+    pub web: WebConfig,
 }
 
 impl std::fmt::Debug for AgentConfig {
@@ -38,6 +57,8 @@ impl std::fmt::Debug for AgentConfig {
             .field("stream", &self.stream)
             .field("max_iterations", &self.max_iterations)
             .field("max_subagent_depth", &self.max_subagent_depth)
+            .field("compression", &self.compression)
+            .field("max_parallel_tools", &self.max_parallel_tools)
             .field("workdir", &self.workdir)
             .field(
                 "host_tool_provider",
@@ -46,6 +67,18 @@ impl std::fmt::Debug for AgentConfig {
                     .as_ref()
                     .map(|_| "<HostToolProvider>"),
             )
+            .field(
+                "subagent_delegate",
+                &self
+                    .subagent_delegate
+                    .as_ref()
+                    .map(|_| "<SubagentDelegate>"),
+            )
+            .field(
+                "mcp_servers",
+                &self.mcp_servers.iter().map(|s| &s.name).collect::<Vec<_>>(),
+            )
+            .field("web", &self.web)
             .finish_non_exhaustive()
     }
 }
@@ -90,6 +123,17 @@ impl AgentConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(12000u64);
 
+        let compression = std::env::var("AIKIT_COMPRESSION")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default();
+
+        let max_parallel_tools = std::env::var("AIKIT_MAX_PARALLEL_TOOLS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4usize)
+            .max(1);
+
         let agents_md_path = {
             let agents_md = workdir.join("AGENTS.md");
             let claude_md = workdir.join("CLAUDE.md");
@@ -124,6 +168,8 @@ impl AgentConfig {
             max_iterations,
             max_subagent_depth,
             context_budget_tokens,
+            compression,
+            max_parallel_tools,
             workdir,
             allowed_roots,
             skills_dirs,
@@ -133,6 +179,9 @@ impl AgentConfig {
             session_persona: None,
             session_agents: HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: WebConfig::from_env(),
         })
     }
 }
@@ -195,6 +244,34 @@ mod tests {
         env::remove_var("AIKIT_MODEL");
     }
 
+    #[test]
+    fn test_from_env_compression_strategy() {
+        let _guard = crate::test_support::env_lock();
+        env::set_var("OPENAI_API_KEY", "test-key");
+        env::remove_var("AIKIT_COMPRESSION");
+        let config = AgentConfig::from_env(std::env::current_dir().unwrap(), false, None).unwrap();
+        assert_eq!(config.compression, CompressionStrategy::Summarize);
+        env::set_var("AIKIT_COMPRESSION", "truncate");
+        let config = AgentConfig::from_env(std::env::current_dir().unwrap(), false, None).unwrap();
+        assert_eq!(config.compression, CompressionStrategy::Truncate);
+        env::remove_var("OPENAI_API_KEY");
+        env::remove_var("AIKIT_COMPRESSION");
+    }
+
+    #[test]
+    fn test_from_env_max_parallel_tools() {
+        let _guard = crate::test_support::env_lock();
+        env::set_var("OPENAI_API_KEY", "test-key");
+        env::remove_var("AIKIT_MAX_PARALLEL_TOOLS");
+        let config = AgentConfig::from_env(std::env::current_dir().unwrap(), false, None).unwrap();
+        assert_eq!(config.max_parallel_tools, 4);
+        env::set_var("AIKIT_MAX_PARALLEL_TOOLS", "0");
+        let config = AgentConfig::from_env(std::env::current_dir().unwrap(), false, None).unwrap();
+        assert_eq!(config.max_parallel_tools, 1, "0 is clamped to serial");
+        env::remove_var("OPENAI_API_KEY");
+        env::remove_var("AIKIT_MAX_PARALLEL_TOOLS");
+    }
+
     #[test]
     fn test_from_env_prefers_agents_md() {
         let _guard = crate::test_support::env_lock();
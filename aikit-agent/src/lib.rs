@@ -6,15 +6,25 @@ pub mod errors;
 pub mod host_tools;
 pub mod llm;
 pub mod loop_runner;
+pub mod mcp;
+pub mod session;
 pub mod skills;
 pub mod subagents;
 pub mod tools;
+pub mod web;
+pub mod worktree;
 
 pub use agent_definition::AgentPersona;
+pub use compression::CompressionStrategy;
 pub use config::AgentConfig;
 pub use errors::AgentError;
 pub use host_tools::{HostToolDefinition, HostToolProvider};
 pub use llm::{LlmError, LlmGateway, LlmRequest, LlmResponse, LlmStreamEvent, LlmUsage};
+pub use mcp::{McpServerConfig, McpServerTransport};
+pub use session::{AgentSession, CancelHandle, ContextUsage};
+pub use subagents::{DelegateTask, SubAgentResult, SubAgentStatus, SubagentDelegate};
+pub use web::{SearchProvider, SearchResult, SearxngProvider, WebConfig};
+pub use worktree::{Worktree, WorktreeError, WorktreePatch};
 
 #[derive(Debug, Clone)]
 pub enum AgentInternalEvent {
@@ -40,17 +50,28 @@ pub enum AgentInternalEvent {
         subagent_id: String,
         workdir: String,
     },
+    /// An event from a sub-agent delegated to an external Backend: the
+    /// Backend's canonical event, serialized by the [`SubagentDelegate`].
+    SubagentEvent {
+        subagent_id: String,
+        event: serde_json::Value,
+    },
     SubagentResult {
         subagent_id: String,
         status: String,
         changed_files: Vec<String>,
         key_findings: String,
         final_message: String,
+        /// The sub-agent's changes as a `git diff`, when it ran in an
+        /// isolated worktree.
+        patch: Option<String>,
     },
     ContextCompressed {
         original_tokens: u64,
         compressed_tokens: u64,
         turns_summarized: u64,
+        /// The summary turn that replaced the old turns.
+        summary: String,
     },
     StepFinish {
         iteration: u32,
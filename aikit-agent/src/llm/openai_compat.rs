@@ -53,7 +53,7 @@ fn build_client(timeout_secs: u64, connect_timeout_secs: u64) -> Result<reqwest:
         })
 }
 
-fn block_on_async<F, T>(future: F) -> T
+pub(crate) fn block_on_async<F, T>(future: F) -> T
 where
     F: std::future::Future<Output = T>,
 {
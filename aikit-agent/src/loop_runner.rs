@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
 
-use crate::compression::maybe_compress;
+use crate::compression::maybe_compress_with;
 use crate::config::AgentConfig;
 use crate::context::{ContextPacket, ContextToolCall, ContextToolResult, TokenBudget, Turn};
 use crate::errors::AgentError;
 use crate::llm::gateway::LlmGateway;
 use crate::llm::types::{LlmMessage, LlmRequest, LlmStreamEvent, LlmUsage, ToolCall};
+use crate::mcp::{policy_matches, McpTool};
 use crate::skills::SkillProvider;
 
 #[cfg(not(feature = "fastskill"))]
@@ -47,7 +50,8 @@ pub fn run_streaming(
     mut on_event: impl FnMut(AgentInternalEvent),
 ) -> Result<(), AgentError> {
     let gateway: Arc<dyn LlmGateway> = Arc::from(gateway);
-    run_inner_streaming(config, prompt, gateway, &mut on_event)
+    let mcp_tools = connect_mcp(&config);
+    run_inner_streaming(config, prompt, gateway, mcp_tools, &mut on_event)
 }
 
 /// Vec-collecting variant of [`run_inner_streaming`] used only by the fastskill-backed
@@ -59,29 +63,57 @@ pub(crate) fn run_inner(
     prompt: &str,
     gateway: Arc<dyn LlmGateway>,
 ) -> Result<Vec<AgentInternalEvent>, AgentError> {
+    let mcp_tools = connect_mcp(&config);
     let mut events = Vec::new();
-    run_inner_streaming(config, prompt, gateway, &mut |e| events.push(e))?;
+    run_inner_streaming(config, prompt, gateway, mcp_tools, &mut |e| events.push(e))?;
     Ok(events)
 }
 
+/// Shared by top-level runs and in-process sub-agents, which pass their
+/// parent's `mcp_tools` and gateway.
 pub(crate) fn run_inner_streaming(
     config: AgentConfig,
     prompt: &str,
     gateway: Arc<dyn LlmGateway>,
+    mcp_tools: Vec<McpTool>,
     on_event: &mut impl FnMut(AgentInternalEvent),
 ) -> Result<(), AgentError> {
+    let (mut context, tools) = prepare_run(&config, &gateway, Vec::new(), mcp_tools)?;
+    context.add_turn(Turn::user(prompt));
+
+    run_loop(
+        &config,
+        &mut context,
+        &tools,
+        &gateway,
+        &AtomicBool::new(false),
+        on_event,
+    )?;
+
+    Ok(())
+}
+
+/// Everything a run needs before its first LLM call: the context packet
+/// (system instructions, skills summary, `prior_turns`) and the tool catalog.
+/// `mcp_tools` come from [`connect_mcp`], or from the parent of a sub-agent.
+pub(crate) fn prepare_run(
+    config: &AgentConfig,
+    gateway: &Arc<dyn LlmGateway>,
+    prior_turns: Vec<Turn>,
+    mcp_tools: Vec<McpTool>,
+) -> Result<(ContextPacket, Vec<Box<dyn Tool>>), AgentError> {
     // 1. Discover skills using the appropriate backend.
     // AK-08 DEFERRED (issue #29): Remote-skills parity for embedded runs. The embedded path
     // already supports local skills via config.skills_dirs (set from AIKIT_SKILLS_DIR or
     // .aikit/skills/). Remote skill resolution via the fastskill feature flag works identically
     // in both CLI and embedded paths when the feature is enabled. Full remote-skills parity
     // for embedded runs is deferred to a follow-up issue.
-    let (skills, provider) = discover_skills_for_run(&config)?;
+    let (skills, provider) = discover_skills_for_run(config)?;
 
     // 2. Build system instructions (includes skills catalog when skills are present)
     let skill_metadatas: Vec<crate::skills::SkillMetadata> =
         skills.iter().map(|s| s.metadata.clone()).collect();
-    let system_instructions = build_system_instructions(&config, &skill_metadatas)?;
+    let system_instructions = build_system_instructions(config, &skill_metadatas)?;
 
     // 3. Build initial context packet
     let budget = TokenBudget {
@@ -90,21 +122,15 @@ pub(crate) fn run_inner_streaming(
         reserve_for_output: 2000,
     };
     let mut context = ContextPacket::new(system_instructions, budget);
-    context.skills_summary = skills.iter().map(|s| s.metadata.clone()).collect();
-    context.add_turn(Turn::user(prompt));
+    context.skills_summary = skill_metadatas;
+    for turn in prior_turns {
+        context.add_turn(turn);
+    }
 
     // 4. Build available tools
-    let tools = build_tools(
-        &config,
-        Arc::clone(&gateway),
-        &skills,
-        Arc::clone(&provider),
-    );
-
-    // 5. Main agent loop
-    run_loop(&config, &mut context, &tools, &gateway, on_event)?;
+    let tools = build_tools(config, Arc::clone(gateway), &skills, provider, mcp_tools);
 
-    Ok(())
+    Ok((context, tools))
 }
 
 /// Run the agent loop starting from an existing context seeded with prior conversation
@@ -134,55 +160,67 @@ pub fn run_with_context_streaming(
 ) -> Result<(), AgentError> {
     let gateway: Arc<dyn LlmGateway> = Arc::from(gateway);
 
-    let (skills, provider) = discover_skills_for_run(&config)?;
-
-    let skill_metadatas: Vec<crate::skills::SkillMetadata> =
-        skills.iter().map(|s| s.metadata.clone()).collect();
-    let system_instructions = build_system_instructions(&config, &skill_metadatas)?;
-
-    let budget = TokenBudget {
-        total_budget: config.context_budget_tokens,
-        reserve_for_tools: 1000,
-        reserve_for_output: 2000,
-    };
-    let mut context = ContextPacket::new(system_instructions, budget);
-    context.skills_summary = skills.iter().map(|s| s.metadata.clone()).collect();
-
-    for turn in prior_turns {
-        context.add_turn(turn);
-    }
+    let mcp_tools = connect_mcp(&config);
+    let (mut context, tools) = prepare_run(&config, &gateway, prior_turns, mcp_tools)?;
     context.add_turn(Turn::user(new_prompt));
 
-    let tools = build_tools(
+    run_loop(
         &config,
-        Arc::clone(&gateway),
-        &skills,
-        Arc::clone(&provider),
-    );
-
-    run_loop(&config, &mut context, &tools, &gateway, &mut on_event)?;
+        &mut context,
+        &tools,
+        &gateway,
+        &AtomicBool::new(false),
+        &mut on_event,
+    )?;
 
     Ok(())
 }
 
-fn run_loop<F: FnMut(AgentInternalEvent)>(
+/// The finish reason reported for a step cut short by `cancel`.
+pub(crate) const INTERRUPTED: &str = "interrupted";
+
+/// Drive `context` until the model stops, iterations run out, or `cancel` is
+/// set. Cancellation is honoured at safe points — before each LLM call,
+/// between streamed chunks, and before each tool call — so the context always
+/// stays well-formed: a cut-off stream keeps its partial text and every tool
+/// call gets a result. The interrupted step finishes with [`INTERRUPTED`].
+pub(crate) fn run_loop<F: FnMut(AgentInternalEvent)>(
     config: &AgentConfig,
     context: &mut ContextPacket,
     tools: &[Box<dyn Tool>],
     gateway: &Arc<dyn LlmGateway>,
+    cancel: &AtomicBool,
     on_event: &mut F,
 ) -> Result<(), AgentError> {
     for iteration in 0..config.max_iterations {
+        if cancel.load(Ordering::SeqCst) {
+            on_event(AgentInternalEvent::StepFinish {
+                iteration,
+                finish_reason: INTERRUPTED.to_string(),
+            });
+            return Ok(());
+        }
+
         // Check context budget and compress if needed
         if let Some(compression) =
-            maybe_compress(context).map_err(|e| AgentError::ContextCompression {
-                message: e.to_string(),
+            maybe_compress_with(context, config, gateway.as_ref()).map_err(|e| {
+                AgentError::ContextCompression {
+                    message: e.to_string(),
+                }
             })?
         {
+            if let Some(u) = compression.usage {
+                on_event(AgentInternalEvent::TokenUsage {
+                    input_tokens: u.input_tokens,
+                    output_tokens: u.output_tokens,
+                    total_tokens: u.total_tokens,
+                });
+            }
             on_event(AgentInternalEvent::ContextCompressed {
                 original_tokens: compression.original_tokens,
                 compressed_tokens: compression.compressed_tokens,
                 turns_summarized: compression.turns_summarized,
+                summary: compression.summary,
             });
         }
 
@@ -192,7 +230,7 @@ fn run_loop<F: FnMut(AgentInternalEvent)>(
 
         // Call LLM
         let (response_text, tool_calls, finish_reason, usage) = if config.stream {
-            call_stream(gateway.as_ref(), req, on_event)?
+            call_stream(gateway.as_ref(), req, cancel, on_event)?
         } else {
             call_complete(gateway.as_ref(), req, on_event)?
         };
@@ -230,45 +268,30 @@ fn run_loop<F: FnMut(AgentInternalEvent)>(
         // Execute tool calls
         if !tool_calls.is_empty() {
             let tool_ctx = ToolContext::new(config.workdir.clone(), config.allowed_roots.clone());
-
-            let mut tool_results = Vec::new();
-            for tc in &tool_calls {
-                let call_id = tc.id.clone();
-                let tool_name = tc.function.name.clone();
-                let args: serde_json::Value =
-                    serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
-
-                on_event(AgentInternalEvent::ToolUse {
-                    tool_name: tool_name.clone(),
-                    tool_input: args.clone(),
-                    call_id: call_id.clone(),
-                });
-
-                let output = execute_tool(tools, &tool_name, args, &tool_ctx);
-
-                on_event(AgentInternalEvent::ToolResult {
-                    call_id: call_id.clone(),
-                    output: output.content.clone(),
-                    is_error: output.is_error,
-                });
-
-                tool_results.push(ContextToolResult {
-                    call_id,
-                    output: output.content,
-                    is_error: output.is_error,
-                });
-            }
-
+            let tool_results = execute_tool_calls(
+                tools,
+                &tool_calls,
+                &tool_ctx,
+                config.max_parallel_tools,
+                cancel,
+                on_event,
+            );
             context.add_turn(Turn::tool_result(tool_results));
         }
 
         // Emit step finish
+        let interrupted = cancel.load(Ordering::SeqCst);
+        let finish_reason = if interrupted {
+            INTERRUPTED.to_string()
+        } else {
+            finish_reason.unwrap_or_else(|| "unknown".to_string())
+        };
         on_event(AgentInternalEvent::StepFinish {
             iteration,
-            finish_reason: finish_reason.unwrap_or_else(|| "unknown".to_string()),
+            finish_reason,
         });
 
-        if done {
+        if done || interrupted {
             break;
         }
 
@@ -357,11 +380,25 @@ fn build_system_instructions(
     Ok(parts.join("\n\n"))
 }
 
+/// Connect `config.mcp_servers` once for a top-level run; sub-agents reuse
+/// the result instead of starting the servers again.
+pub(crate) fn connect_mcp(config: &AgentConfig) -> Vec<McpTool> {
+    if config.mcp_servers.is_empty() {
+        return Vec::new();
+    }
+    crate::mcp::connect_tools(
+        &config.mcp_servers,
+        &config.workdir,
+        Duration::from_secs(config.timeout_secs),
+    )
+}
+
 fn build_tools(
     config: &AgentConfig,
     gateway: Arc<dyn LlmGateway>,
     skills: &[crate::skills::DiscoveredSkill],
     provider: Arc<dyn SkillProvider>,
+    mcp_tools: Vec<McpTool>,
 ) -> Vec<Box<dyn Tool>> {
     let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(ReadFileTool),
@@ -374,13 +411,9 @@ fn build_tools(
         }),
     ];
 
-    // Only add spawn_subagent if max_subagent_depth > 0
-    if config.max_subagent_depth > 0 {
-        tools.push(Box::new(SpawnSubagentTool {
-            parent_config: config.clone(),
-            gateway,
-        }));
-    }
+    tools.extend(subagent_tool(config, gateway, mcp_tools.clone()));
+
+    tools.extend(crate::web::web_tools(&config.web));
 
     // Merge host tools, skipping any whose name collides with a built-in.
     if let Some(ref host_provider) = config.host_tool_provider {
@@ -401,23 +434,64 @@ fn build_tools(
         }
     }
 
+    // Merge MCP server tools. Their `mcp__` prefix keeps them clear of
+    // built-ins; a host tool that already claimed a name wins.
+    if !mcp_tools.is_empty() {
+        let taken: std::collections::HashSet<String> =
+            tools.iter().map(|t| t.name().to_string()).collect();
+        tools.extend(
+            mcp_tools
+                .into_iter()
+                .filter(|t| !taken.contains(t.name()))
+                .map(|t| Box::new(t) as Box<dyn Tool>),
+        );
+    }
+
     // Apply persona tool policy (hard filter at construction time).
-    if let Some(ref persona) = config.session_persona {
-        if let Some(ref allowlist) = persona.tools {
-            tools.retain(|t| allowlist.iter().any(|a| a == t.name()));
+    tools.retain(|t| persona_allows(config, t.name()));
+
+    tools
+}
+
+/// Whether `config.session_persona`'s allow- and deny-lists let the run use
+/// the tool `name`.
+pub(crate) fn persona_allows(config: &AgentConfig, name: &str) -> bool {
+    let Some(ref persona) = config.session_persona else {
+        return true;
+    };
+    if let Some(ref allowlist) = persona.tools {
+        if !allowlist.iter().any(|a| policy_matches(a, name)) {
+            return false;
         }
-        if let Some(ref denylist) = persona.disallowed_tools {
-            tools.retain(|t| {
-                let keep = !denylist.iter().any(|d| d == t.name());
-                if !keep {
-                    tracing::debug!(tool = %t.name(), "persona denylist removed tool");
-                }
-                keep
-            });
+    }
+    if let Some(ref denylist) = persona.disallowed_tools {
+        if denylist.iter().any(|d| policy_matches(d, name)) {
+            tracing::debug!(tool = %name, "persona denylist removed tool");
+            return false;
         }
     }
+    true
+}
 
-    tools
+/// `spawn_subagent`, which runs children on the parent's model and gateway.
+/// Only offered while `max_subagent_depth > 0`. Children get only the MCP
+/// tools the parent's persona allows.
+pub(crate) fn subagent_tool(
+    config: &AgentConfig,
+    gateway: Arc<dyn LlmGateway>,
+    mcp_tools: Vec<McpTool>,
+) -> Option<Box<dyn Tool>> {
+    (config.max_subagent_depth > 0).then(|| {
+        let mcp_tools = mcp_tools
+            .into_iter()
+            .filter(|t| persona_allows(config, t.name()))
+            .collect();
+        Box::new(SpawnSubagentTool {
+            parent_config: config.clone(),
+            gateway,
+            mcp_tools,
+        }) as Box<dyn Tool>
+    })
 }
 
 fn build_llm_request(
@@ -535,6 +609,7 @@ fn call_complete<F: FnMut(AgentInternalEvent)>(
 fn call_stream<F: FnMut(AgentInternalEvent)>(
     gateway: &dyn LlmGateway,
     req: LlmRequest,
+    cancel: &AtomicBool,
     on_event: &mut F,
 ) -> Result<LlmCallResult, AgentError> {
     let handle = gateway.stream(req).map_err(|e| {
@@ -551,6 +626,17 @@ fn call_stream<F: FnMut(AgentInternalEvent)>(
     let mut pending_tool: Option<PartialToolCall> = None;
 
     for event_result in handle {
+        if cancel.load(Ordering::SeqCst) {
+            // Dropping the handle closes the stream. Half-streamed tool calls
+            // are discarded: they never ran, so the context must not claim them.
+            return Ok(finish_stream(
+                full_text,
+                Vec::new(),
+                finish_reason,
+                usage,
+                on_event,
+            ));
+        }
         match event_result {
             Ok(event) => match event {
                 LlmStreamEvent::TextDelta { content } => {
@@ -616,12 +702,27 @@ fn call_stream<F: FnMut(AgentInternalEvent)>(
         tool_calls.push(tc.into_tool_call());
     }
 
+    Ok(finish_stream(
+        full_text,
+        tool_calls,
+        finish_reason,
+        usage,
+        on_event,
+    ))
+}
+
+fn finish_stream<F: FnMut(AgentInternalEvent)>(
+    full_text: String,
+    tool_calls: Vec<ToolCall>,
+    finish_reason: Option<String>,
+    usage: Option<LlmUsage>,
+    on_event: &mut F,
+) -> LlmCallResult {
     on_event(AgentInternalEvent::TextFinal {
         content: full_text.clone(),
         turn_id: None,
     });
-
-    Ok((full_text, tool_calls, finish_reason, usage))
+    (full_text, tool_calls, finish_reason, usage)
 }
 
 struct PartialToolCall {
@@ -643,6 +744,129 @@ impl PartialToolCall {
     }
 }
 
+/// Execute one assistant turn's tool calls.
+///
+/// Consecutive [`Tool::parallel_safe`] calls form a batch that runs
+/// on up to `max_parallel` threads; every other call runs alone, so mutating
+/// tools keep the order the model issued them in. Per batch, all `ToolUse`
+/// events are emitted before execution and all `ToolResult` events after it,
+/// each in call order, and the returned results match `tool_calls`
+/// one-to-one. Once `cancel` is set, calls that have not started yet are
+/// skipped with an error result.
+fn execute_tool_calls<F: FnMut(AgentInternalEvent)>(
+    tools: &[Box<dyn Tool>],
+    tool_calls: &[ToolCall],
+    ctx: &ToolContext,
+    max_parallel: usize,
+    cancel: &AtomicBool,
+    on_event: &mut F,
+) -> Vec<ContextToolResult> {
+    let parallel = |tc: &ToolCall| {
+        max_parallel > 1
+            && tools
+                .iter()
+                .find(|t| t.name() == tc.function.name)
+                .is_some_and(|t| {
+                    t.parallel_safe(
+                        &serde_json::from_str(&tc.function.arguments)
+                            .unwrap_or(serde_json::Value::Null),
+                    )
+                })
+    };
+
+    let mut results = Vec::with_capacity(tool_calls.len());
+    let mut start = 0;
+    while start < tool_calls.len() {
+        let len = tool_calls[start..]
+            .iter()
+            .take_while(|tc| parallel(tc))
+            .count()
+            .max(1);
+        let batch = &tool_calls[start..start + len];
+        start += len;
+
+        let inputs: Vec<serde_json::Value> = batch
+            .iter()
+            .map(|tc| {
+                let args: serde_json::Value =
+                    serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+                on_event(AgentInternalEvent::ToolUse {
+                    tool_name: tc.function.name.clone(),
+                    tool_input: args.clone(),
+                    call_id: tc.id.clone(),
+                });
+                args
+            })
+            .collect();
+
+        let outputs = execute_batch(tools, batch, inputs, ctx, max_parallel, cancel, on_event);
+
+        for (tc, output) in batch.iter().zip(outputs) {
+            on_event(AgentInternalEvent::ToolResult {
+                call_id: tc.id.clone(),
+                output: output.content.clone(),
+                is_error: output.is_error,
+            });
+            results.push(ContextToolResult {
+                call_id: tc.id.clone(),
+                output: output.content,
+                is_error: output.is_error,
+            });
+        }
+    }
+    results
+}
+
+/// Run `batch` with at most `max_parallel` calls in flight; outputs are
+/// returned in `batch` order regardless of completion order. Events the tools
+/// emit through [`ToolContext::emit`] are forwarded to `on_event` live, from
+/// this thread, while the batch runs.
+fn execute_batch<F: FnMut(AgentInternalEvent)>(
+    tools: &[Box<dyn Tool>],
+    batch: &[ToolCall],
+    inputs: Vec<serde_json::Value>,
+    ctx: &ToolContext,
+    max_parallel: usize,
+    cancel: &AtomicBool,
+    on_event: &mut F,
+) -> Vec<crate::tools::ToolOutput> {
+    let (tx, rx) = mpsc::channel();
+    let ctx = ToolContext {
+        events: Some(tx),
+        ..ctx.clone()
+    };
+    let jobs = Mutex::new(inputs.into_iter().enumerate());
+    let outputs = Mutex::new(vec![None; batch.len()]);
+    std::thread::scope(|s| {
+        for _ in 0..max_parallel.clamp(1, batch.len()) {
+            let ctx = ctx.clone();
+            let (jobs, outputs) = (&jobs, &outputs);
+            s.spawn(move || loop {
+                let Some((i, input)) = jobs.lock().unwrap().next() else {
+                    break;
+                };
+                let output = if cancel.load(Ordering::SeqCst) {
+                    crate::tools::ToolOutput::err(format!("{INTERRUPTED}: tool call skipped"))
+                } else {
+                    execute_tool(tools, &batch[i].function.name, input, &ctx)
+                };
+                outputs.lock().unwrap()[i] = Some(output);
+            });
+        }
+        // Workers hold the only senders now, so this ends once they finish.
+        drop(ctx);
+        for event in rx {
+            on_event(event);
+        }
+    });
+    outputs
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|o| o.expect("every queued tool call ran"))
+        .collect()
+}
+
 fn execute_tool(
     tools: &[Box<dyn Tool>],
     name: &str,
@@ -731,6 +955,8 @@ mod tests {
             max_iterations: 3,
             max_subagent_depth: 2,
             context_budget_tokens: 12000,
+            compression: Default::default(),
+            max_parallel_tools: 4,
             workdir: tmp.path().to_path_buf(),
             allowed_roots: vec![tmp.path().to_path_buf()],
             skills_dirs: vec![],
@@ -740,6 +966,9 @@ mod tests {
             session_persona: None,
             session_agents: std::collections::HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: Default::default(),
         }
     }
 
@@ -1473,7 +1702,7 @@ mod tests {
         let skills = vec![];
         let provider: Arc<dyn crate::skills::SkillProvider> =
             Arc::new(crate::skills::FilesystemSkillProvider);
-        let tools = build_tools(&config, gateway, &skills, provider);
+        let tools = build_tools(&config, gateway, &skills, provider, Vec::new());
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(
             names.contains(&"my_host_tool"),
@@ -1493,7 +1722,7 @@ mod tests {
         let skills = vec![];
         let provider: Arc<dyn crate::skills::SkillProvider> =
             Arc::new(crate::skills::FilesystemSkillProvider);
-        let tools = build_tools(&config, gateway, &skills, provider);
+        let tools = build_tools(&config, gateway, &skills, provider, Vec::new());
         let run_bash_count = tools.iter().filter(|t| t.name() == "run_bash").count();
         assert_eq!(
             run_bash_count, 1,
@@ -1513,6 +1742,7 @@ mod tests {
             description: String::new(),
             prompt: String::new(),
             model: None,
+            agent: None,
             tools: Some(vec!["read_file".to_string()]),
             disallowed_tools: None,
         });
@@ -1520,7 +1750,7 @@ mod tests {
         let skills = vec![];
         let provider: Arc<dyn crate::skills::SkillProvider> =
             Arc::new(crate::skills::FilesystemSkillProvider);
-        let tools = build_tools(&config, gateway, &skills, provider);
+        let tools = build_tools(&config, gateway, &skills, provider, Vec::new());
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(
             !names.contains(&"my_host_tool"),
@@ -1546,6 +1776,7 @@ mod tests {
             description: String::new(),
             prompt: String::new(),
             model: None,
+            agent: None,
             tools: None,
             disallowed_tools: Some(vec!["my_host_tool".to_string()]),
         });
@@ -1553,7 +1784,7 @@ mod tests {
         let skills = vec![];
         let provider: Arc<dyn crate::skills::SkillProvider> =
             Arc::new(crate::skills::FilesystemSkillProvider);
-        let tools = build_tools(&config, gateway, &skills, provider);
+        let tools = build_tools(&config, gateway, &skills, provider, Vec::new());
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(
             !names.contains(&"my_host_tool"),
@@ -1561,4 +1792,407 @@ mod tests {
             names
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mcp_tools_merged_and_persona_denies_whole_server() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = make_config(&tmp, false);
+        config.mcp_servers = vec![crate::mcp::tests::scripted_stdio_server()];
+        let gateway: Arc<dyn LlmGateway> = Arc::new(MockGateway::new(vec![]));
+        let provider: Arc<dyn crate::skills::SkillProvider> =
+            Arc::new(crate::skills::FilesystemSkillProvider);
+
+        let tools = build_tools(
+            &config,
+            Arc::clone(&gateway),
+            &[],
+            Arc::clone(&provider),
+            connect_mcp(&config),
+        );
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert!(names.contains(&"mcp__fake__echo"), "{names:?}");
+
+        config.session_persona = Some(crate::agent_definition::AgentPersona {
+            name: "test".to_string(),
+            description: String::new(),
+            prompt: String::new(),
+            model: None,
+            agent: None,
+            tools: None,
+            disallowed_tools: Some(vec!["mcp__fake".to_string()]),
+        });
+        let tools = build_tools(&config, gateway, &[], provider, connect_mcp(&config));
+        assert!(tools.iter().all(|t| !t.name().starts_with("mcp__")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_persona_denied_mcp_tools_stay_hidden_from_subagents() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = make_config(&tmp, false);
+        config.mcp_servers = vec![crate::mcp::tests::scripted_stdio_server()];
+        config.session_persona = Some(crate::agent_definition::AgentPersona {
+            name: "test".to_string(),
+            description: String::new(),
+            prompt: String::new(),
+            model: None,
+            agent: None,
+            tools: None,
+            disallowed_tools: Some(vec!["mcp__fake".to_string()]),
+        });
+        let spawn = serde_json::json!({
+            "workdir": tmp.path().to_str().unwrap(),
+            "prompt": "echo something"
+        });
+        let gw = CapturingGateway::new(vec![
+            MockResponse::tool_call("c1", "spawn_subagent", spawn.to_string()),
+            MockResponse::text("child done"),
+            MockResponse::text("parent done"),
+        ]);
+        let captured = std::sync::Arc::clone(&gw.captured);
+
+        run(config, "delegate", Box::new(gw)).unwrap();
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        for req in requests.iter() {
+            assert!(
+                req.tools
+                    .iter()
+                    .all(|t| !t.function.name.starts_with("mcp__")),
+                "{:?}",
+                req.tools
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mcp_tool_call_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = make_config(&tmp, false);
+        config.mcp_servers = vec![crate::mcp::tests::scripted_stdio_server()];
+        let gw = MockGateway::new(vec![
+            MockResponse::tool_call("c1", "mcp__fake__echo", r#"{"text": "hi"}"#),
+            MockResponse::text("echoed"),
+        ]);
+
+        let events = run(config, "echo hi", Box::new(gw)).unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AgentInternalEvent::ToolUse { tool_name, .. } if tool_name == "mcp__fake__echo"
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AgentInternalEvent::ToolResult { output, is_error: false, .. } if output == "hi"
+        )));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_subagent_reuses_parent_mcp_connection() {
+        let tmp = TempDir::new().unwrap();
+        let spawns = tmp.path().join("spawns");
+        let mut server = crate::mcp::tests::scripted_stdio_server();
+        if let crate::mcp::McpServerTransport::Stdio { args, .. } = &mut server.transport {
+            args[1] = format!("echo started >> '{}'\n{}", spawns.display(), args[1]);
+        }
+        let mut config = make_config(&tmp, false);
+        config.mcp_servers = vec![server];
+        let spawn_args = serde_json::json!({
+            "workdir": tmp.path().to_str().unwrap(),
+            "prompt": "echo hi"
+        });
+        let gw = MockGateway::new(vec![
+            MockResponse::tool_call("c1", "spawn_subagent", spawn_args.to_string()),
+            MockResponse::tool_call("c2", "mcp__fake__echo", r#"{"text": "hi"}"#),
+            MockResponse::text("child echoed"),
+            MockResponse::text("done"),
+        ]);
+
+        let events = run(config, "delegate", Box::new(gw)).unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AgentInternalEvent::SubagentResult { status, final_message, .. }
+                if status == "success" && final_message == "child echoed"
+        )));
+        let started = std::fs::read_to_string(&spawns).unwrap();
+        assert_eq!(
+            started.lines().count(),
+            1,
+            "server started again for the sub-agent"
+        );
+    }
+
+    /// Sleeps for `input["ms"]`, tracking how many calls are in flight and
+    /// the order calls started in.
+    struct ProbeTool {
+        name: &'static str,
+        parallel: bool,
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+        started: Arc<Mutex<Vec<String>>>,
+        /// Calls that reached the latch, and the condvar they wait on.
+        latch: Arc<(Mutex<u64>, std::sync::Condvar)>,
+    }
+
+    impl Tool for ProbeTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn schema(&self) -> crate::llm::types::ToolDefinition {
+            crate::llm::types::ToolDefinition {
+                tool_type: "function".to_string(),
+                function: crate::llm::types::FunctionDefinition {
+                    name: self.name.to_string(),
+                    description: Some("Record the call, then sleep for `ms`".to_string()),
+                    parameters: serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "label": { "type": "string" },
+                            "ms": { "type": "integer" },
+                            "wait_for": { "type": "integer" }
+                        },
+                        "required": ["label"]
+                    }),
+                },
+            }
+        }
+
+        fn parallel_safe(&self, _input: &serde_json::Value) -> bool {
+            self.parallel
+        }
+
+        fn execute(
+            &self,
+            input: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<crate::tools::ToolOutput, crate::tools::ToolError> {
+            use std::sync::atomic::Ordering;
+            let label = input["label"].as_str().unwrap_or_default().to_string();
+            self.started.lock().unwrap().push(label.clone());
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            // Hold until `wait_for` calls are in flight at once; the timeout
+            // turns a serial run into a failed assertion instead of a hang.
+            if let Some(wait_for) = input["wait_for"].as_u64() {
+                let (arrived, all_in) = &*self.latch;
+                let mut count = arrived.lock().unwrap();
+                *count += 1;
+                all_in.notify_all();
+                drop(
+                    all_in
+                        .wait_timeout_while(count, std::time::Duration::from_secs(5), |c| {
+                            *c < wait_for
+                        })
+                        .unwrap(),
+                );
+            }
+            std::thread::sleep(std::time::Duration::from_millis(
+                input["ms"].as_u64().unwrap_or(0),
+            ));
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(crate::tools::ToolOutput::ok(label))
+        }
+    }
+
+    struct Probe {
+        tools: Vec<Box<dyn Tool>>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+        started: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn probe() -> Probe {
+        let in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let latch = Arc::new((Mutex::new(0), std::sync::Condvar::new()));
+        let tool = |name, parallel| -> Box<dyn Tool> {
+            Box::new(ProbeTool {
+                name,
+                parallel,
+                in_flight: Arc::clone(&in_flight),
+                peak: Arc::clone(&peak),
+                started: Arc::clone(&started),
+                latch: Arc::clone(&latch),
+            })
+        };
+        Probe {
+            tools: vec![tool("look", true), tool("edit", false)],
+            peak,
+            started,
+        }
+    }
+
+    fn probe_call(id: &str, name: &str, label: &str, ms: u64) -> ToolCall {
+        ToolCall {
+            id: id.to_string(),
+            call_type: Some("function".to_string()),
+            function: ToolCallFunction {
+                name: name.to_string(),
+                arguments: serde_json::json!({ "label": label, "ms": ms }).to_string(),
+            },
+        }
+    }
+
+    /// `call`, made to wait until `wait_for` probe calls are in flight.
+    fn gated(mut call: ToolCall, wait_for: u64) -> ToolCall {
+        let mut args: serde_json::Value = serde_json::from_str(&call.function.arguments).unwrap();
+        args["wait_for"] = wait_for.into();
+        call.function.arguments = args.to_string();
+        call
+    }
+
+    fn event_labels(events: &[AgentInternalEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| match e {
+                AgentInternalEvent::ToolUse { call_id, .. } => format!("use:{call_id}"),
+                AgentInternalEvent::ToolResult { call_id, .. } => format!("result:{call_id}"),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_safe_calls_run_concurrently_in_call_order() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+        let p = probe();
+        // Each call waits for the other two, then the first is the slowest,
+        // so completion order is reversed.
+        let calls = vec![
+            gated(probe_call("c1", "look", "a", 150), 3),
+            gated(probe_call("c2", "look", "b", 75), 3),
+            gated(probe_call("c3", "look", "c", 0), 3),
+        ];
+        let mut events = Vec::new();
+        let results = execute_tool_calls(
+            &p.tools,
+            &calls,
+            &ctx,
+            4,
+            &AtomicBool::new(false),
+            &mut |e| events.push(e),
+        );
+
+        assert_eq!(
+            p.peak.load(std::sync::atomic::Ordering::SeqCst),
+            3,
+            "all three read-only calls should be in flight together"
+        );
+        let ids: Vec<_> = results.iter().map(|r| r.call_id.as_str()).collect();
+        assert_eq!(ids, ["c1", "c2", "c3"]);
+        let outputs: Vec<_> = results.iter().map(|r| r.output.as_str()).collect();
+        assert_eq!(outputs, ["a", "b", "c"]);
+        assert_eq!(
+            event_labels(&events),
+            [
+                "use:c1",
+                "use:c2",
+                "use:c3",
+                "result:c1",
+                "result:c2",
+                "result:c3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mutating_calls_run_alone_and_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+        let p = probe();
+        let calls = vec![
+            probe_call("c1", "look", "read-1", 50),
+            probe_call("c2", "look", "read-2", 0),
+            probe_call("c3", "edit", "write-1", 20),
+            probe_call("c4", "edit", "write-2", 0),
+            probe_call("c5", "look", "read-3", 0),
+        ];
+        let mut events = Vec::new();
+        let results = execute_tool_calls(
+            &p.tools,
+            &calls,
+            &ctx,
+            4,
+            &AtomicBool::new(false),
+            &mut |e| events.push(e),
+        );
+
+        let started = p.started.lock().unwrap().clone();
+        assert_eq!(&started[2..], ["write-1", "write-2", "read-3"]);
+        let ids: Vec<_> = results.iter().map(|r| r.call_id.as_str()).collect();
+        assert_eq!(ids, ["c1", "c2", "c3", "c4", "c5"]);
+        assert_eq!(
+            event_labels(&events),
+            [
+                "use:c1",
+                "use:c2",
+                "result:c1",
+                "result:c2",
+                "use:c3",
+                "result:c3",
+                "use:c4",
+                "result:c4",
+                "use:c5",
+                "result:c5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_parallel_tools_one_runs_serially() {
+        let tmp = TempDir::new().unwrap();
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+        let p = probe();
+        let calls = vec![
+            probe_call("c1", "look", "a", 20),
+            probe_call("c2", "look", "b", 0),
+        ];
+        let mut events = Vec::new();
+        execute_tool_calls(
+            &p.tools,
+            &calls,
+            &ctx,
+            1,
+            &AtomicBool::new(false),
+            &mut |e| events.push(e),
+        );
+
+        assert_eq!(p.peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            event_labels(&events),
+            ["use:c1", "result:c1", "use:c2", "result:c2"]
+        );
+    }
+
+    #[test]
+    fn test_parallel_tool_calls_round_trip_through_run() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "alpha").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "beta").unwrap();
+        let config = make_config(&tmp, false);
+
+        let mut both = MockResponse::tool_call("c1", "read_file", r#"{"path": "a.txt"}"#);
+        both.tool_calls
+            .extend(MockResponse::tool_call("c2", "read_file", r#"{"path": "b.txt"}"#).tool_calls);
+        let gw = CapturingGateway::new(vec![both, MockResponse::text("done")]);
+        let captured = Arc::clone(&gw.captured);
+        run(config, "Read both", Box::new(gw)).unwrap();
+
+        let requests = captured.lock().unwrap();
+        let tool_msgs: Vec<_> = requests[1]
+            .messages
+            .iter()
+            .filter(|m| m.role == "tool")
+            .map(|m| (m.tool_call_id.clone().unwrap(), m.content.clone().unwrap()))
+            .collect();
+        assert_eq!(tool_msgs.len(), 2);
+        assert_eq!(tool_msgs[0].0, "c1");
+        assert!(tool_msgs[0].1.contains("alpha"));
+        assert_eq!(tool_msgs[1].0, "c2");
+        assert!(tool_msgs[1].1.contains("beta"));
+    }
 }
@@ -0,0 +1,866 @@
+//! MCP client for the built-in agent.
+//!
+//! Each configured server is launched (stdio) or connected to (Streamable
+//! HTTP) when the loop builds its tools. Its tools join the catalog as
+//! `mcp__<server>__<tool>`, so persona allow/deny lists and tool events treat
+//! them like any built-in. A server that fails to start or handshake is
+//! logged and skipped; the run continues without its tools.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::llm::openai_compat::block_on_async;
+use crate::llm::types::{FunctionDefinition, ToolDefinition};
+use crate::tools::{Tool, ToolContext, ToolError, ToolOutput};
+
+/// MCP protocol revision sent in `initialize`.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Stdio or HTTP MCP transport (product-specific JSON/TOML mapping).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpServerTransport {
+    /// `command` / `args` / optional `env`.
+    Stdio {
+        command: String,
+        args: Vec<String>,
+        env: Option<HashMap<String, String>>,
+    },
+    /// Remote Streamable HTTP / SSE URL and optional `headers`.
+    Http {
+        url: String,
+        headers: Option<HashMap<String, String>>,
+    },
+}
+
+/// A named MCP server the agent connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpServerConfig {
+    pub name: String,
+    pub transport: McpServerTransport,
+}
+
+#[derive(Debug, Error)]
+pub enum McpError {
+    #[error("E_AIKIT_MCP_CONNECT: server '{server}': {reason}")]
+    Connect { server: String, reason: String },
+
+    #[error("E_AIKIT_MCP_PROTOCOL: server '{server}': {reason}")]
+    Protocol { server: String, reason: String },
+
+    #[error("E_AIKIT_MCP_TIMEOUT: server '{server}' did not answer '{method}' within {secs}s")]
+    Timeout {
+        server: String,
+        method: String,
+        secs: u64,
+    },
+
+    #[error("E_AIKIT_MCP_RPC: server '{server}' returned error {code}: {message}")]
+    Rpc {
+        server: String,
+        code: i64,
+        message: String,
+    },
+}
+
+/// A tool a server advertised in `tools/list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
+
+/// JSON-RPC message exchange with one server.
+trait Channel: Send + Sync {
+    /// Send a request and wait for the response carrying the same `id`.
+    fn request(&self, message: Value) -> Result<Value, McpError>;
+    /// Send a notification; no response is expected.
+    fn notify(&self, message: Value) -> Result<(), McpError>;
+}
+
+/// A connected, initialized MCP server.
+pub struct McpClient {
+    server: String,
+    channel: Box<dyn Channel>,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    /// Start or reach the server and complete the `initialize` handshake.
+    /// Stdio servers run in `workdir`; every request is bounded by `timeout`.
+    pub fn connect(
+        config: &McpServerConfig,
+        workdir: &Path,
+        timeout: Duration,
+    ) -> Result<Self, McpError> {
+        let channel: Box<dyn Channel> = match &config.transport {
+            McpServerTransport::Stdio { command, args, env } => Box::new(StdioChannel::spawn(
+                &config.name,
+                command,
+                args,
+                env.as_ref(),
+                workdir,
+                timeout,
+            )?),
+            McpServerTransport::Http { url, headers } => Box::new(HttpChannel::new(
+                &config.name,
+                url,
+                headers.clone().unwrap_or_default(),
+                timeout,
+            )?),
+        };
+        let client = Self {
+            server: config.name.clone(),
+            channel,
+            next_id: AtomicU64::new(1),
+        };
+        client.request(
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "aikit", "version": env!("CARGO_PKG_VERSION") }
+            }),
+        )?;
+        client.channel.notify(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/initialized"
+        }))?;
+        Ok(client)
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Every tool the server offers, following `nextCursor` pages.
+    pub fn list_tools(&self) -> Result<Vec<McpToolInfo>, McpError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(c) => json!({ "cursor": c }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params)?;
+            for tool in result["tools"].as_array().into_iter().flatten() {
+                let Some(name) = tool["name"].as_str() else {
+                    continue;
+                };
+                tools.push(McpToolInfo {
+                    name: name.to_string(),
+                    description: tool["description"].as_str().map(str::to_string),
+                    input_schema: match &tool["inputSchema"] {
+                        Value::Object(_) => tool["inputSchema"].clone(),
+                        _ => json!({ "type": "object", "properties": {} }),
+                    },
+                });
+            }
+            match result["nextCursor"].as_str() {
+                Some(next) if !next.is_empty() => cursor = Some(next.to_string()),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Call `name` and flatten its content blocks to text. The flag is the
+    /// server's `isError`.
+    pub fn call_tool(&self, name: &str, arguments: Value) -> Result<(String, bool), McpError> {
+        let arguments = if arguments.is_object() {
+            arguments
+        } else {
+            json!({})
+        };
+        let result = self.request(
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        )?;
+        Ok((
+            content_text(&result),
+            result["isError"].as_bool().unwrap_or(false),
+        ))
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value, McpError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self.channel.request(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        }))?;
+        if let Some(error) = response.get("error") {
+            return Err(McpError::Rpc {
+                server: self.server.clone(),
+                code: error["code"].as_i64().unwrap_or(0),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+}
+
+/// Text of a `tools/call` result: text blocks verbatim, embedded text
+/// resources inline, anything else as a one-line placeholder. Falls back to
+/// `structuredContent` when there are no blocks.
+fn content_text(result: &Value) -> String {
+    let blocks = result["content"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    if blocks.is_empty() {
+        return match result.get("structuredContent") {
+            Some(structured) => structured.to_string(),
+            None => String::new(),
+        };
+    }
+    blocks
+        .iter()
+        .map(|block| match block["type"].as_str() {
+            Some("text") => block["text"].as_str().unwrap_or_default().to_string(),
+            Some("resource") if block["resource"]["text"].is_string() => block["resource"]["text"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            Some("resource_link") => format!("[resource: {}]", block["uri"].as_str().unwrap_or("")),
+            Some(kind) => format!(
+                "[{}: {}]",
+                kind,
+                block["mimeType"].as_str().unwrap_or("binary")
+            ),
+            None => block.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ── stdio ────────────────────────────────────────────────────────────────────
+
+/// A child process speaking newline-delimited JSON-RPC on stdin/stdout.
+struct StdioChannel {
+    server: String,
+    child: Mutex<Child>,
+    stdin: Arc<Mutex<ChildStdin>>,
+    /// Responses, forwarded by the reader thread.
+    responses: Mutex<Receiver<Value>>,
+    timeout: Duration,
+}
+
+impl StdioChannel {
+    fn spawn(
+        server: &str,
+        command: &str,
+        args: &[String],
+        env: Option<&HashMap<String, String>>,
+        workdir: &Path,
+        timeout: Duration,
+    ) -> Result<Self, McpError> {
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .current_dir(workdir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        if let Some(env) = env {
+            cmd.envs(env);
+        }
+        let mut child = cmd.spawn().map_err(|e| McpError::Connect {
+            server: server.to_string(),
+            reason: format!("failed to launch '{}': {}", command, e),
+        })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(McpError::Connect {
+                server: server.to_string(),
+                reason: "child stdio unavailable".to_string(),
+            });
+        };
+        let stdin = Arc::new(Mutex::new(stdin));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let replies = Arc::clone(&stdin);
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                match (message.get("id"), message.get("method")) {
+                    // A request from the server: answer pings, decline the
+                    // rest (no sampling, roots or elicitation support).
+                    (Some(id), Some(method)) => {
+                        let reply = if method == "ping" {
+                            json!({ "jsonrpc": "2.0", "id": id, "result": {} })
+                        } else {
+                            json!({
+                                "jsonrpc": "2.0",
+                                "id": id,
+                                "error": { "code": -32601, "message": "method not supported" }
+                            })
+                        };
+                        if let Ok(mut stdin) = replies.lock() {
+                            let _ = writeln!(stdin, "{}", reply);
+                            let _ = stdin.flush();
+                        }
+                    }
+                    (Some(_), None) => {
+                        if tx.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    // Notifications (logging, list_changed) are ignored.
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(Self {
+            server: server.to_string(),
+            child: Mutex::new(child),
+            stdin,
+            responses: Mutex::new(rx),
+            timeout,
+        })
+    }
+
+    fn send(&self, message: &Value) -> Result<(), McpError> {
+        let mut stdin = self.stdin.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(stdin, "{}", message)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| McpError::Protocol {
+                server: self.server.clone(),
+                reason: format!("write failed: {}", e),
+            })
+    }
+}
+
+impl Channel for StdioChannel {
+    fn request(&self, message: Value) -> Result<Value, McpError> {
+        // One request in flight at a time, so any other id is a late answer
+        // to a request that already timed out.
+        let responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        self.send(&message)?;
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match responses.recv_timeout(remaining) {
+                Ok(response) if response["id"] == message["id"] => return Ok(response),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(McpError::Timeout {
+                        server: self.server.clone(),
+                        method: message["method"].as_str().unwrap_or_default().to_string(),
+                        secs: self.timeout.as_secs(),
+                    })
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(McpError::Protocol {
+                        server: self.server.clone(),
+                        reason: "server exited".to_string(),
+                    })
+                }
+            }
+        }
+    }
+
+    fn notify(&self, message: Value) -> Result<(), McpError> {
+        self.send(&message)
+    }
+}
+
+impl Drop for StdioChannel {
+    fn drop(&mut self) {
+        let child = self.child.get_mut().unwrap_or_else(|e| e.into_inner());
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+// ── Streamable HTTP ──────────────────────────────────────────────────────────
+
+/// A Streamable-HTTP endpoint: one POST per message, answered with JSON or
+/// an SSE stream.
+struct HttpChannel {
+    server: String,
+    client: reqwest::Client,
+    url: String,
+    headers: HashMap<String, String>,
+    /// `Mcp-Session-Id` assigned by the server during `initialize`.
+    session_id: Mutex<Option<String>>,
+    timeout: Duration,
+}
+
+impl HttpChannel {
+    fn new(
+        server: &str,
+        url: &str,
+        headers: HashMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self, McpError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| McpError::Connect {
+                server: server.to_string(),
+                reason: format!("failed to build HTTP client: {}", e),
+            })?;
+        Ok(Self {
+            server: server.to_string(),
+            client,
+            url: url.to_string(),
+            headers,
+            session_id: Mutex::new(None),
+            timeout,
+        })
+    }
+
+    /// POST `message`; `Some` with the matching response for requests.
+    fn post(&self, message: &Value) -> Result<Option<Value>, McpError> {
+        let protocol = |reason: String| McpError::Protocol {
+            server: self.server.clone(),
+            reason,
+        };
+        let mut req = self
+            .client
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .header("MCP-Protocol-Version", PROTOCOL_VERSION)
+            .json(message);
+        for (k, v) in &self.headers {
+            req = req.header(k, v);
+        }
+        if let Some(id) = self
+            .session_id
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            req = req.header("Mcp-Session-Id", id);
+        }
+
+        let (status, session, content_type, body) = block_on_async(async {
+            let resp = req.send().await?;
+            let status = resp.status();
+            let header = |name: &str| {
+                resp.headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+            };
+            let session = header("mcp-session-id");
+            let content_type = header("content-type").unwrap_or_default();
+            let body = resp.text().await?;
+            Ok::<_, reqwest::Error>((status, session, content_type, body))
+        })
+        .map_err(|e| {
+            if e.is_timeout() {
+                McpError::Timeout {
+                    server: self.server.clone(),
+                    method: message["method"].as_str().unwrap_or_default().to_string(),
+                    secs: self.timeout.as_secs(),
+                }
+            } else {
+                protocol(format!("request failed: {}", e))
+            }
+        })?;
+
+        if let Some(id) = session {
+            *self.session_id.lock().unwrap_or_else(|e| e.into_inner()) = Some(id);
+        }
+        if !status.is_success() {
+            return Err(protocol(format!("HTTP {}: {}", status.as_u16(), body)));
+        }
+        let Some(id) = message.get("id") else {
+            return Ok(None);
+        };
+
+        let response = if content_type.starts_with("text/event-stream") {
+            sse_messages(&body).into_iter().find(|m| &m["id"] == id)
+        } else {
+            serde_json::from_str::<Value>(&body).ok()
+        };
+        response
+            .map(Some)
+            .ok_or_else(|| protocol(format!("no response to request {}", id)))
+    }
+}
+
+impl Channel for HttpChannel {
+    fn request(&self, message: Value) -> Result<Value, McpError> {
+        self.post(&message)?.ok_or_else(|| McpError::Protocol {
+            server: self.server.clone(),
+            reason: "empty response".to_string(),
+        })
+    }
+
+    fn notify(&self, message: Value) -> Result<(), McpError> {
+        self.post(&message).map(|_| ())
+    }
+}
+
+/// JSON messages carried by an SSE body; multi-line `data:` fields of one
+/// event are joined before parsing.
+fn sse_messages(body: &str) -> Vec<Value> {
+    let mut messages = Vec::new();
+    let mut data = String::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        if let Some(rest) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        } else if line.is_empty() && !data.is_empty() {
+            if let Ok(message) = serde_json::from_str(&data) {
+                messages.push(message);
+            }
+            data.clear();
+        }
+    }
+    messages
+}
+
+// ── tools ────────────────────────────────────────────────────────────────────
+
+/// Longest tool name function-calling APIs accept.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Catalog name for `tool` on `server`: `mcp__<server>__<tool>`, limited to
+/// the characters and length function-calling APIs accept. A name that has
+/// to be cut ends in a hash of the full name, so two long names sharing a
+/// prefix stay distinct.
+pub fn tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    let mut name = format!("mcp__{}__{}", sanitize(server), sanitize(tool));
+    if name.len() > MAX_TOOL_NAME_LEN {
+        // FNV-1a: stable across builds, unlike `DefaultHasher`.
+        let hash = name.bytes().fold(0x811c_9dc5_u32, |h, b| {
+            (h ^ u32::from(b)).wrapping_mul(0x0100_0193)
+        });
+        name.truncate(MAX_TOOL_NAME_LEN - 9);
+        name.push_str(&format!("_{hash:08x}"));
+    }
+    name
+}
+
+/// Whether a persona allow/deny entry covers `tool_name`: an exact name, or
+/// `mcp__<server>` for every tool that server provides.
+pub fn policy_matches(entry: &str, tool_name: &str) -> bool {
+    entry == tool_name
+        || (entry.starts_with("mcp__")
+            && tool_name
+                .strip_prefix(entry)
+                .is_some_and(|rest| rest.starts_with("__")))
+}
+
+/// One server tool exposed to the loop. Clones share the server connection.
+#[derive(Clone)]
+pub struct McpTool {
+    name: String,
+    info: McpToolInfo,
+    client: Arc<McpClient>,
+}
+
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn schema(&self) -> ToolDefinition {
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: self.name.clone(),
+                description: self.info.description.clone(),
+                parameters: self.info.input_schema.clone(),
+            },
+        }
+    }
+
+    fn execute(&self, input: Value, _ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
+        match self.client.call_tool(&self.info.name, input) {
+            Ok((text, false)) => Ok(ToolOutput::ok(text)),
+            Ok((text, true)) => Ok(ToolOutput::err(text)),
+            Err(e) => Ok(ToolOutput::err(e.to_string())),
+        }
+    }
+}
+
+/// Connect to every server and return their tools. Servers that fail are
+/// logged and skipped, as is any tool whose catalog name another tool already
+/// took. Connections close when the last tool is dropped.
+pub fn connect_tools(
+    servers: &[McpServerConfig],
+    workdir: &Path,
+    timeout: Duration,
+) -> Vec<McpTool> {
+    let mut tools: Vec<McpTool> = Vec::new();
+    for server in servers {
+        let listed = McpClient::connect(server, workdir, timeout)
+            .and_then(|client| Ok((client.list_tools()?, Arc::new(client))));
+        let (infos, client) = match listed {
+            Ok(listed) => listed,
+            Err(e) => {
+                tracing::warn!(server = %server.name, error = %e, "MCP server skipped");
+                continue;
+            }
+        };
+        for info in infos {
+            let name = tool_name(&server.name, &info.name);
+            if tools.iter().any(|t| t.name == name) {
+                tracing::warn!(
+                    server = %server.name,
+                    tool = %info.name,
+                    "MCP tool name collides with another MCP tool; skipped"
+                );
+                continue;
+            }
+            tools.push(McpTool {
+                name,
+                info,
+                client: Arc::clone(&client),
+            });
+        }
+    }
+    tools
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_name_is_namespaced_and_sanitized() {
+        assert_eq!(
+            tool_name("github", "create_issue"),
+            "mcp__github__create_issue"
+        );
+        assert_eq!(tool_name("my.server", "a/b"), "mcp__my_server__a_b");
+        assert_eq!(tool_name("s", &"x".repeat(100)).len(), 64);
+    }
+
+    #[test]
+    fn test_truncated_tool_names_stay_distinct() {
+        let a = tool_name("server", &format!("{}_alpha", "x".repeat(80)));
+        let b = tool_name("server", &format!("{}_beta", "x".repeat(80)));
+        assert_eq!(a.len(), 64);
+        assert_eq!(b.len(), 64);
+        assert_ne!(a, b);
+        assert_eq!(a, tool_name("server", &format!("{}_alpha", "x".repeat(80))));
+    }
+
+    #[test]
+    fn test_policy_matches_exact_and_whole_server() {
+        assert!(policy_matches("read_file", "read_file"));
+        assert!(policy_matches("mcp__github", "mcp__github__create_issue"));
+        assert!(policy_matches(
+            "mcp__github__create_issue",
+            "mcp__github__create_issue"
+        ));
+        assert!(!policy_matches("mcp__git", "mcp__github__create_issue"));
+        assert!(!policy_matches("read", "read_file"));
+    }
+
+    #[test]
+    fn test_content_text_flattens_blocks() {
+        let result = json!({
+            "content": [
+                { "type": "text", "text": "line one" },
+                { "type": "image", "data": "AAAA", "mimeType": "image/png" },
+                { "type": "resource", "resource": { "uri": "file:///a", "text": "inline" } }
+            ]
+        });
+        assert_eq!(
+            content_text(&result),
+            "line one\n[image: image/png]\ninline"
+        );
+        assert_eq!(
+            content_text(&json!({ "content": [], "structuredContent": { "n": 1 } })),
+            r#"{"n":1}"#
+        );
+    }
+
+    #[test]
+    fn test_sse_messages_joins_data_lines() {
+        let body = "event: message\ndata: {\"id\":1,\ndata: \"result\":{}}\n\n: keepalive\n\ndata: {\"id\":2}\n";
+        let messages = sse_messages(body);
+        assert_eq!(
+            messages,
+            vec![json!({"id": 1, "result": {}}), json!({"id": 2})]
+        );
+    }
+
+    /// A stdio server scripted in `sh`: answers initialize, tools/list and
+    /// one tools/call in order, after a ping and a log notification it sends
+    /// itself.
+    #[cfg(unix)]
+    pub(crate) fn scripted_stdio_server() -> McpServerConfig {
+        let script = r#"
+read init
+printf '%s\n' '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info"}}'
+printf '%s\n' '{"jsonrpc":"2.0","id":"srv-1","method":"ping"}'
+read pong
+printf '%s\n' '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"0"}}}'
+read initialized
+read list
+printf '%s\n' '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","description":"Echo text","inputSchema":{"type":"object","properties":{"text":{"type":"string"}}}}]}}'
+read call
+case "$call" in
+  *'"text":"hi"'*) printf '%s\n' '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"hi"}]}}' ;;
+  *) printf '%s\n' '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"bad args"}],"isError":true}}' ;;
+esac
+read eof
+"#;
+        McpServerConfig {
+            name: "fake".to_string(),
+            transport: McpServerTransport::Stdio {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                env: None,
+            },
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_server_tools_are_listed_and_called() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tools = connect_tools(
+            &[scripted_stdio_server()],
+            tmp.path(),
+            Duration::from_secs(10),
+        );
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "mcp__fake__echo");
+        assert_eq!(
+            tools[0].schema().function.description.as_deref(),
+            Some("Echo text")
+        );
+
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![]);
+        let out = tools[0].execute(json!({ "text": "hi" }), &ctx).unwrap();
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(out.content, "hi");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_duplicate_tool_names_are_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut twin = scripted_stdio_server();
+        twin.name = "fake".to_string();
+        let tools = connect_tools(
+            &[scripted_stdio_server(), twin],
+            tmp.path(),
+            Duration::from_secs(10),
+        );
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["mcp__fake__echo"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdio_server_timeout_is_reported() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = McpServerConfig {
+            name: "silent".to_string(),
+            transport: McpServerTransport::Stdio {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "read line; sleep 5".to_string()],
+                env: None,
+            },
+        };
+        let err = McpClient::connect(&config, tmp.path(), Duration::from_millis(200))
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("E_AIKIT_MCP_TIMEOUT"), "{err}");
+    }
+
+    #[test]
+    fn test_unlaunchable_server_is_skipped() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = McpServerConfig {
+            name: "missing".to_string(),
+            transport: McpServerTransport::Stdio {
+                command: "aikit-no-such-mcp-server".to_string(),
+                args: vec![],
+                env: None,
+            },
+        };
+        assert!(connect_tools(&[config], tmp.path(), Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn test_http_server_json_and_sse_responses() {
+        let mut server = mockito::Server::new();
+        let init = server
+            .mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "method": "initialize" }),
+            ))
+            .with_header("content-type", "application/json")
+            .with_header("mcp-session-id", "sess-1")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"capabilities":{}}}"#)
+            .create();
+        let initialized = server
+            .mock("POST", "/mcp")
+            .match_header("mcp-session-id", "sess-1")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "method": "notifications/initialized" }),
+            ))
+            .with_status(202)
+            .create();
+        let list = server
+            .mock("POST", "/mcp")
+            .match_header("mcp-session-id", "sess-1")
+            .match_header("authorization", "Bearer t")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "method": "tools/list" }),
+            ))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "event: message\n",
+                "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n",
+                "event: message\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"tools\":[{\"name\":\"search\"}]}}\n\n"
+            ))
+            .create();
+
+        let config = McpServerConfig {
+            name: "remote".to_string(),
+            transport: McpServerTransport::Http {
+                url: format!("{}/mcp", server.url()),
+                headers: Some(HashMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer t".to_string(),
+                )])),
+            },
+        };
+        let client = McpClient::connect(&config, Path::new("."), Duration::from_secs(10)).unwrap();
+        let tools = client.list_tools().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "search");
+        assert_eq!(tools[0].input_schema["type"], "object");
+        init.assert();
+        initialized.assert();
+        list.assert();
+    }
+}
@@ -0,0 +1,468 @@
+//! Long-lived, multi-turn agent sessions.
+//!
+//! [`run_with_context`](crate::run_with_context) rebuilds the skills, system
+//! prompt and tool catalog (MCP servers included) on every call. An
+//! [`AgentSession`] builds them once and keeps the [`ContextPacket`] in
+//! memory, so each [`AgentSession::run_turn`] only adds the new prompt. A
+//! [`CancelHandle`] taken from the session interrupts the turn in flight from
+//! another thread; the loop stops at its next safe point and the session
+//! stays usable for further turns.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::config::AgentConfig;
+use crate::context::{ContextPacket, Turn};
+use crate::errors::AgentError;
+use crate::llm::gateway::LlmGateway;
+use crate::loop_runner::{connect_mcp, prepare_run, run_loop, subagent_tool};
+use crate::mcp::McpTool;
+use crate::tools::Tool;
+use crate::AgentInternalEvent;
+
+/// Interrupts the turn an [`AgentSession`] is running. Cheap to clone and safe
+/// to use from any thread; a cancel with no turn in flight is a no-op.
+///
+/// Turns are numbered from 1 in the order [`AgentSession::run_turn`] is
+/// called, so [`CancelHandle::cancel_turn`] can aim at a turn that has been
+/// queued but not started yet.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    /// Polled by the agent loop; set while the running turn is cancelled.
+    flag: AtomicBool,
+    /// `(turns started, highest turn cancelled)`.
+    turns: Mutex<(u64, u64)>,
+}
+
+impl CancelHandle {
+    /// Stop the running turn at its next safe point: before the next LLM
+    /// call, between streamed chunks, or before the next tool call. A tool
+    /// already executing runs to completion.
+    pub fn cancel(&self) {
+        let mut turns = self.0.turns.lock().unwrap_or_else(|e| e.into_inner());
+        turns.1 = turns.1.max(turns.0);
+        self.0.flag.store(true, Ordering::SeqCst);
+    }
+
+    /// Cancel turn `turn` and every earlier turn that has not finished. A
+    /// turn that has not started yet stops at its first safe point once it
+    /// does.
+    pub fn cancel_turn(&self, turn: u64) {
+        let mut turns = self.0.turns.lock().unwrap_or_else(|e| e.into_inner());
+        turns.1 = turns.1.max(turn);
+        if turns.0 <= turn {
+            self.0.flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.flag.load(Ordering::SeqCst)
+    }
+
+    /// Start the next turn: cancelled only if it was aimed at beforehand.
+    fn begin_turn(&self) {
+        let mut turns = self.0.turns.lock().unwrap_or_else(|e| e.into_inner());
+        turns.0 += 1;
+        self.0.flag.store(turns.1 >= turns.0, Ordering::SeqCst);
+    }
+}
+
+/// How full a session's context is, by the loop's own token estimate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContextUsage {
+    /// [`ContextPacket::estimated_tokens`] for the current context.
+    pub estimated_tokens: u64,
+    /// The configured budget ([`crate::context::TokenBudget::total_budget`]).
+    pub total_budget: u64,
+    /// Tokens the conversation may use before compression kicks in.
+    pub available: u64,
+    pub reserve_for_tools: u64,
+    pub reserve_for_output: u64,
+    /// Turns currently held (after any compression).
+    pub turns: usize,
+}
+
+/// A multi-turn agent session over one gateway, context and tool catalog.
+pub struct AgentSession {
+    config: AgentConfig,
+    gateway: Arc<dyn LlmGateway>,
+    context: ContextPacket,
+    tools: Vec<Box<dyn Tool>>,
+    /// Kept so a sub-agent tool rebuilt by [`Self::set_model`] still shares
+    /// the session's MCP connections.
+    mcp_tools: Vec<McpTool>,
+    cancel: CancelHandle,
+}
+
+impl AgentSession {
+    /// Build a session seeded with `prior_turns` (empty for a new
+    /// conversation). Skill discovery, `AGENTS.md` and MCP connections happen
+    /// here, once.
+    pub fn new(
+        config: AgentConfig,
+        gateway: Box<dyn LlmGateway>,
+        prior_turns: Vec<Turn>,
+    ) -> Result<Self, AgentError> {
+        let gateway: Arc<dyn LlmGateway> = Arc::from(gateway);
+        let mcp_tools = connect_mcp(&config);
+        let (context, tools) = prepare_run(&config, &gateway, prior_turns, mcp_tools.clone())?;
+        Ok(Self {
+            config,
+            gateway,
+            context,
+            tools,
+            mcp_tools,
+            cancel: CancelHandle::default(),
+        })
+    }
+
+    /// A handle that interrupts this session's running turn.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Run one user turn to completion, or until cancelled. An interrupted
+    /// turn ends with a `StepFinish` whose `finish_reason` is `"interrupted"`;
+    /// what it produced so far stays in the context.
+    pub fn run_turn(
+        &mut self,
+        prompt: &str,
+        mut on_event: impl FnMut(AgentInternalEvent),
+    ) -> Result<(), AgentError> {
+        self.cancel.begin_turn();
+        self.context.add_turn(Turn::user(prompt));
+        run_loop(
+            &self.config,
+            &mut self.context,
+            &self.tools,
+            &self.gateway,
+            &self.cancel.0.flag,
+            &mut on_event,
+        )
+    }
+
+    /// The model following turns are sent to.
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Send following turns to `model` through `gateway`. The conversation
+    /// and tools carry over; sub-agents spawned from now on use the new
+    /// model and gateway too.
+    pub fn set_model(&mut self, model: impl Into<String>, gateway: Box<dyn LlmGateway>) {
+        self.config.model = model.into();
+        self.gateway = Arc::from(gateway);
+        if let Some(tool) = subagent_tool(
+            &self.config,
+            Arc::clone(&self.gateway),
+            self.mcp_tools.clone(),
+        ) {
+            if let Some(slot) = self.tools.iter_mut().find(|t| t.name() == tool.name()) {
+                *slot = tool;
+            }
+        }
+    }
+
+    /// The context's estimated size against its budget.
+    pub fn context_usage(&self) -> ContextUsage {
+        let budget = &self.context.token_budget;
+        ContextUsage {
+            estimated_tokens: self.context.estimated_tokens(),
+            total_budget: budget.total_budget,
+            available: budget.available(),
+            reserve_for_tools: budget.reserve_for_tools,
+            reserve_for_output: budget.reserve_for_output,
+            turns: self.context.conversation.len(),
+        }
+    }
+
+    /// The conversation so far.
+    pub fn turns(&self) -> &[Turn] {
+        &self.context.conversation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::TurnRole;
+    use crate::llm::mock::{MockGateway, MockResponse};
+    use crate::llm::types::{
+        FunctionDefinition, LlmError, LlmRequest, LlmResponse, LlmStreamEvent, LlmStreamHandle,
+        ToolDefinition,
+    };
+    use crate::tools::{ToolContext, ToolOutput};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    fn make_config(tmp: &TempDir, stream: bool) -> AgentConfig {
+        AgentConfig {
+            model: "model-a".to_string(),
+            base_url: "http://localhost".to_string(),
+            api_key: "test-key".to_string(),
+            stream,
+            max_iterations: 5,
+            max_subagent_depth: 1,
+            context_budget_tokens: 12000,
+            compression: Default::default(),
+            max_parallel_tools: 1,
+            workdir: tmp.path().to_path_buf(),
+            allowed_roots: vec![tmp.path().to_path_buf()],
+            skills_dirs: vec![],
+            agents_md_path: None,
+            timeout_secs: 30,
+            connect_timeout_secs: 5,
+            session_persona: None,
+            session_agents: std::collections::HashMap::new(),
+            host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: Default::default(),
+        }
+    }
+
+    /// Records the model and message count of every request it serves.
+    struct Recording {
+        label: &'static str,
+        seen: Arc<Mutex<Vec<String>>>,
+        inner: MockGateway,
+    }
+
+    impl LlmGateway for Recording {
+        fn complete(&self, req: LlmRequest) -> Result<LlmResponse, LlmError> {
+            self.seen.lock().unwrap().push(format!(
+                "{}:{}:{}",
+                self.label,
+                req.model,
+                req.messages.len()
+            ));
+            self.inner.complete(req)
+        }
+        fn stream(&self, req: LlmRequest) -> Result<LlmStreamHandle, LlmError> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(format!("{}:{}", self.label, req.model));
+            self.inner.stream(req)
+        }
+    }
+
+    fn finish_reasons(events: &[AgentInternalEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                AgentInternalEvent::StepFinish { finish_reason, .. } => Some(finish_reason.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn turns_share_one_context_and_set_model_switches_gateway() {
+        let tmp = TempDir::new().unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let first = Recording {
+            label: "a",
+            seen: Arc::clone(&seen),
+            inner: MockGateway::new(vec![MockResponse::text("one")]),
+        };
+        let mut session =
+            AgentSession::new(make_config(&tmp, false), Box::new(first), Vec::new()).unwrap();
+
+        session.run_turn("hello", |_| {}).unwrap();
+        let after_one = session.context_usage();
+        assert_eq!(after_one.turns, 2);
+
+        session.set_model(
+            "model-b",
+            Box::new(Recording {
+                label: "b",
+                seen: Arc::clone(&seen),
+                inner: MockGateway::new(vec![MockResponse::text("two")]),
+            }),
+        );
+        assert_eq!(session.model(), "model-b");
+        session.run_turn("again", |_| {}).unwrap();
+
+        // system + user, then system + user + assistant + user.
+        assert_eq!(*seen.lock().unwrap(), vec!["a:model-a:2", "b:model-b:4"]);
+        let usage = session.context_usage();
+        assert_eq!(usage.turns, 4);
+        assert!(usage.estimated_tokens > after_one.estimated_tokens);
+        assert_eq!(usage.total_budget, 12000);
+        assert_eq!(usage.available, 9000);
+        assert_eq!(session.turns()[3].content, "two");
+    }
+
+    /// Cancels the session the first time it runs, like a user hitting
+    /// interrupt while the tool works.
+    struct CancellingTool {
+        cancel: Arc<Mutex<Option<CancelHandle>>>,
+        runs: Arc<Mutex<u32>>,
+    }
+
+    impl Tool for CancellingTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+        fn schema(&self) -> ToolDefinition {
+            ToolDefinition {
+                tool_type: "function".to_string(),
+                function: FunctionDefinition {
+                    name: "slow".to_string(),
+                    description: None,
+                    parameters: serde_json::json!({"type": "object"}),
+                },
+            }
+        }
+        fn execute(
+            &self,
+            _input: serde_json::Value,
+            _ctx: &ToolContext,
+        ) -> Result<ToolOutput, crate::tools::ToolError> {
+            *self.runs.lock().unwrap() += 1;
+            if let Some(c) = self.cancel.lock().unwrap().as_ref() {
+                c.cancel();
+            }
+            Ok(ToolOutput::ok("done"))
+        }
+    }
+
+    #[test]
+    fn interrupt_skips_remaining_tool_calls_and_keeps_the_session_usable() {
+        let tmp = TempDir::new().unwrap();
+        let mut calls = MockResponse::tool_call("c1", "slow", "{}");
+        calls
+            .tool_calls
+            .extend(MockResponse::tool_call("c2", "slow", "{}").tool_calls);
+        let gateway = MockGateway::new(vec![calls, MockResponse::text("never requested")]);
+        let mut session =
+            AgentSession::new(make_config(&tmp, false), Box::new(gateway), Vec::new()).unwrap();
+        let cancel = Arc::new(Mutex::new(Some(session.cancel_handle())));
+        let runs = Arc::new(Mutex::new(0));
+        session.tools.push(Box::new(CancellingTool {
+            cancel: Arc::clone(&cancel),
+            runs: Arc::clone(&runs),
+        }));
+
+        let mut events = Vec::new();
+        session.run_turn("go", |e| events.push(e)).unwrap();
+        assert_eq!(*runs.lock().unwrap(), 1, "second call must be skipped");
+        assert_eq!(finish_reasons(&events), vec!["interrupted"]);
+        let skipped = events.iter().any(|e| {
+            matches!(e, AgentInternalEvent::ToolResult { call_id, is_error: true, output }
+                if call_id == "c2" && output.starts_with("interrupted"))
+        });
+        assert!(skipped, "{events:?}");
+        // Every tool call still has a result in the context.
+        let last = session.turns().last().unwrap();
+        assert_eq!(last.role, TurnRole::Tool);
+        assert_eq!(last.tool_results.as_ref().unwrap().len(), 2);
+
+        // The flag resets for the next turn.
+        cancel.lock().unwrap().take();
+        let mut events = Vec::new();
+        session.run_turn("next", |e| events.push(e)).unwrap();
+        assert_eq!(finish_reasons(&events), vec!["stop"]);
+    }
+
+    #[test]
+    fn cancel_turn_reaches_a_turn_that_has_not_started() {
+        let tmp = TempDir::new().unwrap();
+        let gateway =
+            MockGateway::new(vec![MockResponse::text("one"), MockResponse::text("three")]);
+        let mut session =
+            AgentSession::new(make_config(&tmp, false), Box::new(gateway), Vec::new()).unwrap();
+        let cancel = session.cancel_handle();
+
+        // Aimed at turn 1 before it starts.
+        cancel.cancel_turn(1);
+        let mut events = Vec::new();
+        session.run_turn("first", |e| events.push(e)).unwrap();
+        assert_eq!(finish_reasons(&events), vec!["interrupted"]);
+
+        // A plain cancel between turns is a no-op for the next one.
+        cancel.cancel();
+        let mut events = Vec::new();
+        session.run_turn("second", |e| events.push(e)).unwrap();
+        assert_eq!(finish_reasons(&events), vec!["stop"]);
+
+        // Aimed at turn 4 before turn 3: both stop, turn 5 runs.
+        cancel.cancel_turn(4);
+        for (prompt, reason) in [
+            ("third", "interrupted"),
+            ("fourth", "interrupted"),
+            ("fifth", "stop"),
+        ] {
+            let mut events = Vec::new();
+            session.run_turn(prompt, |e| events.push(e)).unwrap();
+            assert_eq!(finish_reasons(&events), vec![reason], "{prompt}");
+        }
+    }
+
+    #[test]
+    fn interrupt_cuts_a_stream_and_keeps_the_partial_text() {
+        let tmp = TempDir::new().unwrap();
+        let mut streamed = MockResponse::text("unused");
+        streamed.stream_events = vec![
+            LlmStreamEvent::TextDelta {
+                content: "Hel".into(),
+            },
+            LlmStreamEvent::TextDelta {
+                content: "lo".into(),
+            },
+            LlmStreamEvent::Completed {
+                finish_reason: "stop".into(),
+                usage: None,
+            },
+        ];
+        let mut session = AgentSession::new(
+            make_config(&tmp, true),
+            Box::new(MockGateway::new(vec![streamed])),
+            Vec::new(),
+        )
+        .unwrap();
+        let cancel = session.cancel_handle();
+
+        let mut events = Vec::new();
+        session
+            .run_turn("hi", |e| {
+                if matches!(e, AgentInternalEvent::TextDelta { .. }) {
+                    cancel.cancel();
+                }
+                events.push(e);
+            })
+            .unwrap();
+        let deltas = events
+            .iter()
+            .filter(|e| matches!(e, AgentInternalEvent::TextDelta { .. }))
+            .count();
+        assert_eq!(deltas, 1);
+        assert!(events.iter().any(
+            |e| matches!(e, AgentInternalEvent::TextFinal { content, .. } if content == "Hel")
+        ));
+        assert_eq!(finish_reasons(&events), vec!["interrupted"]);
+        assert_eq!(session.turns().last().unwrap().content, "Hel");
+    }
+
+    #[test]
+    fn set_model_rebinds_spawn_subagent() {
+        let tmp = TempDir::new().unwrap();
+        let mut session = AgentSession::new(
+            make_config(&tmp, false),
+            Box::new(MockGateway::new(vec![])),
+            vec![Turn::user("earlier"), Turn::assistant("reply")],
+        )
+        .unwrap();
+        assert_eq!(session.turns().len(), 2);
+        let count = session.tools.len();
+        session.set_model("model-b", Box::new(MockGateway::new(vec![])));
+        assert_eq!(session.tools.len(), count);
+        assert!(session.tools.iter().any(|t| t.name() == "spawn_subagent"));
+    }
+}
@@ -353,6 +353,8 @@ mod tests {
             max_iterations: 1,
             max_subagent_depth: 0,
             context_budget_tokens: 1000,
+            compression: Default::default(),
+            max_parallel_tools: 4,
             workdir: tmp.path().to_path_buf(),
             allowed_roots: vec![],
             skills_dirs: vec![],
@@ -362,6 +364,9 @@ mod tests {
             session_persona: None,
             session_agents: std::collections::HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: Default::default(),
         }
     }
 
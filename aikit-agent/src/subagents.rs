@@ -1,12 +1,17 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use serde_json::Value;
 
+use crate::agent_definition::AgentPersona;
 use crate::config::AgentConfig;
 use crate::llm::gateway::LlmGateway;
 use crate::llm::types::{FunctionDefinition, ToolDefinition};
+use crate::mcp::McpTool;
 use crate::tools::{Tool, ToolContext, ToolError, ToolOutput};
+use crate::worktree::Worktree;
+use crate::AgentInternalEvent;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SubAgentStatus {
@@ -44,9 +49,39 @@ pub struct SubAgentProfile {
     pub context_seed: Option<String>,
 }
 
+/// A focused task handed to a [`SubagentDelegate`].
+#[derive(Debug, Clone)]
+pub struct DelegateTask {
+    /// Backend key to run the task on (`codex`, `claude`, ...).
+    pub agent: String,
+    pub model: Option<String>,
+    pub prompt: String,
+    pub system_prompt: Option<String>,
+    pub workdir: PathBuf,
+}
+
+/// Runs sub-agent tasks on external Backends.
+///
+/// `aikit-agent` cannot depend on the runner that spawns Backend CLIs, so the
+/// embedder injects one through [`AgentConfig::subagent_delegate`];
+/// `aikit-sdk` provides an implementation over `run_agent_events`.
+pub trait SubagentDelegate: Send + Sync {
+    /// Backend keys this delegate can run.
+    fn agents(&self) -> Vec<String>;
+
+    /// Run `task` to completion. Each canonical event the Backend produces is
+    /// passed to `on_event`, serialized, as it arrives. Failures are reported
+    /// through [`SubAgentResult::status`].
+    fn run(&self, task: &DelegateTask, on_event: &mut (dyn FnMut(Value) + Send)) -> SubAgentResult;
+}
+
 pub struct SpawnSubagentTool {
     pub parent_config: AgentConfig,
     pub gateway: Arc<dyn LlmGateway>,
+    /// The parent's connected MCP tools; in-process sub-agents reuse these
+    /// connections rather than starting `parent_config.mcp_servers` again,
+    /// except under worktree isolation.
+    pub mcp_tools: Vec<McpTool>,
 }
 
 impl Tool for SpawnSubagentTool {
@@ -55,6 +90,13 @@ impl Tool for SpawnSubagentTool {
     }
 
     fn schema(&self) -> ToolDefinition {
+        let mut agent_description = String::from(
+            "Run the task on another agent instead of a nested aikit loop: \
+             the name of a session agent, or a backend key",
+        );
+        if let Some(delegate) = &self.parent_config.subagent_delegate {
+            agent_description.push_str(&format!(" ({})", delegate.agents().join(", ")));
+        }
         ToolDefinition {
             tool_type: "function".to_string(),
             function: FunctionDefinition {
@@ -68,6 +110,18 @@ impl Tool for SpawnSubagentTool {
                         "workdir": { "type": "string" },
                         "system_prompt": { "type": "string" },
                         "prompt": { "type": "string" },
+                        "agent": {
+                            "type": "string",
+                            "description": agent_description
+                        },
+                        "model": { "type": "string" },
+                        "isolation": {
+                            "type": "string",
+                            "enum": ["none", "worktree"],
+                            "description": "worktree: run in a fresh git worktree of workdir's \
+                                repository and return the changes as a patch to review and \
+                                `git apply`, instead of editing workdir directly"
+                        },
                         "tools_allowlist": {
                             "type": "array",
                             "items": { "type": "string" }
@@ -80,6 +134,23 @@ impl Tool for SpawnSubagentTool {
         }
     }
 
+    /// Each sub-agent runs its own loop with its own context, so independent
+    /// spawns from one turn fan out instead of queueing — but only when they
+    /// cannot edit the same files: each gets its own worktree, or it runs
+    /// in-process with nothing but read-only tools.
+    fn parallel_safe(&self, input: &Value) -> bool {
+        if input["isolation"].as_str() == Some("worktree") {
+            return true;
+        }
+        let read_only = input["tools_allowlist"].as_array().is_some_and(|tools| {
+            !tools.is_empty()
+                && tools
+                    .iter()
+                    .all(|t| t.as_str().is_some_and(|t| READ_ONLY_TOOLS.contains(&t)))
+        });
+        read_only && self.runs_in_process(input)
+    }
+
     fn execute(&self, input: Value, ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
         let workdir_str = input["workdir"]
             .as_str()
@@ -120,51 +191,221 @@ impl Tool for SpawnSubagentTool {
             .map(|v| v as u32)
             .unwrap_or(self.parent_config.max_iterations);
 
-        let system_prompt = input["system_prompt"].as_str().map(|s| s.to_string());
+        let mut system_prompt = input["system_prompt"].as_str().map(|s| s.to_string());
         let tools_allowlist = input["tools_allowlist"].as_array().map(|arr| {
             arr.iter()
                 .filter_map(|v| v.as_str().map(|s| s.to_string()))
                 .collect()
         });
+        let mut model = input["model"].as_str().map(|s| s.to_string());
+        let mut agent = input["agent"]
+            .as_str()
+            .filter(|a| !a.is_empty())
+            .map(|s| s.to_string());
 
-        let _profile = SubAgentProfile {
-            workdir: workdir.clone(),
-            system_prompt,
-            skills_allowlist: None,
-            tools_allowlist,
-            max_iterations,
-            context_seed: None,
+        // A session agent's persona supplies the prompt, model and Backend
+        // unless the call overrides them.
+        if let Some(persona) = agent
+            .as_deref()
+            .and_then(|name| self.parent_config.session_agents.get(name))
+        {
+            if system_prompt.is_none() && !persona.prompt.is_empty() {
+                system_prompt = Some(persona.prompt.clone());
+            }
+            model = model.or_else(|| persona.model.clone());
+            agent = persona.agent.clone();
+        }
+
+        let delegate = match agent.as_deref() {
+            None | Some("aikit") => None,
+            Some(backend) => match &self.parent_config.subagent_delegate {
+                Some(d) if d.agents().iter().any(|a| a == backend) => Some((backend, d)),
+                _ => {
+                    return Ok(ToolOutput::err(format!(
+                        "E_AIKIT_SUBAGENT_AGENT: no runnable agent '{}' for spawn_subagent",
+                        backend
+                    )))
+                }
+            },
+        };
+
+        // Held until the result is built; dropping it removes the worktree
+        // however this call exits.
+        let worktree = match input["isolation"].as_str() {
+            None | Some("none") => None,
+            Some("worktree") => match Worktree::create(&canonical_workdir) {
+                Ok(wt) => Some(wt),
+                Err(e) => return Ok(ToolOutput::err(e.to_string())),
+            },
+            Some(other) => {
+                return Ok(ToolOutput::err(format!(
+                    "E_AIKIT_SUBAGENT_LIMIT: unknown isolation '{}'",
+                    other
+                )))
+            }
         };
+        let workdir = worktree.as_ref().map_or(workdir, Worktree::workdir);
+
+        let subagent_id = next_subagent_id();
+        ctx.emit(AgentInternalEvent::SubagentSpawn {
+            subagent_id: subagent_id.clone(),
+            workdir: workdir.display().to_string(),
+        });
+
+        let mut result = match delegate {
+            Some((backend, delegate)) => {
+                let task = DelegateTask {
+                    agent: backend.to_string(),
+                    model,
+                    prompt: prompt.to_string(),
+                    system_prompt,
+                    workdir,
+                };
+                delegate.run(&task, &mut |event| {
+                    ctx.emit(AgentInternalEvent::SubagentEvent {
+                        subagent_id: subagent_id.clone(),
+                        event,
+                    })
+                })
+            }
+            None => {
+                let profile = SubAgentProfile {
+                    workdir,
+                    system_prompt,
+                    skills_allowlist: None,
+                    tools_allowlist,
+                    max_iterations,
+                    context_seed: None,
+                };
+                self.run_in_process(profile, model, prompt, worktree.is_some())
+            }
+        };
+
+        let mut patch = None;
+        let mut patch_file = None;
+        if let Some(wt) = &worktree {
+            match wt.patch() {
+                Ok(p) => {
+                    result.changed_files = p.changed_files.clone();
+                    if !p.is_empty() {
+                        // The worktree goes away below, so without the file
+                        // only the (possibly truncated) inline diff is left.
+                        match wt.save_patch(&p, &subagent_id) {
+                            Ok(file) => patch_file = Some(file.display().to_string()),
+                            Err(e) => {
+                                result.status = SubAgentStatus::Failed;
+                                result.key_findings = format!("could not save the patch: {e}");
+                            }
+                        }
+                        patch = Some(p.diff_text());
+                    }
+                }
+                Err(e) => {
+                    result.status = SubAgentStatus::Failed;
+                    result.key_findings = e.to_string();
+                }
+            }
+        }
+        drop(worktree);
+
+        ctx.emit(AgentInternalEvent::SubagentResult {
+            subagent_id,
+            status: result.status.to_string(),
+            changed_files: result
+                .changed_files
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+            key_findings: result.key_findings.clone(),
+            final_message: result.final_message.clone(),
+            patch: patch.clone(),
+        });
 
+        let mut result_json = serde_json::json!({
+            "status": result.status.to_string(),
+            "changed_files": result.changed_files.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+            "key_findings": result.key_findings,
+            "final_message": result.final_message,
+        });
+        if let Some(mut diff) = patch {
+            if diff.len() > ctx.max_output_bytes {
+                let mut end = ctx.max_output_bytes;
+                while !diff.is_char_boundary(end) {
+                    end -= 1;
+                }
+                diff.truncate(end);
+            }
+            result_json["patch"] = Value::String(diff);
+            result_json["patch_file"] = patch_file.map_or(Value::Null, Value::String);
+        }
+        if result.status == SubAgentStatus::Success {
+            Ok(ToolOutput::ok(result_json.to_string()))
+        } else {
+            Ok(ToolOutput::err(result_json.to_string()))
+        }
+    }
+}
+
+impl SpawnSubagentTool {
+    /// Run a nested aikit loop with a decremented depth budget. An
+    /// `isolated` sub-agent starts its own MCP servers in its worktree: the
+    /// shared clients run in the parent's workdir and would edit that tree.
+    fn run_in_process(
+        &self,
+        profile: SubAgentProfile,
+        model: Option<String>,
+        prompt: &str,
+        isolated: bool,
+    ) -> SubAgentResult {
         // Create sub-agent config with decremented depth
         let sub_config = AgentConfig {
-            model: self.parent_config.model.clone(),
+            model: model.unwrap_or_else(|| self.parent_config.model.clone()),
             base_url: self.parent_config.base_url.clone(),
             api_key: self.parent_config.api_key.clone(),
             stream: self.parent_config.stream,
-            max_iterations,
+            max_iterations: profile.max_iterations,
             max_subagent_depth: self.parent_config.max_subagent_depth.saturating_sub(1),
             context_budget_tokens: self.parent_config.context_budget_tokens,
-            workdir: workdir.clone(),
-            allowed_roots: vec![workdir.clone()],
+            compression: self.parent_config.compression,
+            max_parallel_tools: self.parent_config.max_parallel_tools,
+            workdir: profile.workdir.clone(),
+            allowed_roots: vec![profile.workdir.clone()],
             skills_dirs: self.parent_config.skills_dirs.clone(),
             agents_md_path: None,
             timeout_secs: self.parent_config.timeout_secs,
             connect_timeout_secs: self.parent_config.connect_timeout_secs,
-            session_persona: None,
+            session_persona: subagent_persona(&profile),
             session_agents: std::collections::HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: self.parent_config.subagent_delegate.clone(),
+            mcp_servers: self.parent_config.mcp_servers.clone(),
+            web: self.parent_config.web.clone(),
+        };
+
+        let mcp_tools = if isolated {
+            crate::loop_runner::connect_mcp(&sub_config)
+                .into_iter()
+                .filter(|t| crate::loop_runner::persona_allows(&self.parent_config, t.name()))
+                .collect()
+        } else {
+            self.mcp_tools.clone()
         };
 
         // Run the sub-agent
-        let gateway: Box<dyn LlmGateway> =
-            Box::new(SubAgentGatewayWrapper(Arc::clone(&self.gateway)));
-        match crate::loop_runner::run(sub_config, prompt, gateway) {
-            Ok(events) => {
+        let mut events = Vec::new();
+        let run = crate::loop_runner::run_inner_streaming(
+            sub_config,
+            prompt,
+            Arc::clone(&self.gateway),
+            mcp_tools,
+            &mut |e| events.push(e),
+        );
+        match run {
+            Ok(()) => {
                 let final_message = events
                     .iter()
                     .filter_map(|e| {
-                        if let crate::AgentInternalEvent::TextFinal { content, .. } = e {
+                        if let AgentInternalEvent::TextFinal { content, .. } = e {
                             Some(content.as_str())
                         } else {
                             None
@@ -174,59 +415,63 @@ impl Tool for SpawnSubagentTool {
                     .unwrap_or("Sub-agent completed")
                     .to_string();
 
-                let result = SubAgentResult {
+                SubAgentResult {
                     status: SubAgentStatus::Success,
                     changed_files: vec![],
                     key_findings: final_message.clone(),
                     final_message,
-                };
-
-                let result_json = serde_json::json!({
-                    "status": result.status.to_string(),
-                    "changed_files": result.changed_files.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
-                    "key_findings": result.key_findings,
-                    "final_message": result.final_message,
-                });
-                Ok(ToolOutput::ok(result_json.to_string()))
-            }
-            Err(e) => {
-                let result = SubAgentResult {
-                    status: SubAgentStatus::Failed,
-                    changed_files: vec![],
-                    key_findings: e.to_string(),
-                    final_message: format!("Sub-agent failed: {}", e),
-                };
-                let result_json = serde_json::json!({
-                    "status": result.status.to_string(),
-                    "changed_files": [],
-                    "key_findings": result.key_findings,
-                    "final_message": result.final_message,
-                });
-                Ok(ToolOutput::err(result_json.to_string()))
+                }
             }
+            Err(e) => SubAgentResult {
+                status: SubAgentStatus::Failed,
+                changed_files: vec![],
+                key_findings: e.to_string(),
+                final_message: format!("Sub-agent failed: {}", e),
+            },
         }
     }
 }
 
-/// Wrapper to clone the Arc gateway into a Box for sub-agent use.
-struct SubAgentGatewayWrapper(Arc<dyn LlmGateway>);
-
-impl LlmGateway for SubAgentGatewayWrapper {
-    fn complete(
-        &self,
-        req: crate::llm::types::LlmRequest,
-    ) -> Result<crate::llm::types::LlmResponse, crate::llm::types::LlmError> {
-        self.0.complete(req)
+/// The persona an in-process sub-agent runs under: its system prompt, and
+/// its `tools_allowlist` as a hard filter on the tools it is given.
+fn subagent_persona(profile: &SubAgentProfile) -> Option<AgentPersona> {
+    if profile.system_prompt.is_none() && profile.tools_allowlist.is_none() {
+        return None;
     }
+    Some(AgentPersona {
+        name: "subagent".to_string(),
+        description: String::new(),
+        prompt: profile.system_prompt.clone().unwrap_or_default(),
+        tools: profile.tools_allowlist.clone(),
+        disallowed_tools: None,
+        model: None,
+        agent: None,
+    })
+}
 
-    fn stream(
-        &self,
-        req: crate::llm::types::LlmRequest,
-    ) -> Result<crate::llm::types::LlmStreamHandle, crate::llm::types::LlmError> {
-        self.0.stream(req)
+/// Built-in tools that never change the workspace. An in-process sub-agent
+/// limited to these may run alongside other spawns without isolation.
+const READ_ONLY_TOOLS: &[&str] = &["read_file", "read_skill", "web_fetch", "web_search"];
+
+impl SpawnSubagentTool {
+    /// Whether the call runs the nested aikit loop rather than a delegated
+    /// Backend, which ignores `tools_allowlist`.
+    fn runs_in_process(&self, input: &Value) -> bool {
+        let agent = input["agent"].as_str().filter(|a| !a.is_empty());
+        let agent = match agent.and_then(|name| self.parent_config.session_agents.get(name)) {
+            Some(persona) => persona.agent.as_deref(),
+            None => agent,
+        };
+        matches!(agent, None | Some("aikit"))
     }
 }
 
+/// Process-unique id for a spawned sub-agent, used to correlate its events.
+fn next_subagent_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    format!("subagent-{}", NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,6 +487,8 @@ mod tests {
             max_iterations: 3,
             max_subagent_depth: max_depth,
             context_budget_tokens: 12000,
+            compression: Default::default(),
+            max_parallel_tools: 4,
             workdir: workdir.clone(),
             allowed_roots: vec![workdir],
             skills_dirs: vec![],
@@ -251,6 +498,9 @@ mod tests {
             session_persona: None,
             session_agents: std::collections::HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: Default::default(),
         }
     }
 
@@ -262,6 +512,7 @@ mod tests {
         let tool = SpawnSubagentTool {
             parent_config: config,
             gateway: gw,
+            mcp_tools: Vec::new(),
         };
 
         // When max_depth is 0, the tool should still exist but spawning fails
@@ -291,6 +542,7 @@ mod tests {
         let tool = SpawnSubagentTool {
             parent_config: config,
             gateway: gw,
+            mcp_tools: Vec::new(),
         };
 
         let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
@@ -332,6 +584,7 @@ mod tests {
         let tool = SpawnSubagentTool {
             parent_config: config,
             gateway: Arc::clone(&gw),
+            mcp_tools: Vec::new(),
         };
 
         let sub_dir = tmp.path().join("subwork");
@@ -351,4 +604,424 @@ mod tests {
         assert!(parsed["key_findings"].is_string());
         assert!(parsed["final_message"].is_string());
     }
+
+    /// Answers every task with a fixed result and two canned events.
+    struct FakeDelegate {
+        tasks: std::sync::Mutex<Vec<DelegateTask>>,
+    }
+
+    impl SubagentDelegate for FakeDelegate {
+        fn agents(&self) -> Vec<String> {
+            vec!["codex".to_string(), "claude".to_string()]
+        }
+
+        fn run(
+            &self,
+            task: &DelegateTask,
+            on_event: &mut (dyn FnMut(Value) + Send),
+        ) -> SubAgentResult {
+            self.tasks.lock().unwrap().push(task.clone());
+            on_event(serde_json::json!({ "n": 1 }));
+            on_event(serde_json::json!({ "n": 2 }));
+            SubAgentResult {
+                status: SubAgentStatus::Success,
+                changed_files: vec![PathBuf::from("migrations/001.sql")],
+                key_findings: "wrote the migration".to_string(),
+                final_message: "wrote the migration".to_string(),
+            }
+        }
+    }
+
+    fn delegating_tool(tmp: &TempDir) -> (SpawnSubagentTool, Arc<FakeDelegate>) {
+        let delegate = Arc::new(FakeDelegate {
+            tasks: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut config = make_parent_config(tmp.path().to_path_buf(), 2);
+        config.subagent_delegate = Some(delegate.clone());
+        config.session_agents.insert(
+            "migrator".to_string(),
+            crate::AgentPersona {
+                name: "migrator".to_string(),
+                description: "Writes database migrations".to_string(),
+                prompt: "You write SQL migrations.".to_string(),
+                tools: None,
+                disallowed_tools: None,
+                model: Some("gpt-5-codex".to_string()),
+                agent: Some("codex".to_string()),
+            },
+        );
+        let tool = SpawnSubagentTool {
+            parent_config: config,
+            gateway: Arc::new(MockGateway::new(vec![])),
+            mcp_tools: Vec::new(),
+        };
+        (tool, delegate)
+    }
+
+    #[test]
+    fn test_subagent_delegates_to_backend_and_nests_events() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, delegate) = delegating_tool(&tmp);
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+        ctx.events = Some(tx);
+
+        let input = serde_json::json!({
+            "workdir": ".",
+            "prompt": "add a users table",
+            "agent": "codex",
+            "model": "o4-mini"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        drop(ctx);
+
+        assert!(!result.is_error, "{}", result.content);
+        let parsed: Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(
+            parsed["changed_files"],
+            serde_json::json!(["migrations/001.sql"])
+        );
+        assert_eq!(parsed["final_message"], "wrote the migration");
+
+        let tasks = delegate.tasks.lock().unwrap();
+        assert_eq!(tasks[0].agent, "codex");
+        assert_eq!(tasks[0].model.as_deref(), Some("o4-mini"));
+        assert_eq!(tasks[0].prompt, "add a users table");
+
+        let events: Vec<AgentInternalEvent> = rx.iter().collect();
+        assert_eq!(events.len(), 4, "{events:?}");
+        let AgentInternalEvent::SubagentSpawn { subagent_id, .. } = &events[0] else {
+            panic!("expected spawn first, got {:?}", events[0]);
+        };
+        for (event, n) in events[1..3].iter().zip([1, 2]) {
+            match event {
+                AgentInternalEvent::SubagentEvent {
+                    subagent_id: id,
+                    event,
+                } => {
+                    assert_eq!(id, subagent_id);
+                    assert_eq!(event["n"], n);
+                }
+                other => panic!("expected nested event, got {other:?}"),
+            }
+        }
+        match &events[3] {
+            AgentInternalEvent::SubagentResult {
+                subagent_id: id,
+                status,
+                changed_files,
+                ..
+            } => {
+                assert_eq!(id, subagent_id);
+                assert_eq!(status, "success");
+                assert_eq!(changed_files, &["migrations/001.sql"]);
+            }
+            other => panic!("expected result last, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_subagent_persona_selects_backend_model_and_prompt() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, delegate) = delegating_tool(&tmp);
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": ".",
+            "prompt": "add a users table",
+            "agent": "migrator"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        assert!(!result.is_error, "{}", result.content);
+
+        let tasks = delegate.tasks.lock().unwrap();
+        assert_eq!(tasks[0].agent, "codex");
+        assert_eq!(tasks[0].model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(
+            tasks[0].system_prompt.as_deref(),
+            Some("You write SQL migrations.")
+        );
+    }
+
+    /// Writes `notes.md` into its workdir, like a Backend editing the tree.
+    struct EditingDelegate {
+        workdirs: std::sync::Mutex<Vec<PathBuf>>,
+    }
+
+    impl SubagentDelegate for EditingDelegate {
+        fn agents(&self) -> Vec<String> {
+            vec!["codex".to_string()]
+        }
+
+        fn run(
+            &self,
+            task: &DelegateTask,
+            _on_event: &mut (dyn FnMut(Value) + Send),
+        ) -> SubAgentResult {
+            self.workdirs.lock().unwrap().push(task.workdir.clone());
+            std::fs::write(task.workdir.join("notes.md"), "findings\n").unwrap();
+            SubAgentResult {
+                status: SubAgentStatus::Success,
+                changed_files: vec![],
+                key_findings: String::new(),
+                final_message: "done".to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_subagent_worktree_isolation_returns_patch() {
+        let repo = crate::worktree::tests::init_repo();
+        let delegate = Arc::new(EditingDelegate {
+            workdirs: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut config = make_parent_config(repo.path().to_path_buf(), 2);
+        config.subagent_delegate = Some(delegate.clone());
+        let tool = SpawnSubagentTool {
+            parent_config: config,
+            gateway: Arc::new(MockGateway::new(vec![])),
+            mcp_tools: Vec::new(),
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut ctx = ToolContext::new(repo.path().to_path_buf(), vec![repo.path().to_path_buf()]);
+        ctx.events = Some(tx);
+
+        let input = serde_json::json!({
+            "workdir": "src",
+            "prompt": "take notes",
+            "agent": "codex",
+            "isolation": "worktree"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        drop(ctx);
+        assert!(!result.is_error, "{}", result.content);
+
+        // The sub-agent ran in a worktree, not the parent's tree, and the
+        // worktree is gone once the call returns.
+        let workdir = delegate.workdirs.lock().unwrap()[0].clone();
+        assert!(workdir.ends_with("src"));
+        assert!(!workdir.starts_with(repo.path()));
+        assert!(!workdir.exists());
+        assert!(!repo.path().join("src/notes.md").exists());
+
+        let parsed: Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(parsed["changed_files"], serde_json::json!(["src/notes.md"]));
+        assert!(parsed["patch"].as_str().unwrap().contains("+findings"));
+        let patch_file = PathBuf::from(parsed["patch_file"].as_str().unwrap());
+        assert!(patch_file.exists());
+
+        let patch = rx.iter().find_map(|e| match e {
+            AgentInternalEvent::SubagentResult { patch, .. } => patch,
+            _ => None,
+        });
+        assert!(patch.unwrap().contains("src/notes.md"));
+    }
+
+    #[test]
+    fn test_subagent_worktree_patch_save_failure_is_reported() {
+        let repo = crate::worktree::tests::init_repo();
+        // A file where the patches directory should go.
+        std::fs::write(repo.path().join(".git").join("aikit"), "").unwrap();
+        let mut config = make_parent_config(repo.path().to_path_buf(), 2);
+        config.subagent_delegate = Some(Arc::new(EditingDelegate {
+            workdirs: std::sync::Mutex::new(Vec::new()),
+        }));
+        let tool = SpawnSubagentTool {
+            parent_config: config,
+            gateway: Arc::new(MockGateway::new(vec![])),
+            mcp_tools: Vec::new(),
+        };
+        let ctx = ToolContext::new(repo.path().to_path_buf(), vec![repo.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": "src",
+            "prompt": "take notes",
+            "agent": "codex",
+            "isolation": "worktree"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        assert!(result.is_error);
+        let parsed: Value = serde_json::from_str(&result.content).unwrap();
+        assert!(parsed["key_findings"]
+            .as_str()
+            .unwrap()
+            .starts_with("could not save the patch"));
+        assert!(parsed["patch"].as_str().unwrap().contains("+findings"));
+        assert!(parsed["patch_file"].is_null());
+    }
+
+    #[test]
+    fn test_subagent_worktree_isolation_requires_a_repo() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, delegate) = delegating_tool(&tmp);
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": ".",
+            "prompt": "anything",
+            "agent": "codex",
+            "isolation": "worktree"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("E_AIKIT_WORKTREE_NOT_A_REPO"));
+        assert!(delegate.tasks.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_subagent_unknown_agent_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, delegate) = delegating_tool(&tmp);
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": ".",
+            "prompt": "anything",
+            "agent": "gemini"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("E_AIKIT_SUBAGENT_AGENT"));
+        assert!(delegate.tasks.lock().unwrap().is_empty());
+    }
+
+    /// Records every request it answers.
+    struct Recording {
+        requests: std::sync::Mutex<Vec<crate::llm::LlmRequest>>,
+        inner: MockGateway,
+    }
+
+    impl Recording {
+        fn new(responses: Vec<MockResponse>) -> Arc<Self> {
+            Arc::new(Self {
+                requests: std::sync::Mutex::new(Vec::new()),
+                inner: MockGateway::new(responses),
+            })
+        }
+    }
+
+    impl LlmGateway for Recording {
+        fn complete(
+            &self,
+            req: crate::llm::LlmRequest,
+        ) -> Result<crate::llm::LlmResponse, crate::llm::LlmError> {
+            self.requests.lock().unwrap().push(req.clone());
+            self.inner.complete(req)
+        }
+
+        fn stream(
+            &self,
+            req: crate::llm::LlmRequest,
+        ) -> Result<crate::llm::LlmStreamHandle, crate::llm::LlmError> {
+            self.inner.stream(req)
+        }
+    }
+
+    #[test]
+    fn test_in_process_subagent_gets_only_allowlisted_tools() {
+        let tmp = TempDir::new().unwrap();
+        let gw = Recording::new(vec![MockResponse::text("looked around")]);
+        let tool = SpawnSubagentTool {
+            parent_config: make_parent_config(tmp.path().to_path_buf(), 2),
+            gateway: Arc::clone(&gw) as Arc<dyn LlmGateway>,
+            mcp_tools: Vec::new(),
+        };
+        let ctx = ToolContext::new(tmp.path().to_path_buf(), vec![tmp.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": tmp.path().to_str().unwrap(),
+            "prompt": "look around",
+            "tools_allowlist": ["read_file"]
+        });
+        assert!(tool.parallel_safe(&input));
+        assert!(!tool.execute(input, &ctx).unwrap().is_error);
+        let requests = gw.requests.lock().unwrap();
+        let names: Vec<&str> = requests[0]
+            .tools
+            .iter()
+            .map(|t| t.function.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["read_file"]);
+    }
+
+    /// An MCP server whose only tool reports the directory it runs in.
+    #[cfg(unix)]
+    fn cwd_mcp_server() -> crate::mcp::McpServerConfig {
+        let script = r#"
+read init
+printf '%s\n' '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"cwd","version":"0"}}}'
+read initialized
+read list
+printf '%s\n' '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"pwd","description":"Print the working directory","inputSchema":{"type":"object"}}]}}'
+read call
+printf '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"cwd=%s"}]}}\n' "$(pwd)"
+read eof
+"#;
+        crate::mcp::McpServerConfig {
+            name: "cwd".to_string(),
+            transport: crate::mcp::McpServerTransport::Stdio {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                env: None,
+            },
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_worktree_subagent_runs_its_own_mcp_servers_in_the_worktree() {
+        let repo = crate::worktree::tests::init_repo();
+        let mut config = make_parent_config(repo.path().to_path_buf(), 2);
+        config.mcp_servers = vec![cwd_mcp_server()];
+        let shared = crate::loop_runner::connect_mcp(&config);
+        let gw = Recording::new(vec![
+            MockResponse::tool_call("c1", "mcp__cwd__pwd", "{}"),
+            MockResponse::text("done"),
+        ]);
+        let tool = SpawnSubagentTool {
+            parent_config: config,
+            gateway: Arc::clone(&gw) as Arc<dyn LlmGateway>,
+            mcp_tools: shared,
+        };
+        let ctx = ToolContext::new(repo.path().to_path_buf(), vec![repo.path().to_path_buf()]);
+
+        let input = serde_json::json!({
+            "workdir": repo.path().to_str().unwrap(),
+            "prompt": "where am I",
+            "isolation": "worktree"
+        });
+        let result = tool.execute(input, &ctx).unwrap();
+        assert!(!result.is_error, "{}", result.content);
+        let requests = gw.requests.lock().unwrap();
+        let output = requests[1]
+            .messages
+            .iter()
+            .filter_map(|m| m.content.as_deref())
+            .find(|c| c.contains("cwd="))
+            .expect("the pwd result reaches the model");
+        assert!(output.contains("aikit-worktree-"), "{output}");
+    }
+    #[test]
+    fn test_only_isolated_or_read_only_spawns_run_in_parallel() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, _) = delegating_tool(&tmp);
+        let safe = |input: Value| tool.parallel_safe(&input);
+
+        assert!(!safe(serde_json::json!({ "prompt": "edit" })));
+        assert!(safe(serde_json::json!({ "isolation": "worktree" })));
+        assert!(safe(
+            serde_json::json!({ "tools_allowlist": ["read_file", "web_search"] })
+        ));
+        assert!(!safe(
+            serde_json::json!({ "tools_allowlist": ["read_file", "write_file"] })
+        ));
+        assert!(!safe(serde_json::json!({ "tools_allowlist": [] })));
+        // Delegated Backends ignore the allow-list, so it proves nothing.
+        assert!(!safe(
+            serde_json::json!({ "agent": "migrator", "tools_allowlist": ["read_file"] })
+        ));
+        assert!(safe(
+            serde_json::json!({ "agent": "codex", "isolation": "worktree" })
+        ));
+    }
 }
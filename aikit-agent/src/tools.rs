@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -7,6 +8,7 @@ use serde_json::Value;
 use crate::host_tools::{HostToolDefinition, HostToolProvider};
 use crate::llm::types::{FunctionDefinition, ToolDefinition};
 use crate::skills::{DiscoveredSkill, SkillProvider};
+use crate::AgentInternalEvent;
 
 #[derive(Debug, Clone)]
 pub struct ToolOutput {
@@ -42,6 +44,10 @@ pub struct ToolContext {
     pub allowed_roots: Vec<PathBuf>,
     pub timeout: Duration,
     pub max_output_bytes: usize,
+    /// Where a tool reports events of its own while it runs (sub-agent
+    /// spawns and their progress). The agent loop forwards them to the run's
+    /// event stream; `None` outside the loop.
+    pub events: Option<Sender<AgentInternalEvent>>,
 }
 
 impl ToolContext {
@@ -51,6 +57,14 @@ impl ToolContext {
             allowed_roots,
             timeout: Duration::from_secs(30),
             max_output_bytes: 1024 * 1024,
+            events: None,
+        }
+    }
+
+    /// Report `event` to the run's event stream, if there is one.
+    pub fn emit(&self, event: AgentInternalEvent) {
+        if let Some(tx) = &self.events {
+            let _ = tx.send(event);
         }
     }
 }
@@ -59,6 +73,14 @@ pub trait Tool: Send + Sync {
     fn name(&self) -> &str;
     fn schema(&self) -> ToolDefinition;
     fn execute(&self, input: Value, ctx: &ToolContext) -> Result<ToolOutput, ToolError>;
+
+    /// Whether a call with `input` may run concurrently with other calls
+    /// from the same assistant turn — true for read-only tools. Calls that
+    /// change the workspace keep the default `false` and run alone, in the
+    /// order the model issued them.
+    fn parallel_safe(&self, _input: &Value) -> bool {
+        false
+    }
 }
 
 /// Check if `path` is within any of the allowed roots.
@@ -132,6 +154,10 @@ impl Tool for ReadFileTool {
         }
     }
 
+    fn parallel_safe(&self, _input: &Value) -> bool {
+        true
+    }
+
     fn execute(&self, input: Value, ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
         let path_str = input["path"]
             .as_str()
@@ -395,6 +421,10 @@ impl Tool for ReadSkillTool {
         }
     }
 
+    fn parallel_safe(&self, _input: &Value) -> bool {
+        true
+    }
+
     fn execute(&self, input: Value, _ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
         let skill_name = input["skill_name"]
             .as_str()
@@ -0,0 +1,1353 @@
+//! `web_fetch` and `web_search` tools.
+//!
+//! `web_fetch` retrieves one URL and returns it as text: HTML is converted to
+//! readable markdown (scripts, styles and page chrome dropped, links kept),
+//! other text types pass through, and the result is cut to a token budget.
+//! `web_search` asks a [`SearchProvider`] — [`SearxngProvider`] for any
+//! SearXNG-compatible endpoint — and lists the hits. Both tools are bound by
+//! the domain allow/deny lists in [`WebConfig`], checked on every redirect
+//! hop and on every search result. `web_fetch` also refuses loopback,
+//! private and link-local addresses (cloud metadata endpoints included)
+//! unless `allow_domains` names the host: IP literals are checked with the
+//! URL, and names are checked on every connection by the client's resolver.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::llm::openai_compat::block_on_async;
+use crate::llm::types::{FunctionDefinition, ToolDefinition};
+use crate::tools::{Tool, ToolContext, ToolError, ToolOutput};
+
+/// Token budget for a fetched page when the model does not pass `max_tokens`.
+pub const DEFAULT_FETCH_TOKENS: u64 = 4000;
+
+/// Largest response body read from the network, before conversion.
+const MAX_FETCH_BYTES: usize = 5 * 1024 * 1024;
+
+const MAX_REDIRECTS: usize = 10;
+
+const DEFAULT_SEARCH_RESULTS: usize = 5;
+const MAX_SEARCH_RESULTS: usize = 10;
+
+/// One hit returned by a [`SearchProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// Backend for the `web_search` tool.
+pub trait SearchProvider: Send + Sync {
+    /// Up to `limit` results for `query`.
+    fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        timeout: Duration,
+    ) -> Result<Vec<SearchResult>, String>;
+}
+
+/// Search through a SearXNG instance's JSON API (`/search?format=json`).
+/// The instance must have the `json` output format enabled.
+#[derive(Debug, Clone)]
+pub struct SearxngProvider {
+    endpoint: String,
+}
+
+impl SearxngProvider {
+    /// `base_url` is the instance root (`https://searx.example`) or its
+    /// `/search` endpoint.
+    pub fn new(base_url: &str) -> Self {
+        let base = base_url.trim_end_matches('/');
+        let endpoint = if base.ends_with("/search") {
+            base.to_string()
+        } else {
+            format!("{}/search", base)
+        };
+        Self { endpoint }
+    }
+}
+
+impl SearchProvider for SearxngProvider {
+    fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        timeout: Duration,
+    ) -> Result<Vec<SearchResult>, String> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(user_agent())
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {}", e))?;
+        let req = client
+            .get(&self.endpoint)
+            .query(&[("q", query), ("format", "json")]);
+        let (status, body) = block_on_async(async {
+            let resp = req.send().await?;
+            let status = resp.status();
+            Ok::<_, reqwest::Error>((status, resp.text().await?))
+        })
+        .map_err(|e| format!("search request failed: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("search endpoint returned HTTP {}", status));
+        }
+        let body: Value = serde_json::from_str(&body)
+            .map_err(|e| format!("search endpoint returned invalid JSON: {}", e))?;
+        let str_field = |v: &Value, key: &str| v[key].as_str().unwrap_or_default().to_string();
+        Ok(body["results"]
+            .as_array()
+            .map(|results| {
+                results
+                    .iter()
+                    .filter(|r| r["url"].is_string())
+                    .take(limit)
+                    .map(|r| SearchResult {
+                        title: str_field(r, "title"),
+                        url: str_field(r, "url"),
+                        snippet: str_field(r, "content"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+/// Web access for the built-in agent.
+#[derive(Clone, Default)]
+pub struct WebConfig {
+    /// Hosts the web tools may reach; an entry also covers its subdomains.
+    /// Empty allows every host that is not denied. A host named here may
+    /// also resolve to an address that is not public.
+    pub allow_domains: Vec<String>,
+    /// Hosts the web tools never reach, including subdomains. Wins over
+    /// `allow_domains`.
+    pub deny_domains: Vec<String>,
+    /// Backend for `web_search`; the tool is only offered when this is set.
+    pub search: Option<Arc<dyn SearchProvider>>,
+}
+
+impl std::fmt::Debug for WebConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebConfig")
+            .field("allow_domains", &self.allow_domains)
+            .field("deny_domains", &self.deny_domains)
+            .field("search", &self.search.as_ref().map(|_| "<SearchProvider>"))
+            .finish()
+    }
+}
+
+impl WebConfig {
+    /// Read `AIKIT_WEB_ALLOW_DOMAINS` and `AIKIT_WEB_DENY_DOMAINS`
+    /// (comma-separated) and `AIKIT_SEARCH_URL` (a SearXNG instance).
+    pub fn from_env() -> Self {
+        let domains = |var: &str| {
+            std::env::var(var)
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|d| !d.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let search = std::env::var("AIKIT_SEARCH_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .map(|u| Arc::new(SearxngProvider::new(u.trim())) as Arc<dyn SearchProvider>);
+        Self {
+            allow_domains: domains("AIKIT_WEB_ALLOW_DOMAINS"),
+            deny_domains: domains("AIKIT_WEB_DENY_DOMAINS"),
+            search,
+        }
+    }
+
+    /// Whether the domain lists let the tools reach `host`.
+    pub fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if self.deny_domains.iter().any(|d| domain_matches(d, &host)) {
+            return false;
+        }
+        self.allow_domains.is_empty() || self.allow_domains.iter().any(|d| domain_matches(d, &host))
+    }
+
+    /// Whether the tools may connect to `ip` on behalf of `host`: public
+    /// addresses always, loopback, private and link-local ones only when
+    /// `allow_domains` names the host.
+    pub fn allows_address(&self, host: &str, ip: IpAddr) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        is_public(ip) || self.allow_domains.iter().any(|d| domain_matches(d, &host))
+    }
+
+    /// Why `url`'s host is off limits, if it is: the domain lists, or an IP
+    /// literal that is not a public address. Names are resolved later, by
+    /// [`GuardedResolver`].
+    fn host_error(&self, url: &Url) -> Option<String> {
+        let host = url.host_str().unwrap_or_default();
+        if !self.allows_host(host) {
+            return Some(format!("host '{}' is blocked by the domain policy", host));
+        }
+        let ip: IpAddr = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .ok()?;
+        (!self.allows_address(host, ip)).then(|| non_public_error(host))
+    }
+
+    /// Parse `raw` and check it is an http(s) URL on an allowed host.
+    fn check_url(&self, raw: &str) -> Result<Url, String> {
+        let url = Url::parse(raw).map_err(|e| format!("invalid URL '{}': {}", raw, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("only http and https URLs are supported: {}", raw));
+        }
+        match self.host_error(&url) {
+            Some(e) => Err(e),
+            None => Ok(url),
+        }
+    }
+}
+
+/// Loopback, RFC 1918 private, link-local (169.254.0.0/16, where cloud
+/// metadata lives, and fe80::/10), shared (100.64.0.0/10), "this network"
+/// (0.0.0.0/8), multicast (224.0.0.0/4, ff00::/8), reserved (240.0.0.0/4,
+/// broadcast included) and unique-local (fc00::/7) addresses are not
+/// public. IPv6 forms that carry an IPv4 address — IPv4-mapped
+/// (`::ffff:a.b.c.d`), IPv4-compatible (`::a.b.c.d`) and NAT64
+/// (`64:ff9b::/96`) — are judged by that address.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || a == 0
+                || a >= 224
+                || (a == 100 && b & 0xc0 == 64))
+        }
+        IpAddr::V6(v6) => {
+            let segments = v6.segments();
+            let embedded = v6.to_ipv4_mapped().or_else(|| {
+                let nat64 = segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0];
+                let compatible = segments[..6] == [0; 6];
+                (nat64 || compatible).then(|| {
+                    let [.., hi, lo] = segments;
+                    Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo))
+                })
+            });
+            match embedded {
+                // `::` and `::1` land here as 0.0.0.0 and 0.0.0.1.
+                Some(v4) => is_public(IpAddr::V4(v4)),
+                None => {
+                    let first = segments[0];
+                    !(v6.is_multicast() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80)
+                }
+            }
+        }
+    }
+}
+
+fn non_public_error(host: &str) -> String {
+    format!(
+        "host '{}' is not a public address (loopback, private, link-local or reserved); add it to the allowed domains to reach it",
+        host
+    )
+}
+
+/// DNS for `web_fetch`: resolves like the system resolver, then drops the
+/// addresses [`WebConfig::allows_address`] refuses. It runs for every
+/// connection — the first request, each redirect hop, and a name that
+/// re-resolves to an internal address between them.
+struct GuardedResolver {
+    policy: WebConfig,
+}
+
+impl reqwest::dns::Resolve for GuardedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let resolved: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let allowed: Vec<SocketAddr> = resolved
+                .into_iter()
+                .filter(|addr| policy.allows_address(&host, addr.ip()))
+                .collect();
+            if allowed.is_empty() {
+                return Err(non_public_error(&host).into());
+            }
+            Ok(Box::new(allowed.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// `entry` is `example.com`, `.example.com` or `*.example.com`; each covers
+/// the domain and its subdomains.
+fn domain_matches(entry: &str, host: &str) -> bool {
+    let entry = entry
+        .trim()
+        .trim_start_matches("*.")
+        .trim_start_matches('.')
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    !entry.is_empty()
+        && (host == entry
+            || host
+                .strip_suffix(entry.as_str())
+                .is_some_and(|rest| rest.ends_with('.')))
+}
+
+fn user_agent() -> String {
+    format!("aikit-agent/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// The web tools `config` enables: `web_fetch` always, `web_search` when a
+/// provider is configured.
+pub fn web_tools(config: &WebConfig) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = vec![Box::new(WebFetchTool {
+        config: config.clone(),
+    })];
+    if let Some(provider) = &config.search {
+        tools.push(Box::new(WebSearchTool {
+            provider: Arc::clone(provider),
+            config: config.clone(),
+        }));
+    }
+    tools
+}
+
+// ── web_fetch ────────────────────────────────────────────────────────────────
+
+pub struct WebFetchTool {
+    pub config: WebConfig,
+}
+
+/// A fetched response, before conversion.
+struct FetchedPage {
+    url: Url,
+    content_type: String,
+    body: String,
+}
+
+impl WebFetchTool {
+    fn fetch(&self, url: Url, timeout: Duration) -> Result<FetchedPage, String> {
+        let policy = self.config.clone();
+        let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error(format!("more than {} redirects", MAX_REDIRECTS))
+            } else if let Some(e) = policy.host_error(attempt.url()) {
+                attempt.error(format!("redirect to {}", e))
+            } else {
+                attempt.follow()
+            }
+        });
+        // A proxy would resolve and connect on our behalf, past the
+        // resolver and redirect checks, so environment proxies are ignored.
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(timeout)
+            .redirect(redirects)
+            .dns_resolver(Arc::new(GuardedResolver {
+                policy: self.config.clone(),
+            }))
+            .user_agent(user_agent())
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {}", e))?;
+
+        block_on_async(async {
+            let mut resp = client
+                .get(url)
+                .header(
+                    "Accept",
+                    "text/html, text/markdown, text/plain, application/json;q=0.9, */*;q=0.5",
+                )
+                .send()
+                .await
+                .map_err(|e| describe_request_error(&e))?;
+            let status = resp.status();
+            let final_url = resp.url().clone();
+            if !status.is_success() {
+                return Err(format!("HTTP {} fetching {}", status, final_url));
+            }
+            let content_type = resp
+                .headers()
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            let mut bytes = Vec::new();
+            while let Some(chunk) = resp.chunk().await.map_err(|e| describe_request_error(&e))? {
+                bytes.extend_from_slice(&chunk);
+                if bytes.len() >= MAX_FETCH_BYTES {
+                    bytes.truncate(MAX_FETCH_BYTES);
+                    break;
+                }
+            }
+            Ok(FetchedPage {
+                url: final_url,
+                content_type,
+                body: String::from_utf8_lossy(&bytes).into_owned(),
+            })
+        })
+    }
+}
+
+fn describe_request_error(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        return "request timed out".to_string();
+    }
+    // The redirect policy's message is on the error's source chain.
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(inner) = source {
+        message = format!("{}: {}", message, inner);
+        source = inner.source();
+    }
+    message
+}
+
+impl Tool for WebFetchTool {
+    fn name(&self) -> &str {
+        "web_fetch"
+    }
+
+    fn schema(&self) -> ToolDefinition {
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "web_fetch".to_string(),
+                description: Some(
+                    "Fetch a web page or document by URL. HTML is returned as markdown; long pages are truncated"
+                        .to_string(),
+                ),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "url": {
+                            "type": "string",
+                            "description": "http or https URL to fetch"
+                        },
+                        "max_tokens": {
+                            "type": "integer",
+                            "description": format!(
+                                "Approximate token budget for the returned text (default {})",
+                                DEFAULT_FETCH_TOKENS
+                            )
+                        }
+                    },
+                    "required": ["url"]
+                }),
+            },
+        }
+    }
+
+    fn parallel_safe(&self, _input: &Value) -> bool {
+        true
+    }
+
+    fn execute(&self, input: Value, ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
+        let raw = input["url"]
+            .as_str()
+            .ok_or_else(|| ToolError::Exec("missing 'url' argument".to_string()))?;
+        let max_tokens = input["max_tokens"]
+            .as_u64()
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_FETCH_TOKENS);
+
+        let url = match self.config.check_url(raw) {
+            Ok(url) => url,
+            Err(e) => return Ok(ToolOutput::err(e)),
+        };
+        let page = match self.fetch(url, ctx.timeout) {
+            Ok(page) => page,
+            Err(e) => return Ok(ToolOutput::err(format!("web_fetch {}: {}", raw, e))),
+        };
+
+        let mime = page.content_type.split(';').next().unwrap_or("").trim();
+        let sniffed_html = mime.is_empty() && looks_like_html(&page.body);
+        let (title, text) =
+            if mime == "text/html" || mime == "application/xhtml+xml" || sniffed_html {
+                (
+                    html_title(&page.body),
+                    html_to_markdown(&page.body, Some(&page.url)),
+                )
+            } else if mime.is_empty()
+                || mime.starts_with("text/")
+                || mime.ends_with("json")
+                || mime.ends_with("xml")
+            {
+                (None, page.body)
+            } else {
+                return Ok(ToolOutput::err(format!(
+                    "web_fetch {}: unsupported content type '{}'",
+                    raw, mime
+                )));
+            };
+
+        let mut out = format!("URL: {}\n", page.url);
+        if let Some(title) = title {
+            out.push_str(&format!("Title: {}\n", title));
+        }
+        out.push('\n');
+        let (kept, truncated) = truncate_to_tokens(&text, max_tokens);
+        out.push_str(kept);
+        if truncated {
+            out.push_str(&format!(
+                "\n\n[truncated: about {} of {} tokens shown; pass a larger max_tokens to read more]",
+                max_tokens,
+                crate::context::estimate_tokens(&text)
+            ));
+        }
+        Ok(ToolOutput::ok(out))
+    }
+}
+
+/// Cut `text` so that [`crate::context::estimate_tokens`] stays within
+/// `max_tokens`. Returns the kept prefix and whether anything was dropped.
+fn truncate_to_tokens(text: &str, max_tokens: u64) -> (&str, bool) {
+    // estimate_tokens counts words * 4/3.
+    let max_words = (max_tokens * 3 / 4).max(1);
+    let mut words = 0u64;
+    let mut in_word = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words += 1;
+            if words > max_words {
+                return (text[..i].trim_end(), true);
+            }
+        }
+    }
+    (text, false)
+}
+
+// ── web_search ───────────────────────────────────────────────────────────────
+
+pub struct WebSearchTool {
+    pub provider: Arc<dyn SearchProvider>,
+    pub config: WebConfig,
+}
+
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn schema(&self) -> ToolDefinition {
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: "web_search".to_string(),
+                description: Some(
+                    "Search the web. Returns titles, URLs and snippets; read a result with web_fetch"
+                        .to_string(),
+                ),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Search query"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": format!(
+                                "Number of results (default {}, max {})",
+                                DEFAULT_SEARCH_RESULTS, MAX_SEARCH_RESULTS
+                            )
+                        }
+                    },
+                    "required": ["query"]
+                }),
+            },
+        }
+    }
+
+    fn parallel_safe(&self, _input: &Value) -> bool {
+        true
+    }
+
+    fn execute(&self, input: Value, ctx: &ToolContext) -> Result<ToolOutput, ToolError> {
+        let query = input["query"]
+            .as_str()
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| ToolError::Exec("missing 'query' argument".to_string()))?;
+        let limit = input["limit"]
+            .as_u64()
+            .map(|n| (n as usize).clamp(1, MAX_SEARCH_RESULTS))
+            .unwrap_or(DEFAULT_SEARCH_RESULTS);
+
+        // Ask for extra hits so results dropped by the domain policy do not
+        // leave the list short.
+        let results = match self
+            .provider
+            .search(query, MAX_SEARCH_RESULTS * 2, ctx.timeout)
+        {
+            Ok(results) => results,
+            Err(e) => return Ok(ToolOutput::err(format!("web_search: {}", e))),
+        };
+        let allowed: Vec<SearchResult> = results
+            .into_iter()
+            .filter(|r| {
+                Url::parse(&r.url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(|h| self.config.allows_host(h)))
+                    .unwrap_or(false)
+            })
+            .take(limit)
+            .collect();
+
+        if allowed.is_empty() {
+            return Ok(ToolOutput::ok(format!("No results for '{}'.", query)));
+        }
+        let mut out = String::new();
+        for (i, r) in allowed.iter().enumerate() {
+            let title = if r.title.is_empty() { &r.url } else { &r.title };
+            out.push_str(&format!("{}. [{}]({})\n", i + 1, title, r.url));
+            let snippet = r.snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !snippet.is_empty() {
+                out.push_str(&format!("   {}\n", snippet));
+            }
+        }
+        Ok(ToolOutput::ok(out.trim_end().to_string()))
+    }
+}
+
+// ── HTML to markdown ─────────────────────────────────────────────────────────
+
+/// Elements dropped together with their content.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "iframe", "nav", "button", "select",
+];
+
+/// Elements that start and end a paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "aside",
+    "blockquote",
+    "table",
+    "tr",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "figure",
+    "figcaption",
+    "form",
+    "details",
+    "summary",
+    "address",
+];
+
+fn looks_like_html(body: &str) -> bool {
+    let head = body.trim_start().get(..256).unwrap_or(body.trim_start());
+    let head = head.to_ascii_lowercase();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+/// The document's `<title>`, if any.
+pub fn html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(&html[start..end]);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Convert HTML to readable markdown. Relative links and images resolve
+/// against `base`.
+pub fn html_to_markdown(html: &str, base: Option<&Url>) -> String {
+    let lower = html.to_ascii_lowercase();
+    let mut w = MarkdownWriter::default();
+    // Per open `<a>`: the resolved target, or `None` for links not worth
+    // keeping (fragments, javascript:).
+    let mut links: Vec<Option<String>> = Vec::new();
+    // Per open list: `Some(next number)` for `<ol>`, `None` for `<ul>`.
+    let mut lists: Vec<Option<u32>> = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(offset) = html[pos..].find('<') else {
+            w.text(&html[pos..]);
+            break;
+        };
+        w.text(&html[pos..pos + offset]);
+        pos += offset;
+
+        if lower[pos..].starts_with("<!--") {
+            pos = lower[pos..].find("-->").map_or(html.len(), |e| pos + e + 3);
+            continue;
+        }
+        let Some(tag) = parse_tag(html, pos) else {
+            // A stray `<` in text.
+            w.text("<");
+            pos += 1;
+            continue;
+        };
+        pos = tag.end;
+        let name = tag.name.as_str();
+
+        if !tag.closing && SKIPPED_ELEMENTS.contains(&name) && !tag.self_closing {
+            let close = format!("</{}", name);
+            pos = lower[pos..]
+                .find(&close)
+                .and_then(|e| lower[pos + e..].find('>').map(|g| pos + e + g + 1))
+                .unwrap_or(html.len());
+            continue;
+        }
+
+        match (name, tag.closing) {
+            ("br", _) => w.newline(),
+            ("hr", false) => {
+                w.blank_line();
+                w.raw("---");
+                w.blank_line();
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                w.blank_line();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                w.raw(&format!("{} ", "#".repeat(level)));
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => w.blank_line(),
+            ("pre", false) => {
+                w.blank_line();
+                w.raw("```\n");
+                w.pre_depth += 1;
+            }
+            ("pre", true) => {
+                w.pre_depth = w.pre_depth.saturating_sub(1);
+                w.newline();
+                w.raw("```");
+                w.blank_line();
+            }
+            ("code" | "kbd" | "samp", _) if w.pre_depth == 0 => w.inline("`"),
+            ("strong" | "b", _) if w.pre_depth == 0 => w.inline("**"),
+            ("em" | "i", _) if w.pre_depth == 0 => w.inline("*"),
+            ("ul", false) => {
+                w.newline();
+                lists.push(None);
+            }
+            ("ol", false) => {
+                w.newline();
+                lists.push(Some(1));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                if lists.is_empty() {
+                    w.blank_line();
+                } else {
+                    w.newline();
+                }
+            }
+            ("li", false) => {
+                w.newline();
+                let indent = "  ".repeat(lists.len().saturating_sub(1));
+                let marker = match lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                w.raw(&format!("{}{}", indent, marker));
+            }
+            ("li", true) => w.newline(),
+            ("td" | "th", false) => w.inline(" "),
+            ("a", false) => {
+                let href = tag
+                    .attr("href")
+                    .filter(|h| !h.starts_with('#') && !h.starts_with("javascript:"))
+                    .map(|h| resolve(base, &h));
+                if href.is_some() {
+                    w.inline("[");
+                }
+                links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = links.pop() {
+                    w.raw(&format!("]({})", href));
+                }
+            }
+            ("img", false) => {
+                let alt = tag.attr("alt").unwrap_or_default();
+                if let Some(src) = tag.attr("src").filter(|_| !alt.is_empty()) {
+                    w.inline(&format!("![{}]({})", alt, resolve(base, &src)));
+                }
+            }
+            (_, _) if BLOCK_ELEMENTS.contains(&name) => {
+                if name == "blockquote" && !tag.closing {
+                    w.blank_line();
+                    w.raw("> ");
+                } else {
+                    w.blank_line();
+                }
+            }
+            _ => {}
+        }
+    }
+    w.finish()
+}
+
+fn resolve(base: Option<&Url>, href: &str) -> String {
+    base.and_then(|b| b.join(href).ok())
+        .map(|u| u.to_string())
+        .unwrap_or_else(|| href.to_string())
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+    /// Byte offset just past the closing `>`.
+    end: usize,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<String> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| decode_entities(v.trim()))
+    }
+}
+
+/// Parse the tag starting at `html[start] == '<'`. `None` when the `<` does
+/// not open a tag.
+fn parse_tag(html: &str, start: usize) -> Option<Tag> {
+    let bytes = html.as_bytes();
+    let mut i = start + 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+    // `<!DOCTYPE ...>` and `<?xml ...>` carry nothing to render.
+    let declaration = matches!(bytes.get(i), Some(b'!') | Some(b'?'));
+    let name_start = i;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+        i += 1;
+    }
+    if i == name_start && !declaration {
+        return None;
+    }
+    let name = html[name_start..i].to_ascii_lowercase();
+
+    // Scan to the closing `>`, skipping quoted attribute values.
+    let attrs_start = i;
+    let mut quote = None;
+    while i < bytes.len() {
+        match (quote, bytes[i]) {
+            (None, b'>') => break,
+            (None, q @ (b'"' | b'\'')) => quote = Some(q),
+            (Some(q), c) if c == q => quote = None,
+            _ => {}
+        }
+        i += 1;
+    }
+    let inner = &html[attrs_start..i.min(html.len())];
+    Some(Tag {
+        name: if declaration { String::new() } else { name },
+        closing,
+        self_closing: inner.trim_end().ends_with('/'),
+        attrs: parse_attrs(inner),
+        end: (i + 1).min(html.len()),
+    })
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        if name_len == 0 {
+            rest = rest[1..].trim_start();
+            continue;
+        }
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = rest[name_len..].trim_start();
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (v, remaining) = match after.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let end = body.find(q).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = v.to_string();
+            rest = remaining.trim_start();
+        }
+        attrs.push((name, value));
+    }
+    attrs
+}
+
+/// Decode character references: the common named ones and all numeric ones.
+/// Unknown references are left as written.
+pub fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "hellip" => Some('…'),
+                "lsquo" => Some('‘'),
+                "rsquo" => Some('’'),
+                "ldquo" => Some('“'),
+                "rdquo" => Some('”'),
+                "copy" => Some('©'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Accumulates markdown, collapsing whitespace outside `<pre>`.
+#[derive(Default)]
+struct MarkdownWriter {
+    out: String,
+    pre_depth: usize,
+    /// A space is owed before the next inline content.
+    pending_space: bool,
+}
+
+impl MarkdownWriter {
+    fn text(&mut self, raw: &str) {
+        if raw.is_empty() {
+            return;
+        }
+        let text = decode_entities(raw);
+        if self.pre_depth > 0 {
+            self.out.push_str(&text);
+            return;
+        }
+        let starts_with_space = text.starts_with(char::is_whitespace);
+        let ends_with_space = text.ends_with(char::is_whitespace);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            self.pending_space |= starts_with_space;
+            return;
+        }
+        self.pending_space |= starts_with_space;
+        self.inline(&words.join(" "));
+        self.pending_space = ends_with_space;
+    }
+
+    /// Inline content, preceded by any owed space unless at a line start.
+    fn inline(&mut self, s: &str) {
+        if self.pending_space && !self.at_line_start() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+        self.out.push_str(s);
+    }
+
+    fn raw(&mut self, s: &str) {
+        self.pending_space = false;
+        self.out.push_str(s);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n') || self.out.ends_with("> ")
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+    }
+
+    fn newline(&mut self) {
+        self.trim_trailing_spaces();
+        self.pending_space = false;
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::with_capacity(self.out.len());
+        let mut blank_run = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_run += 1;
+                if blank_run > 1 {
+                    continue;
+                }
+            } else {
+                blank_run = 0;
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn ctx() -> ToolContext {
+        let mut ctx = ToolContext::new(PathBuf::from("."), vec![PathBuf::from(".")]);
+        ctx.timeout = Duration::from_secs(5);
+        ctx
+    }
+
+    fn fetch(config: WebConfig, input: Value) -> ToolOutput {
+        WebFetchTool { config }.execute(input, &ctx()).unwrap()
+    }
+
+    /// The mock servers listen on 127.0.0.1, which has to be allow-listed.
+    fn local() -> WebConfig {
+        WebConfig {
+            allow_domains: vec!["127.0.0.1".to_string()],
+            ..WebConfig::default()
+        }
+    }
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Release &amp; Notes</title>
+<style>body { color: red }</style><script>alert("x")</script></head>
+<body><nav><a href="/">Home</a></nav>
+<h1>Version 2</h1>
+<p>Read the <a href="/docs/guide">guide</a>, or
+   the <strong>changelog</strong>&nbsp;&mdash; it&#39;s <em>short</em>.</p>
+<ul><li>one</li><li>two <code>x</code></li></ul>
+<ol><li>first</li><li>second</li></ol>
+<pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+<!-- a comment -->
+</body></html>"#;
+
+    #[test]
+    fn test_html_to_markdown_structure() {
+        let base = Url::parse("https://example.com/releases/").unwrap();
+        let md = html_to_markdown(PAGE, Some(&base));
+        assert_eq!(
+            md,
+            "# Version 2\n\
+             \n\
+             Read the [guide](https://example.com/docs/guide), or the **changelog** — it's *short*.\n\
+             \n\
+             - one\n\
+             - two `x`\n\
+             \n\
+             1. first\n\
+             2. second\n\
+             \n\
+             ```\n\
+             fn main() {\n    println!(\"hi\");\n}\n\
+             ```"
+        );
+        assert_eq!(html_title(PAGE).as_deref(), Some("Release & Notes"));
+    }
+
+    #[test]
+    fn test_decode_entities_leaves_unknown_references() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#x41;&#66; &bogus; & c"),
+            "a <b> AB &bogus; & c"
+        );
+    }
+
+    #[test]
+    fn test_domain_policy() {
+        let open = WebConfig::default();
+        assert!(open.allows_host("anything.test"));
+
+        let config = WebConfig {
+            allow_domains: vec!["docs.rs".to_string(), "*.github.com".to_string()],
+            deny_domains: vec!["gist.github.com".to_string()],
+            search: None,
+        };
+        assert!(config.allows_host("docs.rs"));
+        assert!(config.allows_host("api.github.com"));
+        assert!(config.allows_host("GitHub.com."));
+        assert!(!config.allows_host("gist.github.com"));
+        assert!(!config.allows_host("notgithub.com"));
+        assert!(!config.allows_host("example.com"));
+        assert!(config.check_url("ftp://docs.rs/x").is_err());
+        assert!(config.check_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_non_public_addresses_need_allow_list() {
+        let open = WebConfig::default();
+        for url in [
+            "http://127.0.0.1:8080/",
+            "http://10.1.2.3/",
+            "http://172.16.0.1/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fe80::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://0.1.2.3/",
+            "http://224.0.0.1/",
+            "http://240.0.0.1/",
+            "http://255.255.255.255/",
+            "http://[::]/",
+            "http://[ff02::1]/",
+            "http://[64:ff9b::7f00:1]/",
+            "http://[64:ff9b::a9fe:a9fe]/",
+            "http://[::127.0.0.1]/",
+            "http://[::169.254.169.254]/",
+        ] {
+            let err = open.check_url(url).unwrap_err();
+            assert!(err.contains("not a public address"), "{url}: {err}");
+        }
+        assert!(open.check_url("http://93.184.216.34/").is_ok());
+        assert!(open.check_url("http://[2606:4700::1111]/").is_ok());
+        assert!(open.check_url("http://[64:ff9b::5db8:d822]/").is_ok());
+
+        let metadata = WebConfig {
+            allow_domains: vec!["169.254.169.254".to_string()],
+            ..WebConfig::default()
+        };
+        assert!(metadata.check_url("http://169.254.169.254/").is_ok());
+        assert!(metadata.allows_address("169.254.169.254", "169.254.169.254".parse().unwrap()));
+        assert!(!metadata.allows_address("evil.test", "169.254.169.254".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_web_fetch_refuses_names_resolving_to_loopback() {
+        let mut server = mockito::Server::new();
+        let untouched = server.mock("GET", "/secret").expect(0).create();
+        let port = server.url().rsplit(':').next().unwrap().to_string();
+        let out = fetch(
+            WebConfig::default(),
+            serde_json::json!({ "url": format!("http://localhost:{}/secret", port) }),
+        );
+        assert!(out.is_error);
+        assert!(
+            out.content.contains("host 'localhost' is not a public address"),
+            "{}",
+            out.content
+        );
+        untouched.assert();
+    }
+
+    #[test]
+    fn test_web_fetch_converts_html() {
+        let mut server = mockito::Server::new();
+        let page = server
+            .mock("GET", "/releases")
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body(PAGE)
+            .create();
+
+        let out = fetch(
+            local(),
+            serde_json::json!({ "url": format!("{}/releases", server.url()) }),
+        );
+        page.assert();
+        assert!(!out.is_error, "{}", out.content);
+        assert!(out.content.contains("Title: Release & Notes"));
+        assert!(out.content.contains("# Version 2"));
+        assert!(out
+            .content
+            .contains(&format!("[guide]({}/docs/guide)", server.url())));
+        assert!(!out.content.contains("alert"));
+    }
+
+    #[test]
+    fn test_web_fetch_truncates_to_token_budget() {
+        let mut server = mockito::Server::new();
+        let body = "word ".repeat(1000);
+        server
+            .mock("GET", "/long.txt")
+            .with_header("content-type", "text/plain")
+            .with_body(&body)
+            .create();
+
+        let out = fetch(
+            local(),
+            serde_json::json!({ "url": format!("{}/long.txt", server.url()), "max_tokens": 40 }),
+        );
+        assert!(!out.is_error);
+        assert_eq!(out.content.matches("word").count(), 30);
+        assert!(out
+            .content
+            .contains("[truncated: about 40 of 1333 tokens shown"));
+    }
+
+    #[test]
+    fn test_web_fetch_refuses_denied_hosts_and_redirects() {
+        let mut server = mockito::Server::new();
+        let untouched = server.mock("GET", "/secret").expect(0).create();
+        let denied = WebConfig {
+            deny_domains: vec!["127.0.0.1".to_string()],
+            ..WebConfig::default()
+        };
+        let out = fetch(
+            denied,
+            serde_json::json!({ "url": format!("{}/secret", server.url()) }),
+        );
+        assert!(out.is_error);
+        assert!(out.content.contains("blocked by the domain policy"));
+        untouched.assert();
+
+        // Allowed first hop, redirect to a host outside the allow list.
+        let port = server.url().rsplit(':').next().unwrap().to_string();
+        server
+            .mock("GET", "/hop")
+            .with_status(302)
+            .with_header("location", &format!("http://localhost:{}/secret", port))
+            .create();
+        let allow_loopback_ip = WebConfig {
+            allow_domains: vec!["127.0.0.1".to_string()],
+            ..WebConfig::default()
+        };
+        let out = fetch(
+            allow_loopback_ip,
+            serde_json::json!({ "url": format!("{}/hop", server.url()) }),
+        );
+        assert!(out.is_error);
+        assert!(
+            out.content
+                .contains("redirect to host 'localhost' is blocked"),
+            "{}",
+            out.content
+        );
+    }
+
+    #[test]
+    fn test_web_fetch_reports_http_errors_and_binary_types() {
+        let mut server = mockito::Server::new();
+        server.mock("GET", "/missing").with_status(404).create();
+        server
+            .mock("GET", "/logo.png")
+            .with_header("content-type", "image/png")
+            .with_body([0x89u8, b'P', b'N', b'G'])
+            .create();
+
+        let out = fetch(
+            local(),
+            serde_json::json!({ "url": format!("{}/missing", server.url()) }),
+        );
+        assert!(out.is_error);
+        assert!(out.content.contains("HTTP 404"));
+
+        let out = fetch(
+            local(),
+            serde_json::json!({ "url": format!("{}/logo.png", server.url()) }),
+        );
+        assert!(out.is_error);
+        assert!(out.content.contains("unsupported content type 'image/png'"));
+    }
+
+    #[test]
+    fn test_web_search_searxng_filters_by_policy() {
+        let mut server = mockito::Server::new();
+        let search = server
+            .mock("GET", "/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), "rust mcp".into()),
+                mockito::Matcher::UrlEncoded("format".into(), "json".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "results": [
+                        { "title": "Spec", "url": "https://modelcontextprotocol.io/spec", "content": "The  protocol\nspec." },
+                        { "title": "Spam", "url": "https://spam.example/x", "content": "no" },
+                        { "title": "", "url": "https://docs.rs/rmcp", "content": "" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create();
+
+        let config = WebConfig {
+            deny_domains: vec!["spam.example".to_string()],
+            search: Some(Arc::new(SearxngProvider::new(&server.url()))),
+            ..WebConfig::default()
+        };
+        let tools = web_tools(&config);
+        assert_eq!(
+            tools.iter().map(|t| t.name()).collect::<Vec<_>>(),
+            ["web_fetch", "web_search"]
+        );
+        let out = tools[1]
+            .execute(serde_json::json!({ "query": "rust mcp" }), &ctx())
+            .unwrap();
+        search.assert();
+        assert!(!out.is_error, "{}", out.content);
+        assert_eq!(
+            out.content,
+            "1. [Spec](https://modelcontextprotocol.io/spec)\n   The protocol spec.\n\
+             2. [https://docs.rs/rmcp](https://docs.rs/rmcp)"
+        );
+        assert_eq!(web_tools(&WebConfig::default()).len(), 1);
+    }
+}
@@ -0,0 +1,335 @@
+//! `git worktree` isolation for sub-agents and eval rollouts.
+//!
+//! A [`Worktree`] checks the current commit of a repository out into a fresh
+//! temp directory so an agent gets a real repo (history, branches, `git`
+//! commands) without touching the caller's tree, and parallel agents never
+//! edit the same files. When the agent is done, [`Worktree::patch`] captures
+//! everything it changed as a patch the caller can review and `git apply`, or
+//! throw away. The worktree is removed when the guard is dropped, so every
+//! exit path — success, error, panic, a cancelled task dropping its future —
+//! cleans up.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorktreeError {
+    #[error("E_AIKIT_WORKTREE_NOT_A_REPO: '{0}' is not inside a git work tree")]
+    NotARepo(PathBuf),
+    #[error("E_AIKIT_WORKTREE_GIT: git {args} failed: {stderr}")]
+    Git { args: String, stderr: String },
+    #[error("E_AIKIT_WORKTREE_IO: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The changes made inside a [`Worktree`], relative to the commit it was
+/// created from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorktreePatch {
+    /// Paths relative to the repository root.
+    pub changed_files: Vec<PathBuf>,
+    /// `git diff --binary` output, byte for byte; empty when nothing
+    /// changed.
+    pub diff: Vec<u8>,
+}
+
+impl WorktreePatch {
+    pub fn is_empty(&self) -> bool {
+        self.diff.is_empty()
+    }
+
+    /// The diff for display. Non-UTF-8 content is replaced, so apply
+    /// [`WorktreePatch::diff`] or a saved patch file, never this.
+    pub fn diff_text(&self) -> String {
+        String::from_utf8_lossy(&self.diff).into_owned()
+    }
+}
+
+/// A detached `git worktree` of the repository containing some directory,
+/// removed on drop.
+#[derive(Debug)]
+pub struct Worktree {
+    /// Top level of the repository the worktree belongs to.
+    repo_root: PathBuf,
+    /// Root of the checked-out worktree.
+    path: PathBuf,
+    /// The requested directory's position inside the repository.
+    subdir: PathBuf,
+    /// Commit the worktree was created at; patches are taken against it so
+    /// commits the agent makes are still included.
+    base: String,
+}
+
+impl Worktree {
+    /// Check out `HEAD` of the repository containing `dir` into a new temp
+    /// directory. Uncommitted changes in the caller's tree are not carried
+    /// over.
+    pub fn create(dir: &Path) -> Result<Self, WorktreeError> {
+        let repo_root = git(dir, &["rev-parse", "--show-toplevel"])
+            .map_err(|_| WorktreeError::NotARepo(dir.to_path_buf()))?;
+        let repo_root = PathBuf::from(repo_root.trim());
+        let subdir = dir
+            .canonicalize()?
+            .strip_prefix(repo_root.canonicalize()?)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let base = git(&repo_root, &["rev-parse", "HEAD"])?.trim().to_string();
+
+        // Worktrees whose directories vanished (a killed process never ran
+        // its drop) would otherwise stay registered forever.
+        let _ = git(&repo_root, &["worktree", "prune"]);
+
+        let path = next_worktree_path();
+        let path_arg = path.to_string_lossy().into_owned();
+        git(
+            &repo_root,
+            &["worktree", "add", "--detach", &path_arg, &base],
+        )?;
+
+        Ok(Self {
+            repo_root,
+            path,
+            subdir,
+            base,
+        })
+    }
+
+    /// Root of the checked-out worktree.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The directory passed to [`Worktree::create`], mapped into the
+    /// worktree. Run the agent here.
+    pub fn workdir(&self) -> PathBuf {
+        self.path.join(&self.subdir)
+    }
+
+    /// The commit the worktree was created at.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Everything changed in the worktree since [`Worktree::base`]: edits,
+    /// new and deleted files, and anything the agent committed.
+    pub fn patch(&self) -> Result<WorktreePatch, WorktreeError> {
+        // Stage in the worktree's own index so untracked files are part of
+        // the diff; the caller's index is untouched.
+        git(&self.path, &["add", "-A"])?;
+        let names = git(&self.path, &["diff", "--cached", "--name-only", &self.base])?;
+        let diff = git_bytes(&self.path, &["diff", "--cached", "--binary", &self.base])?;
+        Ok(WorktreePatch {
+            changed_files: names.lines().map(PathBuf::from).collect(),
+            diff,
+        })
+    }
+
+    /// Write `patch` under the repository's git directory, where it survives
+    /// the worktree and never shows up as an untracked file. The caller can
+    /// merge it with `git apply <path>`.
+    ///
+    /// The file is named `<name>-<pid>-<unix millis>-<n>.patch`, where `n`
+    /// counts saves in this process, so no two saves share a name; an
+    /// existing file is never overwritten.
+    pub fn save_patch(&self, patch: &WorktreePatch, name: &str) -> Result<PathBuf, WorktreeError> {
+        let common = git(&self.repo_root, &["rev-parse", "--git-common-dir"])?;
+        let common = self.repo_root.join(common.trim());
+        let dir = common.join("aikit").join("patches");
+        std::fs::create_dir_all(&dir)?;
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let file = dir.join(format!(
+            "{}-{}-{}-{}.patch",
+            name,
+            std::process::id(),
+            millis,
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut out = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file)?;
+        out.write_all(&patch.diff)?;
+        Ok(file)
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let path_arg = self.path.to_string_lossy().into_owned();
+        if git(
+            &self.repo_root,
+            &["worktree", "remove", "--force", "--force", &path_arg],
+        )
+        .is_err()
+        {
+            let _ = std::fs::remove_dir_all(&self.path);
+            let _ = git(&self.repo_root, &["worktree", "prune"]);
+        }
+    }
+}
+
+/// A not-yet-existing temp path, unique per process and call.
+fn next_worktree_path() -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    std::env::temp_dir().join(format!(
+        "aikit-worktree-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Run `git <args>` in `dir` and return stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String, WorktreeError> {
+    git_bytes(dir, args).map(|out| String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Run `git <args>` in `dir` and return stdout unchanged.
+fn git_bytes(dir: &Path, args: &[&str]) -> Result<Vec<u8>, WorktreeError> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(WorktreeError::Git {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A repository with one commit: `README.md` and `src/lib.rs`.
+    pub(crate) fn init_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("README.md"), "hello\n").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["add", "-A"],
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "init",
+            ],
+        ] {
+            git(dir, args).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn test_worktree_checks_out_head_and_maps_subdir() {
+        let repo = init_repo();
+        let wt = Worktree::create(&repo.path().join("src")).unwrap();
+        assert!(wt.path().join("README.md").exists());
+        assert_eq!(wt.workdir(), wt.path().join("src"));
+        assert!(wt.workdir().join("lib.rs").exists());
+        assert_eq!(wt.base().len(), 40);
+    }
+
+    #[test]
+    fn test_worktree_patch_covers_edits_new_files_and_commits() {
+        let repo = init_repo();
+        let wt = Worktree::create(repo.path()).unwrap();
+        assert!(wt.patch().unwrap().is_empty());
+
+        std::fs::write(wt.path().join("README.md"), "hello world\n").unwrap();
+        std::fs::write(wt.path().join("new.txt"), "new\n").unwrap();
+        let patch = wt.patch().unwrap();
+        assert_eq!(
+            patch.changed_files,
+            vec![PathBuf::from("README.md"), PathBuf::from("new.txt")]
+        );
+        assert!(patch.diff_text().contains("+hello world"));
+
+        // The caller's tree is untouched and the patch applies to it.
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+            "hello\n"
+        );
+        let saved = wt.save_patch(&patch, "t").unwrap();
+        git(repo.path(), &["apply", &saved.to_string_lossy()]).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("new.txt")).unwrap(),
+            "new\n"
+        );
+    }
+
+    #[test]
+    fn test_saved_patches_keep_raw_bytes_and_never_overwrite() {
+        let repo = init_repo();
+        let wt = Worktree::create(repo.path()).unwrap();
+        let latin1 = b"caf\xe9\n".to_vec();
+        std::fs::write(wt.path().join("README.md"), &latin1).unwrap();
+        let patch = wt.patch().unwrap();
+
+        let first = wt.save_patch(&patch, "same").unwrap();
+        let second = wt.save_patch(&patch, "same").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), patch.diff);
+
+        git(repo.path(), &["apply", &first.to_string_lossy()]).unwrap();
+        assert_eq!(
+            std::fs::read(repo.path().join("README.md")).unwrap(),
+            latin1
+        );
+    }
+
+    #[test]
+    fn test_worktree_removed_on_drop() {
+        let repo = init_repo();
+        let wt = Worktree::create(repo.path()).unwrap();
+        let path = wt.path().to_path_buf();
+        std::fs::write(path.join("dirty.txt"), "x").unwrap();
+        drop(wt);
+        assert!(!path.exists());
+        let list = git(repo.path(), &["worktree", "list"]).unwrap();
+        assert_eq!(list.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_worktree_removed_when_owner_panics() {
+        let repo = init_repo();
+        let dir = repo.path().to_path_buf();
+        let path = std::thread::spawn(move || {
+            let wt = Worktree::create(&dir).unwrap();
+            let path = wt.path().to_path_buf();
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+                let _wt = wt;
+                panic!("agent crashed");
+            }));
+            path
+        })
+        .join()
+        .unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_worktree_requires_a_repo() {
+        let tmp = TempDir::new().unwrap();
+        assert!(matches!(
+            Worktree::create(tmp.path()),
+            Err(WorktreeError::NotARepo(_))
+        ));
+    }
+}
@@ -25,7 +25,8 @@ pub use checks::{
 };
 pub use config::{resolve_from_input, EvalConfig, EvalConfigError, EvalConfigInput};
 pub use runner::{
-    run_eval_case, AikitEvalRunner, CaseRunOptions, CaseRunOutput, EvalRunner, RunnerError,
+    run_eval_case, AikitEvalRunner, CaseRunOptions, CaseRunOutput, EvalRunner, Isolation,
+    RunnerError,
 };
 pub use scoring::{item_score, score_cases, split_score, ChecksScorer, GateMetric, Scorer};
 pub use suite::{load_suite, EvalCase, EvalSuite, SuiteError};
@@ -4,8 +4,10 @@ use crate::artifacts::{CaseResult, CaseStatus, CaseTrialsResult, TrialResult};
 use crate::checks::{count_command_events, run_checks, CheckDefinition};
 use crate::suite::EvalCase;
 use crate::trace::{agent_events_to_trace, trace_to_jsonl, TraceEvent, TracePayload};
-use aikit_sdk::{run_agent_events, AgentEvent, RunOptions};
+use aikit_sdk::{run_agent_events, AgentEvent, RunOptions, Worktree};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -13,6 +15,35 @@ use thiserror::Error;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
+/// Where a case's agent runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Isolation {
+    /// Directly in the project root (or its `workspace_subdir`).
+    #[default]
+    Shared,
+    /// In a fresh `git worktree` of the repository containing the project
+    /// root, checked out at `HEAD` and removed after the case. Parallel
+    /// trials never see each other's edits, and the agent's changes come
+    /// back as [`CaseRunOutput::patch`].
+    Worktree,
+}
+
+impl std::str::FromStr for Isolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shared" | "none" => Ok(Self::Shared),
+            "worktree" => Ok(Self::Worktree),
+            other => Err(format!(
+                "unknown isolation '{}' (expected shared or worktree)",
+                other
+            )),
+        }
+    }
+}
+
 /// Options for running a single eval case
 #[derive(Debug, Clone)]
 pub struct CaseRunOptions {
@@ -26,6 +57,8 @@ pub struct CaseRunOptions {
     pub timeout_seconds: u64,
     /// Per-case trial aggregation pass threshold (0.0-1.0)
     pub pass_threshold: f64,
+    /// Where the agent runs
+    pub isolation: Isolation,
 }
 
 /// Raw output from running a case
@@ -35,6 +68,10 @@ pub struct CaseRunOutput {
     pub stderr: Vec<u8>,
     pub exit_code: Option<i32>,
     pub timed_out: bool,
+    /// The agent's changes as raw `git diff --binary` output under
+    /// [`Isolation::Worktree`]; `None` when isolation is off or nothing
+    /// changed.
+    pub patch: Option<Vec<u8>>,
 }
 
 /// Errors during case execution
@@ -99,93 +136,50 @@ impl EvalRunner for AikitEvalRunner {
         trial_count: u32,
         max_parallelism: Option<u32>,
     ) -> CaseTrialsResult {
-        let max_parallel = max_parallelism
-            .unwrap_or_else(|| num_cpus::get().max(1) as u32)
-            .max(1) as usize;
-        let semaphore = Arc::new(Semaphore::new(max_parallel));
-        let mut join_set: JoinSet<TrialResult> = JoinSet::new();
-
-        for trial_id in 1..=trial_count {
-            let permit = Arc::clone(&semaphore);
-            let case_clone = case.clone();
-            let opts_clone = opts.clone();
-            let checks_vec = checks.to_vec();
-            let runner = *self;
-
-            join_set.spawn(async move {
-                let Ok(_permit) = permit.acquire().await else {
-                    return TrialResult {
-                        trial_id,
-                        status: CaseStatus::Error,
-                        command_count: None,
-                        input_tokens: None,
-                        output_tokens: None,
-                        check_results: vec![],
-                        error_message: Some(
-                            "EVAL_PARALLEL_EXHAUSTION: semaphore closed".to_string(),
-                        ),
-                    };
-                };
-                let (_output, case_result, _trace) = runner
-                    .run_case_inner(&case_clone, &opts_clone, &checks_vec)
-                    .await;
-                TrialResult {
-                    trial_id,
-                    status: case_result.status,
-                    command_count: case_result.command_count,
-                    input_tokens: case_result.input_tokens,
-                    output_tokens: case_result.output_tokens,
-                    check_results: case_result.check_results,
-                    error_message: case_result.error_message,
-                }
-            });
-        }
-
-        let mut trials = Vec::with_capacity(trial_count as usize);
-        while let Some(res) = join_set.join_next().await {
-            match res {
-                Ok(trial) => trials.push(trial),
-                Err(e) => {
-                    // Join errors are treated as failed trials.
-                    let next_id = (trials.len() as u32) + 1;
-                    trials.push(TrialResult {
-                        trial_id: next_id,
-                        status: CaseStatus::Error,
-                        command_count: None,
-                        input_tokens: None,
-                        output_tokens: None,
-                        check_results: vec![],
-                        error_message: Some(format!("EVAL_PARALLEL_EXHAUSTION: {}", e)),
-                    });
-                }
-            }
-        }
-
-        trials.sort_by_key(|t| t.trial_id);
-        let pass_count = trials
-            .iter()
-            .filter(|t| t.status == CaseStatus::Passed)
-            .count() as u32;
-        let total_trials = trial_count.max(1);
-        let pass_rate = pass_count as f64 / total_trials as f64;
-        let aggregated_status = if pass_rate >= opts.pass_threshold {
-            CaseStatus::Passed
-        } else {
-            CaseStatus::Failed
-        };
-
-        CaseTrialsResult {
-            id: case.id.clone(),
-            trials,
-            aggregated_status,
-            pass_count,
-            total_trials,
-            pass_rate,
+        let no_hook =
+            |_: &TrialResult, _: &CaseRunOutput, _: &str| Ok::<(), std::convert::Infallible>(());
+        match self
+            .run_case_trials_with(case, opts, checks, trial_count, max_parallelism, no_hook)
+            .await
+        {
+            Ok(result) => result,
+            Err(never) => match never {},
         }
     }
 }
 
 impl AikitEvalRunner {
+    /// [`EvalRunner::run_case_trials`], calling `on_trial` with each finished
+    /// trial's raw output and trace JSONL (in completion order) so callers
+    /// can keep per-trial artifacts. A trial whose task panicked or was
+    /// cancelled is recorded as an `Error` trial carrying the join error, with
+    /// empty output. The first error from `on_trial` stops the remaining
+    /// trials and is returned.
+    pub async fn run_case_trials_with<E>(
+        &self,
+        case: &EvalCase,
+        opts: &CaseRunOptions,
+        checks: &[CheckDefinition],
+        trial_count: u32,
+        max_parallelism: Option<u32>,
+        on_trial: impl FnMut(&TrialResult, &CaseRunOutput, &str) -> Result<(), E>,
+    ) -> Result<CaseTrialsResult, E> {
+        let runner = *self;
+        let run_trial = || {
+            let (case, opts, checks) = (case.clone(), opts.clone(), checks.to_vec());
+            async move { runner.run_case_inner(&case, &opts, &checks).await }
+        };
+        run_trials(
+            &case.id,
+            opts.pass_threshold,
+            trial_count,
+            max_parallelism,
+            run_trial,
+            on_trial,
+        )
+        .await
+    }
+
     async fn run_case_inner(
         &self,
         case: &EvalCase,
@@ -202,6 +196,18 @@ impl AikitEvalRunner {
             None => opts.project_root.clone(),
         };
 
+        // Shared with the blocking task: if this future is dropped mid-run,
+        // the worktree outlives it until the agent exits, then is removed.
+        let worktree = match opts.isolation {
+            Isolation::Shared => None,
+            Isolation::Worktree => match Worktree::create(&working_dir) {
+                Ok(wt) => Some(Arc::new(wt)),
+                Err(e) => return worktree_error(case, e),
+            },
+        };
+        let working_dir = worktree.as_ref().map_or(working_dir, |wt| wt.workdir());
+        let task_worktree = worktree.clone();
+
         let mut run_opts = RunOptions::new()
             .with_yolo(true)
             .with_stream(true)
@@ -215,6 +221,7 @@ impl AikitEvalRunner {
         }
 
         let spawn_result = tokio::task::spawn_blocking(move || {
+            let _worktree = task_worktree;
             let mut events: Vec<AgentEvent> = Vec::new();
             let result = run_agent_events(&agent_key, &prompt, run_opts, |ev| {
                 events.push(ev.clone());
@@ -222,7 +229,7 @@ impl AikitEvalRunner {
             AgentExecutionResult { result, events }
         });
 
-        let (run_output, trace_events, token_usage) = match spawn_result.await {
+        let (mut run_output, trace_events, token_usage) = match spawn_result.await {
             Ok(exec_result) => match exec_result.result {
                 Ok(run_result) => {
                     let token_usage = run_result.token_usage.clone();
@@ -232,6 +239,7 @@ impl AikitEvalRunner {
                         stderr: run_result.stderr,
                         exit_code,
                         timed_out: false,
+                        patch: None,
                     };
                     let trace = agent_events_to_trace(&exec_result.events);
                     (output, trace, token_usage)
@@ -249,6 +257,7 @@ impl AikitEvalRunner {
                         stderr,
                         exit_code: None,
                         timed_out: true,
+                        patch: None,
                     };
                     if output.stderr.is_empty() {
                         let fallback = format!("Case timed out after {}s", timeout.as_secs());
@@ -257,6 +266,7 @@ impl AikitEvalRunner {
                             stderr: fallback.into_bytes(),
                             exit_code: None,
                             timed_out: true,
+                            patch: None,
                         };
                         (output, trace, None)
                     } else {
@@ -270,6 +280,7 @@ impl AikitEvalRunner {
                         stderr: format!("Agent execution failed: {}", e).into_bytes(),
                         exit_code: None,
                         timed_out: false,
+                        patch: None,
                     };
                     (output, trace, None)
                 }
@@ -280,6 +291,7 @@ impl AikitEvalRunner {
                     stderr: format!("spawn_blocking failed: {}", e).into_bytes(),
                     exit_code: None,
                     timed_out: false,
+                    patch: None,
                 };
                 (output, vec![], None)
             }
@@ -290,6 +302,9 @@ impl AikitEvalRunner {
         let command_count = count_command_events(&trace_jsonl);
         let check_results = run_checks(checks, &stdout_str, &trace_jsonl, &working_dir);
         let all_passed = check_results.iter().all(|r| r.passed);
+        if let Some(wt) = &worktree {
+            run_output.patch = wt.patch().ok().filter(|p| !p.is_empty()).map(|p| p.diff);
+        }
 
         let status = if run_output.timed_out {
             CaseStatus::Error
@@ -326,6 +341,115 @@ impl AikitEvalRunner {
     }
 }
 
+/// Run `trial_count` trials of one case, up to `max_parallelism` at a time,
+/// and aggregate them; see [`AikitEvalRunner::run_case_trials_with`].
+async fn run_trials<E, F, Fut>(
+    case_id: &str,
+    pass_threshold: f64,
+    trial_count: u32,
+    max_parallelism: Option<u32>,
+    run_trial: F,
+    mut on_trial: impl FnMut(&TrialResult, &CaseRunOutput, &str) -> Result<(), E>,
+) -> Result<CaseTrialsResult, E>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = (CaseRunOutput, CaseResult, String)> + Send + 'static,
+{
+    let max_parallel = max_parallelism
+        .unwrap_or_else(|| num_cpus::get().max(1) as u32)
+        .max(1) as usize;
+    let semaphore = Arc::new(Semaphore::new(max_parallel));
+    let mut join_set = JoinSet::new();
+    let mut trial_ids = HashMap::new();
+
+    for trial_id in 1..=trial_count {
+        let permit = Arc::clone(&semaphore);
+        let trial_run = run_trial();
+        let handle = join_set.spawn(async move {
+            let Ok(_permit) = permit.acquire().await else {
+                let trial = failed_trial(
+                    trial_id,
+                    "EVAL_PARALLEL_EXHAUSTION: semaphore closed".to_string(),
+                );
+                return (trial, empty_output(), String::new());
+            };
+            let (output, case_result, trace) = trial_run.await;
+            let trial = TrialResult {
+                trial_id,
+                status: case_result.status,
+                command_count: case_result.command_count,
+                input_tokens: case_result.input_tokens,
+                output_tokens: case_result.output_tokens,
+                check_results: case_result.check_results,
+                error_message: case_result.error_message,
+            };
+            (trial, output, trace)
+        });
+        trial_ids.insert(handle.id(), trial_id);
+    }
+
+    let mut trials = Vec::with_capacity(trial_count as usize);
+    while let Some(res) = join_set.join_next().await {
+        let (trial, output, trace) = match res {
+            Ok(finished) => finished,
+            Err(e) => {
+                let trial_id = trial_ids.get(&e.id()).copied().unwrap_or(0);
+                let trial = failed_trial(trial_id, format!("EVAL_TRIAL_ABORTED: {}", e));
+                (trial, empty_output(), String::new())
+            }
+        };
+        on_trial(&trial, &output, &trace)?;
+        trials.push(trial);
+    }
+
+    trials.sort_by_key(|t| t.trial_id);
+    let pass_count = trials
+        .iter()
+        .filter(|t| t.status == CaseStatus::Passed)
+        .count() as u32;
+    let total_trials = trial_count.max(1);
+    let pass_rate = pass_count as f64 / total_trials as f64;
+    let aggregated_status = if pass_rate >= pass_threshold {
+        CaseStatus::Passed
+    } else {
+        CaseStatus::Failed
+    };
+
+    Ok(CaseTrialsResult {
+        id: case_id.to_string(),
+        trials,
+        aggregated_status,
+        pass_count,
+        total_trials,
+        pass_rate,
+    })
+}
+
+/// The result of a case whose worktree could not be created.
+fn worktree_error(
+    case: &EvalCase,
+    error: aikit_sdk::WorktreeError,
+) -> (CaseRunOutput, CaseResult, String) {
+    let message = format!("EVAL_WORKTREE_FAILED: {}", error);
+    let output = CaseRunOutput {
+        stdout: vec![],
+        stderr: message.clone().into_bytes(),
+        exit_code: None,
+        timed_out: false,
+        patch: None,
+    };
+    let case_result = CaseResult {
+        id: case.id.clone(),
+        status: CaseStatus::Error,
+        command_count: None,
+        input_tokens: None,
+        output_tokens: None,
+        check_results: vec![],
+        error_message: Some(message),
+    };
+    (output, case_result, String::new())
+}
+
 /// Run a single eval case using the default [`AikitEvalRunner`].
 pub async fn run_eval_case(
     case: &EvalCase,
@@ -335,6 +459,30 @@ pub async fn run_eval_case(
     AikitEvalRunner.run_case(case, opts, checks).await
 }
 
+/// An `Error` trial that never produced a case result.
+fn failed_trial(trial_id: u32, error_message: String) -> TrialResult {
+    TrialResult {
+        trial_id,
+        status: CaseStatus::Error,
+        command_count: None,
+        input_tokens: None,
+        output_tokens: None,
+        check_results: vec![],
+        error_message: Some(error_message),
+    }
+}
+
+/// Output of a trial that never ran the agent.
+fn empty_output() -> CaseRunOutput {
+    CaseRunOutput {
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+        exit_code: None,
+        timed_out: false,
+        patch: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,6 +506,7 @@ mod tests {
                 stderr: vec![],
                 exit_code: Some(0),
                 timed_out: false,
+                patch: None,
             };
             let result = CaseResult {
                 id: case.id.clone(),
@@ -429,6 +578,7 @@ mod tests {
             project_root: PathBuf::from("/tmp"),
             timeout_seconds: 1,
             pass_threshold: 1.0,
+            isolation: Isolation::Shared,
         };
         let runner = StubEvalRunner;
         let (out, res, trace) = runner.run_case(&case, &opts, &[]).await;
@@ -445,11 +595,48 @@ mod tests {
             project_root: PathBuf::from("/tmp"),
             timeout_seconds: 300,
             pass_threshold: 1.0,
+            isolation: Isolation::Shared,
         };
         assert_eq!(opts.agent_key, "codex");
         assert_eq!(opts.model, Some("gpt-4".to_string()));
     }
 
+    #[test]
+    fn test_isolation_from_str() {
+        assert_eq!("shared".parse::<Isolation>(), Ok(Isolation::Shared));
+        assert_eq!("none".parse::<Isolation>(), Ok(Isolation::Shared));
+        assert_eq!("worktree".parse::<Isolation>(), Ok(Isolation::Worktree));
+        assert!("docker".parse::<Isolation>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_worktree_isolation_outside_a_repo_is_an_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let case = EvalCase {
+            id: "wt".to_string(),
+            prompt: "p".to_string(),
+            should_trigger: true,
+            tags: vec![],
+            workspace_subdir: None,
+        };
+        let opts = CaseRunOptions {
+            agent_key: "agent".to_string(),
+            model: None,
+            project_root: dir.path().to_path_buf(),
+            timeout_seconds: 1,
+            pass_threshold: 1.0,
+            isolation: Isolation::Worktree,
+        };
+        let (out, res, trace) = AikitEvalRunner.run_case(&case, &opts, &[]).await;
+        assert_eq!(res.status, CaseStatus::Error);
+        assert!(res
+            .error_message
+            .unwrap()
+            .starts_with("EVAL_WORKTREE_FAILED"));
+        assert!(out.patch.is_none());
+        assert!(trace.is_empty());
+    }
+
     #[test]
     fn test_runner_error_display() {
         let err = RunnerError::AgentUnavailable("codex".to_string());
@@ -472,6 +659,7 @@ mod tests {
             project_root: PathBuf::from("/tmp"),
             timeout_seconds: 1,
             pass_threshold: 1.0,
+            isolation: Isolation::Shared,
         };
         let runner = StubEvalRunner;
         let (_out, res, _trace) = runner.run_case(&case, &opts, &[]).await;
@@ -502,6 +690,7 @@ mod tests {
             project_root: PathBuf::from("/tmp"),
             timeout_seconds: 1,
             pass_threshold: 1.0,
+            isolation: Isolation::Shared,
         };
         let runner = StubEvalRunner;
         let trials_result = runner.run_case_trials(&case, &opts, &[], 2, None).await;
@@ -510,4 +699,47 @@ mod tests {
             assert_eq!(trial.output_tokens, Some(50));
         }
     }
+
+    #[tokio::test]
+    async fn test_panicked_trials_are_recorded_and_reach_the_hook() {
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let run_trial = || {
+            let call = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                if call == 1 {
+                    panic!("agent exploded");
+                }
+                let result = CaseResult {
+                    id: "c".to_string(),
+                    status: CaseStatus::Passed,
+                    command_count: None,
+                    input_tokens: None,
+                    output_tokens: None,
+                    check_results: vec![],
+                    error_message: None,
+                };
+                (empty_output(), result, String::new())
+            }
+        };
+        let mut seen = Vec::new();
+        let result = run_trials("c", 0.5, 3, Some(1), run_trial, |trial, _, _| {
+            seen.push(trial.trial_id);
+            Ok::<(), std::convert::Infallible>(())
+        })
+        .await
+        .unwrap();
+
+        seen.sort();
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert_eq!(result.trials.len(), 3);
+        assert_eq!(result.pass_count, 2);
+        let failed = &result.trials[1];
+        assert_eq!(failed.trial_id, 2);
+        assert_eq!(failed.status, CaseStatus::Error);
+        assert!(failed
+            .error_message
+            .as_deref()
+            .unwrap()
+            .starts_with("EVAL_TRIAL_ABORTED"));
+    }
 }
@@ -191,7 +191,7 @@ mod tests {
     use std::sync::Mutex;
 
     use crate::artifacts::{CaseResult, CaseStatus, CaseTrialsResult, TrialResult};
-    use crate::runner::CaseRunOutput;
+    use crate::runner::{CaseRunOutput, Isolation};
     use crate::suite::EvalCase;
     use async_trait::async_trait;
 
@@ -228,6 +228,7 @@ mod tests {
             project_root: PathBuf::from("/tmp"),
             timeout_seconds: 1,
             pass_threshold: 1.0,
+            isolation: Isolation::Shared,
         }
     }
 
@@ -407,6 +408,7 @@ mod tests {
                 stderr: vec![],
                 exit_code: Some(0),
                 timed_out: false,
+                patch: None,
             };
             let result = CaseResult {
                 id: case.id.clone(),
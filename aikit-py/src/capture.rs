@@ -0,0 +1,311 @@
+//! Python bindings for passive session capture (`aikit-session-capture`).
+//!
+//! `CaptureStore` parses on-disk Claude Code / Codex / OpenCode sessions with
+//! the same adapters and `parse_session_file` → `upsert_events` pipeline
+//! `aikit serve` uses, into an in-memory `EventStore`, then answers the
+//! `EventStore` queries (sessions, actions, search, token events, file
+//! freshness). Rows are returned as dicts in their serde shape.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use aikit_session_capture::{
+    Adapter, CursorStore, EventBatch, EventStore, InMemoryCursorStore, InMemoryEventStore,
+    ParseCursor, Registry, ToolKind,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::{serialize_to_py, CaptureError};
+
+fn parse_tool(raw: &str) -> PyResult<ToolKind> {
+    match raw {
+        "claude_code" | "claudecode" | "claude" => Ok(ToolKind::ClaudeCode),
+        "codex" => Ok(ToolKind::Codex),
+        "open_code" | "opencode" => Ok(ToolKind::OpenCode),
+        other => Err(PyValueError::new_err(format!(
+            "unknown tool '{other}'; expected claude_code, codex or open_code"
+        ))),
+    }
+}
+
+/// Per-call ingest totals, returned by `scan` / `ingest_file`.
+#[derive(Default)]
+struct IngestOutcome {
+    files_scanned: u64,
+    events_upserted: u64,
+    warnings: Vec<String>,
+}
+
+impl IngestOutcome {
+    fn into_py(self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("files_scanned", self.files_scanned)?;
+        dict.set_item("events_upserted", self.events_upserted)?;
+        dict.set_item("warnings", self.warnings)?;
+        Ok(dict.into())
+    }
+}
+
+/// Captured agent sessions, queryable like serve's capture DB.
+///
+/// Ingestion is incremental: each file's parse offset is remembered, so a
+/// second `scan()` only reads what the agents appended since.
+#[pyclass(name = "CaptureStore")]
+pub struct PyCaptureStore {
+    runtime: tokio::runtime::Runtime,
+    registry: Registry,
+    events: InMemoryEventStore,
+    cursors: InMemoryCursorStore,
+}
+
+impl PyCaptureStore {
+    /// One file through the shared parse → upsert → save-cursor pipeline.
+    async fn ingest(&self, adapter: &dyn Adapter, path: &Path) -> Result<u64, String> {
+        let from_offset = self.cursors.load(path).await.map(|c| c.offset).unwrap_or(0);
+        let result = adapter
+            .parse_session_file(path, from_offset)
+            .await
+            .map_err(|e| e.to_string())?;
+        let inserted = self
+            .events
+            .upsert_events(EventBatch {
+                tool_events: result.tool_events,
+                token_events: result.token_events,
+                cache_observations: result.cache_observations,
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        self.cursors
+            .save(ParseCursor {
+                source_file: path.to_path_buf(),
+                offset: result.new_offset,
+                adapter_kind: adapter.kind(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await;
+        Ok(inserted)
+    }
+
+    fn adapter(&self, tool: ToolKind) -> PyResult<&dyn Adapter> {
+        self.registry.get(tool).ok_or_else(|| {
+            PyValueError::new_err(format!("tool '{}' is not enabled", tool.as_str()))
+        })
+    }
+}
+
+#[pymethods]
+impl PyCaptureStore {
+    /// `tools` restricts the adapters (default: all of claude_code, codex,
+    /// open_code). `roots` overrides a tool's session root directory, e.g.
+    /// `{"claude_code": "/data/alice/.claude/projects"}`.
+    #[new]
+    #[pyo3(signature = (tools=None, roots=None))]
+    fn new(tools: Option<Vec<String>>, roots: Option<HashMap<String, PathBuf>>) -> PyResult<Self> {
+        let allow = tools
+            .map(|ts| {
+                ts.iter()
+                    .map(|t| parse_tool(t))
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .transpose()?;
+        let mut overrides = HashMap::new();
+        for (tool, root) in roots.unwrap_or_default() {
+            overrides.insert(parse_tool(&tool)?, root);
+        }
+        let enabled = |kind: ToolKind| allow.as_ref().map_or(true, |a| a.contains(&kind));
+
+        let mut registry = Registry::new();
+        if enabled(ToolKind::ClaudeCode) {
+            let mut adapter = aikit_session_capture::claudecode::ClaudeCodeAdapter::new();
+            if let Some(root) = overrides.remove(&ToolKind::ClaudeCode) {
+                adapter = adapter.with_override_root(root);
+            }
+            registry.register(Box::new(adapter));
+        }
+        if enabled(ToolKind::Codex) {
+            let mut adapter = aikit_session_capture::codex::CodexAdapter::new();
+            if let Some(root) = overrides.remove(&ToolKind::Codex) {
+                adapter = adapter.with_override_root(root);
+            }
+            registry.register(Box::new(adapter));
+        }
+        if enabled(ToolKind::OpenCode) {
+            let mut adapter = aikit_session_capture::opencode::OpenCodeAdapter::new();
+            if let Some(root) = overrides.remove(&ToolKind::OpenCode) {
+                adapter = adapter.with_override_roots(vec![root]);
+            }
+            registry.register(Box::new(adapter));
+        }
+        if let Some(tool) = overrides.keys().next() {
+            return Err(PyValueError::new_err(format!(
+                "root given for tool '{}' which is not enabled",
+                tool.as_str()
+            )));
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| CaptureError::new_err(format!("tokio runtime: {e}")))?;
+        Ok(Self {
+            runtime,
+            registry,
+            events: InMemoryEventStore::new(),
+            cursors: InMemoryCursorStore::default(),
+        })
+    }
+
+    /// Walk every enabled adapter's session directories and ingest what is
+    /// new. Per-file failures are reported in `warnings`, not raised.
+    fn scan(&self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let outcome = py.detach(|| {
+            self.runtime.block_on(async {
+                let mut outcome = IngestOutcome::default();
+                for adapter in self.registry.all() {
+                    for watch_path in adapter.watch_paths() {
+                        if !watch_path.is_dir() {
+                            continue;
+                        }
+                        for entry in walkdir::WalkDir::new(&watch_path).into_iter().flatten() {
+                            let path = entry.path();
+                            if !entry.file_type().is_file() || !adapter.is_session_file(path) {
+                                continue;
+                            }
+                            match self.ingest(adapter, path).await {
+                                Ok(n) => {
+                                    outcome.files_scanned += 1;
+                                    outcome.events_upserted += n;
+                                }
+                                Err(e) => outcome.warnings.push(format!("{}: {e}", path.display())),
+                            }
+                        }
+                    }
+                }
+                outcome
+            })
+        });
+        outcome.into_py(py)
+    }
+
+    /// Ingest one session file. `tool` is inferred from the path when
+    /// omitted. Raises `CaptureError` if the file cannot be parsed.
+    #[pyo3(signature = (path, tool=None))]
+    fn ingest_file(
+        &self,
+        py: Python<'_>,
+        path: PathBuf,
+        tool: Option<&str>,
+    ) -> PyResult<Py<PyDict>> {
+        let adapter = match tool {
+            Some(t) => self.adapter(parse_tool(t)?)?,
+            None => self
+                .registry
+                .all()
+                .into_iter()
+                .find(|a| a.is_session_file(&path))
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "no enabled adapter recognises {}",
+                        path.display()
+                    ))
+                })?,
+        };
+        let inserted = py
+            .detach(|| self.runtime.block_on(self.ingest(adapter, &path)))
+            .map_err(CaptureError::new_err)?;
+        IngestOutcome {
+            files_scanned: 1,
+            events_upserted: inserted,
+            warnings: Vec::new(),
+        }
+        .into_py(py)
+    }
+
+    /// Parsed sessions for one tool, optionally restricted to a project dir.
+    #[pyo3(signature = (tool, *, cwd=None, limit=50, offset=0))]
+    fn sessions<'py>(
+        &self,
+        py: Python<'py>,
+        tool: &str,
+        cwd: Option<PathBuf>,
+        limit: u32,
+        offset: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let tool = parse_tool(tool)?;
+        let rows = py
+            .detach(|| {
+                self.runtime.block_on(
+                    self.events
+                        .sessions_for(tool, cwd.as_deref(), limit, offset),
+                )
+            })
+            .map_err(|e| CaptureError::new_err(e.to_string()))?;
+        serialize_to_py(py, &rows)
+    }
+
+    /// The action stream of one session, oldest first.
+    #[pyo3(signature = (tool, session_id, *, limit=100, offset=0))]
+    fn actions<'py>(
+        &self,
+        py: Python<'py>,
+        tool: &str,
+        session_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let tool = parse_tool(tool)?;
+        let rows = py
+            .detach(|| {
+                self.runtime.block_on(
+                    self.events
+                        .actions_for_session(tool, session_id, limit, offset),
+                )
+            })
+            .map_err(|e| CaptureError::new_err(e.to_string()))?;
+        serialize_to_py(py, &rows)
+    }
+
+    /// Search past tool outputs.
+    #[pyo3(signature = (query, *, limit=20))]
+    fn search<'py>(&self, py: Python<'py>, query: &str, limit: u32) -> PyResult<Bound<'py, PyAny>> {
+        let rows = py
+            .detach(|| {
+                self.runtime
+                    .block_on(self.events.search_outputs(query, limit))
+            })
+            .map_err(|e| CaptureError::new_err(e.to_string()))?;
+        serialize_to_py(py, &rows)
+    }
+
+    /// Per-turn token usage rows of one session.
+    fn token_events<'py>(
+        &self,
+        py: Python<'py>,
+        tool: &str,
+        session_id: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let tool = parse_tool(tool)?;
+        let rows = py
+            .detach(|| {
+                self.runtime
+                    .block_on(self.events.token_events_for_session(tool, session_id))
+            })
+            .map_err(|e| CaptureError::new_err(e.to_string()))?;
+        serialize_to_py(py, &rows)
+    }
+
+    /// Most recent agent read/edit/write of `path` vs its on-disk mtime, or
+    /// `None` when neither is known.
+    fn last_file_touch<'py>(
+        &self,
+        py: Python<'py>,
+        path: PathBuf,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let touch = py
+            .detach(|| self.runtime.block_on(self.events.last_file_touch(&path)))
+            .map_err(|e| CaptureError::new_err(e.to_string()))?;
+        touch.map(|t| serialize_to_py(py, &t)).transpose()
+    }
+}
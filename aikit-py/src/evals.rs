@@ -0,0 +1,750 @@
+//! Python bindings for `aikit-evals`: suites, checks, eval runs and run-dir
+//! readers.
+//!
+//! Suites, checks and results are typed pyclasses. A scorer is either the
+//! built-in `ChecksScorer` or any Python object with a
+//! `score(stdout, trace_jsonl, working_dir) -> list[CheckResult]` method.
+
+use std::path::{Path, PathBuf};
+
+use aikit_evals::{
+    allocate_run_dir, write_case_trials_summary, write_summary, write_trial_artifacts,
+    AikitEvalRunner, ArtifactsError, CaseResult, CaseRunOptions, CaseStatus, CaseSummary,
+    CaseTrialsResult, CheckDefinition, CheckResult, ChecksScorer, EvalCase, EvalSuite, GateMetric,
+    Isolation, Scorer, SummaryResult, TrialResult,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{serialize_to_py, EvalError};
+
+fn eval_to_py<T, E: std::fmt::Display>(result: Result<T, E>) -> PyResult<T> {
+    result.map_err(|e| EvalError::new_err(e.to_string()))
+}
+
+/// Multi-thread runtime for one blocking call: `AikitEvalRunner` runs
+/// trials on a `JoinSet` and the agent itself on `spawn_blocking`.
+pub(crate) fn runtime() -> PyResult<tokio::runtime::Runtime> {
+    tokio::runtime::Runtime::new().map_err(|e| EvalError::new_err(format!("tokio runtime: {e}")))
+}
+
+/// `"hard"`, `"soft"` or `"mixed"` (with `hard_weight`).
+pub(crate) fn parse_gate_metric(raw: &str, hard_weight: Option<f64>) -> PyResult<GateMetric> {
+    match (raw, hard_weight) {
+        ("hard", None) => Ok(GateMetric::Hard),
+        ("soft", None) => Ok(GateMetric::Soft),
+        ("mixed", Some(hard_weight)) => Ok(GateMetric::Mixed { hard_weight }),
+        ("mixed", None) => Err(PyValueError::new_err(
+            "gate metric 'mixed' requires hard_weight",
+        )),
+        ("hard" | "soft", Some(_)) => Err(PyValueError::new_err(
+            "hard_weight is only valid with gate metric 'mixed'",
+        )),
+        (other, _) => Err(PyValueError::new_err(format!(
+            "unknown gate metric '{other}'; expected hard, soft or mixed"
+        ))),
+    }
+}
+
+pub(crate) fn gate_metric_name(metric: &GateMetric) -> (&'static str, Option<f64>) {
+    match metric {
+        GateMetric::Hard => ("hard", None),
+        GateMetric::Soft => ("soft", None),
+        GateMetric::Mixed { hard_weight } => ("mixed", Some(*hard_weight)),
+    }
+}
+
+// ---- suites ----
+
+/// One eval case (a row of `prompts.csv`).
+#[pyclass(name = "EvalCase", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyEvalCase {
+    #[pyo3(get, set)]
+    pub id: String,
+    #[pyo3(get, set)]
+    pub prompt: String,
+    #[pyo3(get, set)]
+    pub should_trigger: bool,
+    #[pyo3(get, set)]
+    pub tags: Vec<String>,
+    #[pyo3(get, set)]
+    pub workspace_subdir: Option<PathBuf>,
+}
+
+#[pymethods]
+impl PyEvalCase {
+    #[new]
+    #[pyo3(signature = (id, prompt, *, should_trigger=true, tags=Vec::new(), workspace_subdir=None))]
+    fn new(
+        id: String,
+        prompt: String,
+        should_trigger: bool,
+        tags: Vec<String>,
+        workspace_subdir: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            id,
+            prompt,
+            should_trigger,
+            tags,
+            workspace_subdir,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("EvalCase(id={:?}, tags={:?})", self.id, self.tags)
+    }
+}
+
+impl From<EvalCase> for PyEvalCase {
+    fn from(case: EvalCase) -> Self {
+        Self {
+            id: case.id,
+            prompt: case.prompt,
+            should_trigger: case.should_trigger,
+            tags: case.tags,
+            workspace_subdir: case.workspace_subdir,
+        }
+    }
+}
+
+impl From<PyEvalCase> for EvalCase {
+    fn from(case: PyEvalCase) -> Self {
+        EvalCase {
+            id: case.id,
+            prompt: case.prompt,
+            should_trigger: case.should_trigger,
+            tags: case.tags,
+            workspace_subdir: case.workspace_subdir,
+        }
+    }
+}
+
+/// An ordered collection of eval cases.
+#[pyclass(name = "EvalSuite")]
+pub struct PyEvalSuite {
+    pub(crate) inner: EvalSuite,
+}
+
+#[pymethods]
+impl PyEvalSuite {
+    #[new]
+    fn new(cases: Vec<PyEvalCase>) -> Self {
+        Self {
+            inner: EvalSuite::new(cases.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    #[getter]
+    fn cases(&self) -> Vec<PyEvalCase> {
+        self.inner.cases.iter().cloned().map(Into::into).collect()
+    }
+
+    fn filter_by_id(&self, id: &str) -> Self {
+        Self {
+            inner: self.inner.filter_by_id(id),
+        }
+    }
+
+    fn filter_by_tag(&self, tag: &str) -> Self {
+        Self {
+            inner: self.inner.filter_by_tag(tag),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.cases.len()
+    }
+}
+
+/// Load a suite from a prompts CSV (`id,prompt,should_trigger,tags,workspace_subdir`).
+#[pyfunction]
+pub fn load_suite(path: PathBuf) -> PyResult<PyEvalSuite> {
+    Ok(PyEvalSuite {
+        inner: eval_to_py(aikit_evals::load_suite(&path))?,
+    })
+}
+
+// ---- checks ----
+
+/// One deterministic check from `checks.toml`.
+#[pyclass(name = "CheckDefinition", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyCheckDefinition {
+    pub(crate) inner: CheckDefinition,
+}
+
+#[pymethods]
+impl PyCheckDefinition {
+    /// Build a check from its TOML/JSON shape, e.g.
+    /// `{"name": "file_exists", "path": "out.txt"}`.
+    #[staticmethod]
+    fn from_dict(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let json: String = py
+            .import("json")?
+            .call_method1("dumps", (value,))?
+            .extract()?;
+        let inner = serde_json::from_str(&json)
+            .map_err(|e| PyValueError::new_err(format!("invalid check definition: {e}")))?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    #[getter]
+    fn required(&self) -> bool {
+        self.inner.is_required()
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        serialize_to_py(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CheckDefinition({:?})", self.inner)
+    }
+}
+
+/// Load the `[[check]]` entries of a `checks.toml`.
+#[pyfunction]
+pub fn load_checks(path: PathBuf) -> PyResult<Vec<PyCheckDefinition>> {
+    Ok(eval_to_py(aikit_evals::load_checks(&path))?
+        .into_iter()
+        .map(|inner| PyCheckDefinition { inner })
+        .collect())
+}
+
+pub(crate) fn check_definitions(checks: Option<Vec<PyCheckDefinition>>) -> Vec<CheckDefinition> {
+    checks
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| c.inner)
+        .collect()
+}
+
+/// Outcome of one check. Python scorers return a list of these.
+#[pyclass(name = "CheckResult", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyCheckResult {
+    #[pyo3(get, set)]
+    pub check_name: String,
+    #[pyo3(get, set)]
+    pub passed: bool,
+    #[pyo3(get, set)]
+    pub message: Option<String>,
+}
+
+#[pymethods]
+impl PyCheckResult {
+    #[new]
+    #[pyo3(signature = (check_name, passed, message=None))]
+    fn new(check_name: String, passed: bool, message: Option<String>) -> Self {
+        Self {
+            check_name,
+            passed,
+            message,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CheckResult(check_name={:?}, passed={})",
+            self.check_name,
+            if self.passed { "True" } else { "False" }
+        )
+    }
+}
+
+impl From<CheckResult> for PyCheckResult {
+    fn from(r: CheckResult) -> Self {
+        Self {
+            check_name: r.check_name,
+            passed: r.passed,
+            message: r.message,
+        }
+    }
+}
+
+impl From<PyCheckResult> for CheckResult {
+    fn from(r: PyCheckResult) -> Self {
+        CheckResult {
+            check_name: r.check_name,
+            passed: r.passed,
+            message: r.message,
+        }
+    }
+}
+
+fn check_results(results: Vec<CheckResult>) -> Vec<PyCheckResult> {
+    results.into_iter().map(Into::into).collect()
+}
+
+/// Run `checks` against one captured trajectory.
+#[pyfunction]
+pub fn run_checks(
+    checks: Vec<PyCheckDefinition>,
+    stdout: &str,
+    trace_jsonl: &str,
+    working_dir: PathBuf,
+) -> Vec<PyCheckResult> {
+    let checks = check_definitions(Some(checks));
+    check_results(aikit_evals::run_checks(
+        &checks,
+        stdout,
+        trace_jsonl,
+        &working_dir,
+    ))
+}
+
+/// Reduce one item's check results to a score in [0, 1].
+#[pyfunction]
+#[pyo3(signature = (results, metric="hard", hard_weight=None))]
+pub fn item_score(
+    results: Vec<PyCheckResult>,
+    metric: &str,
+    hard_weight: Option<f64>,
+) -> PyResult<f64> {
+    let metric = parse_gate_metric(metric, hard_weight)?;
+    let results: Vec<CheckResult> = results.into_iter().map(Into::into).collect();
+    Ok(aikit_evals::item_score(&results, &metric))
+}
+
+// ---- scorers ----
+
+/// The deterministic checks engine as a scorer.
+#[pyclass(name = "ChecksScorer")]
+pub struct PyChecksScorer {
+    checks: Vec<CheckDefinition>,
+}
+
+#[pymethods]
+impl PyChecksScorer {
+    #[new]
+    fn new(checks: Vec<PyCheckDefinition>) -> Self {
+        Self {
+            checks: check_definitions(Some(checks)),
+        }
+    }
+
+    fn score(&self, stdout: &str, trace_jsonl: &str, working_dir: PathBuf) -> Vec<PyCheckResult> {
+        check_results(aikit_evals::run_checks(
+            &self.checks,
+            stdout,
+            trace_jsonl,
+            &working_dir,
+        ))
+    }
+}
+
+/// A Python object implementing `score(stdout, trace_jsonl, working_dir)`.
+///
+/// `Scorer` cannot fail, so an exception or a malformed return value is
+/// reported as a single failed `python_scorer` check rather than lost.
+struct PythonScorer(Py<PyAny>);
+
+impl Scorer for PythonScorer {
+    fn score(&self, stdout: &str, trace_jsonl: &str, working_dir: &Path) -> Vec<CheckResult> {
+        Python::attach(|py| {
+            self.0
+                .call_method1(py, "score", (stdout, trace_jsonl, working_dir))
+                .and_then(|ret| ret.extract::<Vec<PyCheckResult>>(py))
+                .map(|results| results.into_iter().map(Into::into).collect())
+                .unwrap_or_else(|e| {
+                    vec![CheckResult {
+                        check_name: "python_scorer".to_string(),
+                        passed: false,
+                        message: Some(e.to_string()),
+                    }]
+                })
+        })
+    }
+}
+
+/// Resolve the `scorer` argument: a `ChecksScorer` runs natively, anything
+/// else must have a `score` method.
+pub(crate) fn resolve_scorer(scorer: &Bound<'_, PyAny>) -> PyResult<Box<dyn Scorer>> {
+    if let Ok(checks) = scorer.cast::<PyChecksScorer>() {
+        return Ok(Box::new(ChecksScorer {
+            checks: checks.borrow().checks.clone(),
+        }));
+    }
+    if !scorer.hasattr("score")? {
+        return Err(PyValueError::new_err(
+            "scorer must be a ChecksScorer or define score(stdout, trace_jsonl, working_dir)",
+        ));
+    }
+    Ok(Box::new(PythonScorer(scorer.clone().unbind())))
+}
+
+// ---- results ----
+
+/// One trial of a case.
+#[pyclass(name = "TrialResult", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyTrialResult {
+    #[pyo3(get)]
+    pub trial_id: u32,
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
+    pub command_count: Option<usize>,
+    #[pyo3(get)]
+    pub input_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub output_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub check_results: Vec<PyCheckResult>,
+    #[pyo3(get)]
+    pub error_message: Option<String>,
+}
+
+impl From<TrialResult> for PyTrialResult {
+    fn from(r: TrialResult) -> Self {
+        Self {
+            trial_id: r.trial_id,
+            status: r.status.to_string(),
+            command_count: r.command_count,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            check_results: check_results(r.check_results),
+            error_message: r.error_message,
+        }
+    }
+}
+
+/// Per-case result as read back from a run directory.
+#[pyclass(name = "CaseResult", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyCaseResult {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
+    pub command_count: Option<usize>,
+    #[pyo3(get)]
+    pub input_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub output_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub check_results: Vec<PyCheckResult>,
+    #[pyo3(get)]
+    pub error_message: Option<String>,
+}
+
+impl From<CaseResult> for PyCaseResult {
+    fn from(r: CaseResult) -> Self {
+        Self {
+            id: r.id,
+            status: r.status.to_string(),
+            command_count: r.command_count,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            check_results: check_results(r.check_results),
+            error_message: r.error_message,
+        }
+    }
+}
+
+/// Per-case entry of `summary.json`.
+#[pyclass(name = "CaseSummary", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyCaseSummary {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
+    pub command_count: Option<usize>,
+    #[pyo3(get)]
+    pub input_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub output_tokens: Option<u64>,
+    #[pyo3(get)]
+    pub pass_count: Option<u32>,
+    #[pyo3(get)]
+    pub total_trials: Option<u32>,
+    #[pyo3(get)]
+    pub pass_rate: Option<f64>,
+    #[pyo3(get)]
+    pub trials: Vec<PyTrialResult>,
+}
+
+impl From<CaseSummary> for PyCaseSummary {
+    fn from(s: CaseSummary) -> Self {
+        Self {
+            id: s.id,
+            status: s.status.to_string(),
+            command_count: s.command_count,
+            input_tokens: s.input_tokens,
+            output_tokens: s.output_tokens,
+            pass_count: s.pass_count,
+            total_trials: s.total_trials,
+            pass_rate: s.pass_rate,
+            trials: s.trials.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A run's `summary.json`.
+#[pyclass(name = "SummaryResult", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PySummaryResult {
+    #[pyo3(get)]
+    pub suite_pass: bool,
+    #[pyo3(get)]
+    pub suite_pass_rate: Option<f64>,
+    #[pyo3(get)]
+    pub agent: String,
+    #[pyo3(get)]
+    pub model: Option<String>,
+    #[pyo3(get)]
+    pub total_cases: usize,
+    #[pyo3(get)]
+    pub passed: usize,
+    #[pyo3(get)]
+    pub failed: usize,
+    #[pyo3(get)]
+    pub trials_per_case: Option<u32>,
+    #[pyo3(get)]
+    pub parallel: Option<u32>,
+    #[pyo3(get)]
+    pub pass_threshold: Option<f64>,
+    #[pyo3(get)]
+    pub run_dir: PathBuf,
+    #[pyo3(get)]
+    pub checks_path: Option<PathBuf>,
+    #[pyo3(get)]
+    pub skill_project_root: PathBuf,
+    #[pyo3(get)]
+    pub cases: Vec<PyCaseSummary>,
+}
+
+impl From<SummaryResult> for PySummaryResult {
+    fn from(s: SummaryResult) -> Self {
+        Self {
+            suite_pass: s.suite_pass,
+            suite_pass_rate: s.suite_pass_rate,
+            agent: s.agent,
+            model: s.model,
+            total_cases: s.total_cases,
+            passed: s.passed,
+            failed: s.failed,
+            trials_per_case: s.trials_per_case,
+            parallel: s.parallel,
+            pass_threshold: s.pass_threshold,
+            run_dir: s.run_dir,
+            checks_path: s.checks_path,
+            skill_project_root: s.skill_project_root,
+            cases: s.cases.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Read `summary.json` from a run directory.
+#[pyfunction]
+pub fn read_summary(run_dir: PathBuf) -> PyResult<PySummaryResult> {
+    Ok(eval_to_py(aikit_evals::read_summary(&run_dir))?.into())
+}
+
+/// Read the per-case results of a run directory.
+#[pyfunction]
+pub fn read_case_results(run_dir: PathBuf) -> PyResult<Vec<PyCaseResult>> {
+    Ok(eval_to_py(aikit_evals::read_case_results(&run_dir))?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+// ---- runs ----
+
+/// Run every trial of one case and write its artifacts under `run_dir`.
+///
+/// Each trial's stdout, stderr, trace and patch land in `{case}/trial-{n}/`
+/// like a CLI run, including trials that panicked or were cancelled.
+async fn run_case_with_artifacts(
+    case: &EvalCase,
+    opts: &CaseRunOptions,
+    checks: &[CheckDefinition],
+    trials: u32,
+    parallel: Option<u32>,
+    run_dir: &Path,
+) -> Result<CaseTrialsResult, ArtifactsError> {
+    let aggregated = AikitEvalRunner
+        .run_case_trials_with(
+            case,
+            opts,
+            checks,
+            trials,
+            parallel,
+            |trial, output, trace| {
+                let trial_dir = write_trial_artifacts(
+                    run_dir,
+                    &case.id,
+                    trial.trial_id,
+                    &output.stdout,
+                    &output.stderr,
+                    trace,
+                    trial,
+                )?;
+                if let Some(patch) = &output.patch {
+                    std::fs::write(trial_dir.join("changes.patch"), patch)?;
+                }
+                Ok::<(), ArtifactsError>(())
+            },
+        )
+        .await?;
+    write_case_trials_summary(run_dir, &case.id, &aggregated)?;
+    Ok(aggregated)
+}
+
+fn sum_tokens(trials: &[TrialResult], pick: impl Fn(&TrialResult) -> Option<u64>) -> Option<u64> {
+    trials
+        .iter()
+        .filter_map(pick)
+        .fold(None, |acc, v| Some(acc.unwrap_or(0).saturating_add(v)))
+}
+
+/// Run a suite against `agent_key` and write a run directory (per-trial
+/// artifacts, `aggregated.json` per case, `summary.json`) under
+/// `output_dir`. Returns the summary; read it back later with
+/// `read_summary(summary.run_dir)`.
+#[pyfunction]
+#[pyo3(signature = (
+    suite, agent_key, output_dir, *, project_root, checks=None, model=None,
+    timeout_seconds=600, trials=1, parallel=None, pass_threshold=1.0, run_id=None,
+    isolation="shared"
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn run_eval(
+    py: Python<'_>,
+    suite: PyRef<'_, PyEvalSuite>,
+    agent_key: String,
+    output_dir: PathBuf,
+    project_root: PathBuf,
+    checks: Option<Vec<PyCheckDefinition>>,
+    model: Option<String>,
+    timeout_seconds: u64,
+    trials: u32,
+    parallel: Option<u32>,
+    pass_threshold: f64,
+    run_id: Option<String>,
+    isolation: &str,
+) -> PyResult<PySummaryResult> {
+    if !(1..=1000).contains(&trials) {
+        return Err(PyValueError::new_err("trials must be in range [1, 1000]"));
+    }
+    if !(0.0..=1.0).contains(&pass_threshold) {
+        return Err(PyValueError::new_err(
+            "pass_threshold must be in range [0.0, 1.0]",
+        ));
+    }
+    let isolation: Isolation = isolation.parse().map_err(PyValueError::new_err)?;
+    let checks = check_definitions(checks);
+    let cases = suite.inner.cases.clone();
+    let run_id =
+        run_id.unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%dT%H-%M-%SZ").to_string());
+    let opts = CaseRunOptions {
+        agent_key: agent_key.clone(),
+        model: model.clone(),
+        project_root: project_root.clone(),
+        timeout_seconds,
+        pass_threshold,
+        isolation,
+    };
+    let rt = runtime()?;
+
+    let summary = py.detach(|| {
+        rt.block_on(async {
+            let run_dir = allocate_run_dir(&output_dir, &run_id)?;
+            let mut summaries = Vec::with_capacity(cases.len());
+            for case in &cases {
+                let aggregated =
+                    run_case_with_artifacts(case, &opts, &checks, trials, parallel, &run_dir)
+                        .await?;
+                summaries.push(CaseSummary {
+                    id: aggregated.id.clone(),
+                    status: aggregated.aggregated_status.clone(),
+                    command_count: None,
+                    input_tokens: sum_tokens(&aggregated.trials, |t| t.input_tokens),
+                    output_tokens: sum_tokens(&aggregated.trials, |t| t.output_tokens),
+                    pass_count: Some(aggregated.pass_count),
+                    total_trials: Some(aggregated.total_trials),
+                    pass_rate: Some(aggregated.pass_rate),
+                    trials: aggregated.trials,
+                });
+            }
+            let passed = summaries
+                .iter()
+                .filter(|c| c.status == CaseStatus::Passed)
+                .count();
+            let total_cases = summaries.len();
+            let summary = SummaryResult {
+                suite_pass: passed == total_cases,
+                suite_pass_rate: (total_cases > 0).then(|| passed as f64 / total_cases as f64),
+                agent: agent_key,
+                model,
+                total_cases,
+                passed,
+                failed: total_cases - passed,
+                trials_per_case: Some(trials),
+                parallel,
+                pass_threshold: Some(pass_threshold),
+                run_dir: run_dir.clone(),
+                checks_path: None,
+                skill_project_root: project_root,
+                cases: summaries,
+            };
+            write_summary(&run_dir, &summary)?;
+            Ok::<_, ArtifactsError>(summary)
+        })
+    });
+    Ok(eval_to_py(summary)?.into())
+}
+
+/// Score every case `trials` times with `scorer` and return the per-case
+/// majority-vote check results, in suite order. Nothing is written to disk.
+#[pyfunction]
+#[pyo3(signature = (
+    suite, agent_key, scorer, *, project_root, model=None, timeout_seconds=600,
+    trials=1, parallel=None
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn score_cases(
+    py: Python<'_>,
+    suite: PyRef<'_, PyEvalSuite>,
+    agent_key: String,
+    scorer: &Bound<'_, PyAny>,
+    project_root: PathBuf,
+    model: Option<String>,
+    timeout_seconds: u64,
+    trials: u32,
+    parallel: Option<u32>,
+) -> PyResult<Vec<Vec<PyCheckResult>>> {
+    let scorer = resolve_scorer(scorer)?;
+    let cases = suite.inner.cases.clone();
+    let opts = CaseRunOptions {
+        agent_key,
+        model,
+        project_root,
+        timeout_seconds,
+        pass_threshold: 1.0,
+        isolation: Isolation::Shared,
+    };
+    let rt = runtime()?;
+    let scored = py.detach(|| {
+        rt.block_on(aikit_evals::score_cases(
+            &AikitEvalRunner,
+            &cases,
+            &opts,
+            scorer.as_ref(),
+            trials,
+            parallel,
+        ))
+    });
+    Ok(scored.into_iter().map(check_results).collect())
+}
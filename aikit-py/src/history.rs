@@ -0,0 +1,159 @@
+//! Python bindings for the history (transcript) API (`HistoryReader` /
+//! `HistoryMutator`, spec 008).
+//!
+//! Sessions and messages are returned as dicts in the canonical serde shape
+//! of `HistorySession` / `HistoryMessage`, the same JSON serve's
+//! `/api/v1/history` routes return.
+
+use std::path::{Path, PathBuf};
+
+use aikit_sdk::history::{
+    HistoryError, HistoryMutator, HistoryQuery, HistoryReader, MessagesQuery,
+};
+use aikit_sdk::runner::Backend;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{serialize_to_py, HistoryReadError};
+
+fn history_to_py<T>(result: Result<T, HistoryError>) -> PyResult<T> {
+    result.map_err(|e| HistoryReadError::new_err(e.to_string()))
+}
+
+/// Reads (and, where the store allows, renames/tags) past sessions of one
+/// agent without spawning it.
+#[pyclass(name = "HistoryReader")]
+pub struct PyHistoryReader {
+    backend: Backend,
+    reader: Box<dyn HistoryReader>,
+    mutator: Option<Box<dyn HistoryMutator>>,
+}
+
+impl PyHistoryReader {
+    fn mutator(&self) -> PyResult<&dyn HistoryMutator> {
+        self.mutator.as_deref().ok_or_else(|| {
+            HistoryReadError::new_err(format!("history for '{}' is read-only", self.backend.key()))
+        })
+    }
+}
+
+#[pymethods]
+impl PyHistoryReader {
+    /// Open the history store for `agent_key`. Raises `HistoryError` when
+    /// the agent has no history store (unsupported, not merely empty).
+    #[new]
+    fn new(agent_key: &str) -> PyResult<Self> {
+        let backend = Backend::from_key(agent_key)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown agent '{agent_key}'")))?;
+        let reader = history_to_py(
+            backend
+                .history_reader()
+                .ok_or(HistoryError::Unsupported { backend }),
+        )?;
+        Ok(Self {
+            backend,
+            reader,
+            mutator: backend.history_mutator(),
+        })
+    }
+
+    /// The agent key this reader serves.
+    #[getter]
+    fn agent(&self) -> &'static str {
+        self.backend.key()
+    }
+
+    /// Whether `rename` / `tag` are supported by this store.
+    #[getter]
+    fn supports_mutations(&self) -> bool {
+        self.mutator.is_some()
+    }
+
+    /// Sessions newest-first, optionally restricted to `cwd` and/or `tag`.
+    #[pyo3(signature = (*, cwd=None, tag=None, limit=None, offset=0))]
+    fn list<'py>(
+        &self,
+        py: Python<'py>,
+        cwd: Option<PathBuf>,
+        tag: Option<String>,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut q = HistoryQuery::default();
+        q.cwd = cwd;
+        q.tag = tag;
+        q.limit = limit;
+        q.offset = offset;
+        let sessions = history_to_py(py.detach(|| self.reader.list(&q)))?;
+        serialize_to_py(py, &sessions)
+    }
+
+    /// Metadata for one session, or `None` when it does not exist.
+    #[pyo3(signature = (session_id, *, cwd=None))]
+    fn info<'py>(
+        &self,
+        py: Python<'py>,
+        session_id: &str,
+        cwd: Option<PathBuf>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let session = history_to_py(py.detach(|| self.reader.info(session_id, cwd.as_deref())))?;
+        session.map(|s| serialize_to_py(py, &s)).transpose()
+    }
+
+    /// A session's messages, paged. Raises `HistoryError` when the session
+    /// does not exist; an existing empty session returns `[]`.
+    #[pyo3(signature = (session_id, *, limit=None, offset=0, cwd=None))]
+    fn messages<'py>(
+        &self,
+        py: Python<'py>,
+        session_id: &str,
+        limit: Option<usize>,
+        offset: usize,
+        cwd: Option<PathBuf>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut q = MessagesQuery::default();
+        q.limit = limit;
+        q.offset = offset;
+        let messages =
+            history_to_py(py.detach(|| self.reader.messages(session_id, &q, cwd.as_deref())))?;
+        serialize_to_py(py, &messages)
+    }
+
+    /// Set a custom display title for a session.
+    #[pyo3(signature = (session_id, title, *, cwd=None))]
+    fn rename(
+        &self,
+        py: Python<'_>,
+        session_id: &str,
+        title: &str,
+        cwd: Option<PathBuf>,
+    ) -> PyResult<()> {
+        let mutator = self.mutator()?;
+        history_to_py(py.detach(|| mutator.rename(session_id, title, cwd.as_deref())))
+    }
+
+    /// Set (or, with `None`, clear) a session's tag.
+    #[pyo3(signature = (session_id, tag=None, *, cwd=None))]
+    fn tag(
+        &self,
+        py: Python<'_>,
+        session_id: &str,
+        tag: Option<&str>,
+        cwd: Option<PathBuf>,
+    ) -> PyResult<()> {
+        let mutator = self.mutator()?;
+        let cwd: Option<&Path> = cwd.as_deref();
+        history_to_py(py.detach(|| mutator.tag(session_id, tag, cwd)))
+    }
+}
+
+/// Agent keys whose history store is compiled in (`history_store`
+/// capability).
+#[pyfunction]
+pub fn history_agents() -> Vec<&'static str> {
+    aikit_sdk::runner::backend::ALL
+        .iter()
+        .filter(|b| b.capabilities().history_store)
+        .map(|b| b.key())
+        .collect()
+}
@@ -1,3 +1,9 @@
+mod capture;
+mod evals;
+mod history;
+mod live;
+mod training;
+
 use aikit_sdk::{
     add_mcp_server as add_mcp_server_impl, get_agent_status as get_agent_status_impl,
     get_installed_agents as get_installed_agents_impl,
@@ -16,6 +22,13 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 create_exception!(aikit_py, McpDeployError, PyException);
+create_exception!(aikit_py, LiveSessionError, PyException);
+// Exposed to Python as `HistoryError`; renamed on the Rust side so it does not
+// shadow `aikit_sdk::history::HistoryError`.
+create_exception!(aikit_py, HistoryReadError, PyException);
+create_exception!(aikit_py, CaptureError, PyException);
+create_exception!(aikit_py, EvalError, PyException);
+create_exception!(aikit_py, TrainingError, PyException);
 
 // Removed PyDeployError struct and its #[pyclass]
 
@@ -29,6 +42,20 @@ fn mcp_to_py<T>(result: Result<T, aikit_sdk::McpDeployError>) -> PyResult<T> {
     result.map_err(|e| McpDeployError::new_err(e.to_string()))
 }
 
+/// Decode a JSON document into native Python objects (dict/list/str/…).
+fn json_to_py<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Serialize a Rust value through its serde shape into native Python objects.
+fn serialize_to_py<'py, T: serde::Serialize>(
+    py: Python<'py>,
+    value: &T,
+) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| PyException::new_err(e.to_string()))?;
+    json_to_py(py, &json)
+}
+
 fn mcp_scope_from_str(scope: &str) -> PyResult<McpScope> {
     match scope.to_ascii_lowercase().as_str() {
         "project" => Ok(McpScope::Project),
@@ -513,6 +540,11 @@ fn mcp_parse_header_pairs(pairs: Vec<String>) -> PyResult<HashMap<String, String
 fn aikit_py(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("DeployError", py.get_type::<PyException>())?;
     m.add("McpDeployError", py.get_type::<McpDeployError>())?;
+    m.add("LiveSessionError", py.get_type::<LiveSessionError>())?;
+    m.add("HistoryError", py.get_type::<HistoryReadError>())?;
+    m.add("CaptureError", py.get_type::<CaptureError>())?;
+    m.add("EvalError", py.get_type::<EvalError>())?;
+    m.add("TrainingError", py.get_type::<TrainingError>())?;
 
     m.add_class::<PyDeployConcept>()?;
     m.add_class::<PyAgentConfig>()?;
@@ -546,5 +578,34 @@ fn aikit_py(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pyfunction!(normalize_mcp_agent_key_py))?;
     m.add_wrapped(wrap_pyfunction!(mcp_parse_env_pairs))?;
     m.add_wrapped(wrap_pyfunction!(mcp_parse_header_pairs))?;
+
+    m.add_class::<live::PyLiveSession>()?;
+    m.add_wrapped(wrap_pyfunction!(live::open_live_session))?;
+    m.add_wrapped(wrap_pyfunction!(live::live_session_agents))?;
+    m.add_class::<history::PyHistoryReader>()?;
+    m.add_wrapped(wrap_pyfunction!(history::history_agents))?;
+    m.add_class::<capture::PyCaptureStore>()?;
+
+    m.add_class::<evals::PyEvalCase>()?;
+    m.add_class::<evals::PyEvalSuite>()?;
+    m.add_class::<evals::PyCheckDefinition>()?;
+    m.add_class::<evals::PyCheckResult>()?;
+    m.add_class::<evals::PyChecksScorer>()?;
+    m.add_class::<evals::PyTrialResult>()?;
+    m.add_class::<evals::PyCaseResult>()?;
+    m.add_class::<evals::PyCaseSummary>()?;
+    m.add_class::<evals::PySummaryResult>()?;
+    m.add_wrapped(wrap_pyfunction!(evals::load_suite))?;
+    m.add_wrapped(wrap_pyfunction!(evals::load_checks))?;
+    m.add_wrapped(wrap_pyfunction!(evals::run_checks))?;
+    m.add_wrapped(wrap_pyfunction!(evals::item_score))?;
+    m.add_wrapped(wrap_pyfunction!(evals::read_summary))?;
+    m.add_wrapped(wrap_pyfunction!(evals::read_case_results))?;
+    m.add_wrapped(wrap_pyfunction!(evals::run_eval))?;
+    m.add_wrapped(wrap_pyfunction!(evals::score_cases))?;
+    m.add_class::<training::PyRunConfig>()?;
+    m.add_class::<training::PyTrainingOutcome>()?;
+    m.add_wrapped(wrap_pyfunction!(training::run_training))?;
+    m.add_wrapped(wrap_pyfunction!(training::resume_training))?;
     Ok(())
 }
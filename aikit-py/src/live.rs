@@ -0,0 +1,298 @@
+//! Python bindings for bidirectional live sessions (`LiveSession`).
+//!
+//! `open_live_session` opens a Claude, Codex, Pi, OpenCode or built-in aikit
+//! session and returns a `LiveSession` object that drives the SDK control
+//! handle and yields the canonical event stream as dicts. Every blocking call (open, event wait,
+//! `get_context_usage`) releases the GIL.
+
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use aikit_sdk::{
+    open_aikit_session, open_claude_session, open_codex_session, open_opencode_session,
+    open_pi_session, AgentEvent, AikitSessionOptions, ClaudeSessionOptions, CodexSessionOptions,
+    ControlError, LiveSession, OpenCodeSessionOptions, PiSessionOptions,
+};
+use pyo3::exceptions::{PyException, PyNotImplementedError, PyStopAsyncIteration, PyValueError};
+use pyo3::prelude::*;
+
+use crate::{json_to_py, LiveSessionError};
+
+type Events = mpsc::Receiver<AgentEvent>;
+
+/// Every SDK control handle is a channel sender, so `Sync` holds and the
+/// handle can be shared by the pyclass without a lock.
+type Control = Box<dyn LiveSession + Sync>;
+
+fn control_to_py<T>(result: Result<T, ControlError>) -> PyResult<T> {
+    result.map_err(|e| match e {
+        ControlError::Unsupported(_) => PyNotImplementedError::new_err(e.to_string()),
+        ControlError::Backend(_) => LiveSessionError::new_err(e.to_string()),
+    })
+}
+
+/// A live agent session: control operations plus an iterator of canonical
+/// event dicts (same shape as `run_agent_events_py` callbacks).
+///
+/// Iterate synchronously (`for event in session`) or asynchronously
+/// (`async for event in session`); iteration ends when the session closes.
+#[pyclass(name = "LiveSession")]
+pub struct PyLiveSession {
+    #[pyo3(get)]
+    agent: String,
+    control: Control,
+    // `Receiver` is not `Sync`; the lock is taken with the GIL released so a
+    // second waiter blocks on the mutex, not on the interpreter.
+    events: Mutex<Events>,
+}
+
+impl PyLiveSession {
+    /// Block (GIL released) for the next event. `None` when the session has
+    /// ended or `timeout` elapsed.
+    fn recv(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<AgentEvent>> {
+        let timeout = timeout
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs)
+                    .map_err(|_| PyValueError::new_err("timeout must be a non-negative number"))
+            })
+            .transpose()?;
+        Ok(py.detach(|| {
+            let events = self.events.lock().unwrap();
+            match timeout {
+                Some(t) => events.recv_timeout(t).ok(),
+                None => events.recv().ok(),
+            }
+        }))
+    }
+
+    fn event_to_py<'py>(py: Python<'py>, event: &AgentEvent) -> PyResult<Bound<'py, PyAny>> {
+        let json = event
+            .to_json_string()
+            .map_err(|e| PyException::new_err(e.to_string()))?;
+        json_to_py(py, &json)
+    }
+}
+
+#[pymethods]
+impl PyLiveSession {
+    /// Send a follow-up user turn on the same session.
+    fn send_turn(&self, py: Python<'_>, text: String) -> PyResult<()> {
+        control_to_py(py.detach(|| self.control.send_turn(text)))
+    }
+
+    /// Interrupt the current turn; the session stays open.
+    fn interrupt(&self, py: Python<'_>) -> PyResult<()> {
+        control_to_py(py.detach(|| self.control.interrupt()))
+    }
+
+    /// Disconnect and tear down the session. The event iterator drains and
+    /// then stops.
+    fn disconnect(&self, py: Python<'_>) -> PyResult<()> {
+        control_to_py(py.detach(|| self.control.disconnect()))
+    }
+
+    /// Switch the model mid-session. Raises `NotImplementedError` when the
+    /// agent does not support it (Codex).
+    #[pyo3(signature = (model=None))]
+    fn set_model(&self, py: Python<'_>, model: Option<String>) -> PyResult<()> {
+        control_to_py(py.detach(|| self.control.set_model(model)))
+    }
+
+    /// Context-window usage as reported by the agent, as a dict. Raises
+    /// `NotImplementedError` when the agent does not support it (Codex).
+    fn get_context_usage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let usage = control_to_py(py.detach(|| self.control.get_context_usage()))?;
+        json_to_py(py, &usage.to_string())
+    }
+
+    /// Wait for the next event dict. Returns `None` when `timeout` (seconds)
+    /// elapses or the session has ended.
+    #[pyo3(signature = (timeout=None))]
+    fn next_event<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.recv(py, timeout)?
+            .map(|event| Self::event_to_py(py, &event))
+            .transpose()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.next_event(py, None)
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Await the next event on the running loop's default executor, so the
+    /// blocking wait never stalls the event loop.
+    fn __anext__<'py>(slf: Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let fetch = slf.getattr("_next_or_stop_async")?;
+        event_loop.call_method1("run_in_executor", (py.None(), fetch))
+    }
+
+    /// Executor target for `__anext__`: the next event, or
+    /// `StopAsyncIteration` once the session has ended.
+    #[pyo3(name = "_next_or_stop_async")]
+    fn next_or_stop_async<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match self.recv(py, None)? {
+            Some(event) => Self::event_to_py(py, &event),
+            None => Err(PyStopAsyncIteration::new_err(())),
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: Option<Bound<'_, PyAny>>,
+        _exc_value: Option<Bound<'_, PyAny>>,
+        _traceback: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        // A session whose bridge already exited reports a closed channel;
+        // leaving the `with` block must not raise for that.
+        let _ = py.detach(|| self.control.disconnect());
+        Ok(())
+    }
+}
+
+/// Open a bidirectional session with `prompt` as the first turn.
+///
+/// `agent_key` is `claude`, `codex`, `pi`, `opencode` or `aikit`. `resume`
+/// resumes an existing session id (Claude, Pi, OpenCode, aikit).
+/// `approval_policy` / `sandbox` are Codex-only.
+#[pyfunction]
+#[pyo3(signature = (agent_key, prompt, *, model=None, cwd=None, resume=None, approval_policy=None, sandbox=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn open_live_session(
+    py: Python<'_>,
+    agent_key: &str,
+    prompt: String,
+    model: Option<String>,
+    cwd: Option<PathBuf>,
+    resume: Option<String>,
+    approval_policy: Option<String>,
+    sandbox: Option<String>,
+) -> PyResult<PyLiveSession> {
+    let codex_only = approval_policy.is_some() || sandbox.is_some();
+    let opened: Result<(Control, Events), String> = match agent_key {
+        "claude" | "pi" | "opencode" | "aikit" if codex_only => {
+            return Err(PyValueError::new_err(
+                "approval_policy and sandbox are only supported for codex",
+            ));
+        }
+        "claude" => {
+            let opts = ClaudeSessionOptions {
+                model,
+                cwd,
+                resume,
+                ..ClaudeSessionOptions::default()
+            };
+            py.detach(|| {
+                open_claude_session(prompt, opts)
+                    .map(|s| {
+                        let (ctrl, events) = s.into_parts();
+                        (Box::new(ctrl) as Control, events)
+                    })
+                    .map_err(|e| e.to_string())
+            })
+        }
+        "codex" => {
+            if model.is_some() || resume.is_some() {
+                return Err(PyValueError::new_err(
+                    "model and resume are not supported for codex live sessions",
+                ));
+            }
+            let mut opts = CodexSessionOptions::default()
+                .with_approval_policy(approval_policy)
+                .with_sandbox(sandbox);
+            if let Some(cwd) = cwd {
+                opts.cwd = cwd;
+            }
+            py.detach(|| {
+                open_codex_session(prompt, opts)
+                    .map(|s| {
+                        let (ctrl, events) = s.into_parts();
+                        (Box::new(ctrl) as Control, events)
+                    })
+                    .map_err(|e| e.to_string())
+            })
+        }
+        "pi" => {
+            let opts = PiSessionOptions {
+                model,
+                cwd,
+                session_id: resume,
+            };
+            py.detach(|| {
+                open_pi_session(prompt, opts)
+                    .map(|s| {
+                        let (ctrl, events) = s.into_parts();
+                        (Box::new(ctrl) as Control, events)
+                    })
+                    .map_err(|e| e.to_string())
+            })
+        }
+        "opencode" => {
+            let opts = OpenCodeSessionOptions {
+                model,
+                cwd,
+                session_id: resume,
+                ..OpenCodeSessionOptions::default()
+            };
+            py.detach(|| {
+                open_opencode_session(prompt, opts)
+                    .map(|s| {
+                        let (ctrl, events) = s.into_parts();
+                        (Box::new(ctrl) as Control, events)
+                    })
+                    .map_err(|e| e.to_string())
+            })
+        }
+        "aikit" => {
+            let opts = AikitSessionOptions {
+                model,
+                cwd,
+                session_id: resume,
+                ..AikitSessionOptions::default()
+            };
+            py.detach(|| {
+                open_aikit_session(prompt, opts)
+                    .map(|s| {
+                        let (ctrl, events) = s.into_parts();
+                        (Box::new(ctrl) as Control, events)
+                    })
+                    .map_err(|e| e.to_string())
+            })
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown agent '{other}'; live sessions support 'claude', 'codex', 'pi', 'opencode' or 'aikit'"
+            )));
+        }
+    };
+    let (control, events) = opened.map_err(LiveSessionError::new_err)?;
+    Ok(PyLiveSession {
+        agent: agent_key.to_string(),
+        control,
+        events: Mutex::new(events),
+    })
+}
+
+/// Agent keys `open_live_session` accepts.
+#[pyfunction]
+pub fn live_session_agents() -> Vec<&'static str> {
+    vec!["claude", "codex", "pi", "opencode", "aikit"]
+}
@@ -0,0 +1,224 @@
+//! Python bindings for textgrad training of a skill document
+//! (`aikit-textgrad` driven through `aikit-skillopt`'s `SkillArtifact`).
+//!
+//! Scoring uses the same `scorer` argument as `score_cases`: a
+//! `ChecksScorer` or a Python object with a `score` method.
+
+use std::path::PathBuf;
+
+use aikit_evals::{AikitEvalRunner, EvalCase, Scorer};
+use aikit_skillopt::{skill_prompts, SkillArtifact};
+use aikit_textgrad::training::state::read_runtime_state;
+use aikit_textgrad::training::{RunConfig, SlowUpdateMode, TrainingOutcome};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::evals::{gate_metric_name, parse_gate_metric, resolve_scorer, runtime, PyEvalSuite};
+use crate::{serialize_to_py, TrainingError};
+
+fn parse_slow_update_mode(raw: &str) -> PyResult<SlowUpdateMode> {
+    match raw {
+        "gated" => Ok(SlowUpdateMode::Gated),
+        "force_accept" => Ok(SlowUpdateMode::ForceAccept),
+        other => Err(PyValueError::new_err(format!(
+            "unknown slow_update_mode '{other}'; expected gated or force_accept"
+        ))),
+    }
+}
+
+/// Training run configuration. Only the agents are required; the rest
+/// default to a small single-trial run.
+#[pyclass(name = "RunConfig", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyRunConfig {
+    pub(crate) inner: RunConfig,
+}
+
+#[pymethods]
+impl PyRunConfig {
+    #[new]
+    #[pyo3(signature = (
+        *, target_agent, optimizer_agent, target_model=None, optimizer_model=None,
+        n_epochs=3, batch_size=4, accumulation=1, aggregate_group_size=4, lr_0=4,
+        pass_threshold=0.5, gate_metric="soft", hard_weight=None, gate_trials=1,
+        gate_epsilon=0.0, slow_update_mode="gated", protected_soft_cap_chars=2000,
+        timeout_seconds=600, parallel=None, isolation="shared"
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        target_agent: String,
+        optimizer_agent: String,
+        target_model: Option<String>,
+        optimizer_model: Option<String>,
+        n_epochs: u32,
+        batch_size: u32,
+        accumulation: u32,
+        aggregate_group_size: u32,
+        lr_0: u32,
+        pass_threshold: f64,
+        gate_metric: &str,
+        hard_weight: Option<f64>,
+        gate_trials: u32,
+        gate_epsilon: f64,
+        slow_update_mode: &str,
+        protected_soft_cap_chars: usize,
+        timeout_seconds: u64,
+        parallel: Option<u32>,
+        isolation: &str,
+    ) -> PyResult<Self> {
+        let inner = RunConfig {
+            n_epochs,
+            batch_size,
+            accumulation,
+            aggregate_group_size,
+            lr_0,
+            pass_threshold,
+            gate_metric: parse_gate_metric(gate_metric, hard_weight)?,
+            gate_trials,
+            gate_epsilon,
+            slow_update_mode: parse_slow_update_mode(slow_update_mode)?,
+            protected_soft_cap_chars,
+            target_agent,
+            target_model,
+            optimizer_agent,
+            optimizer_model,
+            timeout_seconds,
+            isolation: isolation.parse().map_err(PyValueError::new_err)?,
+            parallel,
+            // `SkillArtifact` runs require this stem (`best_skill.md`).
+            artifact_stem: "skill".to_string(),
+        };
+        aikit_textgrad::training::config::validate_config(&inner)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn target_agent(&self) -> &str {
+        &self.inner.target_agent
+    }
+
+    #[getter]
+    fn optimizer_agent(&self) -> &str {
+        &self.inner.optimizer_agent
+    }
+
+    #[getter]
+    fn n_epochs(&self) -> u32 {
+        self.inner.n_epochs
+    }
+
+    #[getter]
+    fn gate_metric(&self) -> &'static str {
+        gate_metric_name(&self.inner.gate_metric).0
+    }
+
+    /// The full configuration in its persisted (`state.json`) shape.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        serialize_to_py(py, &self.inner)
+    }
+}
+
+/// Result of `run_training` / `resume_training`.
+#[pyclass(name = "TrainingOutcome", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyTrainingOutcome {
+    #[pyo3(get)]
+    pub best_text: String,
+    #[pyo3(get)]
+    pub best_score: f64,
+    /// Score on the test split, or `best_score` when there are no test cases.
+    #[pyo3(get)]
+    pub final_score: f64,
+    #[pyo3(get)]
+    pub best_artifact_path: PathBuf,
+}
+
+impl From<TrainingOutcome> for PyTrainingOutcome {
+    fn from(o: TrainingOutcome) -> Self {
+        Self {
+            best_text: o.best_text,
+            best_score: o.best_score,
+            final_score: o.final_score,
+            best_artifact_path: o.best_artifact_path,
+        }
+    }
+}
+
+/// Train a skill document from scratch.
+///
+/// `suite` cases must carry their split tag (`train` / `selection` /
+/// `test`), with at least one `selection` case. Progress is checkpointed in
+/// `run_dir`, so an interrupted run continues with `resume_training`.
+#[pyfunction]
+#[pyo3(signature = (skill_md, skill_name, suite, scorer, config, run_dir))]
+pub fn run_training(
+    py: Python<'_>,
+    skill_md: String,
+    skill_name: String,
+    suite: PyRef<'_, PyEvalSuite>,
+    scorer: &Bound<'_, PyAny>,
+    config: PyRunConfig,
+    run_dir: PathBuf,
+) -> PyResult<PyTrainingOutcome> {
+    if skill_md.trim().is_empty() {
+        return Err(PyValueError::new_err("skill_md must be non-empty"));
+    }
+    let scorer = resolve_scorer(scorer)?;
+    let cases: Vec<EvalCase> = suite.inner.cases.clone();
+    let config = config.inner;
+    let mut artifact =
+        SkillArtifact::from_existing(skill_md, skill_name, config.target_agent.clone());
+    let rt = runtime()?;
+    let outcome = py.detach(|| {
+        rt.block_on(aikit_textgrad::training::run_training(
+            &mut artifact,
+            &cases,
+            scorer.as_ref(),
+            &AikitEvalRunner,
+            skill_prompts(),
+            config,
+            &run_dir,
+        ))
+    });
+    outcome
+        .map(Into::into)
+        .map_err(|e| TrainingError::new_err(e.to_string()))
+}
+
+/// Resume an interrupted run from `run_dir`'s checkpoint. The configuration
+/// (including the target agent) is read back from the run directory; the
+/// artifact text is restored from `best_skill.md`.
+#[pyfunction]
+#[pyo3(signature = (run_dir, skill_md, skill_name, suite, scorer))]
+pub fn resume_training(
+    py: Python<'_>,
+    run_dir: PathBuf,
+    skill_md: String,
+    skill_name: String,
+    suite: PyRef<'_, PyEvalSuite>,
+    scorer: &Bound<'_, PyAny>,
+) -> PyResult<PyTrainingOutcome> {
+    let scorer: Box<dyn Scorer> = resolve_scorer(scorer)?;
+    let cases: Vec<EvalCase> = suite.inner.cases.clone();
+    let rt = runtime()?;
+    let outcome = py.detach(|| {
+        rt.block_on(async {
+            let state = read_runtime_state(&run_dir).await?;
+            let mut artifact =
+                SkillArtifact::from_existing(skill_md, skill_name, state.config.target_agent);
+            aikit_textgrad::training::resume_training(
+                &run_dir,
+                &mut artifact,
+                &cases,
+                scorer.as_ref(),
+                &AikitEvalRunner,
+                skill_prompts(),
+            )
+            .await
+        })
+    });
+    outcome
+        .map(Into::into)
+        .map_err(|e| TrainingError::new_err(e.to_string()))
+}
@@ -0,0 +1,191 @@
+import os
+import tempfile
+
+import pytest
+
+import aikit_py
+
+# `windsurf` deploys skills but is not a runnable backend, so every case
+# fails fast without spawning a subprocess.
+NON_RUNNABLE_AGENT = "windsurf"
+
+
+@pytest.fixture
+def tmpdir_path():
+    with tempfile.TemporaryDirectory() as tmpdir:
+        yield tmpdir
+
+
+def write(path, body):
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(body)
+    return path
+
+
+def make_suite():
+    return aikit_py.EvalSuite(
+        [
+            aikit_py.EvalCase("c1", "say hi", tags=["train"]),
+            aikit_py.EvalCase("c2", "say bye", tags=["selection"]),
+        ]
+    )
+
+
+def test_load_suite(tmpdir_path):
+    path = write(
+        os.path.join(tmpdir_path, "prompts.csv"),
+        "id,prompt,should_trigger,tags\n"
+        'a,"do a",true,"train"\n'
+        'b,"do b",false,"selection"\n',
+    )
+    suite = aikit_py.load_suite(path)
+    assert len(suite) == 2
+    assert [c.id for c in suite.cases] == ["a", "b"]
+    assert suite.cases[1].should_trigger is False
+    assert [c.id for c in suite.filter_by_tag("selection").cases] == ["b"]
+    assert len(suite.filter_by_id("a")) == 1
+
+
+def test_load_suite_missing_file_raises(tmpdir_path):
+    with pytest.raises(aikit_py.EvalError) as excinfo:
+        aikit_py.load_suite(os.path.join(tmpdir_path, "missing.csv"))
+    assert "EVAL_PROMPTS_NOT_FOUND" in str(excinfo.value)
+
+
+def test_load_and_run_checks(tmpdir_path):
+    path = write(
+        os.path.join(tmpdir_path, "checks.toml"),
+        '[[check]]\nname = "file_exists"\npath = "out.txt"\n',
+    )
+    checks = aikit_py.load_checks(path)
+    assert [c.name for c in checks] == ["file_exists"]
+    assert checks[0].required is True
+
+    results = aikit_py.run_checks(checks, "", "", tmpdir_path)
+    assert [r.passed for r in results] == [False]
+    write(os.path.join(tmpdir_path, "out.txt"), "done")
+    results = aikit_py.run_checks(checks, "", "", tmpdir_path)
+    assert [r.passed for r in results] == [True]
+
+
+def test_check_definition_from_dict_roundtrip():
+    check = aikit_py.CheckDefinition.from_dict(
+        {"name": "command_contains", "pattern": "cargo test", "required": False}
+    )
+    assert check.name == "command_contains"
+    assert check.required is False
+    assert check.to_dict()["pattern"] == "cargo test"
+    with pytest.raises(ValueError):
+        aikit_py.CheckDefinition.from_dict({"name": "nope"})
+
+
+def test_item_score_metrics():
+    results = [aikit_py.CheckResult("a", True), aikit_py.CheckResult("b", False)]
+    assert aikit_py.item_score(results, "hard") == 0.0
+    assert aikit_py.item_score(results, "soft") == 0.5
+    assert aikit_py.item_score(results, "mixed", hard_weight=0.5) == 0.25
+    with pytest.raises(ValueError):
+        aikit_py.item_score(results, "mixed")
+
+
+def test_run_eval_writes_readable_run_dir(tmpdir_path):
+    out = os.path.join(tmpdir_path, "runs")
+    summary = aikit_py.run_eval(
+        make_suite(),
+        NON_RUNNABLE_AGENT,
+        out,
+        project_root=tmpdir_path,
+        trials=2,
+        run_id="2026-04-01T14-00-00Z",
+    )
+    assert summary.total_cases == 2
+    assert summary.passed == 0
+    assert summary.suite_pass is False
+    assert summary.trials_per_case == 2
+    assert [len(c.trials) for c in summary.cases] == [2, 2]
+    assert os.path.isfile(os.path.join(summary.run_dir, "c1", "trial-2", "result.json"))
+
+    reread = aikit_py.read_summary(summary.run_dir)
+    assert reread.agent == NON_RUNNABLE_AGENT
+    assert [c.id for c in reread.cases] == ["c1", "c2"]
+
+    results = sorted(aikit_py.read_case_results(summary.run_dir), key=lambda r: r.id)
+    assert [r.id for r in results] == ["c1", "c2"]
+    assert all(r.status == "failed" for r in results)
+
+
+def test_read_summary_missing_raises(tmpdir_path):
+    with pytest.raises(aikit_py.EvalError):
+        aikit_py.read_summary(tmpdir_path)
+
+
+def test_score_cases_with_python_scorer(tmpdir_path):
+    calls = []
+
+    class CountingScorer:
+        def score(self, stdout, trace_jsonl, working_dir):
+            calls.append(working_dir)
+            return [aikit_py.CheckResult("always", True)]
+
+    scored = aikit_py.score_cases(
+        make_suite(), NON_RUNNABLE_AGENT, CountingScorer(), project_root=tmpdir_path, trials=1
+    )
+    assert len(calls) == 2
+    assert [[r.check_name for r in case] for case in scored] == [["always"], ["always"]]
+    assert all(r.passed for case in scored for r in case)
+
+
+def test_python_scorer_errors_become_failed_checks(tmpdir_path):
+    class BrokenScorer:
+        def score(self, stdout, trace_jsonl, working_dir):
+            raise RuntimeError("boom")
+
+    scored = aikit_py.score_cases(
+        make_suite().filter_by_id("c1"),
+        NON_RUNNABLE_AGENT,
+        BrokenScorer(),
+        project_root=tmpdir_path,
+    )
+    [[result]] = scored
+    assert result.check_name == "python_scorer"
+    # Majority voting keeps the failed check; its message is the vote tally.
+    assert result.passed is False
+
+
+def test_scorer_must_have_score_method(tmpdir_path):
+    with pytest.raises(ValueError):
+        aikit_py.score_cases(make_suite(), NON_RUNNABLE_AGENT, object(), project_root=tmpdir_path)
+
+
+def test_run_config_validation():
+    config = aikit_py.RunConfig(target_agent="claude", optimizer_agent="claude")
+    assert config.gate_metric == "soft"
+    assert config.to_dict()["artifact_stem"] == "skill"
+    with pytest.raises(ValueError):
+        aikit_py.RunConfig(target_agent="claude", optimizer_agent="claude", batch_size=0)
+    with pytest.raises(ValueError):
+        aikit_py.RunConfig(target_agent="claude", optimizer_agent="claude", slow_update_mode="x")
+
+
+def test_run_training_requires_selection_cases(tmpdir_path):
+    suite = aikit_py.EvalSuite([aikit_py.EvalCase("c1", "hi", tags=["train"])])
+    config = aikit_py.RunConfig(
+        target_agent=NON_RUNNABLE_AGENT, optimizer_agent=NON_RUNNABLE_AGENT, n_epochs=1
+    )
+    with pytest.raises(aikit_py.TrainingError) as excinfo:
+        aikit_py.run_training(
+            "# Skill\n",
+            "demo",
+            suite,
+            aikit_py.ChecksScorer([]),
+            config,
+            os.path.join(tmpdir_path, "run"),
+        )
+    assert "TEXTGRAD_NO_SELECTION_CASES" in str(excinfo.value)
+
+
+def test_resume_training_without_checkpoint_raises(tmpdir_path):
+    with pytest.raises(aikit_py.TrainingError):
+        aikit_py.resume_training(
+            tmpdir_path, "# Skill\n", "demo", make_suite(), aikit_py.ChecksScorer([])
+        )
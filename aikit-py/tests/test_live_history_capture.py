@@ -0,0 +1,106 @@
+import json
+import os
+import tempfile
+
+import pytest
+
+import aikit_py
+
+FIXTURE = os.path.join(
+    os.path.dirname(__file__),
+    "..",
+    "..",
+    "aikit-session-capture",
+    "tests",
+    "fixtures",
+    "claudecode",
+    "simple-session.jsonl",
+)
+
+
+@pytest.fixture
+def claude_root():
+    """A Claude Code projects dir holding one copied fixture session."""
+    with tempfile.TemporaryDirectory() as tmpdir:
+        project = os.path.join(tmpdir, "-tmp-superbased-fixture-simple")
+        os.makedirs(project)
+        with open(FIXTURE, encoding="utf-8") as src:
+            body = src.read()
+        with open(os.path.join(project, "sess-001.jsonl"), "w", encoding="utf-8") as dst:
+            dst.write(body)
+        yield tmpdir
+
+
+def test_live_session_agents():
+    assert aikit_py.live_session_agents() == ["claude", "codex", "pi", "opencode", "aikit"]
+
+
+def test_open_live_session_unknown_agent():
+    with pytest.raises(ValueError) as excinfo:
+        aikit_py.open_live_session("nonexistent", "hi")
+    assert "unknown agent" in str(excinfo.value)
+
+
+def test_open_live_session_rejects_codex_only_options():
+    with pytest.raises(ValueError):
+        aikit_py.open_live_session("claude", "hi", sandbox="read-only")
+    with pytest.raises(ValueError):
+        aikit_py.open_live_session("codex", "hi", model="gpt-5")
+
+
+def test_history_agents_have_history_store():
+    for key in aikit_py.history_agents():
+        reader = aikit_py.HistoryReader(key)
+        assert reader.agent == key
+
+
+def test_history_reader_unknown_agent():
+    with pytest.raises(ValueError):
+        aikit_py.HistoryReader("nonexistent")
+
+
+def test_history_reader_unsupported_agent():
+    # Gemini has no history store: unsupported, not merely empty.
+    with pytest.raises(aikit_py.HistoryError) as excinfo:
+        aikit_py.HistoryReader("gemini")
+    assert "unsupported" in str(excinfo.value)
+
+
+def test_capture_store_rejects_unknown_tool():
+    with pytest.raises(ValueError):
+        aikit_py.CaptureStore(tools=["nonexistent"])
+    with pytest.raises(ValueError):
+        aikit_py.CaptureStore(tools=["codex"], roots={"claude_code": "/tmp"})
+
+
+def test_capture_store_scan_and_query(claude_root):
+    store = aikit_py.CaptureStore(tools=["claude_code"], roots={"claude_code": claude_root})
+    outcome = store.scan()
+    assert outcome["files_scanned"] == 1
+    assert outcome["events_upserted"] > 0
+    assert outcome["warnings"] == []
+
+    sessions = store.sessions("claude_code")
+    assert [s["session_id"] for s in sessions] == ["sess-001"]
+
+    actions = store.actions("claude_code", "sess-001")
+    assert len(actions) >= 2
+    json.dumps(actions)  # plain JSON-compatible values
+
+    hits = store.search("FAIL")
+    assert hits
+
+    tokens = store.token_events("claude_code", "sess-001")
+    assert tokens
+
+    # Incremental: nothing new on a second scan.
+    again = store.scan()
+    assert again["events_upserted"] == 0
+
+
+def test_capture_store_ingest_file(claude_root):
+    store = aikit_py.CaptureStore(roots={"claude_code": claude_root})
+    path = os.path.join(claude_root, "-tmp-superbased-fixture-simple", "sess-001.jsonl")
+    outcome = store.ingest_file(path, tool="claude_code")
+    assert outcome["files_scanned"] == 1
+    assert store.last_file_touch("/nonexistent/never-touched") is None
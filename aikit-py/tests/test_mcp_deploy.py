@@ -23,12 +23,12 @@ def test_mcp_supported_agent_keys():
     keys = aikit_py.mcp_supported_agent_keys()
     assert "claude" in keys
     assert "cursor" in keys
-    assert len(keys) == 6
+    assert len(keys) == 7
 
 
 def test_mcp_supported_agents_shape():
     rows = aikit_py.mcp_supported_agents()
-    assert len(rows) == 6
+    assert len(rows) == 7
     for r in rows:
         assert set(r.keys()) == {
             "agent_key",
@@ -1,19 +1,25 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 use std::sync::Arc;
 
 use aikit_agent::agent_definition::AgentPersona;
 use aikit_agent::context::{ContextToolCall, ContextToolResult};
 use aikit_agent::llm::openai_compat::OpenAiCompatProvider;
-use aikit_agent::{AgentConfig, AgentInternalEvent, HostToolProvider, LlmGateway, Turn};
+use aikit_agent::{
+    AgentConfig, AgentInternalEvent, DelegateTask, HostToolProvider, LlmGateway, McpServerConfig,
+    SubAgentResult, SubAgentStatus, SubagentDelegate, Turn,
+};
 
+use crate::runner::backend;
 use crate::session_store::{
     now_rfc3339, SessionFile, SessionStore, SessionStoreError, SessionToolCall, SessionToolResult,
     SessionTurn,
 };
 use crate::{
-    AgentEvent, AgentEventPayload, AgentEventStream, QuotaCategory, QuotaExceededInfo, RunError,
-    RunOptions, RunResult, TokenUsage, UsageSource,
+    AgentEvent, AgentEventPayload, AgentEventStream, AgentStatus, McpScope, MessageKind,
+    MessagePhase, MessageRole, ProgressViewConfig, QuotaCategory, QuotaExceededInfo, RunError,
+    RunOptions, RunProgress, RunResult, TokenUsage, UsageSource,
 };
 
 #[cfg(unix)]
@@ -44,6 +50,8 @@ where
     let mut config = AgentConfig::from_env(workdir, options.stream, options.model.clone())
         .map_err(|e| emit_error(prompt, options, &mut on_event, e.to_string()))?;
     config.host_tool_provider = host_tool_provider;
+    config.subagent_delegate = Some(Arc::new(BackendDelegate::new(options)));
+    config.mcp_servers = registered_mcp_servers(&config.workdir, options);
 
     apply_session_options(options, &mut config);
 
@@ -80,6 +88,7 @@ where
         .clone()
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
     let mut config = config_for_injected_gateway(workdir, options);
+    config.subagent_delegate = Some(Arc::new(BackendDelegate::new(options)));
 
     apply_session_options(options, &mut config);
 
@@ -94,6 +103,30 @@ where
     )
 }
 
+/// Agent config for a live [`crate::runner::aikit_session`]: the same
+/// resolution a one-shot run uses. With an injected gateway a missing API key
+/// is not an error, and registered MCP servers are left out, as in
+/// [`run_aikit_agent_with_gateway`].
+pub(crate) fn live_session_config(
+    options: &RunOptions,
+    injected_gateway: bool,
+) -> Result<AgentConfig, aikit_agent::AgentError> {
+    let workdir = options
+        .current_dir
+        .clone()
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    let mut config = if injected_gateway {
+        config_for_injected_gateway(workdir, options)
+    } else {
+        let mut config = AgentConfig::from_env(workdir, options.stream, options.model.clone())?;
+        config.mcp_servers = registered_mcp_servers(&config.workdir, options);
+        config
+    };
+    config.subagent_delegate = Some(Arc::new(BackendDelegate::new(options)));
+    apply_session_options(options, &mut config);
+    Ok(config)
+}
+
 fn config_for_injected_gateway(workdir: PathBuf, options: &RunOptions) -> AgentConfig {
     AgentConfig::from_env(workdir.clone(), options.stream, options.model.clone()).unwrap_or_else(
         |_| AgentConfig {
@@ -121,6 +154,15 @@ fn config_for_injected_gateway(workdir: PathBuf, options: &RunOptions) -> AgentC
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(12000u64),
+            compression: std::env::var("AIKIT_COMPRESSION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            max_parallel_tools: std::env::var("AIKIT_MAX_PARALLEL_TOOLS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(4usize)
+                .max(1),
             allowed_roots: vec![workdir.clone()],
             skills_dirs: Vec::new(),
             agents_md_path: agents_md_path(&workdir),
@@ -130,6 +172,9 @@ fn config_for_injected_gateway(workdir: PathBuf, options: &RunOptions) -> AgentC
             session_persona: None,
             session_agents: std::collections::HashMap::new(),
             host_tool_provider: None,
+            subagent_delegate: None,
+            mcp_servers: Vec::new(),
+            web: aikit_agent::WebConfig::from_env(),
         },
     )
 }
@@ -146,6 +191,45 @@ fn agents_md_path(workdir: &std::path::Path) -> Option<PathBuf> {
     }
 }
 
+/// MCP servers registered with `aikit mcp add --agent aikit`: the global
+/// `~/.aikit/mcp.json`, then the project's `.aikit/mcp.json` replacing
+/// same-named entries. `--bare` skips the global file. Project servers are
+/// only started with `--trust-project-mcp`; otherwise they are named in a
+/// warning and left out.
+fn registered_mcp_servers(workdir: &Path, options: &RunOptions) -> Vec<McpServerConfig> {
+    let scopes: &[McpScope] = if options.bare {
+        &[McpScope::Project]
+    } else {
+        &[McpScope::Global, McpScope::Project]
+    };
+    let mut servers: Vec<McpServerConfig> = Vec::new();
+    for scope in scopes {
+        let found = match crate::read_mcp_servers("aikit", *scope, workdir) {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!("ignoring aikit MCP config ({:?}): {}", scope, e);
+                continue;
+            }
+        };
+        if *scope == McpScope::Project && !options.trust_project_mcp {
+            if !found.is_empty() {
+                let names: Vec<&str> = found.iter().map(|(name, _)| name.as_str()).collect();
+                tracing::warn!(
+                    "not starting project MCP servers from .aikit/mcp.json ({}); \
+                     pass --trust-project-mcp to allow them",
+                    names.join(", ")
+                );
+            }
+            continue;
+        }
+        for (name, transport) in found {
+            servers.retain(|s| s.name != name);
+            servers.push(McpServerConfig { name, transport });
+        }
+    }
+    servers
+}
+
 fn apply_session_options(options: &RunOptions, config: &mut AgentConfig) {
     // Apply session persona: deserialize from JSON, then apply model override if no CLI --model.
     if let Some(ref persona_val) = options.session_persona {
@@ -176,6 +260,135 @@ fn apply_session_options(options: &RunOptions, config: &mut AgentConfig) {
             }
         }
     }
+
+    config
+        .web
+        .allow_domains
+        .extend(options.web_allow_domains.iter().cloned());
+    config
+        .web
+        .deny_domains
+        .extend(options.web_deny_domains.iter().cloned());
+}
+
+/// Runs `spawn_subagent` tasks addressed to an external Backend through
+/// [`crate::run_agent_events`]. The sub-run inherits the parent run's
+/// sandbox, approval and timeout settings; its canonical events are forwarded
+/// as they arrive and its changed files are read off a [`RunProgress`].
+struct BackendDelegate {
+    base: RunOptions,
+}
+
+impl BackendDelegate {
+    fn new(parent: &RunOptions) -> Self {
+        let mut base = RunOptions::new()
+            .with_yolo(parent.yolo)
+            .with_auto_approve(parent.auto_approve)
+            .with_bare(parent.bare)
+            .with_skip_git_repo_check(parent.skip_git_repo_check);
+        base.timeout = parent.timeout;
+        base.sandbox = parent.sandbox;
+        Self { base }
+    }
+}
+
+/// External backends whose CLI `status` (from [`crate::get_agent_status`])
+/// reports as available, so the model is never offered one that cannot run.
+fn installed_backends(status: &BTreeMap<String, AgentStatus>) -> Vec<String> {
+    backend::ALL
+        .iter()
+        .filter(|b| !b.is_in_process())
+        .map(|b| b.key())
+        .filter(|key| status.get(*key).is_some_and(|s| s.available))
+        .map(str::to_string)
+        .collect()
+}
+
+impl SubagentDelegate for BackendDelegate {
+    fn agents(&self) -> Vec<String> {
+        installed_backends(&crate::get_agent_status())
+    }
+
+    fn run(
+        &self,
+        task: &DelegateTask,
+        on_event: &mut (dyn FnMut(serde_json::Value) + Send),
+    ) -> SubAgentResult {
+        let mut options = self
+            .base
+            .clone()
+            .with_stream(true)
+            .with_current_dir(task.workdir.clone());
+        if let Some(model) = &task.model {
+            options = options.with_model(model.clone());
+        }
+        let prompt = match &task.system_prompt {
+            Some(system) => format!("{}\n\n{}", system, task.prompt),
+            None => task.prompt.clone(),
+        };
+
+        let mut progress = RunProgress::new(ProgressViewConfig {
+            model: task.model.clone(),
+            ..ProgressViewConfig::default()
+        });
+        let mut final_message: Option<String> = None;
+        let outcome = crate::run_agent_events(&task.agent, &prompt, options, |event| {
+            progress.push(&task.agent, &event);
+            match &event.payload {
+                AgentEventPayload::Result { text, .. } if !text.trim().is_empty() => {
+                    final_message = Some(text.clone());
+                }
+                AgentEventPayload::StreamMessage(sm)
+                    if sm.role == MessageRole::Assistant
+                        && sm.kind == MessageKind::Message
+                        && sm.phase == MessagePhase::Final
+                        && !sm.text.trim().is_empty() =>
+                {
+                    final_message = Some(sm.text.clone());
+                }
+                _ => {}
+            }
+            match serde_json::to_value(&event) {
+                Ok(value) => on_event(value),
+                Err(e) => tracing::warn!("failed to serialize sub-agent event: {}", e),
+            }
+        });
+
+        let changed_files = progress
+            .file_changes()
+            .iter()
+            .map(|change| PathBuf::from(&change.path))
+            .collect();
+        let (status, final_message) = match outcome {
+            Ok(result) if result.status.success() => (
+                SubAgentStatus::Success,
+                final_message.unwrap_or_else(|| format!("{} completed", task.agent)),
+            ),
+            Ok(result) => (
+                SubAgentStatus::Failed,
+                format!(
+                    "{} exited with {}: {}",
+                    task.agent,
+                    result.status,
+                    String::from_utf8_lossy(&result.stderr).trim()
+                ),
+            ),
+            Err(RunError::TimedOut { timeout, .. }) => (
+                SubAgentStatus::TimedOut,
+                format!("{} timed out after {:?}", task.agent, timeout),
+            ),
+            Err(e) => (
+                SubAgentStatus::Failed,
+                format!("{} failed: {}", task.agent, e),
+            ),
+        };
+        SubAgentResult {
+            status,
+            changed_files,
+            key_findings: final_message.clone(),
+            final_message,
+        }
+    }
 }
 
 fn run_with_config_and_gateway<F>(
@@ -296,6 +509,8 @@ where
                     updated_at: now,
                     cwd: cwd_str.clone(),
                     turns,
+                    title: None,
+                    tag: None,
                 };
                 let _ = store.save(&session);
                 let _ = store.update_index(&cwd_str, &session_id);
@@ -328,7 +543,10 @@ where
     }
 }
 
-fn internal_events_to_turns(prompt: &str, events: &[AgentInternalEvent]) -> Vec<SessionTurn> {
+pub(crate) fn internal_events_to_turns(
+    prompt: &str,
+    events: &[AgentInternalEvent],
+) -> Vec<SessionTurn> {
     let mut turns: Vec<SessionTurn> = Vec::new();
 
     turns.push(SessionTurn {
@@ -419,7 +637,7 @@ fn internal_events_to_turns(prompt: &str, events: &[AgentInternalEvent]) -> Vec<
     turns
 }
 
-fn session_turns_to_turns(session_turns: &[SessionTurn]) -> Vec<Turn> {
+pub(crate) fn session_turns_to_turns(session_turns: &[SessionTurn]) -> Vec<Turn> {
     session_turns
         .iter()
         .map(|st| match st.role.as_str() {
@@ -598,7 +816,18 @@ where
     Ok(state.finish(success))
 }
 
-fn convert_event(
+/// Rebuild a delegated sub-agent's canonical event from the JSON the
+/// delegate forwarded. Anything unreadable is kept verbatim as a `JsonLine`.
+fn nested_event(value: serde_json::Value) -> AgentEvent {
+    serde_json::from_value(value.clone()).unwrap_or_else(|_| AgentEvent {
+        agent_key: String::new(),
+        seq: 0,
+        stream: AgentEventStream::Stdout,
+        payload: AgentEventPayload::JsonLine(value),
+    })
+}
+
+pub(crate) fn convert_event(
     event: AgentInternalEvent,
 ) -> (AgentEventStream, AgentEventPayload, Option<String>) {
     match event {
@@ -655,12 +884,21 @@ fn convert_event(
             },
             None,
         ),
+        AgentInternalEvent::SubagentEvent { subagent_id, event } => (
+            AgentEventStream::Stdout,
+            AgentEventPayload::AikitSubagentEvent {
+                subagent_id,
+                event: Box::new(nested_event(event)),
+            },
+            None,
+        ),
         AgentInternalEvent::SubagentResult {
             subagent_id,
             status,
             changed_files,
             key_findings,
             final_message,
+            patch,
         } => (
             AgentEventStream::Stdout,
             AgentEventPayload::AikitSubagentResult {
@@ -669,6 +907,7 @@ fn convert_event(
                 changed_files,
                 key_findings,
                 final_message,
+                patch,
             },
             None,
         ),
@@ -676,12 +915,14 @@ fn convert_event(
             original_tokens,
             compressed_tokens,
             turns_summarized,
+            summary,
         } => (
             AgentEventStream::Stdout,
             AgentEventPayload::AikitContextCompressed {
                 original_tokens,
                 compressed_tokens,
                 turns_summarized,
+                summary,
             },
             None,
         ),
@@ -738,7 +979,7 @@ fn convert_event(
     }
 }
 
-fn error_code(message: &str) -> String {
+pub(crate) fn error_code(message: &str) -> String {
     message
         .split(':')
         .next()
@@ -751,6 +992,54 @@ fn error_code(message: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn web_domain_lists_flow_from_run_options_into_agent_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let options = RunOptions::default()
+            .with_web_allow_domain("docs.rs")
+            .with_web_allow_domain("intranet.local")
+            .with_web_deny_domain("gist.github.com");
+        let mut config = config_for_injected_gateway(tmp.path().to_path_buf(), &options);
+
+        apply_session_options(&options, &mut config);
+
+        assert!(config
+            .web
+            .allow_domains
+            .ends_with(&["docs.rs".to_string(), "intranet.local".to_string()]));
+        assert!(config
+            .web
+            .deny_domains
+            .ends_with(&["gist.github.com".to_string()]));
+    }
+
+    #[test]
+    fn project_mcp_servers_need_trust() {
+        let tmp = tempfile::tempdir().unwrap();
+        crate::add_mcp_server(crate::AddMcpServerOptions {
+            agent_key: "aikit".to_string(),
+            scope: McpScope::Project,
+            project_root: tmp.path().to_path_buf(),
+            server_name: "repo-tool".to_string(),
+            transport: crate::McpServerTransport::Stdio {
+                command: "./run-me.sh".to_string(),
+                args: vec![],
+                env: None,
+            },
+            overwrite: false,
+        })
+        .unwrap();
+
+        // `bare` keeps the developer's global file out of the assertion.
+        let options = RunOptions::default().with_bare(true);
+        assert!(registered_mcp_servers(tmp.path(), &options).is_empty());
+
+        let trusted = options.with_trust_project_mcp(true);
+        let servers = registered_mcp_servers(tmp.path(), &trusted);
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "repo-tool");
+    }
+
     // F3 (D2 follow-up): the previously-untested middle link of the tool-policy
     // chain. serve builds `RunOptions.session_persona` from
     // `SendMessageRequest.tools`/`disallowed_tools` (tested serve-side); this
@@ -790,4 +1079,51 @@ mod tests {
             "no tool policy → persona unset → full toolset (ADR 0012 default)"
         );
     }
+
+    #[test]
+    fn subagent_events_nest_the_backend_event() {
+        let inner = AgentEvent {
+            agent_key: "codex".to_string(),
+            seq: 7,
+            stream: AgentEventStream::Stdout,
+            payload: AgentEventPayload::ToolUse {
+                call_id: "c1".to_string(),
+                tool_name: "apply_patch".to_string(),
+                input: serde_json::json!({"path": "db/001.sql"}),
+            },
+        };
+        let (_, payload, stdout) = convert_event(AgentInternalEvent::SubagentEvent {
+            subagent_id: "subagent-1".to_string(),
+            event: serde_json::to_value(&inner).unwrap(),
+        });
+        assert!(stdout.is_none());
+        match payload {
+            AgentEventPayload::AikitSubagentEvent { subagent_id, event } => {
+                assert_eq!(subagent_id, "subagent-1");
+                assert_eq!(event.agent_key, "codex");
+                assert_eq!(event.seq, 7);
+                assert!(matches!(
+                    event.payload,
+                    AgentEventPayload::ToolUse { ref tool_name, .. } if tool_name == "apply_patch"
+                ));
+            }
+            other => panic!("expected AikitSubagentEvent, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn backend_delegate_offers_installed_external_backends_only() {
+        let status: BTreeMap<String, AgentStatus> = [
+            ("aikit", AgentStatus::available()),
+            ("codex", AgentStatus::available()),
+            (
+                "claude",
+                AgentStatus::unavailable(crate::AgentAvailabilityReason::NotRunnable),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, s)| (key.to_string(), s))
+        .collect();
+        assert_eq!(installed_backends(&status), vec!["codex".to_string()]);
+    }
 }
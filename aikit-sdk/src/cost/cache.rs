@@ -0,0 +1,624 @@
+//! Prompt-cache efficiency from captured `CacheObservation`s.
+//!
+//! Each observation is one assistant turn's cache usage. A healthy session
+//! reads back everything the previous turn read or wrote and only writes the
+//! new tail; a turn that reads back less than half of that prefix and writes
+//! again is an invalidation. [`analyze_cache`] finds those turns, prices the
+//! rewrite with the [`PricingTable`], and attributes each one to the first
+//! cause the observations show: a tool-list change, a system-prompt change,
+//! a model switch, or an idle gap longer than the cache TTL.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use aikit_session_capture::aggregate::{self, AggregateWindow};
+use aikit_session_capture::{CacheObservation, EventStore, StoreError, ToolKind};
+use serde::{Deserialize, Serialize};
+
+use crate::cost::report::{backend_key, csv_field, now_ms};
+use crate::cost::{ModelPricing, PricingTable};
+
+/// Anthropic's default ephemeral cache lifetime.
+const TTL_5M_MS: i64 = 5 * 60 * 1000;
+/// Lifetime of the extended (1h) cache tier.
+const TTL_1H_MS: i64 = 60 * 60 * 1000;
+
+/// Why a turn had to rewrite a prefix the previous turn had cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidationCause {
+    /// The turn's tool list differed from the previous turn's.
+    ToolsChanged,
+    /// The observation's prompt fingerprint changed.
+    SystemPromptChanged,
+    /// A different model served the turn.
+    ModelChanged,
+    /// The gap since the previous turn exceeded the cache TTL.
+    IdleExpired,
+    /// None of the above is visible in the observations.
+    Unknown,
+}
+
+impl InvalidationCause {
+    pub const ALL: [InvalidationCause; 5] = [
+        InvalidationCause::ToolsChanged,
+        InvalidationCause::SystemPromptChanged,
+        InvalidationCause::ModelChanged,
+        InvalidationCause::IdleExpired,
+        InvalidationCause::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InvalidationCause::ToolsChanged => "tools_changed",
+            InvalidationCause::SystemPromptChanged => "system_prompt_changed",
+            InvalidationCause::ModelChanged => "model_changed",
+            InvalidationCause::IdleExpired => "idle_expired",
+            InvalidationCause::Unknown => "unknown",
+        }
+    }
+}
+
+/// What [`analyze_cache`] covers and when a session counts as collapsed.
+#[derive(Debug, Clone)]
+pub struct CacheAnalysisQuery {
+    /// Observations outside the window are left out.
+    pub window: AggregateWindow,
+    /// Only this tool's sessions; all tools when `None`.
+    pub tool: Option<ToolKind>,
+    /// Sessions with fewer turns are never flagged as collapsed.
+    pub collapse_min_turns: usize,
+    /// Flag a session when its hit ratio over the second half of its turns
+    /// is at least this much lower than over the first half.
+    pub collapse_drop: f64,
+}
+
+impl Default for CacheAnalysisQuery {
+    fn default() -> Self {
+        Self {
+            window: AggregateWindow::default(),
+            tool: None,
+            collapse_min_turns: 6,
+            collapse_drop: 0.3,
+        }
+    }
+}
+
+/// Output of [`analyze_cache`]. Sessions are ordered by wasted write spend,
+/// largest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheReport {
+    pub generated_at_ms: i64,
+    pub turns: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// Read tokens over read plus written tokens; `None` with no cache use.
+    pub hit_ratio: Option<f64>,
+    pub write_usd: f64,
+    pub wasted_write_usd: f64,
+    /// One entry per cause that occurred, in [`InvalidationCause::ALL`] order.
+    pub causes: Vec<CauseSummary>,
+    pub collapsed_sessions: u64,
+    pub sessions: Vec<SessionCacheStats>,
+}
+
+/// Invalidations of one cause across a [`CacheReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CauseSummary {
+    pub cause: InvalidationCause,
+    pub count: u64,
+    pub wasted_usd: f64,
+}
+
+/// Cache efficiency of one session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCacheStats {
+    pub backend: String,
+    pub session_id: String,
+    /// The session's `git_root`, when recorded.
+    pub project: Option<String>,
+    /// Model of the last observed turn.
+    pub model: Option<String>,
+    pub first_observed_at_ms: i64,
+    pub last_observed_at_ms: i64,
+    pub turns: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub hit_ratio: Option<f64>,
+    /// Hit ratio over the first and second half of the turns after the
+    /// first (which always writes the whole prefix).
+    pub early_hit_ratio: Option<f64>,
+    pub late_hit_ratio: Option<f64>,
+    /// The late hit ratio fell by at least
+    /// [`CacheAnalysisQuery::collapse_drop`].
+    pub collapsed: bool,
+    /// Everything written to the cache, at cache-write prices.
+    pub write_usd: f64,
+    /// What rewriting invalidated prefixes cost over reading them back.
+    pub wasted_write_usd: f64,
+    pub invalidations: Vec<CacheInvalidation>,
+}
+
+/// One turn that rewrote a prefix the previous turn had cached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheInvalidation {
+    pub source_event_id: String,
+    pub observed_at_ms: i64,
+    pub cause: InvalidationCause,
+    /// Time since the previous turn.
+    pub idle_ms: i64,
+    /// Tokens the previous turn had cached that this turn did not read.
+    pub lost_tokens: u64,
+    /// Tokens this turn wrote.
+    pub rewrite_tokens: u64,
+    pub wasted_usd: f64,
+    /// Tool names from the observation when the tool list changed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools_changed: Vec<String>,
+}
+
+/// Analyse the prompt-cache observations in `store` that `query` selects.
+pub async fn analyze_cache(
+    store: &dyn EventStore,
+    query: &CacheAnalysisQuery,
+    pricing: &PricingTable,
+) -> Result<CacheReport, StoreError> {
+    let mut sessions = Vec::new();
+    for session in aggregate::usage_sessions(store).await? {
+        let tool = session.tool;
+        if query.tool.is_some_and(|t| t != tool) {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        let observations: Vec<CacheObservation> = store
+            .cache_observations_for_session(tool, &session.session_id)
+            .await?
+            .into_iter()
+            .filter(|o| query.window.contains(Some(o.observed_at_ms)))
+            .filter(|o| read_tokens(o) + creation_tokens(o) > 0)
+            .filter(|o| seen.insert(o.source_event_id.clone()))
+            .collect();
+        if observations.is_empty() {
+            continue;
+        }
+        let mut stats = analyze_session(&observations, query, pricing);
+        stats.backend = backend_key(tool).to_string();
+        stats.session_id = session.session_id.clone();
+        stats.project = session.git_root.as_ref().map(|p| p.display().to_string());
+        sessions.push(stats);
+    }
+    sessions.sort_by(|a, b| {
+        b.wasted_write_usd
+            .total_cmp(&a.wasted_write_usd)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    let causes = InvalidationCause::ALL
+        .iter()
+        .filter_map(|&cause| {
+            let matching = sessions
+                .iter()
+                .flat_map(|s| &s.invalidations)
+                .filter(|i| i.cause == cause);
+            let (count, wasted_usd) =
+                matching.fold((0, 0.0), |(n, usd), i| (n + 1, usd + i.wasted_usd));
+            (count > 0).then_some(CauseSummary {
+                cause,
+                count,
+                wasted_usd,
+            })
+        })
+        .collect();
+    let cache_read_tokens = sessions.iter().map(|s| s.cache_read_tokens).sum();
+    let cache_creation_tokens = sessions.iter().map(|s| s.cache_creation_tokens).sum();
+    Ok(CacheReport {
+        generated_at_ms: now_ms(),
+        turns: sessions.iter().map(|s| s.turns).sum(),
+        cache_read_tokens,
+        cache_creation_tokens,
+        hit_ratio: ratio(cache_read_tokens, cache_creation_tokens),
+        write_usd: sessions.iter().map(|s| s.write_usd).sum(),
+        wasted_write_usd: sessions.iter().map(|s| s.wasted_write_usd).sum(),
+        causes,
+        collapsed_sessions: sessions.iter().filter(|s| s.collapsed).count() as u64,
+        sessions,
+    })
+}
+
+/// Stats for one session's observations, oldest first. The caller fills in
+/// the session identity.
+fn analyze_session(
+    observations: &[CacheObservation],
+    query: &CacheAnalysisQuery,
+    pricing: &PricingTable,
+) -> SessionCacheStats {
+    let mut invalidations = Vec::new();
+    for pair in observations.windows(2) {
+        let (prev, cur) = (&pair[0], &pair[1]);
+        let prior = read_tokens(prev) + creation_tokens(prev);
+        let read = read_tokens(cur);
+        let written = creation_tokens(cur);
+        if prior == 0 || written == 0 || read.saturating_mul(2) >= prior {
+            continue;
+        }
+        let idle_ms = cur.observed_at_ms - prev.observed_at_ms;
+        let lost_tokens = prior - read;
+        let rewritten = lost_tokens.min(written);
+        let prices = pricing.pricing_for(cur.model.as_deref());
+        let write_rate = write_usd(cur, prices) / written as f64;
+        let read_rate = prices.cache_read_per_mtok / 1_000_000.0;
+        invalidations.push(CacheInvalidation {
+            source_event_id: cur.source_event_id.clone(),
+            observed_at_ms: cur.observed_at_ms,
+            cause: cause_of(prev, cur, idle_ms),
+            idle_ms,
+            lost_tokens,
+            rewrite_tokens: written,
+            wasted_usd: (rewritten as f64 * (write_rate - read_rate)).max(0.0),
+            tools_changed: cur.tools_changed.clone(),
+        });
+    }
+
+    let cache_read_tokens: u64 = observations.iter().map(read_tokens).sum();
+    let cache_creation_tokens: u64 = observations.iter().map(creation_tokens).sum();
+    let warm = &observations[1..];
+    let (early, late) = warm.split_at(warm.len() / 2);
+    let early_hit_ratio = half_ratio(early);
+    let late_hit_ratio = half_ratio(late);
+    let collapsed = observations.len() >= query.collapse_min_turns
+        && matches!(
+            (early_hit_ratio, late_hit_ratio),
+            (Some(e), Some(l)) if e - l >= query.collapse_drop
+        );
+    SessionCacheStats {
+        backend: String::new(),
+        session_id: String::new(),
+        project: None,
+        model: observations.iter().rev().find_map(|o| o.model.clone()),
+        first_observed_at_ms: observations[0].observed_at_ms,
+        last_observed_at_ms: observations[observations.len() - 1].observed_at_ms,
+        turns: observations.len() as u64,
+        cache_read_tokens,
+        cache_creation_tokens,
+        hit_ratio: ratio(cache_read_tokens, cache_creation_tokens),
+        early_hit_ratio,
+        late_hit_ratio,
+        collapsed,
+        write_usd: observations
+            .iter()
+            .map(|o| write_usd(o, pricing.pricing_for(o.model.as_deref())))
+            .sum(),
+        wasted_write_usd: invalidations.iter().map(|i| i.wasted_usd).sum(),
+        invalidations,
+    }
+}
+
+/// The first cause the observations show, most specific first. The TTL is
+/// the 1h tier's when the previous turn wrote to it.
+fn cause_of(prev: &CacheObservation, cur: &CacheObservation, idle_ms: i64) -> InvalidationCause {
+    let changed =
+        |a: &Option<String>, b: &Option<String>| matches!((a, b), (Some(a), Some(b)) if a != b);
+    let ttl_ms = if prev.cache_creation_1h_input_tokens.unwrap_or(0) > 0 {
+        TTL_1H_MS
+    } else {
+        TTL_5M_MS
+    };
+    if !cur.tools_changed.is_empty() {
+        InvalidationCause::ToolsChanged
+    } else if changed(&prev.prompt_fingerprint, &cur.prompt_fingerprint) {
+        InvalidationCause::SystemPromptChanged
+    } else if changed(&prev.model, &cur.model) {
+        InvalidationCause::ModelChanged
+    } else if idle_ms > ttl_ms {
+        InvalidationCause::IdleExpired
+    } else {
+        InvalidationCause::Unknown
+    }
+}
+
+fn read_tokens(o: &CacheObservation) -> u64 {
+    o.cache_read_input_tokens.unwrap_or(0)
+}
+
+fn creation_tokens(o: &CacheObservation) -> u64 {
+    o.cache_creation_input_tokens.unwrap_or(0)
+}
+
+/// Cost of the turn's cache writes, the 1h portion at the 1h price.
+fn write_usd(o: &CacheObservation, prices: &ModelPricing) -> f64 {
+    let total = creation_tokens(o);
+    let long = o.cache_creation_1h_input_tokens.unwrap_or(0).min(total);
+    ((total - long) as f64 * prices.cache_write_5m_per_mtok
+        + long as f64 * prices.cache_write_1h_per_mtok)
+        / 1_000_000.0
+}
+
+fn half_ratio(observations: &[CacheObservation]) -> Option<f64> {
+    ratio(
+        observations.iter().map(read_tokens).sum(),
+        observations.iter().map(creation_tokens).sum(),
+    )
+}
+
+fn ratio(read: u64, written: u64) -> Option<f64> {
+    let total = read + written;
+    (total > 0).then(|| read as f64 / total as f64)
+}
+
+impl CacheReport {
+    /// One CSV line per session, with a header. `invalidations` counts
+    /// every cause; the top cause is the most frequent one.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "backend,session_id,project,model,turns,cache_read_tokens,\
+             cache_creation_tokens,hit_ratio,early_hit_ratio,late_hit_ratio,collapsed,\
+             invalidations,top_cause,write_usd,wasted_write_usd\n",
+        );
+        let opt = |v: Option<f64>| v.map(|r| format!("{r:.4}")).unwrap_or_default();
+        for s in &self.sessions {
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{:.6},{:.6}",
+                csv_field(&s.backend),
+                csv_field(&s.session_id),
+                csv_field(s.project.as_deref().unwrap_or("")),
+                csv_field(s.model.as_deref().unwrap_or("")),
+                s.turns,
+                s.cache_read_tokens,
+                s.cache_creation_tokens,
+                opt(s.hit_ratio),
+                opt(s.early_hit_ratio),
+                opt(s.late_hit_ratio),
+                s.collapsed,
+                s.invalidations.len(),
+                s.top_cause().map_or("", InvalidationCause::as_str),
+                s.write_usd,
+                s.wasted_write_usd,
+            );
+        }
+        out
+    }
+}
+
+impl SessionCacheStats {
+    /// The most frequent invalidation cause; ties go to the earlier one in
+    /// [`InvalidationCause::ALL`].
+    pub fn top_cause(&self) -> Option<InvalidationCause> {
+        let mut top: Option<(InvalidationCause, usize)> = None;
+        for cause in InvalidationCause::ALL {
+            let n = self
+                .invalidations
+                .iter()
+                .filter(|i| i.cause == cause)
+                .count();
+            if n > 0 && top.map_or(true, |(_, best)| n > best) {
+                top = Some((cause, n));
+            }
+        }
+        top.map(|(cause, _)| cause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aikit_session_capture::{
+        ActionKind, ActionStatus, EventBatch, InMemoryEventStore, ToolEvent,
+    };
+    use std::path::PathBuf;
+
+    const MODEL: &str = "claude-sonnet-4-5";
+
+    fn obs(id: &str, at: i64, read: u64, written: u64) -> CacheObservation {
+        CacheObservation {
+            source_event_id: id.into(),
+            session_id: "s1".into(),
+            tool: ToolKind::ClaudeCode,
+            model: Some(MODEL.into()),
+            cache_read_input_tokens: Some(read),
+            cache_creation_input_tokens: Some(written),
+            cache_creation_1h_input_tokens: None,
+            assistant_blocks_hash: None,
+            tools_changed: vec![],
+            prompt_fingerprint: Some("claude-code/2.0.14".into()),
+            observed_at_ms: at,
+        }
+    }
+
+    fn action(session: &str) -> ToolEvent {
+        ToolEvent {
+            source_event_id: format!("a-{session}"),
+            source_file: PathBuf::from(format!("/tmp/{session}.jsonl")),
+            session_id: session.into(),
+            tool: ToolKind::ClaudeCode,
+            kind: ActionKind::Read,
+            target: None,
+            input: None,
+            output: None,
+            status: ActionStatus::Success,
+            error_message: None,
+            started_at_ms: Some(0),
+            duration_ms: None,
+            git_root: Some(PathBuf::from("/repo")),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    fn analyze(observations: &[CacheObservation]) -> SessionCacheStats {
+        analyze_session(
+            observations,
+            &CacheAnalysisQuery::default(),
+            &PricingTable::builtin(),
+        )
+    }
+
+    #[test]
+    fn steady_prefix_has_no_invalidations() {
+        let stats = analyze(&[
+            obs("c1", 0, 0, 10_000),
+            obs("c2", 1_000, 10_000, 500),
+            obs("c3", 2_000, 10_500, 500),
+        ]);
+        assert!(stats.invalidations.is_empty());
+        assert_eq!(stats.turns, 3);
+        assert_eq!(stats.wasted_write_usd, 0.0);
+        let hit = stats.hit_ratio.unwrap();
+        assert!((hit - 20_500.0 / 31_500.0).abs() < 1e-9, "{hit}");
+    }
+
+    #[test]
+    fn invalidations_are_attributed_to_their_cause() {
+        let mut tools = obs("c3", 2_000, 0, 11_000);
+        tools.tools_changed = vec!["mcp__github__create_issue".into()];
+        // Each change persists into the turns after it.
+        let upgraded = |id: &str, at: i64, read: u64, written: u64, model: &str| {
+            let mut o = obs(id, at, read, written);
+            o.prompt_fingerprint = Some("claude-code/2.0.15".into());
+            o.model = Some(model.into());
+            o
+        };
+        let stats = analyze(&[
+            obs("c1", 0, 0, 10_000),
+            obs("c2", 1_000, 10_000, 500),
+            tools,
+            upgraded("c4", 3_000, 0, 11_500, MODEL),
+            upgraded("c5", 4_000, 0, 12_000, "claude-opus-4-5"),
+            upgraded("c6", 4_000 + TTL_5M_MS + 1, 0, 12_500, "claude-opus-4-5"),
+            upgraded(
+                "c7",
+                4_000 + TTL_5M_MS + 2,
+                1_000,
+                12_500,
+                "claude-opus-4-5",
+            ),
+        ]);
+        let causes: Vec<_> = stats.invalidations.iter().map(|i| i.cause).collect();
+        assert_eq!(
+            causes,
+            [
+                InvalidationCause::ToolsChanged,
+                InvalidationCause::SystemPromptChanged,
+                InvalidationCause::ModelChanged,
+                InvalidationCause::IdleExpired,
+                InvalidationCause::Unknown,
+            ]
+        );
+        let first = &stats.invalidations[0];
+        assert_eq!(first.lost_tokens, 10_500);
+        assert_eq!(first.tools_changed, ["mcp__github__create_issue"]);
+        // 10.5k rewritten at $3.75/M instead of read at $0.30/M.
+        assert!((first.wasted_usd - 10_500.0 * 3.45 / 1e6).abs() < 1e-12);
+        assert_eq!(stats.model.as_deref(), Some("claude-opus-4-5"));
+    }
+
+    #[test]
+    fn one_hour_writes_extend_the_idle_ttl() {
+        let mut prev = obs("c2", 1_000, 10_000, 500);
+        prev.cache_creation_1h_input_tokens = Some(500);
+        let stats = analyze(&[
+            obs("c1", 0, 0, 10_000),
+            prev,
+            obs("c3", 1_000 + TTL_5M_MS * 2, 0, 11_000),
+        ]);
+        assert_eq!(stats.invalidations[0].cause, InvalidationCause::Unknown);
+    }
+
+    #[test]
+    fn falling_hit_ratio_flags_a_collapse() {
+        let mut healthy = vec![obs("c0", 0, 0, 10_000)];
+        for i in 1..=6 {
+            healthy.push(obs(&format!("c{i}"), i * 1_000, 10_000, 100));
+        }
+        assert!(!analyze(&healthy).collapsed);
+
+        let mut collapsing = healthy[..4].to_vec();
+        for i in 4..=6 {
+            collapsing.push(obs(&format!("c{i}"), i * 1_000, 1_000, 9_000));
+        }
+        let stats = analyze(&collapsing);
+        assert!(stats.collapsed);
+        assert!(stats.early_hit_ratio.unwrap() > 0.9);
+        assert!(stats.late_hit_ratio.unwrap() < 0.2);
+        // Too short to judge.
+        assert!(!analyze(&collapsing[..4]).collapsed);
+    }
+
+    #[tokio::test]
+    async fn report_dedupes_and_summarises_causes() {
+        let store = InMemoryEventStore::new();
+        let mut idle = obs("c3", 2_000 + TTL_5M_MS, 0, 11_000);
+        idle.session_id = "s1".into();
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![action("s1")],
+                token_events: vec![],
+                cache_observations: vec![
+                    obs("c1", 0, 0, 10_000),
+                    obs("c2", 1_000, 10_000, 500),
+                    obs("c2", 1_000, 10_000, 500),
+                    idle,
+                    obs("c4", 3_000 + TTL_5M_MS, 0, 0),
+                ],
+            })
+            .await
+            .unwrap();
+        let report = analyze_cache(
+            &store,
+            &CacheAnalysisQuery::default(),
+            &PricingTable::builtin(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.turns, 3);
+        assert_eq!(report.sessions[0].backend, "claude");
+        assert_eq!(report.sessions[0].project.as_deref(), Some("/repo"));
+        assert_eq!(report.causes.len(), 1);
+        assert_eq!(report.causes[0].cause, InvalidationCause::IdleExpired);
+        assert_eq!(report.causes[0].count, 1);
+        assert!((report.wasted_write_usd - report.causes[0].wasted_usd).abs() < 1e-12);
+
+        let csv = report.to_csv();
+        let line = csv.lines().nth(1).unwrap();
+        assert!(
+            line.starts_with("claude,s1,/repo,claude-sonnet-4-5,3,10000,21500,0.3175,"),
+            "{line}"
+        );
+        assert!(line.contains(",false,1,idle_expired,"), "{line}");
+
+        let none = analyze_cache(
+            &store,
+            &CacheAnalysisQuery {
+                tool: Some(ToolKind::Codex),
+                ..CacheAnalysisQuery::default()
+            },
+            &PricingTable::builtin(),
+        )
+        .await
+        .unwrap();
+        assert!(none.sessions.is_empty());
+        assert_eq!(none.hit_ratio, None);
+    }
+
+    #[tokio::test]
+    async fn sessions_without_tool_events_are_analysed() {
+        let store = InMemoryEventStore::new();
+        store
+            .upsert_events(EventBatch {
+                cache_observations: vec![obs("c1", 0, 0, 10_000), obs("c2", 1_000, 10_000, 500)],
+                ..EventBatch::default()
+            })
+            .await
+            .unwrap();
+        let report = analyze_cache(
+            &store,
+            &CacheAnalysisQuery::default(),
+            &PricingTable::builtin(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(report.sessions[0].session_id, "s1");
+        assert_eq!(report.sessions[0].project, None);
+        assert_eq!(report.turns, 2);
+    }
+}
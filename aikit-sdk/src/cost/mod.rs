@@ -4,11 +4,37 @@
 //! returned `None` (no provider-side cost signal), the engine falls back to
 //! adapter-emitted `TokenEvent`s via [`client_computed_cost`]. This is a
 //! **fallback only**, never a replacement — provider-side cost always wins.
+//!
+//! Prices come from the catalog in [`pricing`]; [`report`] rolls captured
+//! token events up into per-session, per-day, per-model and per-project
+//! snapshots; [`cache`] measures prompt-cache efficiency from captured
+//! cache observations.
+//!
+//! The pricing catalog is always compiled in so the `--progress` token meter
+//! can use it; everything that reads captured sessions needs the
+//! `agent-adapters` feature.
 
+#[cfg(feature = "agent-adapters")]
+pub mod cache;
+#[cfg(feature = "agent-adapters")]
 pub mod extract;
+pub mod pricing;
+#[cfg(feature = "agent-adapters")]
+pub mod report;
+
+#[cfg(feature = "agent-adapters")]
+pub use cache::{
+    analyze_cache, CacheAnalysisQuery, CacheInvalidation, CacheReport, CauseSummary,
+    InvalidationCause, SessionCacheStats,
+};
+pub use pricing::PricingError;
+#[cfg(feature = "agent-adapters")]
+pub use report::{build_report, CostGroupBy, CostReport, CostReportQuery, CostReportRow};
 
 use serde::{Deserialize, Serialize};
 
+use crate::TokenUsage;
+
 /// Provenance of a [`CostSnapshot`]. Determines authority level.
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -70,6 +96,11 @@ pub struct ModelSpend {
     pub input_tokens: u64,
     pub output_tokens: u64,
     pub cache_read_tokens: u64,
+    /// Cache writes, both tiers.
+    #[serde(default)]
+    pub cache_creation_tokens: u64,
+    #[serde(default)]
+    pub reasoning_tokens: u64,
     pub amount_usd: f64,
 }
 
@@ -80,21 +111,29 @@ pub struct CreditBalance {
     pub currency: String,
 }
 
-/// Pricing table for computing cost from token counts. Populated by the
-/// host from their pricing config; the `client_computed_cost` function uses
-/// it to estimate spend from adapter `TokenEvent`s.
+/// Pricing table for computing cost from token counts. Built from the
+/// pricing catalog ([`PricingTable::load`]); the `client_computed_cost`
+/// function uses it to estimate spend from adapter `TokenEvent`s.
 #[derive(Debug, Clone, Default)]
 pub struct PricingTable {
     /// Map of model name → per-million-token prices.
     pub models: std::collections::HashMap<String, ModelPricing>,
+    /// Prices for models no entry matches (the catalog's `[default]`).
+    pub fallback: ModelPricing,
 }
 
 /// Per-model pricing (USD per million tokens).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ModelPricing {
     pub input_per_mtok: f64,
     pub output_per_mtok: f64,
     pub cache_read_per_mtok: f64,
+    /// Anthropic 5-minute cache writes; other providers bill writes as input.
+    pub cache_write_5m_per_mtok: f64,
+    /// Anthropic 1-hour cache writes.
+    pub cache_write_1h_per_mtok: f64,
+    /// Reasoning tokens reported apart from `output_tokens`.
+    pub reasoning_per_mtok: f64,
 }
 
 impl Default for ModelPricing {
@@ -103,63 +142,96 @@ impl Default for ModelPricing {
             input_per_mtok: 3.0,
             output_per_mtok: 15.0,
             cache_read_per_mtok: 0.30,
+            cache_write_5m_per_mtok: 3.75,
+            cache_write_1h_per_mtok: 6.0,
+            reasoning_per_mtok: 15.0,
         }
     }
 }
 
+impl ModelPricing {
+    /// Cost of one usage envelope. `cache_creation_tokens` covers both cache
+    /// tiers, so the 1h portion is split out of it.
+    #[cfg(feature = "agent-adapters")]
+    pub fn cost(&self, ev: &aikit_session_capture::TokenEvent) -> f64 {
+        let write_1h = ev.cache_creation_1h_tokens.unwrap_or(0);
+        let write_5m = ev
+            .cache_creation_tokens
+            .unwrap_or(0)
+            .saturating_sub(write_1h);
+        self.priced([
+            (ev.input_tokens, self.input_per_mtok),
+            (ev.output_tokens, self.output_per_mtok),
+            (ev.cache_read_tokens, self.cache_read_per_mtok),
+            (Some(write_5m), self.cache_write_5m_per_mtok),
+            (Some(write_1h), self.cache_write_1h_per_mtok),
+            (ev.reasoning_tokens, self.reasoning_per_mtok),
+        ])
+    }
+
+    /// Cost of a run's [`TokenUsage`]. Agents report cache writes without a
+    /// tier, so they are billed as 5-minute writes.
+    pub fn usage_cost(&self, usage: &TokenUsage) -> f64 {
+        self.priced([
+            (Some(usage.input_tokens), self.input_per_mtok),
+            (Some(usage.output_tokens), self.output_per_mtok),
+            (usage.cache_read_tokens, self.cache_read_per_mtok),
+            (usage.cache_creation_tokens, self.cache_write_5m_per_mtok),
+            (usage.reasoning_tokens, self.reasoning_per_mtok),
+        ])
+    }
+
+    fn priced<const N: usize>(&self, parts: [(Option<u64>, f64); N]) -> f64 {
+        parts
+            .iter()
+            .map(|(tokens, price)| tokens.unwrap_or(0) as f64 / 1_000_000.0 * price)
+            .sum()
+    }
+}
+
+#[cfg(feature = "agent-adapters")]
 impl PricingTable {
     /// Estimate total spend from a set of token events.
     pub fn estimate(&self, tokens: &[aikit_session_capture::TokenEvent]) -> Option<f64> {
         let total: f64 = tokens
             .iter()
-            .map(|ev| {
-                let pricing = self
-                    .models
-                    .get(ev.model.as_deref().unwrap_or("default"))
-                    .cloned()
-                    .unwrap_or_default();
-                let input = ev.input_tokens.unwrap_or(0) as f64 / 1_000_000.0;
-                let output = ev.output_tokens.unwrap_or(0) as f64 / 1_000_000.0;
-                let cache_read = ev.cache_read_tokens.unwrap_or(0) as f64 / 1_000_000.0;
-                input * pricing.input_per_mtok
-                    + output * pricing.output_per_mtok
-                    + cache_read * pricing.cache_read_per_mtok
-            })
+            .map(|ev| self.pricing_for(ev.model.as_deref()).cost(ev))
             .sum();
         Some(total)
     }
 
-    /// Per-model breakdown for a set of token events.
+    /// Per-model breakdown for a set of token events, largest spend first.
     pub fn per_model_breakdown(
         &self,
         tokens: &[aikit_session_capture::TokenEvent],
     ) -> Vec<ModelSpend> {
-        let mut by_model: std::collections::HashMap<String, (u64, u64, u64, f64)> =
+        let mut by_model: std::collections::HashMap<String, ModelSpend> =
             std::collections::HashMap::new();
         for ev in tokens {
             let model = ev.model.clone().unwrap_or_else(|| "unknown".to_string());
-            let pricing = self.models.get(&model).cloned().unwrap_or_default();
-            let input = ev.input_tokens.unwrap_or(0);
-            let output = ev.output_tokens.unwrap_or(0);
-            let cache_read = ev.cache_read_tokens.unwrap_or(0);
-            let cost = (input as f64 / 1_000_000.0) * pricing.input_per_mtok
-                + (output as f64 / 1_000_000.0) * pricing.output_per_mtok
-                + (cache_read as f64 / 1_000_000.0) * pricing.cache_read_per_mtok;
-            let entry = by_model.entry(model).or_insert((0, 0, 0, 0.0));
-            entry.0 += input;
-            entry.1 += output;
-            entry.2 += cache_read;
-            entry.3 += cost;
-        }
-        by_model
-            .into_iter()
-            .map(|(model, (input, output, cache_read, amount))| ModelSpend {
+            let cost = self.pricing_for(ev.model.as_deref()).cost(ev);
+            let entry = by_model.entry(model.clone()).or_insert_with(|| ModelSpend {
                 model,
-                input_tokens: input,
-                output_tokens: output,
-                cache_read_tokens: cache_read,
-                amount_usd: amount,
-            })
-            .collect()
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_read_tokens: 0,
+                cache_creation_tokens: 0,
+                reasoning_tokens: 0,
+                amount_usd: 0.0,
+            });
+            entry.input_tokens += ev.input_tokens.unwrap_or(0);
+            entry.output_tokens += ev.output_tokens.unwrap_or(0);
+            entry.cache_read_tokens += ev.cache_read_tokens.unwrap_or(0);
+            entry.cache_creation_tokens += ev.cache_creation_tokens.unwrap_or(0);
+            entry.reasoning_tokens += ev.reasoning_tokens.unwrap_or(0);
+            entry.amount_usd += cost;
+        }
+        let mut rows: Vec<ModelSpend> = by_model.into_values().collect();
+        rows.sort_by(|a, b| {
+            b.amount_usd
+                .total_cmp(&a.amount_usd)
+                .then_with(|| a.model.cmp(&b.model))
+        });
+        rows
     }
 }
@@ -0,0 +1,279 @@
+//! Pricing catalog: built-in list prices plus user overrides.
+//!
+//! A catalog is a TOML file with one `[models."<name>"]` table per model and
+//! an optional `[default]` table for models it does not list (see
+//! `pricing.toml` for the fields). [`PricingTable::load`] starts from the
+//! built-in catalog and merges an override file over it; an override entry
+//! only needs the fields it changes when the model is already listed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cost::{ModelPricing, PricingTable};
+
+const BUILTIN_CATALOG: &str = include_str!("pricing.toml");
+
+/// Errors loading a pricing catalog.
+#[derive(Debug)]
+pub enum PricingError {
+    /// The override file could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The catalog is not valid TOML, has unknown fields, or has an entry
+    /// with a missing or negative price.
+    Invalid { origin: String, reason: String },
+}
+
+impl std::fmt::Display for PricingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PricingError::Io { path, source } => {
+                write!(
+                    f,
+                    "cannot read pricing catalog {}: {source}",
+                    path.display()
+                )
+            }
+            PricingError::Invalid { origin, reason } => {
+                write!(f, "invalid pricing catalog {origin}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PricingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PricingError::Io { source, .. } => Some(source),
+            PricingError::Invalid { .. } => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    #[serde(default)]
+    default: Option<PriceEntry>,
+    #[serde(default)]
+    models: HashMap<String, PriceEntry>,
+}
+
+/// One catalog table, USD per million tokens. Absent fields inherit from the
+/// entry being overridden, then fall back as documented in `pricing.toml`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PriceEntry {
+    input: Option<f64>,
+    output: Option<f64>,
+    cache_read: Option<f64>,
+    cache_write_5m: Option<f64>,
+    cache_write_1h: Option<f64>,
+    reasoning: Option<f64>,
+}
+
+impl PriceEntry {
+    fn resolve(&self, base: Option<&ModelPricing>) -> Result<ModelPricing, String> {
+        let input = self
+            .input
+            .or(base.map(|b| b.input_per_mtok))
+            .ok_or("missing `input`")?;
+        let output = self
+            .output
+            .or(base.map(|b| b.output_per_mtok))
+            .ok_or("missing `output`")?;
+        let cache_write_5m = self
+            .cache_write_5m
+            .or(base.map(|b| b.cache_write_5m_per_mtok))
+            .unwrap_or(input);
+        let pricing = ModelPricing {
+            input_per_mtok: input,
+            output_per_mtok: output,
+            cache_read_per_mtok: self
+                .cache_read
+                .or(base.map(|b| b.cache_read_per_mtok))
+                .unwrap_or(input),
+            cache_write_5m_per_mtok: cache_write_5m,
+            cache_write_1h_per_mtok: self
+                .cache_write_1h
+                .or(base.map(|b| b.cache_write_1h_per_mtok))
+                .unwrap_or(cache_write_5m),
+            reasoning_per_mtok: self
+                .reasoning
+                .or(base.map(|b| b.reasoning_per_mtok))
+                .unwrap_or(output),
+        };
+        let prices = [
+            pricing.input_per_mtok,
+            pricing.output_per_mtok,
+            pricing.cache_read_per_mtok,
+            pricing.cache_write_5m_per_mtok,
+            pricing.cache_write_1h_per_mtok,
+            pricing.reasoning_per_mtok,
+        ];
+        if prices.iter().any(|p| !p.is_finite() || *p < 0.0) {
+            return Err("prices must be finite and non-negative".into());
+        }
+        Ok(pricing)
+    }
+}
+
+impl PricingTable {
+    /// The catalog compiled into aikit (`pricing.toml`).
+    pub fn builtin() -> Self {
+        let mut table = PricingTable::default();
+        table
+            .merge_toml(BUILTIN_CATALOG, "(built-in)")
+            .expect("built-in pricing catalog is valid");
+        table
+    }
+
+    /// The built-in catalog with `overrides` merged over it. With no
+    /// explicit file, [`PricingTable::default_overrides_path`] is used when
+    /// it exists.
+    pub fn load(overrides: Option<&Path>) -> Result<Self, PricingError> {
+        let mut table = PricingTable::builtin();
+        let path = match overrides {
+            Some(path) => Some(path.to_path_buf()),
+            None => Self::default_overrides_path().filter(|p| p.is_file()),
+        };
+        if let Some(path) = path {
+            table.merge_file(&path)?;
+        }
+        Ok(table)
+    }
+
+    /// `~/.aikit/pricing.toml`.
+    pub fn default_overrides_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".aikit").join("pricing.toml"))
+    }
+
+    /// Merge the catalog at `path` over this table.
+    pub fn merge_file(&mut self, path: &Path) -> Result<(), PricingError> {
+        let text = std::fs::read_to_string(path).map_err(|source| PricingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.merge_toml(&text, &path.display().to_string())
+    }
+
+    /// Merge a catalog document over this table. `origin` names it in
+    /// errors. Nothing is merged when any entry is invalid.
+    pub fn merge_toml(&mut self, text: &str, origin: &str) -> Result<(), PricingError> {
+        let invalid = |reason: String| PricingError::Invalid {
+            origin: origin.to_string(),
+            reason,
+        };
+        let file: CatalogFile = toml::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        let fallback = match &file.default {
+            Some(entry) => entry
+                .resolve(Some(&self.fallback))
+                .map_err(|e| invalid(format!("[default]: {e}")))?,
+            None => self.fallback.clone(),
+        };
+        let mut models = HashMap::with_capacity(file.models.len());
+        for (name, entry) in &file.models {
+            let pricing = entry
+                .resolve(self.models.get(name))
+                .map_err(|e| invalid(format!("[models.\"{name}\"]: {e}")))?;
+            models.insert(name.clone(), pricing);
+        }
+        self.fallback = fallback;
+        self.models.extend(models);
+        Ok(())
+    }
+
+    /// The catalog entry for `model`: an exact match, then the name after a
+    /// `provider/` prefix, then the longest entry that is a prefix ending at
+    /// a `-` boundary. `None` when nothing matches.
+    pub fn lookup(&self, model: &str) -> Option<&ModelPricing> {
+        if let Some(pricing) = self.models.get(model) {
+            return Some(pricing);
+        }
+        let name = model.rsplit('/').next().unwrap_or(model);
+        if let Some(pricing) = self.models.get(name) {
+            return Some(pricing);
+        }
+        self.models
+            .iter()
+            .filter(|(key, _)| {
+                name.strip_prefix(key.as_str())
+                    .is_some_and(|rest| rest.starts_with('-'))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, pricing)| pricing)
+    }
+
+    /// [`PricingTable::lookup`], falling back to [`PricingTable::fallback`].
+    pub fn pricing_for(&self, model: Option<&str>) -> &ModelPricing {
+        model.and_then(|m| self.lookup(m)).unwrap_or(&self.fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_catalog_parses_and_matches_dated_names() {
+        let table = PricingTable::builtin();
+        let sonnet = table.lookup("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.input_per_mtok, 3.0);
+        assert_eq!(sonnet.cache_write_1h_per_mtok, 6.0);
+        let opus = table.lookup("claude-opus-4-5-20251101").unwrap();
+        assert_eq!(opus.output_per_mtok, 25.0);
+        let mini = table.lookup("openai/gpt-5-mini").unwrap();
+        assert_eq!(mini.input_per_mtok, 0.25);
+        // OpenAI entries bill cache writes as input and reasoning as output.
+        assert_eq!(mini.cache_write_5m_per_mtok, 0.25);
+        assert_eq!(mini.reasoning_per_mtok, 2.0);
+        assert!(table.lookup("gpt-5x").is_none());
+        assert!(table.lookup("mystery-model").is_none());
+    }
+
+    #[test]
+    fn overrides_merge_field_by_field() {
+        let mut table = PricingTable::builtin();
+        table
+            .merge_toml(
+                r#"
+                [default]
+                input = 1.0
+                output = 2.0
+
+                [models."claude-sonnet-4"]
+                input = 2.5
+
+                [models."local-llm"]
+                input = 0.0
+                output = 0.0
+                "#,
+                "test",
+            )
+            .unwrap();
+        let sonnet = table.lookup("claude-sonnet-4").unwrap();
+        assert_eq!(sonnet.input_per_mtok, 2.5);
+        assert_eq!(sonnet.output_per_mtok, 15.0);
+        assert_eq!(table.lookup("local-llm").unwrap().cache_read_per_mtok, 0.0);
+        assert_eq!(table.pricing_for(Some("mystery")).output_per_mtok, 2.0);
+        assert_eq!(table.pricing_for(None).cache_read_per_mtok, 0.30);
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected_whole() {
+        let mut table = PricingTable::builtin();
+        for bad in [
+            "[models.\"new-model\"]\ninput = 1.0\n",
+            "[models.\"gpt-5\"]\ninput = -1.0\n",
+            "[models.\"gpt-5\"]\ninptu = 1.0\n",
+        ] {
+            let err = table.merge_toml(bad, "test").unwrap_err();
+            assert!(matches!(err, PricingError::Invalid { .. }), "{bad}");
+        }
+        assert_eq!(table.lookup("gpt-5").unwrap().input_per_mtok, 1.25);
+    }
+}
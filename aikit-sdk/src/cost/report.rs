@@ -0,0 +1,507 @@
+//! Cost reports: captured `TokenEvent`s priced with a [`PricingTable`] and
+//! rolled up per session, per UTC day, per model or per project.
+//!
+//! Every row is a [`CostSnapshot`] with `ClientComputed` provenance, so the
+//! same shape serves `aikit cost` and `GET /api/v1/capture/cost`. Sessions
+//! are discovered through [`aggregate::usage_sessions`], so usage is
+//! reported for sessions that recorded no tool event too.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use aikit_session_capture::aggregate::{self, AggregateWindow};
+use aikit_session_capture::{EventStore, StoreError, TokenEvent, ToolKind};
+use serde::{Deserialize, Serialize};
+
+use crate::cost::{CostProvenance, CostSnapshot, CostWindow, PricingTable, Spend, SpendScope};
+
+/// How [`build_report`] groups token events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostGroupBy {
+    #[default]
+    Session,
+    /// UTC calendar day of `captured_at_ms`.
+    Day,
+    Model,
+    /// The session's `git_root`.
+    Project,
+}
+
+impl CostGroupBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CostGroupBy::Session => "session",
+            CostGroupBy::Day => "day",
+            CostGroupBy::Model => "model",
+            CostGroupBy::Project => "project",
+        }
+    }
+
+    fn scope(self) -> SpendScope {
+        match self {
+            CostGroupBy::Session => SpendScope::Session,
+            CostGroupBy::Day => SpendScope::Daily,
+            CostGroupBy::Model | CostGroupBy::Project => SpendScope::Window,
+        }
+    }
+}
+
+impl std::str::FromStr for CostGroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "session" => Ok(CostGroupBy::Session),
+            "day" => Ok(CostGroupBy::Day),
+            "model" => Ok(CostGroupBy::Model),
+            "project" => Ok(CostGroupBy::Project),
+            other => Err(format!(
+                "unknown grouping '{other}' (expected session, day, model or project)"
+            )),
+        }
+    }
+}
+
+/// What [`build_report`] covers.
+#[derive(Debug, Clone, Default)]
+pub struct CostReportQuery {
+    pub group_by: CostGroupBy,
+    /// Token events outside the window are left out.
+    pub window: AggregateWindow,
+    /// Only this tool's sessions; all tools when `None`.
+    pub tool: Option<ToolKind>,
+}
+
+/// Output of [`build_report`]. Rows are ordered by day for
+/// [`CostGroupBy::Day`] and by spend, largest first, otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostReport {
+    pub group_by: CostGroupBy,
+    pub generated_at_ms: i64,
+    pub total_usd: f64,
+    pub rows: Vec<CostReportRow>,
+    /// Models no catalog entry matched, priced at the catalog's fallback.
+    pub unpriced_models: Vec<String>,
+}
+
+/// One group of a [`CostReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostReportRow {
+    /// Session id, `YYYY-MM-DD`, model name or git root; `"unknown"` when
+    /// the model or git root was not recorded.
+    pub key: String,
+    pub sessions: u64,
+    /// Usage envelopes (assistant turns) in the group.
+    pub turns: u64,
+    pub snapshot: CostSnapshot,
+}
+
+/// Price every token event in `store` that `query` selects and group it.
+pub async fn build_report(
+    store: &dyn EventStore,
+    query: &CostReportQuery,
+    pricing: &PricingTable,
+) -> Result<CostReport, StoreError> {
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+    for session in aggregate::usage_sessions(store).await? {
+        let tool = session.tool;
+        if query.tool.is_some_and(|t| t != tool) {
+            continue;
+        }
+        let project = session
+            .git_root
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        for ev in store
+            .token_events_for_session(tool, &session.session_id)
+            .await?
+        {
+            if !query.window.contains(Some(ev.captured_at_ms)) {
+                continue;
+            }
+            let key = match query.group_by {
+                CostGroupBy::Session => session.session_id.clone(),
+                CostGroupBy::Day => utc_day(ev.captured_at_ms),
+                CostGroupBy::Model => ev.model.clone().unwrap_or_else(|| "unknown".into()),
+                CostGroupBy::Project => project.clone(),
+            };
+            let group = groups.entry(key).or_default();
+            group
+                .sessions
+                .insert((tool.as_str(), session.session_id.clone()));
+            group.backends.insert(backend_key(tool));
+            group.events.push(ev);
+        }
+    }
+
+    let generated_at_ms = now_ms();
+    let mut unpriced = BTreeSet::new();
+    let mut rows: Vec<CostReportRow> = groups
+        .into_iter()
+        .map(|(key, group)| {
+            for ev in &group.events {
+                if let Some(model) = ev.model.as_deref() {
+                    if pricing.lookup(model).is_none() {
+                        unpriced.insert(model.to_string());
+                    }
+                }
+            }
+            let amount_usd = pricing.estimate(&group.events).unwrap_or(0.0);
+            let start_ms = group.events.iter().map(|e| e.captured_at_ms).min();
+            let end_ms = group.events.iter().map(|e| e.captured_at_ms).max();
+            let snapshot = CostSnapshot {
+                backend: group.backends.into_iter().collect::<Vec<_>>().join(","),
+                captured_at_ms: generated_at_ms,
+                windows: vec![CostWindow {
+                    label: key.clone(),
+                    start_ms: start_ms.unwrap_or(0),
+                    end_ms: end_ms.unwrap_or(0),
+                    amount_usd,
+                }],
+                spend: Some(Spend {
+                    amount_usd,
+                    scope: query.group_by.scope(),
+                    is_estimate: true,
+                }),
+                per_model: pricing.per_model_breakdown(&group.events),
+                credits: None,
+                provenance: CostProvenance::ClientComputed,
+            };
+            CostReportRow {
+                key,
+                sessions: group.sessions.len() as u64,
+                turns: group.events.len() as u64,
+                snapshot,
+            }
+        })
+        .collect();
+    if query.group_by != CostGroupBy::Day {
+        rows.sort_by(|a, b| {
+            row_amount(b)
+                .total_cmp(&row_amount(a))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+    }
+    Ok(CostReport {
+        group_by: query.group_by,
+        generated_at_ms,
+        total_usd: rows.iter().map(row_amount).sum(),
+        rows,
+        unpriced_models: unpriced.into_iter().collect(),
+    })
+}
+
+impl CostReport {
+    /// One CSV line per row, with a header. Token columns sum the row's
+    /// per-model breakdown.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "group,key,backend,sessions,turns,input_tokens,output_tokens,\
+             cache_read_tokens,cache_creation_tokens,reasoning_tokens,amount_usd,\
+             first_event_at_ms,last_event_at_ms\n",
+        );
+        for row in &self.rows {
+            let models = &row.snapshot.per_model;
+            let sum = |f: fn(&crate::cost::ModelSpend) -> u64| models.iter().map(f).sum::<u64>();
+            let window = row.snapshot.windows.first();
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{:.6},{},{}",
+                self.group_by.as_str(),
+                csv_field(&row.key),
+                csv_field(&row.snapshot.backend),
+                row.sessions,
+                row.turns,
+                sum(|m| m.input_tokens),
+                sum(|m| m.output_tokens),
+                sum(|m| m.cache_read_tokens),
+                sum(|m| m.cache_creation_tokens),
+                sum(|m| m.reasoning_tokens),
+                row_amount(row),
+                window.map_or(0, |w| w.start_ms),
+                window.map_or(0, |w| w.end_ms),
+            );
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+struct Group {
+    sessions: BTreeSet<(&'static str, String)>,
+    backends: BTreeSet<&'static str>,
+    events: Vec<TokenEvent>,
+}
+
+fn row_amount(row: &CostReportRow) -> f64 {
+    row.snapshot.spend.as_ref().map_or(0.0, |s| s.amount_usd)
+}
+
+pub(super) fn backend_key(tool: ToolKind) -> &'static str {
+    match tool {
+        ToolKind::ClaudeCode => "claude",
+        ToolKind::Codex => "codex",
+        ToolKind::OpenCode => "opencode",
+        ToolKind::Cursor => "cursor",
+        ToolKind::Gemini => "gemini",
+        _ => "unknown",
+    }
+}
+
+/// Quote a CSV field when it holds a delimiter, quote or line break.
+pub(super) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `YYYY-MM-DD` of an epoch-ms timestamp, in UTC.
+fn utc_day(ms: i64) -> String {
+    // Civil-from-days (H. Hinnant), valid for the whole proleptic calendar.
+    let days = ms.div_euclid(86_400_000);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+pub(super) fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aikit_session_capture::{
+        ActionKind, ActionStatus, CaptureSource, EventBatch, InMemoryEventStore, ToolEvent,
+    };
+    use std::path::PathBuf;
+
+    const DAY: i64 = 86_400_000;
+
+    fn action(session: &str, tool: ToolKind, git_root: &str, at: i64) -> ToolEvent {
+        ToolEvent {
+            source_event_id: format!("a-{session}"),
+            source_file: PathBuf::from(format!("/tmp/{session}.jsonl")),
+            session_id: session.into(),
+            tool,
+            kind: ActionKind::Read,
+            target: None,
+            input: None,
+            output: None,
+            status: ActionStatus::Success,
+            error_message: None,
+            started_at_ms: Some(at),
+            duration_ms: None,
+            git_root: Some(PathBuf::from(git_root)),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    fn usage(id: &str, session: &str, tool: ToolKind, model: &str, at: i64) -> TokenEvent {
+        TokenEvent {
+            source_event_id: id.into(),
+            session_id: session.into(),
+            tool,
+            model: Some(model.into()),
+            request_id: None,
+            input_tokens: Some(1_000_000),
+            cache_read_tokens: Some(1_000_000),
+            cache_creation_tokens: Some(1_000_000),
+            cache_creation_1h_tokens: Some(500_000),
+            output_tokens: Some(100_000),
+            reasoning_tokens: None,
+            captured_at_ms: at,
+            captured_via: CaptureSource::Transcript,
+        }
+    }
+
+    async fn store() -> InMemoryEventStore {
+        let store = InMemoryEventStore::new();
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![
+                    action("s1", ToolKind::ClaudeCode, "/repo/a", 0),
+                    action("s2", ToolKind::Codex, "/repo/b", DAY),
+                ],
+                token_events: vec![
+                    usage(
+                        "t1",
+                        "s1",
+                        ToolKind::ClaudeCode,
+                        "claude-sonnet-4-20250514",
+                        10,
+                    ),
+                    usage(
+                        "t2",
+                        "s1",
+                        ToolKind::ClaudeCode,
+                        "claude-sonnet-4-20250514",
+                        DAY + 10,
+                    ),
+                    usage("t3", "s2", ToolKind::Codex, "mystery-model", DAY + 20),
+                ],
+                cache_observations: vec![],
+            })
+            .await
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn sonnet_envelope_prices_every_token_class() {
+        let pricing = PricingTable::builtin();
+        let ev = usage(
+            "t",
+            "s",
+            ToolKind::ClaudeCode,
+            "claude-sonnet-4-20250514",
+            0,
+        );
+        // 1M input $3 + 1M cache read $0.30 + 0.5M 5m writes $1.875
+        // + 0.5M 1h writes $3 + 0.1M output $1.50
+        let cost = pricing.pricing_for(ev.model.as_deref()).cost(&ev);
+        assert!((cost - 9.675).abs() < 1e-9, "{cost}");
+    }
+
+    #[tokio::test]
+    async fn groups_by_day_model_and_project() {
+        let store = store().await;
+        let pricing = PricingTable::builtin();
+        let report = |group_by| {
+            let query = CostReportQuery {
+                group_by,
+                ..CostReportQuery::default()
+            };
+            let store = &store;
+            let pricing = &pricing;
+            async move { build_report(store, &query, pricing).await.unwrap() }
+        };
+
+        let by_day = report(CostGroupBy::Day).await;
+        let days: Vec<&str> = by_day.rows.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(days, ["1970-01-01", "1970-01-02"]);
+        assert_eq!(by_day.rows[1].sessions, 2);
+        assert_eq!(by_day.rows[1].snapshot.backend, "claude,codex");
+        assert_eq!(by_day.unpriced_models, ["mystery-model"]);
+
+        let by_model = report(CostGroupBy::Model).await;
+        assert_eq!(by_model.rows[0].key, "claude-sonnet-4-20250514");
+        assert_eq!(by_model.rows[0].turns, 2);
+        assert!((by_model.total_usd - by_day.total_usd).abs() < 1e-9);
+
+        let by_project = report(CostGroupBy::Project).await;
+        let a = by_project.rows.iter().find(|r| r.key == "/repo/a").unwrap();
+        assert!((a.snapshot.spend.as_ref().unwrap().amount_usd - 2.0 * 9.675).abs() < 1e-9);
+        assert_eq!(a.snapshot.provenance, CostProvenance::ClientComputed);
+    }
+
+    #[tokio::test]
+    async fn window_and_tool_filter_events() {
+        let store = store().await;
+        let query = CostReportQuery {
+            group_by: CostGroupBy::Session,
+            window: AggregateWindow {
+                since_ms: Some(DAY),
+                until_ms: None,
+            },
+            tool: Some(ToolKind::ClaudeCode),
+        };
+        let report = build_report(&store, &query, &PricingTable::builtin())
+            .await
+            .unwrap();
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].key, "s1");
+        assert_eq!(report.rows[0].turns, 1);
+        assert!(report.unpriced_models.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sessions_without_tool_events_are_priced() {
+        let store = store().await;
+        let model = "claude-sonnet-4-20250514";
+        store
+            .upsert_events(EventBatch {
+                token_events: vec![usage("t4", "s3", ToolKind::ClaudeCode, model, 30)],
+                ..EventBatch::default()
+            })
+            .await
+            .unwrap();
+        let report = build_report(
+            &store,
+            &CostReportQuery::default(),
+            &PricingTable::builtin(),
+        )
+        .await
+        .unwrap();
+        let s3 = report.rows.iter().find(|r| r.key == "s3").unwrap();
+        assert_eq!(s3.turns, 1);
+        assert!((s3.snapshot.spend.as_ref().unwrap().amount_usd - 9.675).abs() < 1e-9);
+
+        let by_project = build_report(
+            &store,
+            &CostReportQuery {
+                group_by: CostGroupBy::Project,
+                ..CostReportQuery::default()
+            },
+            &PricingTable::builtin(),
+        )
+        .await
+        .unwrap();
+        assert!(by_project.rows.iter().any(|r| r.key == "unknown"));
+    }
+
+    #[test]
+    fn csv_quotes_keys_and_sums_tokens() {
+        let report = CostReport {
+            group_by: CostGroupBy::Project,
+            generated_at_ms: 0,
+            total_usd: 1.5,
+            rows: vec![CostReportRow {
+                key: "/repo/a,b".into(),
+                sessions: 1,
+                turns: 2,
+                snapshot: CostSnapshot {
+                    backend: "claude".into(),
+                    captured_at_ms: 0,
+                    windows: vec![],
+                    spend: Some(Spend {
+                        amount_usd: 1.5,
+                        scope: SpendScope::Window,
+                        is_estimate: true,
+                    }),
+                    per_model: vec![],
+                    credits: None,
+                    provenance: CostProvenance::ClientComputed,
+                },
+            }],
+            unpriced_models: vec![],
+        };
+        let csv = report.to_csv();
+        let line = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            line,
+            "project,\"/repo/a,b\",claude,1,2,0,0,0,0,0,1.500000,0,0"
+        );
+    }
+
+    #[test]
+    fn utc_day_formats_calendar_dates() {
+        assert_eq!(utc_day(0), "1970-01-01");
+        assert_eq!(utc_day(1_709_251_199_000), "2024-02-29");
+        assert_eq!(utc_day(-1), "1969-12-31");
+    }
+}
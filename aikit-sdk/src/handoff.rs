@@ -0,0 +1,886 @@
+//! Cross-agent session handoff: continue one Backend's transcript in another.
+//!
+//! When an agent stops mid-task (quota, outage, or just preference), the same
+//! conversation can be picked up elsewhere without re-explaining it. A
+//! [`Handoff`] reads the source session through [`HistoryReader::messages`]
+//! and renders it into a seed for the target Backend:
+//!
+//! - the in-process `aikit` agent gets the transcript as prior turns — a new
+//!   [`SessionStore`] session the normal resume path feeds to
+//!   `run_with_context`;
+//! - external CLIs get a condensed handoff prompt (original request, files
+//!   touched, decisions, open TODOs, where the previous agent stopped)
+//!   prepended to the new prompt, since they cannot be handed foreign turns.
+//!
+//! Callers address a source session as `<backend>:<session_id>` (see
+//! [`HandoffSource`]); `aikit agent run --continue-from` and the serve
+//! `continue_from` request field are thin wrappers over [`Handoff::seed`].
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::history::{
+    HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryReader, MessagesQuery,
+};
+use crate::runner::backend::Backend;
+use crate::runner::MessageRole;
+use crate::session_store::{now_rfc3339, SessionFile, SessionStore, SessionTurn};
+
+/// Page size used to drain a transcript (the readers' maximum).
+const PAGE: usize = 1000;
+/// Caps on each digest section, so a long session still yields a prompt of
+/// bounded size.
+const MAX_FILES: usize = 50;
+const MAX_DECISIONS: usize = 10;
+const MAX_TODOS: usize = 20;
+const MAX_FOLLOW_UPS: usize = 5;
+const MAX_LINE_CHARS: usize = 240;
+const MAX_LAST_REPLY_CHARS: usize = 2000;
+
+/// A session to continue from, written `<backend>:<session_id>`
+/// (e.g. `claude:0f5c…`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandoffSource {
+    pub backend: Backend,
+    pub session_id: String,
+}
+
+impl FromStr for HandoffSource {
+    type Err = HandoffError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| HandoffError::InvalidSource {
+            value: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let (key, id) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("expected <backend>:<session_id>"))?;
+        let backend = Backend::from_key(key.trim())
+            .ok_or_else(|| invalid(&format!("unknown backend '{}'", key.trim())))?;
+        let session_id = id.trim();
+        if session_id.is_empty() {
+            return Err(invalid("session id is empty"));
+        }
+        Ok(HandoffSource {
+            backend,
+            session_id: session_id.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for HandoffSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.backend.key(), self.session_id)
+    }
+}
+
+/// Errors from loading or seeding a [`Handoff`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HandoffError {
+    /// The `<backend>:<session_id>` string could not be parsed.
+    InvalidSource { value: String, reason: String },
+    /// The source Backend has no history store to read the session from.
+    Unsupported { backend: Backend },
+    /// Reading the source transcript failed (including `NotFound`).
+    History(HistoryError),
+    /// Writing the seeded `aikit` session failed.
+    Store { message: String },
+}
+
+impl std::fmt::Display for HandoffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandoffError::InvalidSource { value, reason } => {
+                write!(f, "invalid handoff source '{value}': {reason}")
+            }
+            HandoffError::Unsupported { backend } => write!(
+                f,
+                "backend '{}' has no session history to continue from",
+                backend.key()
+            ),
+            HandoffError::History(e) => write!(f, "{e}"),
+            HandoffError::Store { message } => {
+                write!(f, "failed to seed aikit session: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HandoffError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HandoffError::History(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<HistoryError> for HandoffError {
+    fn from(e: HistoryError) -> Self {
+        match e {
+            HistoryError::Unsupported { backend } => HandoffError::Unsupported { backend },
+            other => HandoffError::History(other),
+        }
+    }
+}
+
+/// What a run needs to continue a handed-off session: the prompt to send
+/// and, for the `aikit` target, the seeded session to resume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandoffSeed {
+    /// The prompt to run — the caller's prompt as-is when the history travels
+    /// as prior turns, otherwise prefixed with the condensed handoff.
+    pub prompt: String,
+    /// `Some` for the `aikit` target: pass to `RunOptions::with_session_id`.
+    pub session_id: Option<String>,
+}
+
+/// A condensed view of a transcript, used to render the handoff prompt.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandoffDigest {
+    /// The first user prompt — the task as originally stated.
+    pub original_request: Option<String>,
+    /// Later user prompts, most recent last.
+    pub follow_ups: Vec<String>,
+    /// Files the source agent changed, in first-touched order.
+    pub files_modified: Vec<String>,
+    /// Files the source agent only read.
+    pub files_read: Vec<String>,
+    /// The gist of each assistant reply before the last one.
+    pub decisions: Vec<String>,
+    /// Unfinished items from the agent's latest todo/plan list.
+    pub open_todos: Vec<String>,
+    /// The last thing the source agent said.
+    pub last_reply: Option<String>,
+}
+
+/// A source session loaded for handoff.
+#[derive(Debug, Clone)]
+pub struct Handoff {
+    source: HandoffSource,
+    messages: Vec<HistoryMessage>,
+}
+
+impl Handoff {
+    /// Read `source` through its Backend's [`HistoryReader`].
+    pub fn load(source: HandoffSource) -> Result<Self, HandoffError> {
+        let reader = source
+            .backend
+            .history_reader()
+            .ok_or(HandoffError::Unsupported {
+                backend: source.backend,
+            })?;
+        Self::load_with(reader.as_ref(), &source.session_id)
+    }
+
+    /// Read `session_id` from an explicit reader (e.g. one rooted at a
+    /// non-default store).
+    pub fn load_with(reader: &dyn HistoryReader, session_id: &str) -> Result<Self, HandoffError> {
+        let mut messages = Vec::new();
+        loop {
+            let q = MessagesQuery {
+                limit: Some(PAGE),
+                offset: messages.len(),
+            };
+            let page = reader.messages(session_id, &q, None)?;
+            let done = page.len() < PAGE;
+            messages.extend(page);
+            if done {
+                break;
+            }
+        }
+        Ok(Self::from_messages(
+            HandoffSource {
+                backend: reader.backend(),
+                session_id: session_id.to_string(),
+            },
+            messages,
+        ))
+    }
+
+    /// Build a handoff from an already-read transcript.
+    pub fn from_messages(source: HandoffSource, messages: Vec<HistoryMessage>) -> Self {
+        Self { source, messages }
+    }
+
+    pub fn source(&self) -> &HandoffSource {
+        &self.source
+    }
+
+    pub fn messages(&self) -> &[HistoryMessage] {
+        &self.messages
+    }
+
+    /// Seed a run of `target` with this transcript.
+    ///
+    /// For [`Backend::Aikit`] the transcript is saved as a new session in the
+    /// default [`SessionStore`]; every other target gets [`Handoff::prompt`].
+    pub fn seed(
+        &self,
+        target: Backend,
+        prompt: &str,
+        cwd: &Path,
+    ) -> Result<HandoffSeed, HandoffError> {
+        if target == Backend::Aikit {
+            self.seed_in(&SessionStore::open(), prompt, cwd)
+        } else {
+            Ok(HandoffSeed {
+                prompt: self.prompt(prompt),
+                session_id: None,
+            })
+        }
+    }
+
+    /// Save the transcript as a new `aikit` session in `store` and return a
+    /// seed that resumes it.
+    pub fn seed_in(
+        &self,
+        store: &SessionStore,
+        prompt: &str,
+        cwd: &Path,
+    ) -> Result<HandoffSeed, HandoffError> {
+        let now = now_rfc3339();
+        let file = SessionFile {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            agent: Backend::Aikit.key().to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            cwd: cwd.to_string_lossy().into_owned(),
+            turns: self.session_turns(),
+            title: Some(format!(
+                "Continued from {}:{}",
+                self.source.backend.key(),
+                truncate(&self.source.session_id, 8)
+            )),
+            tag: None,
+        };
+        store.save(&file).map_err(|e| HandoffError::Store {
+            message: format!("{e:?}"),
+        })?;
+        Ok(HandoffSeed {
+            prompt: prompt.to_string(),
+            session_id: Some(file.session_id),
+        })
+    }
+
+    /// The transcript as `aikit` session turns: user prompts and assistant
+    /// replies, alternating.
+    ///
+    /// Tool calls are folded into the assistant text as one-line notes and
+    /// tool output is dropped: the source agent's tools are not the target's,
+    /// and replaying foreign tool-call ids would be rejected by providers
+    /// that pair calls with results.
+    pub fn session_turns(&self) -> Vec<SessionTurn> {
+        let mut turns: Vec<SessionTurn> = Vec::new();
+        for msg in self.main_thread() {
+            let text = match msg.role {
+                MessageRole::User => user_text(msg),
+                MessageRole::Assistant => assistant_text(msg),
+                _ => None,
+            };
+            let Some(text) = text else { continue };
+            let role = if msg.role == MessageRole::User {
+                "user"
+            } else {
+                "assistant"
+            };
+            match turns.last_mut() {
+                Some(last) if last.role == role => {
+                    last.content.push_str("\n\n");
+                    last.content.push_str(&text);
+                }
+                _ => turns.push(SessionTurn {
+                    role: role.to_string(),
+                    content: text,
+                    tool_calls: None,
+                    tool_results: None,
+                }),
+            }
+        }
+        turns
+    }
+
+    /// Condense the transcript into a [`HandoffDigest`].
+    pub fn digest(&self) -> HandoffDigest {
+        let mut digest = HandoffDigest::default();
+        let mut seen_modified = HashSet::new();
+        let mut seen_read = HashSet::new();
+        let mut replies: Vec<String> = Vec::new();
+        let mut latest_todos: Option<Vec<String>> = None;
+
+        for msg in self.main_thread() {
+            match msg.role {
+                MessageRole::User => {
+                    if let Some(text) = user_text(msg) {
+                        if digest.original_request.is_none() {
+                            digest.original_request = Some(text);
+                        } else {
+                            digest.follow_ups.push(text);
+                        }
+                    }
+                }
+                MessageRole::Assistant => {
+                    for block in blocks(msg) {
+                        match block {
+                            HistoryBlock::Text { text } if !text.trim().is_empty() => {
+                                replies.push(text.trim().to_string());
+                            }
+                            HistoryBlock::ToolUse { name, input, .. } => {
+                                if let Some(todos) = open_todos(input) {
+                                    latest_todos = Some(todos);
+                                }
+                                let modifies =
+                                    is_modifying_tool(name) || patch_body(name, input).is_some();
+                                for path in touched_paths(name, input) {
+                                    if modifies {
+                                        seen_read.remove(&path);
+                                        if seen_modified.insert(path.clone()) {
+                                            digest.files_modified.push(path);
+                                        }
+                                    } else if is_reading_tool(name)
+                                        && !seen_modified.contains(&path)
+                                        && seen_read.insert(path.clone())
+                                    {
+                                        digest.files_read.push(path);
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        digest.files_read.retain(|p| !seen_modified.contains(p));
+        digest.files_modified.truncate(MAX_FILES);
+        digest.files_read.truncate(MAX_FILES);
+        skip_to_last(&mut digest.follow_ups, MAX_FOLLOW_UPS);
+
+        digest.last_reply = replies.pop().map(|r| truncate(&r, MAX_LAST_REPLY_CHARS));
+        let mut decisions: Vec<String> = replies.iter().map(|r| gist(r)).collect();
+        skip_to_last(&mut decisions, MAX_DECISIONS);
+        digest.decisions = decisions;
+
+        let mut todos = latest_todos.unwrap_or_default();
+        if let Some(reply) = digest.last_reply.as_deref() {
+            todos.extend(
+                reply
+                    .lines()
+                    .filter_map(|l| l.trim().strip_prefix("- [ ]"))
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty()),
+            );
+        }
+        todos.truncate(MAX_TODOS);
+        digest.open_todos = todos;
+        digest
+    }
+
+    /// Render the condensed handoff prompt for an external CLI, followed by
+    /// `next` (the new instruction; "continue the task" when blank).
+    pub fn prompt(&self, next: &str) -> String {
+        let d = self.digest();
+        let mut out = format!(
+            "You are taking over a task that was started in another coding agent \
+             ({} session {}). Below is a summary of that session; continue from \
+             where it stopped without redoing finished work.\n",
+            self.source.backend.key(),
+            self.source.session_id
+        );
+        if let Some(req) = &d.original_request {
+            out.push_str("\n## Original request\n");
+            out.push_str(&truncate(req, MAX_LAST_REPLY_CHARS));
+            out.push('\n');
+        }
+        section(&mut out, "Follow-up requests", &d.follow_ups, |s| gist(s));
+        if !d.files_modified.is_empty() || !d.files_read.is_empty() {
+            out.push_str("\n## Files touched\n");
+            for f in &d.files_modified {
+                out.push_str(&format!("- modified: {f}\n"));
+            }
+            for f in &d.files_read {
+                out.push_str(&format!("- read: {f}\n"));
+            }
+        }
+        section(&mut out, "Decisions so far", &d.decisions, String::clone);
+        section(&mut out, "Open TODOs", &d.open_todos, |t| {
+            format!("[ ] {t}")
+        });
+        if let Some(last) = &d.last_reply {
+            out.push_str("\n## Where it stopped\n");
+            out.push_str(last);
+            out.push('\n');
+        }
+        out.push_str("\n## Next\n");
+        let next = next.trim();
+        out.push_str(if next.is_empty() {
+            "Continue the task."
+        } else {
+            next
+        });
+        out.push('\n');
+        out
+    }
+
+    /// Messages of the main conversation — subagent (sidechain) turns are
+    /// the parent tool call's business, not the handoff's.
+    fn main_thread(&self) -> impl Iterator<Item = &HistoryMessage> {
+        self.messages
+            .iter()
+            .filter(|m| m.parent_tool_use_id.is_none())
+    }
+}
+
+fn section(out: &mut String, title: &str, items: &[String], render: impl Fn(&String) -> String) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n## {title}\n"));
+    for item in items {
+        out.push_str(&format!("- {}\n", render(item)));
+    }
+}
+
+fn blocks(msg: &HistoryMessage) -> &[HistoryBlock] {
+    match &msg.content {
+        HistoryContent::Blocks(b) => b,
+        _ => &[],
+    }
+}
+
+/// A user message's prompt text. Harness-injected content (Claude's
+/// `<command-name>`/`<system-reminder>` wrappers and the like) is skipped.
+fn user_text(msg: &HistoryMessage) -> Option<String> {
+    let text = match &msg.content {
+        HistoryContent::Text(t) => t.clone(),
+        HistoryContent::Blocks(b) => join_text(b),
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty() && !text.starts_with('<')).then(|| text.to_string())
+}
+
+/// An assistant message's reply text, with each tool call noted on its own
+/// line. Thinking is omitted.
+fn assistant_text(msg: &HistoryMessage) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    match &msg.content {
+        HistoryContent::Text(t) if !t.trim().is_empty() => parts.push(t.trim().to_string()),
+        HistoryContent::Blocks(b) => {
+            for block in b {
+                match block {
+                    HistoryBlock::Text { text } if !text.trim().is_empty() => {
+                        parts.push(text.trim().to_string())
+                    }
+                    HistoryBlock::ToolUse { name, input, .. }
+                    | HistoryBlock::ServerToolUse { name, input, .. } => {
+                        parts.push(format!("[used {}]", describe_tool(name, input)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    (!parts.is_empty()).then(|| parts.join("\n"))
+}
+
+fn join_text(blocks: &[HistoryBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|b| match b {
+            HistoryBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `Name target` for a tool call — its path or command when it has one.
+fn describe_tool(name: &str, input: &Value) -> String {
+    if let Some(path) = touched_paths(name, input).into_iter().next() {
+        return format!("{name} {path}");
+    }
+    let command = match input.get("command").or_else(|| input.get("cmd")) {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Array(parts)) => Some(
+            parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    };
+    match command {
+        Some(c) if !c.trim().is_empty() => format!("{name} `{}`", truncate(c.trim(), 120)),
+        _ => name.to_string(),
+    }
+}
+
+fn is_modifying_tool(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["write", "edit", "patch", "create", "delete", "replace"]
+        .iter()
+        .any(|k| name.contains(k))
+}
+
+fn is_reading_tool(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["read", "view", "open"].iter().any(|k| name.contains(k))
+}
+
+/// File paths a tool call names: the path-like input fields every agent
+/// uses (`file_path`, `path`, `filePath`, `notebook_path`), plus the file
+/// headers of a Codex `apply_patch` body.
+fn touched_paths(name: &str, input: &Value) -> Vec<String> {
+    let mut paths: Vec<String> = ["file_path", "path", "filePath", "notebook_path"]
+        .iter()
+        .filter_map(|k| input.get(*k).and_then(Value::as_str))
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| p.trim().to_string())
+        .take(1)
+        .collect();
+    if let Some(patch) = patch_body(name, input) {
+        paths.extend(patch.lines().filter_map(|l| {
+            ["*** Add File: ", "*** Update File: ", "*** Delete File: "]
+                .iter()
+                .find_map(|prefix| l.strip_prefix(prefix))
+                .map(|p| p.trim().to_string())
+        }));
+    }
+    paths
+}
+
+/// The body of a Codex `apply_patch` call — a `custom_tool_call` whose input
+/// is the patch, or (older rollouts) the shell tool running
+/// `["apply_patch", "<patch>"]`.
+fn patch_body<'a>(name: &str, input: &'a Value) -> Option<&'a str> {
+    match input {
+        Value::String(s) if name == "apply_patch" => Some(s.as_str()),
+        _ => input
+            .get("command")
+            .and_then(Value::as_array)
+            .filter(|c| c.first().and_then(Value::as_str) == Some("apply_patch"))
+            .and_then(|c| c.get(1))
+            .and_then(Value::as_str),
+    }
+}
+
+/// Unfinished items when `input` is a todo/plan list: Claude/OpenCode
+/// `todos: [{content, status}]` or Codex `update_plan`'s
+/// `plan: [{step, status}]`. `None` when the call carries no such list.
+fn open_todos(input: &Value) -> Option<Vec<String>> {
+    let items = input
+        .get("todos")
+        .or_else(|| input.get("plan"))
+        .and_then(Value::as_array)?;
+    Some(
+        items
+            .iter()
+            .filter(|item| {
+                !matches!(
+                    item.get("status").and_then(Value::as_str),
+                    Some("completed" | "cancelled")
+                )
+            })
+            .filter_map(|item| {
+                item.get("content")
+                    .or_else(|| item.get("step"))
+                    .and_then(Value::as_str)
+            })
+            .map(|t| truncate(t.trim(), MAX_LINE_CHARS))
+            .filter(|t| !t.is_empty())
+            .collect(),
+    )
+}
+
+/// First paragraph of `text`, on one line, capped at [`MAX_LINE_CHARS`].
+fn gist(text: &str) -> String {
+    let para = text
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty())
+        .unwrap_or_default();
+    let line = para.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate(&line, MAX_LINE_CHARS)
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+fn skip_to_last<T>(items: &mut Vec<T>, n: usize) {
+    if items.len() > n {
+        items.drain(..items.len() - n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn msg(role: MessageRole, content: HistoryContent) -> HistoryMessage {
+        HistoryMessage {
+            role,
+            uuid: String::new(),
+            session_id: "s1".into(),
+            parent_tool_use_id: None,
+            content,
+        }
+    }
+
+    fn tool(name: &str, input: Value) -> HistoryBlock {
+        HistoryBlock::ToolUse {
+            id: format!("call-{name}"),
+            name: name.into(),
+            input,
+        }
+    }
+
+    fn text(t: &str) -> HistoryBlock {
+        HistoryBlock::Text { text: t.into() }
+    }
+
+    fn claude_session() -> Handoff {
+        let source: HandoffSource = "claude:abc-123".parse().unwrap();
+        Handoff::from_messages(
+            source,
+            vec![
+                msg(
+                    MessageRole::User,
+                    HistoryContent::Text("Add retries to the fetcher".into()),
+                ),
+                msg(
+                    MessageRole::Assistant,
+                    HistoryContent::Blocks(vec![
+                        HistoryBlock::Thinking {
+                            text: "hmm".into(),
+                        },
+                        text("I'll use exponential backoff rather than a fixed delay.\n\nDetails follow."),
+                        tool("Read", json!({"file_path": "src/fetch.rs"})),
+                        tool("Read", json!({"file_path": "src/lib.rs"})),
+                    ]),
+                ),
+                msg(
+                    MessageRole::Tool,
+                    HistoryContent::Blocks(vec![HistoryBlock::ToolResult {
+                        tool_use_id: "call-Read".into(),
+                        content: json!("fn fetch() {}"),
+                        is_error: false,
+                    }]),
+                ),
+                msg(
+                    MessageRole::Assistant,
+                    HistoryContent::Blocks(vec![
+                        tool("Edit", json!({"file_path": "src/fetch.rs", "old_string": "a"})),
+                        tool(
+                            "TodoWrite",
+                            json!({"todos": [
+                                {"content": "Add backoff", "status": "completed"},
+                                {"content": "Write tests", "status": "in_progress"},
+                                {"content": "Update docs", "status": "pending"}
+                            ]}),
+                        ),
+                    ]),
+                ),
+                msg(
+                    MessageRole::User,
+                    HistoryContent::Text("<system-reminder>injected</system-reminder>".into()),
+                ),
+                msg(
+                    MessageRole::User,
+                    HistoryContent::Text("Also cap it at 5 attempts".into()),
+                ),
+                msg(
+                    MessageRole::Assistant,
+                    HistoryContent::Blocks(vec![text("Capped at 5; tests are next.")]),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn source_parses_backend_and_id() {
+        let s: HandoffSource = "codex:0199a213-81c0-7800-8aa1-bbab2a035a53"
+            .parse()
+            .unwrap();
+        assert_eq!(s.backend, Backend::Codex);
+        assert_eq!(s.session_id, "0199a213-81c0-7800-8aa1-bbab2a035a53");
+        assert_eq!(s.to_string(), "codex:0199a213-81c0-7800-8aa1-bbab2a035a53");
+
+        for bad in ["claude", "nope:abc", "claude:", " : "] {
+            assert!(
+                matches!(
+                    bad.parse::<HandoffSource>(),
+                    Err(HandoffError::InvalidSource { .. })
+                ),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn digest_collects_files_decisions_and_todos() {
+        let d = claude_session().digest();
+        assert_eq!(
+            d.original_request.as_deref(),
+            Some("Add retries to the fetcher")
+        );
+        assert_eq!(d.follow_ups, vec!["Also cap it at 5 attempts"]);
+        assert_eq!(d.files_modified, vec!["src/fetch.rs"]);
+        assert_eq!(d.files_read, vec!["src/lib.rs"]);
+        assert_eq!(
+            d.decisions,
+            vec!["I'll use exponential backoff rather than a fixed delay."]
+        );
+        assert_eq!(d.open_todos, vec!["Write tests", "Update docs"]);
+        assert_eq!(
+            d.last_reply.as_deref(),
+            Some("Capped at 5; tests are next.")
+        );
+    }
+
+    #[test]
+    fn digest_reads_codex_apply_patch_and_update_plan() {
+        let patch = "*** Begin Patch\n*** Update File: src/a.rs\n@@\n-x\n+y\n*** Add File: src/b.rs\n+z\n*** End Patch";
+        let handoff = Handoff::from_messages(
+            "codex:s1".parse().unwrap(),
+            vec![msg(
+                MessageRole::Assistant,
+                HistoryContent::Blocks(vec![
+                    tool("apply_patch", Value::String(patch.into())),
+                    tool(
+                        "shell",
+                        json!({"command": ["apply_patch", "*** Delete File: old.rs"]}),
+                    ),
+                    tool(
+                        "update_plan",
+                        json!({"plan": [{"step": "Ship it", "status": "pending"}]}),
+                    ),
+                ]),
+            )],
+        );
+        let d = handoff.digest();
+        assert_eq!(d.files_modified, vec!["src/a.rs", "src/b.rs", "old.rs"]);
+        assert_eq!(d.open_todos, vec!["Ship it"]);
+    }
+
+    #[test]
+    fn prompt_renders_sections_and_next_instruction() {
+        let prompt = claude_session().prompt("finish the tests");
+        assert!(prompt.contains("claude session abc-123"));
+        assert!(prompt.contains("## Original request\nAdd retries to the fetcher"));
+        assert!(prompt.contains("- modified: src/fetch.rs"));
+        assert!(prompt.contains("- read: src/lib.rs"));
+        assert!(prompt.contains("- [ ] Write tests"));
+        assert!(prompt.contains("## Where it stopped\nCapped at 5; tests are next."));
+        assert!(prompt.ends_with("## Next\nfinish the tests\n"));
+        assert!(
+            !prompt.contains("hmm"),
+            "thinking must not leak into the prompt"
+        );
+
+        let empty = Handoff::from_messages("pi:x".parse().unwrap(), vec![]).prompt("  ");
+        assert!(empty.ends_with("## Next\nContinue the task.\n"));
+    }
+
+    #[test]
+    fn session_turns_alternate_and_fold_tool_calls() {
+        let turns = claude_session().session_turns();
+        let roles: Vec<&str> = turns.iter().map(|t| t.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user", "assistant"]);
+        assert!(turns[1].content.contains("exponential backoff"));
+        assert!(turns[1].content.contains("[used Edit src/fetch.rs]"));
+        assert!(turns
+            .iter()
+            .all(|t| t.tool_calls.is_none() && t.tool_results.is_none()));
+    }
+
+    #[test]
+    fn subagent_messages_are_skipped() {
+        let mut side = msg(
+            MessageRole::Assistant,
+            HistoryContent::Blocks(vec![tool("Write", json!({"file_path": "scratch.txt"}))]),
+        );
+        side.parent_tool_use_id = Some("task-1".into());
+        let handoff = Handoff::from_messages("claude:s".parse().unwrap(), vec![side]);
+        assert!(handoff.digest().files_modified.is_empty());
+        assert!(handoff.session_turns().is_empty());
+    }
+
+    #[test]
+    fn seed_in_saves_a_resumable_aikit_session() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = SessionStore {
+            sessions_dir: tmp.path().to_path_buf(),
+        };
+        let seed = claude_session()
+            .seed_in(&store, "keep going", Path::new("/work"))
+            .unwrap();
+        assert_eq!(seed.prompt, "keep going");
+        let saved = store.load(seed.session_id.as_deref().unwrap()).unwrap();
+        assert_eq!(saved.agent, "aikit");
+        assert_eq!(saved.cwd, "/work");
+        assert_eq!(saved.turns.len(), 4);
+        assert_eq!(
+            saved.title.as_deref(),
+            Some("Continued from claude:abc-123")
+        );
+    }
+
+    #[test]
+    fn seed_for_external_target_prefixes_prompt() {
+        let seed = claude_session()
+            .seed(Backend::Codex, "go", Path::new("/work"))
+            .unwrap();
+        assert!(seed.session_id.is_none());
+        assert!(seed.prompt.starts_with("You are taking over a task"));
+        assert!(seed.prompt.ends_with("## Next\ngo\n"));
+    }
+
+    #[test]
+    fn load_with_reads_through_a_history_reader() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = SessionStore {
+            sessions_dir: tmp.path().to_path_buf(),
+        };
+        let seed = claude_session()
+            .seed_in(&store, "x", Path::new("/w"))
+            .unwrap();
+        let reader =
+            crate::history::aikit::AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+        let id = seed.session_id.unwrap();
+        let loaded = Handoff::load_with(&reader, &id).unwrap();
+        assert_eq!(loaded.source().backend, Backend::Aikit);
+        assert_eq!(loaded.session_turns().len(), 4);
+
+        let missing = Handoff::load_with(&reader, "does-not-exist").unwrap_err();
+        assert!(matches!(
+            missing,
+            HandoffError::History(HistoryError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn load_rejects_backend_without_history() {
+        let err = Handoff::load("gemini:abc".parse().unwrap()).unwrap_err();
+        assert!(matches!(
+            err,
+            HandoffError::Unsupported {
+                backend: Backend::Gemini
+            }
+        ));
+    }
+}
@@ -0,0 +1,418 @@
+//! `HistoryReader`/`HistoryMutator` for the built-in aikit agent — spec 008.
+//!
+//! Reads the same [`SessionStore`] the agent resumes from
+//! (`$AIKIT_SESSIONS_DIR` or `~/.aikit/sessions/<id>.json`). The store owns
+//! its file format, so rename/tag are plain fields on [`SessionFile`],
+//! written back through the store's atomic `save`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::runner::backend::Backend;
+use crate::runner::types::MessageRole;
+use crate::session_store::{SessionFile, SessionStore, SessionStoreError, SessionTurn};
+
+use super::common::{
+    display_summary, io_error, mtime_ms, page, parse_rfc3339_ms, select_sessions, validate_safe_id,
+};
+use super::types::{
+    HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryQuery, HistorySession,
+    MessagesQuery,
+};
+use super::{HistoryMutator, HistoryReader};
+
+/// Reads one session directory. [`AikitHistory::new`] resolves the
+/// directory [`SessionStore::open`] uses without creating it; tests point
+/// [`AikitHistory::with_sessions_dir`] at a temp dir instead.
+#[derive(Debug, Clone)]
+pub(crate) struct AikitHistory {
+    sessions_dir: PathBuf,
+}
+
+impl AikitHistory {
+    pub(crate) fn new() -> Self {
+        Self::with_sessions_dir(SessionStore::default_dir())
+    }
+
+    pub(crate) fn with_sessions_dir(sessions_dir: PathBuf) -> Self {
+        Self { sessions_dir }
+    }
+
+    fn store(&self) -> SessionStore {
+        SessionStore {
+            sessions_dir: self.sessions_dir.clone(),
+        }
+    }
+
+    fn session_path(&self, id: &str) -> PathBuf {
+        self.sessions_dir.join(format!("{id}.json"))
+    }
+
+    /// Load a session, mapping the store's errors onto the canonical ones
+    /// and treating a `cwd` mismatch as absent (`Ok(None)`).
+    fn load(&self, id: &str, cwd: Option<&Path>) -> Result<Option<SessionFile>, HistoryError> {
+        validate_safe_id(id)?;
+        match self.store().load(id) {
+            Ok(file) if cwd.map_or(true, |c| Path::new(&file.cwd) == c) => Ok(Some(file)),
+            Ok(_) | Err(SessionStoreError::NotFound(_)) => Ok(None),
+            Err(SessionStoreError::Io(e)) => Err(io_error(e, &self.session_path(id))),
+            Err(SessionStoreError::Parse { id, reason }) => Err(HistoryError::Decode {
+                message: format!("session '{id}': {reason}"),
+            }),
+        }
+    }
+
+    fn load_existing(&self, id: &str, cwd: Option<&Path>) -> Result<SessionFile, HistoryError> {
+        self.load(id, cwd)?.ok_or_else(|| HistoryError::NotFound {
+            session_id: id.to_string(),
+        })
+    }
+
+    fn save(&self, file: &SessionFile) -> Result<(), HistoryError> {
+        self.store().save(file).map_err(|e| match e {
+            SessionStoreError::Io(e) => io_error(e, &self.session_path(&file.session_id)),
+            other => HistoryError::Store {
+                message: format!("{other:?}"),
+            },
+        })
+    }
+
+    fn to_session(&self, file: &SessionFile) -> HistorySession {
+        let path = self.session_path(&file.session_id);
+        let first_prompt = file
+            .turns
+            .iter()
+            .find(|t| t.role == "user")
+            .map(|t| t.content.clone());
+        HistorySession {
+            backend: Backend::Aikit,
+            session_id: file.session_id.clone(),
+            summary: display_summary(
+                file.title.as_deref(),
+                first_prompt.as_deref(),
+                &file.session_id,
+            ),
+            first_prompt,
+            custom_title: file.title.clone(),
+            tag: file.tag.clone(),
+            cwd: (!file.cwd.is_empty()).then(|| PathBuf::from(&file.cwd)),
+            git_branch: None,
+            last_modified_ms: parse_rfc3339_ms(&file.updated_at)
+                .or_else(|| mtime_ms(&path))
+                .unwrap_or(0),
+            created_at_ms: parse_rfc3339_ms(&file.created_at),
+            message_count: Some(file.turns.len() as u64),
+            size_bytes: std::fs::metadata(&path).ok().map(|m| m.len()),
+        }
+    }
+}
+
+impl HistoryReader for AikitHistory {
+    fn backend(&self) -> Backend {
+        Backend::Aikit
+    }
+
+    fn list(&self, q: &HistoryQuery) -> Result<Vec<HistorySession>, HistoryError> {
+        let entries = match std::fs::read_dir(&self.sessions_dir) {
+            Ok(entries) => entries,
+            // No directory yet: the agent has never saved a session.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(e, &self.sessions_dir)),
+        };
+        let store = self.store();
+        let sessions = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                // `<id>.json` only — skips `index.json` and in-flight
+                // `<id>.json.tmp` files from a concurrent save.
+                let name = entry.file_name().into_string().ok()?;
+                let id = name.strip_suffix(".json")?;
+                if id == "index" {
+                    return None;
+                }
+                store.load(id).ok()
+            })
+            .map(|file| self.to_session(&file))
+            .collect();
+        Ok(select_sessions(sessions, q))
+    }
+
+    fn info(&self, id: &str, cwd: Option<&Path>) -> Result<Option<HistorySession>, HistoryError> {
+        Ok(self.load(id, cwd)?.map(|file| self.to_session(&file)))
+    }
+
+    fn messages(
+        &self,
+        id: &str,
+        q: &MessagesQuery,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<HistoryMessage>, HistoryError> {
+        let file = self.load_existing(id, cwd)?;
+        let messages = file
+            .turns
+            .iter()
+            .enumerate()
+            .map(|(i, turn)| to_history_message(&file.session_id, i, turn))
+            .collect();
+        Ok(page(messages, q.limit, q.offset))
+    }
+}
+
+impl HistoryMutator for AikitHistory {
+    fn rename(&self, id: &str, title: &str, cwd: Option<&Path>) -> Result<(), HistoryError> {
+        let title = non_empty(title, "title")?;
+        let mut file = self.load_existing(id, cwd)?;
+        if file.title.as_deref() == Some(title) {
+            return Ok(());
+        }
+        file.title = Some(title.to_string());
+        self.save(&file)
+    }
+
+    fn tag(&self, id: &str, tag: Option<&str>, cwd: Option<&Path>) -> Result<(), HistoryError> {
+        let tag = tag.map(|t| non_empty(t, "tag")).transpose()?;
+        let mut file = self.load_existing(id, cwd)?;
+        if file.tag.as_deref() == tag {
+            return Ok(());
+        }
+        file.tag = tag.map(str::to_string);
+        self.save(&file)
+    }
+}
+
+/// Empty titles/tags are rejected as `InvalidInput`, the same way the Claude
+/// adapter's SDK rejects them — clearing is `tag(None)`, not `tag(Some(""))`.
+fn non_empty<'a>(value: &'a str, what: &str) -> Result<&'a str, HistoryError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        Err(HistoryError::Io {
+            source: std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{what} must not be empty"),
+            ),
+            path: None,
+        })
+    } else {
+        Ok(trimmed)
+    }
+}
+
+/// Turns carry no id; the 0-based turn index is stable because the agent
+/// only ever appends turns.
+fn to_history_message(session_id: &str, index: usize, turn: &SessionTurn) -> HistoryMessage {
+    let (role, content) = match turn.role.as_str() {
+        "user" => (
+            MessageRole::User,
+            HistoryContent::Text(turn.content.clone()),
+        ),
+        "assistant" => {
+            let mut blocks = Vec::new();
+            if !turn.content.is_empty() {
+                blocks.push(HistoryBlock::Text {
+                    text: turn.content.clone(),
+                });
+            }
+            for call in turn.tool_calls.iter().flatten() {
+                // `input` is the model's JSON-encoded argument string; keep
+                // it verbatim when it doesn't parse.
+                let input = serde_json::from_str(&call.input)
+                    .unwrap_or_else(|_| Value::String(call.input.clone()));
+                blocks.push(HistoryBlock::ToolUse {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    input,
+                });
+            }
+            (MessageRole::Assistant, HistoryContent::Blocks(blocks))
+        }
+        "tool" => {
+            let blocks = turn
+                .tool_results
+                .iter()
+                .flatten()
+                .map(|r| HistoryBlock::ToolResult {
+                    tool_use_id: r.tool_call_id.clone(),
+                    content: Value::String(r.output.clone()),
+                    is_error: false,
+                })
+                .collect();
+            (MessageRole::Tool, HistoryContent::Blocks(blocks))
+        }
+        _ => (
+            MessageRole::System,
+            HistoryContent::Text(turn.content.clone()),
+        ),
+    };
+    HistoryMessage {
+        role,
+        uuid: index.to_string(),
+        session_id: session_id.to_string(),
+        parent_tool_use_id: None,
+        content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_store::{SessionToolCall, SessionToolResult};
+
+    const ID: &str = "7d9c2f0e-1b3a-4c5d-8e6f-0a1b2c3d4e5f";
+
+    fn turn(role: &str, content: &str) -> SessionTurn {
+        SessionTurn {
+            role: role.to_string(),
+            content: content.to_string(),
+            tool_calls: None,
+            tool_results: None,
+        }
+    }
+
+    fn write_fixture(dir: &Path, id: &str, cwd: &str) {
+        let mut assistant = turn("assistant", "");
+        assistant.tool_calls = Some(vec![SessionToolCall {
+            id: "call_1".to_string(),
+            name: "read_file".to_string(),
+            input: r#"{"path":"a.rs"}"#.to_string(),
+        }]);
+        let mut tool = turn("tool", "");
+        tool.tool_results = Some(vec![SessionToolResult {
+            tool_call_id: "call_1".to_string(),
+            name: "read_file".to_string(),
+            output: "fn main() {}".to_string(),
+        }]);
+        let file = SessionFile {
+            session_id: id.to_string(),
+            agent: "aikit".to_string(),
+            created_at: "2026-01-15T10:00:00Z".to_string(),
+            updated_at: "2026-01-15T10:05:00Z".to_string(),
+            cwd: cwd.to_string(),
+            turns: vec![
+                turn("user", "what is in a.rs?"),
+                assistant,
+                tool,
+                turn("assistant", "An empty main."),
+            ],
+            title: None,
+            tag: None,
+        };
+        SessionStore {
+            sessions_dir: dir.to_path_buf(),
+        }
+        .save(&file)
+        .unwrap();
+    }
+
+    #[test]
+    fn list_skips_index_and_reports_metadata() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_fixture(tmp.path(), ID, "/tmp/proj");
+        std::fs::write(tmp.path().join("index.json"), r#"{"/tmp/proj": "x"}"#).unwrap();
+        let reader = AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let sessions = reader.list(&HistoryQuery::default()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.backend, Backend::Aikit);
+        assert_eq!(s.summary, "what is in a.rs?");
+        assert_eq!(s.created_at_ms, Some(1_768_471_200_000));
+        assert_eq!(s.last_modified_ms, 1_768_471_500_000);
+        assert_eq!(s.message_count, Some(4));
+    }
+
+    #[test]
+    fn list_on_missing_dir_is_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = AikitHistory::with_sessions_dir(tmp.path().join("absent"));
+        assert!(reader.list(&HistoryQuery::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn messages_map_turns_to_canonical_blocks() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_fixture(tmp.path(), ID, "/tmp/proj");
+        let reader = AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let msgs = reader
+            .messages(ID, &MessagesQuery::default(), None)
+            .unwrap();
+        assert_eq!(msgs.len(), 4);
+        assert_eq!(msgs[0].role, MessageRole::User);
+        match &msgs[1].content {
+            HistoryContent::Blocks(b) => {
+                assert_eq!(b.len(), 1, "empty assistant text adds no Text block");
+                assert!(matches!(&b[0], HistoryBlock::ToolUse { id, input, .. }
+                    if id == "call_1" && input["path"] == "a.rs"));
+            }
+            other => panic!("expected Blocks, got {other:?}"),
+        }
+        assert_eq!(msgs[2].role, MessageRole::Tool);
+        assert_eq!(msgs[3].uuid, "3");
+    }
+
+    #[test]
+    fn not_found_invalid_and_cwd_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_fixture(tmp.path(), ID, "/tmp/proj");
+        let reader = AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+        assert!(matches!(
+            reader.messages("../etc/passwd", &MessagesQuery::default(), None),
+            Err(HistoryError::InvalidId { .. })
+        ));
+        assert!(matches!(
+            reader.messages("missing", &MessagesQuery::default(), None),
+            Err(HistoryError::NotFound { .. })
+        ));
+        assert!(reader
+            .info(ID, Some(Path::new("/tmp/elsewhere")))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rename_and_tag_round_trip_and_survive_reload() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_fixture(tmp.path(), ID, "/tmp/proj");
+        let history = AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        history.rename(ID, "Read a.rs", None).unwrap();
+        history.tag(ID, Some("triage"), None).unwrap();
+        let info = history.info(ID, None).unwrap().unwrap();
+        assert_eq!(info.custom_title.as_deref(), Some("Read a.rs"));
+        assert_eq!(info.summary, "Read a.rs");
+        assert_eq!(info.tag.as_deref(), Some("triage"));
+
+        let q = HistoryQuery {
+            tag: Some("triage".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(history.list(&q).unwrap().len(), 1);
+
+        history.tag(ID, None, None).unwrap();
+        assert_eq!(history.info(ID, None).unwrap().unwrap().tag, None);
+        // The resume path reads the same file: turns are untouched.
+        let reloaded = history.store().load(ID).unwrap();
+        assert_eq!(reloaded.turns.len(), 4);
+        assert_eq!(reloaded.title.as_deref(), Some("Read a.rs"));
+    }
+
+    #[test]
+    fn mutations_reject_empty_values_and_missing_sessions() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_fixture(tmp.path(), ID, "/tmp/proj");
+        let history = AikitHistory::with_sessions_dir(tmp.path().to_path_buf());
+        assert!(matches!(
+            history.rename(ID, "  ", None),
+            Err(HistoryError::Io { .. })
+        ));
+        assert!(matches!(
+            history.tag(ID, Some(""), None),
+            Err(HistoryError::Io { .. })
+        ));
+        assert!(matches!(
+            history.rename("missing", "x", None),
+            Err(HistoryError::NotFound { .. })
+        ));
+    }
+}
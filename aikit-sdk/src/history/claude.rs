@@ -12,6 +12,7 @@ use serde_json::Value;
 use crate::runner::backend::Backend;
 use crate::runner::types::MessageRole;
 
+use super::common::{effective_limit, page};
 use super::types::{
     HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryQuery, HistorySession,
     MessagesQuery,
@@ -25,28 +26,6 @@ use super::{HistoryMutator, HistoryReader};
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct ClaudeHistory;
 
-/// Applied when `HistoryQuery::limit`/`MessagesQuery::limit` is `None` or
-/// `Some(0)` (spec 008 §8 contract: "0/absent → default 100").
-const DEFAULT_LIMIT: usize = 100;
-/// Caps an oversized requested limit (spec 008 §8 contract: "oversized →
-/// capped").
-const MAX_LIMIT: usize = 1000;
-
-fn effective_limit(limit: Option<usize>) -> usize {
-    match limit {
-        None | Some(0) => DEFAULT_LIMIT,
-        Some(l) => l.min(MAX_LIMIT),
-    }
-}
-
-/// Apply `effective_limit`/`offset` to an already-fetched, unpaged `Vec` —
-/// used only on the adapter-side tag-filter path (spec 008 §7), where the
-/// SDK's own paging can't be used because it runs before the tag filter.
-fn page<T>(items: Vec<T>, limit: Option<usize>, offset: usize) -> Vec<T> {
-    let lim = effective_limit(limit);
-    items.into_iter().skip(offset).take(lim).collect()
-}
-
 fn validate_session_id(id: &str) -> Result<(), HistoryError> {
     if claude_agent_sdk::session::paths::validate_uuid(id).is_some() {
         Ok(())
@@ -296,16 +275,6 @@ fn server_tool_name(name: &claude_agent_sdk::ServerToolName) -> String {
 mod tests {
     use super::*;
 
-    // ── effective_limit / page ─────────────────────────────────────────
-
-    #[test]
-    fn effective_limit_defaults_and_caps() {
-        assert_eq!(effective_limit(None), DEFAULT_LIMIT);
-        assert_eq!(effective_limit(Some(0)), DEFAULT_LIMIT);
-        assert_eq!(effective_limit(Some(5)), 5);
-        assert_eq!(effective_limit(Some(999_999)), MAX_LIMIT);
-    }
-
     // ── validate_session_id ─────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,598 @@
+//! `HistoryReader` for the Codex backend — spec 008.
+//!
+//! Codex persists every session as an append-only "rollout" JSONL file under
+//! `$CODEX_HOME/sessions/YYYY/MM/DD/rollout-<timestamp>-<uuid>.jsonl`
+//! (`CODEX_HOME` defaults to `~/.codex`). Each line is
+//! `{"timestamp", "type", "payload"}`; this adapter reads the `session_meta`
+//! header and the `response_item` lines, which carry the model-facing
+//! transcript. `event_msg` lines are UI-stream duplicates of the same turns
+//! and `turn_context` lines are per-turn settings, so both are skipped.
+//!
+//! Read-only: Codex has no rename/tag metadata in its rollout format, so
+//! there is no `HistoryMutator` and `history_mutations` stays `false`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::runner::backend::Backend;
+use crate::runner::types::MessageRole;
+
+use super::common::{
+    display_summary, io_error, mtime_ms, page, parse_rfc3339_ms, read_jsonl, select_sessions,
+    validate_uuid,
+};
+use super::types::{
+    HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryQuery, HistorySession,
+    MessagesQuery,
+};
+use super::HistoryReader;
+
+/// Prefixes of the `user`-role messages Codex injects on its own (workspace
+/// context, AGENTS.md instructions) rather than ones the user typed. They
+/// classify as [`MessageRole::System`] and never become the first prompt.
+const INJECTED_USER_PREFIXES: &[&str] = &[
+    "<environment_context>",
+    "<user_instructions>",
+    "# AGENTS.md instructions",
+];
+
+/// Reads rollouts from one `sessions` directory. [`CodexHistory::new`]
+/// resolves the same directory the Codex CLI writes to; tests point
+/// [`CodexHistory::with_sessions_dir`] at a fixture tree instead.
+#[derive(Debug, Clone)]
+pub(crate) struct CodexHistory {
+    sessions_dir: PathBuf,
+}
+
+impl CodexHistory {
+    pub(crate) fn new() -> Self {
+        let home = std::env::var_os("CODEX_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join(".codex")
+            });
+        Self::with_sessions_dir(home.join("sessions"))
+    }
+
+    pub(crate) fn with_sessions_dir(sessions_dir: PathBuf) -> Self {
+        Self { sessions_dir }
+    }
+
+    /// Every rollout file under the sessions tree, with the session id taken
+    /// from its file name.
+    fn rollouts(&self) -> Vec<(String, PathBuf)> {
+        walkdir::WalkDir::new(&self.sessions_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let id = rollout_session_id(e.path())?;
+                Some((id, e.into_path()))
+            })
+            .collect()
+    }
+
+    fn find_rollout(&self, id: &str) -> Option<PathBuf> {
+        self.rollouts()
+            .into_iter()
+            .find(|(rid, _)| rid.eq_ignore_ascii_case(id))
+            .map(|(_, path)| path)
+    }
+}
+
+/// `rollout-2026-01-15T10-00-00-<uuid>.jsonl` → `<uuid>`.
+fn rollout_session_id(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
+    let id = stem.get(stem.len().checked_sub(36)?..)?;
+    uuid::Uuid::parse_str(id).ok()?;
+    Some(id.to_string())
+}
+
+impl HistoryReader for CodexHistory {
+    fn backend(&self) -> Backend {
+        Backend::Codex
+    }
+
+    fn list(&self, q: &HistoryQuery) -> Result<Vec<HistorySession>, HistoryError> {
+        let mut sessions = Vec::new();
+        for (id, path) in self.rollouts() {
+            // A rollout that vanishes or turns unreadable between the walk
+            // and the read is skipped rather than failing the whole listing.
+            if let Ok(rollout) = read_rollout(&id, &path) {
+                sessions.push(rollout.session);
+            }
+        }
+        Ok(select_sessions(sessions, q))
+    }
+
+    fn info(&self, id: &str, cwd: Option<&Path>) -> Result<Option<HistorySession>, HistoryError> {
+        validate_uuid(id)?;
+        let Some(path) = self.find_rollout(id) else {
+            return Ok(None);
+        };
+        let session = read_rollout(id, &path)?.session;
+        Ok(cwd_matches(&session, cwd).then_some(session))
+    }
+
+    fn messages(
+        &self,
+        id: &str,
+        q: &MessagesQuery,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<HistoryMessage>, HistoryError> {
+        validate_uuid(id)?;
+        let not_found = || HistoryError::NotFound {
+            session_id: id.to_string(),
+        };
+        let path = self.find_rollout(id).ok_or_else(not_found)?;
+        let rollout = read_rollout(id, &path)?;
+        if !cwd_matches(&rollout.session, cwd) {
+            return Err(not_found());
+        }
+        Ok(page(rollout.messages, q.limit, q.offset))
+    }
+}
+
+/// Rollouts are keyed by UUID alone, so `cwd` only narrows: a session that
+/// ran elsewhere is reported as absent, matching the Claude adapter where
+/// `cwd` selects the project directory searched.
+fn cwd_matches(session: &HistorySession, cwd: Option<&Path>) -> bool {
+    cwd.map_or(true, |c| session.cwd.as_deref() == Some(c))
+}
+
+struct Rollout {
+    session: HistorySession,
+    messages: Vec<HistoryMessage>,
+}
+
+fn read_rollout(id: &str, path: &Path) -> Result<Rollout, HistoryError> {
+    let lines = read_jsonl(path)?;
+    let size_bytes = std::fs::metadata(path)
+        .map_err(|e| io_error(e, path))?
+        .len();
+
+    let mut cwd = None;
+    let mut git_branch = None;
+    let mut created_at_ms = None;
+    let mut last_line_ms = None;
+    let mut messages = Vec::new();
+    for (line_no, line) in &lines {
+        let payload = line.get("payload").unwrap_or(&Value::Null);
+        if let Some(ms) = line
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(parse_rfc3339_ms)
+        {
+            last_line_ms = Some(ms);
+        }
+        match line.get("type").and_then(Value::as_str) {
+            Some("session_meta") => {
+                cwd = str_field(payload, "cwd").map(PathBuf::from);
+                git_branch = payload
+                    .get("git")
+                    .and_then(|g| str_field(g, "branch"))
+                    .map(str::to_string);
+                created_at_ms = str_field(payload, "timestamp").and_then(parse_rfc3339_ms);
+            }
+            Some("response_item") => {
+                if let Some((role, content)) = map_response_item(payload) {
+                    messages.push(message(id, *line_no, role, content));
+                }
+            }
+            Some("compacted") => {
+                let text = str_field(payload, "message").unwrap_or_default();
+                messages.push(message(
+                    id,
+                    *line_no,
+                    MessageRole::System,
+                    HistoryContent::Text(text.to_string()),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let first_prompt = messages.iter().find_map(|m| match (&m.role, &m.content) {
+        (MessageRole::User, HistoryContent::Text(text)) => Some(text.clone()),
+        _ => None,
+    });
+    let session = HistorySession {
+        backend: Backend::Codex,
+        session_id: id.to_string(),
+        summary: display_summary(None, first_prompt.as_deref(), id),
+        first_prompt,
+        custom_title: None,
+        tag: None,
+        cwd,
+        git_branch,
+        last_modified_ms: mtime_ms(path).or(last_line_ms).unwrap_or(0),
+        created_at_ms,
+        message_count: Some(messages.len() as u64),
+        size_bytes: Some(size_bytes),
+    };
+    Ok(Rollout { session, messages })
+}
+
+/// Rollout lines carry no per-entry id; the 1-based line number is stable
+/// because rollouts are append-only.
+fn message(id: &str, line_no: usize, role: MessageRole, content: HistoryContent) -> HistoryMessage {
+    HistoryMessage {
+        role,
+        uuid: line_no.to_string(),
+        session_id: id.to_string(),
+        parent_tool_use_id: None,
+        content,
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Map one `response_item` payload to a role and canonical content. `None`
+/// only for reasoning items with no readable summary (encrypted reasoning
+/// has nothing to render); unrecognized item types pass through as
+/// [`HistoryContent::Raw`] under [`MessageRole::System`].
+fn map_response_item(payload: &Value) -> Option<(MessageRole, HistoryContent)> {
+    let call_id = || {
+        str_field(payload, "call_id")
+            .unwrap_or_default()
+            .to_string()
+    };
+    match str_field(payload, "type").unwrap_or_default() {
+        "message" => Some(map_message(payload)),
+        "reasoning" => {
+            let text = payload
+                .get("summary")
+                .and_then(Value::as_array)
+                .map(|parts| {
+                    parts
+                        .iter()
+                        .filter_map(|p| str_field(p, "text"))
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                })
+                .unwrap_or_default();
+            (!text.is_empty()).then(|| {
+                (
+                    MessageRole::Assistant,
+                    HistoryContent::Blocks(vec![HistoryBlock::Thinking { text }]),
+                )
+            })
+        }
+        "function_call" => {
+            // `arguments` is a JSON-encoded string; keep it verbatim when it
+            // doesn't parse so the call still renders.
+            let arguments = str_field(payload, "arguments").unwrap_or_default();
+            let input = serde_json::from_str(arguments)
+                .unwrap_or_else(|_| Value::String(arguments.to_string()));
+            Some(tool_use(call_id(), str_field(payload, "name"), input))
+        }
+        "custom_tool_call" => {
+            let input = payload.get("input").cloned().unwrap_or(Value::Null);
+            Some(tool_use(call_id(), str_field(payload, "name"), input))
+        }
+        "local_shell_call" => {
+            let input = payload.get("action").cloned().unwrap_or(Value::Null);
+            Some(tool_use(call_id(), Some("local_shell"), input))
+        }
+        "function_call_output" | "custom_tool_call_output" | "local_shell_call_output" => {
+            let output = payload.get("output").cloned().unwrap_or(Value::Null);
+            let is_error = output_is_error(&output);
+            Some((
+                MessageRole::Tool,
+                HistoryContent::Blocks(vec![HistoryBlock::ToolResult {
+                    tool_use_id: call_id(),
+                    content: output,
+                    is_error,
+                }]),
+            ))
+        }
+        "web_search_call" => Some((
+            MessageRole::Assistant,
+            HistoryContent::Blocks(vec![HistoryBlock::ServerToolUse {
+                id: str_field(payload, "id").unwrap_or_default().to_string(),
+                name: "web_search".to_string(),
+                input: payload.get("action").cloned().unwrap_or(Value::Null),
+            }]),
+        )),
+        _ => Some((MessageRole::System, HistoryContent::Raw(payload.clone()))),
+    }
+}
+
+fn tool_use(id: String, name: Option<&str>, input: Value) -> (MessageRole, HistoryContent) {
+    (
+        MessageRole::Assistant,
+        HistoryContent::Blocks(vec![HistoryBlock::ToolUse {
+            id,
+            name: name.unwrap_or_default().to_string(),
+            input,
+        }]),
+    )
+}
+
+/// A `message` item: `content` is a list of `input_text`/`output_text`
+/// parts. User and developer text collapses to [`HistoryContent::Text`];
+/// assistant output keeps one block per part.
+fn map_message(payload: &Value) -> (MessageRole, HistoryContent) {
+    let parts = payload
+        .get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let blocks: Vec<HistoryBlock> = parts
+        .iter()
+        .map(|p| match str_field(p, "text") {
+            Some(text) => HistoryBlock::Text {
+                text: text.to_string(),
+            },
+            None => HistoryBlock::Raw(p.clone()),
+        })
+        .collect();
+    let all_text = blocks
+        .iter()
+        .all(|b| matches!(b, HistoryBlock::Text { .. }));
+    let joined = || {
+        blocks
+            .iter()
+            .filter_map(|b| match b {
+                HistoryBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    match str_field(payload, "role").unwrap_or_default() {
+        "assistant" => (MessageRole::Assistant, HistoryContent::Blocks(blocks)),
+        "user" if all_text => {
+            let text = joined();
+            let injected = INJECTED_USER_PREFIXES
+                .iter()
+                .any(|p| text.trim_start().starts_with(p));
+            let role = if injected {
+                MessageRole::System
+            } else {
+                MessageRole::User
+            };
+            (role, HistoryContent::Text(text))
+        }
+        "user" => (MessageRole::User, HistoryContent::Blocks(blocks)),
+        _ if all_text => (MessageRole::System, HistoryContent::Text(joined())),
+        _ => (MessageRole::System, HistoryContent::Blocks(blocks)),
+    }
+}
+
+/// Shell tool output is a JSON string carrying `metadata.exit_code`;
+/// structured outputs may carry `success: false`. Anything else is treated
+/// as a success — Codex records no explicit error flag.
+fn output_is_error(output: &Value) -> bool {
+    let parsed;
+    let value = match output {
+        Value::String(s) => match serde_json::from_str::<Value>(s) {
+            Ok(v) => {
+                parsed = v;
+                &parsed
+            }
+            Err(_) => return false,
+        },
+        other => other,
+    };
+    if value.get("success").and_then(Value::as_bool) == Some(false) {
+        return true;
+    }
+    value
+        .get("metadata")
+        .and_then(|m| m.get("exit_code"))
+        .and_then(Value::as_i64)
+        .is_some_and(|code| code != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "0199a7e2-5c1d-7f10-9a8b-2f4e6d8c0a11";
+
+    fn write_rollout(root: &Path, id: &str, lines: &[Value]) -> PathBuf {
+        let dir = root.join("2026").join("01").join("15");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("rollout-2026-01-15T10-00-00-{id}.jsonl"));
+        let body = lines
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        std::fs::write(&path, body).unwrap();
+        path
+    }
+
+    fn fixture_lines(id: &str, cwd: &str) -> Vec<Value> {
+        vec![
+            serde_json::json!({"timestamp": "2026-01-15T10:00:00Z", "type": "session_meta",
+                "payload": {"id": id, "timestamp": "2026-01-15T10:00:00Z", "cwd": cwd,
+                            "git": {"branch": "main"}}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:00Z", "type": "response_item",
+                "payload": {"type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "<environment_context>\n  <cwd>/tmp</cwd>\n</environment_context>"}]}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:01Z", "type": "response_item",
+                "payload": {"type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "list the files"}]}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:01Z", "type": "event_msg",
+                "payload": {"type": "user_message", "message": "list the files"}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:02Z", "type": "response_item",
+                "payload": {"type": "reasoning", "summary": [
+                    {"type": "summary_text", "text": "Run ls"}]}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:02Z", "type": "response_item",
+                "payload": {"type": "function_call", "name": "shell", "call_id": "call_1",
+                            "arguments": "{\"command\":[\"ls\"]}"}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:03Z", "type": "response_item",
+                "payload": {"type": "function_call_output", "call_id": "call_1",
+                            "output": "{\"output\":\"a.rs\\n\",\"metadata\":{\"exit_code\":0}}"}}),
+            serde_json::json!({"timestamp": "2026-01-15T10:00:04Z", "type": "response_item",
+                "payload": {"type": "message", "role": "assistant", "content": [
+                    {"type": "output_text", "text": "One file: a.rs"}]}}),
+        ]
+    }
+
+    #[test]
+    fn rollout_session_id_comes_from_file_name() {
+        let path = Path::new(&format!("/x/rollout-2026-01-15T10-00-00-{ID}.jsonl")).to_path_buf();
+        assert_eq!(rollout_session_id(&path).as_deref(), Some(ID));
+        assert_eq!(rollout_session_id(Path::new("/x/notes.jsonl")), None);
+        assert_eq!(
+            rollout_session_id(Path::new("/x/rollout-short.jsonl")),
+            None
+        );
+    }
+
+    #[test]
+    fn list_and_info_read_session_meta() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_rollout(tmp.path(), ID, &fixture_lines(ID, "/tmp/proj"));
+        let reader = CodexHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let sessions = reader.list(&HistoryQuery::default()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.backend, Backend::Codex);
+        assert_eq!(s.session_id, ID);
+        assert_eq!(s.summary, "list the files");
+        assert_eq!(s.first_prompt.as_deref(), Some("list the files"));
+        assert_eq!(s.cwd.as_deref(), Some(Path::new("/tmp/proj")));
+        assert_eq!(s.git_branch.as_deref(), Some("main"));
+        assert_eq!(s.created_at_ms, Some(1_768_471_200_000));
+        assert_eq!(s.message_count, Some(6));
+
+        let info = reader.info(ID, None).unwrap().expect("session exists");
+        assert_eq!(info.session_id, ID);
+        assert!(reader
+            .info(ID, Some(Path::new("/elsewhere")))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn list_filters_by_cwd_and_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let other = "0199a7e2-5c1d-7f10-9a8b-2f4e6d8c0a22";
+        write_rollout(tmp.path(), ID, &fixture_lines(ID, "/tmp/proj"));
+        write_rollout(tmp.path(), other, &fixture_lines(other, "/tmp/other"));
+        let reader = CodexHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let q = HistoryQuery {
+            cwd: Some(PathBuf::from("/tmp/other")),
+            ..Default::default()
+        };
+        let sessions = reader.list(&q).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, other);
+
+        // Codex has no tags: any tag filter matches nothing.
+        let q = HistoryQuery {
+            tag: Some("urgent".to_string()),
+            ..Default::default()
+        };
+        assert!(reader.list(&q).unwrap().is_empty());
+    }
+
+    #[test]
+    fn messages_map_response_items_to_canonical_blocks() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_rollout(tmp.path(), ID, &fixture_lines(ID, "/tmp/proj"));
+        let reader = CodexHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let msgs = reader
+            .messages(ID, &MessagesQuery::default(), None)
+            .unwrap();
+        let roles: Vec<MessageRole> = msgs.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            vec![
+                MessageRole::System,
+                MessageRole::User,
+                MessageRole::Assistant,
+                MessageRole::Assistant,
+                MessageRole::Tool,
+                MessageRole::Assistant,
+            ]
+        );
+        assert_eq!(
+            msgs[1].content,
+            HistoryContent::Text("list the files".into())
+        );
+        assert_eq!(msgs[1].uuid, "3");
+        assert!(matches!(&msgs[2].content, HistoryContent::Blocks(b)
+            if matches!(&b[0], HistoryBlock::Thinking { text } if text == "Run ls")));
+        match &msgs[3].content {
+            HistoryContent::Blocks(b) => match &b[0] {
+                HistoryBlock::ToolUse { id, name, input } => {
+                    assert_eq!(id, "call_1");
+                    assert_eq!(name, "shell");
+                    assert_eq!(input["command"][0], "ls");
+                }
+                other => panic!("expected ToolUse, got {other:?}"),
+            },
+            other => panic!("expected Blocks, got {other:?}"),
+        }
+        assert!(matches!(&msgs[4].content, HistoryContent::Blocks(b)
+            if matches!(&b[0], HistoryBlock::ToolResult { tool_use_id, is_error: false, .. }
+                if tool_use_id == "call_1")));
+
+        let paged = reader
+            .messages(
+                ID,
+                &MessagesQuery {
+                    limit: Some(2),
+                    offset: 1,
+                },
+                None,
+            )
+            .unwrap();
+        assert_eq!(paged.len(), 2);
+        assert_eq!(paged[0].role, MessageRole::User);
+    }
+
+    #[test]
+    fn messages_distinguishes_not_found_invalid_and_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = CodexHistory::with_sessions_dir(tmp.path().to_path_buf());
+        assert!(matches!(
+            reader.messages("not-a-uuid", &MessagesQuery::default(), None),
+            Err(HistoryError::InvalidId { .. })
+        ));
+        assert!(matches!(
+            reader.messages(ID, &MessagesQuery::default(), None),
+            Err(HistoryError::NotFound { .. })
+        ));
+
+        write_rollout(tmp.path(), ID, &fixture_lines(ID, "/tmp/proj")[..1]);
+        assert!(reader
+            .messages(ID, &MessagesQuery::default(), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn output_error_detection() {
+        assert!(output_is_error(&Value::String(
+            r#"{"output":"boom","metadata":{"exit_code":2}}"#.into()
+        )));
+        assert!(output_is_error(&serde_json::json!({"success": false})));
+        assert!(!output_is_error(&Value::String("plain text".into())));
+    }
+
+    #[test]
+    fn unrecognized_item_passes_through_as_raw_system() {
+        let payload = serde_json::json!({"type": "future_item", "x": 1});
+        assert_eq!(
+            map_response_item(&payload),
+            Some((MessageRole::System, HistoryContent::Raw(payload.clone())))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Helpers shared by the on-disk history adapters (spec 008): paging,
+//! display-title resolution, timestamp parsing and id validation.
+//!
+//! The Claude adapter delegates discovery to `claude-agent-sdk` and only needs
+//! the paging helpers; the Codex/Pi/aikit/OpenCode adapters scan their stores
+//! themselves and use the rest.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::types::{HistoryError, HistoryQuery, HistorySession};
+
+/// Applied when `HistoryQuery::limit`/`MessagesQuery::limit` is `None` or
+/// `Some(0)` (spec 008 §8 contract: "0/absent → default 100").
+pub(super) const DEFAULT_LIMIT: usize = 100;
+/// Caps an oversized requested limit (spec 008 §8 contract: "oversized →
+/// capped").
+pub(super) const MAX_LIMIT: usize = 1000;
+
+pub(super) fn effective_limit(limit: Option<usize>) -> usize {
+    match limit {
+        None | Some(0) => DEFAULT_LIMIT,
+        Some(l) => l.min(MAX_LIMIT),
+    }
+}
+
+/// Apply `effective_limit`/`offset` to an already-fetched, unpaged `Vec`.
+pub(super) fn page<T>(items: Vec<T>, limit: Option<usize>, offset: usize) -> Vec<T> {
+    let lim = effective_limit(limit);
+    items.into_iter().skip(offset).take(lim).collect()
+}
+
+/// Apply a [`HistoryQuery`] to every session a store holds: `cwd`/`tag`
+/// filters first, then newest-first ordering, then paging — so a page is
+/// always a page over the *filtered* set (spec 008 §7).
+pub(super) fn select_sessions(
+    mut sessions: Vec<HistorySession>,
+    q: &HistoryQuery,
+) -> Vec<HistorySession> {
+    if let Some(cwd) = q.cwd.as_deref() {
+        sessions.retain(|s| s.cwd.as_deref() == Some(cwd));
+    }
+    if let Some(tag) = q.tag.as_deref() {
+        sessions.retain(|s| s.tag.as_deref() == Some(tag));
+    }
+    sessions.sort_by(|a, b| {
+        b.last_modified_ms
+            .cmp(&a.last_modified_ms)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    page(sessions, q.limit, q.offset)
+}
+
+/// Display title for a session: custom title → first prompt (first line) →
+/// `session_id[..8]`, matching the resolution order the Claude SDK applies.
+pub(super) fn display_summary(
+    custom_title: Option<&str>,
+    first_prompt: Option<&str>,
+    session_id: &str,
+) -> String {
+    if let Some(title) = custom_title.filter(|t| !t.trim().is_empty()) {
+        return title.to_string();
+    }
+    if let Some(line) = first_prompt.and_then(|p| p.lines().map(str::trim).find(|l| !l.is_empty()))
+    {
+        return line.to_string();
+    }
+    session_id.chars().take(8).collect()
+}
+
+/// `InvalidId` unless `id` is a UUID — the id shape Codex and Pi both use.
+pub(super) fn validate_uuid(id: &str) -> Result<(), HistoryError> {
+    if uuid::Uuid::parse_str(id).is_ok() {
+        Ok(())
+    } else {
+        Err(HistoryError::InvalidId {
+            session_id: id.to_string(),
+        })
+    }
+}
+
+/// `InvalidId` unless `id` passes [`crate::paths::is_safe_id`] — for stores
+/// whose ids are not UUIDs (OpenCode's `ses_…`) or that accept any safe id
+/// (the aikit `SessionStore`). Also keeps a crafted id from ever reaching a
+/// file path.
+pub(super) fn validate_safe_id(id: &str) -> Result<(), HistoryError> {
+    if crate::paths::is_safe_id(id) {
+        Ok(())
+    } else {
+        Err(HistoryError::InvalidId {
+            session_id: id.to_string(),
+        })
+    }
+}
+
+pub(super) fn io_error(source: std::io::Error, path: &Path) -> HistoryError {
+    HistoryError::Io {
+        source,
+        path: Some(path.to_path_buf()),
+    }
+}
+
+/// Read a JSONL transcript into one `Value` per parseable line, paired with
+/// its 1-based line number. Blank and malformed lines are skipped — a
+/// half-written trailing line from a still-running agent must not make the
+/// whole session unreadable.
+pub(super) fn read_jsonl(path: &Path) -> Result<Vec<(usize, Value)>, HistoryError> {
+    let content = std::fs::read_to_string(path).map_err(|e| io_error(e, path))?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| serde_json::from_str(line).ok().map(|v| (i + 1, v)))
+        .collect())
+}
+
+/// File modification time, milliseconds since epoch.
+pub(super) fn mtime_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let since = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    i64::try_from(since.as_millis()).ok()
+}
+
+/// Parse an RFC 3339 timestamp (`2026-01-15T10:00:00Z`,
+/// `2026-01-15T10:00:00.123+02:00`) into milliseconds since epoch.
+/// Sub-millisecond digits are truncated. `None` on anything malformed.
+pub(super) fn parse_rfc3339_ms(s: &str) -> Option<i64> {
+    let num = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = s.get(range)?;
+        if part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    let bytes = s.as_bytes();
+    if bytes.len() < 19
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let mut rest = s.get(19..)?;
+    let mut millis = 0i64;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        for (i, b) in frac.bytes().take(digits.min(3)).enumerate() {
+            millis += i64::from(b - b'0') * 10i64.pow(2 - i as u32);
+        }
+        rest = &frac[digits..];
+    }
+    let offset_secs = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hh: i64 = rest.get(1..3)?.parse().ok()?;
+            let mm: i64 = rest.get(4..6)?.parse().ok()?;
+            if rest.len() != 6 || rest.as_bytes()[3] != b':' {
+                return None;
+            }
+            sign * (hh * 3600 + mm * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset_secs;
+    Some(secs * 1000 + millis)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's
+/// `days_from_civil`).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_limit_defaults_and_caps() {
+        assert_eq!(effective_limit(None), DEFAULT_LIMIT);
+        assert_eq!(effective_limit(Some(0)), DEFAULT_LIMIT);
+        assert_eq!(effective_limit(Some(5)), 5);
+        assert_eq!(effective_limit(Some(999_999)), MAX_LIMIT);
+    }
+
+    #[test]
+    fn parse_rfc3339_ms_handles_zulu_fraction_and_offset() {
+        assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_ms("2026-01-15T10:00:00Z"),
+            Some(1_768_471_200_000)
+        );
+        assert_eq!(
+            parse_rfc3339_ms("2026-01-15T10:00:00.5Z"),
+            Some(1_768_471_200_500)
+        );
+        assert_eq!(
+            parse_rfc3339_ms("2026-01-15T10:00:00.123456Z"),
+            Some(1_768_471_200_123)
+        );
+        assert_eq!(
+            parse_rfc3339_ms("2026-01-15T12:00:00+02:00"),
+            Some(1_768_471_200_000)
+        );
+    }
+
+    #[test]
+    fn parse_rfc3339_ms_rejects_garbage() {
+        assert_eq!(parse_rfc3339_ms(""), None);
+        assert_eq!(parse_rfc3339_ms("yesterday"), None);
+        assert_eq!(parse_rfc3339_ms("2026-13-01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339_ms("2026-01-15T10:00:00.Z"), None);
+        assert_eq!(parse_rfc3339_ms("2026-01-15T10:00:00+2"), None);
+    }
+
+    #[test]
+    fn display_summary_resolution_order() {
+        assert_eq!(
+            display_summary(Some("Title"), Some("prompt"), "abcdef123456"),
+            "Title"
+        );
+        assert_eq!(
+            display_summary(Some("  "), Some("\n  first line\nsecond"), "abcdef123456"),
+            "first line"
+        );
+        assert_eq!(display_summary(None, None, "abcdef123456"), "abcdef12");
+    }
+}
@@ -5,6 +5,11 @@
 //! browsable history implement [`HistoryReader`]; every other Backend is
 //! "unsupported," never merely empty (see [`HistoryError::Unsupported`]).
 //!
+//! Adapters: Claude (`claude-sdk` feature; read + rename/tag), Codex rollouts
+//! (read-only), OpenCode's SQLite store (`opencode` feature; read-only), Pi
+//! session files (read-only) and the built-in agent's `SessionStore` (read +
+//! rename/tag).
+//!
 //! Callers gate on the capability first, then construct:
 //! - read: `if backend.capabilities().history_store { backend.history_reader() }`
 //! - mutate: gate `history_store`, then `backend.history_mutator()`.
@@ -12,10 +17,16 @@
 //! See `specs/008-history-backend/spec.md` (gitignored) and ADR 0018 for the
 //! full design rationale.
 
+mod common;
 mod types;
 
+pub(crate) mod aikit;
 #[cfg(feature = "claude-sdk")]
 pub(crate) mod claude;
+pub(crate) mod codex;
+#[cfg(feature = "opencode")]
+pub(crate) mod opencode;
+pub(crate) mod pi;
 
 #[cfg(test)]
 mod tests;
@@ -83,6 +94,11 @@ impl Backend {
         match self {
             #[cfg(feature = "claude-sdk")]
             Backend::Claude => Some(Box::new(claude::ClaudeHistory)),
+            Backend::Codex => Some(Box::new(codex::CodexHistory::new())),
+            #[cfg(feature = "opencode")]
+            Backend::OpenCode => Some(Box::new(opencode::OpenCodeHistory::new())),
+            Backend::Pi => Some(Box::new(pi::PiHistory::new())),
+            Backend::Aikit => Some(Box::new(aikit::AikitHistory::new())),
             _ => None,
         }
     }
@@ -94,12 +110,12 @@ impl Backend {
     /// downcast: `Box<dyn HistoryReader>` cannot be upcast to
     /// `Box<dyn HistoryMutator>` in stable Rust. A read-only store would
     /// return `Some` here from `history_reader()` but `None` from this
-    /// method.
-    ///
+    /// method — as Codex, OpenCode and Pi do.
     pub fn history_mutator(self) -> Option<Box<dyn HistoryMutator>> {
         match self {
             #[cfg(feature = "claude-sdk")]
             Backend::Claude => Some(Box::new(claude::ClaudeHistory)),
+            Backend::Aikit => Some(Box::new(aikit::AikitHistory::new())),
             _ => None,
         }
     }
@@ -0,0 +1,427 @@
+//! `HistoryReader` for the OpenCode backend — spec 008.
+//!
+//! OpenCode keeps sessions in SQLite (`opencode.db`), not JSONL: a `session`
+//! row per session, `message` rows (`data` JSON carrying `role`) and `part`
+//! rows holding the content (`text`, `reasoning`, and `tool` parts whose
+//! `state` carries both the call input and its output). The database is
+//! opened read-only with `query_only`, like the capture adapter, so nothing
+//! here can corrupt a database OpenCode may be writing concurrently.
+//!
+//! Read-only: the `session` table does have a `title`, reported as the custom
+//! title, but OpenCode owns and rewrites it while running and has no tag
+//! field, so there is no `HistoryMutator` and `history_mutations` stays
+//! `false`.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde_json::Value;
+
+use crate::runner::backend::Backend;
+use crate::runner::types::MessageRole;
+
+use super::common::{display_summary, effective_limit, select_sessions, validate_safe_id};
+use super::types::{
+    HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryQuery, HistorySession,
+    MessagesQuery,
+};
+use super::HistoryReader;
+
+/// Reads one `opencode.db`. [`OpenCodeHistory::new`] picks the database the
+/// OpenCode CLI writes to; tests point [`OpenCodeHistory::with_db_path`] at
+/// a fixture database instead.
+#[derive(Debug, Clone)]
+pub(crate) struct OpenCodeHistory {
+    db_path: PathBuf,
+}
+
+impl OpenCodeHistory {
+    /// The first existing of `$XDG_DATA_HOME/opencode/opencode.db`,
+    /// `~/.local/share/opencode/opencode.db` and `~/.opencode/opencode.db` —
+    /// the XDG location when none exists yet.
+    pub(crate) fn new() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let mut candidates = Vec::new();
+        if let Some(xdg) = std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()) {
+            candidates.push(PathBuf::from(xdg).join("opencode").join("opencode.db"));
+        }
+        candidates.push(home.join(".local/share/opencode/opencode.db"));
+        candidates.push(home.join(".opencode").join("opencode.db"));
+        let db_path = candidates
+            .iter()
+            .find(|p| p.is_file())
+            .unwrap_or(&candidates[0])
+            .clone();
+        Self::with_db_path(db_path)
+    }
+
+    pub(crate) fn with_db_path(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+
+    /// `Ok(None)` when the database doesn't exist — OpenCode has never run,
+    /// which is an empty store, not an error.
+    fn open(&self) -> Result<Option<Connection>, HistoryError> {
+        if !self.db_path.is_file() {
+            return Ok(None);
+        }
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let url = format!(
+            "file:{}?mode=ro&query_only=1&busy_timeout=2000",
+            self.db_path.to_string_lossy()
+        );
+        Connection::open_with_flags(&url, flags)
+            .map(Some)
+            .map_err(|e| self.sql_error(e))
+    }
+
+    fn sql_error(&self, e: rusqlite::Error) -> HistoryError {
+        HistoryError::Io {
+            source: std::io::Error::other(e.to_string()),
+            path: Some(self.db_path.clone()),
+        }
+    }
+}
+
+impl HistoryReader for OpenCodeHistory {
+    fn backend(&self) -> Backend {
+        Backend::OpenCode
+    }
+
+    fn list(&self, q: &HistoryQuery) -> Result<Vec<HistorySession>, HistoryError> {
+        let Some(db) = self.open()? else {
+            return Ok(Vec::new());
+        };
+        let sessions = load_sessions(&db, None).map_err(|e| self.sql_error(e))?;
+        Ok(select_sessions(sessions, q))
+    }
+
+    fn info(&self, id: &str, cwd: Option<&Path>) -> Result<Option<HistorySession>, HistoryError> {
+        validate_safe_id(id)?;
+        let Some(db) = self.open()? else {
+            return Ok(None);
+        };
+        let session = load_sessions(&db, Some(id))
+            .map_err(|e| self.sql_error(e))?
+            .pop();
+        Ok(session.filter(|s| cwd.map_or(true, |c| s.cwd.as_deref() == Some(c))))
+    }
+
+    fn messages(
+        &self,
+        id: &str,
+        q: &MessagesQuery,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<HistoryMessage>, HistoryError> {
+        if self.info(id, cwd)?.is_none() {
+            return Err(HistoryError::NotFound {
+                session_id: id.to_string(),
+            });
+        }
+        let Some(db) = self.open()? else {
+            return Ok(Vec::new());
+        };
+        load_messages(&db, id, effective_limit(q.limit), q.offset).map_err(|e| self.sql_error(e))
+    }
+}
+
+/// Every session row (or just `only_id`), with its first user prompt and
+/// message count. `SELECT *` plus by-name column reads keeps older schemas
+/// without a `title` column readable.
+fn load_sessions(db: &Connection, only_id: Option<&str>) -> rusqlite::Result<Vec<HistorySession>> {
+    let mut stmt = db.prepare("SELECT * FROM session WHERE ?1 IS NULL OR id = ?1")?;
+    let rows = stmt.query_map([only_id], |row| {
+        Ok((
+            row.get::<_, String>("id")?,
+            row.get::<_, Option<String>>("directory").ok().flatten(),
+            row.get::<_, Option<String>>("title").ok().flatten(),
+            row.get::<_, Option<i64>>("time_created").ok().flatten(),
+            row.get::<_, Option<i64>>("time_updated").ok().flatten(),
+        ))
+    })?;
+
+    let mut sessions = Vec::new();
+    for row in rows {
+        let (id, directory, title, created, updated) = row?;
+        let first_prompt = first_user_text(db, &id)?;
+        let message_count: i64 = db.query_row(
+            "SELECT COUNT(*) FROM message WHERE session_id = ?1",
+            [&id],
+            |r| r.get(0),
+        )?;
+        let custom_title = title.filter(|t| !t.trim().is_empty());
+        sessions.push(HistorySession {
+            backend: Backend::OpenCode,
+            summary: display_summary(custom_title.as_deref(), first_prompt.as_deref(), &id),
+            first_prompt,
+            custom_title,
+            tag: None,
+            cwd: directory.filter(|d| !d.is_empty()).map(PathBuf::from),
+            git_branch: None,
+            // OpenCode timestamps are already milliseconds since epoch.
+            last_modified_ms: updated.or(created).unwrap_or(0),
+            created_at_ms: created,
+            message_count: Some(message_count.max(0) as u64),
+            size_bytes: None,
+            session_id: id,
+        });
+    }
+    Ok(sessions)
+}
+
+fn first_user_text(db: &Connection, session_id: &str) -> rusqlite::Result<Option<String>> {
+    let data: Option<String> = db
+        .query_row(
+            "SELECT p.data FROM part p JOIN message m ON m.id = p.message_id \
+             WHERE m.session_id = ?1 \
+               AND json_extract(m.data, '$.role') = 'user' \
+               AND json_extract(p.data, '$.type') = 'text' \
+             ORDER BY m.time_created, m.id, p.time_created, p.id LIMIT 1",
+            [session_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(data
+        .and_then(|d| serde_json::from_str::<Value>(&d).ok())
+        .and_then(|v| v.get("text").and_then(Value::as_str).map(str::to_string)))
+}
+
+fn load_messages(
+    db: &Connection,
+    session_id: &str,
+    limit: usize,
+    offset: usize,
+) -> rusqlite::Result<Vec<HistoryMessage>> {
+    let mut stmt = db.prepare(
+        "SELECT id, data FROM message WHERE session_id = ?1 \
+         ORDER BY time_created, id LIMIT ?2 OFFSET ?3",
+    )?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![session_id, limit as i64, offset as i64],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, Option<String>>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut parts_stmt =
+        db.prepare("SELECT data FROM part WHERE message_id = ?1 ORDER BY time_created, id")?;
+    let mut messages = Vec::with_capacity(rows.len());
+    for (message_id, data) in rows {
+        let data: Value = data
+            .and_then(|d| serde_json::from_str(&d).ok())
+            .unwrap_or(Value::Null);
+        let parts = parts_stmt
+            .query_map([&message_id], |r| r.get::<_, Option<String>>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .filter_map(|d| serde_json::from_str::<Value>(&d).ok())
+            .collect::<Vec<_>>();
+        let (role, content) = map_message(&data, &parts);
+        messages.push(HistoryMessage {
+            role,
+            uuid: message_id,
+            session_id: session_id.to_string(),
+            parent_tool_use_id: None,
+            content,
+        });
+    }
+    Ok(messages)
+}
+
+/// One `message` row plus its parts. Unrecognized roles pass the row's
+/// `data` through as [`HistoryContent::Raw`] under [`MessageRole::System`].
+fn map_message(data: &Value, parts: &[Value]) -> (MessageRole, HistoryContent) {
+    let blocks = || parts.iter().flat_map(map_part).collect::<Vec<_>>();
+    match data.get("role").and_then(Value::as_str) {
+        Some("user") => {
+            let blocks = blocks();
+            match blocks.as_slice() {
+                [HistoryBlock::Text { text }] => {
+                    (MessageRole::User, HistoryContent::Text(text.clone()))
+                }
+                _ => (MessageRole::User, HistoryContent::Blocks(blocks)),
+            }
+        }
+        Some("assistant") => (MessageRole::Assistant, HistoryContent::Blocks(blocks())),
+        _ => (MessageRole::System, HistoryContent::Raw(data.clone())),
+    }
+}
+
+/// A `tool` part records the call and its outcome together, so it expands
+/// to a `ToolUse` block followed (once the call has finished) by its
+/// `ToolResult`. Bookkeeping parts (`step-start`, `step-finish`, `snapshot`,
+/// `patch`) have nothing to render and are skipped; any other part type
+/// passes through as [`HistoryBlock::Raw`].
+fn map_part(part: &Value) -> Vec<HistoryBlock> {
+    let str_field = |v: &Value, key: &str| {
+        v.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    match part.get("type").and_then(Value::as_str) {
+        Some("text") => vec![HistoryBlock::Text {
+            text: str_field(part, "text"),
+        }],
+        Some("reasoning") => vec![HistoryBlock::Thinking {
+            text: str_field(part, "text"),
+        }],
+        Some("tool") => {
+            let call_id = str_field(part, "callID");
+            let state = part.get("state").unwrap_or(&Value::Null);
+            let mut blocks = vec![HistoryBlock::ToolUse {
+                id: call_id.clone(),
+                name: str_field(part, "tool"),
+                input: state.get("input").cloned().unwrap_or(Value::Null),
+            }];
+            match state.get("status").and_then(Value::as_str) {
+                Some("completed") => blocks.push(HistoryBlock::ToolResult {
+                    tool_use_id: call_id,
+                    content: state.get("output").cloned().unwrap_or(Value::Null),
+                    is_error: false,
+                }),
+                Some("error") => blocks.push(HistoryBlock::ToolResult {
+                    tool_use_id: call_id,
+                    content: state.get("error").cloned().unwrap_or(Value::Null),
+                    is_error: true,
+                }),
+                _ => {}
+            }
+            blocks
+        }
+        Some("step-start") | Some("step-finish") | Some("snapshot") | Some("patch") => Vec::new(),
+        _ => vec![HistoryBlock::Raw(part.clone())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_db(dir: &Path) -> PathBuf {
+        let path = dir.join("opencode.db");
+        let db = Connection::open(&path).unwrap();
+        db.execute_batch(
+            "CREATE TABLE session (id TEXT PRIMARY KEY, directory TEXT, title TEXT,
+                                   time_created INTEGER, time_updated INTEGER);
+             CREATE TABLE message (id TEXT PRIMARY KEY, session_id TEXT,
+                                   time_created INTEGER, time_updated INTEGER, data TEXT);
+             CREATE TABLE part (id TEXT PRIMARY KEY, message_id TEXT, session_id TEXT,
+                                time_created INTEGER, time_updated INTEGER, data TEXT);
+             INSERT INTO session VALUES ('ses_a', '/tmp/proj', 'Fix tests', 1000, 2000);
+             INSERT INTO session VALUES ('ses_b', '/tmp/other', NULL, 1500, 1600);
+             INSERT INTO message VALUES ('msg_1', 'ses_a', 1100, 1100, '{\"role\":\"user\"}');
+             INSERT INTO part VALUES ('prt_1', 'msg_1', 'ses_a', 1100, 1100,
+                                      '{\"type\":\"text\",\"text\":\"Run the tests\"}');
+             INSERT INTO message VALUES ('msg_2', 'ses_a', 1200, 1300, '{\"role\":\"assistant\"}');
+             INSERT INTO part VALUES ('prt_2', 'msg_2', 'ses_a', 1200, 1200,
+                                      '{\"type\":\"step-start\"}');
+             INSERT INTO part VALUES ('prt_3', 'msg_2', 'ses_a', 1210, 1210,
+                                      '{\"type\":\"reasoning\",\"text\":\"use cargo\"}');
+             INSERT INTO part VALUES ('prt_4', 'msg_2', 'ses_a', 1220, 1300,
+                 '{\"type\":\"tool\",\"tool\":\"bash\",\"callID\":\"call_1\",
+                   \"state\":{\"status\":\"error\",\"input\":{\"command\":\"cargo test\"},
+                              \"error\":\"exit 101\"}}');
+             INSERT INTO message VALUES ('msg_3', 'ses_b', 1550, 1550, '{\"role\":\"user\"}');
+             INSERT INTO part VALUES ('prt_5', 'msg_3', 'ses_b', 1550, 1550,
+                                      '{\"type\":\"text\",\"text\":\"hello\"}');",
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn list_reads_session_rows_newest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = OpenCodeHistory::with_db_path(fixture_db(tmp.path()));
+
+        let sessions = reader.list(&HistoryQuery::default()).unwrap();
+        let ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["ses_a", "ses_b"]);
+        let a = &sessions[0];
+        assert_eq!(a.backend, Backend::OpenCode);
+        assert_eq!(a.custom_title.as_deref(), Some("Fix tests"));
+        assert_eq!(a.summary, "Fix tests");
+        assert_eq!(a.first_prompt.as_deref(), Some("Run the tests"));
+        assert_eq!(a.last_modified_ms, 2000);
+        assert_eq!(a.message_count, Some(2));
+        assert_eq!(sessions[1].summary, "hello");
+
+        let q = HistoryQuery {
+            cwd: Some(PathBuf::from("/tmp/other")),
+            ..Default::default()
+        };
+        assert_eq!(reader.list(&q).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn messages_expand_tool_parts_and_skip_step_markers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = OpenCodeHistory::with_db_path(fixture_db(tmp.path()));
+
+        let msgs = reader
+            .messages("ses_a", &MessagesQuery::default(), None)
+            .unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].role, MessageRole::User);
+        assert_eq!(
+            msgs[0].content,
+            HistoryContent::Text("Run the tests".into())
+        );
+        match &msgs[1].content {
+            HistoryContent::Blocks(b) => {
+                assert_eq!(b.len(), 3);
+                assert!(matches!(&b[0], HistoryBlock::Thinking { text } if text == "use cargo"));
+                assert!(matches!(&b[1], HistoryBlock::ToolUse { id, name, .. }
+                    if id == "call_1" && name == "bash"));
+                assert!(matches!(
+                    &b[2],
+                    HistoryBlock::ToolResult { is_error: true, .. }
+                ));
+            }
+            other => panic!("expected Blocks, got {other:?}"),
+        }
+
+        let paged = reader
+            .messages(
+                "ses_a",
+                &MessagesQuery {
+                    limit: Some(1),
+                    offset: 1,
+                },
+                None,
+            )
+            .unwrap();
+        assert_eq!(paged.len(), 1);
+        assert_eq!(paged[0].uuid, "msg_2");
+    }
+
+    #[test]
+    fn missing_db_is_an_empty_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = OpenCodeHistory::with_db_path(tmp.path().join("opencode.db"));
+        assert!(reader.list(&HistoryQuery::default()).unwrap().is_empty());
+        assert!(reader.info("ses_a", None).unwrap().is_none());
+        assert!(matches!(
+            reader.messages("ses_a", &MessagesQuery::default(), None),
+            Err(HistoryError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_id_and_cwd_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = OpenCodeHistory::with_db_path(fixture_db(tmp.path()));
+        assert!(matches!(
+            reader.info("../x", None),
+            Err(HistoryError::InvalidId { .. })
+        ));
+        assert!(reader
+            .info("ses_a", Some(Path::new("/tmp/other")))
+            .unwrap()
+            .is_none());
+    }
+}
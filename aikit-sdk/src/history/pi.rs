@@ -0,0 +1,451 @@
+//! `HistoryReader` for the Pi backend — spec 008.
+//!
+//! Pi stores each session as a JSONL file under
+//! `$PI_CODING_AGENT_DIR/sessions/<encoded-cwd>/<timestamp>_<uuid>.jsonl`
+//! (`PI_CODING_AGENT_DIR` defaults to `~/.pi/agent`). The first line is a
+//! `{"type":"session","id","timestamp","cwd"}` header; every later line is an
+//! entry with its own `id`/`parentId`. `message` entries carry the transcript
+//! (`user`/`assistant`/`toolResult` roles); `compaction`/`branch_summary`
+//! entries surface as system summaries; settings entries (`model_change`,
+//! `thinking_level_change`, `label`, …) are not transcript turns and are
+//! skipped. Entries are returned in file order — a session that was branched
+//! in Pi's tree view lists every branch's turns, oldest first.
+//!
+//! Read-only: a `session_info` entry's `name` is reported as the custom
+//! title, but Pi has no tag field and its tree format is owned by Pi, so
+//! there is no `HistoryMutator` and `history_mutations` stays `false`.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::runner::backend::Backend;
+use crate::runner::types::MessageRole;
+
+use super::common::{
+    display_summary, io_error, mtime_ms, page, parse_rfc3339_ms, read_jsonl, select_sessions,
+    validate_uuid,
+};
+use super::types::{
+    HistoryBlock, HistoryContent, HistoryError, HistoryMessage, HistoryQuery, HistorySession,
+    MessagesQuery,
+};
+use super::HistoryReader;
+
+/// Reads sessions from one Pi `sessions` directory. [`PiHistory::new`]
+/// resolves the directory Pi itself writes to; tests point
+/// [`PiHistory::with_sessions_dir`] at a fixture tree instead.
+#[derive(Debug, Clone)]
+pub(crate) struct PiHistory {
+    sessions_dir: PathBuf,
+}
+
+impl PiHistory {
+    pub(crate) fn new() -> Self {
+        let agent_dir = std::env::var_os("PI_CODING_AGENT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join(".pi")
+                    .join("agent")
+            });
+        Self::with_sessions_dir(agent_dir.join("sessions"))
+    }
+
+    pub(crate) fn with_sessions_dir(sessions_dir: PathBuf) -> Self {
+        Self { sessions_dir }
+    }
+
+    /// Every session file (`<sessions>/<project>/<ts>_<uuid>.jsonl`), with the
+    /// session id taken from its file name.
+    fn session_files(&self) -> Vec<(String, PathBuf)> {
+        walkdir::WalkDir::new(&self.sessions_dir)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let id = file_session_id(e.path())?;
+                Some((id, e.into_path()))
+            })
+            .collect()
+    }
+
+    fn find_session(&self, id: &str) -> Option<PathBuf> {
+        self.session_files()
+            .into_iter()
+            .find(|(sid, _)| sid.eq_ignore_ascii_case(id))
+            .map(|(_, path)| path)
+    }
+}
+
+/// `2026-01-15T10-00-00-000Z_<uuid>.jsonl` → `<uuid>`.
+fn file_session_id(path: &Path) -> Option<String> {
+    let stem = path.file_name()?.to_str()?.strip_suffix(".jsonl")?;
+    let (_, id) = stem.rsplit_once('_')?;
+    uuid::Uuid::parse_str(id).ok()?;
+    Some(id.to_string())
+}
+
+impl HistoryReader for PiHistory {
+    fn backend(&self) -> Backend {
+        Backend::Pi
+    }
+
+    fn list(&self, q: &HistoryQuery) -> Result<Vec<HistorySession>, HistoryError> {
+        let sessions = self
+            .session_files()
+            .into_iter()
+            .filter_map(|(id, path)| read_session(&id, &path).ok())
+            .map(|s| s.session)
+            .collect();
+        Ok(select_sessions(sessions, q))
+    }
+
+    fn info(&self, id: &str, cwd: Option<&Path>) -> Result<Option<HistorySession>, HistoryError> {
+        validate_uuid(id)?;
+        let Some(path) = self.find_session(id) else {
+            return Ok(None);
+        };
+        let session = read_session(id, &path)?.session;
+        Ok(cwd
+            .map_or(true, |c| session.cwd.as_deref() == Some(c))
+            .then_some(session))
+    }
+
+    fn messages(
+        &self,
+        id: &str,
+        q: &MessagesQuery,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<HistoryMessage>, HistoryError> {
+        validate_uuid(id)?;
+        let not_found = || HistoryError::NotFound {
+            session_id: id.to_string(),
+        };
+        let path = self.find_session(id).ok_or_else(not_found)?;
+        let parsed = read_session(id, &path)?;
+        if cwd.is_some_and(|c| parsed.session.cwd.as_deref() != Some(c)) {
+            return Err(not_found());
+        }
+        Ok(page(parsed.messages, q.limit, q.offset))
+    }
+}
+
+struct PiSession {
+    session: HistorySession,
+    messages: Vec<HistoryMessage>,
+}
+
+fn read_session(id: &str, path: &Path) -> Result<PiSession, HistoryError> {
+    let lines = read_jsonl(path)?;
+    let size_bytes = std::fs::metadata(path)
+        .map_err(|e| io_error(e, path))?
+        .len();
+
+    let mut cwd = None;
+    let mut created_at_ms = None;
+    let mut custom_title = None;
+    let mut messages = Vec::new();
+    for (line_no, entry) in &lines {
+        let uuid = entry
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| line_no.to_string());
+        match entry.get("type").and_then(Value::as_str) {
+            Some("session") => {
+                cwd = entry.get("cwd").and_then(Value::as_str).map(PathBuf::from);
+                created_at_ms = entry
+                    .get("timestamp")
+                    .and_then(Value::as_str)
+                    .and_then(parse_rfc3339_ms);
+            }
+            Some("message") => {
+                let message = entry.get("message").unwrap_or(&Value::Null);
+                let (role, content) = map_message(message);
+                messages.push(HistoryMessage {
+                    role,
+                    uuid,
+                    session_id: id.to_string(),
+                    parent_tool_use_id: None,
+                    content,
+                });
+            }
+            Some("compaction") | Some("branch_summary") => {
+                let summary = entry.get("summary").and_then(Value::as_str);
+                messages.push(HistoryMessage {
+                    role: MessageRole::System,
+                    uuid,
+                    session_id: id.to_string(),
+                    parent_tool_use_id: None,
+                    content: HistoryContent::Text(summary.unwrap_or_default().to_string()),
+                });
+            }
+            Some("session_info") => {
+                // Last write wins, like Pi's own session picker.
+                custom_title = entry
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .filter(|n| !n.trim().is_empty())
+                    .map(str::to_string);
+            }
+            _ => {}
+        }
+    }
+
+    let first_prompt = messages.iter().find_map(|m| match (&m.role, &m.content) {
+        (MessageRole::User, HistoryContent::Text(text)) => Some(text.clone()),
+        (MessageRole::User, HistoryContent::Blocks(blocks)) => {
+            blocks.iter().find_map(|b| match b {
+                HistoryBlock::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+        }
+        _ => None,
+    });
+    let session = HistorySession {
+        backend: Backend::Pi,
+        session_id: id.to_string(),
+        summary: display_summary(custom_title.as_deref(), first_prompt.as_deref(), id),
+        first_prompt,
+        custom_title,
+        tag: None,
+        cwd,
+        git_branch: None,
+        last_modified_ms: mtime_ms(path).or(created_at_ms).unwrap_or(0),
+        created_at_ms,
+        message_count: Some(messages.len() as u64),
+        size_bytes: Some(size_bytes),
+    };
+    Ok(PiSession { session, messages })
+}
+
+/// Map one Pi `message` payload. Unrecognized roles (e.g. `bashExecution`,
+/// extension `custom` messages) pass through as [`HistoryContent::Raw`]
+/// under [`MessageRole::System`] — never dropped.
+fn map_message(message: &Value) -> (MessageRole, HistoryContent) {
+    let content = message.get("content");
+    match message.get("role").and_then(Value::as_str) {
+        Some("user") => match content {
+            Some(Value::String(text)) => (MessageRole::User, HistoryContent::Text(text.clone())),
+            Some(Value::Array(blocks)) => (
+                MessageRole::User,
+                HistoryContent::Blocks(blocks.iter().map(map_block).collect()),
+            ),
+            _ => (MessageRole::User, HistoryContent::Raw(message.clone())),
+        },
+        Some("assistant") => match content {
+            Some(Value::Array(blocks)) => (
+                MessageRole::Assistant,
+                HistoryContent::Blocks(blocks.iter().map(map_block).collect()),
+            ),
+            _ => (MessageRole::Assistant, HistoryContent::Raw(message.clone())),
+        },
+        Some("toolResult") => {
+            let block = HistoryBlock::ToolResult {
+                tool_use_id: message
+                    .get("toolCallId")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                content: content.cloned().unwrap_or(Value::Null),
+                is_error: message
+                    .get("isError")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            };
+            (MessageRole::Tool, HistoryContent::Blocks(vec![block]))
+        }
+        _ => (MessageRole::System, HistoryContent::Raw(message.clone())),
+    }
+}
+
+fn map_block(block: &Value) -> HistoryBlock {
+    let text = |key: &str| {
+        block
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    match block.get("type").and_then(Value::as_str) {
+        Some("text") => HistoryBlock::Text { text: text("text") },
+        Some("thinking") => HistoryBlock::Thinking {
+            text: text("thinking"),
+        },
+        Some("toolCall") => HistoryBlock::ToolUse {
+            id: text("id"),
+            name: text("name"),
+            input: block.get("arguments").cloned().unwrap_or(Value::Null),
+        },
+        _ => HistoryBlock::Raw(block.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f2b8c1a-6d4e-4f0a-9b7c-1e2d3c4b5a69";
+
+    fn write_session(root: &Path, project: &str, id: &str, lines: &[Value]) -> PathBuf {
+        let dir = root.join(project);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("2026-01-15T10-00-00-000Z_{id}.jsonl"));
+        let body = lines
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        std::fs::write(&path, body).unwrap();
+        path
+    }
+
+    fn fixture_lines(id: &str, cwd: &str) -> Vec<Value> {
+        vec![
+            serde_json::json!({"type": "session", "version": 3, "id": id,
+                "timestamp": "2026-01-15T10:00:00.000Z", "cwd": cwd}),
+            serde_json::json!({"type": "model_change", "id": "e0", "parentId": null,
+                "provider": "anthropic", "modelId": "claude-x"}),
+            serde_json::json!({"type": "message", "id": "e1", "parentId": "e0",
+                "timestamp": "2026-01-15T10:00:01.000Z",
+                "message": {"role": "user", "content": [{"type": "text", "text": "read a.rs"}]}}),
+            serde_json::json!({"type": "message", "id": "e2", "parentId": "e1",
+                "message": {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "open it"},
+                    {"type": "toolCall", "id": "tc_1", "name": "read",
+                     "arguments": {"path": "a.rs"}}]}}),
+            serde_json::json!({"type": "message", "id": "e3", "parentId": "e2",
+                "message": {"role": "toolResult", "toolCallId": "tc_1", "toolName": "read",
+                            "content": [{"type": "text", "text": "fn main() {}"}],
+                            "isError": false}}),
+            serde_json::json!({"type": "message", "id": "e4", "parentId": "e3",
+                "message": {"role": "assistant", "content": [
+                    {"type": "text", "text": "It is empty."}]}}),
+        ]
+    }
+
+    #[test]
+    fn file_session_id_comes_from_file_name() {
+        let path = PathBuf::from(format!("/s/--tmp--/2026-01-15T10-00-00-000Z_{ID}.jsonl"));
+        assert_eq!(file_session_id(&path).as_deref(), Some(ID));
+        assert_eq!(file_session_id(Path::new("/s/--tmp--/notes.jsonl")), None);
+    }
+
+    #[test]
+    fn list_reads_header_and_first_prompt() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_session(
+            tmp.path(),
+            "--tmp-proj--",
+            ID,
+            &fixture_lines(ID, "/tmp/proj"),
+        );
+        let reader = PiHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let sessions = reader.list(&HistoryQuery::default()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.backend, Backend::Pi);
+        assert_eq!(s.session_id, ID);
+        assert_eq!(s.summary, "read a.rs");
+        assert_eq!(s.cwd.as_deref(), Some(Path::new("/tmp/proj")));
+        assert_eq!(s.created_at_ms, Some(1_768_471_200_000));
+        assert_eq!(s.message_count, Some(4));
+
+        let q = HistoryQuery {
+            cwd: Some(PathBuf::from("/tmp/elsewhere")),
+            ..Default::default()
+        };
+        assert!(reader.list(&q).unwrap().is_empty());
+    }
+
+    #[test]
+    fn session_info_name_becomes_custom_title() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut lines = fixture_lines(ID, "/tmp/proj");
+        lines.push(serde_json::json!({"type": "session_info", "id": "e5", "name": "Refactor"}));
+        write_session(tmp.path(), "--tmp-proj--", ID, &lines);
+        let reader = PiHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let info = reader.info(ID, None).unwrap().expect("session exists");
+        assert_eq!(info.custom_title.as_deref(), Some("Refactor"));
+        assert_eq!(info.summary, "Refactor");
+    }
+
+    #[test]
+    fn messages_map_entries_to_canonical_blocks() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_session(
+            tmp.path(),
+            "--tmp-proj--",
+            ID,
+            &fixture_lines(ID, "/tmp/proj"),
+        );
+        let reader = PiHistory::with_sessions_dir(tmp.path().to_path_buf());
+
+        let msgs = reader
+            .messages(ID, &MessagesQuery::default(), None)
+            .unwrap();
+        let roles: Vec<MessageRole> = msgs.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            vec![
+                MessageRole::User,
+                MessageRole::Assistant,
+                MessageRole::Tool,
+                MessageRole::Assistant,
+            ]
+        );
+        assert_eq!(msgs[0].uuid, "e1");
+        match &msgs[1].content {
+            HistoryContent::Blocks(b) => {
+                assert!(matches!(&b[0], HistoryBlock::Thinking { text } if text == "open it"));
+                assert!(matches!(&b[1], HistoryBlock::ToolUse { id, name, .. }
+                    if id == "tc_1" && name == "read"));
+            }
+            other => panic!("expected Blocks, got {other:?}"),
+        }
+        assert!(matches!(&msgs[2].content, HistoryContent::Blocks(b)
+            if matches!(&b[0], HistoryBlock::ToolResult { tool_use_id, is_error: false, .. }
+                if tool_use_id == "tc_1")));
+    }
+
+    #[test]
+    fn messages_distinguishes_not_found_invalid_and_empty() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = PiHistory::with_sessions_dir(tmp.path().to_path_buf());
+        assert!(matches!(
+            reader.messages("nope", &MessagesQuery::default(), None),
+            Err(HistoryError::InvalidId { .. })
+        ));
+        assert!(matches!(
+            reader.messages(ID, &MessagesQuery::default(), None),
+            Err(HistoryError::NotFound { .. })
+        ));
+
+        write_session(
+            tmp.path(),
+            "--tmp-proj--",
+            ID,
+            &fixture_lines(ID, "/tmp/proj")[..2],
+        );
+        assert!(reader
+            .messages(ID, &MessagesQuery::default(), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn unknown_role_passes_through_as_raw_system() {
+        let message = serde_json::json!({"role": "bashExecution", "command": "ls"});
+        assert_eq!(
+            map_message(&message),
+            (MessageRole::System, HistoryContent::Raw(message.clone()))
+        );
+    }
+}
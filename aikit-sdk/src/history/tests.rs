@@ -1,6 +1,7 @@
 //! Phase 1 seam tests: `Backend::history_reader()`/`history_mutator()`
 //! gating, independent of any concrete adapter. Adapter-specific mapping
-//! tests live in `claude.rs` (Phase 2) and the Phase 4 hardening table.
+//! tests live next to each adapter (`claude.rs`, `codex.rs`, `opencode.rs`,
+//! `pi.rs`, `aikit.rs`).
 
 use super::*;
 use crate::runner::backend::ALL;
@@ -33,23 +34,44 @@ fn history_mutations_capability_matches_mutator_presence_for_all_backends() {
     }
 }
 
-/// Every non-Claude Backend has no history store, in any build
-/// configuration (spec 008 §4 table — only Claude implements it, and only
-/// behind `claude-sdk`).
+/// Spec 008 §4 table: which Backends read and which also mutate their
+/// history store. Gemini and Cursor persist no browsable history in any
+/// build configuration; Codex, OpenCode and Pi are read-only; the built-in
+/// aikit agent supports rename/tag on its own `SessionStore`.
 #[test]
-fn only_claude_can_ever_have_a_history_reader() {
-    for &b in ALL {
-        if b != Backend::Claude {
-            assert!(
-                b.history_reader().is_none(),
-                "{b:?} must never have a history reader"
-            );
-            assert!(
-                b.history_mutator().is_none(),
-                "{b:?} must never have a history mutator"
-            );
-        }
+fn history_support_per_backend() {
+    for b in [Backend::Gemini, Backend::Cursor] {
+        assert!(b.history_reader().is_none(), "{b:?} has no history store");
+        assert!(b.history_mutator().is_none(), "{b:?} has no history store");
+    }
+    for b in [Backend::Codex, Backend::Pi] {
+        let reader = b.history_reader().expect("history reader");
+        assert_eq!(reader.backend(), b);
+        assert!(b.history_mutator().is_none(), "{b:?} history is read-only");
     }
+    assert!(Backend::Aikit.history_reader().is_some());
+    assert_eq!(
+        Backend::Aikit.history_mutator().unwrap().backend(),
+        Backend::Aikit
+    );
+    assert!(Backend::Aikit.capabilities().history_mutations);
+}
+
+#[cfg(feature = "opencode")]
+#[test]
+fn opencode_history_reader_is_some_when_opencode_enabled() {
+    assert_eq!(
+        Backend::OpenCode.history_reader().unwrap().backend(),
+        Backend::OpenCode
+    );
+    assert!(Backend::OpenCode.history_mutator().is_none());
+}
+
+#[cfg(not(feature = "opencode"))]
+#[test]
+fn opencode_history_reader_is_none_without_opencode_feature() {
+    assert!(Backend::OpenCode.history_reader().is_none());
+    assert!(!Backend::OpenCode.capabilities().history_store);
 }
 
 #[cfg(feature = "claude-sdk")]
@@ -453,11 +453,13 @@ pub fn deploy_subagent(
 }
 
 pub mod aikit_agent_adapter;
-#[cfg(feature = "agent-adapters")]
 pub mod cost;
 #[cfg(feature = "history")]
+pub mod handoff;
+#[cfg(feature = "history")]
 pub mod history;
 pub mod runner;
+pub mod schema;
 pub mod session_store;
 
 /// Re-export SDK-MCP builder helpers so callers need no direct
@@ -479,10 +481,19 @@ pub use runner::{
 #[cfg(any(feature = "claude-control", feature = "codex-app-server"))]
 pub use runner::{ControlError, LiveSession};
 
+pub use runner::{
+    open_aikit_session, AikitControlHandle, AikitSession, AikitSessionError, AikitSessionOptions,
+    GatewayFactory,
+};
+pub use runner::{
+    open_opencode_session, OpenCodeControlHandle, OpenCodeSession, OpenCodeSessionError,
+    OpenCodeSessionOptions,
+};
 pub use runner::{open_pi_session, PiControlHandle, PiSession, PiSessionError, PiSessionOptions};
 
 // Re-export host tool types so cli-framework can depend on aikit-sdk alone.
 pub use aikit_agent::{HostToolDefinition, HostToolProvider};
+pub use aikit_agent::{Worktree, WorktreeError, WorktreePatch};
 
 pub use aikit_agent::loop_runner::run_with_context;
 pub use aikit_agent_adapter::{run_aikit_agent, run_aikit_agent_with_gateway};
@@ -496,7 +507,7 @@ pub use runner::{
 };
 
 pub mod run_progress;
-pub use run_progress::{usage_source_label, ProgressViewConfig, RunProgress};
+pub use run_progress::{usage_source_label, ProgressViewConfig, RunProgress, ViewToggles};
 
 pub(crate) mod command_resolve;
 pub mod fetch;
@@ -531,8 +542,8 @@ pub use install::{
 pub use manifest::{PackageInfo, TemplateManifest};
 pub use mcp_deploy::{
     add_mcp_server, mcp_config_path, mcp_supported_agents, normalize_mcp_agent_key,
-    parse_env_pairs, parse_header_pairs, AddMcpServerOptions, McpAgentSupportRow, McpDeployError,
-    McpScope, McpServerTransport, MCP_SUPPORTED_AGENT_KEYS,
+    parse_env_pairs, parse_header_pairs, read_mcp_servers, AddMcpServerOptions, McpAgentSupportRow,
+    McpDeployError, McpScope, McpServerTransport, MCP_SUPPORTED_AGENT_KEYS,
 };
 
 /// Agent catalog entry containing all supported agents and their capabilities.
@@ -14,6 +14,8 @@
 //! - **copilot**: `.vscode/mcp.json` / VS Code user `mcp.json` — `servers` (VS Code shape)
 //! - **opencode**: `opencode.json` / user config — root `mcp` map
 //! - **codex**: `.codex/config.toml` / `~/.codex/config.toml` — `[mcp_servers.NAME]`
+//! - **aikit**: `.aikit/mcp.json` / `~/.aikit/mcp.json` — `mcpServers`, read back by the
+//!   built-in agent (see [`read_mcp_servers`])
 
 use std::collections::HashMap;
 use std::error::Error;
@@ -35,20 +37,9 @@ pub enum McpScope {
 }
 
 /// Stdio or HTTP MCP transport (product-specific JSON/TOML mapping).
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum McpServerTransport {
-    /// `command` / `args` / optional `env`.
-    Stdio {
-        command: String,
-        args: Vec<String>,
-        env: Option<HashMap<String, String>>,
-    },
-    /// Remote Streamable HTTP / SSE URL and optional `headers`.
-    Http {
-        url: String,
-        headers: Option<HashMap<String, String>>,
-    },
-}
+///
+/// Defined in `aikit-agent`, whose MCP client connects to the same shapes.
+pub use aikit_agent::McpServerTransport;
 
 /// Target file and agent for [`add_mcp_server`].
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -64,8 +55,9 @@ pub struct AddMcpServerOptions {
 }
 
 /// Agent keys that support MCP config deployment through this module.
-pub const MCP_SUPPORTED_AGENT_KEYS: &[&str] =
-    &["cursor", "claude", "gemini", "copilot", "opencode", "codex"];
+pub const MCP_SUPPORTED_AGENT_KEYS: &[&str] = &[
+    "cursor", "claude", "gemini", "copilot", "opencode", "codex", "aikit",
+];
 
 /// Human-oriented row for [`mcp_supported_agents`].
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -115,6 +107,12 @@ pub fn mcp_supported_agents() -> Vec<McpAgentSupportRow> {
             project_config_path: ".codex/config.toml",
             global_config_path: "~/.codex/config.toml",
         },
+        McpAgentSupportRow {
+            agent_key: "aikit",
+            display_name: "aikit built-in",
+            project_config_path: ".aikit/mcp.json",
+            global_config_path: "~/.aikit/mcp.json",
+        },
     ]
 }
 
@@ -247,8 +245,11 @@ pub fn mcp_config_path(
     project_root: &Path,
 ) -> Result<PathBuf, McpDeployError> {
     let key = normalize_mcp_agent_key(agent_key);
-    crate::validate_agent_key(key)
-        .map_err(|_| McpDeployError::UnknownAgent(agent_key.to_string()))?;
+    // `aikit` is a runnable backend but not a deploy-catalog agent.
+    if crate::runner::Backend::from_key(key).is_none() {
+        crate::validate_agent_key(key)
+            .map_err(|_| McpDeployError::UnknownAgent(agent_key.to_string()))?;
+    }
 
     if !MCP_SUPPORTED_AGENT_KEYS.contains(&key) {
         let supported = MCP_SUPPORTED_AGENT_KEYS.join(", ");
@@ -272,6 +273,8 @@ pub fn mcp_config_path(
         ("opencode", McpScope::Global) => opencode_user_config_path(&home),
         ("codex", McpScope::Project) => project_root.join(".codex/config.toml"),
         ("codex", McpScope::Global) => home.join(".codex/config.toml"),
+        ("aikit", McpScope::Project) => project_root.join(".aikit/mcp.json"),
+        ("aikit", McpScope::Global) => home.join(".aikit/mcp.json"),
         _ => {
             return Err(McpDeployError::UnsupportedAgent {
                 agent: agent_key.to_string(),
@@ -309,6 +312,39 @@ fn transport_to_mcp_servers_json(t: &McpServerTransport) -> Value {
     }
 }
 
+/// Inverse of [`transport_to_mcp_servers_json`]; `None` for entries with
+/// neither `url` nor `command`.
+fn transport_from_mcp_servers_json(v: &Value) -> Option<McpServerTransport> {
+    let string_map = |key: &str| {
+        v.get(key).and_then(Value::as_object).map(|m| {
+            m.iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect::<HashMap<_, _>>()
+        })
+    };
+    if let Some(url) = v.get("url").and_then(Value::as_str) {
+        return Some(McpServerTransport::Http {
+            url: url.to_string(),
+            headers: string_map("headers"),
+        });
+    }
+    let command = v.get("command").and_then(Value::as_str)?;
+    let args = v
+        .get("args")
+        .and_then(Value::as_array)
+        .map(|a| {
+            a.iter()
+                .filter_map(|s| s.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(McpServerTransport::Stdio {
+        command: command.to_string(),
+        args,
+        env: string_map("env"),
+    })
+}
+
 /// VS Code `.vscode/mcp.json` server entry ([docs](https://code.visualstudio.com/docs/copilot/reference/mcp-configuration)).
 fn transport_to_vscode_server_json(t: &McpServerTransport) -> Value {
     match t {
@@ -569,7 +605,7 @@ pub fn add_mcp_server(opts: AddMcpServerOptions) -> Result<PathBuf, McpDeployErr
             let out = serde_json::to_string_pretty(&merged)?;
             fs::write(&path, out)?;
         }
-        "cursor" | "claude" | "gemini" => {
+        "cursor" | "claude" | "gemini" | "aikit" => {
             let existing = read_or_empty_json_object(&path)?;
             let server_json = transport_to_mcp_servers_json(&opts.transport);
             let merged = merge_json_bucket(
@@ -593,6 +629,38 @@ pub fn add_mcp_server(opts: AddMcpServerOptions) -> Result<PathBuf, McpDeployErr
     Ok(path)
 }
 
+/// Reads the servers registered in an agent's `mcpServers` config file, sorted
+/// by name. A missing file yields an empty list; entries that are neither
+/// stdio nor HTTP are skipped.
+///
+/// Only the `mcpServers` shape (cursor, claude, gemini, aikit) is readable.
+pub fn read_mcp_servers(
+    agent_key: &str,
+    scope: McpScope,
+    project_root: &Path,
+) -> Result<Vec<(String, McpServerTransport)>, McpDeployError> {
+    let key = normalize_mcp_agent_key(agent_key);
+    let path = mcp_config_path(key, scope, project_root)?;
+    if !matches!(key, "cursor" | "claude" | "gemini" | "aikit") {
+        return Err(McpDeployError::UnsupportedAgent {
+            agent: agent_key.to_string(),
+            detail: "reading back is implemented for mcpServers files only".to_string(),
+        });
+    }
+    let root = read_or_empty_json_object(&path)?;
+    let mut servers: Vec<(String, McpServerTransport)> = root
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .map(|m| {
+            m.iter()
+                .filter_map(|(name, v)| Some((name.clone(), transport_from_mcp_servers_json(v)?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    servers.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(servers)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -891,4 +959,48 @@ mod tests {
         let e = mcp_config_path("qwen", McpScope::Project, tmp.path()).unwrap_err();
         assert!(matches!(e, McpDeployError::UnsupportedAgent { .. }));
     }
+
+    #[test]
+    fn aikit_round_trips_through_read_mcp_servers() {
+        let tmp = TempDir::new().unwrap();
+        let add = |name: &str, transport: McpServerTransport| {
+            add_mcp_server(AddMcpServerOptions {
+                agent_key: "aikit".to_string(),
+                scope: McpScope::Project,
+                project_root: tmp.path().to_path_buf(),
+                server_name: name.to_string(),
+                transport,
+                overwrite: false,
+            })
+            .unwrap()
+        };
+        let stdio = McpServerTransport::Stdio {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "srv".to_string()],
+            env: Some(HashMap::from([("K".to_string(), "v".to_string())])),
+        };
+        let http = McpServerTransport::Http {
+            url: "http://127.0.0.1:8730/mcp".to_string(),
+            headers: None,
+        };
+        let path = add("zeta", stdio.clone());
+        add("alpha", http.clone());
+        assert_eq!(path, tmp.path().join(".aikit/mcp.json"));
+
+        let servers = read_mcp_servers("aikit", McpScope::Project, tmp.path()).unwrap();
+        assert_eq!(
+            servers,
+            vec![("alpha".to_string(), http), ("zeta".to_string(), stdio)]
+        );
+    }
+
+    #[test]
+    fn read_mcp_servers_missing_file_and_unsupported_shape() {
+        let tmp = TempDir::new().unwrap();
+        assert!(read_mcp_servers("aikit", McpScope::Project, tmp.path())
+            .unwrap()
+            .is_empty());
+        let e = read_mcp_servers("codex", McpScope::Project, tmp.path()).unwrap_err();
+        assert!(matches!(e, McpDeployError::UnsupportedAgent { .. }));
+    }
 }
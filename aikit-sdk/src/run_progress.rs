@@ -2,9 +2,21 @@
 //!
 //! Classifies and formats [`AgentEvent`] payloads into human-readable lines
 //! stored in a ring buffer, independent of terminal rendering.
+//!
+//! Alongside the flat line log, [`RunProgress`] keeps a structured step view
+//! ([`ProgressStep`]) built only from the canonical event vocabulary
+//! (`ToolUse`/`ToolResult`, reasoning and assistant `StreamMessage`s, `Result`,
+//! `TokenUsageLine`). The built-in agent's `Aikit*` events are folded into the
+//! same vocabulary, so every Backend renders the same way: tool calls paired
+//! with collapsible results, file-change summaries, hideable reasoning, a
+//! token/cost meter and per-step elapsed time.
 
 use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::cost::PricingTable;
+use crate::runner::aggregate_token_usage;
 use crate::{AgentEvent, AgentEventPayload, MessageKind, MessageRole, TokenUsage, UsageSource};
 
 /// Configuration for progress display behaviour.
@@ -18,6 +30,14 @@ pub struct ProgressViewConfig {
     pub max_text_width: usize,
     /// Maximum characters for tool output snippets.
     pub max_tool_output_chars: usize,
+    /// Maximum result lines shown for a tool call when results are expanded.
+    pub max_expanded_lines: usize,
+    /// Pricing catalog for the cost estimate in the token meter. `None`
+    /// hides the cost figure.
+    pub pricing: Option<Arc<PricingTable>>,
+    /// Model the run was started with; usage is priced by it. The cost is
+    /// hidden while it is unknown or has no catalog entry.
+    pub model: Option<String>,
 }
 
 /// Human-readable label for a [`UsageSource`].
@@ -44,6 +64,98 @@ impl Default for ProgressViewConfig {
             show_tokens: true,
             max_text_width: 80,
             max_tool_output_chars: 100,
+            max_expanded_lines: 12,
+            pricing: Some(Arc::new(PricingTable::builtin())),
+            model: None,
+        }
+    }
+}
+
+/// Display toggles for [`RunProgress::view_lines`], flipped by the renderer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewToggles {
+    /// Show reasoning text instead of a one-line placeholder.
+    pub show_reasoning: bool,
+    /// Show tool results in full (up to `max_expanded_lines`) instead of a
+    /// single collapsed line.
+    pub expand_tool_results: bool,
+}
+
+/// One entry of the structured progress view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressStep {
+    /// Offset from the start of the run at which the step began.
+    pub started_at: Duration,
+    /// Step duration; `None` while the step is still running.
+    pub elapsed: Option<Duration>,
+    pub kind: StepKind,
+}
+
+/// What a [`ProgressStep`] shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepKind {
+    /// Assistant or user message text.
+    Message { role: MessageRole, text: String },
+    /// Model reasoning; hidden unless [`ViewToggles::show_reasoning`] is set.
+    Reasoning { text: String },
+    /// A tool call and (once it arrives) its result.
+    Tool(ToolCallView),
+    /// The run's final answer.
+    Result { text: String },
+    /// Any other event, already formatted as a single line.
+    Note(String),
+}
+
+/// A tool call paired with its result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallView {
+    /// Correlation id from `ToolUse`; `None` for Backends that only report
+    /// commands as tool-role messages.
+    pub call_id: Option<String>,
+    pub tool_name: String,
+    /// One-line digest of the tool input (command, path, pattern, ...).
+    pub summary: String,
+    pub result: Option<ToolResultView>,
+    pub file_changes: Vec<FileChange>,
+}
+
+/// The output of a finished tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResultView {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// A file touched by a tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: FileChangeKind,
+    pub path: String,
+}
+
+/// How a [`FileChange`] affected the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Add,
+    Edit,
+    Delete,
+}
+
+impl FileChangeKind {
+    fn from_label(label: &str) -> Self {
+        match label.to_ascii_lowercase().as_str() {
+            "add" | "create" | "new" => FileChangeKind::Add,
+            "delete" | "remove" => FileChangeKind::Delete,
+            _ => FileChangeKind::Edit,
+        }
+    }
+
+    /// Single-character marker used in file summaries (`+`, `~`, `-`).
+    pub fn marker(self) -> char {
+        match self {
+            FileChangeKind::Add => '+',
+            FileChangeKind::Edit => '~',
+            FileChangeKind::Delete => '-',
         }
     }
 }
@@ -57,8 +169,21 @@ impl Default for ProgressViewConfig {
 pub struct RunProgress {
     config: ProgressViewConfig,
     rows: VecDeque<String>,
-    latest_token_usage: Option<(TokenUsage, UsageSource)>,
+    /// Rows ever added (not capped by `max_rows`); lets append-only renderers
+    /// print each row exactly once.
+    rows_added: u64,
+    usage_entries: Vec<(TokenUsage, UsageSource)>,
+    /// The model each of `usage_entries` was reported under.
+    usage_models: Vec<Option<String>>,
     last_assistant_text: Option<String>,
+    started: Instant,
+    steps: VecDeque<ProgressStep>,
+    /// Every file changed during the run, in first-touched order.
+    files: Vec<FileChange>,
+    tool_calls: usize,
+    tool_errors: usize,
+    /// True while built-in agent text deltas are being appended to the last step.
+    streaming_text: bool,
 }
 
 impl RunProgress {
@@ -67,16 +192,42 @@ impl RunProgress {
         Self {
             config,
             rows: VecDeque::new(),
-            latest_token_usage: None,
+            rows_added: 0,
+            usage_entries: Vec::new(),
+            usage_models: Vec::new(),
             last_assistant_text: None,
+            started: Instant::now(),
+            steps: VecDeque::new(),
+            files: Vec::new(),
+            tool_calls: 0,
+            tool_errors: 0,
+            streaming_text: false,
         }
     }
 
     /// Process an agent event and update internal state.
     pub fn push(&mut self, _agent_key: &str, event: &AgentEvent) {
+        let now = self.started.elapsed();
+        self.push_at(event, now);
+    }
+
+    fn push_at(&mut self, event: &AgentEvent, now: Duration) {
+        let rows_before = self.rows_added;
+        self.push_row(event);
+        let new_row = if self.rows_added > rows_before {
+            self.rows.back().cloned()
+        } else {
+            None
+        };
+        self.push_step(event, now, new_row);
+    }
+
+    /// Flat line log: one formatted row per displayable event.
+    fn push_row(&mut self, event: &AgentEvent) {
         match &event.payload {
             AgentEventPayload::TokenUsageLine { usage, source, .. } => {
-                self.latest_token_usage = Some((usage.clone(), source.clone()));
+                self.usage_entries.push((usage.clone(), source.clone()));
+                self.usage_models.push(self.config.model.clone());
             }
             AgentEventPayload::StreamMessage(sm) => {
                 let text = sm.text.replace('\n', " ").replace('\r', "");
@@ -146,22 +297,37 @@ impl RunProgress {
                     self.last_assistant_text = Some(text.to_string());
                 }
             }
-            AgentEventPayload::ToolUse { tool_name, .. } => {
-                self.add_row(format!("tool> {}", tool_name));
+            AgentEventPayload::ToolUse {
+                tool_name, input, ..
+            }
+            | AgentEventPayload::AikitToolUse {
+                tool_name,
+                tool_input: input,
+                ..
+            } => {
+                let summary = tool_input_summary(input);
+                let row = if summary.is_empty() {
+                    format!("tool> {}", tool_name)
+                } else {
+                    format!(
+                        "tool> {} {}",
+                        tool_name,
+                        truncate(&summary, self.config.max_text_width)
+                    )
+                };
+                self.add_row(row);
             }
             AgentEventPayload::ToolResult {
                 output, is_error, ..
             } => {
                 let prefix = if *is_error { "tool error>" } else { "tool>" };
+                let text = tool_output_text(output).replace('\n', " ");
                 self.add_row(format!(
                     "{} {}",
                     prefix,
-                    truncate(&output.to_string(), self.config.max_tool_output_chars)
+                    truncate(text.trim(), self.config.max_tool_output_chars)
                 ));
             }
-            AgentEventPayload::AikitToolUse { tool_name, .. } => {
-                self.add_row(format!("tool> {}", tool_name));
-            }
             AgentEventPayload::AikitToolResult {
                 output, is_error, ..
             } => {
@@ -175,6 +341,27 @@ impl RunProgress {
             AgentEventPayload::AikitSubagentSpawn { subagent_id, .. } => {
                 self.add_row(format!("subagent> spawned {}", subagent_id));
             }
+            // A delegated sub-agent's own events; only its tool calls are
+            // worth a row in the parent's view.
+            AgentEventPayload::AikitSubagentEvent { subagent_id, event } => match &event.payload {
+                AgentEventPayload::ToolUse {
+                    tool_name, input, ..
+                }
+                | AgentEventPayload::AikitToolUse {
+                    tool_name,
+                    tool_input: input,
+                    ..
+                } => {
+                    let summary = tool_input_summary(input);
+                    self.add_row(format!(
+                        "subagent> {} {} {}",
+                        subagent_id,
+                        tool_name,
+                        truncate(&summary, self.config.max_text_width)
+                    ));
+                }
+                _ => {}
+            },
             AgentEventPayload::AikitSubagentResult {
                 subagent_id,
                 status,
@@ -218,33 +405,147 @@ impl RunProgress {
         self.rows.iter().map(|s| s.as_str())
     }
 
+    /// Total number of rows added since creation (or the last [`clear`]),
+    /// including rows already evicted from the ring buffer.
+    ///
+    /// [`clear`]: RunProgress::clear
+    pub fn rows_added(&self) -> u64 {
+        self.rows_added
+    }
+
+    /// The structured steps currently retained (at most `max_rows`).
+    pub fn steps(&self) -> impl Iterator<Item = &ProgressStep> {
+        self.steps.iter()
+    }
+
+    /// Files changed so far, in first-touched order. A file's kind is the
+    /// latest change reported for it.
+    pub fn file_changes(&self) -> &[FileChange] {
+        &self.files
+    }
+
+    /// Token usage for the run so far, aggregated with the same per-source
+    /// rules as [`RunResult`](crate::RunResult) (`Codex` sums turns, other
+    /// sources report cumulative totals).
+    pub fn token_usage(&self) -> Option<(TokenUsage, UsageSource)> {
+        let (_, source) = self.usage_entries.last()?;
+        let usage = aggregate_token_usage(&self.usage_entries, source.clone())?;
+        Some((usage, source.clone()))
+    }
+
+    /// Price usage reported from now on by `model`, e.g. after a session
+    /// switches models mid-run.
+    pub fn set_model(&mut self, model: Option<String>) {
+        self.config.model = model;
+    }
+
+    /// Estimated USD cost of the run so far: each model's usage, aggregated
+    /// like [`RunProgress::token_usage`], priced by that model's catalog
+    /// entry. `None` without a catalog or when any of the usage came from a
+    /// model that is unknown or not in it.
+    pub fn cost_usd(&self) -> Option<f64> {
+        let pricing = self.config.pricing.as_ref()?;
+        let (_, source) = self.usage_entries.last()?;
+        let mut models: Vec<Option<&str>> = Vec::new();
+        for model in &self.usage_models {
+            if !models.contains(&model.as_deref()) {
+                models.push(model.as_deref());
+            }
+        }
+        models
+            .into_iter()
+            .map(|model| {
+                let entries: Vec<(TokenUsage, UsageSource)> = self
+                    .usage_entries
+                    .iter()
+                    .zip(&self.usage_models)
+                    .filter(|(_, m)| m.as_deref() == model)
+                    .map(|(entry, _)| entry.clone())
+                    .collect();
+                let usage = aggregate_token_usage(&entries, source.clone())?;
+                Some(pricing.lookup(model?)?.usage_cost(&usage))
+            })
+            .sum()
+    }
+
+    /// Render the structured view as display lines, one or more per step.
+    pub fn view_lines(&self, toggles: ViewToggles) -> Vec<String> {
+        self.view_lines_at(toggles, self.started.elapsed())
+    }
+
+    /// Footer lines shown below the step view: the token/cost meter, the
+    /// file-change summary and the run status (elapsed time, tool counts).
+    pub fn footer_lines(&self) -> Vec<String> {
+        self.footer_lines_at(self.started.elapsed())
+    }
+
     /// Get current token usage footer text if `show_tokens` is enabled.
     pub fn token_footer(&self) -> Option<String> {
         if !self.config.show_tokens {
             return None;
         }
-        let (usage, source) = self.latest_token_usage.as_ref()?;
-        let source_label = usage_source_label(source);
+        let (usage, source) = self.token_usage()?;
+        let source_label = usage_source_label(&source);
         let computed_total = usage.input_tokens + usage.output_tokens;
         let agent_total_suffix = match usage.total_tokens {
             Some(t) if t != computed_total => format!(" agent_total={}", t),
             _ => String::new(),
         };
+        let cache_suffix = match usage.cache_read_tokens {
+            Some(c) if c > 0 => format!(" cache_read={}", c),
+            _ => String::new(),
+        };
+        let cost_suffix = match self.cost_usd() {
+            Some(cost) => format!("  cost~${:.4}", cost),
+            None => String::new(),
+        };
         Some(format!(
-            "[tokens] {}  in={} out={} total={}{}",
+            "[tokens] {}  in={} out={} total={}{}{}{}",
             source_label,
             usage.input_tokens,
             usage.output_tokens,
             computed_total,
-            agent_total_suffix
+            agent_total_suffix,
+            cache_suffix,
+            cost_suffix
+        ))
+    }
+
+    /// File-change summary line, e.g. `[files] 2 changed: +new.rs ~lib.rs`.
+    pub fn files_footer(&self) -> Option<String> {
+        if self.files.is_empty() {
+            return None;
+        }
+        const SHOWN: usize = 5;
+        let mut listed: Vec<String> = self
+            .files
+            .iter()
+            .take(SHOWN)
+            .map(|f| format!("{}{}", f.kind.marker(), f.path))
+            .collect();
+        if self.files.len() > SHOWN {
+            listed.push(format!("(+{} more)", self.files.len() - SHOWN));
+        }
+        Some(format!(
+            "[files] {} changed: {}",
+            self.files.len(),
+            listed.join(" ")
         ))
     }
 
     /// Clear all progress state.
     pub fn clear(&mut self) {
         self.rows.clear();
-        self.latest_token_usage = None;
+        self.rows_added = 0;
+        self.usage_entries.clear();
+        self.usage_models.clear();
         self.last_assistant_text = None;
+        self.started = Instant::now();
+        self.steps.clear();
+        self.files.clear();
+        self.tool_calls = 0;
+        self.tool_errors = 0;
+        self.streaming_text = false;
     }
 
     // -------------------------------------------------------------------------
@@ -259,6 +560,541 @@ impl RunProgress {
             self.rows.pop_front();
         }
         self.rows.push_back(row);
+        self.rows_added += 1;
+    }
+
+    /// Structured view: folds the event into the step list. `new_row` is the
+    /// flat row `push_row` produced for it, reused for events shown as notes.
+    fn push_step(&mut self, event: &AgentEvent, now: Duration, new_row: Option<String>) {
+        let streaming = std::mem::take(&mut self.streaming_text);
+        match &event.payload {
+            AgentEventPayload::StreamMessage(sm) => {
+                let text = sm.text.trim();
+                if text.is_empty() {
+                    return;
+                }
+                match (sm.role, sm.kind) {
+                    (_, MessageKind::Reasoning) => self.open_step(
+                        now,
+                        StepKind::Reasoning {
+                            text: text.to_string(),
+                        },
+                    ),
+                    (MessageRole::Assistant, _) | (MessageRole::User, _) => {
+                        self.push_message(now, sm.role, text)
+                    }
+                    (MessageRole::Tool, MessageKind::ToolOutput) => {
+                        // Backends without call ids report a command and then its
+                        // output; pair the output with the last unfinished call.
+                        let open_call =
+                            self.steps
+                                .iter_mut()
+                                .rev()
+                                .find_map(|step| match &mut step.kind {
+                                    StepKind::Tool(call)
+                                        if call.call_id.is_none() && call.result.is_none() =>
+                                    {
+                                        Some((step.started_at, &mut step.elapsed, call))
+                                    }
+                                    _ => None,
+                                });
+                        match open_call {
+                            Some((started_at, elapsed, call)) => {
+                                call.result = Some(ToolResultView {
+                                    text: text.to_string(),
+                                    is_error: false,
+                                });
+                                *elapsed = Some(now.saturating_sub(started_at));
+                            }
+                            None => self.open_tool(
+                                now,
+                                ToolCallView {
+                                    call_id: None,
+                                    tool_name: "tool".to_string(),
+                                    summary: String::new(),
+                                    result: Some(ToolResultView {
+                                        text: text.to_string(),
+                                        is_error: false,
+                                    }),
+                                    file_changes: Vec::new(),
+                                },
+                            ),
+                        }
+                    }
+                    (MessageRole::Tool, _) => {
+                        let call = match text.strip_prefix("file_change:") {
+                            Some(list) => ToolCallView {
+                                call_id: None,
+                                tool_name: "file_change".to_string(),
+                                summary: list.trim().to_string(),
+                                // Applied changes have no output to wait for.
+                                result: Some(ToolResultView {
+                                    text: String::new(),
+                                    is_error: false,
+                                }),
+                                file_changes: parse_change_list(list),
+                            },
+                            None => ToolCallView {
+                                call_id: None,
+                                tool_name: "command".to_string(),
+                                summary: text.to_string(),
+                                result: None,
+                                file_changes: Vec::new(),
+                            },
+                        };
+                        self.open_tool(now, call);
+                    }
+                    (MessageRole::System, _) => {
+                        if let Some(row) = new_row {
+                            self.open_step(now, StepKind::Note(row));
+                        }
+                    }
+                }
+            }
+            AgentEventPayload::AikitTextDelta { content, .. } => {
+                if streaming {
+                    if let Some(StepKind::Message { text, .. }) =
+                        self.steps.back_mut().map(|s| &mut s.kind)
+                    {
+                        text.push_str(content);
+                        self.streaming_text = true;
+                        return;
+                    }
+                }
+                if !content.trim().is_empty() {
+                    self.open_step(
+                        now,
+                        StepKind::Message {
+                            role: MessageRole::Assistant,
+                            text: content.clone(),
+                        },
+                    );
+                    self.streaming_text = true;
+                } else {
+                    self.streaming_text = streaming;
+                }
+            }
+            AgentEventPayload::AikitTextFinal { content, .. } => {
+                let text = content.trim();
+                if streaming {
+                    if let Some(StepKind::Message { text: current, .. }) =
+                        self.steps.back_mut().map(|s| &mut s.kind)
+                    {
+                        *current = text.to_string();
+                        return;
+                    }
+                }
+                if !text.is_empty() {
+                    self.push_message(now, MessageRole::Assistant, text);
+                }
+            }
+            AgentEventPayload::ToolUse {
+                call_id,
+                tool_name,
+                input,
+            }
+            | AgentEventPayload::AikitToolUse {
+                call_id,
+                tool_name,
+                tool_input: input,
+            } => {
+                let call = ToolCallView {
+                    call_id: Some(call_id.clone()),
+                    tool_name: tool_name.clone(),
+                    summary: tool_input_summary(input),
+                    result: None,
+                    file_changes: tool_file_changes(tool_name, input),
+                };
+                self.open_tool(now, call);
+            }
+            AgentEventPayload::ToolResult {
+                call_id,
+                output,
+                is_error,
+            } => self.finish_tool(now, call_id, tool_output_text(output), *is_error, new_row),
+            AgentEventPayload::AikitToolResult {
+                call_id,
+                output,
+                is_error,
+            } => self.finish_tool(now, call_id, output.clone(), *is_error, new_row),
+            AgentEventPayload::Result { text, .. } => {
+                let text = text.trim();
+                let repeats_last_message = matches!(
+                    self.steps.back().map(|s| &s.kind),
+                    Some(StepKind::Message { role: MessageRole::Assistant, text: last }) if last.trim() == text
+                );
+                if !text.is_empty() && !repeats_last_message {
+                    self.open_step(
+                        now,
+                        StepKind::Result {
+                            text: text.to_string(),
+                        },
+                    );
+                }
+            }
+            AgentEventPayload::TokenUsageLine { .. }
+            | AgentEventPayload::JsonLine(_)
+            | AgentEventPayload::RawTransportLine { .. }
+            | AgentEventPayload::SessionStarted { .. } => {
+                self.streaming_text = streaming;
+            }
+            AgentEventPayload::RawLine(_)
+            | AgentEventPayload::RawBytes(_)
+            | AgentEventPayload::QuotaExceeded { .. }
+            | AgentEventPayload::AikitSubagentSpawn { .. }
+            | AgentEventPayload::AikitSubagentEvent { .. }
+            | AgentEventPayload::AikitSubagentResult { .. }
+            | AgentEventPayload::AikitContextCompressed { .. }
+            | AgentEventPayload::AikitStepFinish { .. } => {
+                if let Some(row) = new_row {
+                    self.open_step(now, StepKind::Note(row));
+                }
+            }
+        }
+    }
+
+    fn push_message(&mut self, now: Duration, role: MessageRole, text: &str) {
+        let duplicate = matches!(
+            self.steps.back().map(|s| &s.kind),
+            Some(StepKind::Message { role: r, text: last }) if *r == role && last == text
+        );
+        if !duplicate {
+            self.open_step(
+                now,
+                StepKind::Message {
+                    role,
+                    text: text.to_string(),
+                },
+            );
+        }
+    }
+
+    fn open_tool(&mut self, now: Duration, call: ToolCallView) {
+        self.tool_calls += 1;
+        for change in &call.file_changes {
+            match self.files.iter_mut().find(|f| f.path == change.path) {
+                Some(existing) => existing.kind = change.kind,
+                None => self.files.push(change.clone()),
+            }
+        }
+        self.open_step(now, StepKind::Tool(call));
+    }
+
+    fn finish_tool(
+        &mut self,
+        now: Duration,
+        call_id: &str,
+        text: String,
+        is_error: bool,
+        new_row: Option<String>,
+    ) {
+        if is_error {
+            self.tool_errors += 1;
+        }
+        let call = self
+            .steps
+            .iter_mut()
+            .rev()
+            .find_map(|step| match &mut step.kind {
+                StepKind::Tool(call) if call.call_id.as_deref() == Some(call_id) => {
+                    Some((step.started_at, &mut step.elapsed, call))
+                }
+                _ => None,
+            });
+        match call {
+            Some((started_at, elapsed, call)) => {
+                call.result = Some(ToolResultView { text, is_error });
+                *elapsed = Some(now.saturating_sub(started_at));
+            }
+            // The call scrolled out of the view (or was never reported).
+            None => {
+                if let Some(row) = new_row {
+                    self.open_step(now, StepKind::Note(row));
+                }
+            }
+        }
+    }
+
+    /// Start a new step. The previous step, unless it is a tool call still
+    /// waiting for its result, ends here.
+    fn open_step(&mut self, now: Duration, kind: StepKind) {
+        if let Some(last) = self.steps.back_mut() {
+            let awaiting_result = matches!(
+                &last.kind,
+                StepKind::Tool(call) if call.call_id.is_some() && call.result.is_none()
+            );
+            if last.elapsed.is_none() && !awaiting_result {
+                last.elapsed = Some(now.saturating_sub(last.started_at));
+            }
+        }
+        if self.config.max_rows == 0 {
+            return;
+        }
+        while self.steps.len() >= self.config.max_rows {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(ProgressStep {
+            started_at: now,
+            elapsed: None,
+            kind,
+        });
+    }
+
+    fn view_lines_at(&self, toggles: ViewToggles, now: Duration) -> Vec<String> {
+        let width = self.config.max_text_width;
+        let mut lines = Vec::new();
+        for step in &self.steps {
+            let elapsed = step
+                .elapsed
+                .unwrap_or_else(|| now.saturating_sub(step.started_at));
+            let prefix = format!("[{:>6}] ", format_duration(elapsed));
+            let indent = " ".repeat(prefix.len() + 2);
+            match &step.kind {
+                StepKind::Message { role, text } => {
+                    let label = match role {
+                        MessageRole::User => "user>",
+                        _ => "assistant>",
+                    };
+                    lines.push(format!("{prefix}{label} {}", one_line(text, width)));
+                }
+                StepKind::Reasoning { text } => {
+                    if toggles.show_reasoning {
+                        lines.push(format!("{prefix}reasoning> {}", one_line(text, width)));
+                    } else {
+                        lines.push(format!(
+                            "{prefix}reasoning> ({} chars hidden)",
+                            text.chars().count()
+                        ));
+                    }
+                }
+                StepKind::Tool(call) => {
+                    let marker = match &call.result {
+                        None => "…",
+                        Some(r) if r.is_error => "✗",
+                        Some(_) => "✓",
+                    };
+                    let head = if call.summary.is_empty() {
+                        call.tool_name.clone()
+                    } else {
+                        format!("{} {}", call.tool_name, call.summary)
+                    };
+                    lines.push(format!("{prefix}{marker} {}", one_line(&head, width)));
+                    for change in &call.file_changes {
+                        lines.push(format!("{indent}{} {}", change.kind.marker(), change.path));
+                    }
+                    if let Some(result) = &call.result {
+                        lines.extend(self.result_lines(result, &indent, toggles));
+                    }
+                }
+                StepKind::Result { text } => {
+                    lines.push(format!("{prefix}result> {}", one_line(text, width)));
+                }
+                StepKind::Note(row) => lines.push(format!("{prefix}{row}")),
+            }
+        }
+        lines
+    }
+
+    fn result_lines(
+        &self,
+        result: &ToolResultView,
+        indent: &str,
+        toggles: ViewToggles,
+    ) -> Vec<String> {
+        let output: Vec<&str> = result
+            .text
+            .lines()
+            .map(str::trim_end)
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        let Some(first) = output.first() else {
+            return Vec::new();
+        };
+        let max_chars = self.config.max_tool_output_chars;
+        if !toggles.expand_tool_results {
+            let more = match output.len() {
+                1 => String::new(),
+                n => format!(" (+{} lines)", n - 1),
+            };
+            return vec![format!("{indent}└ {}{more}", truncate(first, max_chars))];
+        }
+        let shown = output.len().min(self.config.max_expanded_lines);
+        let mut lines: Vec<String> = output[..shown]
+            .iter()
+            .map(|line| format!("{indent}│ {}", truncate(line, max_chars)))
+            .collect();
+        if output.len() > shown {
+            lines.push(format!("{indent}└ … {} more lines", output.len() - shown));
+        }
+        lines
+    }
+
+    fn footer_lines_at(&self, now: Duration) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.extend(self.token_footer());
+        lines.extend(self.files_footer());
+        let mut status = format!(
+            "[elapsed] {}  steps={} tools={}",
+            format_duration(now),
+            self.steps.len(),
+            self.tool_calls
+        );
+        if self.tool_errors > 0 {
+            status.push_str(&format!(" ({} failed)", self.tool_errors));
+        }
+        lines.push(status);
+        lines
+    }
+}
+
+/// One-line digest of a tool input: the first well-known argument
+/// (command, path, pattern, ...) or compact JSON for anything else.
+fn tool_input_summary(input: &serde_json::Value) -> String {
+    const KEYS: &[&str] = &[
+        "command",
+        "cmd",
+        "file_path",
+        "filePath",
+        "notebook_path",
+        "path",
+        "pattern",
+        "query",
+        "url",
+        "prompt",
+        "description",
+    ];
+    let Some(obj) = input.as_object() else {
+        return match input {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+    };
+    for key in KEYS {
+        match obj.get(*key) {
+            Some(serde_json::Value::String(s)) => return s.clone(),
+            Some(serde_json::Value::Array(parts)) => {
+                let parts: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+                if !parts.is_empty() {
+                    return parts.join(" ");
+                }
+            }
+            _ => {}
+        }
+    }
+    if obj.is_empty() {
+        String::new()
+    } else {
+        input.to_string()
+    }
+}
+
+/// Files a tool call writes, from its input: `write`/`edit`-style tools name
+/// the file in `file_path`/`filePath`/`path`; `apply_patch` carries a patch
+/// with `*** Add File:` / `*** Update File:` / `*** Delete File:` headers.
+fn tool_file_changes(tool_name: &str, input: &serde_json::Value) -> Vec<FileChange> {
+    let name = tool_name.to_ascii_lowercase();
+    let patch = input
+        .get("patch")
+        .or_else(|| input.get("input"))
+        .and_then(|v| v.as_str())
+        .or_else(|| input.as_str());
+    if name.contains("patch") {
+        if let Some(patch) = patch {
+            return patch
+                .lines()
+                .filter_map(|line| {
+                    let line = line.trim();
+                    let (kind, path) = if let Some(p) = line.strip_prefix("*** Add File:") {
+                        (FileChangeKind::Add, p)
+                    } else if let Some(p) = line.strip_prefix("*** Update File:") {
+                        (FileChangeKind::Edit, p)
+                    } else if let Some(p) = line.strip_prefix("*** Delete File:") {
+                        (FileChangeKind::Delete, p)
+                    } else {
+                        return None;
+                    };
+                    Some(FileChange {
+                        kind,
+                        path: path.trim().to_string(),
+                    })
+                })
+                .collect();
+        }
+    }
+    let kind = if name.contains("write") || name.contains("create") {
+        FileChangeKind::Add
+    } else if name.contains("edit") || name.contains("replace") {
+        FileChangeKind::Edit
+    } else {
+        return Vec::new();
+    };
+    ["file_path", "filePath", "notebook_path", "path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+        .map(|path| {
+            vec![FileChange {
+                kind,
+                path: path.to_string(),
+            }]
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a `kind path, kind path` list (Codex `file_change` items).
+fn parse_change_list(list: &str) -> Vec<FileChange> {
+    list.split(',')
+        .filter_map(|entry| {
+            let (kind, path) = entry.trim().split_once(' ')?;
+            Some(FileChange {
+                kind: FileChangeKind::from_label(kind),
+                path: path.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Text of a canonical `ToolResult` output: a plain string, the text blocks of
+/// a content array, a `stdout`/`output` field, or compact JSON otherwise.
+fn tool_output_text(output: &serde_json::Value) -> String {
+    match output {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => {
+            let texts: Vec<&str> = blocks
+                .iter()
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect();
+            if texts.is_empty() {
+                output.to_string()
+            } else {
+                texts.join("\n")
+            }
+        }
+        serde_json::Value::Object(obj) => ["output", "stdout", "content", "text"]
+            .iter()
+            .find_map(|key| obj.get(*key).and_then(|v| v.as_str()))
+            .map(str::to_string)
+            .unwrap_or_else(|| output.to_string()),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Flatten text onto one line and truncate it to `max_chars`.
+fn one_line(text: &str, max_chars: usize) -> String {
+    let flat = text.replace('\n', " ").replace('\r', "");
+    truncate(flat.trim(), max_chars).to_string()
+}
+
+/// Compact step duration: `0.4s`, `12s`, `3m05s`.
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs < 10 {
+        format!("{:.1}s", d.as_secs_f64())
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
     }
 }
 
@@ -596,4 +1432,331 @@ mod tests {
         let lines: Vec<_> = progress.formatted_lines().collect();
         assert_eq!(lines, vec!["tool> ls -la"]);
     }
+
+    fn event(agent_key: &str, payload: AgentEventPayload) -> AgentEvent {
+        AgentEvent {
+            agent_key: agent_key.to_string(),
+            seq: 0,
+            stream: AgentEventStream::Stdout,
+            payload,
+        }
+    }
+
+    fn secs(s: f64) -> Duration {
+        Duration::from_secs_f64(s)
+    }
+
+    fn usage_event(source: UsageSource, input: u64, output: u64) -> AgentEvent {
+        event(
+            "codex",
+            AgentEventPayload::TokenUsageLine {
+                usage: TokenUsage {
+                    input_tokens: input,
+                    output_tokens: output,
+                    total_tokens: None,
+                    cache_read_tokens: None,
+                    cache_creation_tokens: None,
+                    reasoning_tokens: None,
+                },
+                source,
+                raw_agent_line_seq: 0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_tool_call_pairs_result_and_collapses() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        progress.push_at(
+            &event(
+                "claude",
+                AgentEventPayload::ToolUse {
+                    call_id: "t1".to_string(),
+                    tool_name: "Bash".to_string(),
+                    input: serde_json::json!({"command": "ls -la"}),
+                },
+            ),
+            secs(1.0),
+        );
+        progress.push_at(
+            &event(
+                "claude",
+                AgentEventPayload::ToolResult {
+                    call_id: "t1".to_string(),
+                    output: serde_json::json!([{"type": "text", "text": "a.rs\nb.rs\nc.rs"}]),
+                    is_error: false,
+                },
+            ),
+            secs(2.5),
+        );
+
+        let collapsed = progress.view_lines_at(ViewToggles::default(), secs(3.0));
+        assert_eq!(
+            collapsed,
+            vec![
+                "[  1.5s] ✓ Bash ls -la".to_string(),
+                "           └ a.rs (+2 lines)".to_string(),
+            ]
+        );
+
+        let expanded = progress.view_lines_at(
+            ViewToggles {
+                expand_tool_results: true,
+                ..Default::default()
+            },
+            secs(3.0),
+        );
+        assert_eq!(expanded.len(), 4);
+        assert_eq!(expanded[3], "           │ c.rs");
+        assert_eq!(
+            progress.formatted_lines().collect::<Vec<_>>(),
+            vec!["tool> Bash ls -la", "tool> a.rs b.rs c.rs"]
+        );
+    }
+
+    #[test]
+    fn test_pending_tool_shows_running_time_and_error_marker() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        progress.push_at(
+            &event(
+                "aikit",
+                AgentEventPayload::AikitToolUse {
+                    tool_name: "read".to_string(),
+                    tool_input: serde_json::json!({"path": "missing.txt"}),
+                    call_id: "c1".to_string(),
+                },
+            ),
+            secs(0.0),
+        );
+        let running = progress.view_lines_at(ViewToggles::default(), secs(12.0));
+        assert_eq!(running, vec!["[   12s] … read missing.txt".to_string()]);
+
+        progress.push_at(
+            &event(
+                "aikit",
+                AgentEventPayload::AikitToolResult {
+                    call_id: "c1".to_string(),
+                    output: "not found".to_string(),
+                    is_error: true,
+                },
+            ),
+            secs(75.0),
+        );
+        let done = progress.view_lines_at(ViewToggles::default(), secs(80.0));
+        assert_eq!(done[0], "[ 1m15s] ✗ read missing.txt");
+        let footer = progress.footer_lines_at(secs(80.0));
+        assert_eq!(
+            footer.last().unwrap(),
+            "[elapsed] 1m20s  steps=1 tools=1 (1 failed)"
+        );
+    }
+
+    #[test]
+    fn test_reasoning_toggle() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        let (key, e) = make_stream_event(
+            "weighing options",
+            MessageRole::Assistant,
+            MessageKind::Reasoning,
+        );
+        progress.push_at(&e, secs(0.0));
+        let (_, e) = make_stream_event("done", MessageRole::Assistant, MessageKind::Message);
+        progress.push(&key, &e);
+
+        let hidden = progress.view_lines_at(ViewToggles::default(), secs(1.0));
+        assert!(
+            hidden[0].ends_with("reasoning> (16 chars hidden)"),
+            "{hidden:?}"
+        );
+        let shown = progress.view_lines_at(
+            ViewToggles {
+                show_reasoning: true,
+                ..Default::default()
+            },
+            secs(1.0),
+        );
+        assert!(
+            shown[0].ends_with("reasoning> weighing options"),
+            "{shown:?}"
+        );
+        assert!(shown[1].ends_with("assistant> done"));
+    }
+
+    #[test]
+    fn test_file_changes_from_tool_inputs() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        let calls = [
+            (
+                "Write",
+                serde_json::json!({"file_path": "src/new.rs", "content": "x"}),
+            ),
+            ("Edit", serde_json::json!({"file_path": "src/lib.rs"})),
+            ("edit", serde_json::json!({"filePath": "src/new.rs"})),
+            (
+                "apply_patch",
+                serde_json::json!({"input": "*** Begin Patch\n*** Delete File: old.rs\n*** End Patch"}),
+            ),
+            ("Read", serde_json::json!({"file_path": "README.md"})),
+        ];
+        for (i, (name, input)) in calls.into_iter().enumerate() {
+            progress.push(
+                "pi",
+                &event(
+                    "pi",
+                    AgentEventPayload::ToolUse {
+                        call_id: format!("t{i}"),
+                        tool_name: name.to_string(),
+                        input,
+                    },
+                ),
+            );
+        }
+        let files: Vec<_> = progress
+            .file_changes()
+            .iter()
+            .map(|f| format!("{}{}", f.kind.marker(), f.path))
+            .collect();
+        assert_eq!(files, vec!["~src/new.rs", "~src/lib.rs", "-old.rs"]);
+        assert_eq!(
+            progress.files_footer().unwrap(),
+            "[files] 3 changed: ~src/new.rs ~src/lib.rs -old.rs"
+        );
+    }
+
+    #[test]
+    fn test_codex_commands_and_file_changes() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        let (key, e) = make_stream_event("cargo test", MessageRole::Tool, MessageKind::Message);
+        progress.push_at(&e, secs(0.0));
+        let (_, e) = make_stream_event("ok", MessageRole::Tool, MessageKind::ToolOutput);
+        progress.push_at(&e, secs(4.0));
+        let (_, e) = make_stream_event(
+            "file_change: add src/a.rs, update src/b.rs",
+            MessageRole::Tool,
+            MessageKind::Message,
+        );
+        progress.push(&key, &e);
+
+        let lines = progress.view_lines_at(ViewToggles::default(), secs(5.0));
+        assert_eq!(lines[0], "[  4.0s] ✓ command cargo test");
+        assert_eq!(lines[1], "           └ ok");
+        assert!(lines[2].contains("✓ file_change add src/a.rs, update src/b.rs"));
+        assert_eq!(lines[3], "           + src/a.rs");
+        assert_eq!(lines[4], "           ~ src/b.rs");
+    }
+
+    #[test]
+    fn test_aikit_text_deltas_merge_into_one_step() {
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        for chunk in ["Hel", "lo ", "world"] {
+            progress.push(
+                "aikit",
+                &event(
+                    "aikit",
+                    AgentEventPayload::AikitTextDelta {
+                        content: chunk.to_string(),
+                        turn_id: None,
+                    },
+                ),
+            );
+        }
+        progress.push(
+            "aikit",
+            &event(
+                "aikit",
+                AgentEventPayload::AikitTextFinal {
+                    content: "Hello world!".to_string(),
+                    turn_id: None,
+                },
+            ),
+        );
+        progress.push(
+            "aikit",
+            &event(
+                "aikit",
+                AgentEventPayload::Result {
+                    text: "Hello world!".to_string(),
+                    structured: None,
+                    session_id: None,
+                },
+            ),
+        );
+        let steps: Vec<_> = progress.steps().map(|s| s.kind.clone()).collect();
+        assert_eq!(
+            steps,
+            vec![StepKind::Message {
+                role: MessageRole::Assistant,
+                text: "Hello world!".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_token_meter_aggregates_codex_turns_and_estimates_cost() {
+        let mut progress = RunProgress::new(ProgressViewConfig {
+            model: Some("gpt-5".to_string()),
+            ..Default::default()
+        });
+        progress.push("codex", &usage_event(UsageSource::Codex, 1_000_000, 0));
+        progress.push("codex", &usage_event(UsageSource::Codex, 0, 100_000));
+        let footer = progress.token_footer().unwrap();
+        assert_eq!(
+            footer,
+            "[tokens] codex  in=1000000 out=100000 total=1100000  cost~$2.2500"
+        );
+
+        let mut progress = RunProgress::new(ProgressViewConfig {
+            pricing: None,
+            ..Default::default()
+        });
+        progress.push("claude", &usage_event(UsageSource::Claude, 10, 5));
+        progress.push("claude", &usage_event(UsageSource::Claude, 20, 7));
+        assert_eq!(
+            progress.token_footer().unwrap(),
+            "[tokens] claude  in=20 out=7 total=27"
+        );
+    }
+
+    #[test]
+    fn test_token_meter_prices_usage_by_model_and_hides_unknown_models() {
+        // No model, or one the catalog lacks: no made-up cost.
+        let mut progress = RunProgress::new(ProgressViewConfig::default());
+        progress.push("claude", &usage_event(UsageSource::Claude, 1_000_000, 0));
+        assert_eq!(progress.cost_usd(), None);
+        progress.set_model(Some("mystery-model".to_string()));
+        progress.push("claude", &usage_event(UsageSource::Claude, 1_000_000, 0));
+        assert_eq!(progress.cost_usd(), None);
+        assert!(!progress.token_footer().unwrap().contains("cost"));
+
+        // Each model's usage is priced by its own entry.
+        let mut progress = RunProgress::new(ProgressViewConfig {
+            model: Some("claude-sonnet-4-20250514".to_string()),
+            ..Default::default()
+        });
+        progress.push("codex", &usage_event(UsageSource::Codex, 1_000_000, 0));
+        progress.set_model(Some("claude-opus-4-5".to_string()));
+        progress.push("codex", &usage_event(UsageSource::Codex, 0, 100_000));
+        let cost = progress.cost_usd().unwrap();
+        assert!((cost - (3.0 + 2.5)).abs() < 1e-9, "{cost}");
+    }
+
+    #[test]
+    fn test_rows_added_counts_evicted_rows() {
+        let mut progress = RunProgress::new(ProgressViewConfig {
+            max_rows: 2,
+            ..Default::default()
+        });
+        for i in 0..5 {
+            let (key, e) = make_stream_event(
+                &format!("m{i}"),
+                MessageRole::Assistant,
+                MessageKind::Message,
+            );
+            progress.push(&key, &e);
+        }
+        assert_eq!(progress.rows_added(), 5);
+        assert_eq!(progress.steps().count(), 2);
+        progress.clear();
+        assert_eq!(progress.rows_added(), 0);
+    }
 }
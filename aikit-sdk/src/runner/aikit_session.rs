@@ -0,0 +1,681 @@
+//! In-process live sessions for the built-in aikit agent.
+//!
+//! The aikit counterpart of [`pi_session`](super::pi_session) and
+//! [`opencode_session`](super::opencode_session), minus the subprocess: a
+//! bridge thread owns an [`aikit_agent::AgentSession`] — one context, tool
+//! catalog and gateway for the whole conversation — and runs queued turns on
+//! it one after another. Events are the same canonical `Aikit*` payloads a
+//! one-shot run emits; every turn ends with a [`AgentEventPayload::Result`]
+//! and is saved to the [`SessionStore`], so a live session can later be
+//! resumed one-shot by its id (and vice versa).
+//!
+//! `interrupt` does not go through the queue: it cancels the oldest turn not
+//! yet finished — the running one, or the next queued one if it has not
+//! started — and the agent loop stops at its next safe point.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use aikit_agent::llm::openai_compat::OpenAiCompatProvider;
+use aikit_agent::{AgentInternalEvent, AgentSession, CancelHandle, ContextUsage, LlmGateway};
+use serde_json::Value;
+
+use crate::aikit_agent_adapter::{
+    convert_event, error_code, internal_events_to_turns, live_session_config,
+    session_turns_to_turns,
+};
+use crate::runner::backends::aikit;
+use crate::runner::types::{AgentEvent, AgentEventPayload, AgentEventStream, RunOptions};
+use crate::session_store::{now_rfc3339, SessionFile, SessionStore, SessionStoreError};
+
+/// Builds the gateway for a model. Called once when the session opens and
+/// again on every [`AikitControlHandle::set_model`].
+pub type GatewayFactory = Arc<dyn Fn(&str) -> Result<Box<dyn LlmGateway>, String> + Send + Sync>;
+
+/// Options for opening an aikit session.
+#[derive(Default)]
+pub struct AikitSessionOptions {
+    /// Model id. `None` = `AIKIT_MODEL` or the built-in default.
+    pub model: Option<String>,
+    /// Working directory for the agent's tools.
+    pub cwd: Option<PathBuf>,
+    /// Continue a stored session by id instead of starting a new one.
+    pub session_id: Option<String>,
+    /// Gateway per model. `None` = the OpenAI-compatible gateway configured
+    /// by `AIKIT_LLM_URL` and the API-key environment variables.
+    pub gateway: Option<GatewayFactory>,
+    /// Where turns are saved. `None` = [`SessionStore::open`].
+    pub store: Option<SessionStore>,
+}
+
+impl std::fmt::Debug for AikitSessionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AikitSessionOptions")
+            .field("model", &self.model)
+            .field("cwd", &self.cwd)
+            .field("session_id", &self.session_id)
+            .field(
+                "gateway",
+                &self.gateway.as_ref().map(|_| "<GatewayFactory>"),
+            )
+            .field(
+                "store",
+                &self
+                    .store
+                    .as_ref()
+                    .map(|s| s.sessions_dir.display().to_string()),
+            )
+            .finish()
+    }
+}
+
+/// Errors opening or driving an aikit session.
+#[derive(Debug)]
+pub enum AikitSessionError {
+    /// The bridge thread could not be spawned.
+    Runtime(String),
+    /// Configuration, the gateway, or loading the resumed session failed.
+    Connect(String),
+    /// The backend rejected an operation (e.g. no gateway for a model).
+    Backend(String),
+    /// The control channel is closed (the session has ended).
+    Closed,
+}
+
+impl std::fmt::Display for AikitSessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AikitSessionError::Runtime(e) => write!(f, "aikit session runtime error: {e}"),
+            AikitSessionError::Connect(e) => write!(f, "aikit session connect error: {e}"),
+            AikitSessionError::Backend(e) => write!(f, "aikit session error: {e}"),
+            AikitSessionError::Closed => write!(f, "aikit session control channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for AikitSessionError {}
+
+/// Commands queued from an [`AikitControlHandle`] to the bridge.
+enum ControlCmd {
+    /// New user turn, run after any turn already queued.
+    SendTurn(String),
+    /// Switch model and gateway before the next turn.
+    SetModel(String, Box<dyn LlmGateway>),
+    /// End the session once the running turn stops.
+    Disconnect,
+}
+
+/// A sync handle to drive a live aikit session. Turns and model switches are
+/// queued behind the running turn; `interrupt` takes effect immediately.
+pub struct AikitControlHandle {
+    tx: mpsc::Sender<ControlCmd>,
+    cancel: CancelHandle,
+    /// Turns submitted so far, the opening prompt included.
+    sent: AtomicU64,
+    /// Turns the bridge has finished; shared with it.
+    finished: Arc<AtomicU64>,
+    /// Set by `disconnect` so turns still queued behind it are dropped.
+    closed: Arc<AtomicBool>,
+    gateways: GatewayFactory,
+    default_model: String,
+    usage: Arc<Mutex<ContextUsage>>,
+}
+
+impl AikitControlHandle {
+    fn cmd(&self, cmd: ControlCmd) -> Result<(), AikitSessionError> {
+        self.tx.send(cmd).map_err(|_| AikitSessionError::Closed)
+    }
+
+    /// Queue a user turn on the same conversation.
+    pub fn send_turn(&self, text: impl Into<String>) -> Result<(), AikitSessionError> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        self.cmd(ControlCmd::SendTurn(text.into()))
+    }
+
+    /// Stop the running turn at its next safe point (before the next LLM
+    /// call, between streamed chunks, or before the next tool call). A turn
+    /// sent but not yet started counts as running. Later queued turns still
+    /// run; with no turn outstanding this is a no-op.
+    pub fn interrupt(&self) -> Result<(), AikitSessionError> {
+        // Turns run in order, so the oldest unfinished one is the target.
+        let next = self.finished.load(Ordering::SeqCst) + 1;
+        if next <= self.sent.load(Ordering::SeqCst) {
+            self.cancel.cancel_turn(next);
+        }
+        Ok(())
+    }
+
+    /// Use `model` from the next turn on; `None` returns to the model the
+    /// session opened with. The gateway is built here, so a model it cannot
+    /// serve is rejected without touching the session.
+    pub fn set_model(&self, model: Option<String>) -> Result<(), AikitSessionError> {
+        let model = model
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| self.default_model.clone());
+        let gateway = (self.gateways)(&model).map_err(AikitSessionError::Backend)?;
+        self.cmd(ControlCmd::SetModel(model, gateway))
+    }
+
+    /// The context's estimated size against its token budget, as of the last
+    /// completed turn.
+    pub fn get_context_usage(&self) -> Result<Value, AikitSessionError> {
+        let usage = self.usage.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        serde_json::to_value(usage).map_err(|e| AikitSessionError::Backend(e.to_string()))
+    }
+
+    /// End the session, interrupting the running turn and dropping queued
+    /// ones.
+    pub fn disconnect(&self) -> Result<(), AikitSessionError> {
+        self.closed.store(true, Ordering::SeqCst);
+        self.interrupt()?;
+        self.cmd(ControlCmd::Disconnect)
+    }
+}
+
+impl Drop for AikitControlHandle {
+    fn drop(&mut self) {
+        let _ = self.disconnect();
+    }
+}
+
+/// A live aikit session: an [`AikitControlHandle`] and the stream of
+/// canonical events. The event channel closes when the session ends; the
+/// bridge thread is joined on drop.
+pub struct AikitSession {
+    pub control: AikitControlHandle,
+    pub events: mpsc::Receiver<AgentEvent>,
+    join: Option<JoinHandle<()>>,
+}
+
+impl Drop for AikitSession {
+    fn drop(&mut self) {
+        let _ = self.control.disconnect();
+        if let Some(j) = self.join.take() {
+            let _ = j.join();
+        }
+    }
+}
+
+impl AikitSession {
+    /// Dissolve the session into its control handle and event receiver.
+    ///
+    /// The bridge thread is detached and exits when the returned handle is
+    /// dropped (its `Drop` queues a disconnect).
+    pub fn into_parts(self) -> (AikitControlHandle, mpsc::Receiver<AgentEvent>) {
+        let this = std::mem::ManuallyDrop::new(self);
+        // SAFETY: `ManuallyDrop` prevents the destructor; we read every field
+        // exactly once and handle the JoinHandle explicitly.
+        let control = unsafe { std::ptr::read(&this.control) };
+        let events = unsafe { std::ptr::read(&this.events) };
+        let join = unsafe { std::ptr::read(&this.join) };
+        if let Some(j) = join {
+            drop(j); // detach
+        }
+        (control, events)
+    }
+}
+
+/// Open a live aikit session, running `prompt` as the first turn.
+///
+/// Returns once configuration, the gateway and (when resuming) the stored
+/// session have loaded, so those failures surface synchronously as
+/// [`AikitSessionError::Connect`]; the first turn then runs in the background.
+pub fn open_aikit_session(
+    prompt: impl Into<String>,
+    options: AikitSessionOptions,
+) -> Result<AikitSession, AikitSessionError> {
+    let prompt = prompt.into();
+    let mut run_options = RunOptions::new().with_stream(true);
+    run_options.model = options.model.clone();
+    run_options.current_dir = options.cwd.clone();
+    let config = live_session_config(&run_options, options.gateway.is_some())
+        .map_err(|e| AikitSessionError::Connect(e.to_string()))?;
+
+    let gateways: GatewayFactory = options.gateway.unwrap_or_else(|| {
+        let (timeout, connect_timeout) = (config.timeout_secs, config.connect_timeout_secs);
+        Arc::new(move |_model: &str| {
+            OpenAiCompatProvider::new(timeout, connect_timeout)
+                .map(|g| Box::new(g) as Box<dyn LlmGateway>)
+                .map_err(|e| e.to_string())
+        })
+    });
+    let gateway = gateways(&config.model).map_err(AikitSessionError::Connect)?;
+
+    let store = options.store.unwrap_or_else(SessionStore::open);
+    let session_file = match &options.session_id {
+        Some(id) => store.load(id).map_err(|e| {
+            AikitSessionError::Connect(match e {
+                SessionStoreError::NotFound(id) => format!("session '{id}' not found"),
+                SessionStoreError::Parse { id, reason } => {
+                    format!("session '{id}' is unreadable: {reason}")
+                }
+                SessionStoreError::Io(e) => e.to_string(),
+            })
+        })?,
+        None => {
+            let now = now_rfc3339();
+            SessionFile {
+                session_id: uuid::Uuid::new_v4().to_string(),
+                agent: aikit::KEY.to_string(),
+                created_at: now.clone(),
+                updated_at: now,
+                cwd: config.workdir.to_string_lossy().into_owned(),
+                turns: Vec::new(),
+                title: None,
+                tag: None,
+            }
+        }
+    };
+
+    let default_model = config.model.clone();
+    let prior_turns = session_turns_to_turns(&session_file.turns);
+    let agent = AgentSession::new(config, gateway, prior_turns)
+        .map_err(|e| AikitSessionError::Connect(e.to_string()))?;
+    let cancel = agent.cancel_handle();
+    let usage = Arc::new(Mutex::new(agent.context_usage()));
+
+    let finished = Arc::new(AtomicU64::new(0));
+    let closed = Arc::new(AtomicBool::new(false));
+    let (event_tx, event_rx) = mpsc::channel::<AgentEvent>();
+    let (cmd_tx, cmd_rx) = mpsc::channel::<ControlCmd>();
+    let bridge = Bridge {
+        agent,
+        store,
+        session: session_file,
+        usage: Arc::clone(&usage),
+        finished: Arc::clone(&finished),
+        closed: Arc::clone(&closed),
+        events: event_tx,
+        seq: 0,
+    };
+    let join = thread::Builder::new()
+        .name("aikit-agent-session".into())
+        .spawn(move || bridge.run(prompt, cmd_rx))
+        .map_err(|e| AikitSessionError::Runtime(e.to_string()))?;
+
+    Ok(AikitSession {
+        control: AikitControlHandle {
+            tx: cmd_tx,
+            cancel,
+            sent: AtomicU64::new(1),
+            finished,
+            closed,
+            gateways,
+            default_model,
+            usage,
+        },
+        events: event_rx,
+        join: Some(join),
+    })
+}
+
+/// The bridge thread's state: the agent session and everything a turn
+/// writes to.
+struct Bridge {
+    agent: AgentSession,
+    store: SessionStore,
+    session: SessionFile,
+    usage: Arc<Mutex<ContextUsage>>,
+    finished: Arc<AtomicU64>,
+    closed: Arc<AtomicBool>,
+    events: mpsc::Sender<AgentEvent>,
+    seq: u64,
+}
+
+impl Bridge {
+    fn run(mut self, prompt: String, commands: mpsc::Receiver<ControlCmd>) {
+        if !self.send(
+            AgentEventStream::Stdout,
+            AgentEventPayload::SessionStarted {
+                session_id: self.session.session_id.clone(),
+            },
+        ) {
+            return;
+        }
+        if !self.run_turn(&prompt) {
+            return;
+        }
+        while let Ok(cmd) = commands.recv() {
+            match cmd {
+                ControlCmd::SendTurn(text) => {
+                    if self.closed.load(Ordering::SeqCst) || !self.run_turn(&text) {
+                        return;
+                    }
+                }
+                ControlCmd::SetModel(model, gateway) => self.agent.set_model(model, gateway),
+                ControlCmd::Disconnect => return,
+            }
+        }
+    }
+
+    /// Run one turn, save it, and close it with a `Result`. Returns `false`
+    /// once the event receiver is gone.
+    fn run_turn(&mut self, prompt: &str) -> bool {
+        let mut collected: Vec<AgentInternalEvent> = Vec::new();
+        let mut had_error = false;
+        let mut open = true;
+        let cancel = self.agent.cancel_handle();
+        let (events, seq) = (&self.events, &mut self.seq);
+        let outcome = self.agent.run_turn(prompt, |event| {
+            had_error |= matches!(event, AgentInternalEvent::Error { .. });
+            collected.push(event.clone());
+            if open && !emit(events, seq, event) {
+                // Nobody is listening: stop working for them.
+                open = false;
+                cancel.cancel();
+            }
+        });
+        if let Err(err) = outcome {
+            // As in one-shot runs: make sure a failed turn surfaced an error.
+            if !had_error {
+                let message = err.to_string();
+                let event = AgentInternalEvent::Error {
+                    code: error_code(&message),
+                    message,
+                };
+                open = open && emit(events, seq, event);
+            }
+        }
+
+        let text = collected
+            .iter()
+            .rev()
+            .find_map(|e| match e {
+                AgentInternalEvent::TextFinal { content, .. } => Some(content.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        self.session
+            .turns
+            .extend(internal_events_to_turns(prompt, &collected));
+        self.session.updated_at = now_rfc3339();
+        if let Err(e) = self.store.save(&self.session) {
+            tracing::warn!("failed to save aikit session: {e:?}");
+        }
+        let _ = self
+            .store
+            .update_index(&self.session.cwd, &self.session.session_id);
+        *self.usage.lock().unwrap_or_else(|e| e.into_inner()) = self.agent.context_usage();
+        self.finished.fetch_add(1, Ordering::SeqCst);
+
+        open && self.send(
+            AgentEventStream::Stdout,
+            AgentEventPayload::Result {
+                text,
+                structured: None,
+                session_id: Some(self.session.session_id.clone()),
+            },
+        )
+    }
+
+    fn send(&mut self, stream: AgentEventStream, payload: AgentEventPayload) -> bool {
+        send_event(&self.events, &mut self.seq, stream, payload)
+    }
+}
+
+fn emit(events: &mpsc::Sender<AgentEvent>, seq: &mut u64, event: AgentInternalEvent) -> bool {
+    let (stream, payload, _) = convert_event(event);
+    send_event(events, seq, stream, payload)
+}
+
+fn send_event(
+    events: &mpsc::Sender<AgentEvent>,
+    seq: &mut u64,
+    stream: AgentEventStream,
+    payload: AgentEventPayload,
+) -> bool {
+    let ev = AgentEvent {
+        agent_key: aikit::KEY.to_string(),
+        seq: *seq,
+        stream,
+        payload,
+    };
+    *seq += 1;
+    events.send(ev).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aikit_agent::llm::mock::{MockGateway, MockResponse};
+    use aikit_agent::llm::LlmStreamHandle;
+    use aikit_agent::{LlmError, LlmRequest, LlmResponse};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Serves canned replies and records which model each request named.
+    struct Scripted {
+        models: Arc<Mutex<Vec<String>>>,
+        inner: MockGateway,
+    }
+
+    impl LlmGateway for Scripted {
+        fn complete(&self, req: LlmRequest) -> Result<LlmResponse, LlmError> {
+            self.models.lock().unwrap().push(req.model.clone());
+            self.inner.complete(req)
+        }
+        fn stream(&self, req: LlmRequest) -> Result<LlmStreamHandle, LlmError> {
+            self.models.lock().unwrap().push(req.model.clone());
+            self.inner.stream(req)
+        }
+    }
+
+    fn factory(models: &Arc<Mutex<Vec<String>>>) -> GatewayFactory {
+        let models = Arc::clone(models);
+        Arc::new(move |model: &str| {
+            if model == "unservable" {
+                return Err(format!("no gateway for '{model}'"));
+            }
+            Ok(Box::new(Scripted {
+                models: Arc::clone(&models),
+                inner: MockGateway::new(vec![MockResponse::text(format!("from {model}"))]),
+            }) as Box<dyn LlmGateway>)
+        })
+    }
+
+    fn options(tmp: &TempDir, models: &Arc<Mutex<Vec<String>>>) -> AikitSessionOptions {
+        AikitSessionOptions {
+            model: Some("model-a".into()),
+            cwd: Some(tmp.path().to_path_buf()),
+            gateway: Some(factory(models)),
+            store: Some(SessionStore {
+                sessions_dir: tmp.path().to_path_buf(),
+            }),
+            ..AikitSessionOptions::default()
+        }
+    }
+
+    fn until_result(events: &mpsc::Receiver<AgentEvent>) -> Vec<AgentEventPayload> {
+        let mut out = Vec::new();
+        loop {
+            let ev = events
+                .recv_timeout(Duration::from_secs(10))
+                .expect("event before the turn's result");
+            let done = matches!(ev.payload, AgentEventPayload::Result { .. });
+            out.push(ev.payload);
+            if done {
+                return out;
+            }
+        }
+    }
+
+    fn result_text(payloads: &[AgentEventPayload]) -> (String, String) {
+        match payloads.last() {
+            Some(AgentEventPayload::Result {
+                text,
+                session_id: Some(id),
+                ..
+            }) => (text.clone(), id.clone()),
+            other => panic!("expected a result, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn session_runs_queued_turns_switches_models_and_saves_each_turn() {
+        let tmp = TempDir::new().unwrap();
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let session = open_aikit_session("hi", options(&tmp, &models)).expect("open");
+
+        let first = until_result(&session.events);
+        assert!(matches!(
+            &first[0],
+            AgentEventPayload::SessionStarted { .. }
+        ));
+        let (text, id) = result_text(&first);
+        assert_eq!(text, "from model-a");
+
+        session.control.set_model(Some("model-b".into())).unwrap();
+        session.control.send_turn("next").unwrap();
+        let (text, _) = result_text(&until_result(&session.events));
+        assert_eq!(text, "from model-b");
+
+        session.control.set_model(None).unwrap();
+        session.control.send_turn("back").unwrap();
+        let (text, _) = result_text(&until_result(&session.events));
+        assert_eq!(text, "from model-a");
+        assert_eq!(
+            *models.lock().unwrap(),
+            vec!["model-a", "model-b", "model-a"]
+        );
+
+        let usage = session.control.get_context_usage().unwrap();
+        assert_eq!(usage["turns"], 6);
+        assert!(usage["estimated_tokens"].as_u64().unwrap() > 0);
+        assert!(usage["total_budget"].as_u64().is_some());
+
+        let saved = SessionStore {
+            sessions_dir: tmp.path().to_path_buf(),
+        }
+        .load(&id)
+        .unwrap();
+        assert_eq!(saved.agent, "aikit");
+        assert_eq!(saved.turns.len(), 6);
+
+        session.control.disconnect().unwrap();
+        while session.events.recv_timeout(Duration::from_secs(10)).is_ok() {}
+    }
+
+    #[test]
+    fn resuming_continues_the_stored_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let (_, id) = {
+            let session = open_aikit_session("first", options(&tmp, &models)).unwrap();
+            result_text(&until_result(&session.events))
+        };
+
+        let resumed = open_aikit_session(
+            "second",
+            AikitSessionOptions {
+                session_id: Some(id.clone()),
+                ..options(&tmp, &models)
+            },
+        )
+        .unwrap();
+        let (_, resumed_id) = result_text(&until_result(&resumed.events));
+        assert_eq!(resumed_id, id);
+        let usage = resumed.control.get_context_usage().unwrap();
+        assert_eq!(usage["turns"], 4);
+    }
+
+    #[test]
+    fn open_and_set_model_fail_synchronously() {
+        let tmp = TempDir::new().unwrap();
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let err = open_aikit_session(
+            "hi",
+            AikitSessionOptions {
+                session_id: Some("missing".into()),
+                ..options(&tmp, &models)
+            },
+        )
+        .err()
+        .expect("unknown session must fail to open");
+        assert!(matches!(err, AikitSessionError::Connect(_)), "{err}");
+
+        let session = open_aikit_session("hi", options(&tmp, &models)).unwrap();
+        assert!(matches!(
+            session.control.set_model(Some("unservable".into())),
+            Err(AikitSessionError::Backend(_))
+        ));
+    }
+
+    /// Answers each request only once the test lets it through.
+    struct Gated {
+        gate: Arc<Mutex<mpsc::Receiver<()>>>,
+        inner: MockGateway,
+    }
+
+    impl LlmGateway for Gated {
+        fn complete(&self, req: LlmRequest) -> Result<LlmResponse, LlmError> {
+            self.gate.lock().unwrap().recv().unwrap();
+            self.inner.complete(req)
+        }
+        fn stream(&self, req: LlmRequest) -> Result<LlmStreamHandle, LlmError> {
+            self.gate.lock().unwrap().recv().unwrap();
+            self.inner.stream(req)
+        }
+    }
+
+    #[test]
+    fn interrupt_reaches_a_sent_turn_before_it_starts() {
+        let tmp = TempDir::new().unwrap();
+        let (release, gate) = mpsc::channel();
+        let gate = Arc::new(Mutex::new(gate));
+        let mut opts = options(&tmp, &Arc::new(Mutex::new(Vec::new())));
+        opts.gateway = Some(Arc::new(move |_: &str| {
+            Ok(Box::new(Gated {
+                gate: Arc::clone(&gate),
+                inner: MockGateway::new(vec![
+                    MockResponse::text("one"),
+                    MockResponse::text("two"),
+                    MockResponse::text("three"),
+                ]),
+            }) as Box<dyn LlmGateway>)
+        }));
+        let session = open_aikit_session("hi", opts).unwrap();
+        release.send(()).unwrap();
+        until_result(&session.events);
+
+        // Whether or not the bridge has picked the turn up yet, it is the
+        // one interrupted, and it cannot finish first: its request is held.
+        session.control.send_turn("again").unwrap();
+        session.control.interrupt().unwrap();
+        release.send(()).unwrap();
+        let finish = |payloads: &[AgentEventPayload]| {
+            payloads
+                .iter()
+                .find_map(|p| match p {
+                    AgentEventPayload::AikitStepFinish { finish_reason, .. } => {
+                        Some(finish_reason.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+        assert_eq!(finish(&until_result(&session.events)), "interrupted");
+
+        session.control.send_turn("third").unwrap();
+        release.send(()).unwrap();
+        assert_eq!(finish(&until_result(&session.events)), "stop");
+    }
+
+    #[test]
+    fn interrupt_with_no_turn_running_is_harmless() {
+        let tmp = TempDir::new().unwrap();
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let session = open_aikit_session("hi", options(&tmp, &models)).unwrap();
+        until_result(&session.events);
+        session.control.interrupt().unwrap();
+        session.control.send_turn("again").unwrap();
+        let payloads = until_result(&session.events);
+        assert!(payloads.iter().any(|p| matches!(
+            p,
+            AgentEventPayload::AikitStepFinish { finish_reason, .. } if finish_reason == "stop"
+        )));
+    }
+}
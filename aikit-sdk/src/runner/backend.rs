@@ -590,4 +590,10 @@ mod tests {
             "OpenCode.passive_capture MUST be false without agent-adapters + opencode features"
         );
     }
+
+    #[test]
+    fn opencode_is_bidirectional_via_its_http_server() {
+        let c = Backend::OpenCode.capabilities();
+        assert!(c.bidirectional && c.interruptible && c.structured_tools);
+    }
 }
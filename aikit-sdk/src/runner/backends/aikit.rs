@@ -17,7 +17,7 @@ pub(crate) const KEY: &str = "aikit";
 /// In-process — no external binary to probe.
 pub(crate) const BINARY_CANDIDATES: &[&str] = &[];
 
-pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
+const BASE_CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_bidirectional()
     .with_structured_tools()
     .with_file_changes()
@@ -28,6 +28,16 @@ pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_context_compression()
     .with_supports_tool_policy();
 
+// History (spec 008): `history::aikit::AikitHistory` reads and annotates the
+// agent's own `SessionStore` whenever the `history` feature is on.
+#[cfg(feature = "history")]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES
+    .with_history_store()
+    .with_history_mutations();
+
+#[cfg(not(feature = "history"))]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES;
+
 /// aikit emits canonical events directly; there is no line Dialect to decode.
 pub(crate) fn decode(
     _value: &serde_json::Value,
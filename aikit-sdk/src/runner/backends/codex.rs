@@ -21,7 +21,7 @@ pub(crate) const BINARY_CANDIDATES: &[&str] = &["codex"];
 // `passive_capture` flips on only when both `agent-adapters` and the
 // `codex` adapter feature are enabled. Spec 010 §17.2.
 #[cfg(all(feature = "agent-adapters", feature = "codex"))]
-pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
+const BASE_CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_bidirectional()
     .with_structured_tools()
     .with_reasoning()
@@ -31,7 +31,7 @@ pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_passive_capture();
 
 #[cfg(not(all(feature = "agent-adapters", feature = "codex")))]
-pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
+const BASE_CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_bidirectional()
     .with_structured_tools()
     .with_reasoning()
@@ -39,6 +39,15 @@ pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_interruptible()
     .with_resumable_sessions();
 
+// History (spec 008): `history_store` follows the `history` feature, which
+// compiles in `history::codex::CodexHistory`. Rollouts carry no rename/tag
+// metadata, so `history_mutations` stays off — the reader is read-only.
+#[cfg(feature = "history")]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES.with_history_store();
+
+#[cfg(not(feature = "history"))]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES;
+
 const SPEC: ArgvSpec = ArgvSpec {
     binary: "codex",
     model_flag: "-m",
@@ -17,16 +17,30 @@ pub(crate) const KEY: &str = "opencode";
 
 pub(crate) const BINARY_CANDIDATES: &[&str] = &["opencode", "opencode-desktop"];
 
+// bidirectional / interruptible: `opencode_session` drives `opencode serve`
+// over HTTP — follow-up turns post to the same session and interrupt is the
+// server's `/abort`; the one-shot `run` path keeps the process-group kill.
+const LIVE_CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
+    .with_bidirectional()
+    .with_structured_tools()
+    .with_interruptible();
+
 // `passive_capture` flips on only when both `agent-adapters` and the
 // `opencode` adapter feature are enabled. Spec 010 §17.2.
 #[cfg(all(feature = "agent-adapters", feature = "opencode"))]
-pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
-    .with_structured_tools()
-    .with_passive_capture();
+const BASE_CAPABILITIES: BackendCapabilities = LIVE_CAPABILITIES.with_passive_capture();
 
 #[cfg(not(all(feature = "agent-adapters", feature = "opencode")))]
-pub(crate) const CAPABILITIES: BackendCapabilities =
-    BackendCapabilities::NONE.with_structured_tools();
+const BASE_CAPABILITIES: BackendCapabilities = LIVE_CAPABILITIES;
+
+// History (spec 008): the SQLite-backed `history::opencode::OpenCodeHistory`
+// needs both `history` and the `opencode` feature (which brings rusqlite).
+// Read-only — OpenCode owns its database — so `history_mutations` stays off.
+#[cfg(all(feature = "history", feature = "opencode"))]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES.with_history_store();
+
+#[cfg(not(all(feature = "history", feature = "opencode")))]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES;
 
 const SPEC: ArgvSpec = ArgvSpec {
     binary: "opencode",
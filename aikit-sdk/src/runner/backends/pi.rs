@@ -33,13 +33,22 @@ pub(crate) const BINARY_CANDIDATES: &[&str] = &["pi"];
 // `file_changes`/`context_compression` are intentionally `false` for v1
 // (edits fold into `ToolResult`; compaction surfaces as a status message).
 // `false -> true` is a non-breaking change later.
-pub(crate) const CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
+const BASE_CAPABILITIES: BackendCapabilities = BackendCapabilities::NONE
     .with_bidirectional()
     .with_structured_tools()
     .with_reasoning()
     .with_interruptible()
     .with_resumable_sessions();
 
+// History (spec 008): `history::pi::PiHistory` reads Pi's session files
+// whenever the `history` feature is on. Read-only (no tag field in Pi's
+// format), so `history_mutations` stays off.
+#[cfg(feature = "history")]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES.with_history_store();
+
+#[cfg(not(feature = "history"))]
+pub(crate) const CAPABILITIES: BackendCapabilities = BASE_CAPABILITIES;
+
 /// Keywords that signal a quota / rate-limit condition, matched
 /// case-insensitively against a line's text. No Pi-specific error object is
 /// known upstream, so the generic OpenAI/Anthropic vocabulary is reused.
@@ -841,6 +850,9 @@ mod tests {
         assert!(!c.subagents);
         assert!(!c.passive_capture);
         assert!(!c.supports_tool_policy);
+        // Session files are readable via `history::pi`; never mutated.
+        assert_eq!(c.history_store, cfg!(feature = "history"));
+        assert!(!c.history_mutations);
     }
 
     /// Each `true` capability is backed by a decoder fixture that exercises it,
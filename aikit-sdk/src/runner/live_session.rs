@@ -128,6 +128,50 @@ impl LiveSession for super::pi_session::PiControlHandle {
     // concrete handle, not on the trait (mirrors Codex's `steer`).
 }
 
+impl LiveSession for super::aikit_session::AikitControlHandle {
+    fn send_turn(&self, text: String) -> Result<(), ControlError> {
+        super::aikit_session::AikitControlHandle::send_turn(self, text)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn interrupt(&self) -> Result<(), ControlError> {
+        super::aikit_session::AikitControlHandle::interrupt(self)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn disconnect(&self) -> Result<(), ControlError> {
+        super::aikit_session::AikitControlHandle::disconnect(self)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn set_model(&self, model: Option<String>) -> Result<(), ControlError> {
+        super::aikit_session::AikitControlHandle::set_model(self, model)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn get_context_usage(&self) -> Result<serde_json::Value, ControlError> {
+        super::aikit_session::AikitControlHandle::get_context_usage(self)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+}
+
+impl LiveSession for super::opencode_session::OpenCodeControlHandle {
+    fn send_turn(&self, text: String) -> Result<(), ControlError> {
+        super::opencode_session::OpenCodeControlHandle::send_turn(self, text)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn interrupt(&self) -> Result<(), ControlError> {
+        super::opencode_session::OpenCodeControlHandle::interrupt(self)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn disconnect(&self) -> Result<(), ControlError> {
+        super::opencode_session::OpenCodeControlHandle::disconnect(self)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    fn set_model(&self, model: Option<String>) -> Result<(), ControlError> {
+        super::opencode_session::OpenCodeControlHandle::set_model(self, model)
+            .map_err(|e| ControlError::Backend(e.to_string()))
+    }
+    // get_context_usage falls through to the default `Unsupported`: the
+    // server reports usage per step, not a context-window snapshot.
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
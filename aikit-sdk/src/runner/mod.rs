@@ -1,4 +1,6 @@
 pub mod approval;
+// Always compiled: the in-process agent needs no optional dependency.
+pub mod aikit_session;
 pub mod argv;
 pub mod availability;
 pub mod backend;
@@ -11,6 +13,9 @@ pub mod codex_session;
 pub mod invocation;
 #[cfg(any(feature = "claude-control", feature = "codex-app-server"))]
 pub mod live_session;
+// Always compiled for the same reason as `pi_session`: std threads plus the
+// SDK's non-optional blocking `reqwest` client.
+pub mod opencode_session;
 // Always compiled: unlike the Claude/Codex sessions this bridge pulls no
 // optional dependency (no async SDK, no tokio feature) — it drives Pi's plain
 // JSONL-over-stdio RPC with std threads. The `LiveSession` impl lives in
@@ -27,6 +32,10 @@ pub use types::{
     UsageSource,
 };
 
+pub use aikit_session::{
+    open_aikit_session, AikitControlHandle, AikitSession, AikitSessionError, AikitSessionOptions,
+    GatewayFactory,
+};
 pub use argv::{is_runnable, runnable_agents};
 pub use availability::{get_agent_status, get_installed_agents, is_agent_available};
 pub use backend::{Backend, Decoded};
@@ -46,6 +55,10 @@ pub use invocation::{
     exit_code_for, format_capabilities, resolve_envelope, sandbox_env_for, InvocationEnvelope,
     UnsupportedKnob,
 };
+pub use opencode_session::{
+    open_opencode_session, OpenCodeControlHandle, OpenCodeSession, OpenCodeSessionError,
+    OpenCodeSessionOptions,
+};
 pub use pi_session::{
     open_pi_session, PiControlHandle, PiSession, PiSessionError, PiSessionOptions,
 };
@@ -273,9 +286,11 @@ pub fn run_builtin_agent(
                 crate::aikit_agent_adapter::run_aikit_agent(prompt, &options, None, |event| {
                     collected.push(event);
                 })?;
-            let mut progress = crate::run_progress::RunProgress::new(
-                crate::run_progress::ProgressViewConfig::default(),
-            );
+            let mut config = sink.view_config();
+            if config.model.is_none() {
+                config.model = options.model.clone();
+            }
+            let mut progress = crate::run_progress::RunProgress::new(config);
             for event in &collected {
                 progress.push("aikit", event);
                 sink.on_progress(&progress);
@@ -1275,6 +1290,7 @@ mod tests {
                 AgentEventPayload::AikitToolUse { .. } => "aikit_tool_use",
                 AgentEventPayload::AikitToolResult { .. } => "aikit_tool_result",
                 AgentEventPayload::AikitSubagentSpawn { .. } => "aikit_subagent_spawn",
+                AgentEventPayload::AikitSubagentEvent { .. } => "aikit_subagent_event",
                 AgentEventPayload::AikitSubagentResult { .. } => "aikit_subagent_result",
                 AgentEventPayload::AikitContextCompressed { .. } => "aikit_context_compressed",
                 AgentEventPayload::AikitStepFinish { .. } => "aikit_step_finish",
@@ -0,0 +1,1180 @@
+//! Bidirectional OpenCode sessions over `opencode serve`'s HTTP API.
+//!
+//! The OpenCode analogue of [`pi_session`](super::pi_session): a dedicated
+//! bridge thread owns an OpenCode server — spawned as `opencode serve` on a
+//! free loopback port, or an already-running one the caller points at — and
+//! one OpenCode session on it. The server's `/event` SSE stream is mapped to
+//! canonical [`AgentEvent`]s while an [`OpenCodeControlHandle`] queues
+//! follow-up turns, interrupts and model switches.
+//!
+//! Like Pi this module is pure-std plus `reqwest::blocking`: every HTTP call
+//! happens on the bridge thread or a per-turn worker thread, never on the
+//! caller's thread, so opening a session from inside an async handler is safe.
+//!
+//! Each turn is a `POST /session/{id}/message` on its own worker thread (the
+//! endpoint answers when the turn completes); progress arrives on the event
+//! stream, which ends the turn with `session.idle`. Interrupt is
+//! `POST /session/{id}/abort`. OpenCode takes the model per message, so
+//! `set_model` applies from the next turn and `None` returns to the server's
+//! configured default.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, ChildStderr, Command, Stdio};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::runner::backends::opencode;
+use crate::runner::transport::subprocess::kill_process_group;
+use crate::runner::types::{
+    AgentEvent, AgentEventPayload, AgentEventStream, MessageKind, MessagePhase, MessageRole,
+    StreamMessage,
+};
+
+/// How long [`open_opencode_session`] waits for a spawned `opencode serve` to
+/// answer HTTP before surfacing a [`OpenCodeSessionError::Connect`]. OpenCode
+/// installs providers and plugins on first start, which can take a while.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for the short control requests (session creation, abort).
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Options for opening an OpenCode session.
+#[derive(Clone, Debug, Default)]
+pub struct OpenCodeSessionOptions {
+    /// Model as `provider/id` (e.g. `anthropic/claude-sonnet-4`). `None` =
+    /// the server's configured default.
+    pub model: Option<String>,
+    /// Working directory for a spawned `opencode serve`. An attached server
+    /// keeps its own directory.
+    pub cwd: Option<PathBuf>,
+    /// Base URL of a running `opencode serve` to attach to instead of
+    /// spawning one (e.g. `http://127.0.0.1:4096`).
+    pub server_url: Option<String>,
+    /// Continue an existing OpenCode session by id instead of creating one.
+    pub session_id: Option<String>,
+}
+
+/// Errors opening or driving an OpenCode session.
+#[derive(Debug)]
+pub enum OpenCodeSessionError {
+    /// The bridge thread could not be spawned.
+    Runtime(String),
+    /// Spawning or reaching the server, or creating the session, failed.
+    Connect(String),
+    /// The backend rejected an operation (e.g. `set_model` without a provider).
+    Backend(String),
+    /// The control channel is closed (the session has ended).
+    Closed,
+}
+
+impl std::fmt::Display for OpenCodeSessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenCodeSessionError::Runtime(e) => write!(f, "opencode session runtime error: {e}"),
+            OpenCodeSessionError::Connect(e) => write!(f, "opencode session connect error: {e}"),
+            OpenCodeSessionError::Backend(e) => write!(f, "opencode session error: {e}"),
+            OpenCodeSessionError::Closed => write!(f, "opencode session control channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for OpenCodeSessionError {}
+
+/// Commands forwarded from an [`OpenCodeControlHandle`] to the bridge.
+#[derive(Debug)]
+enum ControlCmd {
+    /// New user turn on the same session.
+    SendTurn(String),
+    /// Abort the in-flight turn (`POST /session/{id}/abort`).
+    Interrupt,
+    /// Model for the following turns; `None` = the server default.
+    SetModel(Option<String>),
+    /// End the session.
+    Disconnect,
+}
+
+/// One unit multiplexed over the bridge's inbound channel.
+#[derive(Debug)]
+enum Msg {
+    /// A parsed `/event` payload for this session.
+    Event(Value),
+    /// The event stream closed (the server went away).
+    StreamEnded,
+    /// A turn's `POST /message` failed.
+    TurnFailed(String),
+    /// A line of the spawned server's stderr.
+    Stderr(String),
+    Cmd(ControlCmd),
+}
+
+/// A sync handle to drive a live OpenCode session. Methods queue a command and
+/// return once it is on the channel; the bridge acts on each asynchronously.
+pub struct OpenCodeControlHandle {
+    tx: mpsc::Sender<Msg>,
+}
+
+impl OpenCodeControlHandle {
+    fn cmd(&self, cmd: ControlCmd) -> Result<(), OpenCodeSessionError> {
+        self.tx
+            .send(Msg::Cmd(cmd))
+            .map_err(|_| OpenCodeSessionError::Closed)
+    }
+
+    /// Send a follow-up user turn on the same session.
+    pub fn send_turn(&self, text: impl Into<String>) -> Result<(), OpenCodeSessionError> {
+        self.cmd(ControlCmd::SendTurn(text.into()))
+    }
+
+    /// Abort the in-flight turn. The session stays open for further turns.
+    pub fn interrupt(&self) -> Result<(), OpenCodeSessionError> {
+        self.cmd(ControlCmd::Interrupt)
+    }
+
+    /// Use `provider/id` for the following turns; `None` returns to the
+    /// server's default. A model without a provider is rejected without
+    /// sending, since OpenCode addresses models by both.
+    pub fn set_model(&self, model: Option<String>) -> Result<(), OpenCodeSessionError> {
+        let model = model
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+        if let Some(m) = &model {
+            split_model(m).ok_or_else(|| {
+                OpenCodeSessionError::Backend(format!(
+                    "opencode set_model requires 'provider/id'; got '{m}'"
+                ))
+            })?;
+        }
+        self.cmd(ControlCmd::SetModel(model))
+    }
+
+    /// End the session. A server this session spawned is shut down; an
+    /// attached server keeps running.
+    pub fn disconnect(&self) -> Result<(), OpenCodeSessionError> {
+        self.cmd(ControlCmd::Disconnect)
+    }
+}
+
+impl Drop for OpenCodeControlHandle {
+    fn drop(&mut self) {
+        // The bridge and its worker threads hold sender clones, so the channel
+        // never closes on its own; queue a disconnect so teardown happens.
+        let _ = self.tx.send(Msg::Cmd(ControlCmd::Disconnect));
+    }
+}
+
+/// A live OpenCode session: an [`OpenCodeControlHandle`] and the stream of
+/// canonical events. The event channel closes when the session ends; the
+/// bridge thread is joined on drop.
+pub struct OpenCodeSession {
+    pub control: OpenCodeControlHandle,
+    pub events: mpsc::Receiver<AgentEvent>,
+    join: Option<JoinHandle<()>>,
+}
+
+impl Drop for OpenCodeSession {
+    fn drop(&mut self) {
+        let _ = self.control.disconnect();
+        if let Some(j) = self.join.take() {
+            let _ = j.join();
+        }
+    }
+}
+
+impl OpenCodeSession {
+    /// Dissolve the session into its control handle and event receiver.
+    ///
+    /// The bridge thread is detached and exits when the returned handle is
+    /// dropped (its `Drop` queues a disconnect).
+    pub fn into_parts(self) -> (OpenCodeControlHandle, mpsc::Receiver<AgentEvent>) {
+        let this = std::mem::ManuallyDrop::new(self);
+        // SAFETY: `ManuallyDrop` prevents the destructor; we read every field
+        // exactly once and handle the JoinHandle explicitly.
+        let control = unsafe { std::ptr::read(&this.control) };
+        let events = unsafe { std::ptr::read(&this.events) };
+        let join = unsafe { std::ptr::read(&this.join) };
+        if let Some(j) = join {
+            drop(j); // detach
+        }
+        (control, events)
+    }
+}
+
+/// Open a bidirectional OpenCode session, sending `prompt` as the first turn.
+///
+/// Blocks until the server answers, the session exists and the event stream
+/// is connected, so spawn and connection failures surface synchronously as
+/// [`OpenCodeSessionError::Connect`].
+pub fn open_opencode_session(
+    prompt: impl Into<String>,
+    options: OpenCodeSessionOptions,
+) -> Result<OpenCodeSession, OpenCodeSessionError> {
+    if let Some(m) = &options.model {
+        if split_model(m).is_none() {
+            return Err(OpenCodeSessionError::Connect(format!(
+                "opencode models are 'provider/id'; got '{m}'"
+            )));
+        }
+    }
+    let prompt = prompt.into();
+    let (event_tx, event_rx) = mpsc::channel::<AgentEvent>();
+    let (msg_tx, msg_rx) = mpsc::channel::<Msg>();
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+    let bridge_tx = msg_tx.clone();
+
+    let join = thread::Builder::new()
+        .name("aikit-opencode-session".into())
+        .spawn(move || run_session(prompt, options, event_tx, bridge_tx, msg_rx, ready_tx))
+        .map_err(|e| OpenCodeSessionError::Runtime(e.to_string()))?;
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(OpenCodeSession {
+            control: OpenCodeControlHandle { tx: msg_tx },
+            events: event_rx,
+            join: Some(join),
+        }),
+        Ok(Err(msg)) => {
+            let _ = join.join();
+            Err(OpenCodeSessionError::Connect(msg))
+        }
+        Err(_) => {
+            let _ = join.join();
+            Err(OpenCodeSessionError::Connect(
+                "opencode session thread terminated before ready".to_string(),
+            ))
+        }
+    }
+}
+
+/// `provider/id` → (`providerID`, `modelID`).
+fn split_model(model: &str) -> Option<(&str, &str)> {
+    model
+        .split_once('/')
+        .filter(|(provider, id)| !provider.is_empty() && !id.is_empty())
+}
+
+/// The HTTP side of a session: the server's base URL and the session on it.
+#[derive(Clone)]
+struct Server {
+    base: String,
+    session_id: String,
+    /// No overall timeout: turns and the event stream run for as long as
+    /// the agent works. Control requests set their own.
+    client: reqwest::blocking::Client,
+}
+
+impl Server {
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    fn session_url(&self, suffix: &str) -> String {
+        self.url(&format!("/session/{}{}", self.session_id, suffix))
+    }
+
+    /// `POST /session/{id}/message`; returns once OpenCode finishes the turn.
+    fn send_message(&self, text: &str, model: Option<&str>) -> Result<(), String> {
+        let resp = self
+            .client
+            .post(self.session_url("/message"))
+            .json(&message_body(text, model))
+            .send()
+            .map_err(|e| format!("failed to send turn: {e}"))?;
+        let status = resp.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = resp.text().unwrap_or_default();
+            Err(format!(
+                "opencode rejected the turn (HTTP {status}): {body}"
+            ))
+        }
+    }
+
+    fn abort(&self) -> Result<(), String> {
+        let resp = self
+            .client
+            .post(self.session_url("/abort"))
+            .timeout(CONTROL_TIMEOUT)
+            .send()
+            .map_err(|e| format!("failed to abort: {e}"))?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("opencode rejected abort (HTTP {})", resp.status()))
+        }
+    }
+}
+
+/// The `POST /session/{id}/message` body for one text turn.
+fn message_body(text: &str, model: Option<&str>) -> Value {
+    let mut body = json!({ "parts": [{ "type": "text", "text": text }] });
+    if let Some((provider, id)) = model.and_then(split_model) {
+        body["model"] = json!({ "providerID": provider, "modelID": id });
+    }
+    body
+}
+
+/// The bridge body: bring the server and session up, report readiness, then
+/// multiplex events and commands until the session ends.
+fn run_session(
+    prompt: String,
+    options: OpenCodeSessionOptions,
+    event_tx: mpsc::Sender<AgentEvent>,
+    msg_tx: mpsc::Sender<Msg>,
+    msg_rx: mpsc::Receiver<Msg>,
+    ready_tx: mpsc::Sender<Result<(), String>>,
+) {
+    let mut child: Option<Arc<Mutex<Child>>> = None;
+    let connected = connect(&options, &msg_tx, &mut child);
+    let server = match connected {
+        Ok(server) => server,
+        Err(msg) => {
+            let _ = ready_tx.send(Err(msg));
+            if let Some(child) = &child {
+                kill_process_group(child);
+            }
+            return;
+        }
+    };
+
+    let mut model = options.model.clone();
+    spawn_turn(&server, prompt, model.clone(), &msg_tx);
+    let _ = ready_tx.send(Ok(()));
+
+    let mut seq: u64 = 0;
+    let mut state = MapState::default();
+    // A turn is in flight from `send_turn` until `session.idle`; disconnecting
+    // mid-turn aborts it so an attached server does not keep working.
+    let mut busy = true;
+    loop {
+        match msg_rx.recv() {
+            Ok(Msg::Event(v)) => {
+                if v["type"] == "session.idle" {
+                    busy = false;
+                }
+                for (stream, payload) in map_event(&v, &mut state, seq) {
+                    if !send_event(&event_tx, &mut seq, stream, payload) {
+                        return shutdown(&server, busy, child);
+                    }
+                }
+            }
+            Ok(Msg::StreamEnded) => break,
+            Ok(Msg::TurnFailed(e)) | Ok(Msg::Stderr(e)) => {
+                if !emit_stderr(&event_tx, &mut seq, e) {
+                    return shutdown(&server, busy, child);
+                }
+            }
+            Ok(Msg::Cmd(ControlCmd::SendTurn(t))) => {
+                busy = true;
+                spawn_turn(&server, t, model.clone(), &msg_tx);
+            }
+            Ok(Msg::Cmd(ControlCmd::Interrupt)) => {
+                if let Err(e) = server.abort() {
+                    let _ = emit_stderr(&event_tx, &mut seq, e);
+                }
+            }
+            Ok(Msg::Cmd(ControlCmd::SetModel(m))) => model = m,
+            Ok(Msg::Cmd(ControlCmd::Disconnect)) | Err(_) => break,
+        }
+    }
+    shutdown(&server, busy, child);
+}
+
+/// Abort an unfinished turn, then stop a spawned server. The event reader
+/// thread ends with the server's stream; for an attached server it is left to
+/// exit on its next event, when it finds the bridge gone.
+fn shutdown(server: &Server, busy: bool, child: Option<Arc<Mutex<Child>>>) {
+    if busy {
+        let _ = server.abort();
+    }
+    if let Some(child) = child {
+        kill_process_group(&child);
+    }
+}
+
+/// Reach (or spawn) the server, create or attach the session, and subscribe
+/// to its events. `child` is set as soon as a server process exists so the
+/// caller can clean it up on failure.
+fn connect(
+    options: &OpenCodeSessionOptions,
+    msg_tx: &mpsc::Sender<Msg>,
+    child: &mut Option<Arc<Mutex<Child>>>,
+) -> Result<Server, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()
+        .map_err(|e| format!("failed to build HTTP client: {e}"))?;
+
+    let base = match &options.server_url {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => {
+            let (process, base) = spawn_server(options)?;
+            let process = Arc::new(Mutex::new(process));
+            *child = Some(Arc::clone(&process));
+            wait_ready(&client, &base, &process, msg_tx)?;
+            base
+        }
+    };
+
+    let session_id = match &options.session_id {
+        Some(id) => id.clone(),
+        None => create_session(&client, &base)?,
+    };
+    let server = Server {
+        base,
+        session_id,
+        client,
+    };
+    subscribe(&server, msg_tx.clone())?;
+    Ok(server)
+}
+
+/// Spawn `opencode serve` on a free loopback port.
+fn spawn_server(options: &OpenCodeSessionOptions) -> Result<(Child, String), String> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .map(|a| a.port())
+        .map_err(|e| format!("no free port for opencode serve: {e}"))?;
+
+    let program = opencode::BINARY_CANDIDATES[0];
+    let mut cmd = Command::new(crate::command_resolve::resolve_command(program));
+    cmd.args([
+        "serve",
+        "--hostname",
+        "127.0.0.1",
+        "--port",
+        &port.to_string(),
+    ])
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped());
+    if let Some(dir) = &options.cwd {
+        cmd.current_dir(dir);
+    }
+    // Own process group, as in the one-shot subprocess transport, so
+    // teardown also reaps tool subprocesses the server spawned.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let child = cmd
+        .spawn()
+        .map_err(|e| format!("failed to spawn `{program} serve`: {e}"))?;
+    Ok((child, format!("http://127.0.0.1:{port}")))
+}
+
+/// Poll the spawned server until it answers, forwarding its stderr.
+fn wait_ready(
+    client: &reqwest::blocking::Client,
+    base: &str,
+    child: &Arc<Mutex<Child>>,
+    msg_tx: &mpsc::Sender<Msg>,
+) -> Result<(), String> {
+    if let Some(stderr) = child
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .stderr
+        .take()
+    {
+        spawn_stderr_reader(stderr, msg_tx.clone());
+    }
+    let deadline = Instant::now() + READINESS_TIMEOUT;
+    loop {
+        if let Ok(Some(status)) = child.lock().unwrap_or_else(|e| e.into_inner()).try_wait() {
+            return Err(format!(
+                "opencode serve exited before it was ready ({status})"
+            ));
+        }
+        let probe = client
+            .get(format!("{base}/session"))
+            .timeout(Duration::from_secs(2))
+            .send();
+        if matches!(probe, Ok(ref r) if r.status().is_success()) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err("opencode serve did not answer within the readiness timeout".to_string());
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn create_session(client: &reqwest::blocking::Client, base: &str) -> Result<String, String> {
+    let resp = client
+        .post(format!("{base}/session"))
+        .timeout(CONTROL_TIMEOUT)
+        .json(&json!({}))
+        .send()
+        .map_err(|e| format!("failed to reach opencode at {base}: {e}"))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!(
+            "opencode could not create a session (HTTP {status})"
+        ));
+    }
+    let body: Value = resp
+        .json()
+        .map_err(|e| format!("opencode returned an invalid session: {e}"))?;
+    body["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "opencode returned a session without an id".to_string())
+}
+
+/// Connect to `/event` and forward this session's events from a reader thread.
+fn subscribe(server: &Server, tx: mpsc::Sender<Msg>) -> Result<(), String> {
+    let resp = server
+        .client
+        .get(server.url("/event"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .map_err(|e| format!("failed to subscribe to opencode events: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "opencode event stream refused (HTTP {})",
+            resp.status()
+        ));
+    }
+    let session_id = server.session_id.clone();
+    thread::Builder::new()
+        .name("aikit-opencode-session-events".into())
+        .spawn(move || {
+            read_sse(resp, |event| {
+                if event_session_id(&event) != Some(session_id.as_str()) {
+                    return true;
+                }
+                tx.send(Msg::Event(event)).is_ok()
+            });
+            let _ = tx.send(Msg::StreamEnded);
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Run one turn on a worker thread; a failure comes back as `TurnFailed`.
+fn spawn_turn(server: &Server, text: String, model: Option<String>, tx: &mpsc::Sender<Msg>) {
+    let server = server.clone();
+    let tx = tx.clone();
+    let spawned = thread::Builder::new()
+        .name("aikit-opencode-session-turn".into())
+        .spawn(move || {
+            if let Err(e) = server.send_message(&text, model.as_deref()) {
+                let _ = tx.send(Msg::TurnFailed(e));
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("failed to spawn opencode turn thread: {e}");
+    }
+}
+
+fn spawn_stderr_reader(stderr: ChildStderr, tx: mpsc::Sender<Msg>) {
+    let _ = thread::Builder::new()
+        .name("aikit-opencode-session-stderr".into())
+        .spawn(move || {
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(Msg::Stderr(line)).is_err() {
+                    break;
+                }
+            }
+        });
+}
+
+/// Parse a `text/event-stream` body, calling `on_event` with each `data:`
+/// payload that is JSON, until the stream ends or `on_event` returns `false`.
+fn read_sse(reader: impl Read, mut on_event: impl FnMut(Value) -> bool) {
+    let mut data = String::new();
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else { break };
+        if line.is_empty() {
+            if !data.is_empty() {
+                if let Ok(v) = serde_json::from_str::<Value>(&data) {
+                    if !on_event(v) {
+                        return;
+                    }
+                }
+                data.clear();
+            }
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        }
+    }
+}
+
+/// The session an OpenCode event belongs to, wherever the event type keeps it.
+fn event_session_id(event: &Value) -> Option<&str> {
+    let props = &event["properties"];
+    props["sessionID"]
+        .as_str()
+        .or_else(|| props["info"]["sessionID"].as_str())
+        .or_else(|| props["part"]["sessionID"].as_str())
+}
+
+// ── event mapping ────────────────────────────────────────────────────────────
+
+/// What [`map_event`] remembers across events.
+#[derive(Default)]
+struct MapState {
+    /// Message id → role, from `message.updated`.
+    roles: HashMap<String, String>,
+    /// Tool calls already reported as `ToolUse`.
+    started_tools: HashSet<String>,
+    /// Tool calls already reported as `ToolResult`.
+    finished_tools: HashSet<String>,
+}
+
+/// Map one OpenCode server event to canonical payloads. `seq` is the sequence
+/// number the first resulting event will carry.
+fn map_event(
+    event: &Value,
+    state: &mut MapState,
+    seq: u64,
+) -> Vec<(AgentEventStream, AgentEventPayload)> {
+    let stdout = AgentEventStream::Stdout;
+    let props = &event["properties"];
+    let mut out = Vec::new();
+    match event["type"].as_str().unwrap_or_default() {
+        "message.updated" => {
+            let info = &props["info"];
+            if let (Some(id), Some(role)) = (info["id"].as_str(), info["role"].as_str()) {
+                state.roles.insert(id.to_string(), role.to_string());
+            }
+        }
+        "message.part.updated" => {
+            let part = &props["part"];
+            let message_id = part["messageID"].as_str().unwrap_or_default();
+            if state.roles.get(message_id).map(String::as_str) == Some("user") {
+                return out;
+            }
+            let message = |text: &str, phase, kind| {
+                AgentEventPayload::StreamMessage(StreamMessage {
+                    text: text.to_string(),
+                    phase,
+                    role: MessageRole::Assistant,
+                    kind,
+                    source: stdout,
+                    raw_line_seq: seq,
+                    turn_id: None,
+                })
+            };
+            match part["type"].as_str().unwrap_or_default() {
+                kind @ ("text" | "reasoning") => {
+                    let kind = if kind == "text" {
+                        MessageKind::Message
+                    } else {
+                        MessageKind::Reasoning
+                    };
+                    if let Some(delta) = props["delta"].as_str().filter(|d| !d.is_empty()) {
+                        out.push((stdout, message(delta, MessagePhase::Delta, kind)));
+                    } else if !part["time"]["end"].is_null() {
+                        let text = part["text"].as_str().unwrap_or_default();
+                        if !text.is_empty() {
+                            out.push((stdout, message(text, MessagePhase::Final, kind)));
+                        }
+                    }
+                }
+                "tool" => {
+                    let call_id = part["callID"].as_str().unwrap_or_default().to_string();
+                    let tool_state = &part["state"];
+                    let status = tool_state["status"].as_str().unwrap_or_default();
+                    if status == "pending" || call_id.is_empty() {
+                        return out;
+                    }
+                    if state.started_tools.insert(call_id.clone()) {
+                        out.push((
+                            stdout,
+                            AgentEventPayload::ToolUse {
+                                call_id: call_id.clone(),
+                                tool_name: part["tool"].as_str().unwrap_or_default().to_string(),
+                                input: tool_state
+                                    .get("input")
+                                    .cloned()
+                                    .unwrap_or_else(|| json!({})),
+                            },
+                        ));
+                    }
+                    let result = match status {
+                        "completed" => Some((tool_state["output"].clone(), false)),
+                        "error" => Some((tool_state["error"].clone(), true)),
+                        _ => None,
+                    };
+                    if let Some((output, is_error)) = result {
+                        if state.finished_tools.insert(call_id.clone()) {
+                            out.push((
+                                stdout,
+                                AgentEventPayload::ToolResult {
+                                    call_id,
+                                    output,
+                                    is_error,
+                                },
+                            ));
+                        }
+                    }
+                }
+                "step-finish" => {
+                    // Same `tokens` shape `opencode run --format json` emits.
+                    let line = json!({ "type": "step_finish", "part": part });
+                    if let Some((usage, source)) = opencode::extract_usage(&line) {
+                        out.push((
+                            stdout,
+                            AgentEventPayload::TokenUsageLine {
+                                usage,
+                                source,
+                                raw_agent_line_seq: seq,
+                            },
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        "session.idle" => out.push((
+            stdout,
+            AgentEventPayload::AikitStepFinish {
+                iteration: 0,
+                finish_reason: "turn_completed".into(),
+            },
+        )),
+        "session.error" => {
+            let error = &props["error"];
+            let message = error["data"]["message"]
+                .as_str()
+                .or_else(|| error["name"].as_str())
+                .unwrap_or("unknown error");
+            let line = AgentEventPayload::RawLine(format!("opencode session error: {message}"));
+            if let Some(info) = opencode::extract_quota(&line) {
+                out.push((
+                    AgentEventStream::Stderr,
+                    AgentEventPayload::QuotaExceeded {
+                        info,
+                        raw_agent_line_seq: seq,
+                    },
+                ));
+            }
+            out.insert(0, (AgentEventStream::Stderr, line));
+        }
+        _ => {}
+    }
+    out
+}
+
+fn send_event(
+    event_tx: &mpsc::Sender<AgentEvent>,
+    seq: &mut u64,
+    stream: AgentEventStream,
+    payload: AgentEventPayload,
+) -> bool {
+    let ev = AgentEvent {
+        agent_key: opencode::KEY.to_string(),
+        seq: *seq,
+        stream,
+        payload,
+    };
+    *seq += 1;
+    event_tx.send(ev).is_ok()
+}
+
+fn emit_stderr(event_tx: &mpsc::Sender<AgentEvent>, seq: &mut u64, line: String) -> bool {
+    if line.is_empty() {
+        return true;
+    }
+    send_event(
+        event_tx,
+        seq,
+        AgentEventStream::Stderr,
+        AgentEventPayload::RawLine(line),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream};
+
+    fn json(s: &str) -> Value {
+        serde_json::from_str(s).unwrap()
+    }
+
+    /// A stand-in for `opencode serve`: records every request and streams
+    /// whatever events the test pushes to `/event` subscribers.
+    struct StubServer {
+        url: String,
+        requests: Arc<Mutex<Vec<(String, String, Value)>>>,
+        events: Option<mpsc::Sender<String>>,
+    }
+
+    impl StubServer {
+        fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let (events_tx, events_rx) = mpsc::channel::<String>();
+            let events_rx = Arc::new(Mutex::new(events_rx));
+            let recorded = Arc::clone(&requests);
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let recorded = Arc::clone(&recorded);
+                    let events_rx = Arc::clone(&events_rx);
+                    thread::spawn(move || handle(stream, &recorded, &events_rx));
+                }
+            });
+            Self {
+                url,
+                requests,
+                events: Some(events_tx),
+            }
+        }
+
+        fn push(&self, event: Value) {
+            self.events
+                .as_ref()
+                .unwrap()
+                .send(event.to_string())
+                .unwrap();
+        }
+
+        /// Wait for a request matching `method path`, returning its body.
+        fn wait_for(&self, method: &str, path: &str, nth: usize) -> Value {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                let found: Vec<Value> = self
+                    .requests
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|(m, p, _)| m == method && p == path)
+                    .map(|(_, _, b)| b.clone())
+                    .collect();
+                if let Some(body) = found.get(nth) {
+                    return body.clone();
+                }
+                assert!(Instant::now() < deadline, "no {method} {path} #{nth}");
+                thread::sleep(Duration::from_millis(20));
+            }
+        }
+    }
+
+    fn handle(
+        mut stream: TcpStream,
+        recorded: &Mutex<Vec<(String, String, Value)>>,
+        events: &Mutex<mpsc::Receiver<String>>,
+    ) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((k, v)) = header.split_once(':') {
+                if k.eq_ignore_ascii_case("content-length") {
+                    content_length = v.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        recorded
+            .lock()
+            .unwrap()
+            .push((method.clone(), path.clone(), body));
+
+        if path == "/event" {
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n",
+            );
+            let _ = stream.flush();
+            while let Ok(event) = events.lock().unwrap().recv() {
+                if stream
+                    .write_all(format!("data: {event}\n\n").as_bytes())
+                    .and_then(|_| stream.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            return;
+        }
+        let reply = match (method.as_str(), path.as_str()) {
+            ("POST", "/session") => r#"{"id":"ses_stub"}"#,
+            ("POST", p) if p.ends_with("/abort") => "true",
+            _ => "{}",
+        };
+        let _ = stream.write_all(
+            format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .as_bytes(),
+        );
+    }
+
+    fn part_event(part: Value, delta: Option<&str>) -> Value {
+        let mut props = serde_json::json!({ "part": part });
+        if let Some(d) = delta {
+            props["delta"] = Value::String(d.to_string());
+        }
+        serde_json::json!({ "type": "message.part.updated", "properties": props })
+    }
+
+    fn next_until_step_finish(events: &mpsc::Receiver<AgentEvent>) -> Vec<AgentEventPayload> {
+        let mut out = Vec::new();
+        loop {
+            let ev = events
+                .recv_timeout(Duration::from_secs(5))
+                .expect("event before step finish");
+            let done = matches!(ev.payload, AgentEventPayload::AikitStepFinish { .. });
+            out.push(ev.payload);
+            if done {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn session_streams_turns_and_drives_controls_against_a_stub_server() {
+        let mut stub = StubServer::start();
+        let session = open_opencode_session(
+            "hi",
+            OpenCodeSessionOptions {
+                server_url: Some(stub.url.clone()),
+                model: Some("anthropic/claude-x".into()),
+                ..OpenCodeSessionOptions::default()
+            },
+        )
+        .expect("open against stub");
+
+        let first = stub.wait_for("POST", "/session/ses_stub/message", 0);
+        assert_eq!(
+            first,
+            json(
+                r#"{"parts":[{"type":"text","text":"hi"}],"model":{"providerID":"anthropic","modelID":"claude-x"}}"#
+            )
+        );
+
+        stub.push(serde_json::json!({"type":"message.updated","properties":{"info":{"id":"msg_u","role":"user","sessionID":"ses_stub"}}}));
+        stub.push(part_event(
+            serde_json::json!({"type":"text","text":"hi","messageID":"msg_u","sessionID":"ses_stub","time":{"end":1}}),
+            None,
+        ));
+        // Another session's traffic on the same server is ignored.
+        stub.push(
+            serde_json::json!({"type":"session.idle","properties":{"sessionID":"ses_other"}}),
+        );
+        stub.push(serde_json::json!({"type":"message.updated","properties":{"info":{"id":"msg_a","role":"assistant","sessionID":"ses_stub"}}}));
+        stub.push(part_event(
+            serde_json::json!({"type":"text","text":"Hel","messageID":"msg_a","sessionID":"ses_stub"}),
+            Some("Hel"),
+        ));
+        stub.push(part_event(
+            serde_json::json!({"type":"tool","callID":"call_1","tool":"bash","messageID":"msg_a","sessionID":"ses_stub","state":{"status":"running","input":{"command":"ls"}}}),
+            None,
+        ));
+        stub.push(part_event(
+            serde_json::json!({"type":"tool","callID":"call_1","tool":"bash","messageID":"msg_a","sessionID":"ses_stub","state":{"status":"completed","input":{"command":"ls"},"output":"a.txt"}}),
+            None,
+        ));
+        stub.push(part_event(
+            serde_json::json!({"type":"step-finish","messageID":"msg_a","sessionID":"ses_stub","tokens":{"input":10,"output":3,"reasoning":0,"cache":{"read":4,"write":0}}}),
+            None,
+        ));
+        stub.push(serde_json::json!({"type":"session.idle","properties":{"sessionID":"ses_stub"}}));
+
+        let payloads = next_until_step_finish(&session.events);
+        assert_eq!(payloads.len(), 5, "{payloads:?}");
+        assert!(
+            matches!(&payloads[0], AgentEventPayload::StreamMessage(m) if m.text == "Hel" && m.phase == MessagePhase::Delta)
+        );
+        assert!(
+            matches!(&payloads[1], AgentEventPayload::ToolUse { call_id, tool_name, .. } if call_id == "call_1" && tool_name == "bash")
+        );
+        assert!(
+            matches!(&payloads[2], AgentEventPayload::ToolResult { output, is_error: false, .. } if output == "a.txt")
+        );
+        assert!(
+            matches!(&payloads[3], AgentEventPayload::TokenUsageLine { usage, .. } if usage.input_tokens == 10 && usage.cache_read_tokens == Some(4))
+        );
+
+        session.control.set_model(None).unwrap();
+        session.control.send_turn("again").unwrap();
+        let second = stub.wait_for("POST", "/session/ses_stub/message", 1);
+        assert_eq!(
+            second,
+            json(r#"{"parts":[{"type":"text","text":"again"}]}"#)
+        );
+
+        session.control.interrupt().unwrap();
+        stub.wait_for("POST", "/session/ses_stub/abort", 0);
+
+        session.control.disconnect().unwrap();
+        // Disconnecting mid-turn aborts it on the (attached) server.
+        stub.wait_for("POST", "/session/ses_stub/abort", 1);
+        loop {
+            match session.events.recv_timeout(Duration::from_secs(5)) {
+                Ok(_) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => panic!("session did not close"),
+            }
+        }
+        stub.events.take();
+    }
+
+    #[test]
+    fn open_fails_synchronously_when_the_server_is_unreachable() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let err = open_opencode_session(
+            "hi",
+            OpenCodeSessionOptions {
+                server_url: Some(format!("http://127.0.0.1:{port}")),
+                ..OpenCodeSessionOptions::default()
+            },
+        )
+        .err()
+        .expect("unreachable server must fail to open");
+        assert!(matches!(err, OpenCodeSessionError::Connect(_)), "{err}");
+    }
+
+    #[test]
+    fn open_rejects_a_model_without_provider() {
+        let err = open_opencode_session(
+            "hi",
+            OpenCodeSessionOptions {
+                model: Some("claude-x".into()),
+                server_url: Some("http://127.0.0.1:1".into()),
+                ..OpenCodeSessionOptions::default()
+            },
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("provider/id"), "{err}");
+    }
+
+    #[test]
+    fn set_model_validates_provider_and_allows_reset() {
+        let (tx, rx) = mpsc::channel::<Msg>();
+        let h = OpenCodeControlHandle { tx };
+        h.set_model(Some("openai/gpt-5".into())).unwrap();
+        assert!(matches!(
+            rx.recv().unwrap(),
+            Msg::Cmd(ControlCmd::SetModel(Some(m))) if m == "openai/gpt-5"
+        ));
+        h.set_model(Some("  ".into())).unwrap();
+        assert!(matches!(
+            rx.recv().unwrap(),
+            Msg::Cmd(ControlCmd::SetModel(None))
+        ));
+        assert!(matches!(
+            h.set_model(Some("gpt-5".into())),
+            Err(OpenCodeSessionError::Backend(_))
+        ));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn dropping_control_handle_queues_disconnect() {
+        let (tx, rx) = mpsc::channel::<Msg>();
+        drop(OpenCodeControlHandle { tx });
+        assert!(matches!(
+            rx.recv().unwrap(),
+            Msg::Cmd(ControlCmd::Disconnect)
+        ));
+    }
+
+    #[test]
+    fn read_sse_joins_data_lines_and_skips_comments() {
+        let body = ": keepalive\n\nevent: message\ndata: {\"a\":\ndata: 1}\n\ndata: not json\n\ndata: {\"b\":2}\n\n";
+        let mut seen = Vec::new();
+        read_sse(body.as_bytes(), |v| {
+            seen.push(v);
+            true
+        });
+        assert_eq!(seen, vec![json(r#"{"a":1}"#), json(r#"{"b":2}"#)]);
+    }
+
+    #[test]
+    fn map_event_reports_final_text_errors_and_quota() {
+        let mut state = MapState::default();
+        let finished = part_event(
+            serde_json::json!({"type":"reasoning","text":"thinking","messageID":"m","time":{"end":2}}),
+            None,
+        );
+        let out = map_event(&finished, &mut state, 7);
+        assert!(matches!(
+            &out[0].1,
+            AgentEventPayload::StreamMessage(m)
+                if m.kind == MessageKind::Reasoning && m.phase == MessagePhase::Final && m.raw_line_seq == 7
+        ));
+
+        let failed_tool = part_event(
+            serde_json::json!({"type":"tool","callID":"c","tool":"edit","messageID":"m","state":{"status":"error","input":{},"error":"denied"}}),
+            None,
+        );
+        let out = map_event(&failed_tool, &mut state, 0);
+        assert_eq!(out.len(), 2);
+        assert!(matches!(
+            &out[1].1,
+            AgentEventPayload::ToolResult { is_error: true, output, .. } if output == "denied"
+        ));
+        // Re-sent tool state is not reported twice.
+        assert!(map_event(&failed_tool, &mut state, 0).is_empty());
+
+        let error = json(
+            r#"{"type":"session.error","properties":{"sessionID":"s","error":{"name":"APIError","data":{"message":"insufficient_quota: rate limit"}}}}"#,
+        );
+        let out = map_event(&error, &mut state, 3);
+        assert!(matches!(
+            &out[0],
+            (AgentEventStream::Stderr, AgentEventPayload::RawLine(l)) if l.contains("insufficient_quota")
+        ));
+        assert!(matches!(
+            &out[1].1,
+            AgentEventPayload::QuotaExceeded {
+                raw_agent_line_seq: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn event_session_id_reads_every_shape() {
+        assert_eq!(
+            event_session_id(&json(r#"{"properties":{"sessionID":"a"}}"#)),
+            Some("a")
+        );
+        assert_eq!(
+            event_session_id(&json(r#"{"properties":{"info":{"sessionID":"b"}}}"#)),
+            Some("b")
+        );
+        assert_eq!(
+            event_session_id(&json(r#"{"properties":{"part":{"sessionID":"c"}}}"#)),
+            Some("c")
+        );
+        assert_eq!(
+            event_session_id(&json(r#"{"type":"server.connected"}"#)),
+            None
+        );
+    }
+}
@@ -253,6 +253,18 @@ pub struct RunOptions {
     pub ephemeral: bool,
     /// Skip the headless git-repository guard (`--skip-git-repo-check`, spec 013 D6).
     pub skip_git_repo_check: bool,
+    /// Start the MCP servers in the project's `.aikit/mcp.json`
+    /// (`--trust-project-mcp`). Off by default: a checked-out repository
+    /// must not get to run commands just by being the workdir. aikit
+    /// backend only.
+    pub trust_project_mcp: bool,
+    /// Hosts `web_fetch` / `web_search` may reach (`--web-allow-domain`),
+    /// added to `AIKIT_WEB_ALLOW_DOMAINS`. A host listed here may also be a
+    /// loopback or private address. aikit backend only.
+    pub web_allow_domains: Vec<String>,
+    /// Hosts the web tools never reach (`--web-deny-domain`), added to
+    /// `AIKIT_WEB_DENY_DOMAINS`. aikit backend only.
+    pub web_deny_domains: Vec<String>,
 }
 
 impl Default for RunOptions {
@@ -276,6 +288,9 @@ impl Default for RunOptions {
             bare: false,
             ephemeral: false,
             skip_git_repo_check: false,
+            trust_project_mcp: false,
+            web_allow_domains: Vec::new(),
+            web_deny_domains: Vec::new(),
         }
     }
 }
@@ -392,6 +407,24 @@ impl RunOptions {
         self.skip_git_repo_check = skip;
         self
     }
+
+    /// Start project-scope MCP servers (`--trust-project-mcp`).
+    pub fn with_trust_project_mcp(mut self, trust: bool) -> Self {
+        self.trust_project_mcp = trust;
+        self
+    }
+
+    /// Let the web tools reach `domain` and its subdomains (`--web-allow-domain`).
+    pub fn with_web_allow_domain(mut self, domain: impl Into<String>) -> Self {
+        self.web_allow_domains.push(domain.into());
+        self
+    }
+
+    /// Keep the web tools off `domain` and its subdomains (`--web-deny-domain`).
+    pub fn with_web_deny_domain(mut self, domain: impl Into<String>) -> Self {
+        self.web_deny_domains.push(domain.into());
+        self
+    }
 }
 
 /// Result of running an agent.
@@ -559,6 +592,12 @@ pub enum OutputMode {
 pub trait ProgressSink: Send {
     fn on_progress(&mut self, progress: &crate::run_progress::RunProgress);
     fn on_finalize(&mut self, exit_code: i32, token_footer: Option<String>);
+
+    /// Configuration for the [`RunProgress`](crate::RunProgress) fed to this
+    /// sink. A `None` model is filled from [`RunOptions::model`].
+    fn view_config(&self) -> crate::run_progress::ProgressViewConfig {
+        crate::run_progress::ProgressViewConfig::default()
+    }
 }
 
 /// Identifies which stream an event or error originated from.
@@ -681,6 +720,14 @@ pub enum AgentEventPayload {
         subagent_id: String,
         workdir: String,
     },
+    /// A canonical event from a sub-agent the built-in agent delegated to an
+    /// external Backend, nested between that sub-agent's
+    /// `AikitSubagentSpawn` and `AikitSubagentResult`. `event.agent_key` names
+    /// the Backend and `event.seq` is the sub-run's own sequence.
+    AikitSubagentEvent {
+        subagent_id: String,
+        event: Box<AgentEvent>,
+    },
     /// Built-in aikit agent sub-agent result.
     AikitSubagentResult {
         subagent_id: String,
@@ -688,12 +735,20 @@ pub enum AgentEventPayload {
         changed_files: Vec<String>,
         key_findings: String,
         final_message: String,
+        /// The sub-agent's changes as a `git diff` when it ran in an isolated
+        /// worktree; the parent decides whether to apply it.
+        #[serde(default)]
+        patch: Option<String>,
     },
     /// Built-in aikit agent context compression.
     AikitContextCompressed {
         original_tokens: u64,
         compressed_tokens: u64,
         turns_summarized: u64,
+        /// The summary that replaced the old turns. Empty in events recorded
+        /// before summaries were carried.
+        #[serde(default)]
+        summary: String,
     },
     /// Built-in aikit agent step finish.
     AikitStepFinish {
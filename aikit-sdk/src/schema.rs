@@ -0,0 +1,901 @@
+//! JSON Schemas for the SDK's wire types.
+//!
+//! [`component_schemas`] describes the serialized shape of
+//! [`AgentEventPayload`](crate::AgentEventPayload) (and everything it
+//! nests), [`BackendCapabilities`](crate::BackendCapabilities) and the
+//! history types, in the OpenAPI 3.1 dialect of JSON Schema: references are
+//! `#/components/schemas/<Name>`, so the map can be dropped straight into an
+//! OpenAPI document's `components.schemas`. `aikit serve` does exactly that
+//! for `GET /api/v1/openapi.json`.
+//!
+//! The schemas are hand-declared next to the serde derives they mirror. The
+//! tests below serialize a value of every type and variant and check it both
+//! ways: it must [`validate`], and its fields must be exactly the
+//! [`property_names`] the schema declares. A field added to a type without
+//! the schema, or left in the schema after the type dropped it, fails the
+//! build. Serve's tests use the same two checks on its own types.
+
+use serde_json::{json, Map, Value};
+
+/// `$ref` to a schema under `#/components/schemas/`.
+pub fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// `schema` or JSON `null` — the shape of an `Option<T>` field that serde
+/// serializes as `null` when absent.
+pub fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+/// A closed object: every listed property, `required` ones must be present,
+/// nothing else allowed.
+pub fn object(properties: &[(&str, Value)], required: &[&str]) -> Value {
+    let props: Map<String, Value> = properties
+        .iter()
+        .map(|(k, v)| ((*k).to_string(), v.clone()))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": props,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// A string restricted to `values`.
+pub fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// Serde's externally tagged variant: `{"<tag>": <inner>}`.
+fn tagged(tag: &str, inner: Value) -> Value {
+    object(&[(tag, inner)], &[tag])
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn signed_integer() -> Value {
+    json!({ "type": "integer" })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+/// Any JSON value.
+fn any() -> Value {
+    json!({})
+}
+
+/// Snake-case tags of every [`AgentEventPayload`](crate::AgentEventPayload)
+/// variant, in declaration order. These double as `aikit serve`'s SSE event
+/// names.
+pub const AGENT_EVENT_TAGS: &[&str] = &[
+    "json_line",
+    "raw_line",
+    "raw_bytes",
+    "stream_message",
+    "tool_use",
+    "tool_result",
+    "token_usage_line",
+    "quota_exceeded",
+    "raw_transport_line",
+    "aikit_text_delta",
+    "aikit_text_final",
+    "aikit_tool_use",
+    "aikit_tool_result",
+    "aikit_subagent_spawn",
+    "aikit_subagent_event",
+    "aikit_subagent_result",
+    "aikit_context_compressed",
+    "aikit_step_finish",
+    "result",
+    "session_started",
+];
+
+/// The inner (`data`) schema of one `AgentEventPayload` variant, by tag.
+pub fn agent_event_data_schema(tag: &str) -> Option<Value> {
+    let s = match tag {
+        "json_line" => any(),
+        "raw_line" => string(),
+        "raw_bytes" => array(json!({ "type": "integer", "minimum": 0, "maximum": 255 })),
+        "stream_message" => schema_ref("StreamMessage"),
+        "tool_use" => object(
+            &[
+                ("call_id", string()),
+                ("tool_name", string()),
+                ("input", any()),
+            ],
+            &["call_id", "tool_name", "input"],
+        ),
+        "tool_result" => object(
+            &[
+                ("call_id", string()),
+                ("output", any()),
+                ("is_error", boolean()),
+            ],
+            &["call_id", "output", "is_error"],
+        ),
+        "token_usage_line" => object(
+            &[
+                ("usage", schema_ref("TokenUsage")),
+                ("source", schema_ref("UsageSource")),
+                ("raw_agent_line_seq", integer()),
+            ],
+            &["usage", "source", "raw_agent_line_seq"],
+        ),
+        "quota_exceeded" => object(
+            &[
+                ("info", schema_ref("QuotaExceededInfo")),
+                ("raw_agent_line_seq", integer()),
+            ],
+            &["info", "raw_agent_line_seq"],
+        ),
+        "raw_transport_line" => object(
+            &[
+                ("raw", string()),
+                ("stream", schema_ref("AgentEventStream")),
+                ("seq", integer()),
+            ],
+            &["raw", "stream", "seq"],
+        ),
+        "aikit_text_delta" | "aikit_text_final" => object(
+            &[("content", string()), ("turn_id", nullable(string()))],
+            &["content", "turn_id"],
+        ),
+        "aikit_tool_use" => object(
+            &[
+                ("tool_name", string()),
+                ("tool_input", any()),
+                ("call_id", string()),
+            ],
+            &["tool_name", "tool_input", "call_id"],
+        ),
+        "aikit_tool_result" => object(
+            &[
+                ("call_id", string()),
+                ("output", string()),
+                ("is_error", boolean()),
+            ],
+            &["call_id", "output", "is_error"],
+        ),
+        "aikit_subagent_spawn" => object(
+            &[("subagent_id", string()), ("workdir", string())],
+            &["subagent_id", "workdir"],
+        ),
+        "aikit_subagent_event" => object(
+            &[
+                ("subagent_id", string()),
+                ("event", schema_ref("AgentEvent")),
+            ],
+            &["subagent_id", "event"],
+        ),
+        "aikit_subagent_result" => object(
+            &[
+                ("subagent_id", string()),
+                ("status", string()),
+                ("changed_files", array(string())),
+                ("key_findings", string()),
+                ("final_message", string()),
+                ("patch", nullable(string())),
+            ],
+            &[
+                "subagent_id",
+                "status",
+                "changed_files",
+                "key_findings",
+                "final_message",
+            ],
+        ),
+        "aikit_context_compressed" => object(
+            &[
+                ("original_tokens", integer()),
+                ("compressed_tokens", integer()),
+                ("turns_summarized", integer()),
+                ("summary", string()),
+            ],
+            &["original_tokens", "compressed_tokens", "turns_summarized"],
+        ),
+        "aikit_step_finish" => object(
+            &[("iteration", integer()), ("finish_reason", string())],
+            &["iteration", "finish_reason"],
+        ),
+        "result" => object(
+            &[
+                ("text", string()),
+                ("structured", nullable(any())),
+                ("session_id", nullable(string())),
+            ],
+            &["text", "structured", "session_id"],
+        ),
+        "session_started" => object(&[("session_id", string())], &["session_id"]),
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn history_block_schema() -> Value {
+    let tool_use = object(
+        &[("id", string()), ("name", string()), ("input", any())],
+        &["id", "name", "input"],
+    );
+    let tool_result = object(
+        &[
+            ("tool_use_id", string()),
+            ("content", any()),
+            ("is_error", boolean()),
+        ],
+        &["tool_use_id", "content", "is_error"],
+    );
+    let text = object(&[("text", string())], &["text"]);
+    json!({
+        "oneOf": [
+            tagged("text", text.clone()),
+            tagged("thinking", text),
+            tagged("tool_use", tool_use.clone()),
+            tagged("tool_result", tool_result.clone()),
+            tagged("server_tool_use", tool_use),
+            tagged("server_tool_result", tool_result),
+            tagged("raw", any()),
+        ]
+    })
+}
+
+/// Schemas for the SDK's serialized wire types, keyed by component name.
+pub fn component_schemas() -> Map<String, Value> {
+    let mut c = Map::new();
+    let mut put = |name: &str, schema: Value| {
+        c.insert(name.to_string(), schema);
+    };
+
+    put("AgentEventStream", string_enum(&["stdout", "stderr"]));
+    put("MessagePhase", string_enum(&["delta", "final"]));
+    put(
+        "MessageRole",
+        string_enum(&["assistant", "tool", "system", "user"]),
+    );
+    put(
+        "MessageKind",
+        string_enum(&["message", "reasoning", "tool_output", "status"]),
+    );
+    put(
+        "StreamMessage",
+        object(
+            &[
+                ("text", string()),
+                ("phase", schema_ref("MessagePhase")),
+                ("role", schema_ref("MessageRole")),
+                ("kind", schema_ref("MessageKind")),
+                ("source", schema_ref("AgentEventStream")),
+                ("raw_line_seq", integer()),
+                ("turn_id", nullable(string())),
+            ],
+            &[
+                "text",
+                "phase",
+                "role",
+                "kind",
+                "source",
+                "raw_line_seq",
+                "turn_id",
+            ],
+        ),
+    );
+    put(
+        "TokenUsage",
+        object(
+            &[
+                ("input_tokens", integer()),
+                ("output_tokens", integer()),
+                ("total_tokens", nullable(integer())),
+                ("cache_read_tokens", nullable(integer())),
+                ("cache_creation_tokens", nullable(integer())),
+                ("reasoning_tokens", nullable(integer())),
+            ],
+            &[
+                "input_tokens",
+                "output_tokens",
+                "total_tokens",
+                "cache_read_tokens",
+                "cache_creation_tokens",
+                "reasoning_tokens",
+            ],
+        ),
+    );
+    put(
+        "UsageSource",
+        string_enum(&[
+            "Codex", "Claude", "Gemini", "OpenCode", "Cursor", "Pi", "Aikit",
+        ]),
+    );
+    put(
+        "QuotaCategory",
+        string_enum(&["hourly", "daily", "weekly", "requests", "tokens", "unknown"]),
+    );
+    put(
+        "QuotaExceededInfo",
+        object(
+            &[
+                ("agent_key", string()),
+                ("category", schema_ref("QuotaCategory")),
+                ("raw_message", string()),
+            ],
+            &["agent_key", "category", "raw_message"],
+        ),
+    );
+    let variants: Vec<Value> = AGENT_EVENT_TAGS
+        .iter()
+        .map(|tag| tagged(tag, agent_event_data_schema(tag).expect("known tag")))
+        .collect();
+    put("AgentEventPayload", json!({ "oneOf": variants }));
+    put(
+        "AgentEvent",
+        object(
+            &[
+                ("agent_key", string()),
+                ("seq", integer()),
+                ("stream", schema_ref("AgentEventStream")),
+                ("payload", schema_ref("AgentEventPayload")),
+            ],
+            &["agent_key", "seq", "stream", "payload"],
+        ),
+    );
+
+    let capability_fields = [
+        "bidirectional",
+        "structured_tools",
+        "reasoning",
+        "file_changes",
+        "interruptible",
+        "resumable_sessions",
+        "mcp_routing",
+        "hooks",
+        "server_tools",
+        "subagents",
+        "context_compression",
+        "passive_capture",
+        "supports_tool_policy",
+        "history_store",
+        "history_mutations",
+    ];
+    let capability_props: Vec<(&str, Value)> =
+        capability_fields.iter().map(|f| (*f, boolean())).collect();
+    put(
+        "BackendCapabilities",
+        object(&capability_props, &capability_fields),
+    );
+
+    put(
+        "HistorySession",
+        object(
+            &[
+                ("backend", string()),
+                ("session_id", string()),
+                ("summary", string()),
+                ("first_prompt", nullable(string())),
+                ("custom_title", nullable(string())),
+                ("tag", nullable(string())),
+                ("cwd", nullable(string())),
+                ("git_branch", nullable(string())),
+                ("last_modified_ms", signed_integer()),
+                ("created_at_ms", nullable(signed_integer())),
+                ("message_count", nullable(integer())),
+                ("size_bytes", nullable(integer())),
+            ],
+            &[
+                "backend",
+                "session_id",
+                "summary",
+                "first_prompt",
+                "custom_title",
+                "tag",
+                "cwd",
+                "git_branch",
+                "last_modified_ms",
+                "created_at_ms",
+                "message_count",
+                "size_bytes",
+            ],
+        ),
+    );
+    put("HistoryBlock", history_block_schema());
+    put(
+        "HistoryContent",
+        json!({
+            "oneOf": [
+                tagged("text", string()),
+                tagged("blocks", array(schema_ref("HistoryBlock"))),
+                tagged("raw", any()),
+            ]
+        }),
+    );
+    put(
+        "HistoryMessage",
+        object(
+            &[
+                ("role", schema_ref("MessageRole")),
+                ("uuid", string()),
+                ("session_id", string()),
+                ("parent_tool_use_id", nullable(string())),
+                ("content", schema_ref("HistoryContent")),
+            ],
+            &[
+                "role",
+                "uuid",
+                "session_id",
+                "parent_tool_use_id",
+                "content",
+            ],
+        ),
+    );
+    c
+}
+
+/// The property names object `schema` declares, following a `$ref` in
+/// `components`, sorted. Empty for a schema that is not an object.
+pub fn property_names(components: &Map<String, Value>, schema: &Value) -> Vec<String> {
+    let mut schema = schema;
+    while let Some(r) = schema.get("$ref").and_then(Value::as_str) {
+        match components.get(r.trim_start_matches("#/components/schemas/")) {
+            Some(target) => schema = target,
+            None => return Vec::new(),
+        }
+    }
+    let mut names: Vec<String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Check `instance` against `schema`, resolving `$ref`s in `components`.
+///
+/// Structural only — the subset of JSON Schema the schemas in this crate
+/// (and serve's) use: `type`, `enum`, `const`, `properties`, `required`,
+/// `additionalProperties`, `items`, `oneOf`, `anyOf`, `minimum`, `maximum`
+/// and `$ref`. Returns the JSON pointer of the first mismatch.
+pub fn validate(
+    components: &Map<String, Value>,
+    schema: &Value,
+    instance: &Value,
+) -> Result<(), String> {
+    check(components, schema, instance, "")
+}
+
+fn check(c: &Map<String, Value>, schema: &Value, v: &Value, at: &str) -> Result<(), String> {
+    let s = match schema.as_object() {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    if let Some(r) = s.get("$ref").and_then(Value::as_str) {
+        let name = r.trim_start_matches("#/components/schemas/");
+        let target = c
+            .get(name)
+            .ok_or_else(|| format!("{at}: unresolved $ref {r}"))?;
+        return check(c, target, v, at);
+    }
+    if let Some(Value::Array(options)) = s.get("oneOf") {
+        let matched = options
+            .iter()
+            .filter(|o| check(c, o, v, at).is_ok())
+            .count();
+        if matched != 1 {
+            return Err(format!(
+                "{at}: matched {matched} oneOf branches, expected 1"
+            ));
+        }
+    }
+    if let Some(Value::Array(options)) = s.get("anyOf") {
+        if !options.iter().any(|o| check(c, o, v, at).is_ok()) {
+            return Err(format!("{at}: matched no anyOf branch"));
+        }
+    }
+    if let Some(t) = s.get("type") {
+        let types: Vec<&str> = match t {
+            Value::String(one) => vec![one.as_str()],
+            Value::Array(many) => many.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.iter().any(|t| type_matches(t, v)) {
+            return Err(format!("{at}: expected {}, got {v}", types.join("|")));
+        }
+    }
+    if let Some(Value::Array(allowed)) = s.get("enum") {
+        if !allowed.contains(v) {
+            return Err(format!("{at}: {v} is not one of {allowed:?}"));
+        }
+    }
+    if let Some(expected) = s.get("const") {
+        if expected != v {
+            return Err(format!("{at}: expected {expected}, got {v}"));
+        }
+    }
+    if let Some(n) = v.as_f64() {
+        if let Some(min) = s.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                return Err(format!("{at}: {n} < minimum {min}"));
+            }
+        }
+        if let Some(max) = s.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                return Err(format!("{at}: {n} > maximum {max}"));
+            }
+        }
+    }
+    if let (Some(items), Value::Array(elems)) = (s.get("items"), v) {
+        for (i, e) in elems.iter().enumerate() {
+            check(c, items, e, &format!("{at}/{i}"))?;
+        }
+    }
+    if let Value::Object(obj) = v {
+        let props = s.get("properties").and_then(Value::as_object);
+        if let Some(Value::Array(required)) = s.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !obj.contains_key(key) {
+                    return Err(format!("{at}: missing required property '{key}'"));
+                }
+            }
+        }
+        for (key, value) in obj {
+            let path = format!("{at}/{key}");
+            match props.and_then(|p| p.get(key)) {
+                Some(prop) => check(c, prop, value, &path)?,
+                None => match s.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(format!("{at}: unexpected property '{key}'"))
+                    }
+                    Some(extra @ Value::Object(_)) => check(c, extra, value, &path)?,
+                    _ => {}
+                },
+            }
+        }
+    }
+    Ok(())
+}
+
+fn type_matches(t: &str, v: &Value) -> bool {
+    match t {
+        "null" => v.is_null(),
+        "boolean" => v.is_boolean(),
+        "string" => v.is_string(),
+        "integer" => v.is_i64() || v.is_u64(),
+        "number" => v.is_number(),
+        "array" => v.is_array(),
+        "object" => v.is_object(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AgentEvent, AgentEventPayload, AgentEventStream, MessageKind, MessagePhase, MessageRole,
+        QuotaCategory, QuotaExceededInfo, StreamMessage, TokenUsage, UsageSource,
+    };
+
+    /// `value` validates against `schema` and carries exactly the properties
+    /// it declares. Samples set every optional field, so a declared property
+    /// the type no longer serializes shows up as missing.
+    fn assert_matches(c: &Map<String, Value>, schema: &Value, value: &Value, what: &str) {
+        validate(c, schema, value).unwrap_or_else(|e| panic!("{what}: {e}"));
+        let mut keys: Vec<String> = value
+            .as_object()
+            .unwrap_or_else(|| panic!("{what}: not an object"))
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        assert_eq!(keys, property_names(c, schema), "{what}: fields vs schema");
+    }
+
+    /// Exhaustive on purpose: a new `AgentEventPayload` variant stops this
+    /// compiling until it is given a tag here (and a schema above).
+    fn tag_of(p: &AgentEventPayload) -> &'static str {
+        match p {
+            AgentEventPayload::JsonLine(_) => "json_line",
+            AgentEventPayload::RawLine(_) => "raw_line",
+            AgentEventPayload::RawBytes(_) => "raw_bytes",
+            AgentEventPayload::StreamMessage(_) => "stream_message",
+            AgentEventPayload::ToolUse { .. } => "tool_use",
+            AgentEventPayload::ToolResult { .. } => "tool_result",
+            AgentEventPayload::TokenUsageLine { .. } => "token_usage_line",
+            AgentEventPayload::QuotaExceeded { .. } => "quota_exceeded",
+            AgentEventPayload::RawTransportLine { .. } => "raw_transport_line",
+            AgentEventPayload::AikitTextDelta { .. } => "aikit_text_delta",
+            AgentEventPayload::AikitTextFinal { .. } => "aikit_text_final",
+            AgentEventPayload::AikitToolUse { .. } => "aikit_tool_use",
+            AgentEventPayload::AikitToolResult { .. } => "aikit_tool_result",
+            AgentEventPayload::AikitSubagentSpawn { .. } => "aikit_subagent_spawn",
+            AgentEventPayload::AikitSubagentEvent { .. } => "aikit_subagent_event",
+            AgentEventPayload::AikitSubagentResult { .. } => "aikit_subagent_result",
+            AgentEventPayload::AikitContextCompressed { .. } => "aikit_context_compressed",
+            AgentEventPayload::AikitStepFinish { .. } => "aikit_step_finish",
+            AgentEventPayload::Result { .. } => "result",
+            AgentEventPayload::SessionStarted { .. } => "session_started",
+        }
+    }
+
+    fn sample_payloads() -> Vec<AgentEventPayload> {
+        vec![
+            AgentEventPayload::JsonLine(json!({"type": "system"})),
+            AgentEventPayload::RawLine("plain".into()),
+            AgentEventPayload::RawBytes(vec![0, 255]),
+            AgentEventPayload::StreamMessage(StreamMessage {
+                text: "hi".into(),
+                phase: MessagePhase::Delta,
+                role: MessageRole::Assistant,
+                kind: MessageKind::Reasoning,
+                source: AgentEventStream::Stdout,
+                raw_line_seq: 3,
+                turn_id: None,
+            }),
+            AgentEventPayload::ToolUse {
+                call_id: "c1".into(),
+                tool_name: "Bash".into(),
+                input: json!({"command": "ls"}),
+            },
+            AgentEventPayload::ToolResult {
+                call_id: "c1".into(),
+                output: json!("a.txt"),
+                is_error: false,
+            },
+            AgentEventPayload::TokenUsageLine {
+                usage: TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    total_tokens: Some(15),
+                    cache_read_tokens: None,
+                    cache_creation_tokens: Some(2),
+                    reasoning_tokens: None,
+                },
+                source: UsageSource::OpenCode,
+                raw_agent_line_seq: 4,
+            },
+            AgentEventPayload::QuotaExceeded {
+                info: QuotaExceededInfo {
+                    agent_key: "claude".into(),
+                    category: QuotaCategory::Daily,
+                    raw_message: "limit".into(),
+                },
+                raw_agent_line_seq: 5,
+            },
+            AgentEventPayload::RawTransportLine {
+                raw: "{}".into(),
+                stream: AgentEventStream::Stderr,
+                seq: 6,
+            },
+            AgentEventPayload::AikitTextDelta {
+                content: "par".into(),
+                turn_id: Some("t1".into()),
+            },
+            AgentEventPayload::AikitTextFinal {
+                content: "partial".into(),
+                turn_id: None,
+            },
+            AgentEventPayload::AikitToolUse {
+                tool_name: "read_file".into(),
+                tool_input: json!({"path": "a"}),
+                call_id: "c2".into(),
+            },
+            AgentEventPayload::AikitToolResult {
+                call_id: "c2".into(),
+                output: "contents".into(),
+                is_error: true,
+            },
+            AgentEventPayload::AikitSubagentSpawn {
+                subagent_id: "s1".into(),
+                workdir: "/tmp/w".into(),
+            },
+            AgentEventPayload::AikitSubagentEvent {
+                subagent_id: "s1".into(),
+                event: Box::new(AgentEvent {
+                    agent_key: "codex".into(),
+                    seq: 0,
+                    stream: AgentEventStream::Stdout,
+                    payload: AgentEventPayload::ToolUse {
+                        call_id: "c9".into(),
+                        tool_name: "apply_patch".into(),
+                        input: json!({"path": "db/001.sql"}),
+                    },
+                }),
+            },
+            AgentEventPayload::AikitSubagentResult {
+                subagent_id: "s1".into(),
+                status: "ok".into(),
+                changed_files: vec!["a.rs".into()],
+                key_findings: "none".into(),
+                final_message: "done".into(),
+                patch: Some("diff --git a/a.rs b/a.rs\n".into()),
+            },
+            AgentEventPayload::AikitContextCompressed {
+                original_tokens: 100,
+                compressed_tokens: 40,
+                turns_summarized: 3,
+                summary: "## Goals\n- ship it".into(),
+            },
+            AgentEventPayload::AikitStepFinish {
+                iteration: 2,
+                finish_reason: "stop".into(),
+            },
+            AgentEventPayload::Result {
+                text: "final".into(),
+                structured: Some(json!({"ok": true})),
+                session_id: None,
+            },
+            AgentEventPayload::SessionStarted {
+                session_id: "sess".into(),
+            },
+        ]
+    }
+
+    #[test]
+    fn every_agent_event_variant_conforms() {
+        let c = component_schemas();
+        let samples = sample_payloads();
+        let tags: Vec<&str> = samples.iter().map(tag_of).collect();
+        assert_eq!(tags, AGENT_EVENT_TAGS, "one sample per variant, in order");
+
+        for payload in samples {
+            let value = serde_json::to_value(&payload).unwrap();
+            let tag = tag_of(&payload);
+            assert_eq!(value.as_object().unwrap().keys().next().unwrap(), tag);
+            validate(&c, &schema_ref("AgentEventPayload"), &value)
+                .unwrap_or_else(|e| panic!("{tag}: {e}"));
+            let data = agent_event_data_schema(tag).unwrap();
+            if data.get("properties").is_some() {
+                assert_matches(&c, &data, &value[tag], &format!("{tag} data"));
+            } else {
+                validate(&c, &data, &value[tag]).unwrap_or_else(|e| panic!("{tag} data: {e}"));
+            }
+
+            let event = AgentEvent {
+                agent_key: "codex".into(),
+                seq: 1,
+                stream: AgentEventStream::Stdout,
+                payload,
+            };
+            assert_matches(
+                &c,
+                &schema_ref("AgentEvent"),
+                &serde_json::to_value(&event).unwrap(),
+                &format!("{tag} event"),
+            );
+        }
+    }
+
+    #[test]
+    fn nested_types_declare_exactly_their_fields() {
+        let c = component_schemas();
+        for payload in sample_payloads() {
+            let (name, value) = match &payload {
+                AgentEventPayload::StreamMessage(m) => ("StreamMessage", serde_json::to_value(m)),
+                AgentEventPayload::TokenUsageLine { usage, .. } => {
+                    ("TokenUsage", serde_json::to_value(usage))
+                }
+                AgentEventPayload::QuotaExceeded { info, .. } => {
+                    ("QuotaExceededInfo", serde_json::to_value(info))
+                }
+                _ => continue,
+            };
+            assert_matches(&c, &schema_ref(name), &value.unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn capabilities_conform_for_every_backend() {
+        let c = component_schemas();
+        for backend in crate::runner::backend::ALL {
+            let value = serde_json::to_value(backend.capabilities()).unwrap();
+            assert_matches(
+                &c,
+                &schema_ref("BackendCapabilities"),
+                &value,
+                backend.key(),
+            );
+        }
+    }
+
+    #[cfg(feature = "history")]
+    #[test]
+    fn history_types_conform() {
+        use crate::history::{HistoryBlock, HistoryContent, HistoryMessage, HistorySession};
+        use crate::runner::Backend;
+
+        let c = component_schemas();
+        let session = HistorySession {
+            backend: Backend::Claude,
+            session_id: "s".into(),
+            summary: "sum".into(),
+            first_prompt: Some("hi".into()),
+            custom_title: None,
+            tag: None,
+            cwd: Some("/repo".into()),
+            git_branch: None,
+            last_modified_ms: 1,
+            created_at_ms: None,
+            message_count: Some(2),
+            size_bytes: None,
+        };
+        assert_matches(
+            &c,
+            &schema_ref("HistorySession"),
+            &serde_json::to_value(&session).unwrap(),
+            "HistorySession",
+        );
+
+        let blocks = vec![
+            HistoryBlock::Text { text: "a".into() },
+            HistoryBlock::Thinking { text: "b".into() },
+            HistoryBlock::ToolUse {
+                id: "t".into(),
+                name: "Bash".into(),
+                input: json!({}),
+            },
+            HistoryBlock::ToolResult {
+                tool_use_id: "t".into(),
+                content: json!("ok"),
+                is_error: false,
+            },
+            HistoryBlock::ServerToolUse {
+                id: "u".into(),
+                name: "advisor".into(),
+                input: json!({}),
+            },
+            HistoryBlock::ServerToolResult {
+                tool_use_id: "u".into(),
+                content: json!([]),
+                is_error: true,
+            },
+            HistoryBlock::Raw(json!({"type": "image"})),
+        ];
+        for content in [
+            HistoryContent::Text("plain".into()),
+            HistoryContent::Blocks(blocks),
+            HistoryContent::Raw(json!(42)),
+        ] {
+            let message = HistoryMessage {
+                role: MessageRole::Tool,
+                uuid: "m".into(),
+                session_id: "s".into(),
+                parent_tool_use_id: None,
+                content,
+            };
+            assert_matches(
+                &c,
+                &schema_ref("HistoryMessage"),
+                &serde_json::to_value(&message).unwrap(),
+                "HistoryMessage",
+            );
+        }
+    }
+
+    #[test]
+    fn validate_rejects_shape_drift() {
+        let c = component_schemas();
+        let stray = json!({"session_started": {"session_id": "s", "extra": 1}});
+        assert!(validate(&c, &schema_ref("AgentEventPayload"), &stray).is_err());
+        let missing = json!({"tool_result": {"call_id": "c", "output": 1}});
+        assert!(validate(&c, &schema_ref("AgentEventPayload"), &missing).is_err());
+        let bad_enum = json!({"info": {"agent_key": "a", "category": "yearly", "raw_message": ""}, "raw_agent_line_seq": 0});
+        assert!(validate(
+            &c,
+            &agent_event_data_schema("quota_exceeded").unwrap(),
+            &bad_enum
+        )
+        .is_err());
+    }
+}
@@ -13,6 +13,12 @@ pub struct SessionFile {
     pub updated_at: String,
     pub cwd: String,
     pub turns: Vec<SessionTurn>,
+    /// User-set display title (history rename).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// User-set tag (history tag).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,16 +65,21 @@ impl Default for SessionStore {
 impl SessionStore {
     /// Resolves AIKIT_SESSIONS_DIR env var, then ~/.aikit/sessions/. Creates dir if absent.
     pub fn open() -> Self {
-        let sessions_dir = if let Ok(dir) = std::env::var("AIKIT_SESSIONS_DIR") {
+        let sessions_dir = Self::default_dir();
+        let _ = std::fs::create_dir_all(&sessions_dir);
+        Self { sessions_dir }
+    }
+
+    /// The directory [`SessionStore::open`] uses, without creating it.
+    pub fn default_dir() -> PathBuf {
+        if let Ok(dir) = std::env::var("AIKIT_SESSIONS_DIR") {
             PathBuf::from(dir)
         } else {
             dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
                 .join(".aikit")
                 .join("sessions")
-        };
-        let _ = std::fs::create_dir_all(&sessions_dir);
-        Self { sessions_dir }
+        }
     }
 
     pub fn load(&self, id: &str) -> Result<SessionFile, SessionStoreError> {
@@ -224,6 +235,8 @@ mod tests {
                 tool_calls: None,
                 tool_results: None,
             }],
+            title: None,
+            tag: None,
         }
     }
 
@@ -283,6 +296,23 @@ mod tests {
         std::env::remove_var("AIKIT_SESSIONS_DIR");
     }
 
+    #[test]
+    fn test_load_file_without_title_or_tag() {
+        // Files written before history rename/tag existed carry neither field.
+        let tmp = TempDir::new().unwrap();
+        let store = make_store(&tmp);
+        let raw = r#"{"session_id":"old","agent":"aikit","created_at":"","updated_at":"","cwd":"","turns":[]}"#;
+        std::fs::write(store.sessions_dir.join("old.json"), raw).unwrap();
+        let loaded = store.load("old").unwrap();
+        assert_eq!(loaded.title, None);
+        assert_eq!(loaded.tag, None);
+
+        // ...and a session without them serializes without them.
+        store.save(&loaded).unwrap();
+        let written = std::fs::read_to_string(store.sessions_dir.join("old.json")).unwrap();
+        assert!(!written.contains("title") && !written.contains("\"tag\""));
+    }
+
     #[test]
     fn test_load_corrupt_returns_parse_error() {
         let tmp = TempDir::new().unwrap();
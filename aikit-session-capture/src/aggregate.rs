@@ -0,0 +1,513 @@
+//! Team-level aggregates over one [`EventStore`] per owner.
+//!
+//! A team aggregation host (`aikit serve` in ingest mode) keeps each owner's
+//! events in a store of its own and answers "who did what" by walking those
+//! stores through the ordinary [`EventStore`] query API — no store needs a
+//! cross-owner schema. Sessions are discovered with
+//! [`EventStore::sessions_for`], except for token usage, which
+//! [`usage_sessions`] also finds in sessions without a tool event.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::event_store::{EventStore, SessionSummary, StoreError};
+use crate::models::{ActionKind, ToolKind};
+
+/// Every tool a store may hold sessions for.
+const TOOLS: [ToolKind; 5] = [
+    ToolKind::ClaudeCode,
+    ToolKind::Codex,
+    ToolKind::OpenCode,
+    ToolKind::Cursor,
+    ToolKind::Gemini,
+];
+
+/// One owner's event store, as handed to the aggregate functions.
+pub type OwnerStore<'a> = (&'a str, &'a dyn EventStore);
+
+/// Time window shared by every aggregate. Bounds are inclusive epoch ms; an
+/// event with no timestamp only counts when both bounds are open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateWindow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_ms: Option<i64>,
+}
+
+impl AggregateWindow {
+    /// Whether an event at `at_ms` falls inside the window.
+    pub fn contains(&self, at_ms: Option<i64>) -> bool {
+        match at_ms {
+            Some(at) => {
+                self.since_ms.map_or(true, |s| at >= s) && self.until_ms.map_or(true, |u| at <= u)
+            }
+            None => self.since_ms.is_none() && self.until_ms.is_none(),
+        }
+    }
+
+    fn overlaps(&self, session: &SessionSummary) -> bool {
+        self.since_ms
+            .map_or(true, |s| session.last_event_at_ms >= s)
+            && self
+                .until_ms
+                .map_or(true, |u| session.first_event_at_ms <= u)
+    }
+}
+
+/// Row of [`sessions_per_owner`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnerSessions {
+    pub owner: String,
+    pub sessions: u64,
+    pub actions: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_event_at_ms: Option<i64>,
+}
+
+/// Row of [`actions_by_kind`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KindCount {
+    pub kind: ActionKind,
+    pub count: u64,
+}
+
+/// Row of [`tokens_by_model`]. Absent token fields add nothing; `turns`
+/// counts every usage envelope.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// `"unknown"` when the transcript did not name the model.
+    pub model: String,
+    pub turns: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub reasoning_tokens: u64,
+}
+
+/// Row of [`hot_files`]. `writes` covers `Write`, `Edit` and `Delete`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotFile {
+    pub path: String,
+    pub touches: u64,
+    pub reads: u64,
+    pub writes: u64,
+    pub owners: Vec<String>,
+}
+
+/// Sessions active in `window`, per owner, most recently active first.
+/// `actions` counts the sessions' actions inside the window.
+pub async fn sessions_per_owner(
+    stores: &[OwnerStore<'_>],
+    window: AggregateWindow,
+) -> Result<Vec<OwnerSessions>, StoreError> {
+    let mut rows = Vec::with_capacity(stores.len());
+    for (owner, store) in stores {
+        let mut row = OwnerSessions {
+            owner: owner.to_string(),
+            sessions: 0,
+            actions: 0,
+            last_event_at_ms: None,
+        };
+        for (tool, session) in sessions_in(*store, window).await? {
+            row.sessions += 1;
+            row.last_event_at_ms = row.last_event_at_ms.max(Some(session.last_event_at_ms));
+            if window.since_ms.is_none() && window.until_ms.is_none() {
+                row.actions += session.action_count;
+            } else {
+                let actions = store
+                    .actions_for_session(tool, &session.session_id, u32::MAX, 0)
+                    .await?;
+                row.actions += actions
+                    .iter()
+                    .filter(|a| window.contains(a.started_at_ms))
+                    .count() as u64;
+            }
+        }
+        rows.push(row);
+    }
+    rows.sort_by(|a, b| {
+        b.last_event_at_ms
+            .cmp(&a.last_event_at_ms)
+            .then_with(|| a.owner.cmp(&b.owner))
+    });
+    Ok(rows)
+}
+
+/// Action counts by kind across all owners, most frequent first.
+pub async fn actions_by_kind(
+    stores: &[OwnerStore<'_>],
+    window: AggregateWindow,
+) -> Result<Vec<KindCount>, StoreError> {
+    let mut counts: BTreeMap<&'static str, (ActionKind, u64)> = BTreeMap::new();
+    for (_, store) in stores {
+        for (tool, session) in sessions_in(*store, window).await? {
+            for action in store
+                .actions_for_session(tool, &session.session_id, u32::MAX, 0)
+                .await?
+            {
+                if window.contains(action.started_at_ms) {
+                    counts
+                        .entry(action.kind.as_str())
+                        .or_insert((action.kind, 0))
+                        .1 += 1;
+                }
+            }
+        }
+    }
+    let mut rows: Vec<KindCount> = counts
+        .into_values()
+        .map(|(kind, count)| KindCount { kind, count })
+        .collect();
+    rows.sort_by(|a, b| b.count.cmp(&a.count));
+    Ok(rows)
+}
+
+/// Token usage by model across all owners, most output tokens first.
+pub async fn tokens_by_model(
+    stores: &[OwnerStore<'_>],
+    window: AggregateWindow,
+) -> Result<Vec<ModelUsage>, StoreError> {
+    let mut models: BTreeMap<String, ModelUsage> = BTreeMap::new();
+    for (_, store) in stores {
+        for session in usage_sessions(*store).await? {
+            for ev in store
+                .token_events_for_session(session.tool, &session.session_id)
+                .await?
+            {
+                if !window.contains(Some(ev.captured_at_ms)) {
+                    continue;
+                }
+                let model = ev.model.clone().unwrap_or_else(|| "unknown".to_string());
+                let row = models.entry(model.clone()).or_insert_with(|| ModelUsage {
+                    model,
+                    ..ModelUsage::default()
+                });
+                row.turns += 1;
+                row.input_tokens += ev.input_tokens.unwrap_or(0);
+                row.output_tokens += ev.output_tokens.unwrap_or(0);
+                row.cache_read_tokens += ev.cache_read_tokens.unwrap_or(0);
+                row.cache_creation_tokens += ev.cache_creation_tokens.unwrap_or(0);
+                row.reasoning_tokens += ev.reasoning_tokens.unwrap_or(0);
+            }
+        }
+    }
+    let mut rows: Vec<ModelUsage> = models.into_values().collect();
+    rows.sort_by(|a, b| b.output_tokens.cmp(&a.output_tokens));
+    Ok(rows)
+}
+
+/// The `limit` files agents read or changed most across all owners, with
+/// the owners whose sessions touched them. `limit == 0` returns them all.
+/// Paths are keyed by [`repo_relative`], so one file in several owners'
+/// checkouts is one row.
+pub async fn hot_files(
+    stores: &[OwnerStore<'_>],
+    window: AggregateWindow,
+    limit: usize,
+) -> Result<Vec<HotFile>, StoreError> {
+    let mut files: BTreeMap<String, (u64, u64, BTreeSet<String>)> = BTreeMap::new();
+    for (owner, store) in stores {
+        for (tool, session) in sessions_in(*store, window).await? {
+            for action in store
+                .actions_for_session(tool, &session.session_id, u32::MAX, 0)
+                .await?
+            {
+                let Some(path) = action.target.as_deref() else {
+                    continue;
+                };
+                let read = match action.kind {
+                    ActionKind::Read => true,
+                    ActionKind::Write | ActionKind::Edit | ActionKind::Delete => false,
+                    _ => continue,
+                };
+                if !window.contains(action.started_at_ms) {
+                    continue;
+                }
+                let path = repo_relative(path, action.git_root.as_deref());
+                let entry = files.entry(path).or_default();
+                if read {
+                    entry.0 += 1;
+                } else {
+                    entry.1 += 1;
+                }
+                entry.2.insert(owner.to_string());
+            }
+        }
+    }
+    let mut rows: Vec<HotFile> = files
+        .into_iter()
+        .map(|(path, (reads, writes, owners))| HotFile {
+            path,
+            touches: reads + writes,
+            reads,
+            writes,
+            owners: owners.into_iter().collect(),
+        })
+        .collect();
+    rows.sort_by(|a, b| b.touches.cmp(&a.touches));
+    if limit > 0 {
+        rows.truncate(limit);
+    }
+    Ok(rows)
+}
+
+/// `target` relative to `git_root` when it is a path under it (`"."` for the
+/// root itself); otherwise `target` unchanged.
+pub fn repo_relative(target: &str, git_root: Option<&Path>) -> String {
+    match git_root.and_then(|root| Path::new(target).strip_prefix(root).ok()) {
+        Some(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Some(rel) => rel.to_string_lossy().into_owned(),
+        None => target.to_string(),
+    }
+}
+
+/// Row of [`usage_sessions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageSession {
+    pub tool: ToolKind,
+    pub session_id: String,
+    /// From the session's tool events; `None` when it has none.
+    pub git_root: Option<PathBuf>,
+}
+
+/// Every session in `store`, across all tools, with token events or cache
+/// observations, whether or not it recorded a tool event.
+pub async fn usage_sessions(store: &dyn EventStore) -> Result<Vec<UsageSession>, StoreError> {
+    let mut out = Vec::new();
+    for tool in TOOLS {
+        let ids = store.usage_sessions_for(tool).await?;
+        if ids.is_empty() {
+            continue;
+        }
+        let roots: HashMap<String, Option<PathBuf>> = store
+            .sessions_for(tool, None, u32::MAX, 0)
+            .await?
+            .into_iter()
+            .map(|s| (s.session_id, s.git_root))
+            .collect();
+        for session_id in ids {
+            out.push(UsageSession {
+                tool,
+                git_root: roots.get(&session_id).cloned().flatten(),
+                session_id,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Every session in `store`, across all tools, that overlaps `window`.
+pub async fn sessions_in(
+    store: &dyn EventStore,
+    window: AggregateWindow,
+) -> Result<Vec<(ToolKind, SessionSummary)>, StoreError> {
+    let mut out = Vec::new();
+    for tool in TOOLS {
+        for session in store.sessions_for(tool, None, u32::MAX, 0).await? {
+            if window.overlaps(&session) {
+                out.push((tool, session));
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::{EventBatch, InMemoryEventStore};
+    use crate::models::{ActionStatus, CaptureSource, TokenEvent, ToolEvent};
+    use std::path::PathBuf;
+
+    fn action(id: &str, session: &str, kind: ActionKind, target: &str, at: i64) -> ToolEvent {
+        ToolEvent {
+            source_event_id: id.into(),
+            source_file: PathBuf::from(format!("/tmp/{session}.jsonl")),
+            session_id: session.into(),
+            tool: ToolKind::ClaudeCode,
+            kind,
+            target: Some(target.into()),
+            input: None,
+            output: None,
+            status: ActionStatus::Success,
+            error_message: None,
+            started_at_ms: Some(at),
+            duration_ms: None,
+            git_root: None,
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    fn usage(id: &str, session: &str, model: Option<&str>, output: u64, at: i64) -> TokenEvent {
+        TokenEvent {
+            source_event_id: id.into(),
+            session_id: session.into(),
+            tool: ToolKind::ClaudeCode,
+            model: model.map(Into::into),
+            request_id: None,
+            input_tokens: Some(10),
+            cache_read_tokens: None,
+            cache_creation_tokens: None,
+            cache_creation_1h_tokens: None,
+            output_tokens: Some(output),
+            reasoning_tokens: None,
+            captured_at_ms: at,
+            captured_via: CaptureSource::Transcript,
+        }
+    }
+
+    async fn team() -> (InMemoryEventStore, InMemoryEventStore) {
+        let alice = InMemoryEventStore::new();
+        alice
+            .upsert_events(EventBatch {
+                tool_events: vec![
+                    action("1", "a1", ActionKind::Read, "src/lib.rs", 1_000),
+                    action("2", "a1", ActionKind::Edit, "src/lib.rs", 2_000),
+                    action("3", "a1", ActionKind::Bash, "cargo test", 3_000),
+                ],
+                token_events: vec![
+                    usage("t1", "a1", Some("opus"), 100, 1_500),
+                    usage("t2", "a1", None, 5, 2_500),
+                ],
+                cache_observations: vec![],
+            })
+            .await
+            .unwrap();
+        let bob = InMemoryEventStore::new();
+        bob.upsert_events(EventBatch {
+            tool_events: vec![
+                action("1", "b1", ActionKind::Read, "src/lib.rs", 10_000),
+                action("2", "b1", ActionKind::Write, "README.md", 11_000),
+            ],
+            token_events: vec![usage("t1", "b1", Some("opus"), 50, 10_500)],
+            cache_observations: vec![],
+        })
+        .await
+        .unwrap();
+        (alice, bob)
+    }
+
+    #[tokio::test]
+    async fn aggregates_span_every_owner() {
+        let (alice, bob) = team().await;
+        let stores: [OwnerStore<'_>; 2] = [("alice", &alice), ("bob", &bob)];
+        let all = AggregateWindow::default();
+
+        let sessions = sessions_per_owner(&stores, all).await.unwrap();
+        assert_eq!(sessions[0].owner, "bob");
+        assert_eq!((sessions[0].sessions, sessions[0].actions), (1, 2));
+        assert_eq!((sessions[1].sessions, sessions[1].actions), (1, 3));
+
+        let kinds = actions_by_kind(&stores, all).await.unwrap();
+        assert_eq!(
+            kinds[0],
+            KindCount {
+                kind: ActionKind::Read,
+                count: 2
+            }
+        );
+        assert_eq!(kinds.iter().map(|k| k.count).sum::<u64>(), 5);
+
+        let tokens = tokens_by_model(&stores, all).await.unwrap();
+        assert_eq!(tokens[0].model, "opus");
+        assert_eq!((tokens[0].turns, tokens[0].output_tokens), (2, 150));
+        assert_eq!(tokens[0].input_tokens, 20);
+        assert_eq!(tokens[1].model, "unknown");
+
+        let files = hot_files(&stores, all, 1).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!((files[0].reads, files[0].writes), (2, 1));
+        assert_eq!(files[0].owners, vec!["alice", "bob"]);
+    }
+
+    #[tokio::test]
+    async fn window_limits_sessions_and_events() {
+        let (alice, bob) = team().await;
+        let stores: [OwnerStore<'_>; 2] = [("alice", &alice), ("bob", &bob)];
+        let window = AggregateWindow {
+            since_ms: Some(2_000),
+            until_ms: Some(10_000),
+        };
+
+        let sessions = sessions_per_owner(&stores, window).await.unwrap();
+        let alice_row = sessions.iter().find(|r| r.owner == "alice").unwrap();
+        assert_eq!(alice_row.actions, 2);
+        let bob_row = sessions.iter().find(|r| r.owner == "bob").unwrap();
+        assert_eq!(bob_row.actions, 1);
+
+        let tokens = tokens_by_model(&stores, window).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].model, "unknown");
+
+        let files = hot_files(&stores, window, 0).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].reads, files[0].writes), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn hot_files_merge_paths_across_checkouts() {
+        let in_checkout = |id: &str, target: &str, root: &str| ToolEvent {
+            git_root: Some(PathBuf::from(root)),
+            ..action(id, "s1", ActionKind::Read, target, 1_000)
+        };
+        let alice = InMemoryEventStore::new();
+        alice
+            .upsert_events(EventBatch {
+                tool_events: vec![in_checkout(
+                    "1",
+                    "/home/alice/app/src/lib.rs",
+                    "/home/alice/app",
+                )],
+                ..EventBatch::default()
+            })
+            .await
+            .unwrap();
+        let bob = InMemoryEventStore::new();
+        bob.upsert_events(EventBatch {
+            tool_events: vec![
+                in_checkout("1", "/work/app/src/lib.rs", "/work/app"),
+                in_checkout("2", "/etc/hosts", "/work/app"),
+            ],
+            ..EventBatch::default()
+        })
+        .await
+        .unwrap();
+        let stores: [OwnerStore<'_>; 2] = [("alice", &alice), ("bob", &bob)];
+        let files = hot_files(&stores, AggregateWindow::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].owners, vec!["alice", "bob"]);
+        assert_eq!(files[1].path, "/etc/hosts");
+        assert_eq!(
+            repo_relative("/work/app", Some(Path::new("/work/app"))),
+            "."
+        );
+    }
+
+    #[tokio::test]
+    async fn token_usage_counts_sessions_without_tool_events() {
+        let (alice, bob) = team().await;
+        bob.upsert_events(EventBatch {
+            token_events: vec![usage("t1", "b2", Some("haiku"), 7, 20_000)],
+            ..EventBatch::default()
+        })
+        .await
+        .unwrap();
+        let stores: [OwnerStore<'_>; 2] = [("alice", &alice), ("bob", &bob)];
+        let tokens = tokens_by_model(&stores, AggregateWindow::default())
+            .await
+            .unwrap();
+        let haiku = tokens.iter().find(|row| row.model == "haiku").unwrap();
+        assert_eq!((haiku.turns, haiku.output_tokens), (1, 7));
+
+        let sessions = usage_sessions(&bob).await.unwrap();
+        let ids: Vec<_> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, ["b1", "b2"]);
+    }
+}
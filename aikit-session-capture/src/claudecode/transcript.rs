@@ -210,13 +210,14 @@ pub(crate) fn parse(
                         msg_id_to_idx.insert(id.clone(), res.token_events.len());
                         res.token_events.push(ev);
                     }
-                    // Also emit a Tier-2 CacheObservation so the cachetrack
-                    // module (future) can attribute invalidations. Spec 010
-                    // data-model.md.
+                    // Also emit a Tier-2 CacheObservation so the cache
+                    // analysis (aikit-sdk `cost::cache`) can attribute
+                    // invalidations. Spec 010 data-model.md.
                     res.cache_observations.push(CacheObservation {
                         source_event_id: format!("cachetrack:{}", id),
                         session_id: rec.session_id.clone().unwrap_or_default(),
                         tool: ToolKind::ClaudeCode,
+                        model: msg.model.clone(),
                         cache_read_input_tokens: usage.cache_read_input_tokens,
                         cache_creation_input_tokens: if cache_creation_total > 0 {
                             Some(cache_creation_total)
@@ -229,7 +230,14 @@ pub(crate) fn parse(
                             None
                         },
                         assistant_blocks_hash: None, // Phase 2 leaves this for the cachetrack module.
+                        // Transcripts don't record the tool list sent with
+                        // each request, so there is nothing to diff.
                         tools_changed: Vec::new(),
+                        prompt_fingerprint: rec
+                            .version
+                            .as_deref()
+                            .filter(|v| !v.is_empty())
+                            .map(|v| format!("claude-code/{v}")),
                         observed_at_ms: ts_ms.unwrap_or(0),
                     });
                     continue;
@@ -353,6 +361,9 @@ struct RawLine {
     git_branch: Option<String>,
     #[serde(default)]
     cwd: Option<String>,
+    /// Claude Code CLI version that wrote the record.
+    #[serde(default)]
+    version: Option<String>,
     #[serde(default)]
     uuid: Option<String>,
     #[serde(default)]
@@ -915,4 +926,24 @@ mod tests {
             "concatenated records should produce warnings or partial events, not a silent no-op"
         );
     }
+
+    #[test]
+    fn cache_observations_carry_model_and_prompt_fingerprint() {
+        let bytes = concat!(
+            r#"{"type":"assistant","sessionId":"s","version":"2.0.14","uuid":"u1","timestamp":"2026-04-16T10:00:00Z","message":{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5","content":[],"usage":{"input_tokens":3,"output_tokens":9,"cache_creation_input_tokens":1200,"cache_read_input_tokens":0}}}"#,
+            "\n",
+            r#"{"type":"assistant","sessionId":"s","uuid":"u2","timestamp":"2026-04-16T10:00:05Z","message":{"id":"msg_2","role":"assistant","model":"claude-sonnet-4-5","content":[],"usage":{"input_tokens":3,"output_tokens":9,"cache_creation_input_tokens":40,"cache_read_input_tokens":1200}}}"#,
+            "\n",
+        );
+        let res = parse(Path::new("/tmp/s.jsonl"), bytes.as_bytes(), 0, &scrubber()).unwrap();
+        assert_eq!(res.cache_observations.len(), 2);
+        let first = &res.cache_observations[0];
+        assert_eq!(first.model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(
+            first.prompt_fingerprint.as_deref(),
+            Some("claude-code/2.0.14")
+        );
+        assert_eq!(first.cache_creation_input_tokens, Some(1200));
+        assert!(res.cache_observations[1].prompt_fingerprint.is_none());
+    }
 }
@@ -4,22 +4,38 @@
 //! The crate ships [`InMemoryEventStore`] for tests. Production SQLite impl
 //! lives in `aikit-serve/src/storage/`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 use async_trait::async_trait;
 
 use crate::models::{ActionKind, CacheObservation, TokenEvent, ToolEvent, ToolKind};
+use crate::search::{self, SearchMatch, SearchQuery};
 
 /// Argument to [`EventStore::upsert_events`]. Each field may be empty.
-#[derive(Debug, Clone, Default)]
+/// Serializable so a batch can be forwarded to a team aggregation host.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct EventBatch {
+    #[serde(default)]
     pub tool_events: Vec<ToolEvent>,
+    #[serde(default)]
     pub token_events: Vec<TokenEvent>,
+    #[serde(default)]
     pub cache_observations: Vec<CacheObservation>,
 }
 
+impl EventBatch {
+    /// Total number of events across all three kinds.
+    pub fn len(&self) -> usize {
+        self.tool_events.len() + self.token_events.len() + self.cache_observations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 /// One row returned by [`EventStore::sessions_for`].
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SessionSummary {
@@ -84,10 +100,15 @@ pub trait EventStore: Send + Sync {
         offset: u32,
     ) -> Result<Vec<ToolEvent>, StoreError>;
 
-    /// Substring/regex search over `ToolEvent.output`. Used by MCP
-    /// `search_past_outputs`. Hosts with FTS5 should override.
+    /// Case-insensitive substring search over `ToolEvent.output`. Prefer
+    /// [`EventStore::search`], which ranks and filters.
     async fn search_outputs(&self, query: &str, limit: u32) -> Result<Vec<ToolEvent>, StoreError>;
 
+    /// Full-text search over `target`, `input`, `output` and
+    /// `error_message` with the [`crate::search`] query syntax, best match
+    /// first. Used by MCP `search_past_outputs` and `GET /capture/search`.
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchMatch>, StoreError>;
+
     /// Cost-engine pull accessor (spec 009 integration, spec 010 §16).
     async fn token_events_for_session(
         &self,
@@ -95,6 +116,19 @@ pub trait EventStore: Send + Sync {
         session_id: &str,
     ) -> Result<Vec<TokenEvent>, StoreError>;
 
+    /// Tier-2 prompt-cache observations for one session, oldest first. Read
+    /// by the cache analysis in aikit-sdk `cost::cache`.
+    async fn cache_observations_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+    ) -> Result<Vec<CacheObservation>, StoreError>;
+
+    /// Ids of `tool`'s sessions with token events or cache observations,
+    /// sorted. Unlike [`EventStore::sessions_for`] this includes sessions
+    /// that recorded no tool event. Used to find every session with usage.
+    async fn usage_sessions_for(&self, tool: ToolKind) -> Result<Vec<String>, StoreError>;
+
     /// Freshness join: most recent `Read`/`Edit`/`Write` of `path` across
     /// every session. Used by MCP `check_file_freshness`.
     async fn last_file_touch(&self, path: &Path) -> Result<Option<FileTouch>, StoreError>;
@@ -263,6 +297,37 @@ impl EventStore for InMemoryEventStore {
         Ok(matches)
     }
 
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchMatch>, StoreError> {
+        let te = self.tool_events.read().unwrap();
+        let terms = query.terms();
+        let mut hits: Vec<SearchMatch> = te
+            .iter()
+            .filter(|e| query.filters_match(e))
+            .filter_map(|e| {
+                let (score, snippet) = if terms.is_empty() {
+                    (0.0, search::leading_snippet(e))
+                } else {
+                    search::match_event(&terms, e)?
+                };
+                Some(SearchMatch {
+                    event: e.clone(),
+                    snippet,
+                    score,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.event.started_at_ms.cmp(&a.event.started_at_ms))
+        });
+        Ok(hits
+            .into_iter()
+            .skip(query.offset as usize)
+            .take(query.limit as usize)
+            .collect())
+    }
+
     async fn token_events_for_session(
         &self,
         tool: ToolKind,
@@ -276,6 +341,38 @@ impl EventStore for InMemoryEventStore {
             .collect())
     }
 
+    async fn cache_observations_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+    ) -> Result<Vec<CacheObservation>, StoreError> {
+        let cache = self.cache_observations.read().unwrap();
+        let mut out: Vec<CacheObservation> = cache
+            .iter()
+            .filter(|e| e.tool == tool && e.session_id == session_id)
+            .cloned()
+            .collect();
+        out.sort_by_key(|e| e.observed_at_ms);
+        Ok(out)
+    }
+
+    async fn usage_sessions_for(&self, tool: ToolKind) -> Result<Vec<String>, StoreError> {
+        let tk = self.token_events.read().unwrap();
+        let cache = self.cache_observations.read().unwrap();
+        let ids: BTreeSet<String> = tk
+            .iter()
+            .filter(|e| e.tool == tool)
+            .map(|e| e.session_id.clone())
+            .chain(
+                cache
+                    .iter()
+                    .filter(|e| e.tool == tool)
+                    .map(|e| e.session_id.clone()),
+            )
+            .collect();
+        Ok(ids.into_iter().collect())
+    }
+
     async fn last_file_touch(&self, path: &Path) -> Result<Option<FileTouch>, StoreError> {
         let te = self.tool_events.read().unwrap();
         let last_read = te
@@ -384,6 +481,48 @@ mod tests {
         assert_eq!(got.len(), 1);
     }
 
+    #[tokio::test]
+    async fn search_ranks_and_filters() {
+        let store = InMemoryEventStore::new();
+        let mut a = ev("1", "s1", ActionKind::Bash, 100);
+        a.output = Some("error: mismatched types in main.rs".into());
+        let mut b = ev("2", "s1", ActionKind::Bash, 200);
+        b.output = Some("error: mismatched types; error: unused import".into());
+        let mut c = ev("3", "s2", ActionKind::Read, 300);
+        c.target = Some("/repo/src/errors.rs".into());
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![a, b, c],
+                token_events: vec![],
+                cache_observations: vec![],
+            })
+            .await
+            .unwrap();
+
+        let got = store.search(&SearchQuery::new("error")).await.unwrap();
+        let ids: Vec<_> = got
+            .iter()
+            .map(|m| m.event.source_event_id.as_str())
+            .collect();
+        assert_eq!(ids, ["2", "1"], "more occurrences rank first");
+        assert!(got[0].snippet.contains("<mark>error</mark>"));
+
+        let got = store.search(&SearchQuery::new("error*")).await.unwrap();
+        assert_eq!(got.len(), 3, "prefix reaches errors.rs");
+
+        let mut q = SearchQuery::new("\"mismatched types\"");
+        q.since_ms = Some(150);
+        let got = store.search(&q).await.unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].event.source_event_id, "2");
+
+        let mut q = SearchQuery::new("");
+        q.kind = Some(ActionKind::Read);
+        let got = store.search(&q).await.unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].event.session_id, "s2");
+    }
+
     #[tokio::test]
     async fn token_events_absence_is_none() {
         // Sanity-check the CaptureSource variant deserializes cleanly.
@@ -27,12 +27,14 @@
 //! [`SecretScrubber`]: scrub::SecretScrubber
 
 pub mod adapter;
+pub mod aggregate;
 pub mod cursor_offset;
 pub mod event_store;
 pub mod homes;
 pub mod models;
 pub mod registry;
 pub mod scrub;
+pub mod search;
 
 #[cfg(feature = "claudecode")]
 pub mod claudecode;
@@ -48,6 +50,7 @@ pub mod mcp;
 pub mod watch;
 
 pub use adapter::{Adapter, AdapterError, ParseResult, ParseWarning};
+pub use aggregate::{AggregateWindow, HotFile, KindCount, ModelUsage, OwnerSessions, OwnerStore};
 pub use cursor_offset::{CursorStore, InMemoryCursorStore, JsonSidecarCursorStore, ParseCursor};
 pub use event_store::{
     EventBatch, EventStore, FileTouch, InMemoryEventStore, SessionSummary, StoreError,
@@ -57,4 +60,8 @@ pub use models::{
     ActionKind, ActionStatus, CacheObservation, CaptureSource, TokenEvent, ToolEvent, ToolKind,
 };
 pub use registry::Registry;
-pub use scrub::{SecretScrubber, SCRUBBER_PATTERN_VERSION};
+pub use scrub::{
+    EntropyDetector, PiiDetectors, ScrubConfigError, ScrubReport, SecretScrubber,
+    HIGH_ENTROPY_LABEL, SCRUBBER_PATTERN_VERSION,
+};
+pub use search::{SearchMatch, SearchQuery, SearchTerm};
@@ -2,7 +2,7 @@
 //!
 //! Four tools backed by [`EventStore`](crate::EventStore):
 //! - `check_file_freshness` — freshness join: most recent Read/Edit/Write of a path
-//! - `search_past_outputs` — substring search over `ToolEvent.output`
+//! - `search_past_outputs` — ranked full-text search over captured tool calls
 //! - `get_session_summary` — rule-based summary (no LLM, §15.1)
 //! - `list_actions_around` — chronological ±N actions around a given action
 //!
@@ -17,7 +17,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::event_store::EventStore;
-use crate::models::{ToolEvent, ToolKind};
+use crate::models::{ActionKind, ToolEvent, ToolKind};
+use crate::search::SearchQuery;
 
 use cli_framework::app::AppBuilder;
 use cli_framework::command::Command;
@@ -83,10 +84,32 @@ fn command_search_past_outputs(store: Arc<dyn EventStore>) -> Command {
     command(
         "search_past_outputs",
         CommandSpec {
-            summary: "Search prior captured tool outputs",
+            summary: "Search prior captured tool calls, best match first",
             args: vec![
-                string_arg("query", Cardinality::Required, "Substring to search for"),
+                string_arg(
+                    "query",
+                    Cardinality::Required,
+                    "Words to find; \"quoted phrase\", prefix*",
+                ),
                 int_arg("limit", 20, "Maximum result count"),
+                string_arg(
+                    "tool",
+                    Cardinality::Optional,
+                    "Only this tool (e.g. claude_code)",
+                ),
+                string_arg(
+                    "kind",
+                    Cardinality::Optional,
+                    "Only this action kind (e.g. bash)",
+                ),
+                string_arg("session_id", Cardinality::Optional, "Only this session"),
+                optional_int_arg("since_ms", "Only actions started at or after (epoch ms)"),
+                optional_int_arg("until_ms", "Only actions started before (epoch ms)"),
+                string_arg(
+                    "git_root",
+                    Cardinality::Optional,
+                    "Only this repository root",
+                ),
             ],
             category: Some("session-capture"),
             ..CommandSpec::default()
@@ -99,6 +122,12 @@ fn command_search_past_outputs(store: Arc<dyn EventStore>) -> Command {
                     SearchPastOutputsArgs {
                         query: str_arg(&args, "query").unwrap_or_default(),
                         limit: u32_arg(&args, "limit", 20),
+                        tool: enum_arg(&args, "tool")?,
+                        kind: enum_arg(&args, "kind")?,
+                        session_id: str_arg(&args, "session_id"),
+                        since_ms: i64_arg(&args, "since_ms"),
+                        until_ms: i64_arg(&args, "until_ms"),
+                        git_root: str_arg(&args, "git_root"),
                     },
                 )
                 .await?;
@@ -216,6 +245,17 @@ fn int_arg(name: &'static str, default: i64, help: &'static str) -> ArgSpec {
     }
 }
 
+fn optional_int_arg(name: &'static str, help: &'static str) -> ArgSpec {
+    ArgSpec {
+        name,
+        kind: ArgKind::Option,
+        value_type: ArgValueType::Int,
+        cardinality: Cardinality::Optional,
+        help,
+        ..ArgSpec::default()
+    }
+}
+
 fn str_arg(args: &HashMap<String, ArgValue>, name: &str) -> Option<String> {
     match args.get(name) {
         Some(ArgValue::Str(s)) | Some(ArgValue::Enum(s)) => Some(s.clone()),
@@ -223,6 +263,26 @@ fn str_arg(args: &HashMap<String, ArgValue>, name: &str) -> Option<String> {
     }
 }
 
+fn i64_arg(args: &HashMap<String, ArgValue>, name: &str) -> Option<i64> {
+    match args.get(name) {
+        Some(ArgValue::Int(i)) => Some(*i),
+        _ => None,
+    }
+}
+
+/// Parse a string arg into a snake_case model enum (`ToolKind`, `ActionKind`).
+fn enum_arg<T: serde::de::DeserializeOwned>(
+    args: &HashMap<String, ArgValue>,
+    name: &str,
+) -> anyhow::Result<Option<T>> {
+    str_arg(args, name)
+        .map(|s| {
+            serde_json::from_value(Value::String(s.clone()))
+                .map_err(|_| anyhow::anyhow!("unknown {name} '{s}'"))
+        })
+        .transpose()
+}
+
 fn u32_arg(args: &HashMap<String, ArgValue>, name: &str, default: u32) -> u32 {
     match args.get(name) {
         Some(ArgValue::Int(i)) if *i >= 0 => (*i).min(u32::MAX as i64) as u32,
@@ -279,12 +339,41 @@ pub async fn check_file_freshness(
 
 // ── search_past_outputs ───────────────────────────────────────────────────────
 
-/// Args for [`search_past_outputs`].
+/// Args for [`search_past_outputs`]. `query` uses the
+/// [`crate::search`] syntax; the other fields filter (`None` = any).
 #[derive(Debug, Deserialize)]
 pub struct SearchPastOutputsArgs {
     pub query: String,
     #[serde(default = "default_search_limit")]
     pub limit: u32,
+    #[serde(default)]
+    pub tool: Option<ToolKind>,
+    #[serde(default)]
+    pub kind: Option<ActionKind>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub since_ms: Option<i64>,
+    #[serde(default)]
+    pub until_ms: Option<i64>,
+    #[serde(default)]
+    pub git_root: Option<String>,
+}
+
+impl SearchPastOutputsArgs {
+    /// Unfiltered search for `query` with the default limit.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            limit: default_search_limit(),
+            tool: None,
+            kind: None,
+            session_id: None,
+            since_ms: None,
+            until_ms: None,
+            git_root: None,
+        }
+    }
 }
 
 fn default_search_limit() -> u32 {
@@ -305,26 +394,48 @@ pub struct SearchHit {
     pub kind: String,
     pub target: Option<String>,
     pub output_excerpt: String,
+    /// Best-matching excerpt with hits wrapped in `<mark>`…`</mark>`.
+    pub snippet: String,
+    pub score: f64,
     pub started_at_ms: Option<i64>,
 }
 
-/// Substring search over all `ToolEvent.output` fields. Used by agents to
-/// recall past tool outputs ("what did the test suite say last time?").
+/// Ranked full-text search over captured tool targets, inputs, outputs and
+/// errors. Used by agents to recall past tool calls ("what did the test
+/// suite say last time?").
 pub async fn search_past_outputs(
     store: &dyn EventStore,
     args: SearchPastOutputsArgs,
 ) -> anyhow_result::Result<SearchResult> {
-    let events = store.search_outputs(&args.query, args.limit).await?;
-    let results = events
+    let query = SearchQuery {
+        tool: args.tool,
+        kind: args.kind,
+        session_id: args.session_id,
+        since_ms: args.since_ms,
+        until_ms: args.until_ms,
+        git_root: args.git_root.map(Into::into),
+        limit: args.limit,
+        ..SearchQuery::new(args.query)
+    };
+    let matches = store.search(&query).await?;
+    let results = matches
         .into_iter()
-        .map(|ev| SearchHit {
-            source_event_id: ev.source_event_id,
-            session_id: ev.session_id,
-            tool: ev.tool.as_str().to_string(),
-            kind: ev.kind.as_str().to_string(),
-            target: ev.target,
-            output_excerpt: ev.output.unwrap_or_default().chars().take(500).collect(),
-            started_at_ms: ev.started_at_ms,
+        .map(|m| SearchHit {
+            source_event_id: m.event.source_event_id,
+            session_id: m.event.session_id,
+            tool: m.event.tool.as_str().to_string(),
+            kind: m.event.kind.as_str().to_string(),
+            target: m.event.target,
+            output_excerpt: m
+                .event
+                .output
+                .unwrap_or_default()
+                .chars()
+                .take(500)
+                .collect(),
+            snippet: m.snippet,
+            score: m.score,
+            started_at_ms: m.event.started_at_ms,
         })
         .collect();
     Ok(SearchResult { results })
@@ -480,8 +591,14 @@ pub fn tool_args_schema(tool: &str) -> Value {
         "search_past_outputs" => serde_json::json!({
             "type": "object",
             "properties": {
-                "query": { "type": "string", "description": "Substring to search for" },
-                "limit": { "type": "integer", "default": 20, "description": "Max results" }
+                "query": { "type": "string", "description": "Words to find; \"quoted phrase\", prefix*" },
+                "limit": { "type": "integer", "default": 20, "description": "Max results" },
+                "tool": { "type": "string", "description": "Only this tool (e.g. claude_code)" },
+                "kind": { "type": "string", "description": "Only this action kind (e.g. bash)" },
+                "session_id": { "type": "string", "description": "Only this session" },
+                "since_ms": { "type": "integer", "description": "Only actions started at or after (epoch ms)" },
+                "until_ms": { "type": "integer", "description": "Only actions started before (epoch ms)" },
+                "git_root": { "type": "string", "description": "Only this repository root" }
             },
             "required": ["query"]
         }),
@@ -565,8 +682,8 @@ mod tests {
         let result = search_past_outputs(
             &store,
             SearchPastOutputsArgs {
-                query: "PASSED".into(),
-                limit: 10,
+                kind: Some(ActionKind::Bash),
+                ..SearchPastOutputsArgs::new("PASSED")
             },
         )
         .await
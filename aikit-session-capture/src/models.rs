@@ -176,6 +176,9 @@ pub struct CacheObservation {
     pub source_event_id: String,
     pub session_id: String,
     pub tool: ToolKind,
+    /// Model that served the turn; a prompt cache never survives a switch.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub model: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_read_input_tokens: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -189,6 +192,11 @@ pub struct CacheObservation {
     /// Tool names that differ from the prior turn (a cache invalidation cause).
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub tools_changed: Vec<String>,
+    /// Opaque value that changes whenever the system prompt the client sends
+    /// does, as far as the adapter can tell. Claude Code stamps its CLI
+    /// version, which fixes the system prompt and built-in tool definitions.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub prompt_fingerprint: Option<String>,
     pub observed_at_ms: i64,
 }
 
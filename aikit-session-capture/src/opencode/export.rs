@@ -0,0 +1,164 @@
+//! Per-session JSONL export of `opencode.db`, for session sync.
+//!
+//! OpenCode has no transcript file to upload, so sync renders each session
+//! from the `session` / `message` / `part` tables into a canonical JSONL
+//! document instead:
+//!
+//! ```text
+//! {"type":"session","id":…,"directory":…,"time_created":…}
+//! {"type":"message","id":…,"time_created":…,"time_updated":…,"data":{…}}
+//! {"type":"part","id":…,"message_id":…,"time_created":…,"time_updated":…,"data":{…}}
+//! ```
+//!
+//! Messages are ordered by `(time_created, id)`, each followed by its parts in
+//! the same order, so a session that only grows renders as an append. The
+//! session row's `time_updated` moves with every message and is left out of
+//! the header for the same reason. `data` columns are embedded as JSON when
+//! they parse and as a string otherwise. Nothing is scrubbed here; the caller
+//! owns that.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Value};
+
+use super::{db, mirror};
+use crate::adapter::AdapterError;
+
+/// One session in `opencode.db` and the newest `time_updated` across its
+/// session, message and part rows — the session's change stamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenCodeSession {
+    pub id: String,
+    pub time_updated: i64,
+}
+
+/// Every session in the DB at `path`, ordered by id.
+pub fn list_sessions(path: &Path) -> Result<Vec<OpenCodeSession>, AdapterError> {
+    let conn = open(path)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, MAX( \
+                 COALESCE(s.time_updated, 0), \
+                 COALESCE((SELECT MAX(m.time_updated) FROM message m WHERE m.session_id = s.id), 0), \
+                 COALESCE((SELECT MAX(p.time_updated) FROM part p WHERE p.session_id = s.id), 0)) \
+             FROM session s \
+             ORDER BY s.id ASC",
+        )
+        .map_err(|e| sqlite_error(path, e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(OpenCodeSession {
+                id: row.get(0)?,
+                time_updated: row.get(1)?,
+            })
+        })
+        .map_err(|e| sqlite_error(path, e))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| sqlite_error(path, e))
+}
+
+/// The canonical JSONL rendering of `session_id`, one record per line, each
+/// line newline-terminated.
+pub fn export_session(path: &Path, session_id: &str) -> Result<String, AdapterError> {
+    let conn = open(path)?;
+    let header = conn
+        .query_row(
+            "SELECT COALESCE(directory, ''), time_created FROM session WHERE id = ?1",
+            [session_id],
+            |row| {
+                Ok(json!({
+                    "type": "session",
+                    "id": session_id,
+                    "directory": row.get::<_, String>(0)?,
+                    "time_created": row.get::<_, Option<i64>>(1)?,
+                }))
+            },
+        )
+        .optional()
+        .map_err(|e| sqlite_error(path, e))?
+        .unwrap_or_else(|| json!({ "type": "session", "id": session_id }));
+    let mut out = String::new();
+    push_line(&mut out, &header);
+
+    let mut messages = conn
+        .prepare(
+            "SELECT id, time_created, time_updated, data FROM message \
+             WHERE session_id = ?1 \
+             ORDER BY time_created ASC, id ASC",
+        )
+        .map_err(|e| sqlite_error(path, e))?;
+    let mut parts = conn
+        .prepare(
+            "SELECT id, time_created, time_updated, data FROM part \
+             WHERE message_id = ?1 \
+             ORDER BY time_created ASC, id ASC",
+        )
+        .map_err(|e| sqlite_error(path, e))?;
+    let rows = messages
+        .query_map(params![session_id], Row::read)
+        .map_err(|e| sqlite_error(path, e))?;
+    for message in rows {
+        let message = message.map_err(|e| sqlite_error(path, e))?;
+        push_line(&mut out, &message.render("message", None));
+        let rows = parts
+            .query_map(params![message.id], Row::read)
+            .map_err(|e| sqlite_error(path, e))?;
+        for part in rows {
+            let part = part.map_err(|e| sqlite_error(path, e))?;
+            push_line(&mut out, &part.render("part", Some(&message.id)));
+        }
+    }
+    Ok(out)
+}
+
+fn open(path: &Path) -> Result<Connection, AdapterError> {
+    db::open_read_only(&mirror::stage_mirror_if_foreign(path)?)
+}
+
+fn sqlite_error(path: &Path, source: rusqlite::Error) -> AdapterError {
+    AdapterError::SqliteOpen {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn push_line(out: &mut String, value: &Value) {
+    out.push_str(&value.to_string());
+    out.push('\n');
+}
+
+struct Row {
+    id: String,
+    time_created: Option<i64>,
+    time_updated: Option<i64>,
+    data: Option<String>,
+}
+
+impl Row {
+    fn read(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            time_created: row.get(1)?,
+            time_updated: row.get(2)?,
+            data: row.get(3)?,
+        })
+    }
+
+    fn render(&self, kind: &str, message_id: Option<&str>) -> Value {
+        let data = self.data.as_deref().map(|raw| {
+            serde_json::from_str::<Value>(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+        });
+        let mut record = json!({
+            "type": kind,
+            "id": self.id,
+            "time_created": self.time_created,
+            "time_updated": self.time_updated,
+            "data": data,
+        });
+        if let Some(message_id) = message_id {
+            record["message_id"] = Value::String(message_id.to_string());
+        }
+        record
+    }
+}
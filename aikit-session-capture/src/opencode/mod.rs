@@ -14,6 +14,7 @@
 //!     (macOS), `~/.config/ai.opencode.desktop/` (Linux)
 
 mod db;
+mod export;
 mod mirror;
 
 use std::path::{Path, PathBuf};
@@ -28,6 +29,8 @@ use crate::homes::HomeResolver;
 use crate::models::{ActionKind, ActionStatus, CaptureSource, TokenEvent, ToolEvent, ToolKind};
 use crate::scrub::SecretScrubber;
 
+pub use export::{export_session, list_sessions, OpenCodeSession};
+
 pub struct OpenCodeAdapter {
     scrubber: SecretScrubber,
     homes: Vec<crate::homes::HomeRoot>,
@@ -0,0 +1,336 @@
+//! Full-text search over captured tool events: the query type hosts accept
+//! in [`EventStore::search`](crate::EventStore::search) and the hit type they
+//! return.
+//!
+//! Query syntax is deliberately small so every store can honour it the same
+//! way: bare words must all match, `"quoted phrases"` match as consecutive
+//! words, and a trailing `*` turns a word or phrase into a prefix match
+//! (`deser*`, `"cargo te"*`). Words are runs of letters and digits, compared
+//! case-insensitively — the same tokenization SQLite FTS5's `unicode61`
+//! tokenizer applies, so the in-memory store and the SQLite store agree.
+
+use std::path::PathBuf;
+
+use crate::models::{ActionKind, ToolEvent, ToolKind};
+
+/// Opening highlight marker in [`SearchMatch::snippet`].
+pub const SNIPPET_OPEN: &str = "<mark>";
+/// Closing highlight marker in [`SearchMatch::snippet`].
+pub const SNIPPET_CLOSE: &str = "</mark>";
+/// Marks text elided from either end of a snippet.
+pub const SNIPPET_ELLIPSIS: &str = "…";
+/// Words of context a snippet keeps around its first hit.
+pub const SNIPPET_WORDS: usize = 12;
+
+/// Argument to [`EventStore::search`](crate::EventStore::search).
+///
+/// `text` is matched against each event's `target`, `input`, `output` and
+/// `error_message`. An empty `text` matches every event, newest first, so
+/// the filters alone can page through history. Each `Option` filter narrows
+/// the result set; `None` means "any".
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pub text: String,
+    pub tool: Option<ToolKind>,
+    pub kind: Option<ActionKind>,
+    pub session_id: Option<String>,
+    /// Inclusive lower bound on `started_at_ms`.
+    pub since_ms: Option<i64>,
+    /// Exclusive upper bound on `started_at_ms`.
+    pub until_ms: Option<i64>,
+    pub git_root: Option<PathBuf>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl SearchQuery {
+    /// A query for `text` with no filters and the default page (20 hits).
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            tool: None,
+            kind: None,
+            session_id: None,
+            since_ms: None,
+            until_ms: None,
+            git_root: None,
+            limit: 20,
+            offset: 0,
+        }
+    }
+
+    /// Parse `text` into terms. Terms with no letters or digits (`*`, `--`)
+    /// are dropped, so an all-punctuation query has no terms.
+    pub fn terms(&self) -> Vec<SearchTerm> {
+        let mut terms = Vec::new();
+        let mut chars = self.text.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            let mut raw = String::new();
+            if c == '"' {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    raw.push(c);
+                }
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    raw.push('*');
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' {
+                        break;
+                    }
+                    raw.push(c);
+                    chars.next();
+                }
+            }
+            let prefix = raw.ends_with('*');
+            let words = words(&raw).map(|(_, w)| w.to_lowercase()).collect();
+            let term = SearchTerm { words, prefix };
+            if !term.words.is_empty() {
+                terms.push(term);
+            }
+        }
+        terms
+    }
+
+    /// Whether `ev` passes every filter (ignores `text`).
+    pub fn filters_match(&self, ev: &ToolEvent) -> bool {
+        self.tool.map_or(true, |t| ev.tool == t)
+            && self.kind.map_or(true, |k| ev.kind == k)
+            && self
+                .session_id
+                .as_deref()
+                .map_or(true, |s| ev.session_id == s)
+            && self
+                .since_ms
+                .map_or(true, |t| ev.started_at_ms.is_some_and(|s| s >= t))
+            && self
+                .until_ms
+                .map_or(true, |t| ev.started_at_ms.is_some_and(|s| s < t))
+            && self
+                .git_root
+                .as_deref()
+                .map_or(true, |g| ev.git_root.as_deref() == Some(g))
+    }
+}
+
+/// One parsed query term: consecutive lowercase words, the last of which is
+/// a prefix when `prefix` is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchTerm {
+    pub words: Vec<String>,
+    pub prefix: bool,
+}
+
+/// One hit returned by [`EventStore::search`](crate::EventStore::search).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SearchMatch {
+    pub event: ToolEvent,
+    /// Excerpt of the best-matching field with hits wrapped in
+    /// [`SNIPPET_OPEN`] / [`SNIPPET_CLOSE`]. Without search text, the start of
+    /// the event's output.
+    pub snippet: String,
+    /// Relevance; higher is better. Only comparable within one result set.
+    pub score: f64,
+}
+
+/// A half-open `(start, end)` range: bytes for word spans, word indices for
+/// hits.
+type Span = (usize, usize);
+
+/// Byte spans of the words in `text`, with the word itself.
+fn words(text: &str) -> impl Iterator<Item = (Span, &str)> {
+    let mut start = None;
+    let mut spans = Vec::new();
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans.into_iter().map(move |(s, e)| ((s, e), &text[s..e]))
+}
+
+/// Word positions in `words` where `term` starts.
+fn term_hits(term: &SearchTerm, words: &[String]) -> Vec<usize> {
+    let n = term.words.len();
+    if words.len() < n {
+        return Vec::new();
+    }
+    (0..=words.len() - n)
+        .filter(|&i| {
+            term.words.iter().enumerate().all(|(j, w)| {
+                if term.prefix && j == n - 1 {
+                    words[i + j].starts_with(w.as_str())
+                } else {
+                    words[i + j] == *w
+                }
+            })
+        })
+        .collect()
+}
+
+/// Searchable fields of `ev`, in the order the SQLite index declares them.
+fn searchable_fields(ev: &ToolEvent) -> [Option<&str>; 4] {
+    [
+        ev.target.as_deref(),
+        ev.input.as_deref(),
+        ev.output.as_deref(),
+        ev.error_message.as_deref(),
+    ]
+}
+
+/// Match `ev` against `terms` the way the SQLite store does: every term must
+/// occur in some field. Returns `(score, snippet)` on a match; the score is
+/// the total number of term occurrences.
+pub(crate) fn match_event(terms: &[SearchTerm], ev: &ToolEvent) -> Option<(f64, String)> {
+    let mut total = 0usize;
+    // (field text, word spans, hit word ranges) of the field with most hits.
+    let mut best: Option<(&str, Vec<Span>, Vec<Span>)> = None;
+    let mut matched = vec![false; terms.len()];
+    for text in searchable_fields(ev).into_iter().flatten() {
+        let (spans, lower): (Vec<_>, Vec<_>) = words(text)
+            .map(|(span, w)| (span, w.to_lowercase()))
+            .unzip();
+        let mut ranges = Vec::new();
+        for (t, term) in terms.iter().enumerate() {
+            let hits = term_hits(term, &lower);
+            if !hits.is_empty() {
+                matched[t] = true;
+            }
+            ranges.extend(hits.into_iter().map(|i| (i, i + term.words.len())));
+        }
+        total += ranges.len();
+        if !ranges.is_empty() && best.as_ref().map_or(true, |b| ranges.len() > b.2.len()) {
+            ranges.sort_unstable();
+            best = Some((text, spans, ranges));
+        }
+    }
+    if !matched.iter().all(|m| *m) {
+        return None;
+    }
+    let (text, spans, ranges) = best?;
+    Some((total as f64, snippet(text, &spans, &ranges)))
+}
+
+/// Up to [`SNIPPET_WORDS`] words of `text` around the first hit, with every
+/// hit inside the window highlighted.
+fn snippet(text: &str, spans: &[Span], hits: &[Span]) -> String {
+    let first = hits[0].0;
+    let start = first.saturating_sub(SNIPPET_WORDS / 4);
+    let end = (start + SNIPPET_WORDS).min(spans.len());
+    let mut out = String::new();
+    if start > 0 {
+        out.push_str(SNIPPET_ELLIPSIS);
+    }
+    let mut cursor = spans[start].0;
+    for &(from, to) in hits.iter().filter(|(from, _)| (start..end).contains(from)) {
+        let to = to.min(end);
+        if spans[from].0 < cursor {
+            continue; // overlaps a hit already highlighted
+        }
+        out.push_str(&text[cursor..spans[from].0]);
+        out.push_str(SNIPPET_OPEN);
+        out.push_str(&text[spans[from].0..spans[to - 1].1]);
+        out.push_str(SNIPPET_CLOSE);
+        cursor = spans[to - 1].1;
+    }
+    out.push_str(&text[cursor..spans[end - 1].1]);
+    if end < spans.len() {
+        out.push_str(SNIPPET_ELLIPSIS);
+    }
+    out
+}
+
+/// Snippet for a filter-only query: the first [`SNIPPET_WORDS`] words of the
+/// event's output (or whichever searchable field is set).
+pub fn leading_snippet(ev: &ToolEvent) -> String {
+    let [target, input, output, error] = searchable_fields(ev);
+    let Some(text) = output.or(error).or(input).or(target) else {
+        return String::new();
+    };
+    let spans: Vec<_> = words(text).map(|(span, _)| span).collect();
+    match spans.get(SNIPPET_WORDS) {
+        Some(&(cut, _)) => format!("{}{SNIPPET_ELLIPSIS}", text[..cut].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(words: &[&str], prefix: bool) -> SearchTerm {
+        SearchTerm {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            prefix,
+        }
+    }
+
+    #[test]
+    fn terms_parse_words_phrases_and_prefixes() {
+        let q = SearchQuery::new(r#"Panic "thread main"  deser* "cargo te"* -- *"#);
+        assert_eq!(
+            q.terms(),
+            vec![
+                term(&["panic"], false),
+                term(&["thread", "main"], false),
+                term(&["deser"], true),
+                term(&["cargo", "te"], true),
+            ]
+        );
+        // Punctuation inside a word splits it into a phrase.
+        assert_eq!(
+            SearchQuery::new("src/main.rs").terms(),
+            vec![term(&["src", "main", "rs"], false)]
+        );
+    }
+
+    #[test]
+    fn snippet_highlights_hits_and_elides_the_rest() {
+        let terms = SearchQuery::new("\"test result\" fail*").terms();
+        let mut ev = ToolEvent {
+            source_event_id: "1".into(),
+            source_file: PathBuf::from("/tmp/s.jsonl"),
+            session_id: "s".into(),
+            tool: ToolKind::ClaudeCode,
+            kind: ActionKind::Bash,
+            target: None,
+            input: None,
+            output: Some(
+                "running 3 tests ... one two three four five test result: FAILED. 2 passed; 1 failed; 0 ignored and more words after the hits here"
+                    .into(),
+            ),
+            status: crate::models::ActionStatus::Failure,
+            error_message: None,
+            started_at_ms: None,
+            duration_ms: None,
+            git_root: None,
+            metadata: serde_json::Value::Null,
+        };
+        let (score, snippet) = match_event(&terms, &ev).unwrap();
+        assert_eq!(score, 3.0);
+        assert_eq!(
+            snippet,
+            "…three four five <mark>test result</mark>: <mark>FAILED</mark>. 2 passed; 1 <mark>failed</mark>; 0 ignored…"
+        );
+
+        ev.output = Some("all green".into());
+        assert!(match_event(&terms, &ev).is_none());
+    }
+}
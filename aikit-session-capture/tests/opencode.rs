@@ -154,3 +154,49 @@ async fn no_new_rows_returns_empty_result_with_advanced_watermark() {
     // Watermark is the actual latest in the DB (1300), not the requested offset.
     assert_eq!(res.new_offset, 1300);
 }
+
+#[test]
+fn sessions_list_with_their_newest_row_stamp() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = opencode_fixture::open_fixture_file(tmp.path()).unwrap();
+    let sessions = aikit_session_capture::opencode::list_sessions(&db_path).unwrap();
+    // The session row says 1000; its newest part says 1300.
+    assert_eq!(
+        sessions,
+        vec![aikit_session_capture::opencode::OpenCodeSession {
+            id: "sess-1".into(),
+            time_updated: 1300,
+        }]
+    );
+}
+
+#[test]
+fn export_renders_messages_then_their_parts_in_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = opencode_fixture::open_fixture_file(tmp.path()).unwrap();
+    let rendered = aikit_session_capture::opencode::export_session(&db_path, "sess-1").unwrap();
+    assert!(rendered.ends_with('\n'));
+    let lines: Vec<serde_json::Value> = rendered
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let ids: Vec<&str> = lines.iter().map(|l| l["id"].as_str().unwrap()).collect();
+    assert_eq!(
+        ids,
+        [
+            "sess-1",
+            "msg-user-1",
+            "part-text-1",
+            "msg-asst-1",
+            "part-tool-1"
+        ]
+    );
+    assert_eq!(lines[0]["directory"], "/tmp/opencode-fixture");
+    assert_eq!(lines[2]["message_id"], "msg-user-1");
+    assert_eq!(lines[2]["data"]["text"], "Run the tests");
+    // Deterministic: a second export is byte-identical.
+    assert_eq!(
+        aikit_session_capture::opencode::export_session(&db_path, "sess-1").unwrap(),
+        rendered
+    );
+}
@@ -0,0 +1,102 @@
+//! Dry-run scrub audit: what the scrubber would redact in each detected
+//! session, counted by label. Reads the same sources as
+//! [`SyncEngine::sync_detected`](crate::SyncEngine::sync_detected) but needs
+//! no sink or owner and uploads nothing. Redacted text is never reported.
+
+use std::path::PathBuf;
+
+use aikit_session_capture::{Registry, ScrubReport, SecretScrubber, ToolKind};
+use tracing::error;
+
+use crate::engine::{session_files, sync_adapters};
+use crate::SyncError;
+
+/// One session source with at least one redaction.
+#[derive(Debug, Clone, serde::Serialize, PartialEq, Eq)]
+pub struct ScrubAuditEntry {
+    pub tool: String,
+    /// The transcript, or an OpenCode session's export path.
+    pub file: PathBuf,
+    #[serde(flatten)]
+    pub report: ScrubReport,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, PartialEq, Eq)]
+pub struct ScrubAudit {
+    /// Sources read, redacted or not.
+    pub scanned: u64,
+    /// Sources that could not be read.
+    pub failed: u64,
+    /// Counts by label across every source.
+    pub totals: ScrubReport,
+    pub files: Vec<ScrubAuditEntry>,
+}
+
+impl ScrubAudit {
+    fn record(&mut self, tool: ToolKind, file: PathBuf, content: &str, scrubber: &SecretScrubber) {
+        self.scanned += 1;
+        let (_, report) = scrubber.scrub_with_report(content);
+        if report.is_empty() {
+            return;
+        }
+        self.totals.merge(&report);
+        self.files.push(ScrubAuditEntry {
+            tool: tool.as_str().to_string(),
+            file,
+            report,
+        });
+    }
+
+    fn fail(&mut self, file: &std::path::Path, error: &SyncError) {
+        error!(
+            file = %file.display(),
+            kind = error.kind(),
+            err = %error,
+            "sync.audit.fail"
+        );
+        self.failed += 1;
+    }
+}
+
+/// Scrub every session the registry detects (narrowed to `allow`) with
+/// `scrubber` and report the redactions per source.
+pub async fn scrub_audit(
+    registry: &Registry,
+    allow: Option<&[ToolKind]>,
+    scrubber: &SecretScrubber,
+) -> ScrubAudit {
+    let mut audit = ScrubAudit::default();
+    for adapter in sync_adapters(registry, allow) {
+        #[cfg(feature = "opencode")]
+        if adapter.kind() == ToolKind::OpenCode {
+            use crate::engine::{export_opencode_session, list_opencode_sessions, opencode_dbs};
+            for db in opencode_dbs(adapter) {
+                let sessions = match list_opencode_sessions(&db).await {
+                    Ok(sessions) => sessions,
+                    Err(error) => {
+                        audit.fail(&db, &error);
+                        continue;
+                    }
+                };
+                for session in sessions {
+                    let file = crate::opencode_export_path(&db, &session.id);
+                    match export_opencode_session(&db, &session.id).await {
+                        Ok(rendered) => audit.record(adapter.kind(), file, &rendered, scrubber),
+                        Err(error) => audit.fail(&file, &error),
+                    }
+                }
+            }
+            continue;
+        }
+        for file in session_files(adapter) {
+            match tokio::fs::read(&file).await {
+                Ok(bytes) => {
+                    let content = String::from_utf8_lossy(&bytes).into_owned();
+                    audit.record(adapter.kind(), file, &content, scrubber);
+                }
+                Err(error) => audit.fail(&file, &error.into()),
+            }
+        }
+    }
+    audit
+}
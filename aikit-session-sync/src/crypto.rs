@@ -0,0 +1,379 @@
+//! Optional client-side encryption for synced transcripts.
+//!
+//! [`EncryptedSink`] wraps any [`SyncSink`] and seals content with AES-256-GCM
+//! before it leaves the machine, so the bucket only ever holds ciphertext.
+//! Keys are 32 random bytes held locally in a key file; each one derives an
+//! encryption key, a content-hash key and a public key id.
+//!
+//! Content hashes (and so object keys) are an HMAC-SHA256 of the scrubbed
+//! plaintext under the active key's derived hash key, which identifies
+//! identical content without revealing it. Rotating the active key therefore
+//! changes the hash of the same content: delta sync is offset-based and each
+//! object verifies under the key its envelope names, so nothing breaks, but
+//! content synced again from offset 0 lands under new object keys instead of
+//! matching older ones. Envelopes and manifests stay readable, so listing and
+//! pulling still work without the key; only transcript bytes are encrypted.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, KeyInit, Nonce};
+use async_trait::async_trait;
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::manifest::SessionManifest;
+use crate::{SyncError, SyncObject, SyncSink};
+
+/// [`EnvelopeEncryption::cipher`] for AES-256-GCM with a 96-bit random nonce
+/// prepended to the ciphertext and the object key as associated data.
+pub const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+
+const NONCE_LEN: usize = 12;
+
+/// How a stored object was encrypted. Absent on plaintext objects.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvelopeEncryption {
+    pub cipher: String,
+    pub key_id: String,
+}
+
+/// One locally held key and the keys derived from it.
+pub struct SyncKey {
+    id: String,
+    cipher: Aes256Gcm,
+    hash_key: [u8; 32],
+}
+
+impl SyncKey {
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        let encrypt_key = derive(secret, b"aikit-session-sync/v1/encrypt");
+        Self {
+            id: hex::encode(&derive(secret, b"aikit-session-sync/v1/key-id")[..8]),
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&encrypt_key)),
+            hash_key: derive(secret, b"aikit-session-sync/v1/content-hash"),
+        }
+    }
+
+    /// Parse 64 hex characters (e.g. `openssl rand -hex 32`).
+    pub fn from_hex(hex_key: &str) -> Result<Self, SyncError> {
+        let bytes = hex::decode(hex_key.trim())
+            .map_err(|e| SyncError::Auth(format!("sync key is not hex: {e}")))?;
+        let secret: [u8; 32] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            SyncError::Auth(format!("sync key must be 32 bytes, got {}", bytes.len()))
+        })?;
+        Ok(Self::from_bytes(&secret))
+    }
+
+    /// Public identifier recorded in envelopes; does not reveal the key.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Keyed content hash of `plaintext`, hex-encoded.
+    pub fn content_hash(&self, plaintext: &[u8]) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.hash_key)
+            .expect("HMAC accepts any key length");
+        mac.update(plaintext);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn seal(&self, object_key: &str, plaintext: &[u8]) -> Result<Bytes, SyncError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: object_key.as_bytes(),
+                },
+            )
+            .map_err(|_| SyncError::Integrity(format!("{object_key}: encryption failed")))?;
+        let mut out = Vec::with_capacity(NONCE_LEN + sealed.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(Bytes::from(out))
+    }
+
+    fn open(&self, object_key: &str, stored: &[u8]) -> Result<Bytes, SyncError> {
+        let failed = || {
+            SyncError::Integrity(format!(
+                "{object_key}: decryption failed (tampered, or sealed under another key)"
+            ))
+        };
+        if stored.len() < NONCE_LEN {
+            return Err(failed());
+        }
+        let (nonce, sealed) = stored.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: sealed,
+                    aad: object_key.as_bytes(),
+                },
+            )
+            .map(Bytes::from)
+            .map_err(|_| failed())
+    }
+}
+
+impl std::fmt::Debug for SyncKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyncKey").field("id", &self.id).finish()
+    }
+}
+
+fn derive(secret: &[u8; 32], label: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(label);
+    mac.finalize().into_bytes().into()
+}
+
+/// The key new objects are sealed with, plus older keys kept so objects
+/// sealed before a rotation still decrypt.
+#[derive(Debug)]
+pub struct Keyring {
+    keys: Vec<SyncKey>,
+}
+
+impl Keyring {
+    pub fn new(active: SyncKey) -> Self {
+        Self { keys: vec![active] }
+    }
+
+    /// Add a decrypt-only key.
+    pub fn with_key(mut self, key: SyncKey) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// One hex key per line; the first is active, the rest decrypt-only.
+    /// Blank lines and `#` comments are ignored.
+    pub fn parse(text: &str) -> Result<Self, SyncError> {
+        let mut keys = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(SyncKey::from_hex);
+        let active = keys
+            .next()
+            .ok_or_else(|| SyncError::Auth("sync key file has no keys".to_string()))??;
+        keys.try_fold(Self::new(active), |ring, key| Ok(ring.with_key(key?)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SyncError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            SyncError::Auth(format!("cannot read sync key file {}: {e}", path.display()))
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn active(&self) -> &SyncKey {
+        &self.keys[0]
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&SyncKey> {
+        self.keys.iter().find(|key| key.id == key_id)
+    }
+
+    fn key_for(&self, object: &SyncObject) -> Result<&SyncKey, SyncError> {
+        let Some(encryption) = &object.envelope.encryption else {
+            return Ok(self.active());
+        };
+        if encryption.cipher != CIPHER_AES_256_GCM {
+            return Err(SyncError::Integrity(format!(
+                "{}: unsupported cipher {}",
+                object.key, encryption.cipher
+            )));
+        }
+        self.get(&encryption.key_id).ok_or_else(|| {
+            SyncError::Auth(format!(
+                "{}: sealed with key {}, which is not in the key file",
+                object.key, encryption.key_id
+            ))
+        })
+    }
+}
+
+/// A [`SyncSink`] that encrypts content on `put` and decrypts it on `get`.
+/// Plaintext objects written before encryption was enabled still read back.
+pub struct EncryptedSink {
+    inner: Arc<dyn SyncSink>,
+    keyring: Keyring,
+}
+
+impl EncryptedSink {
+    pub fn new(inner: Arc<dyn SyncSink>, keyring: Keyring) -> Self {
+        Self { inner, keyring }
+    }
+}
+
+#[async_trait]
+impl SyncSink for EncryptedSink {
+    async fn put(&self, mut object: SyncObject) -> Result<(), SyncError> {
+        let key = self.keyring.active();
+        object.content = key.seal(&object.key, &object.content)?;
+        object.envelope.encryption = Some(EnvelopeEncryption {
+            cipher: CIPHER_AES_256_GCM.to_string(),
+            key_id: key.id.clone(),
+        });
+        self.inner.put(object).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError> {
+        self.inner.list(prefix).await
+    }
+
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError> {
+        let mut object = self.inner.get(key).await?;
+        if object.envelope.encryption.is_some() {
+            object.content = self.keyring.key_for(&object)?.open(key, &object.content)?;
+        }
+        Ok(object)
+    }
+
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError> {
+        self.inner.put_manifest(key, manifest).await
+    }
+
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError> {
+        self.inner.get_manifest(key).await
+    }
+
+    fn content_hash(&self, content: &[u8]) -> String {
+        self.keyring.active().content_hash(content)
+    }
+
+    fn verify(&self, object: &SyncObject) -> Result<(), SyncError> {
+        if object.envelope.encryption.is_none() {
+            return crate::verify_object(object);
+        }
+        let actual = self.keyring.key_for(object)?.content_hash(&object.content);
+        crate::pull::check_hash_and_len(object, &actual)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Envelope, InMemorySink};
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const OLD_KEY: &str = "ffeeddccbbaa99887766554433221100ffeeddccbbaa99887766554433221100";
+
+    fn object(sink: &dyn SyncSink, content: &'static [u8]) -> SyncObject {
+        let content_hash = sink.content_hash(content);
+        SyncObject {
+            key: format!("sessions/alice/codex/s1/{content_hash}.jsonl"),
+            content: Bytes::from_static(content),
+            envelope: Envelope {
+                schema_version: 1,
+                owner: "alice".into(),
+                tool: "codex".into(),
+                session_id: "s1".into(),
+                source_file: "/tmp/s1.jsonl".into(),
+                host: "h".into(),
+                captured_at_ms: 1,
+                content_hash,
+                byte_len: content.len() as u64,
+                scrubber_version: "1".into(),
+                sync_tool_version: "0.1.0".into(),
+                encryption: None,
+            },
+        }
+    }
+
+    fn encrypted(inner: &Arc<InMemorySink>, keys: &str) -> EncryptedSink {
+        EncryptedSink::new(
+            inner.clone() as Arc<dyn SyncSink>,
+            Keyring::parse(keys).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn round_trip_stores_only_ciphertext() {
+        let inner = Arc::new(InMemorySink::new());
+        let sink = encrypted(&inner, KEY);
+        let object = object(&sink, b"{\"text\":\"customer data\"}\n");
+        let key = object.key.clone();
+        sink.put(object).await.unwrap();
+
+        let stored = inner.get_content(&key).unwrap();
+        assert!(!stored.windows(b"customer".len()).any(|w| w == b"customer"));
+        let envelope = inner.get_envelope(&crate::sink::meta_key(&key)).unwrap();
+        let encryption = envelope.encryption.as_ref().unwrap();
+        assert_eq!(encryption.cipher, CIPHER_AES_256_GCM);
+        assert_eq!(encryption.key_id, sink.keyring.active().id());
+
+        let pulled = sink.get(&key).await.unwrap();
+        assert_eq!(pulled.content.as_ref(), b"{\"text\":\"customer data\"}\n");
+        sink.verify(&pulled).unwrap();
+    }
+
+    #[test]
+    fn content_hash_is_stable_keyed_and_not_plain_sha256() {
+        let a = SyncKey::from_hex(KEY).unwrap();
+        let b = SyncKey::from_hex(KEY).unwrap();
+        let other = SyncKey::from_hex(OLD_KEY).unwrap();
+        assert_eq!(a.content_hash(b"line\n"), b.content_hash(b"line\n"));
+        assert_ne!(a.content_hash(b"line\n"), other.content_hash(b"line\n"));
+        assert_ne!(
+            a.content_hash(b"line\n"),
+            InMemorySink::new().content_hash(b"line\n")
+        );
+        assert_eq!(a.id(), b.id());
+        assert_eq!(a.id().len(), 16);
+    }
+
+    #[tokio::test]
+    async fn rotated_key_still_decrypts_older_objects() {
+        let inner = Arc::new(InMemorySink::new());
+        let old = encrypted(&inner, OLD_KEY);
+        let object = object(&old, b"{}\n");
+        let key = object.key.clone();
+        old.put(object).await.unwrap();
+
+        let rotated = encrypted(&inner, &format!("# current\n{KEY}\n\n{OLD_KEY}\n"));
+        let pulled = rotated.get(&key).await.unwrap();
+        assert_eq!(pulled.content.as_ref(), b"{}\n");
+        rotated.verify(&pulled).unwrap();
+
+        let unknown = encrypted(&inner, KEY);
+        assert!(matches!(unknown.get(&key).await, Err(SyncError::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn tampered_ciphertext_fails_to_open() {
+        let inner = Arc::new(InMemorySink::new());
+        let sink = encrypted(&inner, KEY);
+        let object = object(&sink, b"{}\n");
+        let key = object.key.clone();
+        let mut sealed = object.clone();
+        sealed.content = sink.keyring.active().seal(&key, &object.content).unwrap();
+        let mut bytes = sealed.content.to_vec();
+        *bytes.last_mut().unwrap() ^= 1;
+        sealed.content = Bytes::from(bytes);
+        sealed.envelope.encryption = Some(EnvelopeEncryption {
+            cipher: CIPHER_AES_256_GCM.into(),
+            key_id: sink.keyring.active().id().into(),
+        });
+        inner.put(sealed).await.unwrap();
+        assert!(matches!(sink.get(&key).await, Err(SyncError::Integrity(_))));
+    }
+
+    #[test]
+    fn key_file_errors_are_auth_errors() {
+        assert!(matches!(
+            Keyring::parse("# none\n"),
+            Err(SyncError::Auth(_))
+        ));
+        assert!(matches!(Keyring::parse("abcd\n"), Err(SyncError::Auth(_))));
+        assert!(matches!(Keyring::parse("zz\n"), Err(SyncError::Auth(_))));
+    }
+}
@@ -0,0 +1,185 @@
+//! A [`SyncSink`] over a local directory, e.g. an NFS or SMB mount for teams
+//! without an object store. Objects keep the `object_key` layout as relative
+//! paths under the root, with the same `.meta.json` sidecars and
+//! `manifest.json` files, so the tree can later be copied into a bucket
+//! unchanged.
+
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+
+use crate::sink::{key_has_prefix, meta_key};
+use crate::{Envelope, SessionManifest, SyncError, SyncObject, SyncSink};
+
+/// Suffix of the temp file each write goes through before its rename.
+const TMP_SUFFIX: &str = ".aikit-tmp";
+
+pub struct FsSink {
+    root: PathBuf,
+}
+
+impl FsSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where `key` lives under the root. Keys are built from percent-encoded
+    /// segments, so anything that could step outside the root is rejected.
+    fn path_for(&self, key: &str) -> Result<PathBuf, SyncError> {
+        let mut path = self.root.clone();
+        for segment in key.split('/') {
+            if segment.is_empty()
+                || segment == "."
+                || segment == ".."
+                || segment.contains('\\')
+                || segment.contains(':')
+            {
+                return Err(SyncError::Backend(format!("invalid object key {key:?}")));
+            }
+            path.push(segment);
+        }
+        Ok(path)
+    }
+
+    async fn write_atomic(&self, key: &str, body: &[u8]) -> Result<(), SyncError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(TMP_SUFFIX);
+        let tmp = PathBuf::from(tmp);
+        tokio::fs::write(&tmp, body).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn read(&self, key: &str) -> Result<Bytes, SyncError> {
+        match tokio::fs::read(self.path_for(key)?).await {
+            Ok(body) => Ok(Bytes::from(body)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(SyncError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SyncSink for FsSink {
+    async fn put(&self, object: SyncObject) -> Result<(), SyncError> {
+        self.write_atomic(&object.key, &object.content).await?;
+        let meta = serde_json::to_vec(&object.envelope)
+            .map_err(|e| SyncError::Backend(format!("serialize envelope: {e}")))?;
+        self.write_atomic(&meta_key(&object.key), &meta).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError> {
+        let prefix = prefix.trim_matches('/');
+        let start = if prefix.is_empty() {
+            self.root.clone()
+        } else {
+            self.path_for(prefix)?
+        };
+        let root = self.root.clone();
+        let prefix = prefix.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut keys = Vec::new();
+            for entry in walkdir::WalkDir::new(&start) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e)
+                        if e.io_error().map(|e| e.kind()) == Some(std::io::ErrorKind::NotFound) =>
+                    {
+                        continue
+                    }
+                    Err(e) => return Err(SyncError::Io(e.into())),
+                };
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let Ok(relative) = entry.path().strip_prefix(&root) else {
+                    continue;
+                };
+                let key = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if key.ends_with(".jsonl") && key_has_prefix(&key, &prefix) {
+                    keys.push(key);
+                }
+            }
+            keys.sort();
+            Ok(keys)
+        })
+        .await
+        .map_err(|e| SyncError::Backend(format!("list task: {e}")))?
+    }
+
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError> {
+        let content = self.read(key).await?;
+        let meta = self.read(&meta_key(key)).await?;
+        let envelope: Envelope = serde_json::from_slice(&meta)
+            .map_err(|e| SyncError::Backend(format!("parse envelope for {key}: {e}")))?;
+        Ok(SyncObject {
+            key: key.to_string(),
+            content,
+            envelope,
+        })
+    }
+
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError> {
+        let body = serde_json::to_vec(manifest)
+            .map_err(|e| SyncError::Backend(format!("serialize manifest: {e}")))?;
+        self.write_atomic(key, &body).await
+    }
+
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError> {
+        let body = match self.read(key).await {
+            Ok(body) => body,
+            Err(SyncError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_slice(&body)
+            .map(Some)
+            .map_err(|e| SyncError::Backend(format!("parse manifest {key}: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_cannot_escape_the_root() {
+        let sink = FsSink::new("/srv/sessions");
+        assert_eq!(
+            sink.path_for("sessions/alice/codex/s%2F1/abc.jsonl")
+                .unwrap(),
+            Path::new("/srv/sessions/sessions/alice/codex/s%2F1/abc.jsonl")
+        );
+        for key in ["../etc/passwd", "a//b", "a/./b", "/abs", "a\\..\\b", "c:/x"] {
+            assert!(
+                matches!(sink.path_for(key), Err(SyncError::Backend(_))),
+                "{key}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_prefix_lists_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sink = FsSink::new(tmp.path().join("not-yet"));
+        assert!(sink.list("sessions/alice").await.unwrap().is_empty());
+        assert!(sink
+            .get_manifest("sessions/alice/codex/s/manifest.json")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -24,6 +24,19 @@ pub fn percent_decode_segment(segment: &str) -> Result<String, std::str::Utf8Err
         .map(|s| s.into_owned())
 }
 
+/// The "folder" holding every object of one session:
+/// `{prefix}/{owner}/{tool}/{session}`.
+pub fn session_folder(prefix: &str, owner: &str, tool: ToolKind, session_id: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    let session = percent_encode_segment(session_id);
+    if prefix.is_empty() {
+        format!("{}/{}/{}", owner, tool.as_str(), session)
+    } else {
+        format!("{}/{}/{}/{}", prefix, owner, tool.as_str(), session)
+    }
+}
+
+/// Key of a full snapshot: `{folder}/{content_hash}.jsonl`.
 pub fn object_key(
     prefix: &str,
     owner: &str,
@@ -31,26 +44,51 @@ pub fn object_key(
     session_id: &str,
     content_hash: &str,
 ) -> String {
-    let prefix = prefix.trim_matches('/');
-    let session = percent_encode_segment(session_id);
-    if prefix.is_empty() {
-        format!(
-            "{}/{}/{}/{}.jsonl",
-            owner,
-            tool.as_str(),
-            session,
-            content_hash
-        )
-    } else {
-        format!(
-            "{}/{}/{}/{}/{}.jsonl",
-            prefix,
-            owner,
-            tool.as_str(),
-            session,
-            content_hash
-        )
-    }
+    format!(
+        "{}/{}.jsonl",
+        session_folder(prefix, owner, tool, session_id),
+        content_hash
+    )
+}
+
+/// Key of an appended chunk starting at raw-file byte `start_offset`:
+/// `{folder}/chunk-{start_offset:020}-{content_hash}.jsonl`. The zero-padded
+/// offset makes a plain key sort match file order.
+pub fn chunk_key(
+    prefix: &str,
+    owner: &str,
+    tool: ToolKind,
+    session_id: &str,
+    start_offset: u64,
+    content_hash: &str,
+) -> String {
+    format!(
+        "{}/{CHUNK_KEY_PREFIX}{start_offset:020}-{content_hash}.jsonl",
+        session_folder(prefix, owner, tool, session_id)
+    )
+}
+
+/// File-name prefix that marks a chunk object (as opposed to a snapshot).
+pub const CHUNK_KEY_PREFIX: &str = "chunk-";
+
+/// File name of the per-session manifest inside its folder.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Key of the manifest for the session folder `folder`.
+pub fn manifest_key(folder: &str) -> String {
+    format!("{}/{MANIFEST_FILE}", folder.trim_end_matches('/'))
+}
+
+/// The session folder a content key lives in (everything before the last `/`).
+pub fn folder_of_key(key: &str) -> &str {
+    key.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// Whether `key` names an appended chunk rather than a full snapshot.
+pub fn is_chunk_key(key: &str) -> bool {
+    key.rsplit('/')
+        .next()
+        .is_some_and(|name| name.starts_with(CHUNK_KEY_PREFIX))
 }
 
 pub fn decode_session_id_from_key(key: &str) -> Result<String, std::str::Utf8Error> {
@@ -79,4 +117,24 @@ mod tests {
             assert_eq!(key.split('/').count(), 5);
         }
     }
+
+    #[test]
+    fn chunk_keys_share_the_session_folder_and_sort_by_offset() {
+        let a = chunk_key("sessions/", "o", ToolKind::Codex, "has/slash", 9, "aa");
+        let b = chunk_key("sessions/", "o", ToolKind::Codex, "has/slash", 10, "00");
+        assert!(a < b);
+        assert!(is_chunk_key(&a));
+        assert_eq!(decode_session_id_from_key(&a).unwrap(), "has/slash");
+        assert_eq!(
+            manifest_key(folder_of_key(&a)),
+            "sessions/o/codex/has%2Fslash/manifest.json"
+        );
+        assert!(!is_chunk_key(&object_key(
+            "sessions/",
+            "o",
+            ToolKind::Codex,
+            "s",
+            "aa"
+        )));
+    }
 }
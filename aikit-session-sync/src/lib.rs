@@ -1,18 +1,37 @@
-//! `aikit-session-sync`: raw scrubbed transcript sync for JSONL session files.
+//! `aikit-session-sync`: raw scrubbed transcript sync for JSONL session files
+//! (and OpenCode sessions exported from SQLite), and pulling them back down.
 
+pub mod audit;
+pub mod crypto;
 pub mod engine;
+pub mod fs;
 pub mod key;
+pub mod manifest;
+pub mod pull;
 pub mod s3;
 pub mod sink;
 pub mod state;
+pub mod webdav;
 
+pub use audit::{scrub_audit, ScrubAudit, ScrubAuditEntry};
+pub use crypto::{EncryptedSink, EnvelopeEncryption, Keyring, SyncKey, CIPHER_AES_256_GCM};
+#[cfg(feature = "opencode")]
+pub use engine::opencode_export_path;
 pub use engine::{
-    credential_owner_from_env, resolve_owner, OutputFormat, SyncConfig, SyncEngine, SyncOutcome,
-    SyncRunSummary, WatchRetryPolicy,
+    credential_owner_from_env, resolve_owner, CompactSummary, OutputFormat, SyncBackend,
+    SyncConfig, SyncEngine, SyncOutcome, SyncRunSummary, WatchRetryPolicy,
 };
+pub use fs::FsSink;
 pub use key::{
-    decode_session_id_from_key, object_key, percent_decode_segment, percent_encode_segment,
+    chunk_key, decode_session_id_from_key, folder_of_key, is_chunk_key, manifest_key, object_key,
+    percent_decode_segment, percent_encode_segment, session_folder,
+};
+pub use manifest::{assemble, ManifestPart, SessionManifest, MANIFEST_SCHEMA_VERSION};
+pub use pull::{
+    fetch_session, ingest_transcript, pull_prefix, pull_sessions, restore_path, session_folders,
+    verify_object, write_transcript, PullConfig, PullDestination, PullRunSummary, RestoreOutcome,
 };
 pub use s3::{S3Sink, S3SinkConfig};
 pub use sink::{Envelope, InMemorySink, SyncError, SyncObject, SyncSink};
 pub use state::{FileFingerprint, JsonSyncStateStore, SyncStateEntry, SyncStateStore};
+pub use webdav::{WebDavSink, WebDavSinkConfig};
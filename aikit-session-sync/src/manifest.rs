@@ -0,0 +1,93 @@
+//! Per-session manifest for append-only delta sync.
+//!
+//! A growing transcript is stored as an optional full snapshot (`base`) plus
+//! the chunks of complete lines appended since, in file order. Concatenating
+//! `base` and `chunks` reproduces the scrubbed transcript up to
+//! [`SessionManifest::end_offset`]. Compaction folds the chunks into a new
+//! base; the old objects are retained like every other version.
+
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{Envelope, SyncError, SyncSink};
+
+/// Current [`SessionManifest::schema_version`].
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionManifest {
+    pub schema_version: u32,
+    pub owner: String,
+    pub tool: String,
+    pub session_id: String,
+    pub source_file: String,
+    pub host: String,
+    /// Bumped when the source file was rewritten (shrank, or the bytes before
+    /// the synced offset changed) and syncing restarted from offset 0.
+    pub generation: u32,
+    pub base: Option<ManifestPart>,
+    pub chunks: Vec<ManifestPart>,
+    pub updated_at_ms: i64,
+}
+
+/// One stored object covering raw-file bytes `start_offset..end_offset`.
+/// `content_hash` and `byte_len` describe the stored (scrubbed) bytes, which
+/// differ in length from the raw range whenever something was redacted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestPart {
+    pub key: String,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub content_hash: String,
+    pub byte_len: u64,
+}
+
+impl SessionManifest {
+    /// Base first, then chunks: the order to concatenate them in.
+    pub fn parts(&self) -> impl Iterator<Item = &ManifestPart> {
+        self.base.iter().chain(self.chunks.iter())
+    }
+
+    /// Raw-file offset just past the last synced line.
+    pub fn end_offset(&self) -> u64 {
+        self.parts().last().map_or(0, |part| part.end_offset)
+    }
+
+    /// Total stored bytes across all parts.
+    pub fn byte_len(&self) -> u64 {
+        self.parts().map(|part| part.byte_len).sum()
+    }
+}
+
+/// Fetch every part of `manifest` and concatenate them, checking each against
+/// its envelope and the hash and length the manifest recorded for it. Also
+/// returns the last part's envelope, whose scrubber and tool versions describe
+/// the newest bytes.
+pub async fn assemble(
+    sink: &dyn SyncSink,
+    manifest: &SessionManifest,
+) -> Result<(Bytes, Envelope), SyncError> {
+    let mut out = BytesMut::with_capacity(manifest.byte_len() as usize);
+    let mut last = None;
+    for part in manifest.parts() {
+        let object = sink.get(&part.key).await?;
+        sink.verify(&object)?;
+        if object.envelope.content_hash != part.content_hash
+            || object.envelope.byte_len != part.byte_len
+        {
+            return Err(SyncError::Integrity(format!(
+                "{}: envelope {} ({} bytes) does not match manifest {} ({} bytes)",
+                part.key,
+                object.envelope.content_hash,
+                object.envelope.byte_len,
+                part.content_hash,
+                part.byte_len
+            )));
+        }
+        out.extend_from_slice(&object.content);
+        last = Some(object.envelope);
+    }
+    let envelope =
+        last.ok_or_else(|| SyncError::NotFound(format!("parts of {}", manifest.session_id)))?;
+    Ok((out.freeze(), envelope))
+}
@@ -0,0 +1,455 @@
+//! `aikit session pull`: the read side of sync. Lists an owner's objects in a
+//! [`SyncSink`], reassembles each session from its manifest (or takes the
+//! newest snapshot of a pre-manifest session), verifies every part, writes the
+//! transcript to disk (optionally back into the agent's own session directory
+//! so `--resume` finds it) and optionally ingests it into a capture
+//! [`EventStore`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use aikit_session_capture::{Adapter, EventBatch, EventStore, Registry, ToolKind};
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+
+use crate::key::{
+    decode_session_id_from_key, folder_of_key, is_chunk_key, manifest_key, percent_encode_segment,
+};
+use crate::manifest::assemble;
+use crate::{Envelope, SyncError, SyncObject, SyncSink};
+
+#[derive(Debug, Clone)]
+pub struct PullConfig {
+    pub key_prefix: String,
+    pub owner: String,
+    /// Restrict to one tool; `None` pulls every tool under the owner.
+    pub tool: Option<ToolKind>,
+    /// Restrict to one session id.
+    pub session_id: Option<String>,
+    pub destination: PullDestination,
+    /// Replace a local transcript that differs from the pulled one. Off by
+    /// default so a pull never clobbers a session that kept growing locally.
+    pub overwrite: bool,
+    /// Parse each written transcript and upsert it into the event store.
+    pub ingest: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullDestination {
+    /// Into the adapter's session root (`~/.claude/projects`,
+    /// `~/.codex/sessions`), at the path the transcript had on its origin host.
+    Restore,
+    /// Under `<dir>/<tool>/`.
+    Dir(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreOutcome {
+    Written,
+    /// The destination already held identical content.
+    Unchanged,
+    /// The destination held different content and `overwrite` was off.
+    SkippedExisting,
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, PartialEq, Eq)]
+pub struct PullRunSummary {
+    pub pulled: u64,
+    pub written: u64,
+    pub unchanged: u64,
+    pub skipped_existing: u64,
+    pub failed: u64,
+    pub events_ingested: u64,
+    pub bytes_downloaded: u64,
+}
+
+/// The listing prefix for `owner`, narrowed to `tool` and then `session_id`
+/// where the key layout allows (a session without a tool can't be narrowed).
+pub fn pull_prefix(
+    key_prefix: &str,
+    owner: &str,
+    tool: Option<ToolKind>,
+    session_id: Option<&str>,
+) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let key_prefix = key_prefix.trim_matches('/');
+    if !key_prefix.is_empty() {
+        parts.push(key_prefix.to_string());
+    }
+    parts.push(owner.to_string());
+    if let Some(tool) = tool {
+        parts.push(tool.as_str().to_string());
+        if let Some(session_id) = session_id {
+            parts.push(percent_encode_segment(session_id));
+        }
+    }
+    parts.join("/")
+}
+
+/// Session folders matching `config`, each with its content keys, ordered by
+/// folder (tool, then session id).
+pub async fn session_folders(
+    sink: &dyn SyncSink,
+    config: &PullConfig,
+) -> Result<Vec<(String, Vec<String>)>, SyncError> {
+    let prefix = pull_prefix(
+        &config.key_prefix,
+        &config.owner,
+        config.tool,
+        config.session_id.as_deref(),
+    );
+    let mut folders: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in sink.list(&prefix).await? {
+        if let Some(session_id) = &config.session_id {
+            if decode_session_id_from_key(&key).ok().as_ref() != Some(session_id) {
+                continue;
+            }
+        }
+        folders
+            .entry(folder_of_key(&key).to_string())
+            .or_default()
+            .push(key);
+    }
+    Ok(folders.into_iter().collect())
+}
+
+/// The current transcript of the session in `folder`, verified. Sessions with
+/// a manifest are reassembled from its base and chunks; older snapshot-only
+/// sessions yield their newest snapshot (by `captured_at_ms`). An assembled
+/// transcript comes back under the manifest's key with an envelope describing
+/// the whole: it is plaintext, so it carries no encryption and a plain SHA-256
+/// that [`verify_object`] accepts.
+pub async fn fetch_session(
+    sink: &dyn SyncSink,
+    folder: &str,
+    keys: &[String],
+) -> Result<SyncObject, SyncError> {
+    let manifest_key = manifest_key(folder);
+    if let Some(manifest) = sink.get_manifest(&manifest_key).await? {
+        let (content, last) = assemble(sink, &manifest).await?;
+        return Ok(SyncObject {
+            key: manifest_key,
+            envelope: Envelope {
+                source_file: manifest.source_file,
+                host: manifest.host,
+                captured_at_ms: manifest.updated_at_ms,
+                content_hash: hex::encode(Sha256::digest(&content)),
+                byte_len: content.len() as u64,
+                encryption: None,
+                ..last
+            },
+            content,
+        });
+    }
+    let mut latest: Option<SyncObject> = None;
+    for key in keys.iter().filter(|key| !is_chunk_key(key)) {
+        let object = sink.get(key).await?;
+        if latest.as_ref().map_or(true, |kept| {
+            object.envelope.captured_at_ms > kept.envelope.captured_at_ms
+        }) {
+            latest = Some(object);
+        }
+    }
+    let object = latest.ok_or_else(|| SyncError::NotFound(folder.to_string()))?;
+    sink.verify(&object)?;
+    Ok(object)
+}
+
+/// Check a plaintext `object.content` against the SHA-256 and length its
+/// envelope recorded. Encrypted objects need the sink that decrypted them:
+/// use [`SyncSink::verify`].
+pub fn verify_object(object: &SyncObject) -> Result<(), SyncError> {
+    if let Some(encryption) = &object.envelope.encryption {
+        return Err(SyncError::Auth(format!(
+            "{}: encrypted ({}, key {}); a sync key file is needed to read it",
+            object.key, encryption.cipher, encryption.key_id
+        )));
+    }
+    check_hash_and_len(object, &hex::encode(Sha256::digest(&object.content)))
+}
+
+pub(crate) fn check_hash_and_len(object: &SyncObject, actual: &str) -> Result<(), SyncError> {
+    if actual != object.envelope.content_hash {
+        return Err(SyncError::Integrity(format!(
+            "{}: content hash {actual} does not match envelope {}",
+            object.key, object.envelope.content_hash
+        )));
+    }
+    if object.content.len() as u64 != object.envelope.byte_len {
+        return Err(SyncError::Integrity(format!(
+            "{}: {} bytes, envelope says {}",
+            object.key,
+            object.content.len(),
+            object.envelope.byte_len
+        )));
+    }
+    Ok(())
+}
+
+/// Where `envelope`'s transcript lands under `root`. The part of the origin
+/// `source_file` below a directory named like `root` (`projects`, `sessions`)
+/// is kept, so Claude Code's per-project folders and Codex's date folders
+/// survive the trip; otherwise the transcript goes directly under `root`.
+/// `source_file` may come from another OS, so both separators are honoured
+/// and anything that could climb out of `root` is dropped.
+pub fn restore_path(root: &Path, envelope: &Envelope) -> PathBuf {
+    let segments: Vec<&str> = envelope
+        .source_file
+        .split(['/', '\\'])
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .collect();
+    let anchor = root.file_name().and_then(|n| n.to_str());
+    let relative = anchor
+        .and_then(|anchor| segments.iter().rposition(|s| *s == anchor))
+        .map(|i| &segments[i + 1..])
+        .filter(|rest| !rest.is_empty());
+    match relative {
+        Some(rest) => rest.iter().fold(root.to_path_buf(), |p, s| p.join(s)),
+        None => root.join(match segments.last() {
+            Some(name) => name.to_string(),
+            None => format!("{}.jsonl", percent_encode_segment(&envelope.session_id)),
+        }),
+    }
+}
+
+/// Write `object.content` to `dest`, creating parent directories. The write
+/// goes through a sibling temp file so an agent never sees half a transcript.
+pub async fn write_transcript(
+    object: &SyncObject,
+    dest: &Path,
+    overwrite: bool,
+) -> Result<RestoreOutcome, SyncError> {
+    match tokio::fs::read(dest).await {
+        Ok(existing) if existing == object.content => return Ok(RestoreOutcome::Unchanged),
+        Ok(_) if !overwrite => return Ok(RestoreOutcome::SkippedExisting),
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".aikit-pull");
+    let tmp = PathBuf::from(tmp);
+    tokio::fs::write(&tmp, &object.content).await?;
+    tokio::fs::rename(&tmp, dest).await?;
+    Ok(RestoreOutcome::Written)
+}
+
+/// Parse the transcript at `path` from the start and upsert its events.
+/// Returns the number of newly inserted events.
+pub async fn ingest_transcript(
+    adapter: &dyn Adapter,
+    path: &Path,
+    store: &dyn EventStore,
+) -> Result<u64, SyncError> {
+    let result = adapter.parse_session_file(path, 0).await.map_err(|e| {
+        SyncError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("parse {}: {e}", path.display()),
+        ))
+    })?;
+    store
+        .upsert_events(EventBatch {
+            tool_events: result.tool_events,
+            token_events: result.token_events,
+            cache_observations: result.cache_observations,
+        })
+        .await
+        .map_err(|e| SyncError::Io(std::io::Error::other(format!("event store: {e}"))))
+}
+
+/// Pull every session matching `config`. Per-session failures (integrity,
+/// disk, parse) are logged and counted; only listing/fetch failures abort.
+pub async fn pull_sessions(
+    sink: &dyn SyncSink,
+    registry: &Registry,
+    store: Option<&dyn EventStore>,
+    config: &PullConfig,
+) -> Result<PullRunSummary, SyncError> {
+    let folders = session_folders(sink, config).await?;
+    info!(
+        owner = %config.owner,
+        tool = config.tool.map(|t| t.as_str()).unwrap_or(""),
+        session = config.session_id.as_deref().unwrap_or(""),
+        total = folders.len(),
+        "pull.run.start"
+    );
+    let mut summary = PullRunSummary::default();
+    for (folder, keys) in &folders {
+        let pulled = match fetch_session(sink, folder, keys).await {
+            Ok(object) => pull_one(&object, registry, store, config)
+                .await
+                .map(|(outcome, ingested)| (outcome, ingested, object.content.len() as u64)),
+            Err(error) => Err(error),
+        };
+        match pulled {
+            Ok((outcome, ingested, bytes)) => {
+                summary.pulled += 1;
+                summary.bytes_downloaded += bytes;
+                summary.events_ingested += ingested;
+                match outcome {
+                    RestoreOutcome::Written => summary.written += 1,
+                    RestoreOutcome::Unchanged => summary.unchanged += 1,
+                    RestoreOutcome::SkippedExisting => summary.skipped_existing += 1,
+                }
+            }
+            Err(error) => {
+                error!(
+                    session = %folder,
+                    kind = error.kind(),
+                    err = %error,
+                    "pull.session.fail"
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+    info!(
+        pulled = summary.pulled,
+        written = summary.written,
+        failed = summary.failed,
+        events = summary.events_ingested,
+        "pull.run.end"
+    );
+    Ok(summary)
+}
+
+async fn pull_one(
+    object: &SyncObject,
+    registry: &Registry,
+    store: Option<&dyn EventStore>,
+    config: &PullConfig,
+) -> Result<(RestoreOutcome, u64), SyncError> {
+    let kind = tool_kind(&object.envelope.tool);
+    // The OpenCode adapter reads its SQLite DB; a pulled JSONL rendering
+    // can neither go back into it nor be parsed by it.
+    if kind == Some(ToolKind::OpenCode)
+        && (config.ingest || config.destination == PullDestination::Restore)
+    {
+        return Err(SyncError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "open_code transcripts cannot be restored or ingested; pull them with --out and without --ingest",
+        )));
+    }
+    let adapter = kind.and_then(|kind| registry.get(kind));
+    let root = match &config.destination {
+        PullDestination::Restore => adapter
+            .and_then(|adapter| adapter.watch_paths().into_iter().next())
+            .ok_or_else(|| {
+                SyncError::Io(std::io::Error::other(format!(
+                    "no session root to restore {} transcripts into",
+                    object.envelope.tool
+                )))
+            })?,
+        PullDestination::Dir(dir) => tool_dir(dir, &object.envelope.tool)?,
+    };
+    let dest = restore_path(&root, &object.envelope);
+    let outcome = write_transcript(object, &dest, config.overwrite).await?;
+    let ingested = match (config.ingest, store, adapter) {
+        // A skipped transcript is the local one, not what we pulled.
+        (true, Some(store), Some(adapter)) if outcome != RestoreOutcome::SkippedExisting => {
+            ingest_transcript(adapter, &dest, store).await?
+        }
+        _ => 0,
+    };
+    info!(
+        key = %object.key,
+        dest = %dest.display(),
+        outcome = ?outcome,
+        ingested,
+        "pull.session"
+    );
+    Ok((outcome, ingested))
+}
+
+/// `<dir>/<tool>`. The tool name comes from the pulled envelope, so anything
+/// but a plain identifier (`claude_code`, `codex`, …) is refused rather than
+/// joined — a `..` or `/` in it would write outside `dir`.
+fn tool_dir(dir: &Path, tool: &str) -> Result<PathBuf, SyncError> {
+    let safe = !tool.is_empty()
+        && tool
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !safe {
+        return Err(SyncError::Integrity(format!(
+            "envelope tool {tool:?} is not a valid directory name"
+        )));
+    }
+    Ok(dir.join(tool))
+}
+
+fn tool_kind(name: &str) -> Option<ToolKind> {
+    [ToolKind::ClaudeCode, ToolKind::Codex, ToolKind::OpenCode]
+        .into_iter()
+        .find(|kind| kind.as_str() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(source_file: &str) -> Envelope {
+        Envelope {
+            schema_version: 1,
+            owner: "owner".into(),
+            tool: "claude_code".into(),
+            session_id: "s1".into(),
+            source_file: source_file.into(),
+            host: "h".into(),
+            captured_at_ms: 1,
+            content_hash: String::new(),
+            byte_len: 0,
+            scrubber_version: "1".into(),
+            sync_tool_version: "0.1.0".into(),
+            encryption: None,
+        }
+    }
+
+    #[test]
+    fn restore_path_keeps_layout_below_the_root_name() {
+        let root = Path::new("/home/bob/.claude/projects");
+        assert_eq!(
+            restore_path(
+                root,
+                &envelope("/home/alice/.claude/projects/-home-alice-app/s1.jsonl")
+            ),
+            root.join("-home-alice-app").join("s1.jsonl")
+        );
+        assert_eq!(
+            restore_path(
+                root,
+                &envelope(r"C:\Users\alice\.claude\projects\C--app\s1.jsonl")
+            ),
+            root.join("C--app").join("s1.jsonl")
+        );
+        // No anchor: straight under the root, never above it.
+        assert_eq!(
+            restore_path(root, &envelope("/tmp/../../etc/s1.jsonl")),
+            root.join("s1.jsonl")
+        );
+    }
+
+    #[test]
+    fn tool_dir_refuses_names_that_leave_the_out_dir() {
+        let out = Path::new("/tmp/aikit-pull");
+        assert_eq!(tool_dir(out, "open_code").unwrap(), out.join("open_code"));
+        for tool in ["", "..", "../../etc", "a/b", r"a\b", "/abs", "Codex"] {
+            assert!(
+                matches!(tool_dir(out, tool), Err(SyncError::Integrity(_))),
+                "{tool:?} must be refused"
+            );
+        }
+    }
+
+    #[test]
+    fn pull_prefix_narrows_only_as_far_as_the_layout_allows() {
+        assert_eq!(
+            pull_prefix("sessions/", "o", Some(ToolKind::Codex), Some("a/b")),
+            "sessions/o/codex/a%2Fb"
+        );
+        assert_eq!(pull_prefix("", "o", None, Some("a")), "o");
+    }
+}
@@ -1,9 +1,11 @@
+use futures::TryStreamExt;
 use object_store::aws::AmazonS3Builder;
 use object_store::path::Path;
-use object_store::{Certificate, ClientOptions, ObjectStoreExt};
+use object_store::{Certificate, ClientOptions, ObjectStore, ObjectStoreExt};
 
+use crate::key::percent_decode_segment;
 use crate::sink::meta_key;
-use crate::{SyncError, SyncObject, SyncSink};
+use crate::{Envelope, SessionManifest, SyncError, SyncObject, SyncSink};
 
 #[derive(Debug, Clone)]
 pub struct S3SinkConfig {
@@ -61,6 +63,86 @@ impl SyncSink for S3Sink {
             .map_err(|e| SyncError::Backend(e.to_string()))?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError> {
+        let prefix = prefix.trim_matches('/');
+        let prefix = (!prefix.is_empty()).then(|| Path::from(prefix));
+        let objects: Vec<_> = self
+            .store
+            .list(prefix.as_ref())
+            .try_collect()
+            .await
+            .map_err(|e| SyncError::Backend(e.to_string()))?;
+        let mut keys: Vec<_> = objects
+            .into_iter()
+            .map(|meta| logical_key(&meta.location))
+            .filter(|key| key.ends_with(".jsonl"))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError> {
+        let content = self.get_bytes(key).await?;
+        let meta = self.get_bytes(&meta_key(key)).await?;
+        let envelope: Envelope = serde_json::from_slice(&meta)
+            .map_err(|e| SyncError::Backend(format!("parse envelope for {key}: {e}")))?;
+        Ok(SyncObject {
+            key: key.to_string(),
+            content,
+            envelope,
+        })
+    }
+
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError> {
+        let body = serde_json::to_vec(manifest)
+            .map_err(|e| SyncError::Backend(format!("serialize manifest: {e}")))?;
+        self.store
+            .put(&Path::from(key), body.into())
+            .await
+            .map_err(|e| SyncError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError> {
+        let body = match self.get_bytes(key).await {
+            Ok(body) => body,
+            Err(SyncError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_slice(&body)
+            .map(Some)
+            .map_err(|e| SyncError::Backend(format!("parse manifest {key}: {e}")))
+    }
+}
+
+/// The key `put` was given for `location`. `Path::from` percent-encodes each
+/// segment (including the `%` of our own key encoding), so listing has to undo
+/// it before the key can be fed back through `Path::from`.
+fn logical_key(location: &Path) -> String {
+    location
+        .parts()
+        .map(|part| {
+            percent_decode_segment(part.as_ref()).unwrap_or_else(|_| part.as_ref().to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl S3Sink {
+    async fn get_bytes(&self, key: &str) -> Result<bytes::Bytes, SyncError> {
+        let result = match self.store.get(&Path::from(key)).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => {
+                return Err(SyncError::NotFound(key.to_string()))
+            }
+            Err(e) => return Err(SyncError::Backend(e.to_string())),
+        };
+        result
+            .bytes()
+            .await
+            .map_err(|e| SyncError::Backend(e.to_string()))
+    }
 }
 
 #[cfg(test)]
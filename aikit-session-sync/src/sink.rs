@@ -4,10 +4,42 @@ use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::crypto::EnvelopeEncryption;
+use crate::manifest::SessionManifest;
 
 #[async_trait]
 pub trait SyncSink: Send + Sync {
     async fn put(&self, object: SyncObject) -> Result<(), SyncError>;
+
+    /// Content keys (`*.jsonl`, never their `.meta.json` siblings) under
+    /// `prefix`, sorted. `prefix` matches whole path segments: `a/b` lists
+    /// `a/b/…` but not `a/bc/…`; an empty prefix lists everything.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError>;
+
+    /// The content and envelope stored under content key `key`.
+    /// [`SyncError::NotFound`] when either half is missing (e.g. a `put` that
+    /// failed between the two writes).
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError>;
+
+    /// Create or replace the session manifest at `key`.
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError>;
+
+    /// The manifest at `key`, or `None` for a session synced before delta
+    /// uploads (snapshots only).
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError>;
+
+    /// Hash naming `content` in object keys, envelopes and manifests: SHA-256
+    /// by default, a keyed HMAC for [`EncryptedSink`](crate::EncryptedSink).
+    fn content_hash(&self, content: &[u8]) -> String {
+        hex::encode(Sha256::digest(content))
+    }
+
+    /// Check an object returned by [`get`](Self::get) against its envelope.
+    fn verify(&self, object: &SyncObject) -> Result<(), SyncError> {
+        crate::verify_object(object)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,8 +60,29 @@ pub struct Envelope {
     pub captured_at_ms: i64,
     pub content_hash: String,
     pub byte_len: u64,
-    pub scrubber_version: u32,
+    /// [`SecretScrubber::version`](aikit_session_capture::SecretScrubber::version)
+    /// of the rule set that scrubbed `content`. Envelopes written before rule
+    /// sets were configurable carry the bare pattern version (`"1"`).
+    #[serde(deserialize_with = "string_or_number")]
+    pub scrubber_version: String,
     pub sync_tool_version: String,
+    /// Set when `content` is stored encrypted; `content_hash` is then a keyed
+    /// hash of the plaintext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EnvelopeEncryption>,
+}
+
+fn string_or_number<'de, D: serde::Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Version {
+        Text(String),
+        Number(u64),
+    }
+    Ok(match Version::deserialize(de)? {
+        Version::Text(text) => text,
+        Version::Number(n) => n.to_string(),
+    })
 }
 
 #[non_exhaustive]
@@ -41,17 +94,24 @@ pub enum SyncError {
     Backend(String),
     #[error("auth: {0}")]
     Auth(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("integrity: {0}")]
+    Integrity(String),
 }
 
 impl SyncError {
-    /// Stable, low-cardinality label for logs/metrics: `io` | `backend` | `auth`.
-    /// Lets an analyst filter `kind=auth` (creds) vs `kind=backend` (S3/network)
-    /// vs `kind=io` (local file) without parsing the message.
+    /// Stable, low-cardinality label for logs/metrics: `io` | `backend` |
+    /// `auth` | `not_found` | `integrity`. Lets an analyst filter `kind=auth`
+    /// (creds) vs `kind=backend` (S3/network) vs `kind=io` (local file) without
+    /// parsing the message.
     pub fn kind(&self) -> &'static str {
         match self {
             SyncError::Io(_) => "io",
             SyncError::Backend(_) => "backend",
             SyncError::Auth(_) => "auth",
+            SyncError::NotFound(_) => "not_found",
+            SyncError::Integrity(_) => "integrity",
         }
     }
 }
@@ -65,6 +125,7 @@ pub struct InMemorySink {
 struct InMemoryInner {
     objects: HashMap<String, Bytes>,
     envelopes: HashMap<String, Envelope>,
+    manifests: HashMap<String, SessionManifest>,
     put_calls: usize,
     fail_meta_once: bool,
     fail_all: bool,
@@ -107,6 +168,10 @@ impl InMemorySink {
         self.inner.lock().unwrap().envelopes.get(key).cloned()
     }
 
+    pub fn manifest(&self, key: &str) -> Option<SessionManifest> {
+        self.inner.lock().unwrap().manifests.get(key).cloned()
+    }
+
     pub fn keys(&self) -> Vec<String> {
         let mut keys: Vec<_> = self.inner.lock().unwrap().objects.keys().cloned().collect();
         keys.sort();
@@ -135,6 +200,64 @@ impl SyncSink for InMemorySink {
             .insert(meta_key(&object.key), object.envelope);
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError> {
+        let inner = self.inner.lock().unwrap();
+        if inner.fail_all {
+            return Err(SyncError::Backend("injected failure".to_string()));
+        }
+        let mut keys: Vec<_> = inner
+            .objects
+            .keys()
+            .filter(|key| key_has_prefix(key, prefix))
+            .cloned()
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError> {
+        let inner = self.inner.lock().unwrap();
+        if inner.fail_all {
+            return Err(SyncError::Backend("injected failure".to_string()));
+        }
+        let content = inner.objects.get(key);
+        let envelope = inner.envelopes.get(&meta_key(key));
+        match (content, envelope) {
+            (Some(content), Some(envelope)) => Ok(SyncObject {
+                key: key.to_string(),
+                content: content.clone(),
+                envelope: envelope.clone(),
+            }),
+            _ => Err(SyncError::NotFound(key.to_string())),
+        }
+    }
+
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.fail_all {
+            return Err(SyncError::Backend("injected failure".to_string()));
+        }
+        inner.manifests.insert(key.to_string(), manifest.clone());
+        Ok(())
+    }
+
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError> {
+        let inner = self.inner.lock().unwrap();
+        if inner.fail_all {
+            return Err(SyncError::Backend("injected failure".to_string()));
+        }
+        Ok(inner.manifests.get(key).cloned())
+    }
+}
+
+/// Whether `key` sits under `prefix` on a `/` segment boundary.
+pub(crate) fn key_has_prefix(key: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_matches('/');
+    prefix.is_empty()
+        || key
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
 }
 
 pub(crate) fn meta_key(content_key: &str) -> String {
@@ -148,6 +271,15 @@ pub(crate) fn meta_key(content_key: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn envelopes_with_a_numeric_scrubber_version_still_parse() {
+        let legacy = r#"{"schema_version":1,"owner":"o","tool":"codex","session_id":"s",
+            "source_file":"/tmp/s.jsonl","host":"h","captured_at_ms":1,"content_hash":"x",
+            "byte_len":1,"scrubber_version":1,"sync_tool_version":"0.1.0"}"#;
+        let envelope: Envelope = serde_json::from_str(legacy).unwrap();
+        assert_eq!(envelope.scrubber_version, "1");
+    }
+
     #[tokio::test]
     async fn in_memory_sink_writes_content_and_meta() {
         let sink = InMemorySink::new();
@@ -161,8 +293,9 @@ mod tests {
             captured_at_ms: 1,
             content_hash: "abc".into(),
             byte_len: 4,
-            scrubber_version: 1,
+            scrubber_version: "1".into(),
             sync_tool_version: "0.1.0".into(),
+            encryption: None,
         };
         sink.put(SyncObject {
             key: "sessions/owner/codex/s/abc.jsonl".into(),
@@ -175,7 +308,52 @@ mod tests {
         assert_eq!(sink.meta_count(), 1);
         assert_eq!(
             sink.get_envelope("sessions/owner/codex/s/abc.meta.json"),
-            Some(env)
+            Some(env.clone())
+        );
+
+        let got = sink.get("sessions/owner/codex/s/abc.jsonl").await.unwrap();
+        assert_eq!(got.content, Bytes::from_static(b"body"));
+        assert_eq!(got.envelope, env);
+        assert!(matches!(
+            sink.get("sessions/owner/codex/s/nope.jsonl").await,
+            Err(SyncError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn in_memory_list_matches_whole_segments() {
+        let sink = InMemorySink::new();
+        for key in [
+            "sessions/owner/codex/s/a.jsonl",
+            "sessions/owner/codex/s2/b.jsonl",
+            "sessions/other/codex/s/c.jsonl",
+        ] {
+            sink.put(SyncObject {
+                key: key.into(),
+                content: Bytes::from_static(b"x"),
+                envelope: Envelope {
+                    schema_version: 1,
+                    owner: "owner".into(),
+                    tool: "codex".into(),
+                    session_id: "s".into(),
+                    source_file: String::new(),
+                    host: "h".into(),
+                    captured_at_ms: 1,
+                    content_hash: "x".into(),
+                    byte_len: 1,
+                    scrubber_version: "1".into(),
+                    sync_tool_version: "0.1.0".into(),
+                    encryption: None,
+                },
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            sink.list("sessions/owner/codex/s").await.unwrap(),
+            vec!["sessions/owner/codex/s/a.jsonl"]
         );
+        assert_eq!(sink.list("sessions/owner/").await.unwrap().len(), 2);
+        assert_eq!(sink.list("").await.unwrap().len(), 3);
     }
 }
@@ -33,8 +33,22 @@ pub struct SyncStateEntry {
     pub last_synced_content_hash: String,
     pub mtime_ms: u128,
     pub size: u64,
+    /// Raw-file byte offset just past the last complete line uploaded. Zero
+    /// for entries written before delta sync, which restarts them from the top.
+    #[serde(default)]
+    pub synced_offset: u64,
+    /// sha256 of the raw bytes just before `synced_offset` (up to
+    /// [`ANCHOR_LEN`]). If they no longer hash the same, the file was rewritten
+    /// rather than appended to. For an exported OpenCode session it covers the
+    /// whole synced rendering, and `mtime_ms` holds the session's
+    /// `time_updated` stamp.
+    #[serde(default)]
+    pub anchor_hash: String,
 }
 
+/// Bytes before the synced offset that [`SyncStateEntry::anchor_hash`] covers.
+pub const ANCHOR_LEN: u64 = 256;
+
 impl SyncStateEntry {
     pub fn fingerprint(&self) -> FileFingerprint {
         FileFingerprint {
@@ -144,6 +158,8 @@ mod tests {
             last_synced_content_hash: "abc".into(),
             mtime_ms: 10,
             size: 20,
+            synced_offset: 20,
+            anchor_hash: "def".into(),
         };
         store.save(&source, entry.clone()).await.unwrap();
         assert_eq!(store.load(&source).await, Some(entry));
@@ -186,6 +202,14 @@ mod tests {
         assert!(tmp.path().join(".aikit").join("session-sync").is_dir());
     }
 
+    #[test]
+    fn entries_from_before_delta_sync_still_load() {
+        let old = r#"{"last_synced_content_hash":"abc","mtime_ms":10,"size":20}"#;
+        let entry: SyncStateEntry = serde_json::from_str(old).unwrap();
+        assert_eq!(entry.synced_offset, 0);
+        assert!(entry.anchor_hash.is_empty());
+    }
+
     #[tokio::test]
     async fn load_missing_source_is_none() {
         let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,391 @@
+//! A [`SyncSink`] over WebDAV (RFC 4918), for teams whose shared storage is
+//! a company WebDAV server (Nextcloud, Apache `mod_dav`, IIS, …) rather than
+//! an object store. Objects keep the `object_key` layout as paths under the
+//! base URL, with the same `.meta.json` sidecars and `manifest.json` files.
+//!
+//! Only plain `PUT`/`GET`, `MKCOL` and `PROPFIND` with `Depth: 1` are used, so
+//! servers that disable `Depth: infinity` still work.
+
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+use quick_xml::events::Event;
+use reqwest::{Method, StatusCode};
+
+use crate::key::{percent_decode_segment, percent_encode_segment};
+use crate::sink::{key_has_prefix, meta_key};
+use crate::{Envelope, SessionManifest, SyncError, SyncObject, SyncSink};
+
+#[derive(Debug, Clone)]
+pub struct WebDavSinkConfig {
+    /// Collection everything is stored under, e.g. `https://dav.example.com/remote.php/dav/files/svc/sessions`.
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub allow_http: bool,
+    pub endpoint_ca_bundle: Option<std::path::PathBuf>,
+}
+
+pub struct WebDavSink {
+    client: reqwest::Client,
+    /// Base URL without a trailing slash.
+    base: String,
+    /// Path component of `base`, to turn PROPFIND hrefs back into keys.
+    base_path: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl WebDavSink {
+    pub fn new(config: WebDavSinkConfig) -> Result<Self, SyncError> {
+        let url = reqwest::Url::parse(&config.url)
+            .map_err(|e| SyncError::Backend(format!("invalid WebDAV url {}: {e}", config.url)))?;
+        match url.scheme() {
+            "https" => {}
+            "http" if config.allow_http => {}
+            "http" => {
+                return Err(SyncError::Backend(
+                    "plain-HTTP WebDAV url requires --allow-http".to_string(),
+                ))
+            }
+            other => {
+                return Err(SyncError::Backend(format!(
+                    "unsupported WebDAV url scheme {other}"
+                )))
+            }
+        }
+        let mut builder = reqwest::Client::builder();
+        if let Some(path) = &config.endpoint_ca_bundle {
+            let pem = std::fs::read(path)?;
+            for cert in reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| SyncError::Backend(format!("invalid CA bundle: {e}")))?
+            {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        let client = builder
+            .build()
+            .map_err(|e| SyncError::Backend(e.to_string()))?;
+        Ok(Self {
+            client,
+            base: config.url.trim_end_matches('/').to_string(),
+            base_path: url.path().trim_end_matches('/').to_string(),
+            username: config.username,
+            password: config.password,
+        })
+    }
+
+    /// URL of `key` (or of a collection when `collection` is set). Each key
+    /// segment is percent-encoded again, so the server stores our own `%`
+    /// escapes literally.
+    fn url_for(&self, key: &str, collection: bool) -> String {
+        let mut url = self.base.clone();
+        for segment in key.split('/').filter(|s| !s.is_empty()) {
+            url.push('/');
+            url.push_str(&percent_encode_segment(segment));
+        }
+        if collection {
+            url.push('/');
+        }
+        url
+    }
+
+    fn request(&self, method: Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.username {
+            Some(user) => request.basic_auth(user, self.password.as_deref()),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, SyncError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SyncError::Backend(e.to_string()))?;
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(SyncError::Auth(format!(
+                "WebDAV server returned {} for {}",
+                response.status(),
+                response.url()
+            ))),
+            _ => Ok(response),
+        }
+    }
+
+    async fn put_bytes(&self, key: &str, body: Bytes) -> Result<(), SyncError> {
+        let url = self.url_for(key, false);
+        let response = self
+            .send(self.request(Method::PUT, &url).body(body.clone()))
+            .await?;
+        // RFC 4918 §9.7.1: 409 when the parent collection does not exist.
+        // Some servers answer 404 instead.
+        let status = match response.status() {
+            StatusCode::CONFLICT | StatusCode::NOT_FOUND => {
+                self.make_collections(key).await?;
+                self.send(self.request(Method::PUT, &url).body(body))
+                    .await?
+                    .status()
+            }
+            status => status,
+        };
+        if !status.is_success() {
+            return Err(SyncError::Backend(format!("PUT {url}: {status}")));
+        }
+        Ok(())
+    }
+
+    /// `MKCOL` every ancestor collection of `key`, outermost first.
+    async fn make_collections(&self, key: &str) -> Result<(), SyncError> {
+        let segments: Vec<_> = key.split('/').filter(|s| !s.is_empty()).collect();
+        for depth in 1..segments.len() {
+            let url = self.url_for(&segments[..depth].join("/"), true);
+            let status = self
+                .send(self.request(Method::from_bytes(b"MKCOL").unwrap(), &url))
+                .await?
+                .status();
+            // 405: the collection already exists.
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(SyncError::Backend(format!("MKCOL {url}: {status}")));
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Bytes, SyncError> {
+        let url = self.url_for(key, false);
+        let response = self.send(self.request(Method::GET, &url)).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(SyncError::NotFound(key.to_string())),
+            status if !status.is_success() => {
+                Err(SyncError::Backend(format!("GET {url}: {status}")))
+            }
+            _ => response
+                .bytes()
+                .await
+                .map_err(|e| SyncError::Backend(e.to_string())),
+        }
+    }
+
+    /// Members of the collection at `key`: `(key, is_collection)`, excluding
+    /// the collection itself. A missing collection has no members.
+    async fn propfind(&self, key: &str) -> Result<Vec<(String, bool)>, SyncError> {
+        let url = self.url_for(key, true);
+        let response = self
+            .send(
+                self.request(Method::from_bytes(b"PROPFIND").unwrap(), &url)
+                    .header("Depth", "1")
+                    .header("Content-Type", "application/xml")
+                    .body(PROPFIND_BODY),
+            )
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Ok(Vec::new()),
+            status if status.as_u16() != 207 && !status.is_success() => {
+                return Err(SyncError::Backend(format!("PROPFIND {url}: {status}")))
+            }
+            _ => {}
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SyncError::Backend(e.to_string()))?;
+        let self_key = key.trim_matches('/');
+        Ok(parse_multistatus(&body)?
+            .into_iter()
+            .filter_map(|(href, collection)| {
+                self.key_for_href(&href)
+                    .filter(|member| member != self_key)
+                    .map(|member| (member, collection))
+            })
+            .collect())
+    }
+
+    /// The key an href from a PROPFIND response names, or `None` when it lies
+    /// outside the base collection.
+    fn key_for_href(&self, href: &str) -> Option<String> {
+        let path = match reqwest::Url::parse(href) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => href.to_string(),
+        };
+        let rest = path.strip_prefix(&self.base_path)?;
+        if !(rest.is_empty() || rest.starts_with('/')) {
+            return None;
+        }
+        rest.split('/')
+            .filter(|s| !s.is_empty())
+            .map(|segment| percent_decode_segment(segment).ok())
+            .collect::<Option<Vec<_>>>()
+            .map(|segments| segments.join("/"))
+    }
+}
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#;
+
+/// `(href, is_collection)` for every `response` in a `multistatus` body.
+fn parse_multistatus(body: &str) -> Result<Vec<(String, bool)>, SyncError> {
+    let mut reader = quick_xml::Reader::from_str(body);
+    let mut out = Vec::new();
+    let mut href: Option<String> = None;
+    let mut collection = false;
+    let mut in_href = false;
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| SyncError::Backend(format!("parse PROPFIND response: {e}")))?
+        {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"response" => {
+                    href = None;
+                    collection = false;
+                }
+                b"href" => in_href = true,
+                b"collection" => collection = true,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"collection" => collection = true,
+            Event::Text(text) if in_href => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| SyncError::Backend(format!("parse PROPFIND href: {e}")))?;
+                href.get_or_insert_with(String::new).push_str(text.trim());
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"href" => in_href = false,
+                b"response" => {
+                    if let Some(href) = href.take() {
+                        out.push((href, collection));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+#[async_trait::async_trait]
+impl SyncSink for WebDavSink {
+    async fn put(&self, object: SyncObject) -> Result<(), SyncError> {
+        self.put_bytes(&object.key, object.content).await?;
+        let meta = serde_json::to_vec(&object.envelope)
+            .map_err(|e| SyncError::Backend(format!("serialize envelope: {e}")))?;
+        self.put_bytes(&meta_key(&object.key), Bytes::from(meta))
+            .await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, SyncError> {
+        let prefix = prefix.trim_matches('/').to_string();
+        let mut keys = Vec::new();
+        let mut pending = VecDeque::from([prefix.clone()]);
+        while let Some(collection) = pending.pop_front() {
+            for (key, is_collection) in self.propfind(&collection).await? {
+                if is_collection {
+                    pending.push_back(key);
+                } else if key.ends_with(".jsonl") && key_has_prefix(&key, &prefix) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<SyncObject, SyncError> {
+        let content = self.get_bytes(key).await?;
+        let meta = self.get_bytes(&meta_key(key)).await?;
+        let envelope: Envelope = serde_json::from_slice(&meta)
+            .map_err(|e| SyncError::Backend(format!("parse envelope for {key}: {e}")))?;
+        Ok(SyncObject {
+            key: key.to_string(),
+            content,
+            envelope,
+        })
+    }
+
+    async fn put_manifest(&self, key: &str, manifest: &SessionManifest) -> Result<(), SyncError> {
+        let body = serde_json::to_vec(manifest)
+            .map_err(|e| SyncError::Backend(format!("serialize manifest: {e}")))?;
+        self.put_bytes(key, Bytes::from(body)).await
+    }
+
+    async fn get_manifest(&self, key: &str) -> Result<Option<SessionManifest>, SyncError> {
+        let body = match self.get_bytes(key).await {
+            Ok(body) => body,
+            Err(SyncError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_slice(&body)
+            .map(Some)
+            .map_err(|e| SyncError::Backend(format!("parse manifest {key}: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(url: &str) -> WebDavSink {
+        WebDavSink::new(WebDavSinkConfig {
+            url: url.into(),
+            username: None,
+            password: None,
+            allow_http: true,
+            endpoint_ca_bundle: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn plain_http_needs_allow_http() {
+        let config = WebDavSinkConfig {
+            url: "http://dav.internal/sessions".into(),
+            username: None,
+            password: None,
+            allow_http: false,
+            endpoint_ca_bundle: None,
+        };
+        assert!(matches!(
+            WebDavSink::new(config),
+            Err(SyncError::Backend(_))
+        ));
+    }
+
+    #[test]
+    fn urls_and_hrefs_round_trip_encoded_keys() {
+        let sink = sink("http://dav.internal/remote.php/dav/files/svc/");
+        let key = "sessions/alice/codex/a%2Fb/abc.jsonl";
+        let url = sink.url_for(key, false);
+        assert_eq!(
+            url,
+            "http://dav.internal/remote.php/dav/files/svc/sessions/alice/codex/a%252Fb/abc.jsonl"
+        );
+        assert_eq!(sink.key_for_href(&url).as_deref(), Some(key));
+        assert_eq!(
+            sink.key_for_href("/remote.php/dav/files/svc/sessions/alice/codex/a%252Fb/abc.jsonl")
+                .as_deref(),
+            Some(key)
+        );
+        assert_eq!(sink.key_for_href("/remote.php/dav/files/other/x"), None);
+    }
+
+    #[test]
+    fn multistatus_parsing_handles_prefixes_and_collections() {
+        let body = r#"<?xml version="1.0"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response><D:href>/dav/sessions/</D:href>
+    <D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response>
+  <D:response><D:href>/dav/sessions/a.jsonl</D:href>
+    <D:propstat><D:prop><D:resourcetype/></D:prop></D:propstat></D:response>
+</D:multistatus>"#;
+        assert_eq!(
+            parse_multistatus(body).unwrap(),
+            vec![
+                ("/dav/sessions/".to_string(), true),
+                ("/dav/sessions/a.jsonl".to_string(), false)
+            ]
+        );
+    }
+}
@@ -227,7 +227,7 @@ async fn e2e_sync_round_trips_content_scrubbed_and_envelope_via_minio() {
         SyncOutcome::SkippedUnchanged
     );
 
-    // Grown file → a second, distinct version object is retained.
+    // Grown file → only the appended line goes up, as a second chunk object.
     tokio::time::sleep(std::time::Duration::from_millis(2)).await;
     tokio::fs::write(
         &file,
@@ -239,10 +239,7 @@ async fn e2e_sync_round_trips_content_scrubbed_and_envelope_via_minio() {
         SyncOutcome::Synced { key, .. } => key,
         other => panic!("expected Synced on grown file, got {other:?}"),
     };
-    assert_ne!(
-        key, key2,
-        "grown file must produce a new content-addressed key"
-    );
+    assert_ne!(key, key2, "grown file must produce a new chunk key");
 
     let listed = store
         .list(Some(&object_store::path::Path::from(format!(
@@ -255,10 +252,31 @@ async fn e2e_sync_round_trips_content_scrubbed_and_envelope_via_minio() {
         .filter_map(Result::ok)
         .filter(|m| m.location.as_ref().ends_with(".jsonl"))
         .count();
+    assert_eq!(jsonl_versions, 2, "both chunks must be retained");
+
+    // Read side through S3Sink itself: list returns the keys `put` was given
+    // (no meta siblings), and get pairs content with its verified envelope.
+    let mut expected = vec![key.clone(), key2.clone()];
+    expected.sort();
     assert_eq!(
-        jsonl_versions, 2,
-        "both transcript versions must be retained"
+        sink.list("sessions/alice/claude_code").await.unwrap(),
+        expected
     );
+    let manifest = sink
+        .get_manifest(&aikit_session_sync::manifest_key(
+            aikit_session_sync::folder_of_key(&key2),
+        ))
+        .await
+        .unwrap()
+        .expect("manifest stored next to the chunks");
+    assert_eq!(manifest.chunks.len(), 2);
+    assert_eq!(manifest.chunks[1].key, key2);
+    let pulled = sink.get(&key2).await.expect("get via sink");
+    aikit_session_sync::verify_object(&pulled).expect("pulled object verifies");
+    assert!(matches!(
+        sink.get("sessions/alice/claude_code/nope/0.jsonl").await,
+        Err(aikit_session_sync::SyncError::NotFound(_))
+    ));
 
     // S3Sink error path: a PUT to a bucket that does not exist returns a fast
     // NoSuchBucket error, which must surface as SyncError::Backend (not panic).
@@ -0,0 +1,323 @@
+//! Sync → pull round trip: a Codex rollout synced from one "laptop" is pulled
+//! onto another, restored under that machine's Codex session root at the same
+//! relative path (so `codex resume` finds it), and ingested into its capture
+//! store. Both machines are temp dirs; the sink is in-memory.
+
+use std::sync::Arc;
+
+use aikit_session_capture::codex::CodexAdapter;
+use aikit_session_capture::{EventStore, InMemoryEventStore, Registry};
+use aikit_session_sync::state::InMemorySyncStateStore;
+use aikit_session_sync::{
+    fetch_session, pull_sessions, session_folders, verify_object, EncryptedSink, InMemorySink,
+    Keyring, PullConfig, PullDestination, SyncConfig, SyncEngine, SyncObject, SyncSink,
+    SyncStateStore,
+};
+use bytes::Bytes;
+
+const ROLLOUT: &str = concat!(
+    r#"{"id":"sc","timestamp":"2026-04-16T12:00:00Z","type":"session_configured","payload":{"session_id":"cx-001","model":"gpt-5-codex","cwd":"/tmp/app"}}"#,
+    "\n",
+    r#"{"id":"tc-1","timestamp":"2026-04-16T12:00:02Z","type":"tool_call","payload":{"call_id":"call_01","tool":"shell","input":{"command":["bash","-lc","cargo test"]}}}"#,
+    "\n",
+    r#"{"id":"to-1","timestamp":"2026-04-16T12:00:03Z","type":"tool_output","payload":{"call_id":"call_01","output":"test result: ok","success":true}}"#,
+    "\n",
+);
+
+fn pull_config(session_id: Option<&str>) -> PullConfig {
+    PullConfig {
+        key_prefix: "sessions/".into(),
+        owner: "alice".into(),
+        tool: None,
+        session_id: session_id.map(str::to_string),
+        destination: PullDestination::Restore,
+        overwrite: false,
+        ingest: true,
+    }
+}
+
+#[tokio::test]
+async fn synced_session_is_restored_and_ingested_on_another_machine() {
+    let origin = tempfile::tempdir().unwrap();
+    let laptop = tempfile::tempdir().unwrap();
+    let origin_root = origin.path().join(".codex").join("sessions");
+    let laptop_root = laptop.path().join(".codex").join("sessions");
+    let file = origin_root
+        .join("2026")
+        .join("04")
+        .join("16")
+        .join("rollout-cx-001.jsonl");
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(&file, ROLLOUT.lines().next().unwrap().to_string() + "\n").unwrap();
+
+    let sink = Arc::new(InMemorySink::new());
+    let engine = SyncEngine::new(
+        SyncConfig {
+            owner: Some("alice".into()),
+            host: "origin".into(),
+            ..SyncConfig::default()
+        },
+        sink.clone() as Arc<dyn SyncSink>,
+        Arc::new(InMemorySyncStateStore::default()) as Arc<dyn SyncStateStore>,
+    )
+    .unwrap();
+    let origin_adapter = CodexAdapter::new().with_override_root(origin_root);
+    engine.sync_file(&origin_adapter, &file).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    std::fs::write(&file, ROLLOUT).unwrap();
+    engine.sync_file(&origin_adapter, &file).await.unwrap();
+    assert_eq!(sink.object_count(), 2);
+
+    let mut registry = Registry::new();
+    registry.register(Box::new(
+        CodexAdapter::new().with_override_root(laptop_root.clone()),
+    ));
+    let store = InMemoryEventStore::default();
+    let summary = pull_sessions(
+        sink.as_ref(),
+        &registry,
+        Some(&store as &dyn EventStore),
+        &pull_config(Some("rollout-cx-001")),
+    )
+    .await
+    .unwrap();
+
+    // The two synced chunks come back as one transcript.
+    assert_eq!(summary.pulled, 1);
+    assert_eq!(summary.written, 1);
+    assert_eq!(summary.failed, 0);
+    assert!(summary.events_ingested > 0);
+    let restored = laptop_root
+        .join("2026")
+        .join("04")
+        .join("16")
+        .join("rollout-cx-001.jsonl");
+    assert_eq!(std::fs::read_to_string(&restored).unwrap(), ROLLOUT);
+
+    // A second pull finds the transcript already in place.
+    let again = pull_sessions(sink.as_ref(), &registry, None, &pull_config(None))
+        .await
+        .unwrap();
+    assert_eq!(again.unchanged, 1);
+    assert_eq!(again.written, 0);
+
+    // A transcript that kept growing locally is left alone unless asked.
+    std::fs::write(&restored, format!("{ROLLOUT}{{}}\n")).unwrap();
+    let kept = pull_sessions(sink.as_ref(), &registry, None, &pull_config(None))
+        .await
+        .unwrap();
+    assert_eq!(kept.skipped_existing, 1);
+    let mut overwrite = pull_config(None);
+    overwrite.overwrite = true;
+    overwrite.ingest = false;
+    let replaced = pull_sessions(sink.as_ref(), &registry, None, &overwrite)
+        .await
+        .unwrap();
+    assert_eq!(replaced.written, 1);
+    assert_eq!(std::fs::read_to_string(&restored).unwrap(), ROLLOUT);
+}
+
+#[tokio::test]
+async fn tampered_object_fails_verification_and_is_not_written() {
+    let out = tempfile::tempdir().unwrap();
+    let sink = InMemorySink::new();
+    let key = "sessions/alice/codex/s1/abc.jsonl";
+    sink.put(SyncObject {
+        key: key.into(),
+        content: Bytes::from_static(b"{\"tampered\":true}\n"),
+        envelope: aikit_session_sync::Envelope {
+            schema_version: 1,
+            owner: "alice".into(),
+            tool: "codex".into(),
+            session_id: "s1".into(),
+            source_file: "/home/alice/.codex/sessions/s1.jsonl".into(),
+            host: "origin".into(),
+            captured_at_ms: 1,
+            content_hash: "abc".into(),
+            byte_len: 18,
+            scrubber_version: "1".into(),
+            sync_tool_version: "0.1.0".into(),
+            encryption: None,
+        },
+    })
+    .await
+    .unwrap();
+
+    let mut config = pull_config(None);
+    config.destination = PullDestination::Dir(out.path().to_path_buf());
+    let summary = pull_sessions(&sink, &Registry::new(), None, &config)
+        .await
+        .unwrap();
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.pulled, 0);
+    assert!(!out.path().join("codex").join("s1.jsonl").exists());
+}
+
+#[tokio::test]
+async fn encrypted_sync_pulls_back_only_with_the_key() {
+    const KEY: &str = "8f4e2c1a9b7d5e3f0a6c8e2b4d6f8a0c1e3b5d7f9a2c4e6b8d0f1a3c5e7b9d2f";
+    let origin = tempfile::tempdir().unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let root = origin.path().join("sessions");
+    let file = root.join("rollout-cx-002.jsonl");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(&file, ROLLOUT).unwrap();
+
+    let bucket = Arc::new(InMemorySink::new());
+    let encrypted = Arc::new(EncryptedSink::new(
+        bucket.clone() as Arc<dyn SyncSink>,
+        Keyring::parse(KEY).unwrap(),
+    ));
+    let engine = SyncEngine::new(
+        SyncConfig {
+            owner: Some("alice".into()),
+            host: "origin".into(),
+            ..SyncConfig::default()
+        },
+        encrypted.clone() as Arc<dyn SyncSink>,
+        Arc::new(InMemorySyncStateStore::default()) as Arc<dyn SyncStateStore>,
+    )
+    .unwrap();
+    let key = match engine
+        .sync_file(&CodexAdapter::new().with_override_root(root), &file)
+        .await
+        .unwrap()
+    {
+        aikit_session_sync::SyncOutcome::Synced { key, .. } => key,
+        other => panic!("expected Synced, got {other:?}"),
+    };
+    let stored = bucket.get_content(&key).unwrap();
+    assert!(!String::from_utf8_lossy(&stored).contains("cargo test"));
+
+    let mut config = pull_config(None);
+    config.destination = PullDestination::Dir(out.path().to_path_buf());
+    config.ingest = false;
+    let without_key = pull_sessions(bucket.as_ref(), &Registry::new(), None, &config)
+        .await
+        .unwrap();
+    assert_eq!(without_key.failed, 1);
+
+    let with_key = pull_sessions(encrypted.as_ref(), &Registry::new(), None, &config)
+        .await
+        .unwrap();
+    assert_eq!(with_key.failed, 0);
+    assert_eq!(with_key.written, 1);
+    let pulled = walkdir::WalkDir::new(out.path())
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file())
+        .expect("pulled transcript");
+    assert_eq!(std::fs::read_to_string(pulled.path()).unwrap(), ROLLOUT);
+}
+
+#[tokio::test]
+async fn encrypted_chunked_session_assembles_into_a_plaintext_envelope() {
+    const KEY: &str = "8f4e2c1a9b7d5e3f0a6c8e2b4d6f8a0c1e3b5d7f9a2c4e6b8d0f1a3c5e7b9d2f";
+    let origin = tempfile::tempdir().unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let root = origin.path().join("sessions");
+    let file = root.join("rollout-cx-003.jsonl");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(&file, ROLLOUT.lines().next().unwrap().to_string() + "\n").unwrap();
+
+    let bucket = Arc::new(InMemorySink::new());
+    let encrypted = Arc::new(EncryptedSink::new(
+        bucket.clone() as Arc<dyn SyncSink>,
+        Keyring::parse(KEY).unwrap(),
+    ));
+    let engine = SyncEngine::new(
+        SyncConfig {
+            owner: Some("alice".into()),
+            host: "origin".into(),
+            ..SyncConfig::default()
+        },
+        encrypted.clone() as Arc<dyn SyncSink>,
+        Arc::new(InMemorySyncStateStore::default()) as Arc<dyn SyncStateStore>,
+    )
+    .unwrap();
+    let adapter = CodexAdapter::new().with_override_root(root);
+    engine.sync_file(&adapter, &file).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    std::fs::write(&file, ROLLOUT).unwrap();
+    engine.sync_file(&adapter, &file).await.unwrap();
+    assert_eq!(bucket.object_count(), 2);
+
+    let mut config = pull_config(None);
+    config.destination = PullDestination::Dir(out.path().to_path_buf());
+    config.ingest = false;
+    let folders = session_folders(encrypted.as_ref(), &config).await.unwrap();
+    assert_eq!(folders.len(), 1);
+    let (folder, keys) = &folders[0];
+    let assembled = fetch_session(encrypted.as_ref(), folder, keys)
+        .await
+        .unwrap();
+    assert_eq!(assembled.content.as_ref(), ROLLOUT.as_bytes());
+    assert_eq!(assembled.envelope.encryption, None);
+    verify_object(&assembled).unwrap();
+    encrypted.verify(&assembled).unwrap();
+
+    let summary = pull_sessions(encrypted.as_ref(), &Registry::new(), None, &config)
+        .await
+        .unwrap();
+    assert_eq!(summary.failed, 0);
+    assert_eq!(summary.written, 1);
+}
+
+#[tokio::test]
+async fn key_rotation_keeps_delta_sync_and_pulls_chunks_sealed_under_both_keys() {
+    const OLD_KEY: &str = "8f4e2c1a9b7d5e3f0a6c8e2b4d6f8a0c1e3b5d7f9a2c4e6b8d0f1a3c5e7b9d2f";
+    const NEW_KEY: &str = "1d3f5b7a9c2e4f6a8b0d1c3e5f7a9b2d4c6e8f0a1b3d5c7e9f2a4b6d8c0e1f3a";
+    let origin = tempfile::tempdir().unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let root = origin.path().join("sessions");
+    let file = root.join("rollout-cx-004.jsonl");
+    std::fs::create_dir_all(&root).unwrap();
+    let first_line = ROLLOUT.split_inclusive('\n').next().unwrap();
+    std::fs::write(&file, first_line).unwrap();
+
+    let bucket = Arc::new(InMemorySink::new());
+    let state = Arc::new(InMemorySyncStateStore::default());
+    let engine = |keys: &str| {
+        SyncEngine::new(
+            SyncConfig {
+                owner: Some("alice".into()),
+                host: "origin".into(),
+                ..SyncConfig::default()
+            },
+            Arc::new(EncryptedSink::new(
+                bucket.clone() as Arc<dyn SyncSink>,
+                Keyring::parse(keys).unwrap(),
+            )) as Arc<dyn SyncSink>,
+            state.clone() as Arc<dyn SyncStateStore>,
+        )
+        .unwrap()
+    };
+    let adapter = CodexAdapter::new().with_override_root(root);
+    engine(OLD_KEY).sync_file(&adapter, &file).await.unwrap();
+
+    // Rotate: the new key encrypts, the old one stays for decryption.
+    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+    std::fs::write(&file, ROLLOUT).unwrap();
+    let rotated = format!("{NEW_KEY}\n{OLD_KEY}\n");
+    // Only the appended lines go up, sealed and hashed under the new key.
+    match engine(&rotated).sync_file(&adapter, &file).await.unwrap() {
+        aikit_session_sync::SyncOutcome::Synced { bytes_uploaded, .. } => {
+            assert_eq!(bytes_uploaded, (ROLLOUT.len() - first_line.len()) as u64)
+        }
+        other => panic!("expected Synced, got {other:?}"),
+    }
+    assert_eq!(bucket.object_count(), 2);
+
+    let mut config = pull_config(None);
+    config.destination = PullDestination::Dir(out.path().to_path_buf());
+    config.ingest = false;
+    let sink = EncryptedSink::new(
+        bucket.clone() as Arc<dyn SyncSink>,
+        Keyring::parse(&rotated).unwrap(),
+    );
+    let summary = pull_sessions(&sink, &Registry::new(), None, &config)
+        .await
+        .unwrap();
+    assert_eq!(summary.failed, 0);
+    assert_eq!(summary.written, 1);
+}
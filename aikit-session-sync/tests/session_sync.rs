@@ -1,4 +1,4 @@
-use aikit_session_capture::{SecretScrubber, SCRUBBER_PATTERN_VERSION};
+use aikit_session_capture::SecretScrubber;
 use aikit_session_sync::{
     decode_session_id_from_key, object_key, Envelope, InMemorySink, SyncError, SyncObject, SyncSink,
 };
@@ -15,8 +15,9 @@ fn envelope() -> Envelope {
         captured_at_ms: 1,
         content_hash: "hash".into(),
         byte_len: 4,
-        scrubber_version: SCRUBBER_PATTERN_VERSION,
+        scrubber_version: SecretScrubber::default().version(),
         sync_tool_version: "0.1.0".into(),
+        encryption: None,
     }
 }
 
@@ -0,0 +1,360 @@
+//! The engine behaviours first pinned down against `InMemorySink`
+//! (idempotency, sidecar failure, dry-run) and the pull round trip, run
+//! against the directory sink and the WebDAV sink. WebDAV talks to a small
+//! in-process server that implements the subset of RFC 4918 the sink uses,
+//! including the 409 a real server answers for a missing parent collection.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use aikit_session_capture::codex::CodexAdapter;
+use aikit_session_capture::{Registry, ToolKind};
+use aikit_session_sync::state::InMemorySyncStateStore;
+use aikit_session_sync::{
+    chunk_key, pull_sessions, FsSink, PullConfig, PullDestination, SyncConfig, SyncEngine,
+    SyncError, SyncOutcome, SyncSink, SyncStateStore, WebDavSink, WebDavSinkConfig,
+};
+use axum::body::Bytes;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+const LINES: &str = "{\"role\":\"user\",\"text\":\"hi\"}\n{\"role\":\"assistant\"}\n";
+
+struct Session {
+    _dir: tempfile::TempDir,
+    adapter: CodexAdapter,
+    file: PathBuf,
+}
+
+fn session() -> Session {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("sessions");
+    std::fs::create_dir_all(&root).unwrap();
+    let file = root.join("rollout-s1.jsonl");
+    std::fs::write(&file, LINES).unwrap();
+    Session {
+        adapter: CodexAdapter::new().with_override_root(root),
+        file,
+        _dir: dir,
+    }
+}
+
+fn engine(sink: Arc<dyn SyncSink>, dry_run: bool) -> SyncEngine {
+    SyncEngine::new(
+        SyncConfig {
+            owner: Some("alice".into()),
+            host: "h".into(),
+            dry_run,
+            ..SyncConfig::default()
+        },
+        sink,
+        Arc::new(InMemorySyncStateStore::default()) as Arc<dyn SyncStateStore>,
+    )
+    .unwrap()
+}
+
+fn first_chunk_key(sink: &dyn SyncSink) -> String {
+    chunk_key(
+        "sessions/",
+        "alice",
+        ToolKind::Codex,
+        "rollout-s1",
+        0,
+        &sink.content_hash(LINES.as_bytes()),
+    )
+}
+
+/// Same content synced by two engines (fresh state each) lands on one key;
+/// the second sync of an unchanged file is skipped by the local cache.
+async fn assert_idempotent(sink: Arc<dyn SyncSink>) {
+    let s = session();
+    let first = engine(sink.clone(), false);
+    let key = match first.sync_file(&s.adapter, &s.file).await.unwrap() {
+        SyncOutcome::Synced { key, .. } => key,
+        other => panic!("expected Synced, got {other:?}"),
+    };
+    assert_eq!(key, first_chunk_key(sink.as_ref()));
+    assert_eq!(
+        first.sync_file(&s.adapter, &s.file).await.unwrap(),
+        SyncOutcome::SkippedUnchanged
+    );
+    let second = engine(sink.clone(), false);
+    match second.sync_file(&s.adapter, &s.file).await.unwrap() {
+        SyncOutcome::Synced { key: again, .. } => assert_eq!(again, key),
+        other => panic!("expected Synced, got {other:?}"),
+    }
+    assert_eq!(
+        sink.list("sessions/alice").await.unwrap(),
+        vec![key.clone()]
+    );
+    let object = sink.get(&key).await.unwrap();
+    assert_eq!(object.content.as_ref(), LINES.as_bytes());
+    sink.verify(&object).unwrap();
+}
+
+async fn assert_round_trip_through_pull(sink: Arc<dyn SyncSink>) {
+    let s = session();
+    engine(sink.clone(), false)
+        .sync_file(&s.adapter, &s.file)
+        .await
+        .unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let summary = pull_sessions(
+        sink.as_ref(),
+        &Registry::new(),
+        None,
+        &PullConfig {
+            key_prefix: "sessions/".into(),
+            owner: "alice".into(),
+            tool: None,
+            session_id: None,
+            destination: PullDestination::Dir(out.path().to_path_buf()),
+            overwrite: false,
+            ingest: false,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(summary.written, 1);
+    assert_eq!(summary.failed, 0);
+    let pulled = walkdir::WalkDir::new(out.path())
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file())
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(pulled.path()).unwrap(), LINES);
+}
+
+// ── directory sink ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn fs_sink_is_idempotent_and_keeps_the_key_layout() {
+    let root = tempfile::tempdir().unwrap();
+    let sink = Arc::new(FsSink::new(root.path()));
+    assert_idempotent(sink.clone()).await;
+    let key = first_chunk_key(sink.as_ref());
+    let content = root.path().join(&key);
+    assert_eq!(std::fs::read_to_string(&content).unwrap(), LINES);
+    assert!(content.with_extension("meta.json").is_file());
+    assert!(content.parent().unwrap().join("manifest.json").is_file());
+}
+
+#[tokio::test]
+async fn fs_sink_round_trips_through_pull() {
+    let root = tempfile::tempdir().unwrap();
+    assert_round_trip_through_pull(Arc::new(FsSink::new(root.path()))).await;
+}
+
+#[tokio::test]
+async fn fs_sidecar_failure_does_not_advance_state_and_retry_is_idempotent() {
+    let root = tempfile::tempdir().unwrap();
+    let sink = Arc::new(FsSink::new(root.path()));
+    let s = session();
+    let engine = engine(sink.clone(), false);
+
+    // A directory squatting on the sidecar path makes its write fail after
+    // the content file has landed.
+    let key = first_chunk_key(sink.as_ref());
+    let blocker = root.path().join(key.replace(".jsonl", ".meta.json"));
+    std::fs::create_dir_all(blocker.join("x")).unwrap();
+    assert!(engine.sync_file(&s.adapter, &s.file).await.is_err());
+    assert!(root.path().join(&key).is_file());
+
+    std::fs::remove_dir_all(&blocker).unwrap();
+    match engine.sync_file(&s.adapter, &s.file).await.unwrap() {
+        SyncOutcome::Synced { key: retried, .. } => assert_eq!(retried, key),
+        other => panic!("retry must re-upload, got {other:?}"),
+    }
+    assert!(blocker.is_file());
+}
+
+#[tokio::test]
+async fn fs_dry_run_writes_nothing() {
+    let root = tempfile::tempdir().unwrap();
+    let s = session();
+    let outcome = engine(Arc::new(FsSink::new(root.path().join("out"))), true)
+        .sync_file(&s.adapter, &s.file)
+        .await
+        .unwrap();
+    assert!(matches!(outcome, SyncOutcome::Synced { .. }));
+    assert!(!root.path().join("out").exists());
+}
+
+// ── WebDAV sink ───────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Dav {
+    files: BTreeMap<String, Bytes>,
+    collections: BTreeSet<String>,
+    requests: Vec<String>,
+    fail_meta_once: bool,
+}
+
+type Shared = Arc<Mutex<Dav>>;
+
+async fn dav(State(state): State<Shared>, request: Request) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_end_matches('/').to_string();
+    let body = axum::body::to_bytes(request.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let mut dav = state.lock().unwrap();
+    dav.requests.push(format!("{method} {path}"));
+    let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+    let parent_exists = parent.is_empty() || dav.collections.contains(parent);
+    match method.as_str() {
+        "PUT" if !parent_exists => StatusCode::CONFLICT.into_response(),
+        "PUT" if dav.fail_meta_once && path.ends_with(".meta.json") => {
+            dav.fail_meta_once = false;
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        "PUT" => {
+            dav.files.insert(path, body);
+            StatusCode::CREATED.into_response()
+        }
+        "GET" => match dav.files.get(&path) {
+            Some(body) => body.clone().into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+        "MKCOL" if dav.collections.contains(&path) => {
+            StatusCode::METHOD_NOT_ALLOWED.into_response()
+        }
+        "MKCOL" if !parent_exists => StatusCode::CONFLICT.into_response(),
+        "MKCOL" => {
+            dav.collections.insert(path);
+            StatusCode::CREATED.into_response()
+        }
+        "PROPFIND" if !dav.collections.contains(&path) => StatusCode::NOT_FOUND.into_response(),
+        "PROPFIND" => {
+            let child = |p: &String| {
+                p.strip_prefix(&path)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .is_some_and(|rest| !rest.contains('/'))
+            };
+            let mut xml = String::from(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">"#);
+            let members = std::iter::once((format!("{path}/"), true))
+                .chain(
+                    dav.collections
+                        .iter()
+                        .filter(|p| child(p))
+                        .map(|p| (format!("{p}/"), true)),
+                )
+                .chain(
+                    dav.files
+                        .keys()
+                        .filter(|p| child(p))
+                        .map(|p| (p.clone(), false)),
+                );
+            for (href, collection) in members {
+                let kind = if collection { "<d:collection/>" } else { "" };
+                xml.push_str(&format!(
+                    "<d:response><d:href>{href}</d:href><d:propstat><d:prop><d:resourcetype>{kind}</d:resourcetype></d:prop></d:propstat></d:response>"
+                ));
+            }
+            xml.push_str("</d:multistatus>");
+            (StatusCode::MULTI_STATUS, xml).into_response()
+        }
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+}
+
+/// Start the fake server with `/dav` as the only existing collection.
+async fn dav_server() -> (WebDavSink, Shared) {
+    let state: Shared = Arc::default();
+    state.lock().unwrap().collections.insert("/dav".into());
+    let app = axum::Router::new().fallback(dav).with_state(state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let sink = WebDavSink::new(WebDavSinkConfig {
+        url: format!("http://{addr}/dav"),
+        username: None,
+        password: None,
+        allow_http: true,
+        endpoint_ca_bundle: None,
+    })
+    .unwrap();
+    (sink, state)
+}
+
+#[tokio::test]
+async fn webdav_sink_is_idempotent_and_creates_collections() {
+    let (sink, state) = dav_server().await;
+    assert_idempotent(Arc::new(sink)).await;
+    let dav = state.lock().unwrap();
+    assert!(dav
+        .collections
+        .contains("/dav/sessions/alice/codex/rollout-s1"));
+    assert!(dav
+        .files
+        .keys()
+        .any(|p| p.ends_with("/rollout-s1/manifest.json")));
+}
+
+#[tokio::test]
+async fn webdav_sink_round_trips_through_pull() {
+    let (sink, _state) = dav_server().await;
+    assert_round_trip_through_pull(Arc::new(sink)).await;
+}
+
+#[tokio::test]
+async fn webdav_sidecar_failure_does_not_advance_state_and_retry_is_idempotent() {
+    let (sink, state) = dav_server().await;
+    let sink = Arc::new(sink);
+    state.lock().unwrap().fail_meta_once = true;
+    let s = session();
+    let engine = engine(sink.clone(), false);
+    assert!(matches!(
+        engine.sync_file(&s.adapter, &s.file).await,
+        Err(SyncError::Backend(_))
+    ));
+    let key = first_chunk_key(sink.as_ref());
+    match engine.sync_file(&s.adapter, &s.file).await.unwrap() {
+        SyncOutcome::Synced { key: retried, .. } => assert_eq!(retried, key),
+        other => panic!("retry must re-upload, got {other:?}"),
+    }
+    assert!(sink.get(&key).await.is_ok());
+}
+
+#[tokio::test]
+async fn webdav_dry_run_never_writes() {
+    let (sink, state) = dav_server().await;
+    let s = session();
+    engine(Arc::new(sink), true)
+        .sync_file(&s.adapter, &s.file)
+        .await
+        .unwrap();
+    // Reading the manifest to find the resume offset is fine; writing is not.
+    let dav = state.lock().unwrap();
+    assert!(
+        dav.requests.iter().all(|r| r.starts_with("GET ")),
+        "{:?}",
+        dav.requests
+    );
+}
+
+#[tokio::test]
+async fn webdav_auth_failure_maps_to_auth_error() {
+    async fn deny() -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = axum::Router::new().fallback(deny);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let sink = WebDavSink::new(WebDavSinkConfig {
+        url: format!("http://{addr}/dav"),
+        username: Some("svc".into()),
+        password: Some("wrong".into()),
+        allow_http: true,
+        endpoint_ca_bundle: None,
+    })
+    .unwrap();
+    assert!(matches!(
+        sink.list("sessions").await,
+        Err(SyncError::Auth(_))
+    ));
+}
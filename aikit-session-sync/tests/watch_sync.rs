@@ -133,7 +133,8 @@ async fn new_and_grown_session_files_are_synced_via_watch() {
     assert_eq!(sink.object_count(), 1);
 
     // 2. The same transcript grows (a new turn is appended). mtime advances,
-    //    so the poller re-reports it; a second, distinct version is synced.
+    //    so the poller re-reports it; the appended line is synced as a second
+    //    chunk.
     tokio::time::sleep(Duration::from_millis(20)).await;
     tokio::fs::write(
         &file,
@@ -146,9 +147,12 @@ async fn new_and_grown_session_files_are_synced_via_watch() {
         SyncOutcome::Synced { key, .. } => key,
         other => panic!("expected Synced on grown file, got {other:?}"),
     };
-    assert_ne!(
-        key1, key2,
-        "grown transcript must produce a new version key"
+    assert_ne!(key1, key2, "grown transcript must produce a new chunk key");
+    assert_eq!(sink.object_count(), 2, "both chunks retained");
+    let appended = sink.get_content(&key2).expect("chunk present");
+    assert_eq!(
+        appended.as_ref(),
+        b"{\"role\":\"assistant\",\"text\":\"more\"}\n",
+        "only the appended line is uploaded"
     );
-    assert_eq!(sink.object_count(), 2, "both versions retained");
 }
@@ -92,7 +92,7 @@ mod tests {
     use super::*;
     use aikit_evals::{
         AikitEvalRunner, CaseResult, CaseRunOptions, CaseRunOutput, CaseStatus, CaseTrialsResult,
-        EvalCase, TrialResult,
+        EvalCase, Isolation, TrialResult,
     };
     use aikit_textgrad::training::state::{init_run_dir, write_runtime_state, RuntimeState};
     use aikit_textgrad::training::{SlowUpdateMode, StepRecord};
@@ -185,6 +185,7 @@ mod tests {
                 stderr: vec![],
                 exit_code: Some(0),
                 timed_out: matches!(outcome, ScriptedOutcome::TimedOut),
+                patch: None,
             };
             let result = CaseResult {
                 id: case.id.clone(),
@@ -283,6 +284,7 @@ mod tests {
             optimizer_agent: "windsurf".to_string(),
             optimizer_model: None,
             timeout_seconds: 30,
+            isolation: Default::default(),
             parallel: Some(1),
             artifact_stem: "skill".to_string(),
         }
@@ -548,6 +550,7 @@ mod tests {
             project_root: std::path::PathBuf::from("/tmp"),
             timeout_seconds: 1,
             pass_threshold: 0.5,
+            isolation: Isolation::Shared,
         };
 
         let result = runner.run_case_trials(&case, &opts, &[], 3, None).await;
@@ -1,6 +1,6 @@
 //! Run configuration, prompt containers, and error types for the training loop.
 
-use aikit_evals::GateMetric;
+use aikit_evals::{GateMetric, Isolation};
 use serde::{Deserialize, Serialize};
 
 /// Complete configuration for a training run.
@@ -38,6 +38,11 @@ pub struct RunConfig {
     pub optimizer_model: Option<String>,
     /// Timeout in seconds per rollout case execution.
     pub timeout_seconds: u64,
+    /// Where every case the loop runs (rollouts, gates, test scoring)
+    /// executes. Defaults to shared for state files written before it
+    /// existed.
+    #[serde(default)]
+    pub isolation: Isolation,
     /// Maximum concurrent rollout executions (default: number of CPUs).
     pub parallel: Option<u32>,
     /// Stem for artifact filenames: `best_{stem}.md`, `{stem}s/{stem}_vNNNN.md`.
@@ -152,6 +152,7 @@ pub(super) async fn run_slow_update(
                         project_root: gate_ws.path().to_path_buf(),
                         timeout_seconds: config.timeout_seconds,
                         pass_threshold: config.pass_threshold,
+                        isolation: config.isolation,
                     };
                     let results = score_cases(
                         runner,
@@ -104,6 +104,7 @@ async fn compute_initial_score(
         project_root: ws.path().to_path_buf(),
         timeout_seconds: config.timeout_seconds,
         pass_threshold: config.pass_threshold,
+        isolation: config.isolation,
     };
     let results = score_cases(
         runner,
@@ -389,6 +390,7 @@ async fn compute_final_score(
         project_root: ws.path().to_path_buf(),
         timeout_seconds: config.timeout_seconds,
         pass_threshold: config.pass_threshold,
+        isolation: config.isolation,
     };
     let results = score_cases(
         runner,
@@ -454,6 +456,7 @@ mod tests {
                 stderr: vec![],
                 exit_code: Some(0),
                 timed_out: false,
+                patch: None,
             };
             let result = CaseResult {
                 id: case.id.clone(),
@@ -527,6 +530,7 @@ mod tests {
             optimizer_agent: "stub-optimizer".to_string(),
             optimizer_model: None,
             timeout_seconds: 30,
+            isolation: Default::default(),
             parallel: Some(1),
             artifact_stem: "artifact".to_string(),
         }
@@ -182,6 +182,7 @@ mod tests {
             optimizer_agent: "stub-opt".to_string(),
             optimizer_model: None,
             timeout_seconds: 30,
+            isolation: Default::default(),
             parallel: Some(1),
             artifact_stem: "artifact".to_string(),
         }
@@ -245,6 +246,24 @@ mod tests {
         assert_eq!(restored.optimizer_strategy, "custom strategy");
     }
 
+    // Resuming restores isolation; state files written before it existed run shared.
+    #[tokio::test]
+    async fn test_isolation_survives_checkpoint_and_defaults_to_shared() {
+        let dir = TempDir::new().unwrap();
+        let mut config = make_config();
+        config.isolation = aikit_evals::Isolation::Worktree;
+        write_runtime_state(dir.path(), &make_state(config))
+            .await
+            .unwrap();
+        let restored = read_runtime_state(dir.path()).await.unwrap();
+        assert_eq!(restored.config.isolation, aikit_evals::Isolation::Worktree);
+
+        let mut legacy = serde_json::to_value(make_config()).unwrap();
+        legacy.as_object_mut().unwrap().remove("isolation");
+        let legacy: RunConfig = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.isolation, aikit_evals::Isolation::Shared);
+    }
+
     #[test]
     fn test_sha256_hex_deterministic() {
         let h1 = sha256_hex("hello");
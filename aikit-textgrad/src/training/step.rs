@@ -223,6 +223,7 @@ pub(super) async fn run_step(
             project_root: ws.path().to_path_buf(),
             timeout_seconds: config.timeout_seconds,
             pass_threshold: config.pass_threshold,
+            isolation: config.isolation,
         };
         let (output, _case_result, trace_jsonl) = runner.run_case(case, &opts, &[]).await;
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -268,6 +269,7 @@ pub(super) async fn run_step(
         project_root: run_dir.to_path_buf(),
         timeout_seconds: config.timeout_seconds,
         pass_threshold: config.pass_threshold,
+        isolation: config.isolation,
     };
 
     // Temporarily set candidate text to materialize into the gate workspace.
@@ -0,0 +1,689 @@
+// Code generated from `aikit serve`'s OpenAPI document (GET /api/v1/openapi.json).
+// DO NOT EDIT. Regenerate with: AIKIT_UPDATE_CLIENT=1 cargo test openapi
+
+export interface AgentEvent {
+  agent_key: string;
+  payload: AgentEventPayload;
+  seq: number;
+  stream: AgentEventStream;
+}
+
+export type AgentEventPayload =
+  | { json_line: unknown }
+  | { raw_line: string }
+  | { raw_bytes: Array<number> }
+  | { stream_message: StreamMessage }
+  | { tool_use: { call_id: string; input: unknown; tool_name: string } }
+  | { tool_result: { call_id: string; is_error: boolean; output: unknown } }
+  | { token_usage_line: { raw_agent_line_seq: number; source: UsageSource; usage: TokenUsage } }
+  | { quota_exceeded: { info: QuotaExceededInfo; raw_agent_line_seq: number } }
+  | { raw_transport_line: { raw: string; seq: number; stream: AgentEventStream } }
+  | { aikit_text_delta: { content: string; turn_id: string | null } }
+  | { aikit_text_final: { content: string; turn_id: string | null } }
+  | { aikit_tool_use: { call_id: string; tool_input: unknown; tool_name: string } }
+  | { aikit_tool_result: { call_id: string; is_error: boolean; output: string } }
+  | { aikit_subagent_spawn: { subagent_id: string; workdir: string } }
+  | { aikit_subagent_event: { event: AgentEvent; subagent_id: string } }
+  | { aikit_subagent_result: { changed_files: Array<string>; final_message: string; key_findings: string; patch?: string | null; status: string; subagent_id: string } }
+  | { aikit_context_compressed: { compressed_tokens: number; original_tokens: number; summary?: string; turns_summarized: number } }
+  | { aikit_step_finish: { finish_reason: string; iteration: number } }
+  | { result: { session_id: string | null; structured: unknown; text: string } }
+  | { session_started: { session_id: string } };
+
+export type AgentEventStream = "stdout" | "stderr";
+
+export interface AgentInfo {
+  auth: AuthStatus;
+  available: boolean;
+  capabilities?: BackendCapabilities;
+  key: string;
+  name: string;
+}
+
+export type AuthStatus = "ok" | "unauthenticated" | "unknown";
+
+export interface BackendCapabilities {
+  bidirectional: boolean;
+  context_compression: boolean;
+  file_changes: boolean;
+  history_mutations: boolean;
+  history_store: boolean;
+  hooks: boolean;
+  interruptible: boolean;
+  mcp_routing: boolean;
+  passive_capture: boolean;
+  reasoning: boolean;
+  resumable_sessions: boolean;
+  server_tools: boolean;
+  structured_tools: boolean;
+  subagents: boolean;
+  supports_tool_policy: boolean;
+}
+
+export interface CacheInvalidation {
+  cause: InvalidationCause;
+  idle_ms: number;
+  lost_tokens: number;
+  observed_at_ms: number;
+  rewrite_tokens: number;
+  source_event_id: string;
+  tools_changed?: Array<string>;
+  wasted_usd: number;
+}
+
+export interface CacheReport {
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  causes: Array<CauseSummary>;
+  collapsed_sessions: number;
+  generated_at_ms: number;
+  hit_ratio: number | null;
+  sessions: Array<SessionCacheStats>;
+  turns: number;
+  wasted_write_usd: number;
+  write_usd: number;
+}
+
+export interface CaptureSearchHit {
+  event: CapturedAction;
+  score: number;
+  snippet: string;
+}
+
+/** One normalized action from `aikit-session-capture`'s event store. */
+export type CapturedAction = Record<string, unknown>;
+
+export interface CapturedSession {
+  action_count: number;
+  backend: string;
+  first_event_at_ms: number;
+  git_root: string | null;
+  last_event_at_ms: number;
+  session_id: string;
+  source_file: string;
+  tool_kinds: Array<string>;
+}
+
+export interface CauseSummary {
+  cause: InvalidationCause;
+  count: number;
+  wasted_usd: number;
+}
+
+export type CostGroupBy = "session" | "day" | "model" | "project";
+
+export interface CostReport {
+  generated_at_ms: number;
+  group_by: CostGroupBy;
+  rows: Array<CostReportRow>;
+  total_usd: number;
+  unpriced_models: Array<string>;
+}
+
+export interface CostReportRow {
+  key: string;
+  sessions: number;
+  snapshot: CostSnapshot;
+  turns: number;
+}
+
+export interface CostSnapshot {
+  backend: string;
+  captured_at_ms: number;
+  credits: CreditBalance | null;
+  per_model: Array<ModelSpend>;
+  provenance: "proxy" | "provider" | "client_computed";
+  spend: Spend | null;
+  windows: Array<CostWindow>;
+}
+
+export interface CostWindow {
+  amount_usd: number;
+  end_ms: number;
+  label: string;
+  start_ms: number;
+}
+
+export interface CreateLiveSessionRequest {
+  agent: string;
+  approval_policy?: string | null;
+  fork_session?: boolean;
+  mcp_servers?: Record<string, Record<string, unknown>>;
+  model?: string | null;
+  prompt: string;
+  resume?: string | null;
+  sandbox?: string | null;
+}
+
+export interface CreditBalance {
+  currency: string;
+  remaining_usd: number;
+}
+
+export interface DeleteLiveSessionResponse {
+  session_id: string;
+  status: LiveSessionStatus;
+}
+
+export interface DeleteSessionResponse {
+  session_id: string;
+  status: RunStatus;
+}
+
+export interface DetectedAdapter {
+  backend: string;
+  detected: boolean;
+  last_parse_at_ms: number | null;
+  tool_kind: string;
+  watch_paths: Array<string>;
+}
+
+export interface DetectedAdapters {
+  adapters: Array<DetectedAdapter>;
+}
+
+export interface ErrorDetail {
+  code: string;
+  message: string;
+}
+
+export interface ErrorResponse {
+  error: ErrorDetail;
+}
+
+export type HistoryBlock =
+  | { text: { text: string } }
+  | { thinking: { text: string } }
+  | { tool_use: { id: string; input: unknown; name: string } }
+  | { tool_result: { content: unknown; is_error: boolean; tool_use_id: string } }
+  | { server_tool_use: { id: string; input: unknown; name: string } }
+  | { server_tool_result: { content: unknown; is_error: boolean; tool_use_id: string } }
+  | { raw: unknown };
+
+export type HistoryContent =
+  | { text: string }
+  | { blocks: Array<HistoryBlock> }
+  | { raw: unknown };
+
+export interface HistoryErrorResponse {
+  error: string;
+}
+
+export interface HistoryMessage {
+  content: HistoryContent;
+  parent_tool_use_id: string | null;
+  role: MessageRole;
+  session_id: string;
+  uuid: string;
+}
+
+/** `tag: null` clears the tag; an absent key leaves it untouched. */
+export interface HistoryPatchRequest {
+  rename?: string | null;
+  tag?: string | null;
+}
+
+export interface HistorySession {
+  backend: string;
+  created_at_ms: number | null;
+  custom_title: string | null;
+  cwd: string | null;
+  first_prompt: string | null;
+  git_branch: string | null;
+  last_modified_ms: number;
+  message_count: number | null;
+  session_id: string;
+  size_bytes: number | null;
+  summary: string;
+  tag: string | null;
+}
+
+export type InvalidationCause = "tools_changed" | "system_prompt_changed" | "model_changed" | "idle_expired" | "unknown";
+
+export interface ListAgentsResponse {
+  agents: Array<AgentInfo>;
+}
+
+export interface ListLiveSessionsResponse {
+  sessions: Array<LiveSessionSummary>;
+}
+
+export interface ListRunsResponse {
+  sessions: Array<RunSummary>;
+}
+
+export type LiveSessionAction = "interrupt" | "disconnect" | "set_model" | "send_turn" | "get_context_usage";
+
+export interface LiveSessionControlAck {
+  action: LiveSessionAction;
+  ok: true;
+}
+
+export interface LiveSessionControlRequest {
+  action: LiveSessionAction;
+  model?: string | null;
+  text?: string | null;
+}
+
+export type LiveSessionControlResponse =
+  | LiveSessionControlAck
+  | Record<string, unknown>;
+
+export type LiveSessionStatus = "active" | "closed";
+
+export interface LiveSessionSummary {
+  agent: string;
+  created_at: string;
+  session_id: string;
+  status: LiveSessionStatus;
+}
+
+export type MessageKind = "message" | "reasoning" | "tool_output" | "status";
+
+export type MessagePhase = "delta" | "final";
+
+export type MessageRole = "assistant" | "tool" | "system" | "user";
+
+export interface ModelSpend {
+  amount_usd: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  input_tokens: number;
+  model: string;
+  output_tokens: number;
+  reasoning_tokens: number;
+}
+
+export type QuotaCategory = "hourly" | "daily" | "weekly" | "requests" | "tokens" | "unknown";
+
+export interface QuotaExceededInfo {
+  agent_key: string;
+  category: QuotaCategory;
+  raw_message: string;
+}
+
+export type RunStatus = "running" | "idle" | "closed";
+
+export interface RunSummary {
+  agent: string;
+  last_active_at: string;
+  session_id: string;
+  started_at: string;
+  status: RunStatus;
+}
+
+export interface ScanAccepted {
+  job_id: string;
+}
+
+export interface ScanJobSnapshot {
+  completed_at_ms: number | null;
+  deduplicated_count: number;
+  error: string | null;
+  events_upserted: number;
+  files_scanned: number;
+  files_skipped: number;
+  job_id: string;
+  started_at_ms: number;
+  state: ScanJobState;
+  warnings: Array<Record<string, unknown>>;
+}
+
+export type ScanJobState = "queued" | "running" | "complete" | "failed";
+
+export interface ScanRequest {
+  force?: boolean;
+}
+
+export interface SendMessageRequest {
+  agent: string;
+  content: string;
+  continue_from?: string | null;
+  disallowed_tools?: Array<string> | null;
+  model?: string | null;
+  session_id?: string | null;
+  tools?: Array<string> | null;
+  yolo?: boolean;
+}
+
+/** One SSE frame: `event` is the SSE event name, `data` its decoded JSON. */
+export type ServeStreamEvent =
+  | { data: unknown; event: "json_line" }
+  | { data: string; event: "raw_line" }
+  | { data: Array<number>; event: "raw_bytes" }
+  | { data: StreamMessage; event: "stream_message" }
+  | { data: { call_id: string; input: unknown; tool_name: string }; event: "tool_use" }
+  | { data: { call_id: string; is_error: boolean; output: unknown }; event: "tool_result" }
+  | { data: { raw_agent_line_seq: number; source: UsageSource; usage: TokenUsage }; event: "token_usage_line" }
+  | { data: { info: QuotaExceededInfo; raw_agent_line_seq: number }; event: "quota_exceeded" }
+  | { data: { raw: string; seq: number; stream: AgentEventStream }; event: "raw_transport_line" }
+  | { data: { content: string; turn_id: string | null }; event: "aikit_text_delta" }
+  | { data: { content: string; turn_id: string | null }; event: "aikit_text_final" }
+  | { data: { call_id: string; tool_input: unknown; tool_name: string }; event: "aikit_tool_use" }
+  | { data: { call_id: string; is_error: boolean; output: string }; event: "aikit_tool_result" }
+  | { data: { subagent_id: string; workdir: string }; event: "aikit_subagent_spawn" }
+  | { data: { event: AgentEvent; subagent_id: string }; event: "aikit_subagent_event" }
+  | { data: { changed_files: Array<string>; final_message: string; key_findings: string; patch?: string | null; status: string; subagent_id: string }; event: "aikit_subagent_result" }
+  | { data: { compressed_tokens: number; original_tokens: number; summary?: string; turns_summarized: number }; event: "aikit_context_compressed" }
+  | { data: { finish_reason: string; iteration: number }; event: "aikit_step_finish" }
+  | { data: { session_id: string | null; structured: unknown; text: string }; event: "result" }
+  | { data: { session_id: string }; event: "session_started" }
+  | { data: ErrorDetail; event: "error" }
+  | { data: { exit_code: number }; event: "done" };
+
+export interface SessionCacheStats {
+  backend: string;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  collapsed: boolean;
+  early_hit_ratio: number | null;
+  first_observed_at_ms: number;
+  hit_ratio: number | null;
+  invalidations: Array<CacheInvalidation>;
+  last_observed_at_ms: number;
+  late_hit_ratio: number | null;
+  model: string | null;
+  project: string | null;
+  session_id: string;
+  turns: number;
+  wasted_write_usd: number;
+  write_usd: number;
+}
+
+export interface Spend {
+  amount_usd: number;
+  is_estimate: boolean;
+  scope: "session" | "window" | "daily";
+}
+
+export interface StreamMessage {
+  kind: MessageKind;
+  phase: MessagePhase;
+  raw_line_seq: number;
+  role: MessageRole;
+  source: AgentEventStream;
+  text: string;
+  turn_id: string | null;
+}
+
+export interface SyncMessageResponse {
+  content: string;
+  error?: ErrorDetail;
+  exit_code: number;
+  session_id: string | null;
+  usage?: UsageSummary;
+}
+
+export interface TokenUsage {
+  cache_creation_tokens: number | null;
+  cache_read_tokens: number | null;
+  input_tokens: number;
+  output_tokens: number;
+  reasoning_tokens: number | null;
+  total_tokens: number | null;
+}
+
+export type UsageSource = "Codex" | "Claude" | "Gemini" | "OpenCode" | "Cursor" | "Pi" | "Aikit";
+
+export interface UsageSummary {
+  cache_read_tokens?: number;
+  input_tokens: number;
+  output_tokens: number;
+}
+
+/** A non-2xx response. `body` is the decoded JSON error body when there is one. */
+export class AikitServeError extends Error {
+  readonly status: number;
+  readonly body: unknown;
+
+  constructor(status: number, body: unknown) {
+    super(`aikit serve responded ${status}${errorCode(body) ? `: ${errorCode(body)}` : ""}`);
+    this.name = "AikitServeError";
+    this.status = status;
+    this.body = body;
+  }
+
+  /** Machine-readable code: `error.code`, or `error` on the history routes. */
+  get code(): string | undefined {
+    return errorCode(this.body);
+  }
+}
+
+function errorCode(body: unknown): string | undefined {
+  if (body === null || typeof body !== "object" || !("error" in body)) return undefined;
+  const error = (body as { error: unknown }).error;
+  if (typeof error === "string") return error;
+  if (error !== null && typeof error === "object" && typeof (error as { code?: unknown }).code === "string") {
+    return (error as { code: string }).code;
+  }
+  return undefined;
+}
+
+export interface AikitServeClientOptions {
+  /** Server origin, e.g. `http://127.0.0.1:8080`; `/api/v1` is appended. */
+  baseUrl: string;
+  /** Sent as `Authorization: Bearer <apiKey>` (servers started with `--api-key`). */
+  apiKey?: string;
+  fetch?: typeof fetch;
+}
+
+/** A streaming response: the `X-Session-Id` header and the decoded events. */
+export interface ServeEventStream {
+  sessionId: string | null;
+  events: AsyncGenerator<ServeStreamEvent>;
+}
+
+type QueryValue = string | number | boolean | null | undefined;
+
+interface RequestOptions {
+  body?: unknown;
+  query?: { [key: string]: QueryValue };
+  accept?: string;
+}
+
+export class AikitServeClient {
+  private readonly baseUrl: string;
+  private readonly apiKey?: string;
+  private readonly fetchImpl: typeof fetch;
+
+  constructor(options: AikitServeClientOptions) {
+    this.baseUrl = options.baseUrl.replace(/\/+$/, "") + "/api/v1";
+    this.apiKey = options.apiKey;
+    this.fetchImpl = options.fetch ?? globalThis.fetch.bind(globalThis);
+  }
+
+  /** Runnable agents with auth status and capabilities. */
+  async listAgents(): Promise<ListAgentsResponse> {
+    return this.json("GET", `/agents`);
+  }
+
+  /** Compiled-in capture adapters and whether their stores exist. */
+  async listCaptureAdapters(): Promise<DetectedAdapters> {
+    return this.json("GET", `/capture`);
+  }
+
+  /** Prompt-cache hit ratios, wasted cache-write spend and invalidation causes per session. `format=csv` returns `text/csv`. */
+  async getCaptureCache(query: { backend?: string; since_ms?: number; until_ms?: number; collapse_min_turns?: number; collapse_drop?: number; format?: "json" | "csv" } = {}): Promise<CacheReport> {
+    return this.json("GET", `/capture/cache`, { query });
+  }
+
+  /** Estimated spend from captured token usage, grouped by session, day, model or project. `format=csv` returns `text/csv`. */
+  async getCaptureCost(query: { by?: CostGroupBy; backend?: string; since_ms?: number; until_ms?: number; format?: "json" | "csv" } = {}): Promise<CostReport> {
+    return this.json("GET", `/capture/cost`, { query });
+  }
+
+  /** Start an asynchronous scan of every adapter's store. */
+  async startCaptureScan(body?: ScanRequest): Promise<ScanAccepted> {
+    return this.json("POST", `/capture/scan`, { body });
+  }
+
+  /** Progress of a scan job. */
+  async getCaptureScan(jobId: string): Promise<ScanJobSnapshot> {
+    return this.json("GET", `/capture/scan/${encodeURIComponent(jobId)}`);
+  }
+
+  /** Full-text search over captured actions, best match first. */
+  async searchCapturedActions(query: { q?: string; backend?: string; kind?: string; session_id?: string; since_ms?: number; until_ms?: number; git_root?: string; limit?: number; offset?: number } = {}): Promise<Array<CaptureSearchHit>> {
+    return this.json("GET", `/capture/search`, { query });
+  }
+
+  /** One capture adapter. */
+  async getCaptureAdapter(backend: string): Promise<DetectedAdapter> {
+    return this.json("GET", `/capture/${encodeURIComponent(backend)}`);
+  }
+
+  /** Sessions parsed into the capture store. */
+  async listCapturedSessions(backend: string, query: { cwd?: string; limit?: number; offset?: number } = {}): Promise<Array<CapturedSession>> {
+    return this.json("GET", `/capture/${encodeURIComponent(backend)}/sessions`, { query });
+  }
+
+  /** A captured session's normalized actions. */
+  async listCapturedActions(backend: string, sessionId: string, query: { limit?: number; offset?: number } = {}): Promise<Array<CapturedAction>> {
+    return this.json("GET", `/capture/${encodeURIComponent(backend)}/sessions/${encodeURIComponent(sessionId)}/actions`, { query });
+  }
+
+  /** Sessions in a Backend's transcript store, newest first. */
+  async listHistory(backend: string, query: { cwd?: string; tag?: string; limit?: number; offset?: number } = {}): Promise<Array<HistorySession>> {
+    return this.json("GET", `/history/${encodeURIComponent(backend)}`, { query });
+  }
+
+  /** One session's metadata. */
+  async getHistorySession(backend: string, sessionId: string, query: { cwd?: string } = {}): Promise<HistorySession> {
+    return this.json("GET", `/history/${encodeURIComponent(backend)}/${encodeURIComponent(sessionId)}`, { query });
+  }
+
+  /** Rename and/or tag a session. */
+  async patchHistorySession(backend: string, sessionId: string, body: HistoryPatchRequest, query: { cwd?: string } = {}): Promise<void> {
+    return this.empty("PATCH", `/history/${encodeURIComponent(backend)}/${encodeURIComponent(sessionId)}`, { body, query });
+  }
+
+  /** A session's transcript messages, in order. */
+  async listHistoryMessages(backend: string, sessionId: string, query: { cwd?: string; limit?: number; offset?: number } = {}): Promise<Array<HistoryMessage>> {
+    return this.json("GET", `/history/${encodeURIComponent(backend)}/${encodeURIComponent(sessionId)}/messages`, { query });
+  }
+
+  /** Open and recently closed live sessions. */
+  async listLiveSessions(): Promise<ListLiveSessionsResponse> {
+    return this.json("GET", `/live-sessions`);
+  }
+
+  /** Open a bidirectional session and stream its events. */
+  async createLiveSession(body: CreateLiveSessionRequest): Promise<ServeEventStream> {
+    return this.stream("POST", `/live-sessions`, { body });
+  }
+
+  /** Disconnect a live session. */
+  async deleteLiveSession(sessionId: string): Promise<DeleteLiveSessionResponse> {
+    return this.json("DELETE", `/live-sessions/${encodeURIComponent(sessionId)}`);
+  }
+
+  /** Interrupt, switch model, send a turn, read context usage or disconnect. */
+  async controlLiveSession(sessionId: string, body: LiveSessionControlRequest): Promise<LiveSessionControlResponse> {
+    return this.json("POST", `/live-sessions/${encodeURIComponent(sessionId)}/control`, { body });
+  }
+
+  /** Run one turn. `Accept: text/event-stream` (default) streams events; `application/json` returns the accumulated reply. */
+  async sendMessage(body: SendMessageRequest): Promise<SyncMessageResponse> {
+    return this.json("POST", `/messages`, { body });
+  }
+
+  /** Run one turn. `Accept: text/event-stream` (default) streams events; `application/json` returns the accumulated reply. */
+  async sendMessageStream(body: SendMessageRequest): Promise<ServeEventStream> {
+    return this.stream("POST", `/messages`, { body });
+  }
+
+  /** This document. */
+  async getOpenApi(): Promise<Record<string, unknown>> {
+    return this.json("GET", `/openapi.json`);
+  }
+
+  /** One-shot run sessions known to this server. */
+  async listSessions(): Promise<ListRunsResponse> {
+    return this.json("GET", `/sessions`);
+  }
+
+  /** One run session. */
+  async getSession(sessionId: string): Promise<RunSummary> {
+    return this.json("GET", `/sessions/${encodeURIComponent(sessionId)}`);
+  }
+
+  /** Cancel any in-flight run and forget the session. */
+  async deleteSession(sessionId: string): Promise<DeleteSessionResponse> {
+    return this.json("DELETE", `/sessions/${encodeURIComponent(sessionId)}`);
+  }
+
+  private async send(method: string, path: string, options: RequestOptions): Promise<Response> {
+    const url = new URL(this.baseUrl + path);
+    for (const [key, value] of Object.entries(options.query ?? {})) {
+      if (value !== undefined && value !== null) url.searchParams.set(key, String(value));
+    }
+    const headers: Record<string, string> = { Accept: options.accept ?? "application/json" };
+    if (this.apiKey) headers.Authorization = `Bearer ${this.apiKey}`;
+    let body: string | undefined;
+    if (options.body !== undefined) {
+      headers["Content-Type"] = "application/json";
+      body = JSON.stringify(options.body);
+    }
+    const response = await this.fetchImpl(url, { method, headers, body });
+    if (!response.ok) {
+      const text = await response.text();
+      let parsed: unknown = text;
+      try {
+        parsed = JSON.parse(text);
+      } catch {
+        // Not JSON (e.g. an extractor rejection); keep the text.
+      }
+      throw new AikitServeError(response.status, parsed);
+    }
+    return response;
+  }
+
+  private async json<T>(method: string, path: string, options: RequestOptions = {}): Promise<T> {
+    const response = await this.send(method, path, options);
+    return (await response.json()) as T;
+  }
+
+  private async empty(method: string, path: string, options: RequestOptions = {}): Promise<void> {
+    await this.send(method, path, options);
+  }
+
+  private async stream(method: string, path: string, options: RequestOptions = {}): Promise<ServeEventStream> {
+    const response = await this.send(method, path, { ...options, accept: "text/event-stream" });
+    if (response.body === null) throw new AikitServeError(response.status, "missing event stream body");
+    return { sessionId: response.headers.get("X-Session-Id"), events: parseServeEvents(response.body) };
+  }
+}
+
+/** Decode an `aikit serve` SSE body into typed events. Keep-alive comments are skipped. */
+export async function* parseServeEvents(body: ReadableStream<Uint8Array>): AsyncGenerator<ServeStreamEvent> {
+  const reader = body.pipeThrough(new TextDecoderStream()).getReader();
+  let buffer = "";
+  let event = "message";
+  let data: string[] = [];
+  try {
+    for (;;) {
+      const chunk = await reader.read();
+      if (chunk.done) break;
+      buffer += chunk.value;
+      let newline: number;
+      while ((newline = buffer.indexOf("\n")) >= 0) {
+        const line = buffer.slice(0, newline).replace(/\r$/, "");
+        buffer = buffer.slice(newline + 1);
+        if (line === "") {
+          if (data.length > 0) yield { event, data: JSON.parse(data.join("\n")) } as ServeStreamEvent;
+          event = "message";
+          data = [];
+        } else if (!line.startsWith(":")) {
+          const colon = line.indexOf(":");
+          const field = colon < 0 ? line : line.slice(0, colon);
+          let value = colon < 0 ? "" : line.slice(colon + 1);
+          if (value.startsWith(" ")) value = value.slice(1);
+          if (field === "event") event = value;
+          else if (field === "data") data.push(value);
+        }
+      }
+    }
+  } finally {
+    reader.releaseLock();
+  }
+}
@@ -119,6 +119,7 @@ fn print_json(records: &[&DefinitionRecord]) {
                 "description": record.definition.description,
                 "source": record.source.to_string(),
                 "model": record.definition.model,
+                "agent": record.definition.agent,
                 "tools": record.definition.tools,
                 "disallowedTools": record.definition.disallowed_tools,
                 "agents": delegation_to_json(&record.definition.delegation),
@@ -0,0 +1,239 @@
+//! `aikit cost` — spend estimates from captured token events.
+//!
+//! Reads the capture store `aikit serve` fills (or any store given with
+//! `--db`, e.g. one owner's store on a team host), prices every usage
+//! envelope with the pricing catalog, and prints one row per session, day,
+//! model or project as a table, CSV or JSON. With `--cache` it reports
+//! prompt-cache efficiency per session instead.
+
+use std::path::PathBuf;
+
+use aikit_sdk::cost::PricingTable;
+#[cfg(feature = "agent-adapters")]
+use aikit_sdk::cost::{
+    analyze_cache, build_report, CacheAnalysisQuery, CacheReport, CostGroupBy, CostReport,
+    CostReportQuery,
+};
+#[cfg(feature = "agent-adapters")]
+use aikit_session_capture::{AggregateWindow, ToolKind};
+
+#[derive(Debug)]
+pub struct CostArgs {
+    /// `session`, `day`, `model` or `project`.
+    pub by: String,
+    /// Lower bound: `YYYY-MM-DD` (UTC) or epoch ms.
+    pub since: Option<String>,
+    /// Upper bound, inclusive: `YYYY-MM-DD` (the whole UTC day) or epoch ms.
+    pub until: Option<String>,
+    pub tool: Option<String>,
+    /// Pricing overrides merged over the built-in catalog; see
+    /// [`load_pricing`].
+    pub pricing: Option<PathBuf>,
+    /// Capture store to read instead of the `aikit serve` default.
+    pub db: Option<PathBuf>,
+    /// `table`, `csv` or `json`.
+    pub format: String,
+    /// Cache hit ratios and invalidations instead of spend; `by` is ignored.
+    pub cache: bool,
+}
+
+/// The pricing catalog every command prices with: the built-in catalog with
+/// `overrides` (`--pricing`), else `AIKIT_PRICING`, else
+/// `~/.aikit/pricing.toml` merged over it. `aikit cost`, `aikit serve` and
+/// the `aikit run --progress` meter all load it here so they agree.
+pub fn load_pricing(overrides: Option<PathBuf>) -> anyhow::Result<PricingTable> {
+    let path = overrides.or_else(|| std::env::var_os("AIKIT_PRICING").map(PathBuf::from));
+    Ok(PricingTable::load(path.as_deref())?)
+}
+
+#[cfg(feature = "agent-adapters")]
+pub async fn execute(args: CostArgs) -> anyhow::Result<()> {
+    let group_by: CostGroupBy = args.by.parse().map_err(anyhow::Error::msg)?;
+    if !matches!(args.format.as_str(), "table" | "csv" | "json") {
+        anyhow::bail!(
+            "--format must be table, csv or json (got '{}')",
+            args.format
+        );
+    }
+    let window = AggregateWindow {
+        since_ms: args
+            .since
+            .as_deref()
+            .map(|v| parse_bound(v, "--since", false))
+            .transpose()?,
+        until_ms: args
+            .until
+            .as_deref()
+            .map(|v| parse_bound(v, "--until", true))
+            .transpose()?,
+    };
+    let tool = match args.tool.as_deref() {
+        None => None,
+        Some("claude_code" | "claudecode" | "claude") => Some(ToolKind::ClaudeCode),
+        Some("codex") => Some(ToolKind::Codex),
+        Some("open_code" | "opencode") => Some(ToolKind::OpenCode),
+        Some(other) => anyhow::bail!("unknown --tool '{other}'"),
+    };
+    let pricing = load_pricing(args.pricing)?;
+
+    let db = args.db.unwrap_or_else(super::serve::capture_db_path);
+    if !db.is_file() {
+        anyhow::bail!(
+            "no capture store at {} (run `aikit serve` to capture sessions)",
+            db.display()
+        );
+    }
+    let store =
+        super::serve::storage::SqliteEventStore::new(super::serve::storage::schema::open(&db)?);
+    if args.cache {
+        let query = CacheAnalysisQuery {
+            window,
+            tool,
+            ..CacheAnalysisQuery::default()
+        };
+        let report = analyze_cache(&store, &query, &pricing).await?;
+        match args.format.as_str() {
+            "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+            "csv" => print!("{}", report.to_csv()),
+            _ => print_cache_table(&report),
+        }
+        return Ok(());
+    }
+    let query = CostReportQuery {
+        group_by,
+        window,
+        tool,
+    };
+    let report = build_report(&store, &query, &pricing).await?;
+
+    match args.format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "csv" => print!("{}", report.to_csv()),
+        _ => print_table(&report),
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "agent-adapters"))]
+pub async fn execute(_args: CostArgs) -> anyhow::Result<()> {
+    anyhow::bail!("aikit cost requires the agent-adapters feature")
+}
+
+/// Epoch ms, or a UTC date — its first millisecond, or its last when `end`.
+#[cfg(feature = "agent-adapters")]
+fn parse_bound(value: &str, flag: &str, end: bool) -> anyhow::Result<i64> {
+    if let Ok(ms) = value.parse::<i64>() {
+        return Ok(ms);
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("{flag} must be YYYY-MM-DD or epoch milliseconds"))?;
+    let start = date
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc()
+        .timestamp_millis();
+    Ok(if end { start + 86_400_000 - 1 } else { start })
+}
+
+#[cfg(feature = "agent-adapters")]
+fn print_table(report: &CostReport) {
+    let key_header = match report.group_by {
+        CostGroupBy::Session => "SESSION",
+        CostGroupBy::Day => "DAY",
+        CostGroupBy::Model => "MODEL",
+        CostGroupBy::Project => "PROJECT",
+    };
+    let key_w = report
+        .rows
+        .iter()
+        .map(|r| r.key.len())
+        .max()
+        .unwrap_or(0)
+        .max(key_header.len());
+    let backend_w = report
+        .rows
+        .iter()
+        .map(|r| r.snapshot.backend.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    println!(
+        "{:<key_w$} {:<backend_w$} {:>8} {:>6} {:>12} {:>12} {:>12} {:>10}",
+        key_header, "BACKEND", "SESSIONS", "TURNS", "INPUT", "OUTPUT", "CACHE READ", "USD",
+    );
+    for row in &report.rows {
+        let models = &row.snapshot.per_model;
+        let amount = row.snapshot.spend.as_ref().map_or(0.0, |s| s.amount_usd);
+        println!(
+            "{:<key_w$} {:<backend_w$} {:>8} {:>6} {:>12} {:>12} {:>12} {:>10.4}",
+            row.key,
+            row.snapshot.backend,
+            row.sessions,
+            row.turns,
+            models.iter().map(|m| m.input_tokens).sum::<u64>(),
+            models.iter().map(|m| m.output_tokens).sum::<u64>(),
+            models.iter().map(|m| m.cache_read_tokens).sum::<u64>(),
+            amount,
+        );
+    }
+    println!("total: ${:.4} (estimate)", report.total_usd);
+    if !report.unpriced_models.is_empty() {
+        eprintln!(
+            "warning: no catalog price for {}; priced at the default rate",
+            report.unpriced_models.join(", ")
+        );
+    }
+}
+
+#[cfg(feature = "agent-adapters")]
+fn print_cache_table(report: &CacheReport) {
+    let percent = |r: Option<f64>| r.map_or("-".to_string(), |r| format!("{:.1}%", r * 100.0));
+    let session_w = report
+        .sessions
+        .iter()
+        .map(|s| s.session_id.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    println!(
+        "{:<session_w$} {:<8} {:>6} {:>7} {:>7} {:>7} {:>6} {:<21} {:>10}",
+        "SESSION", "BACKEND", "TURNS", "HIT", "EARLY", "LATE", "INVAL", "TOP CAUSE", "WASTED USD",
+    );
+    for s in &report.sessions {
+        println!(
+            "{:<session_w$} {:<8} {:>6} {:>7} {:>7} {:>7} {:>6} {:<21} {:>10.4}{}",
+            s.session_id,
+            s.backend,
+            s.turns,
+            percent(s.hit_ratio),
+            percent(s.early_hit_ratio),
+            percent(s.late_hit_ratio),
+            s.invalidations.len(),
+            s.top_cause().map_or("-", |c| c.as_str()),
+            s.wasted_write_usd,
+            if s.collapsed { "  collapsed" } else { "" },
+        );
+    }
+    println!(
+        "hit ratio: {}, cache writes: ${:.4}, wasted on rewrites: ${:.4} (estimate)",
+        percent(report.hit_ratio),
+        report.write_usd,
+        report.wasted_write_usd,
+    );
+    for cause in &report.causes {
+        println!(
+            "  {:<21} {:>5} invalidations  ${:.4}",
+            cause.cause.as_str(),
+            cause.count,
+            cause.wasted_usd
+        );
+    }
+    if report.collapsed_sessions > 0 {
+        println!(
+            "{} session(s) with a collapsed hit ratio",
+            report.collapsed_sessions
+        );
+    }
+}
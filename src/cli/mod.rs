@@ -3,6 +3,7 @@
 mod agents;
 mod check;
 pub mod context;
+mod cost;
 mod init;
 mod mcp;
 mod release;
@@ -134,6 +135,21 @@ pub fn build_app() -> Result<AikitApp> {
                 .api_key
                 .or_else(|| std::env::var("AIKIT_SERVE_API_KEY").ok()),
             insecure: args.insecure,
+            team: serve::TeamArgs {
+                tokens_file: args
+                    .team_tokens
+                    .or_else(|| std::env::var("AIKIT_TEAM_TOKENS").ok())
+                    .map(std::path::PathBuf::from),
+                dir: args
+                    .team_dir
+                    .or_else(|| std::env::var("AIKIT_TEAM_DIR").ok())
+                    .map(std::path::PathBuf::from),
+                forward_url: args
+                    .team_url
+                    .or_else(|| std::env::var("AIKIT_TEAM_URL").ok()),
+                forward_token: std::env::var("AIKIT_TEAM_TOKEN").ok(),
+                forward_full_payloads: args.team_full_payloads,
+            },
         };
         serve::execute(serve_args)
             .await
@@ -162,11 +178,14 @@ pub fn build_app() -> Result<AikitApp> {
             stream: args.stream,
             events: args.events,
             progress: args.progress,
+            show_reasoning: args.show_reasoning,
+            expand_tool_output: args.expand_tool_output,
             dry_run: args.dry_run,
             session_agents: args.session_agents,
             session_persona: args.session_persona,
             resume: args.resume,
             resume_last: args.resume_last,
+            continue_from: args.continue_from,
             sandbox: args.sandbox,
             auto_approve: args.auto_approve,
             cd: args.cd,
@@ -176,6 +195,9 @@ pub fn build_app() -> Result<AikitApp> {
             bare: args.bare,
             ephemeral: args.ephemeral,
             skip_git_repo_check: args.skip_git_repo_check,
+            trust_project_mcp: args.trust_project_mcp,
+            web_allow_domain: args.web_allow_domain,
+            web_deny_domain: args.web_deny_domain,
             capabilities: args.capabilities,
         };
         // run::execute is synchronous and creates its own tokio runtime internally
@@ -328,9 +350,6 @@ pub fn build_app() -> Result<AikitApp> {
             if args.agent.is_empty() {
                 return Err(anyhow::anyhow!("--agent is required (e.g. --agent claude)"));
             }
-            if args.prompt.is_empty() {
-                return Err(anyhow::anyhow!("--prompt is required for session new"));
-            }
             tokio::task::spawn_blocking(move || {
                 session::execute_new(session::NewSessionArgs {
                     agent: args.agent,
@@ -339,6 +358,8 @@ pub fn build_app() -> Result<AikitApp> {
                     approval_policy: args.approval_policy,
                     sandbox: args.sandbox,
                     events: args.events,
+                    resume: args.resume,
+                    no_tui: args.no_tui,
                 })
             })
             .await
@@ -363,6 +384,8 @@ pub fn build_app() -> Result<AikitApp> {
         path!["session", "sync"],
         |_ctx, args: SessionSyncArgs| async move {
             let code = session::execute_sync(session::SyncSessionsArgs {
+                sink_dir: args.sink_dir.map(std::path::PathBuf::from),
+                webdav_url: args.webdav_url,
                 bucket: args.bucket,
                 endpoint: args.endpoint,
                 region: args.region,
@@ -371,6 +394,42 @@ pub fn build_app() -> Result<AikitApp> {
                 tools: args.tool,
                 watch: args.watch,
                 dry_run: args.dry_run,
+                compact: args.compact,
+                key_file: args.key_file.map(std::path::PathBuf::from),
+                scrub_rules: args.scrub_rules.map(std::path::PathBuf::from),
+                scrub_audit: args.scrub_audit,
+                allow_http: args.allow_http,
+                format: args.format,
+                log_level: args.log_level,
+                log_format: args.log_format,
+            })
+            .await?;
+            if code == 0 {
+                Ok(())
+            } else {
+                std::process::exit(code);
+            }
+        },
+    )?;
+
+    builder = builder.register(
+        path!["session", "pull"],
+        |_ctx, args: SessionPullArgs| async move {
+            let code = session::execute_pull(session::PullSessionsArgs {
+                sink_dir: args.sink_dir.map(std::path::PathBuf::from),
+                webdav_url: args.webdav_url,
+                bucket: args.bucket,
+                endpoint: args.endpoint,
+                region: args.region,
+                owner: args.owner,
+                key_prefix: args.key_prefix,
+                tool: args.tool,
+                session: args.session,
+                out: args.out.map(std::path::PathBuf::from),
+                restore: args.restore,
+                overwrite: args.overwrite,
+                ingest: args.ingest,
+                key_file: args.key_file.map(std::path::PathBuf::from),
                 allow_http: args.allow_http,
                 format: args.format,
                 log_level: args.log_level,
@@ -385,6 +444,20 @@ pub fn build_app() -> Result<AikitApp> {
         },
     )?;
 
+    builder = builder.register(path!["cost"], |_ctx, args: CostArgs| async move {
+        cost::execute(cost::CostArgs {
+            by: args.by,
+            since: args.since,
+            until: args.until,
+            tool: args.tool,
+            pricing: args.pricing.map(std::path::PathBuf::from),
+            db: args.db.map(std::path::PathBuf::from),
+            format: args.format,
+            cache: args.cache,
+        })
+        .await
+    })?;
+
     #[cfg(all(feature = "agent-adapters", feature = "mcp-tools"))]
     {
         let conn = serve::storage::schema::open(&serve::capture_db_path())?;
@@ -747,6 +820,10 @@ struct ServeArgs {
     max_sessions: String,
     api_key: Option<String>,
     insecure: bool,
+    team_tokens: Option<String>,
+    team_dir: Option<String>,
+    team_url: Option<String>,
+    team_full_payloads: bool,
 }
 
 impl IntoCommandSpec for ServeArgs {
@@ -784,6 +861,29 @@ impl IntoCommandSpec for ServeArgs {
                      perimeter guards this server (see ADR 0012) — do not set this outside \
                      a disposable, network-isolated sandbox.",
                 ),
+                opt_spec(
+                    "team-tokens",
+                    "Team ingest mode: serve only the /api/v1/team routes, authenticating \
+                     uploads with the per-owner tokens in this TOML file. Also reads \
+                     AIKIT_TEAM_TOKENS.",
+                ),
+                opt_spec(
+                    "team-dir",
+                    "Ingest mode: directory for the per-owner stores (default: \
+                     <data dir>/aikit/team). Also reads AIKIT_TEAM_DIR.",
+                ),
+                opt_spec(
+                    "team-url",
+                    "Forward captured session events to the team host at this URL, \
+                     authenticating with the owner token in AIKIT_TEAM_TOKEN. Also reads \
+                     AIKIT_TEAM_URL.",
+                ),
+                flag_spec(
+                    "team-full-payloads",
+                    "With --team-url, also forward tool inputs, outputs and error messages \
+                     (scrubbed with the AIKIT_SCRUB_RULES rules). By default only targets, \
+                     kinds, timings and token counts leave the machine.",
+                ),
             ],
             ..CommandSpec::default()
         }
@@ -799,6 +899,10 @@ impl FromArgValueMap for ServeArgs {
             max_sessions: get_str_default(map, "max-sessions", "10"),
             api_key: get_opt_val(map, "api-key"),
             insecure: get_bool_val(map, "insecure"),
+            team_tokens: get_opt_val(map, "team-tokens"),
+            team_dir: get_opt_val(map, "team-dir"),
+            team_url: get_opt_val(map, "team-url"),
+            team_full_payloads: get_bool_val(map, "team-full-payloads"),
         }
     }
 }
@@ -813,11 +917,14 @@ struct RunArgs {
     stream: bool,
     events: bool,
     progress: bool,
+    show_reasoning: bool,
+    expand_tool_output: bool,
     dry_run: bool,
     session_agents: Option<String>,
     session_persona: Option<String>,
     resume: Option<String>,
     resume_last: bool,
+    continue_from: Option<String>,
     // spec 013 (slice 2): common invocation envelope
     sandbox: Option<String>,
     auto_approve: bool,
@@ -828,6 +935,9 @@ struct RunArgs {
     bare: bool,
     ephemeral: bool,
     skip_git_repo_check: bool,
+    trust_project_mcp: bool,
+    web_allow_domain: Vec<String>,
+    web_deny_domain: Vec<String>,
     capabilities: bool,
 }
 
@@ -874,6 +984,32 @@ impl IntoCommandSpec for RunArgs {
                     help: "Display live human-readable progress on stderr",
                     ..Default::default()
                 },
+                ArgSpec {
+                    name: "show-reasoning",
+                    short: None,
+                    long: Some("show-reasoning"),
+                    kind: ArgKind::Flag,
+                    value_type: ArgValueType::Bool,
+                    cardinality: Cardinality::Optional,
+                    default: None,
+                    conflicts_with: vec![],
+                    requires: vec!["progress"],
+                    help: "With --progress, show model reasoning instead of a placeholder",
+                    ..Default::default()
+                },
+                ArgSpec {
+                    name: "expand-tool-output",
+                    short: None,
+                    long: Some("expand-tool-output"),
+                    kind: ArgKind::Flag,
+                    value_type: ArgValueType::Bool,
+                    cardinality: Cardinality::Optional,
+                    default: None,
+                    conflicts_with: vec![],
+                    requires: vec!["progress"],
+                    help: "With --progress, expand tool results instead of showing one line",
+                    ..Default::default()
+                },
                 // ── spec 013 (slice 2): common invocation envelope ───────────────
                 ArgSpec {
                     name: "sandbox",
@@ -935,6 +1071,36 @@ impl IntoCommandSpec for RunArgs {
                     "skip-git-repo-check",
                     "Skip the headless git-repo guard (spec 013 D6)",
                 ),
+                flag_spec(
+                    "trust-project-mcp",
+                    "Start the MCP servers in the project's .aikit/mcp.json (aikit agent)",
+                ),
+                ArgSpec {
+                    name: "web-allow-domain",
+                    short: None,
+                    long: Some("web-allow-domain"),
+                    kind: ArgKind::Option,
+                    value_type: ArgValueType::String,
+                    cardinality: Cardinality::Repeated,
+                    default: None,
+                    conflicts_with: vec![],
+                    requires: vec![],
+                    help: "Host web_fetch/web_search may reach, with subdomains; repeatable (aikit agent)",
+                    ..Default::default()
+                },
+                ArgSpec {
+                    name: "web-deny-domain",
+                    short: None,
+                    long: Some("web-deny-domain"),
+                    kind: ArgKind::Option,
+                    value_type: ArgValueType::String,
+                    cardinality: Cardinality::Repeated,
+                    default: None,
+                    conflicts_with: vec![],
+                    requires: vec![],
+                    help: "Host the web tools never reach, with subdomains; repeatable (aikit agent)",
+                    ..Default::default()
+                },
                 flag_spec(
                     "capabilities",
                     "Print the resolved spec-013 capability matrix for --agent and exit",
@@ -992,6 +1158,19 @@ impl IntoCommandSpec for RunArgs {
                     help: "Resume the most recent session for the current directory",
                     ..Default::default()
                 },
+                ArgSpec {
+                    name: "continue-from",
+                    short: None,
+                    long: Some("continue-from"),
+                    kind: ArgKind::Option,
+                    value_type: ArgValueType::String,
+                    cardinality: Cardinality::Optional,
+                    default: None,
+                    conflicts_with: vec!["resume", "resume-last"],
+                    requires: vec![],
+                    help: "Continue another agent's session: <backend>:<session_id>",
+                    ..Default::default()
+                },
             ],
             ..CommandSpec::default()
         }
@@ -1008,11 +1187,14 @@ impl FromArgValueMap for RunArgs {
             stream: get_bool_val(map, "stream"),
             events: get_bool_val(map, "events"),
             progress: get_bool_val(map, "progress"),
+            show_reasoning: get_bool_val(map, "show-reasoning"),
+            expand_tool_output: get_bool_val(map, "expand-tool-output"),
             dry_run: get_bool_val(map, "dry-run"),
             session_agents: get_opt_val(map, "session-agents"),
             session_persona: get_opt_val(map, "session-persona"),
             resume: get_opt_val(map, "resume"),
             resume_last: get_bool_val(map, "resume-last"),
+            continue_from: get_opt_val(map, "continue-from"),
             sandbox: get_opt_val(map, "sandbox"),
             auto_approve: get_bool_val(map, "auto-approve"),
             cd: get_opt_val(map, "cd"),
@@ -1022,6 +1204,9 @@ impl FromArgValueMap for RunArgs {
             bare: get_bool_val(map, "bare"),
             ephemeral: get_bool_val(map, "ephemeral"),
             skip_git_repo_check: get_bool_val(map, "skip-git-repo-check"),
+            trust_project_mcp: get_bool_val(map, "trust-project-mcp"),
+            web_allow_domain: get_repeated_val(map, "web-allow-domain"),
+            web_deny_domain: get_repeated_val(map, "web-deny-domain"),
             capabilities: get_bool_val(map, "capabilities"),
         }
     }
@@ -1134,7 +1319,7 @@ impl IntoCommandSpec for McpAddArgs {
                     default: None,
                     conflicts_with: vec![],
                     requires: vec![],
-                    help: "Agent key (e.g. cursor, claude, gemini, copilot, codex)",
+                    help: "Agent key (e.g. cursor, claude, gemini, copilot, codex, aikit)",
                     ..Default::default()
                 },
                 ArgSpec {
@@ -1274,13 +1459,15 @@ struct SessionNewArgs {
     approval_policy: Option<String>,
     sandbox: Option<String>,
     events: bool,
+    resume: Option<String>,
+    no_tui: bool,
 }
 
 impl IntoCommandSpec for SessionNewArgs {
     fn command_spec() -> CommandSpec {
         CommandSpec {
-            summary: "Open a live bidirectional agent session (multi-turn REPL)",
-            syntax: Some("session new --agent <AGENT> --prompt <TEXT>"),
+            summary: "Open a live bidirectional agent session (full-screen chat UI)",
+            syntax: Some("session new --agent <AGENT> [--prompt <TEXT>] [--resume <SESSION_ID>]"),
             category: Some("agents"),
             args: vec![
                 ArgSpec {
@@ -1293,17 +1480,25 @@ impl IntoCommandSpec for SessionNewArgs {
                     default: None,
                     conflicts_with: vec![],
                     requires: vec![],
-                    help: "Agent to connect to: 'claude' or 'codex'",
+                    help: "Agent to connect to: 'claude', 'codex' or 'aikit'",
                     ..Default::default()
                 },
                 opt_short_spec("prompt", 'p', "Initial prompt to send"),
-                opt_short_spec("model", 'm', "Model identifier (claude only)"),
+                opt_short_spec("model", 'm', "Model identifier (claude, aikit)"),
                 opt_spec(
                     "approval-policy",
                     "Codex approval policy: never|on-request|on-failure|untrusted",
                 ),
                 opt_spec("sandbox", "Codex sandbox mode"),
                 flag_spec("events", "Print events as NDJSON instead of human-readable"),
+                opt_spec(
+                    "resume",
+                    "Resume a stored session by id, replaying its transcript (claude, aikit)",
+                ),
+                flag_spec(
+                    "no-tui",
+                    "Use the line-by-line REPL instead of the full-screen chat UI",
+                ),
             ],
             ..CommandSpec::default()
         }
@@ -1319,6 +1514,8 @@ impl FromArgValueMap for SessionNewArgs {
             approval_policy: get_opt_val(map, "approval-policy"),
             sandbox: get_opt_val(map, "sandbox"),
             events: get_bool_val(map, "events"),
+            resume: get_opt_val(map, "resume"),
+            no_tui: get_bool_val(map, "no-tui"),
         }
     }
 }
@@ -1351,6 +1548,8 @@ impl FromArgValueMap for SessionListArgs {
 }
 
 struct SessionSyncArgs {
+    sink_dir: Option<String>,
+    webdav_url: Option<String>,
     bucket: Option<String>,
     endpoint: Option<String>,
     region: Option<String>,
@@ -1359,6 +1558,10 @@ struct SessionSyncArgs {
     tool: Vec<String>,
     watch: bool,
     dry_run: bool,
+    compact: bool,
+    key_file: Option<String>,
+    scrub_rules: Option<String>,
+    scrub_audit: bool,
     allow_http: bool,
     format: String,
     log_level: Option<String>,
@@ -1369,9 +1572,19 @@ impl IntoCommandSpec for SessionSyncArgs {
     fn command_spec() -> CommandSpec {
         CommandSpec {
             summary: "Sync raw scrubbed session transcripts to S3-compatible storage",
-            syntax: Some("session sync --bucket <BUCKET> --endpoint <URL>"),
+            syntax: Some(
+                "session sync (--bucket <BUCKET> --endpoint <URL> | --sink-dir <DIR> | --webdav-url <URL>)",
+            ),
             category: Some("agents"),
             args: vec![
+                opt_spec(
+                    "sink-dir",
+                    "Use this directory instead of S3, e.g. an NFS mount (or AIKIT_SYNC_DIR)",
+                ),
+                opt_spec(
+                    "webdav-url",
+                    "Use this WebDAV collection instead of S3 (or AIKIT_SYNC_WEBDAV_URL)",
+                ),
                 opt_spec("bucket", "S3 bucket (or AIKIT_SYNC_BUCKET)"),
                 opt_spec(
                     "endpoint",
@@ -1390,7 +1603,7 @@ impl IntoCommandSpec for SessionSyncArgs {
                     default: None,
                     conflicts_with: vec![],
                     requires: vec![],
-                    help: "Tool to sync; repeatable: claude_code or codex",
+                    help: "Tool to sync; repeatable: claude_code, codex or open_code",
                     ..Default::default()
                 },
                 flag_spec("watch", "Continuously watch for session changes"),
@@ -1398,6 +1611,22 @@ impl IntoCommandSpec for SessionSyncArgs {
                     "dry-run",
                     "Detect, scrub, hash, and summarize without uploading",
                 ),
+                flag_spec(
+                    "compact",
+                    "Roll each session's appended chunks up into a full snapshot",
+                ),
+                opt_spec(
+                    "key-file",
+                    "Encrypt transcripts with this key file (or AIKIT_SYNC_KEY_FILE)",
+                ),
+                opt_spec(
+                    "scrub-rules",
+                    "TOML file of extra scrub rules and detectors (or AIKIT_SCRUB_RULES)",
+                ),
+                flag_spec(
+                    "scrub-audit",
+                    "List what scrubbing would redact per session, by label; uploads nothing",
+                ),
                 flag_spec("allow-http", "Allow plain HTTP endpoints for local MinIO"),
                 opt_spec("format", "Output format: default or json"),
                 opt_spec("log-level", "Log level (default: info or RUST_LOG)"),
@@ -1411,6 +1640,8 @@ impl IntoCommandSpec for SessionSyncArgs {
 impl FromArgValueMap for SessionSyncArgs {
     fn from_arg_value_map(map: &HashMap<String, ArgValue>) -> Self {
         Self {
+            sink_dir: get_opt_val(map, "sink-dir"),
+            webdav_url: get_opt_val(map, "webdav-url"),
             bucket: get_opt_val(map, "bucket"),
             endpoint: get_opt_val(map, "endpoint"),
             region: get_opt_val(map, "region"),
@@ -1419,6 +1650,119 @@ impl FromArgValueMap for SessionSyncArgs {
             tool: get_repeated_val(map, "tool"),
             watch: get_bool_val(map, "watch"),
             dry_run: get_bool_val(map, "dry-run"),
+            compact: get_bool_val(map, "compact"),
+            key_file: get_opt_val(map, "key-file"),
+            scrub_rules: get_opt_val(map, "scrub-rules"),
+            scrub_audit: get_bool_val(map, "scrub-audit"),
+            allow_http: get_bool_val(map, "allow-http"),
+            format: get_str_default(map, "format", "default"),
+            log_level: get_opt_val(map, "log-level"),
+            log_format: get_str_default(map, "log-format", "text"),
+        }
+    }
+}
+
+struct SessionPullArgs {
+    sink_dir: Option<String>,
+    webdav_url: Option<String>,
+    bucket: Option<String>,
+    endpoint: Option<String>,
+    region: Option<String>,
+    owner: Option<String>,
+    key_prefix: Option<String>,
+    tool: Option<String>,
+    session: Option<String>,
+    out: Option<String>,
+    restore: bool,
+    overwrite: bool,
+    ingest: bool,
+    key_file: Option<String>,
+    allow_http: bool,
+    format: String,
+    log_level: Option<String>,
+    log_format: String,
+}
+
+impl IntoCommandSpec for SessionPullArgs {
+    fn command_spec() -> CommandSpec {
+        CommandSpec {
+            summary: "Download synced session transcripts, verify them, and restore or ingest them",
+            syntax: Some(
+                "session pull --owner <OWNER> [--tool <TOOL>] [--session <ID>] [--restore | --out <DIR>] [--ingest]",
+            ),
+            category: Some("agents"),
+            args: vec![
+                opt_spec(
+                    "sink-dir",
+                    "Use this directory instead of S3, e.g. an NFS mount (or AIKIT_SYNC_DIR)",
+                ),
+                opt_spec(
+                    "webdav-url",
+                    "Use this WebDAV collection instead of S3 (or AIKIT_SYNC_WEBDAV_URL)",
+                ),
+                opt_spec("bucket", "S3 bucket (or AIKIT_SYNC_BUCKET)"),
+                opt_spec(
+                    "endpoint",
+                    "S3-compatible endpoint (or AIKIT_SYNC_ENDPOINT)",
+                ),
+                opt_spec("region", "S3 region (default: us-east-1)"),
+                opt_spec("owner", "Owner whose sessions to pull (or AIKIT_SYNC_OWNER)"),
+                opt_spec("key-prefix", "Object key prefix (default: sessions/)"),
+                opt_spec("tool", "Only pull this tool: claude_code or codex"),
+                opt_spec("session", "Only pull this session id"),
+                ArgSpec {
+                    name: "out",
+                    short: None,
+                    long: Some("out"),
+                    kind: ArgKind::Option,
+                    value_type: ArgValueType::String,
+                    cardinality: Cardinality::Optional,
+                    default: None,
+                    conflicts_with: vec!["restore"],
+                    requires: vec![],
+                    help: "Directory to write transcripts under (default: ./aikit-pull)",
+                    ..Default::default()
+                },
+                flag_spec(
+                    "restore",
+                    "Write into the agent's session directory so --resume finds them",
+                ),
+                flag_spec(
+                    "overwrite",
+                    "Replace local transcripts that differ from the pulled version",
+                ),
+                flag_spec("ingest", "Parse pulled transcripts into the local capture DB"),
+                opt_spec(
+                    "key-file",
+                    "Key file to decrypt encrypted transcripts (or AIKIT_SYNC_KEY_FILE)",
+                ),
+                flag_spec("allow-http", "Allow plain HTTP endpoints for local MinIO"),
+                opt_spec("format", "Output format: default or json"),
+                opt_spec("log-level", "Log level (default: info or RUST_LOG)"),
+                opt_spec("log-format", "Log output: text (default) or json (ndjson)"),
+            ],
+            ..CommandSpec::default()
+        }
+    }
+}
+
+impl FromArgValueMap for SessionPullArgs {
+    fn from_arg_value_map(map: &HashMap<String, ArgValue>) -> Self {
+        Self {
+            sink_dir: get_opt_val(map, "sink-dir"),
+            webdav_url: get_opt_val(map, "webdav-url"),
+            bucket: get_opt_val(map, "bucket"),
+            endpoint: get_opt_val(map, "endpoint"),
+            region: get_opt_val(map, "region"),
+            owner: get_opt_val(map, "owner"),
+            key_prefix: get_opt_val(map, "key-prefix"),
+            tool: get_opt_val(map, "tool"),
+            session: get_opt_val(map, "session"),
+            out: get_opt_val(map, "out"),
+            restore: get_bool_val(map, "restore"),
+            overwrite: get_bool_val(map, "overwrite"),
+            ingest: get_bool_val(map, "ingest"),
+            key_file: get_opt_val(map, "key-file"),
             allow_http: get_bool_val(map, "allow-http"),
             format: get_str_default(map, "format", "default"),
             log_level: get_opt_val(map, "log-level"),
@@ -1426,3 +1770,72 @@ impl FromArgValueMap for SessionSyncArgs {
         }
     }
 }
+
+// ── cost ──────────────────────────────────────────────────────────────────────
+
+struct CostArgs {
+    by: String,
+    since: Option<String>,
+    until: Option<String>,
+    tool: Option<String>,
+    pricing: Option<String>,
+    db: Option<String>,
+    format: String,
+    cache: bool,
+}
+
+impl IntoCommandSpec for CostArgs {
+    fn command_spec() -> CommandSpec {
+        CommandSpec {
+            summary: "Estimate spend from captured token usage, per session, day, model or project",
+            syntax: Some(
+                "cost [--by session|day|model|project] [--cache] [--since <DATE>] [--format table|csv|json]",
+            ),
+            category: Some("agents"),
+            args: vec![
+                opt_spec(
+                    "by",
+                    "Group rows by session (default), day, model or project",
+                ),
+                opt_spec(
+                    "since",
+                    "Only usage at or after this UTC date (YYYY-MM-DD) or epoch ms",
+                ),
+                opt_spec(
+                    "until",
+                    "Only usage up to the end of this UTC date or epoch ms",
+                ),
+                opt_spec("tool", "Only this tool: claude_code, codex or open_code"),
+                opt_spec(
+                    "pricing",
+                    "TOML pricing overrides (or AIKIT_PRICING; default: ~/.aikit/pricing.toml)",
+                ),
+                opt_spec(
+                    "db",
+                    "Capture store to read (default: the aikit serve capture.db)",
+                ),
+                opt_spec("format", "Output format: table (default), csv or json"),
+                flag_spec(
+                    "cache",
+                    "Report prompt-cache hit ratios and invalidations per session instead",
+                ),
+            ],
+            ..CommandSpec::default()
+        }
+    }
+}
+
+impl FromArgValueMap for CostArgs {
+    fn from_arg_value_map(map: &HashMap<String, ArgValue>) -> Self {
+        Self {
+            by: get_str_default(map, "by", "session"),
+            since: get_opt_val(map, "since"),
+            until: get_opt_val(map, "until"),
+            tool: get_opt_val(map, "tool"),
+            pricing: get_opt_val(map, "pricing"),
+            db: get_opt_val(map, "db"),
+            format: get_str_default(map, "format", "table"),
+            cache: get_bool_val(map, "cache"),
+        }
+    }
+}
@@ -1,9 +1,11 @@
+use aikit_sdk::handoff::{Handoff, HandoffSource};
 use aikit_sdk::runner::{exit_code_for, format_capabilities, Backend, SandboxPolicy};
 use aikit_sdk::session_store::SessionStore;
 use aikit_sdk::{run_agent, run_agent_events, run_builtin_agent, AgentEvent, OutputMode};
-use aikit_sdk::{ProgressViewConfig, RunError, RunOptions, RunProgress};
+use aikit_sdk::{ProgressViewConfig, RunError, RunOptions, RunProgress, ViewToggles};
 use anyhow::Result;
 use std::io::{self, Read, Write};
+use std::sync::Arc;
 
 use crate::core::agent_definition::{
     load_persisted_registry, parse_agent_markdown, parse_session_agents_json, AgentDefinition,
@@ -20,11 +22,15 @@ pub struct RunArgs {
     pub stream: bool,
     pub events: bool,
     pub progress: bool,
+    pub show_reasoning: bool,
+    pub expand_tool_output: bool,
     pub dry_run: bool,
     pub session_agents: Option<String>,
     pub session_persona: Option<String>,
     pub resume: Option<String>,
     pub resume_last: bool,
+    /// `<backend>:<session_id>` of another agent's session to continue.
+    pub continue_from: Option<String>,
     // spec 013 (slice 2): common invocation envelope
     pub sandbox: Option<String>,
     pub auto_approve: bool,
@@ -35,6 +41,9 @@ pub struct RunArgs {
     pub bare: bool,
     pub ephemeral: bool,
     pub skip_git_repo_check: bool,
+    pub trust_project_mcp: bool,
+    pub web_allow_domain: Vec<String>,
+    pub web_deny_domain: Vec<String>,
     pub capabilities: bool,
 }
 
@@ -94,7 +103,7 @@ pub fn execute(args: RunArgs) -> Result<()> {
     let mut agent = args.agent;
     let mut model = args.model;
 
-    let prompt = match args.prompt {
+    let mut prompt = match args.prompt {
         Some(p) => p,
         None => {
             let mut buffer = String::new();
@@ -103,6 +112,14 @@ pub fn execute(args: RunArgs) -> Result<()> {
         }
     };
 
+    let continue_from: Option<HandoffSource> = match args.continue_from.as_deref() {
+        Some(s) => Some(
+            s.parse::<HandoffSource>()
+                .map_err(|e| anyhow::anyhow!("--continue-from: {}", e))?,
+        ),
+        None => None,
+    };
+
     if agent == "auto" {
         let tier_str = model.as_deref().unwrap_or("");
         let tier =
@@ -238,6 +255,9 @@ pub fn execute(args: RunArgs) -> Result<()> {
                 args.session_persona.as_deref().unwrap_or("")
             );
         }
+        if let Some(ref source) = continue_from {
+            println!("Continue from: {}", source);
+        }
         println!("Configuration validated successfully (dry-run)");
         return Ok(());
     }
@@ -262,6 +282,25 @@ pub fn execute(args: RunArgs) -> Result<()> {
         None
     };
 
+    // Cross-agent handoff: the built-in agent resumes a session seeded with
+    // the source transcript; external CLIs get a condensed handoff prompt.
+    // Unknown agent keys are left to the dispatch error path below.
+    let target = if agent == "agent" {
+        Some(Backend::Aikit)
+    } else {
+        Backend::from_key(&agent)
+    };
+    let resolved_session_id = match (continue_from, target) {
+        (Some(source), Some(target)) => {
+            let seed = Handoff::load(source)
+                .and_then(|h| h.seed(target, &prompt, &workdir))
+                .map_err(|e| anyhow::anyhow!("--continue-from: {}", e))?;
+            prompt = seed.prompt;
+            seed.session_id
+        }
+        _ => resolved_session_id,
+    };
+
     let mut options = RunOptions::new()
         .with_yolo(args.yolo)
         .with_stream(args.stream || args.progress);
@@ -325,6 +364,15 @@ pub fn execute(args: RunArgs) -> Result<()> {
     if args.skip_git_repo_check {
         options = options.with_skip_git_repo_check(true);
     }
+    if args.trust_project_mcp {
+        options = options.with_trust_project_mcp(true);
+    }
+    for d in &args.web_allow_domain {
+        options = options.with_web_allow_domain(d.clone());
+    }
+    for d in &args.web_deny_domain {
+        options = options.with_web_deny_domain(d.clone());
+    }
 
     // spec 013 D3: capture the result-file path before `options` is moved; the
     // SDK now emits the terminal Result event canonically.
@@ -342,8 +390,11 @@ pub fn execute(args: RunArgs) -> Result<()> {
         };
 
         let progress_sink: Option<Box<dyn aikit_sdk::ProgressSink>> = if args.progress {
-            let renderer = ProgressRenderer::new().unwrap_or_else(|_| ProgressRenderer::non_tty());
-            Some(Box::new(ProgressRendererSink::new(renderer)))
+            let renderer = progress_renderer(args.show_reasoning, args.expand_tool_output);
+            Some(Box::new(
+                ProgressRendererSink::new(renderer)
+                    .with_view_config(progress_view_config(model.clone())),
+            ))
         } else {
             None
         };
@@ -397,8 +448,8 @@ pub fn execute(args: RunArgs) -> Result<()> {
             }
         }
     } else if args.progress {
-        let mut progress = RunProgress::new(ProgressViewConfig::default());
-        let mut renderer = ProgressRenderer::new().unwrap_or_else(|_| ProgressRenderer::non_tty());
+        let mut progress = RunProgress::new(progress_view_config(model.clone()));
+        let mut renderer = progress_renderer(args.show_reasoning, args.expand_tool_output);
         let agent_key = agent.clone();
         let mut result_text: Option<String> = None;
         match run_agent_events(&agent, &prompt, options, |event: AgentEvent| {
@@ -447,6 +498,33 @@ pub fn execute(args: RunArgs) -> Result<()> {
     }
 }
 
+/// Stderr progress renderer with the `--show-reasoning` / `--expand-tool-output` toggles applied.
+fn progress_renderer(show_reasoning: bool, expand_tool_results: bool) -> ProgressRenderer {
+    ProgressRenderer::new()
+        .unwrap_or_else(|_| ProgressRenderer::non_tty())
+        .with_toggles(ViewToggles {
+            show_reasoning,
+            expand_tool_results,
+        })
+}
+
+/// Token meter configuration for `--progress`: `model` priced with the same
+/// catalog as `aikit cost`. A broken override file only costs the estimate.
+fn progress_view_config(model: Option<String>) -> ProgressViewConfig {
+    let pricing = match super::cost::load_pricing(None) {
+        Ok(pricing) => Some(Arc::new(pricing)),
+        Err(e) => {
+            eprintln!("warning: no cost estimate: {:#}", e);
+            None
+        }
+    };
+    ProgressViewConfig {
+        pricing,
+        model,
+        ..ProgressViewConfig::default()
+    }
+}
+
 /// Write the `--output-result` file (spec 013 D3). Best-effort on error: the
 /// run already produced its output, so a write failure is reported on stderr
 /// rather than turning a successful run into a non-zero exit.
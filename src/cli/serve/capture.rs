@@ -1,10 +1,13 @@
 //! `aikit serve` capture routes — the HTTP surface for passive session
 //! capture (spec 010 §14).
 //!
-//! Five routes under `/api/v1/capture`:
+//! Eight routes under `/api/v1/capture`:
 //! - `GET  /capture`                          — list detected adapters
 //! - `GET  /capture/{backend}/sessions`       — list parsed sessions
 //! - `GET  /capture/{backend}/sessions/{id}/actions` — action stream
+//! - `GET  /capture/search`                   — ranked full-text search
+//! - `GET  /capture/cost`                     — spend report (JSON or CSV)
+//! - `GET  /capture/cache`                    — prompt-cache efficiency report
 //! - `POST /capture/scan`                     — trigger async scan job
 //! - `GET  /capture/scan/{job_id}`            — scan job status
 //!
@@ -25,9 +28,13 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use aikit_sdk::cost::{
+    analyze_cache, build_report, CacheAnalysisQuery, CostGroupBy, CostReportQuery, PricingTable,
+};
 use aikit_sdk::runner::Backend;
 use aikit_session_capture::{
-    Adapter, CursorStore, EventBatch, EventStore, ParseCursor, ParseWarning, Registry, ToolKind,
+    ActionKind, Adapter, AggregateWindow, CursorStore, EventBatch, EventStore, ParseCursor,
+    ParseWarning, Registry, SearchQuery, ToolKind,
 };
 
 use super::error_response;
@@ -41,6 +48,8 @@ pub struct CaptureState {
     pub event_store: Arc<dyn EventStore>,
     pub cursor_store: Arc<dyn CursorStore>,
     pub scan_jobs: Arc<ScanJobRegistry>,
+    /// Prices for `GET /capture/cost` and `GET /capture/cache`.
+    pub pricing: Arc<PricingTable>,
     /// Last parse timestamp per adapter kind, for the `GET /capture` summary.
     last_parse: Arc<Mutex<HashMap<ToolKind, i64>>>,
 }
@@ -56,10 +65,18 @@ impl CaptureState {
             event_store,
             cursor_store,
             scan_jobs: Arc::new(ScanJobRegistry::default()),
+            pricing: Arc::new(PricingTable::builtin()),
             last_parse: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Replace the built-in pricing catalog used by the cost and cache
+    /// reports.
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = Arc::new(pricing);
+        self
+    }
+
     pub fn touch_last_parse(&self, kind: ToolKind) {
         self.last_parse.lock().unwrap().insert(kind, now_ms());
     }
@@ -120,6 +137,9 @@ pub fn build_router(state: CaptureState) -> Router {
             "/capture/{backend}/sessions/{session_id}/actions",
             get(list_actions),
         )
+        .route("/capture/search", get(search_actions))
+        .route("/capture/cost", get(cost_report))
+        .route("/capture/cache", get(cache_report))
         .route("/capture/scan", post(start_scan))
         .route("/capture/scan/{job_id}", get(scan_status));
 
@@ -339,6 +359,187 @@ async fn list_actions(
     }
 }
 
+// ── GET /capture/search ───────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    q: String,
+    backend: Option<String>,
+    kind: Option<String>,
+    session_id: Option<String>,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    git_root: Option<String>,
+    #[serde(default = "default_search_limit")]
+    limit: u32,
+    #[serde(default)]
+    offset: u32,
+}
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+/// `q` uses the `aikit_session_capture::search` syntax (words, `"phrases"`,
+/// `prefix*`); an empty `q` lists matching actions newest first.
+async fn search_actions(State(cs): State<CaptureState>, Query(p): Query<SearchParams>) -> Response {
+    let tool = match p.backend.as_deref() {
+        Some(key) => match resolve_backend(&cs, key) {
+            Ok((tool, _)) => Some(tool),
+            Err(resp) => return resp,
+        },
+        None => None,
+    };
+    let kind = match p.kind.as_deref() {
+        Some(k) => match serde_json::from_value::<ActionKind>(serde_json::Value::from(k)) {
+            Ok(kind) => Some(kind),
+            Err(_) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_request",
+                    &format!("unknown action kind '{k}'"),
+                )
+            }
+        },
+        None => None,
+    };
+    let query = SearchQuery {
+        tool,
+        kind,
+        session_id: p.session_id,
+        since_ms: p.since_ms,
+        until_ms: p.until_ms,
+        git_root: p.git_root.map(Into::into),
+        limit: p.limit,
+        offset: p.offset,
+        ..SearchQuery::new(p.q)
+    };
+    match cs.event_store.search(&query).await {
+        Ok(matches) => json_ok(StatusCode::OK, &matches),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "store_error",
+            &e.to_string(),
+        ),
+    }
+}
+
+// ── GET /capture/cost ─────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct CostParams {
+    by: Option<String>,
+    backend: Option<String>,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    format: Option<String>,
+}
+
+/// Client-computed spend from captured token events, grouped by `by`
+/// (`session` by default, `day`, `model` or `project`). `format=csv`
+/// answers `text/csv` instead of a JSON `CostReport`.
+async fn cost_report(State(cs): State<CaptureState>, Query(p): Query<CostParams>) -> Response {
+    let invalid = |msg: &str| error_response(StatusCode::BAD_REQUEST, "invalid_request", msg);
+    let group_by = match p.by.as_deref().map(str::parse::<CostGroupBy>) {
+        None => CostGroupBy::Session,
+        Some(Ok(group_by)) => group_by,
+        Some(Err(e)) => return invalid(&e),
+    };
+    let csv = match p.format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => return invalid(&format!("unknown format '{other}' (expected json or csv)")),
+    };
+    let tool = match p.backend.as_deref() {
+        Some(key) => match resolve_backend(&cs, key) {
+            Ok((tool, _)) => Some(tool),
+            Err(resp) => return resp,
+        },
+        None => None,
+    };
+    let query = CostReportQuery {
+        group_by,
+        window: AggregateWindow {
+            since_ms: p.since_ms,
+            until_ms: p.until_ms,
+        },
+        tool,
+    };
+    match build_report(cs.event_store.as_ref(), &query, &cs.pricing).await {
+        Ok(report) if csv => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            report.to_csv(),
+        )
+            .into_response(),
+        Ok(report) => json_ok(StatusCode::OK, &report),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "store_error",
+            &e.to_string(),
+        ),
+    }
+}
+
+// ── GET /capture/cache ────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct CacheParams {
+    backend: Option<String>,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    collapse_min_turns: Option<usize>,
+    collapse_drop: Option<f64>,
+    format: Option<String>,
+}
+
+/// Per-session prompt-cache hit ratios, wasted cache-write spend and
+/// invalidation causes. `format=csv` answers `text/csv` (one line per
+/// session) instead of a JSON `CacheReport`.
+async fn cache_report(State(cs): State<CaptureState>, Query(p): Query<CacheParams>) -> Response {
+    let invalid = |msg: &str| error_response(StatusCode::BAD_REQUEST, "invalid_request", msg);
+    let csv = match p.format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => return invalid(&format!("unknown format '{other}' (expected json or csv)")),
+    };
+    if p.collapse_drop.is_some_and(|d| !(0.0..=1.0).contains(&d)) {
+        return invalid("collapse_drop must be between 0 and 1");
+    }
+    let tool = match p.backend.as_deref() {
+        Some(key) => match resolve_backend(&cs, key) {
+            Ok((tool, _)) => Some(tool),
+            Err(resp) => return resp,
+        },
+        None => None,
+    };
+    let defaults = CacheAnalysisQuery::default();
+    let query = CacheAnalysisQuery {
+        window: AggregateWindow {
+            since_ms: p.since_ms,
+            until_ms: p.until_ms,
+        },
+        tool,
+        collapse_min_turns: p.collapse_min_turns.unwrap_or(defaults.collapse_min_turns),
+        collapse_drop: p.collapse_drop.unwrap_or(defaults.collapse_drop),
+    };
+    match analyze_cache(cs.event_store.as_ref(), &query, &cs.pricing).await {
+        Ok(report) if csv => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            report.to_csv(),
+        )
+            .into_response(),
+        Ok(report) => json_ok(StatusCode::OK, &report),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "store_error",
+            &e.to_string(),
+        ),
+    }
+}
+
 // ── POST /capture/scan ────────────────────────────────────────────────────────
 
 #[derive(Deserialize)]
@@ -843,6 +1044,145 @@ mod tests {
         assert_eq!(o2.deduplicated_count, 2);
     }
 
+    #[tokio::test]
+    async fn cost_route_reports_json_and_csv() {
+        use super::super::openapi::assert_type_matches;
+        use aikit_session_capture::{CaptureSource, TokenEvent};
+
+        let store = Arc::new(InMemoryEventStore::new());
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![sample_event("1", "s1")],
+                token_events: vec![TokenEvent {
+                    source_event_id: "t1".into(),
+                    session_id: "s1".into(),
+                    tool: ToolKind::ClaudeCode,
+                    model: Some("claude-sonnet-4-20250514".into()),
+                    request_id: None,
+                    input_tokens: Some(1_000_000),
+                    cache_read_tokens: None,
+                    cache_creation_tokens: None,
+                    cache_creation_1h_tokens: None,
+                    output_tokens: Some(1_000_000),
+                    reasoning_tokens: None,
+                    captured_at_ms: 1_000,
+                    captured_via: CaptureSource::Transcript,
+                }],
+                cache_observations: vec![],
+            })
+            .await
+            .unwrap();
+        let state = CaptureState::new(
+            Registry::new(),
+            store,
+            Arc::new(InMemoryCursorStore::default()),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!(
+            "http://{}/api/v1/capture/cost",
+            listener.local_addr().unwrap()
+        );
+        let app = Router::new().nest("/api/v1", build_router(state));
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+        let client = reqwest::Client::new();
+
+        let resp = client.get(format!("{base}?by=model")).send().await.unwrap();
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_type_matches("CostReport", &body);
+        assert_eq!(body["rows"][0]["key"], "claude-sonnet-4-20250514");
+        assert_eq!(body["total_usd"], 18.0);
+
+        let resp = client
+            .get(format!("{base}?format=csv"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()["content-type"], "text/csv; charset=utf-8");
+        let csv = resp.text().await.unwrap();
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("session,s1,claude,1,1,"));
+
+        let resp = client.get(format!("{base}?by=week")).send().await.unwrap();
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn cache_route_reports_invalidations() {
+        use super::super::openapi::assert_type_matches;
+        use aikit_session_capture::CacheObservation;
+
+        let obs = |id: &str, at: i64, read: u64, written: u64| CacheObservation {
+            source_event_id: id.into(),
+            session_id: "s1".into(),
+            tool: ToolKind::ClaudeCode,
+            model: Some("claude-sonnet-4-5".into()),
+            cache_read_input_tokens: Some(read),
+            cache_creation_input_tokens: Some(written),
+            cache_creation_1h_input_tokens: None,
+            assistant_blocks_hash: None,
+            tools_changed: vec![],
+            prompt_fingerprint: None,
+            observed_at_ms: at,
+        };
+        let mut changed = obs("c3", 2_000, 0, 21_000);
+        changed.tools_changed = vec!["mcp__linear__list_issues".into()];
+        let store = Arc::new(InMemoryEventStore::new());
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![sample_event("1", "s1")],
+                token_events: vec![],
+                cache_observations: vec![
+                    obs("c1", 0, 0, 20_000),
+                    obs("c2", 1_000, 20_000, 500),
+                    changed,
+                ],
+            })
+            .await
+            .unwrap();
+        let state = CaptureState::new(
+            Registry::new(),
+            store,
+            Arc::new(InMemoryCursorStore::default()),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!(
+            "http://{}/api/v1/capture/cache",
+            listener.local_addr().unwrap()
+        );
+        let app = Router::new().nest("/api/v1", build_router(state));
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+        let client = reqwest::Client::new();
+
+        let resp = client.get(&base).send().await.unwrap();
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_type_matches("CacheReport", &body);
+        assert_eq!(body["sessions"][0]["session_id"], "s1");
+        let invalidation = &body["sessions"][0]["invalidations"][0];
+        assert_eq!(invalidation["cause"], "tools_changed");
+        assert_eq!(invalidation["lost_tokens"], 20_500);
+        assert_eq!(body["causes"][0]["count"], 1);
+
+        let resp = client
+            .get(format!("{base}?format=csv"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()["content-type"], "text/csv; charset=utf-8");
+        assert!(resp.text().await.unwrap().contains(",tools_changed,"));
+
+        let resp = client
+            .get(format!("{base}?collapse_drop=2"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 400);
+    }
+
     #[test]
     fn backend_mapping_returns_none_for_unsupported() {
         // Cursor/Gemini/Aikit never have adapters in this spec.
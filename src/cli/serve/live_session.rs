@@ -1,7 +1,8 @@
 //! Bidirectional live-session handlers (`/api/v1/live-sessions`).
 //!
-//! Covers: Claude and Codex bidirectional sessions opened via `open_*_session`,
-//! streamed as SSE, and driven via a control endpoint.
+//! Covers: Claude, Codex, Pi, OpenCode and built-in aikit bidirectional
+//! sessions opened via `open_*_session`, streamed as SSE, and driven via a
+//! control endpoint.
 
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
@@ -13,9 +14,10 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use aikit_sdk::{
-    open_claude_session, open_codex_session, open_pi_session, ClaudeSessionError,
+    open_aikit_session, open_claude_session, open_codex_session, open_opencode_session,
+    open_pi_session, AikitSessionError, AikitSessionOptions, ClaudeSessionError,
     ClaudeSessionOptions, CodexSessionError, CodexSessionOptions, ControlError, LiveSession,
-    PiSessionError, PiSessionOptions,
+    OpenCodeSessionError, OpenCodeSessionOptions, PiSessionError, PiSessionOptions,
 };
 use uuid::Uuid;
 
@@ -99,6 +101,7 @@ fn try_reserve_live_slot(
 // ── HTTP body types ───────────────────────────────────────────────────────────
 
 #[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
 #[serde(deny_unknown_fields)]
 pub(super) struct CreateLiveSessionRequest {
     pub agent: String,
@@ -118,6 +121,7 @@ pub(super) struct CreateLiveSessionRequest {
 }
 
 #[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
 #[serde(deny_unknown_fields)]
 pub(super) struct LiveSessionControlRequest {
     action: String,
@@ -292,11 +296,67 @@ pub(super) async fn create_live_session_handler(
                 }
             }
         }
+        "opencode" => {
+            let opts = OpenCodeSessionOptions {
+                model: body.model.clone(),
+                session_id: body.resume.clone(),
+                ..OpenCodeSessionOptions::default()
+            };
+            match open_opencode_session(&body.prompt, opts) {
+                Ok(s) => {
+                    let (ctrl, evts) = s.into_parts();
+                    (Box::new(ctrl), evts)
+                }
+                Err(OpenCodeSessionError::Connect(msg)) => {
+                    return error_response(
+                        StatusCode::BAD_GATEWAY,
+                        "session_connect_failed",
+                        &format!("Failed to connect to opencode: {msg}"),
+                    );
+                }
+                Err(e) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "session_error",
+                        &e.to_string(),
+                    );
+                }
+            }
+        }
+        "aikit" => {
+            let opts = AikitSessionOptions {
+                model: body.model.clone(),
+                session_id: body.resume.clone(),
+                ..AikitSessionOptions::default()
+            };
+            match open_aikit_session(&body.prompt, opts) {
+                Ok(s) => {
+                    let (ctrl, evts) = s.into_parts();
+                    (Box::new(ctrl), evts)
+                }
+                Err(AikitSessionError::Connect(msg)) => {
+                    return error_response(
+                        StatusCode::BAD_GATEWAY,
+                        "session_connect_failed",
+                        &format!("Failed to start aikit: {msg}"),
+                    );
+                }
+                Err(e) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "session_error",
+                        &e.to_string(),
+                    );
+                }
+            }
+        }
         other => {
             return error_response(
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "agent_not_supported",
-                &format!("Live sessions require agent 'claude', 'codex', or 'pi', got '{other}'"),
+                &format!(
+                    "Live sessions require agent 'claude', 'codex', 'pi', 'opencode', or 'aikit', got '{other}'"
+                ),
             );
         }
     };
@@ -828,4 +888,57 @@ mod tests {
             StatusCode::GONE
         );
     }
+
+    #[test]
+    fn live_session_bodies_match_openapi() {
+        use super::super::openapi::{assert_conforms, assert_type_matches};
+
+        assert_type_matches(
+            "CreateLiveSessionRequest",
+            &CreateLiveSessionRequest::default(),
+        );
+        let mut docs = serde_json::Map::new();
+        docs.insert("command".into(), "docs-mcp".into());
+        let create = CreateLiveSessionRequest {
+            agent: "codex".into(),
+            prompt: "hi".into(),
+            approval_policy: Some("never".into()),
+            sandbox: Some("read-only".into()),
+            mcp_servers: [("docs".to_string(), docs)].into(),
+            ..Default::default()
+        };
+        assert_type_matches("CreateLiveSessionRequest", &create);
+        let body = serde_json::to_value(&create).unwrap();
+        assert!(serde_json::from_value::<CreateLiveSessionRequest>(body).is_ok());
+
+        let control = LiveSessionControlRequest {
+            action: "set_model".into(),
+            model: Some("o3".into()),
+            ..Default::default()
+        };
+        assert_type_matches("LiveSessionControlRequest", &control);
+        let body = serde_json::to_value(&control).unwrap();
+        assert!(serde_json::from_value::<LiveSessionControlRequest>(body).is_ok());
+        assert_conforms(
+            "LiveSessionControlResponse",
+            &serde_json::json!({ "ok": true, "action": "interrupt" }),
+        );
+
+        let now = Utc::now();
+        let listed = ListLiveSessionsResponse {
+            sessions: vec![LiveSessionSummary {
+                session_id: "s1".into(),
+                agent: "claude".into(),
+                status: LiveSessionStatus::Active,
+                created_at: now,
+            }],
+        };
+        assert_type_matches("ListLiveSessionsResponse", &listed);
+        assert_type_matches("LiveSessionSummary", &listed.sessions[0]);
+        let deleted = DeleteLiveSessionResponse {
+            session_id: "s1".into(),
+            status: LiveSessionStatus::Closed,
+        };
+        assert_type_matches("DeleteLiveSessionResponse", &deleted);
+    }
 }
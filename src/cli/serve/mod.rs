@@ -21,12 +21,17 @@
 
 mod history;
 mod live_session;
+mod openapi;
+#[cfg(test)]
+mod openapi_client;
 mod run_session;
 
 #[cfg(feature = "agent-adapters")]
 mod capture;
 #[cfg(feature = "agent-adapters")]
 pub(crate) mod storage;
+#[cfg(feature = "agent-adapters")]
+mod team;
 
 use std::collections::{BTreeMap, HashMap};
 use std::convert::Infallible;
@@ -70,6 +75,25 @@ pub struct ServeArgs {
     /// otherwise refuses to bind a non-loopback address without
     /// `--api-key`. Off by default; operators must opt in explicitly.
     pub insecure: bool,
+    /// Team aggregation: ingest mode, or forwarding to a team host.
+    pub team: TeamArgs,
+}
+
+/// Team aggregation settings. All off by default.
+#[derive(Debug, Clone, Default)]
+pub struct TeamArgs {
+    /// Ingest mode: the owner → token file. When set, serve hosts only the
+    /// `/api/v1/team` routes.
+    pub tokens_file: Option<std::path::PathBuf>,
+    /// Where ingest mode keeps one `<owner>.db` per owner. Defaults to
+    /// `<data dir>/aikit/team`.
+    pub dir: Option<std::path::PathBuf>,
+    /// Forward every captured batch to the team host at this base URL.
+    pub forward_url: Option<String>,
+    /// Owner token presented when forwarding.
+    pub forward_token: Option<String>,
+    /// Also forward tool inputs, outputs and error messages (scrubbed).
+    pub forward_full_payloads: bool,
 }
 
 // ── shared config ─────────────────────────────────────────────────────────────
@@ -303,6 +327,7 @@ fn payload_kind_name(p: &AgentEventPayload) -> &'static str {
         AgentEventPayload::AikitToolUse { .. } => "aikit_tool_use",
         AgentEventPayload::AikitToolResult { .. } => "aikit_tool_result",
         AgentEventPayload::AikitSubagentSpawn { .. } => "aikit_subagent_spawn",
+        AgentEventPayload::AikitSubagentEvent { .. } => "aikit_subagent_event",
         AgentEventPayload::AikitSubagentResult { .. } => "aikit_subagent_result",
         AgentEventPayload::AikitContextCompressed { .. } => "aikit_context_compressed",
         AgentEventPayload::AikitStepFinish { .. } => "aikit_step_finish",
@@ -496,7 +521,7 @@ pub(crate) fn capture_db_path() -> std::path::PathBuf {
 }
 
 #[cfg(feature = "agent-adapters")]
-fn build_capture_state() -> anyhow::Result<capture::CaptureState> {
+fn build_capture_state(team_args: &TeamArgs) -> anyhow::Result<capture::CaptureState> {
     use aikit_session_capture::Registry;
 
     let mut reg = Registry::new();
@@ -513,12 +538,35 @@ fn build_capture_state() -> anyhow::Result<capture::CaptureState> {
 
     let db_path = capture_db_path();
     let conn = storage::schema::open(&db_path)?;
-    let event_store: std::sync::Arc<dyn aikit_session_capture::EventStore> =
+    let mut event_store: std::sync::Arc<dyn aikit_session_capture::EventStore> =
         std::sync::Arc::new(storage::SqliteEventStore::new(conn.clone()));
+    if let Some(url) = team_args.forward_url.as_deref() {
+        let token = team_args.forward_token.clone().ok_or_else(|| {
+            anyhow::anyhow!("--team-url needs an owner token in AIKIT_TEAM_TOKEN")
+        })?;
+        // Forwarded text goes through the same rules as `aikit session sync`.
+        let scrubber = match std::env::var_os("AIKIT_SCRUB_RULES") {
+            Some(path) => aikit_session_capture::SecretScrubber::load(std::path::Path::new(&path))?,
+            None => aikit_session_capture::SecretScrubber::default(),
+        };
+        event_store = std::sync::Arc::new(
+            team::ForwardingEventStore::new(event_store, url, token)?
+                .with_scrubber(scrubber)
+                .with_full_payloads(team_args.forward_full_payloads),
+        );
+        tracing::info!(
+            target: "aikit::serve::capture",
+            team_url = %url,
+            full_payloads = team_args.forward_full_payloads,
+            "forwarding captured batches to team host"
+        );
+    }
     let cursor_store: std::sync::Arc<dyn aikit_session_capture::CursorStore> =
         std::sync::Arc::new(storage::SqliteCursorStore::new(conn));
 
-    let state = capture::CaptureState::new(reg, event_store, cursor_store);
+    // `GET /capture/cost` prices with the same catalog as `aikit cost`.
+    let pricing = crate::cli::cost::load_pricing(None)?;
+    let state = capture::CaptureState::new(reg, event_store, cursor_store).with_pricing(pricing);
 
     // Spawn the background WatchDriver if the `watcher` feature is enabled.
     // The driver drains into the same parse_and_store_file pipeline the
@@ -554,6 +602,7 @@ fn build_capture_state() -> anyhow::Result<capture::CaptureState> {
 
 fn build_router(state: AppState) -> Router {
     Router::new()
+        .route("/openapi.json", get(openapi::openapi_handler))
         .route("/agents", get(run_session::agents_handler))
         .route("/messages", post(run_session::messages_handler))
         .route("/sessions", get(run_session::list_runs_handler))
@@ -600,6 +649,9 @@ fn init_tracing() {
 }
 
 pub async fn execute_with_run_fn(args: ServeArgs, run_fn: RunFn) -> anyhow::Result<()> {
+    if let Some(tokens_file) = args.team.tokens_file.clone() {
+        return execute_team_ingest(args, &tokens_file).await;
+    }
     let config = ServeConfig {
         host: args.host.clone(),
         port: args.port,
@@ -670,7 +722,7 @@ pub async fn execute_with_run_fn(args: ServeArgs, run_fn: RunFn) -> anyhow::Resu
 
     #[cfg(feature = "agent-adapters")]
     let domain_router = {
-        let capture_state = build_capture_state()?;
+        let capture_state = build_capture_state(&args.team)?;
         domain_router.merge(capture::build_router(capture_state))
     };
 
@@ -752,6 +804,53 @@ pub async fn execute_with_run_fn(args: ServeArgs, run_fn: RunFn) -> anyhow::Resu
         .map_err(|e| anyhow::anyhow!("server error: {}", e))
 }
 
+/// Ingest mode: serve only the team routes, which authenticate every call
+/// with an owner token (or `--api-key` for reads). No agent runs are
+/// exposed, so a non-loopback bind needs no `--insecure`.
+#[cfg(feature = "agent-adapters")]
+async fn execute_team_ingest(args: ServeArgs, tokens_file: &std::path::Path) -> anyhow::Result<()> {
+    let addr = build_bind_addr(&args.host, args.port)?;
+    let owners = team::load_tokens(tokens_file)?;
+    let dir = args.team.dir.clone().unwrap_or_else(team::team_dir);
+    let owner_count = owners.len();
+    let state = team::TeamState::open(&dir, owners, args.api_key)?;
+
+    let server = ApiServerBuilder::new()
+        .version(ApiVersion {
+            name: ApiVersionName::new_unchecked("v1"),
+            router: team::build_router(state),
+            stability: Stability::Stable,
+            deprecation: None,
+        })
+        .default_version(DefaultVersion::Pinned(ApiVersionName::new_unchecked("v1")))
+        .readiness_check(Arc::new(|| {
+            Box::pin(async {
+                ReadinessReport {
+                    ready: true,
+                    checks: BTreeMap::new(),
+                }
+            })
+        }))
+        .build();
+
+    eprintln!(
+        "Team ingest listening on http://{addr} ({owner_count} owners, stores in {})",
+        dir.display()
+    );
+    server
+        .serve(&addr.to_string())
+        .await
+        .map_err(|e| anyhow::anyhow!("server error: {}", e))
+}
+
+#[cfg(not(feature = "agent-adapters"))]
+async fn execute_team_ingest(
+    _args: ServeArgs,
+    _tokens_file: &std::path::Path,
+) -> anyhow::Result<()> {
+    anyhow::bail!("team ingest mode requires the `agent-adapters` feature")
+}
+
 // ── tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -913,12 +1012,27 @@ mod tests {
         assert_eq!(tag, "aikit_subagent_spawn");
         assert_eq!(data["subagent_id"], "sub-1");
 
+        let nested = AgentEventPayload::AikitSubagentEvent {
+            subagent_id: "sub-1".into(),
+            event: Box::new(AgentEvent {
+                agent_key: "codex".into(),
+                seq: 0,
+                stream: AgentEventStream::Stdout,
+                payload: AgentEventPayload::RawLine("working".into()),
+            }),
+        };
+        let (tag, data) = tag_and_inner(&nested);
+        assert_eq!(tag, "aikit_subagent_event");
+        assert_eq!(data["event"]["agent_key"], "codex");
+        assert_eq!(data["event"]["payload"]["raw_line"], "working");
+
         let result = AgentEventPayload::AikitSubagentResult {
             subagent_id: "sub-1".into(),
             status: "done".into(),
             changed_files: vec!["a.rs".into()],
             key_findings: "fixed it".into(),
             final_message: "all good".into(),
+            patch: None,
         };
         let (tag, data) = tag_and_inner(&result);
         assert_eq!(tag, "aikit_subagent_result");
@@ -928,10 +1042,12 @@ mod tests {
             original_tokens: 1000,
             compressed_tokens: 200,
             turns_summarized: 5,
+            summary: "## Goals\n- fix the build".into(),
         };
         let (tag, data) = tag_and_inner(&compressed);
         assert_eq!(tag, "aikit_context_compressed");
         assert_eq!(data["original_tokens"], 1000);
+        assert_eq!(data["summary"], "## Goals\n- fix the build");
 
         let step = AgentEventPayload::AikitStepFinish {
             iteration: 3,
@@ -0,0 +1,1295 @@
+//! `GET /api/v1/openapi.json` — the serve API as an OpenAPI 3.1 document,
+//! plus the generator for the typed TypeScript client in
+//! `clients/typescript/`.
+//!
+//! Component schemas come from two places: the SDK's wire types
+//! (`AgentEventPayload`, `BackendCapabilities`, history) from
+//! [`aikit_sdk::schema::component_schemas`], and serve's own request/response
+//! bodies declared below next to the routes. Each schema is pinned to the
+//! serde type it describes by a conformance test (here, in `run_session.rs`,
+//! `live_session.rs` and `capture.rs`): `assert_type_matches` serializes a
+//! real value of the type (a request type's `Default`) and requires its
+//! fields to equal the declared properties, so a field added or dropped on
+//! either side fails `cargo test`.
+//!
+//! SSE has no first-class OpenAPI representation. Streaming operations
+//! declare a `text/event-stream` response whose schema is
+//! `ServeStreamEvent`: a `oneOf` of `{event, data}` objects discriminated on
+//! `event`, one per `AgentEventPayload` tag plus serve's own `error` and
+//! `done` (see the module docs in `mod.rs`).
+//!
+//! The typed TypeScript client generated from this document lives in
+//! `clients/typescript/`; see `openapi_client.rs` for the generator and its
+//! drift check.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde_json::{json, Map, Value};
+
+use aikit_sdk::schema::{
+    agent_event_data_schema, nullable, object, schema_ref, string_enum, AGENT_EVENT_TAGS,
+};
+
+/// Path prefix the domain router is mounted under.
+const API_PREFIX: &str = "/api/v1";
+
+pub(super) async fn openapi_handler() -> Response {
+    let include_capture = cfg!(feature = "agent-adapters");
+    (
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        openapi_document(include_capture).to_string(),
+    )
+        .into_response()
+}
+
+// ── document ──────────────────────────────────────────────────────────────────
+
+/// The full document. `include_capture` adds the `/capture` routes, which
+/// are only mounted when the `agent-adapters` feature is compiled in; the
+/// TypeScript client is always generated with them.
+pub(super) fn openapi_document(include_capture: bool) -> Value {
+    let mut schemas = aikit_sdk::schema::component_schemas();
+    serve_schemas(&mut schemas);
+    let mut paths = Map::new();
+    run_paths(&mut paths);
+    live_session_paths(&mut paths);
+    history_paths(&mut paths);
+    if include_capture {
+        capture_schemas(&mut schemas);
+        capture_paths(&mut paths);
+    }
+    paths.insert(
+        "/openapi.json".into(),
+        json!({
+            "get": op(
+                "getOpenApi",
+                "This document.",
+                vec![],
+                None,
+                json!({ "200": json_body("OpenAPI document", json!({ "type": "object" })) }),
+            )
+        }),
+    );
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "aikit serve",
+            "version": "v1",
+            "description": "HTTP API for one-shot agent runs, live sessions, session history and passive capture.",
+        },
+        "servers": [{ "url": API_PREFIX }],
+        // No key configured → unauthenticated; `--api-key` → bearer.
+        "security": [{}, { "bearer": [] }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+        },
+    })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn date_time() -> Value {
+    json!({ "type": "string", "format": "date-time" })
+}
+
+fn serve_schemas(c: &mut Map<String, Value>) {
+    let mut put = |name: &str, schema: Value| {
+        c.insert(name.to_string(), schema);
+    };
+
+    put(
+        "ErrorDetail",
+        object(
+            &[("code", string()), ("message", string())],
+            &["code", "message"],
+        ),
+    );
+    put(
+        "ErrorResponse",
+        object(&[("error", schema_ref("ErrorDetail"))], &["error"]),
+    );
+    // History routes predate `ErrorResponse` and return a bare code plus
+    // route-specific context (e.g. `backend`).
+    put(
+        "HistoryErrorResponse",
+        json!({
+            "type": "object",
+            "properties": { "error": string() },
+            "required": ["error"],
+        }),
+    );
+
+    put("RunStatus", string_enum(&["running", "idle", "closed"]));
+    put(
+        "AuthStatus",
+        string_enum(&["ok", "unauthenticated", "unknown"]),
+    );
+    put(
+        "AgentInfo",
+        object(
+            &[
+                ("key", string()),
+                ("name", string()),
+                ("available", boolean()),
+                ("auth", schema_ref("AuthStatus")),
+                ("capabilities", schema_ref("BackendCapabilities")),
+            ],
+            &["key", "name", "available", "auth"],
+        ),
+    );
+    put(
+        "ListAgentsResponse",
+        object(&[("agents", array(schema_ref("AgentInfo")))], &["agents"]),
+    );
+    put(
+        "SendMessageRequest",
+        object(
+            &[
+                ("agent", string()),
+                ("session_id", nullable(string())),
+                ("content", string()),
+                ("model", nullable(string())),
+                ("yolo", boolean()),
+                ("tools", nullable(array(string()))),
+                ("disallowed_tools", nullable(array(string()))),
+                ("continue_from", nullable(string())),
+            ],
+            &["agent", "content"],
+        ),
+    );
+    put(
+        "UsageSummary",
+        object(
+            &[
+                ("input_tokens", integer()),
+                ("output_tokens", integer()),
+                ("cache_read_tokens", integer()),
+            ],
+            &["input_tokens", "output_tokens"],
+        ),
+    );
+    put(
+        "SyncMessageResponse",
+        object(
+            &[
+                ("session_id", nullable(string())),
+                ("content", string()),
+                ("exit_code", json!({ "type": "integer" })),
+                ("error", schema_ref("ErrorDetail")),
+                ("usage", schema_ref("UsageSummary")),
+            ],
+            &["session_id", "content", "exit_code"],
+        ),
+    );
+    put(
+        "RunSummary",
+        object(
+            &[
+                ("session_id", string()),
+                ("agent", string()),
+                ("status", schema_ref("RunStatus")),
+                ("started_at", date_time()),
+                ("last_active_at", date_time()),
+            ],
+            &[
+                "session_id",
+                "agent",
+                "status",
+                "started_at",
+                "last_active_at",
+            ],
+        ),
+    );
+    put(
+        "ListRunsResponse",
+        object(
+            &[("sessions", array(schema_ref("RunSummary")))],
+            &["sessions"],
+        ),
+    );
+    put(
+        "DeleteSessionResponse",
+        object(
+            &[
+                ("session_id", string()),
+                ("status", schema_ref("RunStatus")),
+            ],
+            &["session_id", "status"],
+        ),
+    );
+
+    put("LiveSessionStatus", string_enum(&["active", "closed"]));
+    put(
+        "CreateLiveSessionRequest",
+        object(
+            &[
+                ("agent", string()),
+                ("prompt", string()),
+                ("model", nullable(string())),
+                ("approval_policy", nullable(string())),
+                ("sandbox", nullable(string())),
+                (
+                    "mcp_servers",
+                    json!({
+                        "type": "object",
+                        "additionalProperties": { "type": "object" },
+                    }),
+                ),
+                ("fork_session", boolean()),
+                ("resume", nullable(string())),
+            ],
+            &["agent", "prompt"],
+        ),
+    );
+    put(
+        "LiveSessionAction",
+        string_enum(&[
+            "interrupt",
+            "disconnect",
+            "set_model",
+            "send_turn",
+            "get_context_usage",
+        ]),
+    );
+    put(
+        "LiveSessionControlRequest",
+        object(
+            &[
+                ("action", schema_ref("LiveSessionAction")),
+                ("model", nullable(string())),
+                ("text", nullable(string())),
+            ],
+            &["action"],
+        ),
+    );
+    put(
+        "LiveSessionControlAck",
+        object(
+            &[
+                ("ok", json!({ "const": true })),
+                ("action", schema_ref("LiveSessionAction")),
+            ],
+            &["ok", "action"],
+        ),
+    );
+    // `get_context_usage` returns the Backend's own usage object verbatim.
+    put(
+        "LiveSessionControlResponse",
+        json!({ "anyOf": [schema_ref("LiveSessionControlAck"), { "type": "object" }] }),
+    );
+    put(
+        "LiveSessionSummary",
+        object(
+            &[
+                ("session_id", string()),
+                ("agent", string()),
+                ("status", schema_ref("LiveSessionStatus")),
+                ("created_at", date_time()),
+            ],
+            &["session_id", "agent", "status", "created_at"],
+        ),
+    );
+    put(
+        "ListLiveSessionsResponse",
+        object(
+            &[("sessions", array(schema_ref("LiveSessionSummary")))],
+            &["sessions"],
+        ),
+    );
+    put(
+        "DeleteLiveSessionResponse",
+        object(
+            &[
+                ("session_id", string()),
+                ("status", schema_ref("LiveSessionStatus")),
+            ],
+            &["session_id", "status"],
+        ),
+    );
+
+    put(
+        "HistoryPatchRequest",
+        json!({
+            "type": "object",
+            "properties": {
+                "rename": nullable(string()),
+                "tag": nullable(string()),
+            },
+            "description": "`tag: null` clears the tag; an absent key leaves it untouched.",
+        }),
+    );
+
+    let mut events: Vec<Value> = AGENT_EVENT_TAGS
+        .iter()
+        .map(|tag| stream_event(tag, agent_event_data_schema(tag).expect("known tag")))
+        .collect();
+    events.push(stream_event("error", schema_ref("ErrorDetail")));
+    events.push(stream_event(
+        "done",
+        object(
+            &[("exit_code", json!({ "type": "integer" }))],
+            &["exit_code"],
+        ),
+    ));
+    put(
+        "ServeStreamEvent",
+        json!({
+            "oneOf": events,
+            "discriminator": { "propertyName": "event" },
+            "description": "One SSE frame: `event` is the SSE event name, `data` its decoded JSON.",
+        }),
+    );
+}
+
+fn stream_event(name: &str, data: Value) -> Value {
+    object(
+        &[("event", json!({ "const": name })), ("data", data)],
+        &["event", "data"],
+    )
+}
+
+fn capture_schemas(c: &mut Map<String, Value>) {
+    let mut put = |name: &str, schema: Value| {
+        c.insert(name.to_string(), schema);
+    };
+    put(
+        "DetectedAdapter",
+        object(
+            &[
+                ("backend", string()),
+                ("tool_kind", string()),
+                ("watch_paths", array(string())),
+                ("detected", boolean()),
+                ("last_parse_at_ms", nullable(json!({ "type": "integer" }))),
+            ],
+            &[
+                "backend",
+                "tool_kind",
+                "watch_paths",
+                "detected",
+                "last_parse_at_ms",
+            ],
+        ),
+    );
+    put(
+        "DetectedAdapters",
+        object(
+            &[("adapters", array(schema_ref("DetectedAdapter")))],
+            &["adapters"],
+        ),
+    );
+    put(
+        "CapturedSession",
+        object(
+            &[
+                ("backend", string()),
+                ("session_id", string()),
+                ("source_file", string()),
+                ("first_event_at_ms", json!({ "type": "integer" })),
+                ("last_event_at_ms", json!({ "type": "integer" })),
+                ("action_count", integer()),
+                ("tool_kinds", array(string())),
+                ("git_root", nullable(string())),
+            ],
+            &[
+                "backend",
+                "session_id",
+                "source_file",
+                "first_event_at_ms",
+                "last_event_at_ms",
+                "action_count",
+                "tool_kinds",
+                "git_root",
+            ],
+        ),
+    );
+    put(
+        "CapturedAction",
+        json!({
+            "type": "object",
+            "description": "One normalized action from `aikit-session-capture`'s event store.",
+        }),
+    );
+    put(
+        "CaptureSearchHit",
+        object(
+            &[
+                ("event", schema_ref("CapturedAction")),
+                ("snippet", string()),
+                ("score", json!({ "type": "number" })),
+            ],
+            &["event", "snippet", "score"],
+        ),
+    );
+    let number = || json!({ "type": "number" });
+    let epoch_ms = || json!({ "type": "integer" });
+    put(
+        "CostGroupBy",
+        string_enum(&["session", "day", "model", "project"]),
+    );
+    put(
+        "CostWindow",
+        object(
+            &[
+                ("label", string()),
+                ("start_ms", epoch_ms()),
+                ("end_ms", epoch_ms()),
+                ("amount_usd", number()),
+            ],
+            &["label", "start_ms", "end_ms", "amount_usd"],
+        ),
+    );
+    put(
+        "Spend",
+        object(
+            &[
+                ("amount_usd", number()),
+                ("scope", string_enum(&["session", "window", "daily"])),
+                ("is_estimate", boolean()),
+            ],
+            &["amount_usd", "scope", "is_estimate"],
+        ),
+    );
+    put(
+        "ModelSpend",
+        object(
+            &[
+                ("model", string()),
+                ("input_tokens", integer()),
+                ("output_tokens", integer()),
+                ("cache_read_tokens", integer()),
+                ("cache_creation_tokens", integer()),
+                ("reasoning_tokens", integer()),
+                ("amount_usd", number()),
+            ],
+            &[
+                "model",
+                "input_tokens",
+                "output_tokens",
+                "cache_read_tokens",
+                "cache_creation_tokens",
+                "reasoning_tokens",
+                "amount_usd",
+            ],
+        ),
+    );
+    put(
+        "CreditBalance",
+        object(
+            &[("remaining_usd", number()), ("currency", string())],
+            &["remaining_usd", "currency"],
+        ),
+    );
+    put(
+        "CostSnapshot",
+        object(
+            &[
+                ("backend", string()),
+                ("captured_at_ms", epoch_ms()),
+                ("windows", array(schema_ref("CostWindow"))),
+                ("spend", nullable(schema_ref("Spend"))),
+                ("per_model", array(schema_ref("ModelSpend"))),
+                ("credits", nullable(schema_ref("CreditBalance"))),
+                (
+                    "provenance",
+                    string_enum(&["proxy", "provider", "client_computed"]),
+                ),
+            ],
+            &[
+                "backend",
+                "captured_at_ms",
+                "windows",
+                "spend",
+                "per_model",
+                "credits",
+                "provenance",
+            ],
+        ),
+    );
+    put(
+        "CostReportRow",
+        object(
+            &[
+                ("key", string()),
+                ("sessions", integer()),
+                ("turns", integer()),
+                ("snapshot", schema_ref("CostSnapshot")),
+            ],
+            &["key", "sessions", "turns", "snapshot"],
+        ),
+    );
+    put(
+        "CostReport",
+        object(
+            &[
+                ("group_by", schema_ref("CostGroupBy")),
+                ("generated_at_ms", epoch_ms()),
+                ("total_usd", number()),
+                ("rows", array(schema_ref("CostReportRow"))),
+                ("unpriced_models", array(string())),
+            ],
+            &[
+                "group_by",
+                "generated_at_ms",
+                "total_usd",
+                "rows",
+                "unpriced_models",
+            ],
+        ),
+    );
+    put(
+        "InvalidationCause",
+        string_enum(&[
+            "tools_changed",
+            "system_prompt_changed",
+            "model_changed",
+            "idle_expired",
+            "unknown",
+        ]),
+    );
+    put(
+        "CacheInvalidation",
+        object(
+            &[
+                ("source_event_id", string()),
+                ("observed_at_ms", epoch_ms()),
+                ("cause", schema_ref("InvalidationCause")),
+                ("idle_ms", integer()),
+                ("lost_tokens", integer()),
+                ("rewrite_tokens", integer()),
+                ("wasted_usd", number()),
+                ("tools_changed", array(string())),
+            ],
+            &[
+                "source_event_id",
+                "observed_at_ms",
+                "cause",
+                "idle_ms",
+                "lost_tokens",
+                "rewrite_tokens",
+                "wasted_usd",
+            ],
+        ),
+    );
+    put(
+        "SessionCacheStats",
+        object(
+            &[
+                ("backend", string()),
+                ("session_id", string()),
+                ("project", nullable(string())),
+                ("model", nullable(string())),
+                ("first_observed_at_ms", epoch_ms()),
+                ("last_observed_at_ms", epoch_ms()),
+                ("turns", integer()),
+                ("cache_read_tokens", integer()),
+                ("cache_creation_tokens", integer()),
+                ("hit_ratio", nullable(number())),
+                ("early_hit_ratio", nullable(number())),
+                ("late_hit_ratio", nullable(number())),
+                ("collapsed", boolean()),
+                ("write_usd", number()),
+                ("wasted_write_usd", number()),
+                ("invalidations", array(schema_ref("CacheInvalidation"))),
+            ],
+            &[
+                "backend",
+                "session_id",
+                "project",
+                "model",
+                "first_observed_at_ms",
+                "last_observed_at_ms",
+                "turns",
+                "cache_read_tokens",
+                "cache_creation_tokens",
+                "hit_ratio",
+                "early_hit_ratio",
+                "late_hit_ratio",
+                "collapsed",
+                "write_usd",
+                "wasted_write_usd",
+                "invalidations",
+            ],
+        ),
+    );
+    put(
+        "CauseSummary",
+        object(
+            &[
+                ("cause", schema_ref("InvalidationCause")),
+                ("count", integer()),
+                ("wasted_usd", number()),
+            ],
+            &["cause", "count", "wasted_usd"],
+        ),
+    );
+    put(
+        "CacheReport",
+        object(
+            &[
+                ("generated_at_ms", epoch_ms()),
+                ("turns", integer()),
+                ("cache_read_tokens", integer()),
+                ("cache_creation_tokens", integer()),
+                ("hit_ratio", nullable(number())),
+                ("write_usd", number()),
+                ("wasted_write_usd", number()),
+                ("causes", array(schema_ref("CauseSummary"))),
+                ("collapsed_sessions", integer()),
+                ("sessions", array(schema_ref("SessionCacheStats"))),
+            ],
+            &[
+                "generated_at_ms",
+                "turns",
+                "cache_read_tokens",
+                "cache_creation_tokens",
+                "hit_ratio",
+                "write_usd",
+                "wasted_write_usd",
+                "causes",
+                "collapsed_sessions",
+                "sessions",
+            ],
+        ),
+    );
+    put("ScanRequest", object(&[("force", boolean())], &[]));
+    put("ScanAccepted", object(&[("job_id", string())], &["job_id"]));
+    put(
+        "ScanJobState",
+        string_enum(&["queued", "running", "complete", "failed"]),
+    );
+    put(
+        "ScanJobSnapshot",
+        object(
+            &[
+                ("job_id", string()),
+                ("state", schema_ref("ScanJobState")),
+                ("started_at_ms", json!({ "type": "integer" })),
+                ("completed_at_ms", nullable(json!({ "type": "integer" }))),
+                ("files_scanned", integer()),
+                ("files_skipped", integer()),
+                ("events_upserted", integer()),
+                ("deduplicated_count", integer()),
+                ("warnings", array(json!({ "type": "object" }))),
+                ("error", nullable(string())),
+            ],
+            &[
+                "job_id",
+                "state",
+                "started_at_ms",
+                "completed_at_ms",
+                "files_scanned",
+                "files_skipped",
+                "events_upserted",
+                "deduplicated_count",
+                "warnings",
+                "error",
+            ],
+        ),
+    );
+}
+
+// ── operations ────────────────────────────────────────────────────────────────
+
+fn op(
+    id: &str,
+    summary: &str,
+    parameters: Vec<Value>,
+    body: Option<Value>,
+    responses: Value,
+) -> Value {
+    let mut o = json!({ "operationId": id, "summary": summary, "responses": responses });
+    if !parameters.is_empty() {
+        o["parameters"] = Value::Array(parameters);
+    }
+    if let Some(body) = body {
+        o["requestBody"] = body;
+    }
+    o
+}
+
+fn path_param(name: &str) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "schema": string() })
+}
+
+fn query_param(name: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "schema": schema })
+}
+
+fn json_request(schema_name: &str, required: bool) -> Value {
+    json!({
+        "required": required,
+        "content": { "application/json": { "schema": schema_ref(schema_name) } },
+    })
+}
+
+fn json_body(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": schema } } })
+}
+
+fn sse_content() -> Value {
+    json!({ "schema": schema_ref("ServeStreamEvent") })
+}
+
+fn error_body(description: &str) -> Value {
+    json_body(description, schema_ref("ErrorResponse"))
+}
+
+fn history_error_body(description: &str) -> Value {
+    json_body(description, schema_ref("HistoryErrorResponse"))
+}
+
+fn run_paths(paths: &mut Map<String, Value>) {
+    paths.insert(
+        "/agents".into(),
+        json!({
+            "get": op(
+                "listAgents",
+                "Runnable agents with auth status and capabilities.",
+                vec![],
+                None,
+                json!({ "200": json_body("Agents", schema_ref("ListAgentsResponse")) }),
+            )
+        }),
+    );
+    paths.insert(
+        "/messages".into(),
+        json!({
+            "post": op(
+                "sendMessage",
+                "Run one turn. `Accept: text/event-stream` (default) streams events; `application/json` returns the accumulated reply.",
+                vec![],
+                Some(json_request("SendMessageRequest", true)),
+                json!({
+                    "200": {
+                        "description": "Event stream or accumulated reply",
+                        "headers": { "X-Session-Id": { "schema": string() } },
+                        "content": {
+                            "text/event-stream": sse_content(),
+                            "application/json": { "schema": schema_ref("SyncMessageResponse") },
+                        },
+                    },
+                    "400": error_body("Malformed JSON"),
+                    "404": error_body("Unknown session"),
+                    "406": error_body("Unsupported Accept type"),
+                    "409": error_body("Session busy, or continue_from source has no history"),
+                    "422": error_body("Invalid request"),
+                    "429": error_body("Session capacity reached"),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/sessions".into(),
+        json!({
+            "get": op(
+                "listSessions",
+                "One-shot run sessions known to this server.",
+                vec![],
+                None,
+                json!({ "200": json_body("Sessions", schema_ref("ListRunsResponse")) }),
+            )
+        }),
+    );
+    paths.insert(
+        "/sessions/{session_id}".into(),
+        json!({
+            "get": op(
+                "getSession",
+                "One run session.",
+                vec![path_param("session_id")],
+                None,
+                json!({
+                    "200": json_body("Session", schema_ref("RunSummary")),
+                    "404": error_body("Unknown session"),
+                }),
+            ),
+            "delete": op(
+                "deleteSession",
+                "Cancel any in-flight run and forget the session.",
+                vec![path_param("session_id")],
+                None,
+                json!({
+                    "200": json_body("Closed session", schema_ref("DeleteSessionResponse")),
+                    "404": error_body("Unknown session"),
+                }),
+            ),
+        }),
+    );
+}
+
+fn live_session_paths(paths: &mut Map<String, Value>) {
+    paths.insert(
+        "/live-sessions".into(),
+        json!({
+            "post": op(
+                "createLiveSession",
+                "Open a bidirectional session and stream its events.",
+                vec![],
+                Some(json_request("CreateLiveSessionRequest", true)),
+                json!({
+                    "200": {
+                        "description": "Event stream; the first event is `session_started`",
+                        "headers": { "X-Session-Id": { "schema": string() } },
+                        "content": { "text/event-stream": sse_content() },
+                    },
+                    "422": error_body("Invalid request or unsupported agent"),
+                    "429": error_body("Session capacity reached"),
+                    "502": error_body("Agent failed to start"),
+                }),
+            ),
+            "get": op(
+                "listLiveSessions",
+                "Open and recently closed live sessions.",
+                vec![],
+                None,
+                json!({ "200": json_body("Live sessions", schema_ref("ListLiveSessionsResponse")) }),
+            ),
+        }),
+    );
+    paths.insert(
+        "/live-sessions/{session_id}/control".into(),
+        json!({
+            "post": op(
+                "controlLiveSession",
+                "Interrupt, switch model, send a turn, read context usage or disconnect.",
+                vec![path_param("session_id")],
+                Some(json_request("LiveSessionControlRequest", true)),
+                json!({
+                    "200": json_body("Acknowledgement, or the context usage object", schema_ref("LiveSessionControlResponse")),
+                    "404": error_body("Unknown session"),
+                    "410": error_body("Session closed"),
+                    "422": error_body("Unknown or unsupported action"),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/live-sessions/{session_id}".into(),
+        json!({
+            "delete": op(
+                "deleteLiveSession",
+                "Disconnect a live session.",
+                vec![path_param("session_id")],
+                None,
+                json!({
+                    "200": json_body("Closed session", schema_ref("DeleteLiveSessionResponse")),
+                    "404": error_body("Unknown session"),
+                }),
+            )
+        }),
+    );
+}
+
+fn history_paths(paths: &mut Map<String, Value>) {
+    let cwd = || query_param("cwd", string());
+    let common_errors = |mut responses: Value| {
+        responses["400"] = history_error_body("Invalid session id");
+        responses["404"] = history_error_body("Unknown backend or session");
+        responses["409"] = history_error_body("Backend has no history store");
+        responses
+    };
+    paths.insert(
+        "/history/{backend}".into(),
+        json!({
+            "get": op(
+                "listHistory",
+                "Sessions in a Backend's transcript store, newest first.",
+                vec![
+                    path_param("backend"),
+                    cwd(),
+                    query_param("tag", string()),
+                    query_param("limit", integer()),
+                    query_param("offset", integer()),
+                ],
+                None,
+                common_errors(json!({ "200": json_body("Sessions", array(schema_ref("HistorySession"))) })),
+            )
+        }),
+    );
+    let mut patch_responses = common_errors(json!({ "204": { "description": "Applied" } }));
+    patch_responses["409"] = history_error_body("No history store, or mutations unsupported");
+    paths.insert(
+        "/history/{backend}/{session_id}".into(),
+        json!({
+            "get": op(
+                "getHistorySession",
+                "One session's metadata.",
+                vec![path_param("backend"), path_param("session_id"), cwd()],
+                None,
+                common_errors(json!({ "200": json_body("Session", schema_ref("HistorySession")) })),
+            ),
+            "patch": op(
+                "patchHistorySession",
+                "Rename and/or tag a session.",
+                vec![path_param("backend"), path_param("session_id"), cwd()],
+                Some(json_request("HistoryPatchRequest", true)),
+                patch_responses,
+            ),
+        }),
+    );
+    paths.insert(
+        "/history/{backend}/{session_id}/messages".into(),
+        json!({
+            "get": op(
+                "listHistoryMessages",
+                "A session's transcript messages, in order.",
+                vec![
+                    path_param("backend"),
+                    path_param("session_id"),
+                    cwd(),
+                    query_param("limit", integer()),
+                    query_param("offset", integer()),
+                ],
+                None,
+                common_errors(json!({ "200": json_body("Messages", array(schema_ref("HistoryMessage"))) })),
+            )
+        }),
+    );
+}
+
+fn capture_paths(paths: &mut Map<String, Value>) {
+    let unsupported = || error_body("No capture adapter for this Backend");
+    paths.insert(
+        "/capture".into(),
+        json!({
+            "get": op(
+                "listCaptureAdapters",
+                "Compiled-in capture adapters and whether their stores exist.",
+                vec![],
+                None,
+                json!({
+                    "200": json_body("Adapters", schema_ref("DetectedAdapters")),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/{backend}".into(),
+        json!({
+            "get": op(
+                "getCaptureAdapter",
+                "One capture adapter.",
+                vec![path_param("backend")],
+                None,
+                json!({
+                    "200": json_body("Adapter", schema_ref("DetectedAdapter")),
+                    "404": error_body("Unknown backend"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/{backend}/sessions".into(),
+        json!({
+            "get": op(
+                "listCapturedSessions",
+                "Sessions parsed into the capture store.",
+                vec![
+                    path_param("backend"),
+                    query_param("cwd", string()),
+                    query_param("limit", integer()),
+                    query_param("offset", integer()),
+                ],
+                None,
+                json!({
+                    "200": json_body("Sessions", array(schema_ref("CapturedSession"))),
+                    "404": error_body("Unknown backend"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/{backend}/sessions/{session_id}/actions".into(),
+        json!({
+            "get": op(
+                "listCapturedActions",
+                "A captured session's normalized actions.",
+                vec![
+                    path_param("backend"),
+                    path_param("session_id"),
+                    query_param("limit", integer()),
+                    query_param("offset", integer()),
+                ],
+                None,
+                json!({
+                    "200": json_body("Actions", array(schema_ref("CapturedAction"))),
+                    "404": error_body("Unknown backend or session"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/search".into(),
+        json!({
+            "get": op(
+                "searchCapturedActions",
+                "Full-text search over captured actions, best match first.",
+                vec![
+                    query_param("q", string()),
+                    query_param("backend", string()),
+                    query_param("kind", string()),
+                    query_param("session_id", string()),
+                    query_param("since_ms", json!({ "type": "integer" })),
+                    query_param("until_ms", json!({ "type": "integer" })),
+                    query_param("git_root", string()),
+                    query_param("limit", integer()),
+                    query_param("offset", integer()),
+                ],
+                None,
+                json!({
+                    "200": json_body("Hits", array(schema_ref("CaptureSearchHit"))),
+                    "400": error_body("Unknown action kind"),
+                    "404": error_body("Unknown backend"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/cost".into(),
+        json!({
+            "get": op(
+                "getCaptureCost",
+                "Estimated spend from captured token usage, grouped by session, day, model or project. `format=csv` returns `text/csv`.",
+                vec![
+                    query_param("by", schema_ref("CostGroupBy")),
+                    query_param("backend", string()),
+                    query_param("since_ms", json!({ "type": "integer" })),
+                    query_param("until_ms", json!({ "type": "integer" })),
+                    query_param("format", string_enum(&["json", "csv"])),
+                ],
+                None,
+                json!({
+                    "200": {
+                        "description": "Cost report",
+                        "content": {
+                            "application/json": { "schema": schema_ref("CostReport") },
+                            "text/csv": { "schema": string() },
+                        },
+                    },
+                    "400": error_body("Unknown grouping or format"),
+                    "404": error_body("Unknown backend"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/cache".into(),
+        json!({
+            "get": op(
+                "getCaptureCache",
+                "Prompt-cache hit ratios, wasted cache-write spend and invalidation causes per session. `format=csv` returns `text/csv`.",
+                vec![
+                    query_param("backend", string()),
+                    query_param("since_ms", json!({ "type": "integer" })),
+                    query_param("until_ms", json!({ "type": "integer" })),
+                    query_param("collapse_min_turns", json!({ "type": "integer" })),
+                    query_param("collapse_drop", json!({ "type": "number" })),
+                    query_param("format", string_enum(&["json", "csv"])),
+                ],
+                None,
+                json!({
+                    "200": {
+                        "description": "Cache report",
+                        "content": {
+                            "application/json": { "schema": schema_ref("CacheReport") },
+                            "text/csv": { "schema": string() },
+                        },
+                    },
+                    "400": error_body("Invalid format or collapse threshold"),
+                    "404": error_body("Unknown backend"),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/scan".into(),
+        json!({
+            "post": op(
+                "startCaptureScan",
+                "Start an asynchronous scan of every adapter's store.",
+                vec![],
+                Some(json_request("ScanRequest", false)),
+                json!({
+                    "202": json_body("Scan job started", schema_ref("ScanAccepted")),
+                    "409": unsupported(),
+                }),
+            )
+        }),
+    );
+    paths.insert(
+        "/capture/scan/{job_id}".into(),
+        json!({
+            "get": op(
+                "getCaptureScan",
+                "Progress of a scan job.",
+                vec![path_param("job_id")],
+                None,
+                json!({
+                    "200": json_body("Scan job", schema_ref("ScanJobSnapshot")),
+                    "404": error_body("Unknown job"),
+                }),
+            )
+        }),
+    );
+}
+
+/// Check `value` against component `name` of the served document. Used by
+/// the per-route conformance tests.
+#[cfg(test)]
+pub(super) fn assert_conforms(name: &str, value: &Value) {
+    let spec = openapi_document(true);
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    if let Err(e) = aikit_sdk::schema::validate(schemas, &schema_ref(name), value) {
+        panic!("{name} does not match its OpenAPI schema: {e}\nvalue: {value}");
+    }
+}
+
+/// Serialize `value`, a real instance of the type behind component `name`,
+/// and check it both ways: it conforms, and its fields are exactly the
+/// properties the component declares. Request types pass their `Default`,
+/// so a field added to the type shows up here without touching the test.
+#[cfg(test)]
+pub(super) fn assert_type_matches<T: serde::Serialize>(name: &str, value: &T) {
+    let value = serde_json::to_value(value).unwrap();
+    assert_conforms(name, &value);
+    let spec = openapi_document(true);
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        aikit_sdk::schema::property_names(schemas, &schema_ref(name)),
+        "{name}: serialized fields vs OpenAPI properties"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every `$ref` in the document points at a declared component.
+    fn collect_refs<'a>(v: &'a Value, out: &mut Vec<&'a str>) {
+        match v {
+            Value::Object(map) => {
+                if let Some(r) = v.get("$ref").and_then(Value::as_str) {
+                    out.push(r.trim_start_matches("#/components/schemas/"));
+                }
+                map.values().for_each(|x| collect_refs(x, out));
+            }
+            Value::Array(items) => items.iter().for_each(|x| collect_refs(x, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn openapi_refs_resolve() {
+        for include_capture in [false, true] {
+            let spec = openapi_document(include_capture);
+            let schemas = spec["components"]["schemas"].as_object().unwrap();
+            let mut refs = Vec::new();
+            collect_refs(&spec, &mut refs);
+            for r in refs {
+                assert!(schemas.contains_key(r), "dangling $ref {r}");
+            }
+        }
+    }
+
+    #[test]
+    fn openapi_operation_ids_are_unique() {
+        let spec = openapi_document(true);
+        let mut ids = Vec::new();
+        for item in spec["paths"].as_object().unwrap().values() {
+            for operation in item.as_object().unwrap().values() {
+                ids.push(operation["operationId"].as_str().unwrap().to_string());
+            }
+        }
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn openapi_stream_events_cover_every_sse_name() {
+        let spec = openapi_document(false);
+        let events = spec["components"]["schemas"]["ServeStreamEvent"]["oneOf"]
+            .as_array()
+            .unwrap();
+        let names: Vec<&str> = events
+            .iter()
+            .map(|e| e["properties"]["event"]["const"].as_str().unwrap())
+            .collect();
+        let mut expected: Vec<&str> = AGENT_EVENT_TAGS.to_vec();
+        expected.extend(["error", "done"]);
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn openapi_stream_frames_conform() {
+        let event = aikit_sdk::AgentEvent {
+            agent_key: "codex".into(),
+            seq: 1,
+            stream: aikit_sdk::AgentEventStream::Stdout,
+            payload: aikit_sdk::AgentEventPayload::ToolResult {
+                call_id: "c1".into(),
+                output: json!({"stdout": "ok"}),
+                is_error: false,
+            },
+        };
+        // Same split `serve_event_to_sse` performs: tag → event, inner → data.
+        let payload = serde_json::to_value(&event.payload).unwrap();
+        let (tag, inner) = payload.as_object().unwrap().iter().next().unwrap();
+        assert_conforms("ServeStreamEvent", &json!({ "event": tag, "data": inner }));
+        assert_conforms(
+            "ServeStreamEvent",
+            &json!({ "event": "error", "data": { "code": "timeout", "message": "run timed out" } }),
+        );
+        assert_conforms(
+            "ServeStreamEvent",
+            &json!({ "event": "done", "data": { "exit_code": 0 } }),
+        );
+    }
+
+    #[test]
+    fn openapi_error_response_conforms() {
+        let body = json!({ "error": super::super::ErrorDetail {
+            code: "invalid_request".into(),
+            message: "bad".into(),
+        } });
+        assert_conforms("ErrorResponse", &body);
+    }
+
+    #[test]
+    fn openapi_served_document_omits_capture_without_adapters() {
+        let spec = openapi_document(false);
+        assert!(spec["paths"].get("/capture").is_none());
+        assert!(spec["paths"].get("/history/{backend}").is_some());
+        assert!(openapi_document(true)["paths"].get("/capture").is_some());
+    }
+}
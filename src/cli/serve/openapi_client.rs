@@ -0,0 +1,505 @@
+//! Generator for the typed TypeScript client in `clients/typescript/`,
+//! and the drift check that keeps the checked-in copy current.
+//!
+//! The client is derived entirely from [`openapi_document`]: one declaration
+//! per component schema, then `AikitServeClient` with one method per
+//! operation. Operations that answer both JSON and SSE get a second
+//! `<operationId>Stream` method. The hand-written runtime (fetch wrapper,
+//! SSE decoder) is embedded verbatim.
+//!
+//! Test-only: `AIKIT_UPDATE_CLIENT=1 cargo test openapi` rewrites
+//! `clients/typescript/{openapi.json,src/index.ts}`; without the variable
+//! the test fails when either file differs from what this build generates.
+
+use serde_json::{json, Map, Value};
+
+use super::openapi::openapi_document;
+
+const CLIENT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/clients/typescript");
+
+/// The document as checked in: pretty-printed with object keys sorted, so
+/// the file is stable whatever `serde_json`'s map ordering.
+fn render_openapi_json(spec: &Value) -> String {
+    let mut out = serde_json::to_string_pretty(&sorted(spec)).expect("serializable");
+    out.push('\n');
+    out
+}
+
+fn sorted(v: &Value) -> Value {
+    match v {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .map(|k| (k.clone(), sorted(&map[k])))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+        other => other.clone(),
+    }
+}
+
+fn sorted_keys(map: &Map<String, Value>) -> Vec<&String> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+fn ref_name(schema: &Value) -> Option<&str> {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .map(|r| r.trim_start_matches("#/components/schemas/"))
+}
+
+/// TypeScript type expression for `schema`.
+fn ts_type(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return name.to_string();
+    }
+    if let Some(c) = schema.get("const") {
+        return c.to_string();
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(options)) = schema.get(key) {
+            let mut parts: Vec<String> = Vec::new();
+            for o in options {
+                let t = ts_type(o);
+                if t == "unknown" {
+                    return t;
+                }
+                if !parts.contains(&t) {
+                    parts.push(t);
+                }
+            }
+            return parts.join(" | ");
+        }
+    }
+    match schema.get("type") {
+        Some(Value::Array(types)) => types
+            .iter()
+            .map(|t| ts_type(&json!({ "type": t })))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Some(Value::String(t)) => match t.as_str() {
+            "string" => "string".into(),
+            "integer" | "number" => "number".into(),
+            "boolean" => "boolean".into(),
+            "null" => "null".into(),
+            "array" => format!(
+                "Array<{}>",
+                schema
+                    .get("items")
+                    .map(ts_type)
+                    .unwrap_or_else(|| "unknown".into())
+            ),
+            "object" => ts_object(schema),
+            _ => "unknown".into(),
+        },
+        _ => "unknown".into(),
+    }
+}
+
+fn ts_object(schema: &Value) -> String {
+    match schema.get("properties").and_then(Value::as_object) {
+        Some(props) if !props.is_empty() => {
+            let fields: Vec<String> = sorted_keys(props)
+                .into_iter()
+                .map(|k| format!("{}{}: {}", k, optional_mark(schema, k), ts_type(&props[k])))
+                .collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        _ => match schema.get("additionalProperties") {
+            Some(extra @ Value::Object(_)) => format!("Record<string, {}>", ts_type(extra)),
+            _ => "Record<string, unknown>".into(),
+        },
+    }
+}
+
+fn optional_mark(schema: &Value, key: &str) -> &'static str {
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|r| r.iter().any(|k| k == key));
+    if required {
+        ""
+    } else {
+        "?"
+    }
+}
+
+fn doc_comment(out: &mut String, indent: &str, text: Option<&str>) {
+    if let Some(text) = text {
+        out.push_str(&format!("{indent}/** {} */\n", text.replace("*/", "*\\/")));
+    }
+}
+
+fn render_component(out: &mut String, name: &str, schema: &Value) {
+    doc_comment(out, "", schema.get("description").and_then(Value::as_str));
+    let props = schema.get("properties").and_then(Value::as_object);
+    if let (Some(props), Some("object")) = (props, schema.get("type").and_then(Value::as_str)) {
+        out.push_str(&format!("export interface {name} {{\n"));
+        for key in sorted_keys(props) {
+            let prop = &props[key];
+            doc_comment(out, "  ", prop.get("description").and_then(Value::as_str));
+            out.push_str(&format!(
+                "  {key}{}: {};\n",
+                optional_mark(schema, key),
+                ts_type(prop)
+            ));
+        }
+        out.push_str("}\n\n");
+        return;
+    }
+    let union = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array);
+    match union {
+        Some(options) if options.len() > 1 => {
+            out.push_str(&format!("export type {name} =\n"));
+            for o in options {
+                out.push_str(&format!("  | {}\n", ts_type(o)));
+            }
+            out.truncate(out.len() - 1);
+            out.push_str(";\n\n");
+        }
+        _ => out.push_str(&format!("export type {name} = {};\n\n", ts_type(schema))),
+    }
+}
+
+fn camel_case(s: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for ch in s.chars() {
+        if ch == '_' || ch == '-' {
+            upper = true;
+        } else if upper {
+            out.extend(ch.to_uppercase());
+            upper = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// What a client method resolves to.
+enum Returns {
+    Json(String),
+    Stream,
+    Empty,
+}
+
+fn success_returns(operation: &Value) -> Vec<Returns> {
+    let responses = operation["responses"].as_object().expect("responses");
+    let (_, ok) = sorted_keys(responses)
+        .into_iter()
+        .map(|k| (k, &responses[k]))
+        .find(|(k, _)| k.starts_with('2'))
+        .expect("a 2xx response");
+    let content = match ok.get("content").and_then(Value::as_object) {
+        Some(c) => c,
+        None => return vec![Returns::Empty],
+    };
+    let mut out = Vec::new();
+    if let Some(json) = content.get("application/json") {
+        out.push(Returns::Json(ts_type(&json["schema"])));
+    }
+    if content.contains_key("text/event-stream") {
+        out.push(Returns::Stream);
+    }
+    out
+}
+
+fn render_method(out: &mut String, path: &str, method: &str, operation: &Value) {
+    let id = operation["operationId"].as_str().expect("operationId");
+    let params = operation
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut args: Vec<String> = Vec::new();
+    let mut url = String::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        url.push('/');
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => {
+                let arg = camel_case(name);
+                url.push_str(&format!("${{encodeURIComponent({arg})}}"));
+                args.push(format!("{arg}: string"));
+            }
+            None => url.push_str(segment),
+        }
+    }
+
+    let mut options: Vec<&str> = Vec::new();
+    if let Some(body) = operation.get("requestBody") {
+        let ty = ts_type(&body["content"]["application/json"]["schema"]);
+        let required = body
+            .get("required")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        args.push(format!("body{}: {ty}", if required { "" } else { "?" }));
+        options.push("body");
+    }
+    let query: Vec<String> = params
+        .iter()
+        .filter(|p| p["in"] == "query")
+        .map(|p| {
+            format!(
+                "{}?: {}",
+                p["name"].as_str().unwrap(),
+                ts_type(&p["schema"])
+            )
+        })
+        .collect();
+    if !query.is_empty() {
+        args.push(format!("query: {{ {} }} = {{}}", query.join("; ")));
+        options.push("query");
+    }
+    let options = if options.is_empty() {
+        String::new()
+    } else {
+        format!(", {{ {} }}", options.join(", "))
+    };
+    let verb = method.to_uppercase();
+    let summary = operation.get("summary").and_then(Value::as_str);
+
+    let returns = success_returns(operation);
+    let streams_too = returns.len() > 1;
+    for r in returns {
+        let (name, ret, call) = match r {
+            Returns::Json(ty) => (
+                id.to_string(),
+                format!("Promise<{ty}>"),
+                format!("return this.json(\"{verb}\", `{url}`{options});"),
+            ),
+            Returns::Stream => (
+                if streams_too {
+                    format!("{id}Stream")
+                } else {
+                    id.to_string()
+                },
+                "Promise<ServeEventStream>".to_string(),
+                format!("return this.stream(\"{verb}\", `{url}`{options});"),
+            ),
+            Returns::Empty => (
+                id.to_string(),
+                "Promise<void>".to_string(),
+                format!("return this.empty(\"{verb}\", `{url}`{options});"),
+            ),
+        };
+        doc_comment(out, "  ", summary);
+        out.push_str(&format!(
+            "  async {name}({}): {ret} {{\n    {call}\n  }}\n\n",
+            args.join(", ")
+        ));
+    }
+}
+
+const TS_HEADER: &str = "\
+// Code generated from `aikit serve`'s OpenAPI document (GET /api/v1/openapi.json).
+// DO NOT EDIT. Regenerate with: AIKIT_UPDATE_CLIENT=1 cargo test openapi
+
+";
+
+const TS_RUNTIME_HEAD: &str = r#"/** A non-2xx response. `body` is the decoded JSON error body when there is one. */
+export class AikitServeError extends Error {
+  readonly status: number;
+  readonly body: unknown;
+
+  constructor(status: number, body: unknown) {
+    super(`aikit serve responded ${status}${errorCode(body) ? `: ${errorCode(body)}` : ""}`);
+    this.name = "AikitServeError";
+    this.status = status;
+    this.body = body;
+  }
+
+  /** Machine-readable code: `error.code`, or `error` on the history routes. */
+  get code(): string | undefined {
+    return errorCode(this.body);
+  }
+}
+
+function errorCode(body: unknown): string | undefined {
+  if (body === null || typeof body !== "object" || !("error" in body)) return undefined;
+  const error = (body as { error: unknown }).error;
+  if (typeof error === "string") return error;
+  if (error !== null && typeof error === "object" && typeof (error as { code?: unknown }).code === "string") {
+    return (error as { code: string }).code;
+  }
+  return undefined;
+}
+
+export interface AikitServeClientOptions {
+  /** Server origin, e.g. `http://127.0.0.1:8080`; `/api/v1` is appended. */
+  baseUrl: string;
+  /** Sent as `Authorization: Bearer <apiKey>` (servers started with `--api-key`). */
+  apiKey?: string;
+  fetch?: typeof fetch;
+}
+
+/** A streaming response: the `X-Session-Id` header and the decoded events. */
+export interface ServeEventStream {
+  sessionId: string | null;
+  events: AsyncGenerator<ServeStreamEvent>;
+}
+
+type QueryValue = string | number | boolean | null | undefined;
+
+interface RequestOptions {
+  body?: unknown;
+  query?: { [key: string]: QueryValue };
+  accept?: string;
+}
+
+export class AikitServeClient {
+  private readonly baseUrl: string;
+  private readonly apiKey?: string;
+  private readonly fetchImpl: typeof fetch;
+
+  constructor(options: AikitServeClientOptions) {
+    this.baseUrl = options.baseUrl.replace(/\/+$/, "") + "/api/v1";
+    this.apiKey = options.apiKey;
+    this.fetchImpl = options.fetch ?? globalThis.fetch.bind(globalThis);
+  }
+
+"#;
+
+const TS_RUNTIME_TAIL: &str = r#"  private async send(method: string, path: string, options: RequestOptions): Promise<Response> {
+    const url = new URL(this.baseUrl + path);
+    for (const [key, value] of Object.entries(options.query ?? {})) {
+      if (value !== undefined && value !== null) url.searchParams.set(key, String(value));
+    }
+    const headers: Record<string, string> = { Accept: options.accept ?? "application/json" };
+    if (this.apiKey) headers.Authorization = `Bearer ${this.apiKey}`;
+    let body: string | undefined;
+    if (options.body !== undefined) {
+      headers["Content-Type"] = "application/json";
+      body = JSON.stringify(options.body);
+    }
+    const response = await this.fetchImpl(url, { method, headers, body });
+    if (!response.ok) {
+      const text = await response.text();
+      let parsed: unknown = text;
+      try {
+        parsed = JSON.parse(text);
+      } catch {
+        // Not JSON (e.g. an extractor rejection); keep the text.
+      }
+      throw new AikitServeError(response.status, parsed);
+    }
+    return response;
+  }
+
+  private async json<T>(method: string, path: string, options: RequestOptions = {}): Promise<T> {
+    const response = await this.send(method, path, options);
+    return (await response.json()) as T;
+  }
+
+  private async empty(method: string, path: string, options: RequestOptions = {}): Promise<void> {
+    await this.send(method, path, options);
+  }
+
+  private async stream(method: string, path: string, options: RequestOptions = {}): Promise<ServeEventStream> {
+    const response = await this.send(method, path, { ...options, accept: "text/event-stream" });
+    if (response.body === null) throw new AikitServeError(response.status, "missing event stream body");
+    return { sessionId: response.headers.get("X-Session-Id"), events: parseServeEvents(response.body) };
+  }
+}
+
+/** Decode an `aikit serve` SSE body into typed events. Keep-alive comments are skipped. */
+export async function* parseServeEvents(body: ReadableStream<Uint8Array>): AsyncGenerator<ServeStreamEvent> {
+  const reader = body.pipeThrough(new TextDecoderStream()).getReader();
+  let buffer = "";
+  let event = "message";
+  let data: string[] = [];
+  try {
+    for (;;) {
+      const chunk = await reader.read();
+      if (chunk.done) break;
+      buffer += chunk.value;
+      let newline: number;
+      while ((newline = buffer.indexOf("\n")) >= 0) {
+        const line = buffer.slice(0, newline).replace(/\r$/, "");
+        buffer = buffer.slice(newline + 1);
+        if (line === "") {
+          if (data.length > 0) yield { event, data: JSON.parse(data.join("\n")) } as ServeStreamEvent;
+          event = "message";
+          data = [];
+        } else if (!line.startsWith(":")) {
+          const colon = line.indexOf(":");
+          const field = colon < 0 ? line : line.slice(0, colon);
+          let value = colon < 0 ? "" : line.slice(colon + 1);
+          if (value.startsWith(" ")) value = value.slice(1);
+          if (field === "event") event = value;
+          else if (field === "data") data.push(value);
+        }
+      }
+    }
+  } finally {
+    reader.releaseLock();
+  }
+}
+"#;
+
+/// Render the typed TypeScript client for `spec`: one declaration per
+/// component schema, then `AikitServeClient` with one method per operation
+/// (`<operationId>Stream` for the SSE side of dual-mode operations).
+fn render_typescript_client(spec: &Value) -> String {
+    let mut out = String::from(TS_HEADER);
+    let schemas = spec["components"]["schemas"]
+        .as_object()
+        .expect("components.schemas");
+    for name in sorted_keys(schemas) {
+        render_component(&mut out, name, &schemas[name]);
+    }
+
+    out.push_str(TS_RUNTIME_HEAD);
+    let paths = spec["paths"].as_object().expect("paths");
+    for path in sorted_keys(paths) {
+        for method in ["get", "post", "patch", "delete"] {
+            if let Some(operation) = paths[path].get(method) {
+                render_method(&mut out, path, method, operation);
+            }
+        }
+    }
+    out.push_str(TS_RUNTIME_TAIL);
+    out
+}
+
+/// The checked-in spec and TypeScript client match what this build
+/// generates. Set `AIKIT_UPDATE_CLIENT=1` to rewrite them.
+#[test]
+fn openapi_client_is_up_to_date() {
+    let spec = openapi_document(true);
+    let files = [
+        ("openapi.json", render_openapi_json(&spec)),
+        ("src/index.ts", render_typescript_client(&spec)),
+    ];
+    let update = std::env::var_os("AIKIT_UPDATE_CLIENT").is_some();
+    for (rel, generated) in files {
+        let path = std::path::Path::new(CLIENT_DIR).join(rel);
+        if update {
+            std::fs::write(&path, &generated).unwrap();
+            continue;
+        }
+        let checked_in = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            checked_in == generated,
+            "{} is stale; regenerate with `AIKIT_UPDATE_CLIENT=1 cargo test openapi`",
+            path.display()
+        );
+    }
+}
@@ -15,6 +15,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use aikit_sdk::handoff::{Handoff, HandoffError, HandoffSource};
+use aikit_sdk::history::HistoryError;
 use aikit_sdk::session_store::{SessionStore, SessionStoreError};
 use aikit_sdk::{get_agent_status, AgentEvent, AgentEventPayload, RunError, RunOptions};
 
@@ -275,6 +277,7 @@ where
 }
 
 #[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
 #[serde(deny_unknown_fields)]
 pub(super) struct SendMessageRequest {
     pub agent: String,
@@ -298,6 +301,11 @@ pub(super) struct SendMessageRequest {
     /// above (`AgentPersona.disallowed_tools`).
     #[serde(default)]
     pub disallowed_tools: Option<Vec<String>>,
+    /// Cross-agent handoff: `<backend>:<session_id>` of another agent's
+    /// session to continue in this run (`aikit_sdk::handoff`). Starts a new
+    /// session, so it cannot be combined with `session_id`.
+    #[serde(default)]
+    pub continue_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -430,6 +438,37 @@ pub(super) fn validate_request(
             ),
         ));
     }
+    // Cross-agent handoff seeds a *new* session from another agent's history,
+    // so it needs a source with a readable history store and no resume id.
+    if let Some(ref source) = body.continue_from {
+        if body.session_id.is_some() {
+            return Some(error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_request",
+                "continue_from starts a new session; omit session_id",
+            ));
+        }
+        match source.parse::<HandoffSource>() {
+            Err(e) => {
+                return Some(error_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "invalid_request",
+                    &format!("continue_from: {}", e),
+                ));
+            }
+            Ok(source) if !source.backend.capabilities().history_store => {
+                return Some(error_response(
+                    StatusCode::CONFLICT,
+                    "history_unsupported",
+                    &format!(
+                        "continue_from: backend '{}' has no session history",
+                        source.backend.key()
+                    ),
+                ));
+            }
+            Ok(_) => {}
+        }
+    }
     if let Some(ref sid) = body.session_id {
         let in_memory = state.runs.lock().unwrap().contains_key(sid.as_str());
         if !in_memory {
@@ -901,6 +940,10 @@ pub(super) async fn messages_handler(
     if let Some(err) = validate_request(&body, &state, &runnable) {
         return err;
     }
+    let body = match apply_continue_from(body).await {
+        Ok(body) => body,
+        Err(resp) => return resp,
+    };
     let (rx, server_session_id) = match spawn_run(&state, &body) {
         Ok(v) => v,
         Err(resp) => return resp,
@@ -925,6 +968,75 @@ pub(super) async fn messages_handler(
     }
 }
 
+/// Cross-agent handoff: replace `continue_from` with the seeded run — the
+/// handoff prompt for an external CLI, or for `aikit` a new session holding
+/// the source transcript, resumed through the ordinary `session_id` path.
+/// Reading history and writing the seed touch disk, so both run on the
+/// blocking pool.
+#[allow(clippy::result_large_err)]
+async fn apply_continue_from(mut body: SendMessageRequest) -> Result<SendMessageRequest, Response> {
+    let Some(source) = body.continue_from.take() else {
+        return Ok(body);
+    };
+    let source: HandoffSource = source.parse().map_err(|e: HandoffError| {
+        error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_request",
+            &format!("continue_from: {}", e),
+        )
+    })?;
+    let Some(target) = aikit_sdk::runner::Backend::from_key(&body.agent) else {
+        return Ok(body);
+    };
+    let content = body.content.clone();
+    let seeded = tokio::task::spawn_blocking(move || {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        Handoff::load(source).and_then(|h| h.seed(target, &content, &cwd))
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("handoff task failed: {}", e);
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            "Internal error",
+        )
+    })?;
+    match seeded {
+        Ok(seed) => {
+            body.content = seed.prompt;
+            body.session_id = seed.session_id;
+            Ok(body)
+        }
+        Err(HandoffError::History(HistoryError::NotFound { session_id })) => Err(error_response(
+            StatusCode::NOT_FOUND,
+            "session_not_found",
+            &format!("continue_from: session '{}' not found", session_id),
+        )),
+        Err(HandoffError::History(HistoryError::InvalidId { session_id })) => Err(error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_request",
+            &format!("continue_from: invalid session id '{}'", session_id),
+        )),
+        Err(HandoffError::Unsupported { backend }) => Err(error_response(
+            StatusCode::CONFLICT,
+            "history_unsupported",
+            &format!(
+                "continue_from: backend '{}' has no session history",
+                backend.key()
+            ),
+        )),
+        Err(e) => {
+            tracing::error!("handoff failed: {}", e);
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                "Failed to load the continue_from session",
+            ))
+        }
+    }
+}
+
 /// Drain the receiver and return a single JSON body.
 async fn sync_response(
     mut rx: tokio::sync::mpsc::Receiver<ServeEvent>,
@@ -1314,6 +1426,7 @@ mod tests {
                 yolo: false,
                 tools: None,
                 disallowed_tools: None,
+                continue_from: None,
             };
             assert!(
                 validate_request(&body, &state, &[]).is_some(),
@@ -1371,6 +1484,7 @@ mod tests {
                 yolo: false,
                 tools: None,
                 disallowed_tools: None,
+                continue_from: None,
             };
             let resp = validate_request(&body, &state, &runnable);
             assert!(
@@ -1393,6 +1507,7 @@ mod tests {
             yolo: false,
             tools: None,
             disallowed_tools: None,
+            continue_from: None,
         };
         let resp = validate_request(&body, &state, &runnable);
         std::env::remove_var("AIKIT_SESSIONS_DIR");
@@ -1447,6 +1562,7 @@ mod tests {
             yolo: false,
             tools,
             disallowed_tools: None,
+            continue_from: None,
         };
 
         // A tool policy on an external CLI backend is rejected loud (422), not
@@ -1481,6 +1597,73 @@ mod tests {
         );
     }
 
+    // --- cross-agent handoff: continue_from is validated up front ---
+
+    #[test]
+    fn continue_from_is_validated() {
+        use crate::cli::serve::ServeConfig;
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        let state = AppState {
+            runs: Arc::new(Mutex::new(HashMap::new())),
+            live_sessions: Arc::new(Mutex::new(HashMap::new())),
+            pending_live_sessions: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            config: ServeConfig {
+                host: "127.0.0.1".into(),
+                port: 8787,
+                run_timeout_secs: 30,
+                max_sessions: 10,
+                api_key: None,
+                insecure: false,
+            },
+            run_fn: make_stub_run_fn(),
+            auth_cache: Arc::new(Mutex::new(None)),
+        };
+        let runnable = vec![AgentInfo {
+            key: "codex".to_string(),
+            name: "codex".to_string(),
+            available: true,
+            auth: AuthStatus::Unknown,
+            capabilities: None,
+        }];
+        let make = |session_id: Option<&str>, continue_from: &str| SendMessageRequest {
+            agent: "codex".into(),
+            session_id: session_id.map(str::to_string),
+            content: "hi".into(),
+            model: None,
+            yolo: false,
+            tools: None,
+            disallowed_tools: None,
+            continue_from: Some(continue_from.into()),
+        };
+        let status = |body: &SendMessageRequest| {
+            validate_request(body, &state, &runnable).map(|r| r.status())
+        };
+
+        assert_eq!(
+            status(&make(None, "claude")),
+            Some(StatusCode::UNPROCESSABLE_ENTITY),
+            "missing ':' is malformed"
+        );
+        assert_eq!(
+            status(&make(None, "nope:abc")),
+            Some(StatusCode::UNPROCESSABLE_ENTITY),
+            "unknown backend is malformed"
+        );
+        assert_eq!(
+            status(&make(Some("abc"), "codex:abc")),
+            Some(StatusCode::UNPROCESSABLE_ENTITY),
+            "continue_from cannot resume an existing session"
+        );
+        assert_eq!(
+            status(&make(None, "gemini:abc")),
+            Some(StatusCode::CONFLICT),
+            "a backend without history cannot be continued from"
+        );
+        assert_eq!(status(&make(None, "codex:abc")), None);
+    }
+
     // --- BUG-10: a Closed session must not be resurrectable via POST ---
 
     #[test]
@@ -1532,6 +1715,7 @@ mod tests {
             yolo: false,
             tools: None,
             disallowed_tools: None,
+            continue_from: None,
         };
 
         let err = spawn_run(&state, &body).expect_err("a Closed session must reject resume");
@@ -1559,4 +1743,104 @@ mod tests {
         );
         assert_eq!(classify_error_code(""), "agent_error");
     }
+
+    #[test]
+    fn responses_conform_to_openapi() {
+        use super::super::openapi::{assert_conforms, assert_type_matches};
+
+        let agents = ListAgentsResponse {
+            agents: vec![
+                AgentInfo {
+                    key: "codex".into(),
+                    name: "Codex".into(),
+                    available: true,
+                    auth: AuthStatus::Ok,
+                    capabilities: Some(aikit_sdk::runner::Backend::Codex.capabilities()),
+                },
+                AgentInfo {
+                    key: "other".into(),
+                    name: "Other".into(),
+                    available: true,
+                    auth: AuthStatus::Unknown,
+                    capabilities: None,
+                },
+            ],
+        };
+        assert_type_matches("ListAgentsResponse", &agents);
+        assert_type_matches("AgentInfo", &agents.agents[0]);
+
+        let now = Utc::now();
+        let runs = ListRunsResponse {
+            sessions: vec![RunSummary {
+                session_id: "s1".into(),
+                agent: "codex".into(),
+                status: RunStatus::Idle,
+                started_at: now,
+                last_active_at: now,
+            }],
+        };
+        assert_type_matches("ListRunsResponse", &runs);
+        assert_type_matches("RunSummary", &runs.sessions[0]);
+        let deleted = DeleteSessionResponse {
+            session_id: "s1".into(),
+            status: RunStatus::Closed,
+        };
+        assert_type_matches("DeleteSessionResponse", &deleted);
+
+        for (error, usage) in [
+            (None, None),
+            (
+                Some(super::super::ErrorDetail {
+                    code: "timeout".into(),
+                    message: "run timed out".into(),
+                }),
+                Some(UsageSummary {
+                    input_tokens: 3,
+                    output_tokens: 2,
+                    cache_read_tokens: Some(1),
+                }),
+            ),
+        ] {
+            let sync = SyncMessageResponse {
+                session_id: None,
+                content: "hello".into(),
+                exit_code: 0,
+                error,
+                usage,
+            };
+            if sync.usage.is_some() {
+                assert_type_matches("SyncMessageResponse", &sync);
+                assert_type_matches("UsageSummary", sync.usage.as_ref().unwrap());
+            } else {
+                assert_conforms("SyncMessageResponse", &serde_json::to_value(&sync).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn send_message_request_matches_openapi() {
+        use super::super::openapi::{assert_conforms, assert_type_matches};
+
+        let request = SendMessageRequest::default();
+        assert_type_matches("SendMessageRequest", &request);
+        let body = serde_json::to_value(&request).unwrap();
+        assert!(serde_json::from_value::<SendMessageRequest>(body).is_ok());
+        let filled = SendMessageRequest {
+            agent: "codex".into(),
+            content: "hi".into(),
+            model: Some("gpt-5".into()),
+            yolo: true,
+            tools: Some(vec!["read_file".into()]),
+            continue_from: Some("claude:0f5c2a4e".into()),
+            ..Default::default()
+        };
+        assert_type_matches("SendMessageRequest", &filled);
+
+        // `deny_unknown_fields` ↔ `additionalProperties: false`.
+        let minimal = serde_json::json!({ "agent": "codex", "content": "hi" });
+        assert_conforms("SendMessageRequest", &minimal);
+        assert!(serde_json::from_value::<SendMessageRequest>(minimal).is_ok());
+        let extra = serde_json::json!({ "agent": "codex", "content": "hi", "stream": true });
+        assert!(serde_json::from_value::<SendMessageRequest>(extra).is_err());
+    }
 }
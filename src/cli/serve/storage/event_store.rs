@@ -13,9 +13,12 @@ use async_trait::async_trait;
 use rusqlite::{params, Connection};
 use serde_json::Value;
 
+use aikit_session_capture::search::{
+    self, SearchTerm, SNIPPET_CLOSE, SNIPPET_ELLIPSIS, SNIPPET_OPEN, SNIPPET_WORDS,
+};
 use aikit_session_capture::{
     ActionKind, ActionStatus, CacheObservation, CaptureSource, EventBatch, EventStore, FileTouch,
-    SessionSummary, StoreError, TokenEvent, ToolEvent, ToolKind,
+    SearchMatch, SearchQuery, SessionSummary, StoreError, TokenEvent, ToolEvent, ToolKind,
 };
 
 /// Map a rusqlite error into the crate-agnostic [`StoreError::Backend`] variant.
@@ -56,8 +59,16 @@ impl EventStore for SqliteEventStore {
                         started_at_ms, duration_ms, git_root, metadata
                     ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 )?;
+                // Only rows that were actually inserted get indexed, so a
+                // re-walk never duplicates FTS entries.
+                let mut fts = tx.prepare(
+                    r#"INSERT INTO capture_tool_events_fts (
+                        target, input, output, error_message,
+                        source_file, source_event_id
+                    ) VALUES (?, ?, ?, ?, ?, ?)"#,
+                )?;
                 for ev in &events.tool_events {
-                    inserted += stmt.execute(params![
+                    let n = stmt.execute(params![
                         ev.source_file.to_string_lossy(),
                         &ev.source_event_id,
                         &ev.session_id,
@@ -74,7 +85,18 @@ impl EventStore for SqliteEventStore {
                             .as_deref()
                             .map(|s| s.to_string_lossy().into_owned()),
                         ev.metadata.to_string(),
-                    ])? as u64;
+                    ])?;
+                    if n > 0 {
+                        fts.execute(params![
+                            ev.target.as_deref(),
+                            ev.input.as_deref(),
+                            ev.output.as_deref(),
+                            ev.error_message.as_deref(),
+                            ev.source_file.to_string_lossy(),
+                            &ev.source_event_id,
+                        ])?;
+                    }
+                    inserted += n as u64;
                 }
             }
 
@@ -112,11 +134,11 @@ impl EventStore for SqliteEventStore {
             {
                 let mut stmt = tx.prepare(
                     r#"INSERT OR IGNORE INTO capture_cache_observations (
-                        source_file, source_event_id, session_id, tool,
+                        source_file, source_event_id, session_id, tool, model,
                         cache_read_input_tokens, cache_creation_input_tokens,
                         cache_creation_1h_input_tokens, assistant_blocks_hash,
-                        tools_changed, observed_at_ms
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                        tools_changed, prompt_fingerprint, observed_at_ms
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 )?;
                 for ev in &events.cache_observations {
                     let source_file =
@@ -126,11 +148,13 @@ impl EventStore for SqliteEventStore {
                         &ev.source_event_id,
                         &ev.session_id,
                         ev.tool.as_str(),
+                        ev.model.as_deref(),
                         ev.cache_read_input_tokens.map(|v| v as i64),
                         ev.cache_creation_input_tokens.map(|v| v as i64),
                         ev.cache_creation_1h_input_tokens.map(|v| v as i64),
                         ev.assistant_blocks_hash.as_deref(),
                         serde_json::to_string(&ev.tools_changed).unwrap_or_else(|_| "[]".into()),
+                        ev.prompt_fingerprint.as_deref(),
                         ev.observed_at_ms,
                     ])? as u64;
                 }
@@ -231,34 +255,10 @@ impl EventStore for SqliteEventStore {
                    WHERE tool = ?1 AND session_id = ?2
                    ORDER BY started_at_ms ASC LIMIT ?3 OFFSET ?4"#,
                 )?;
-                let iter =
-                    stmt.query_map(params![tool.as_str(), &session_id, limit, offset], |row| {
-                        let metadata_str: String = row.get(13)?;
-                        let metadata: Value =
-                            serde_json::from_str(&metadata_str).unwrap_or(Value::Null);
-                        let duration_ms: Option<i64> = row.get(11)?;
-                        Ok(ToolEvent {
-                            source_event_id: row.get(0)?,
-                            source_file: PathBuf::from(row.get::<_, String>(1)?),
-                            session_id: row.get(2)?,
-                            tool,
-                            kind: parse_action_kind(&row.get::<_, String>(3)?),
-                            target: row.get::<_, Option<String>>(4)?.filter(|s| !s.is_empty()),
-                            input: row.get::<_, Option<String>>(5)?.filter(|s| !s.is_empty()),
-                            output: row.get::<_, Option<String>>(6)?.filter(|s| !s.is_empty()),
-                            status: parse_action_status(&row.get::<_, String>(7)?),
-                            error_message: row
-                                .get::<_, Option<String>>(8)?
-                                .filter(|s| !s.is_empty()),
-                            started_at_ms: row.get(9)?,
-                            duration_ms: duration_ms.map(|d| d as u64),
-                            git_root: row
-                                .get::<_, Option<String>>(12)?
-                                .filter(|s| !s.is_empty())
-                                .map(PathBuf::from),
-                            metadata,
-                        })
-                    })?;
+                let iter = stmt.query_map(
+                    params![tool.as_str(), &session_id, limit, offset],
+                    tool_event_from_row,
+                )?;
                 iter.collect()
             })
             .await
@@ -281,32 +281,81 @@ impl EventStore for SqliteEventStore {
                    WHERE output LIKE ?1
                    ORDER BY started_at_ms DESC LIMIT ?2"#,
                 )?;
-                let iter = stmt.query_map(params![&query, limit], |row| {
-                    let tool_str: String = row.get(3)?;
-                    let metadata_str: String = row.get(13)?;
-                    let metadata: Value =
-                        serde_json::from_str(&metadata_str).unwrap_or(Value::Null);
-                    let duration_ms: Option<i64> = row.get(11)?;
-                    Ok(ToolEvent {
-                        source_event_id: row.get(0)?,
-                        source_file: PathBuf::from(row.get::<_, String>(1)?),
-                        session_id: row.get(2)?,
-                        tool: parse_tool_kind(&tool_str),
-                        kind: parse_action_kind(&row.get::<_, String>(4)?),
-                        target: row.get::<_, Option<String>>(5)?.filter(|s| !s.is_empty()),
-                        input: row.get::<_, Option<String>>(6)?.filter(|s| !s.is_empty()),
-                        output: row.get::<_, Option<String>>(7)?.filter(|s| !s.is_empty()),
-                        status: parse_action_status(&row.get::<_, String>(8)?),
-                        error_message: row.get::<_, Option<String>>(9)?.filter(|s| !s.is_empty()),
-                        started_at_ms: row.get(10)?,
-                        duration_ms: duration_ms.map(|d| d as u64),
-                        git_root: row
-                            .get::<_, Option<String>>(12)?
-                            .filter(|s| !s.is_empty())
-                            .map(PathBuf::from),
-                        metadata,
-                    })
-                })?;
+                let iter = stmt.query_map(params![&query, limit], tool_event_from_row)?;
+                iter.collect()
+            })
+            .await
+            .map_err(|e| StoreError::Backend(format!("join error: {e}")))?
+            .map_err(sqlite_err)?;
+        Ok(rows)
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchMatch>, StoreError> {
+        let conn = self.conn.clone();
+        let query = query.clone();
+        let rows =
+            tokio::task::spawn_blocking(move || -> Result<Vec<SearchMatch>, rusqlite::Error> {
+                let conn = conn.lock().unwrap();
+                let terms = query.terms();
+                // ?1 is the MATCH expression (NULL, and only checked as such,
+                // without terms); the filters take ?2..?7, the page ?8/?9.
+                let filters = "(?2 IS NULL OR e.tool = ?2) AND (?3 IS NULL OR e.kind = ?3) \
+                     AND (?4 IS NULL OR e.session_id = ?4) \
+                     AND (?5 IS NULL OR e.started_at_ms >= ?5) \
+                     AND (?6 IS NULL OR e.started_at_ms < ?6) \
+                     AND (?7 IS NULL OR e.git_root = ?7)";
+                let sql = if terms.is_empty() {
+                    format!(
+                        "SELECT {TOOL_EVENT_COLUMNS}, NULL, 0.0 FROM capture_tool_events e \
+                         WHERE ?1 IS NULL AND {filters} \
+                         ORDER BY e.started_at_ms DESC LIMIT ?8 OFFSET ?9"
+                    )
+                } else {
+                    // bm25 is lower-is-better; weights favour the target
+                    // (paths, URLs, commands) and error messages.
+                    format!(
+                        "SELECT {TOOL_EVENT_COLUMNS}, \
+                         snippet(capture_tool_events_fts, -1, '{SNIPPET_OPEN}', '{SNIPPET_CLOSE}', \
+                                 '{SNIPPET_ELLIPSIS}', {SNIPPET_WORDS}), \
+                         -bm25(capture_tool_events_fts, 4.0, 1.0, 1.0, 2.0) AS score \
+                         FROM capture_tool_events_fts \
+                         JOIN capture_tool_events e \
+                           ON e.source_file = capture_tool_events_fts.source_file \
+                          AND e.source_event_id = capture_tool_events_fts.source_event_id \
+                         WHERE capture_tool_events_fts MATCH ?1 AND {filters} \
+                         ORDER BY score DESC, e.started_at_ms DESC LIMIT ?8 OFFSET ?9"
+                    )
+                };
+                let expr = (!terms.is_empty()).then(|| fts_match_expr(&terms));
+                let mut stmt = conn.prepare(&sql)?;
+                let iter = stmt.query_map(
+                    params![
+                        expr,
+                        query.tool.map(|t| t.as_str()),
+                        query.kind.map(|k| k.as_str()),
+                        query.session_id,
+                        query.since_ms,
+                        query.until_ms,
+                        query
+                            .git_root
+                            .as_deref()
+                            .map(|p| p.to_string_lossy().into_owned()),
+                        query.limit,
+                        query.offset,
+                    ],
+                    |row| {
+                        let event = tool_event_from_row(row)?;
+                        let snippet = match row.get::<_, Option<String>>(14)? {
+                            Some(s) => s,
+                            None => search::leading_snippet(&event),
+                        };
+                        Ok(SearchMatch {
+                            event,
+                            snippet,
+                            score: row.get(15)?,
+                        })
+                    },
+                )?;
                 iter.collect()
             })
             .await
@@ -366,6 +415,72 @@ impl EventStore for SqliteEventStore {
         Ok(rows)
     }
 
+    async fn cache_observations_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+    ) -> Result<Vec<CacheObservation>, StoreError> {
+        let conn = self.conn.clone();
+        let session_id = session_id.to_string();
+        let rows = tokio::task::spawn_blocking(
+            move || -> Result<Vec<CacheObservation>, rusqlite::Error> {
+                let conn = conn.lock().unwrap();
+                let mut stmt = conn.prepare(
+                    r#"SELECT source_event_id, session_id, model,
+                          cache_read_input_tokens, cache_creation_input_tokens,
+                          cache_creation_1h_input_tokens, assistant_blocks_hash,
+                          tools_changed, prompt_fingerprint, observed_at_ms
+                   FROM capture_cache_observations
+                   WHERE tool = ?1 AND session_id = ?2
+                   ORDER BY observed_at_ms ASC"#,
+                )?;
+                let iter = stmt.query_map(params![tool.as_str(), &session_id], |row| {
+                    let read: Option<i64> = row.get(3)?;
+                    let creation: Option<i64> = row.get(4)?;
+                    let creation_1h: Option<i64> = row.get(5)?;
+                    let tools_changed: String = row.get(7)?;
+                    Ok(CacheObservation {
+                        source_event_id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        tool,
+                        model: row.get::<_, Option<String>>(2)?.filter(|s| !s.is_empty()),
+                        cache_read_input_tokens: read.map(|v| v as u64),
+                        cache_creation_input_tokens: creation.map(|v| v as u64),
+                        cache_creation_1h_input_tokens: creation_1h.map(|v| v as u64),
+                        assistant_blocks_hash: row.get(6)?,
+                        tools_changed: serde_json::from_str(&tools_changed).unwrap_or_default(),
+                        prompt_fingerprint: row.get(8)?,
+                        observed_at_ms: row.get(9)?,
+                    })
+                })?;
+                iter.collect()
+            },
+        )
+        .await
+        .map_err(|e| StoreError::Backend(format!("join error: {e}")))?
+        .map_err(sqlite_err)?;
+        Ok(rows)
+    }
+
+    async fn usage_sessions_for(&self, tool: ToolKind) -> Result<Vec<String>, StoreError> {
+        let conn = self.conn.clone();
+        let rows = tokio::task::spawn_blocking(move || -> Result<Vec<String>, rusqlite::Error> {
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                r#"SELECT session_id FROM capture_token_events WHERE tool = ?1
+                   UNION
+                   SELECT session_id FROM capture_cache_observations WHERE tool = ?1
+                   ORDER BY session_id"#,
+            )?;
+            let iter = stmt.query_map(params![tool.as_str()], |row| row.get(0))?;
+            iter.collect()
+        })
+        .await
+        .map_err(|e| StoreError::Backend(format!("join error: {e}")))?
+        .map_err(sqlite_err)?;
+        Ok(rows)
+    }
+
     async fn last_file_touch(&self, path: &Path) -> Result<Option<FileTouch>, StoreError> {
         let conn = self.conn.clone();
         let path_str = path.to_string_lossy().into_owned();
@@ -406,6 +521,54 @@ impl EventStore for SqliteEventStore {
 
 // ── helpers ───────────────────────────────────────────────────────────────────
 
+/// Column list [`tool_event_from_row`] reads, in order, from `e`.
+const TOOL_EVENT_COLUMNS: &str = "e.source_event_id, e.source_file, e.session_id, e.tool, e.kind, \
+     e.target, e.input, e.output, e.status, e.error_message, \
+     e.started_at_ms, e.duration_ms, e.git_root, e.metadata";
+
+/// Build a [`ToolEvent`] from the first 14 columns of `row`, laid out as
+/// [`TOOL_EVENT_COLUMNS`].
+fn tool_event_from_row(row: &rusqlite::Row) -> rusqlite::Result<ToolEvent> {
+    let tool_str: String = row.get(3)?;
+    let metadata_str: String = row.get(13)?;
+    let metadata: Value = serde_json::from_str(&metadata_str).unwrap_or(Value::Null);
+    let duration_ms: Option<i64> = row.get(11)?;
+    Ok(ToolEvent {
+        source_event_id: row.get(0)?,
+        source_file: PathBuf::from(row.get::<_, String>(1)?),
+        session_id: row.get(2)?,
+        tool: parse_tool_kind(&tool_str),
+        kind: parse_action_kind(&row.get::<_, String>(4)?),
+        target: row.get::<_, Option<String>>(5)?.filter(|s| !s.is_empty()),
+        input: row.get::<_, Option<String>>(6)?.filter(|s| !s.is_empty()),
+        output: row.get::<_, Option<String>>(7)?.filter(|s| !s.is_empty()),
+        status: parse_action_status(&row.get::<_, String>(8)?),
+        error_message: row.get::<_, Option<String>>(9)?.filter(|s| !s.is_empty()),
+        started_at_ms: row.get(10)?,
+        duration_ms: duration_ms.map(|d| d as u64),
+        git_root: row
+            .get::<_, Option<String>>(12)?
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from),
+        metadata,
+    })
+}
+
+/// FTS5 MATCH expression for parsed terms: each term becomes a quoted
+/// phrase (`*` appended for prefixes), implicitly ANDed. Term words are
+/// letters and digits only, so quoting needs no escaping and FTS5 operators
+/// in the user's text (`OR`, `NEAR`, `-`) are matched as plain words.
+fn fts_match_expr(terms: &[SearchTerm]) -> String {
+    terms
+        .iter()
+        .map(|t| {
+            let star = if t.prefix { "*" } else { "" };
+            format!("\"{}\"{star}", t.words.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Token events don't carry their own `source_file`; derive it from the batch's
 /// tool events (same session).
 fn derive_token_source_file(tool_events: &[ToolEvent], token: &TokenEvent) -> String {
@@ -584,6 +747,33 @@ mod tests {
         assert_eq!(got.len(), 2);
     }
 
+    #[tokio::test]
+    async fn actions_for_session_round_trips_every_column() {
+        let store =
+            SqliteEventStore::new(crate::cli::serve::storage::schema::open_in_memory().unwrap());
+        let mut ev = sample_tool_event("1", "s1");
+        ev.kind = ActionKind::Edit;
+        ev.output = None;
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![ev],
+                token_events: vec![],
+                cache_observations: vec![],
+            })
+            .await
+            .unwrap();
+        let got = store
+            .actions_for_session(ToolKind::ClaudeCode, "s1", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].kind, ActionKind::Edit);
+        assert_eq!(got[0].target.as_deref(), Some("/tmp/file.go"));
+        assert_eq!(got[0].output, None);
+        assert_eq!(got[0].status, ActionStatus::Success);
+        assert_eq!(got[0].started_at_ms, Some(1000));
+    }
+
     #[tokio::test]
     async fn search_outputs_matches_substring() {
         let store =
@@ -599,4 +789,194 @@ mod tests {
         let got = store.search_outputs("package", 10).await.unwrap();
         assert_eq!(got.len(), 1);
     }
+
+    #[tokio::test]
+    async fn search_ranks_filters_and_highlights() {
+        let store =
+            SqliteEventStore::new(crate::cli::serve::storage::schema::open_in_memory().unwrap());
+        let mut a = sample_tool_event("1", "s1");
+        a.kind = ActionKind::Bash;
+        a.target = Some("cargo test".into());
+        a.output = Some("test result: FAILED. 1 passed; 2 failed".into());
+        a.started_at_ms = Some(100);
+        let mut b = sample_tool_event("2", "s2");
+        b.kind = ActionKind::Bash;
+        b.target = Some("cargo build".into());
+        b.output = Some("error: build failed".into());
+        b.started_at_ms = Some(200);
+        let batch = EventBatch {
+            tool_events: vec![a, b, sample_tool_event("3", "s2")],
+            token_events: vec![],
+            cache_observations: vec![],
+        };
+        store.upsert_events(batch.clone()).await.unwrap();
+        // A re-walk must not duplicate index rows.
+        store.upsert_events(batch).await.unwrap();
+
+        let got = store.search(&SearchQuery::new("fail*")).await.unwrap();
+        let ids: Vec<_> = got
+            .iter()
+            .map(|m| m.event.source_event_id.as_str())
+            .collect();
+        assert_eq!(ids, ["1", "2"]);
+        assert!(got[0].score > got[1].score);
+        assert!(got[0].snippet.contains("<mark>FAILED</mark>"));
+        assert_eq!(got[0].event.kind, ActionKind::Bash);
+
+        let mut q = SearchQuery::new("\"test result\"");
+        q.session_id = Some("s2".into());
+        assert!(store.search(&q).await.unwrap().is_empty());
+
+        let mut q = SearchQuery::new("cargo");
+        q.since_ms = Some(150);
+        let got = store.search(&q).await.unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].event.source_event_id, "2");
+
+        // Operators in user text are plain words, not FTS5 syntax.
+        assert!(store
+            .search(&SearchQuery::new("cargo OR NEAR(x"))
+            .await
+            .unwrap()
+            .is_empty());
+
+        let mut q = SearchQuery::new("");
+        q.kind = Some(ActionKind::Read);
+        let got = store.search(&q).await.unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].snippet, "package main");
+    }
+
+    #[tokio::test]
+    async fn migrate_backfills_index_for_existing_events() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(crate::cli::serve::storage::schema::MIGRATION_SQL)
+            .unwrap();
+        conn.execute(
+            "INSERT INTO capture_tool_events \
+             (source_file, source_event_id, session_id, tool, kind, output, status) \
+             VALUES ('/tmp/old.jsonl', 'e1', 's1', 'codex', 'bash', 'segmentation fault', 'failure')",
+            [],
+        )
+        .unwrap();
+        crate::cli::serve::storage::schema::migrate(&mut conn).unwrap();
+        // Running it again is a no-op.
+        crate::cli::serve::storage::schema::migrate(&mut conn).unwrap();
+
+        let store = SqliteEventStore::new(Arc::new(Mutex::new(conn)));
+        let got = store
+            .search(&SearchQuery::new("segmentation"))
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].event.tool, ToolKind::Codex);
+    }
+
+    #[tokio::test]
+    async fn cache_observations_round_trip_after_v1_migration() {
+        // A version-1 DB predates the `model` / `prompt_fingerprint` columns.
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE capture_cache_observations (
+                source_file TEXT NOT NULL, source_event_id TEXT NOT NULL,
+                session_id TEXT NOT NULL, tool TEXT NOT NULL,
+                cache_read_input_tokens INTEGER, cache_creation_input_tokens INTEGER,
+                cache_creation_1h_input_tokens INTEGER, assistant_blocks_hash TEXT,
+                tools_changed TEXT NOT NULL DEFAULT '[]', observed_at_ms INTEGER NOT NULL,
+                PRIMARY KEY (source_file, source_event_id));
+             PRAGMA user_version = 1;",
+        )
+        .unwrap();
+        crate::cli::serve::storage::schema::migrate(&mut conn).unwrap();
+
+        let store = SqliteEventStore::new(Arc::new(Mutex::new(conn)));
+        let obs = |id: &str, at: i64| CacheObservation {
+            source_event_id: id.into(),
+            session_id: "s1".into(),
+            tool: ToolKind::ClaudeCode,
+            model: Some("claude-sonnet-4-5".into()),
+            cache_read_input_tokens: Some(900),
+            cache_creation_input_tokens: Some(100),
+            cache_creation_1h_input_tokens: None,
+            assistant_blocks_hash: None,
+            tools_changed: vec!["mcp__github__create_issue".into()],
+            prompt_fingerprint: Some("claude-code/2.0.14".into()),
+            observed_at_ms: at,
+        };
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![sample_tool_event("1", "s1")],
+                token_events: vec![],
+                cache_observations: vec![obs("c2", 20), obs("c1", 10)],
+            })
+            .await
+            .unwrap();
+        let got = store
+            .cache_observations_for_session(ToolKind::ClaudeCode, "s1")
+            .await
+            .unwrap();
+        let ids: Vec<_> = got.iter().map(|o| o.source_event_id.as_str()).collect();
+        assert_eq!(ids, ["c1", "c2"]);
+        assert_eq!(got[0].model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(got[0].tools_changed, ["mcp__github__create_issue"]);
+        assert_eq!(
+            got[0].prompt_fingerprint.as_deref(),
+            Some("claude-code/2.0.14")
+        );
+        assert_eq!(got[0].cache_creation_1h_input_tokens, None);
+    }
+
+    #[tokio::test]
+    async fn usage_sessions_for_includes_sessions_without_tool_events() {
+        let store =
+            SqliteEventStore::new(crate::cli::serve::storage::schema::open_in_memory().unwrap());
+        let usage = |session: &str, tool: ToolKind| TokenEvent {
+            source_event_id: format!("t-{session}"),
+            session_id: session.into(),
+            tool,
+            model: None,
+            request_id: None,
+            input_tokens: Some(10),
+            cache_read_tokens: None,
+            cache_creation_tokens: None,
+            cache_creation_1h_tokens: None,
+            output_tokens: Some(1),
+            reasoning_tokens: None,
+            captured_at_ms: 10,
+            captured_via: CaptureSource::Transcript,
+        };
+        store
+            .upsert_events(EventBatch {
+                tool_events: vec![sample_tool_event("1", "s1")],
+                token_events: vec![
+                    usage("s3", ToolKind::ClaudeCode),
+                    usage("s2", ToolKind::ClaudeCode),
+                    usage("s4", ToolKind::Codex),
+                ],
+                cache_observations: vec![CacheObservation {
+                    source_event_id: "c1".into(),
+                    session_id: "s3".into(),
+                    tool: ToolKind::ClaudeCode,
+                    model: None,
+                    cache_read_input_tokens: Some(5),
+                    cache_creation_input_tokens: None,
+                    cache_creation_1h_input_tokens: None,
+                    assistant_blocks_hash: None,
+                    tools_changed: vec![],
+                    prompt_fingerprint: None,
+                    observed_at_ms: 10,
+                }],
+            })
+            .await
+            .unwrap();
+        let got = store
+            .usage_sessions_for(ToolKind::ClaudeCode)
+            .await
+            .unwrap();
+        assert_eq!(got, ["s2", "s3"]);
+        assert_eq!(
+            store.usage_sessions_for(ToolKind::Codex).await.unwrap(),
+            ["s4"]
+        );
+    }
 }
@@ -8,6 +8,8 @@
 //! - `capture_token_events`  — per-turn token-usage rows
 //! - `capture_cursors`       — one row per source file (resume offset)
 //!
+//! plus `capture_tool_events_fts`, the FTS5 index behind `EventStore::search`.
+//!
 //! The `(source_file, source_event_id)` uniqueness invariant on both event
 //! tables is the idempotency contract: `INSERT OR IGNORE` makes a full re-walk
 //! safe (previously-seen rows are silently deduplicated).
@@ -2,7 +2,8 @@
 //!
 //! Three tables live in one DB file. Both event tables enforce the
 //! `(source_file, source_event_id)` uniqueness invariant — the idempotency
-//! contract that makes `scan --force` safe.
+//! contract that makes `scan --force` safe. An FTS5 index over the tool
+//! events' text backs `EventStore::search`.
 
 use std::sync::Arc;
 
@@ -55,11 +56,13 @@ CREATE TABLE IF NOT EXISTS capture_cache_observations (
     source_event_id                   TEXT    NOT NULL,
     session_id                        TEXT    NOT NULL,
     tool                              TEXT    NOT NULL,
+    model                             TEXT,
     cache_read_input_tokens           INTEGER,
     cache_creation_input_tokens       INTEGER,
     cache_creation_1h_input_tokens    INTEGER,
     assistant_blocks_hash             TEXT,
     tools_changed                     TEXT    NOT NULL DEFAULT '[]',
+    prompt_fingerprint                TEXT,
     observed_at_ms                    INTEGER NOT NULL,
     PRIMARY KEY (source_file, source_event_id)
 );
@@ -74,6 +77,15 @@ CREATE TABLE IF NOT EXISTS capture_cursors (
     updated_at_ms INTEGER NOT NULL
 );
 
+-- Full-text index over the searchable text of capture_tool_events. Holds
+-- its own copy of the text (not external content: the event table's implicit
+-- rowid is not stable across VACUUM) and joins back on the event key. Rows
+-- are written by SqliteEventStore::upsert_events alongside each new event.
+CREATE VIRTUAL TABLE IF NOT EXISTS capture_tool_events_fts USING fts5(
+    target, input, output, error_message,
+    source_file UNINDEXED, source_event_id UNINDEXED
+);
+
 -- Query accelerators for the serve routes + MCP tools.
 CREATE INDEX IF NOT EXISTS idx_tool_events_tool_session
     ON capture_tool_events(tool, session_id);
@@ -85,6 +97,49 @@ CREATE INDEX IF NOT EXISTS idx_cache_observations_tool_session
     ON capture_cache_observations(tool, session_id);
 "#;
 
+/// `PRAGMA user_version` after [`migrate`]. Version 1 added the FTS index;
+/// opening an older DB backfills it from the existing events. Version 2
+/// added `model` and `prompt_fingerprint` to `capture_cache_observations`.
+pub const SCHEMA_VERSION: i64 = 2;
+
+/// Run [`MIGRATION_SQL`] and the one-off data migrations between the DB's
+/// `user_version` and [`SCHEMA_VERSION`].
+pub fn migrate(conn: &mut Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(MIGRATION_SQL)?;
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+    let tx = conn.transaction()?;
+    if version < 1 {
+        tx.execute_batch(
+            "DELETE FROM capture_tool_events_fts; \
+             INSERT INTO capture_tool_events_fts \
+                 (target, input, output, error_message, source_file, source_event_id) \
+             SELECT target, input, output, error_message, source_file, source_event_id \
+             FROM capture_tool_events;",
+        )?;
+    }
+    if version < 2 {
+        // `CREATE TABLE IF NOT EXISTS` already made them on a fresh DB.
+        for column in ["model", "prompt_fingerprint"] {
+            let exists: bool = tx.query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('capture_cache_observations') \
+                 WHERE name = ?1",
+                [column],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                tx.execute_batch(&format!(
+                    "ALTER TABLE capture_cache_observations ADD COLUMN {column} TEXT"
+                ))?;
+            }
+        }
+    }
+    tx.execute_batch(&format!("PRAGMA user_version = {SCHEMA_VERSION}"))?;
+    tx.commit()
+}
+
 /// Open or create the capture DB at `path`, run migrations, and return a
 /// connection ready for [`crate::storage::SqliteEventStore`] /
 /// [`crate::storage::SqliteCursorStore`] to share.
@@ -92,16 +147,16 @@ pub fn open(path: &std::path::Path) -> Result<Arc<std::sync::Mutex<Connection>>,
     if let Some(parent) = path.parent() {
         let _ = std::fs::create_dir_all(parent);
     }
-    let conn = Connection::open(path)?;
+    let mut conn = Connection::open(path)?;
     conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
-    conn.execute_batch(MIGRATION_SQL)?;
+    migrate(&mut conn)?;
     Ok(Arc::new(std::sync::Mutex::new(conn)))
 }
 
 /// Open an in-memory DB. For tests.
 #[cfg(test)]
 pub fn open_in_memory() -> Result<Arc<std::sync::Mutex<Connection>>, rusqlite::Error> {
-    let conn = Connection::open_in_memory()?;
-    conn.execute_batch(MIGRATION_SQL)?;
+    let mut conn = Connection::open_in_memory()?;
+    migrate(&mut conn)?;
     Ok(Arc::new(std::sync::Mutex::new(conn)))
 }
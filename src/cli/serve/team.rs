@@ -0,0 +1,778 @@
+//! `aikit serve` team aggregation — ingest mode and the client forwarder.
+//!
+//! In ingest mode (`--team-tokens <file>`) serve hosts only the team routes:
+//! other aikit instances upload the [`EventBatch`]es their capture pipeline
+//! produces, and leads query aggregates across everyone's sessions. Each
+//! owner gets a SQLite store of their own under the team directory; the
+//! aggregates walk those stores through the ordinary [`EventStore`] API (see
+//! [`aikit_session_capture::aggregate`]).
+//!
+//! Five routes under `/api/v1/team`:
+//! - `POST /team/ingest`   — upsert a batch into the caller's store
+//! - `GET  /team/sessions` — sessions per owner
+//! - `GET  /team/actions`  — action counts by kind
+//! - `GET  /team/tokens`   — token usage by model
+//! - `GET  /team/files`    — most-touched files
+//!
+//! Every route takes `Authorization: Bearer <token>`. Ingest needs an owner
+//! token from the tokens file and writes only to that owner's store; the
+//! read routes accept any owner token or the server's `--api-key`.
+//!
+//! On the client side, [`ForwardingEventStore`] wraps the local capture store
+//! so every batch the watcher (or a scan) upserts is also posted to a team
+//! host. Only what the aggregates need leaves the machine by default: tool
+//! inputs, outputs, error messages and metadata are dropped, targets are made
+//! relative to the git root and go through the [`SecretScrubber`], and
+//! transcript paths are cut to their file names.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+use aikit_session_capture::aggregate::{self, AggregateWindow, OwnerStore};
+use aikit_session_capture::{
+    CacheObservation, EventBatch, EventStore, FileTouch, SearchMatch, SearchQuery, SecretScrubber,
+    SessionSummary, StoreError, TokenEvent, ToolEvent, ToolKind,
+};
+
+use super::{error_response, storage};
+
+/// Upper bound on one ingest body. A first scan of a long transcript is
+/// forwarded as a single batch.
+const MAX_INGEST_BYTES: usize = 64 * 1024 * 1024;
+
+/// Default number of rows `GET /team/files` returns.
+const DEFAULT_HOT_FILES: usize = 20;
+
+/// Default home of the per-owner team stores.
+pub(crate) fn team_dir() -> PathBuf {
+    dirs::data_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("aikit")
+        .join("team")
+}
+
+// ── tokens file ───────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct TokensFile {
+    #[serde(default)]
+    owners: BTreeMap<String, String>,
+}
+
+/// Read the owner → token table from `path`:
+///
+/// ```toml
+/// [owners]
+/// alice = "4f1c…"
+/// bob = "9a07…"
+/// ```
+///
+/// Owner names become file names, so they are limited to ASCII letters,
+/// digits, `.`, `_` and `-`. Tokens must be non-empty and distinct.
+pub(crate) fn load_tokens(path: &Path) -> anyhow::Result<BTreeMap<String, String>> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("cannot read team tokens {}: {e}", path.display()))?;
+    let file: TokensFile = toml::from_str(&raw)
+        .map_err(|e| anyhow::anyhow!("invalid team tokens {}: {e}", path.display()))?;
+    if file.owners.is_empty() {
+        anyhow::bail!("team tokens {} list no [owners]", path.display());
+    }
+    let mut seen = HashMap::new();
+    for (owner, token) in &file.owners {
+        let valid_name = !owner.is_empty()
+            && !owner.starts_with('.')
+            && owner
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+        if !valid_name {
+            anyhow::bail!("invalid owner name '{owner}' in {}", path.display());
+        }
+        if token.is_empty() {
+            anyhow::bail!("owner '{owner}' has an empty token in {}", path.display());
+        }
+        if let Some(other) = seen.insert(token.as_str(), owner.as_str()) {
+            anyhow::bail!("owners '{other}' and '{owner}' share a token");
+        }
+    }
+    Ok(file.owners)
+}
+
+// ── TeamState ─────────────────────────────────────────────────────────────────
+
+/// Shared state for the `/api/v1/team` routes.
+#[derive(Clone)]
+pub struct TeamState {
+    /// Owner → that owner's store, in owner order.
+    stores: Arc<BTreeMap<String, Arc<dyn EventStore>>>,
+    /// Token → owner.
+    tokens: Arc<HashMap<String, String>>,
+    api_key: Option<String>,
+}
+
+impl TeamState {
+    pub fn new(
+        stores: BTreeMap<String, Arc<dyn EventStore>>,
+        tokens: HashMap<String, String>,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            stores: Arc::new(stores),
+            tokens: Arc::new(tokens),
+            api_key,
+        }
+    }
+
+    /// Open (or create) `<dir>/<owner>.db` for every owner in `owners`.
+    pub fn open(
+        dir: &Path,
+        owners: BTreeMap<String, String>,
+        api_key: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let mut stores: BTreeMap<String, Arc<dyn EventStore>> = BTreeMap::new();
+        let mut tokens = HashMap::new();
+        for (owner, token) in owners {
+            let conn = storage::schema::open(&dir.join(format!("{owner}.db")))?;
+            stores.insert(
+                owner.clone(),
+                Arc::new(storage::SqliteEventStore::new(conn)),
+            );
+            tokens.insert(token, owner);
+        }
+        Ok(Self::new(stores, tokens, api_key))
+    }
+
+    /// The owner whose token `headers` carry. Every known token is compared,
+    /// in constant time, so the response time says nothing about how close a
+    /// guess came.
+    fn owner_for(&self, headers: &HeaderMap) -> Option<&str> {
+        let token = bearer(headers)?;
+        let mut owner = None;
+        for (known, name) in self.tokens.iter() {
+            if token_matches(known, token) {
+                owner = Some(name.as_str());
+            }
+        }
+        owner
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn authorize_read(&self, headers: &HeaderMap) -> Result<(), Response> {
+        let is_admin = match (bearer(headers), self.api_key.as_deref()) {
+            (Some(token), Some(key)) => token_matches(key, token),
+            _ => false,
+        };
+        if is_admin || self.owner_for(headers).is_some() {
+            Ok(())
+        } else {
+            Err(unauthorized())
+        }
+    }
+
+    /// The stores a read covers: every owner, or just `owner`.
+    #[allow(clippy::result_large_err)]
+    fn select(&self, owner: Option<&str>) -> Result<Vec<OwnerStore<'_>>, Response> {
+        match owner {
+            None => Ok(self
+                .stores
+                .iter()
+                .map(|(o, s)| (o.as_str(), s.as_ref()))
+                .collect()),
+            Some(owner) => match self.stores.get_key_value(owner) {
+                Some((o, s)) => Ok(vec![(o.as_str(), s.as_ref())]),
+                None => Err(error_response(
+                    StatusCode::NOT_FOUND,
+                    "unknown_owner",
+                    &format!("owner '{owner}' not found"),
+                )),
+            },
+        }
+    }
+}
+
+/// Build the team router bound to `state`. In ingest mode this is the whole
+/// `/api/v1` surface.
+pub fn build_router(state: TeamState) -> Router {
+    Router::new()
+        .route(
+            "/team/ingest",
+            post(ingest).layer(DefaultBodyLimit::max(MAX_INGEST_BYTES)),
+        )
+        .route("/team/sessions", get(sessions))
+        .route("/team/actions", get(actions))
+        .route("/team/tokens", get(tokens))
+        .route("/team/files", get(files))
+        .with_state(state)
+}
+
+// ── POST /team/ingest ─────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct IngestResult {
+    owner: String,
+    received: u64,
+    inserted: u64,
+    deduplicated: u64,
+}
+
+async fn ingest(
+    State(ts): State<TeamState>,
+    headers: HeaderMap,
+    body: Result<Json<EventBatch>, JsonRejection>,
+) -> Response {
+    let Some(owner) = ts.owner_for(&headers).map(str::to_string) else {
+        return unauthorized();
+    };
+    let batch = match body {
+        Ok(Json(batch)) => batch,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, "invalid_request", &e.body_text())
+        }
+    };
+    let received = batch.len() as u64;
+    let store = &ts.stores[&owner];
+    match store.upsert_events(batch).await {
+        Ok(inserted) => {
+            tracing::debug!(
+                target: "aikit::serve::team",
+                owner = %owner, received, inserted,
+                "ingested batch"
+            );
+            json_ok(&IngestResult {
+                owner,
+                received,
+                inserted,
+                deduplicated: received.saturating_sub(inserted),
+            })
+        }
+        Err(e) => store_error(e),
+    }
+}
+
+// ── GET aggregates ────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct AggregateParams {
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    owner: Option<String>,
+    limit: Option<usize>,
+}
+
+impl AggregateParams {
+    fn window(&self) -> AggregateWindow {
+        AggregateWindow {
+            since_ms: self.since_ms,
+            until_ms: self.until_ms,
+        }
+    }
+}
+
+async fn sessions(
+    State(ts): State<TeamState>,
+    headers: HeaderMap,
+    Query(p): Query<AggregateParams>,
+) -> Response {
+    let stores = match ts
+        .authorize_read(&headers)
+        .and_then(|()| ts.select(p.owner.as_deref()))
+    {
+        Ok(stores) => stores,
+        Err(resp) => return resp,
+    };
+    respond(aggregate::sessions_per_owner(&stores, p.window()).await)
+}
+
+async fn actions(
+    State(ts): State<TeamState>,
+    headers: HeaderMap,
+    Query(p): Query<AggregateParams>,
+) -> Response {
+    let stores = match ts
+        .authorize_read(&headers)
+        .and_then(|()| ts.select(p.owner.as_deref()))
+    {
+        Ok(stores) => stores,
+        Err(resp) => return resp,
+    };
+    respond(aggregate::actions_by_kind(&stores, p.window()).await)
+}
+
+async fn tokens(
+    State(ts): State<TeamState>,
+    headers: HeaderMap,
+    Query(p): Query<AggregateParams>,
+) -> Response {
+    let stores = match ts
+        .authorize_read(&headers)
+        .and_then(|()| ts.select(p.owner.as_deref()))
+    {
+        Ok(stores) => stores,
+        Err(resp) => return resp,
+    };
+    respond(aggregate::tokens_by_model(&stores, p.window()).await)
+}
+
+async fn files(
+    State(ts): State<TeamState>,
+    headers: HeaderMap,
+    Query(p): Query<AggregateParams>,
+) -> Response {
+    let stores = match ts
+        .authorize_read(&headers)
+        .and_then(|()| ts.select(p.owner.as_deref()))
+    {
+        Ok(stores) => stores,
+        Err(resp) => return resp,
+    };
+    let limit = p.limit.unwrap_or(DEFAULT_HOT_FILES);
+    respond(aggregate::hot_files(&stores, p.window(), limit).await)
+}
+
+// ── ForwardingEventStore ──────────────────────────────────────────────────────
+
+/// Client side of team aggregation: an [`EventStore`] that upserts into the
+/// local store, then posts the batch to a team host's ingest route. Queries
+/// are answered locally.
+///
+/// The posted copy keeps only what the team routes aggregate (sessions,
+/// kinds, targets, timings, token counts). Targets under the session's git
+/// root are made relative to it and then scrubbed, and `source_file` is cut
+/// to its file name, so local home paths stay local. With
+/// [`ForwardingEventStore::with_full_payloads`] it also carries tool inputs,
+/// outputs, error messages and metadata, each scrubbed.
+///
+/// A failed post fails the upsert, so the capture pipeline keeps its cursor
+/// and re-parses the file on the next change; both stores deduplicate, so
+/// the retry is safe.
+pub struct ForwardingEventStore {
+    local: Arc<dyn EventStore>,
+    client: reqwest::Client,
+    ingest_url: String,
+    token: String,
+    scrubber: SecretScrubber,
+    full_payloads: bool,
+}
+
+impl ForwardingEventStore {
+    /// `base_url` is the team host's root (`https://team.example:8787`).
+    pub fn new(local: Arc<dyn EventStore>, base_url: &str, token: String) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()?;
+        Ok(Self {
+            local,
+            client,
+            ingest_url: format!("{}/api/v1/team/ingest", base_url.trim_end_matches('/')),
+            token,
+            scrubber: SecretScrubber::default(),
+            full_payloads: false,
+        })
+    }
+
+    /// Scrub forwarded text with `scrubber` instead of the default rules.
+    pub fn with_scrubber(mut self, scrubber: SecretScrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    /// Also forward tool inputs, outputs, error messages and metadata
+    /// (scrubbed). Off by default.
+    pub fn with_full_payloads(mut self, full_payloads: bool) -> Self {
+        self.full_payloads = full_payloads;
+        self
+    }
+
+    /// The copy of `batch` that leaves the machine.
+    fn outbound(&self, batch: &EventBatch) -> EventBatch {
+        let scrub = |text: &Option<String>| text.as_deref().map(|t| self.scrubber.scrub(t));
+        let tool_events = batch
+            .tool_events
+            .iter()
+            .map(|ev| {
+                let mut out = ev.clone();
+                let target = ev
+                    .target
+                    .as_deref()
+                    .map(|t| aggregate::repo_relative(t, ev.git_root.as_deref()));
+                out.target = scrub(&target);
+                out.source_file = ev
+                    .source_file
+                    .file_name()
+                    .map(PathBuf::from)
+                    .unwrap_or_default();
+                if self.full_payloads {
+                    out.input = scrub(&ev.input);
+                    out.output = scrub(&ev.output);
+                    out.error_message = scrub(&ev.error_message);
+                    scrub_json(&mut out.metadata, &self.scrubber);
+                } else {
+                    out.input = None;
+                    out.output = None;
+                    out.error_message = None;
+                    out.metadata = serde_json::Value::Null;
+                }
+                out
+            })
+            .collect();
+        EventBatch {
+            tool_events,
+            token_events: batch.token_events.clone(),
+            cache_observations: batch.cache_observations.clone(),
+        }
+    }
+
+    async fn forward(&self, batch: &EventBatch) -> Result<(), StoreError> {
+        let batch = self.outbound(batch);
+        let resp = self
+            .client
+            .post(&self.ingest_url)
+            .bearer_auth(&self.token)
+            .json(&batch)
+            .send()
+            .await
+            .map_err(|e| StoreError::Backend(format!("team forward: {e}")))?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = resp.text().await.unwrap_or_default();
+        Err(StoreError::Backend(format!(
+            "team forward: {status}: {}",
+            body.trim()
+        )))
+    }
+}
+
+#[async_trait]
+impl EventStore for ForwardingEventStore {
+    async fn upsert_events(&self, events: EventBatch) -> Result<u64, StoreError> {
+        if events.is_empty() {
+            return self.local.upsert_events(events).await;
+        }
+        let inserted = self.local.upsert_events(events.clone()).await?;
+        self.forward(&events).await?;
+        Ok(inserted)
+    }
+
+    async fn sessions_for(
+        &self,
+        tool: ToolKind,
+        cwd: Option<&Path>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<SessionSummary>, StoreError> {
+        self.local.sessions_for(tool, cwd, limit, offset).await
+    }
+
+    async fn actions_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ToolEvent>, StoreError> {
+        self.local
+            .actions_for_session(tool, session_id, limit, offset)
+            .await
+    }
+
+    async fn search_outputs(&self, query: &str, limit: u32) -> Result<Vec<ToolEvent>, StoreError> {
+        self.local.search_outputs(query, limit).await
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchMatch>, StoreError> {
+        self.local.search(query).await
+    }
+
+    async fn token_events_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+    ) -> Result<Vec<TokenEvent>, StoreError> {
+        self.local.token_events_for_session(tool, session_id).await
+    }
+
+    async fn cache_observations_for_session(
+        &self,
+        tool: ToolKind,
+        session_id: &str,
+    ) -> Result<Vec<CacheObservation>, StoreError> {
+        self.local
+            .cache_observations_for_session(tool, session_id)
+            .await
+    }
+
+    async fn usage_sessions_for(&self, tool: ToolKind) -> Result<Vec<String>, StoreError> {
+        self.local.usage_sessions_for(tool).await
+    }
+
+    async fn last_file_touch(&self, path: &Path) -> Result<Option<FileTouch>, StoreError> {
+        self.local.last_file_touch(path).await
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Scrub every string inside `value` in place.
+fn scrub_json(value: &mut serde_json::Value, scrubber: &SecretScrubber) {
+    match value {
+        serde_json::Value::String(s) => *s = scrubber.scrub(s),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                scrub_json(item, scrubber);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                scrub_json(item, scrubber);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+}
+
+/// Constant-time token comparison; only the length can leak.
+fn token_matches(known: &str, given: &str) -> bool {
+    known.as_bytes().ct_eq(given.as_bytes()).into()
+}
+
+fn unauthorized() -> Response {
+    error_response(
+        StatusCode::UNAUTHORIZED,
+        "unauthorized",
+        "Invalid or missing team token",
+    )
+}
+
+fn store_error(e: StoreError) -> Response {
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "store_error",
+        &e.to_string(),
+    )
+}
+
+fn respond<T: Serialize>(result: Result<T, StoreError>) -> Response {
+    match result {
+        Ok(body) => json_ok(&body),
+        Err(e) => store_error(e),
+    }
+}
+
+fn json_ok<T: Serialize>(body: &T) -> Response {
+    (
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        serde_json::to_string(body).unwrap_or_else(|_| "{}".into()),
+    )
+        .into_response()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aikit_session_capture::InMemoryEventStore;
+
+    fn write_tokens(body: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("team.toml");
+        std::fs::write(&path, body).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn tokens_file_maps_owners_to_tokens() {
+        let (_dir, path) = write_tokens("[owners]\nalice = \"a-token\"\nbob = \"b-token\"\n");
+        let owners = load_tokens(&path).unwrap();
+        assert_eq!(owners["alice"], "a-token");
+        assert_eq!(owners.len(), 2);
+    }
+
+    #[test]
+    fn tokens_file_rejects_bad_names_and_shared_tokens() {
+        let (_dir, path) = write_tokens("[owners]\n\"../alice\" = \"t\"\n");
+        assert!(load_tokens(&path).is_err());
+        let (_dir, path) = write_tokens("[owners]\nalice = \"t\"\nbob = \"t\"\n");
+        assert!(load_tokens(&path).is_err());
+        let (_dir, path) = write_tokens("[owners]\n");
+        assert!(load_tokens(&path).is_err());
+    }
+
+    #[test]
+    fn reads_accept_owner_tokens_and_the_api_key() {
+        let store: Arc<dyn EventStore> = Arc::new(InMemoryEventStore::new());
+        let state = TeamState::new(
+            BTreeMap::from([("alice".to_string(), store)]),
+            HashMap::from([("a-token".to_string(), "alice".to_string())]),
+            Some("admin".to_string()),
+        );
+        let with = |token: &str| {
+            let mut h = HeaderMap::new();
+            h.insert(
+                axum::http::header::AUTHORIZATION,
+                format!("Bearer {token}").parse().unwrap(),
+            );
+            h
+        };
+        assert_eq!(state.owner_for(&with("a-token")), Some("alice"));
+        assert_eq!(state.owner_for(&with("admin")), None);
+        assert_eq!(state.owner_for(&with("a-toke")), None);
+        assert_eq!(state.owner_for(&with("a-tokens")), None);
+        assert!(state.authorize_read(&with("a-token")).is_ok());
+        assert!(state.authorize_read(&with("admin")).is_ok());
+        assert!(state.authorize_read(&with("nope")).is_err());
+        assert!(state.authorize_read(&HeaderMap::new()).is_err());
+        assert!(state.select(Some("bob")).is_err());
+        assert_eq!(state.select(None).unwrap().len(), 1);
+    }
+
+    fn read_event(id: &str, target: &str) -> ToolEvent {
+        ToolEvent {
+            source_event_id: id.into(),
+            source_file: PathBuf::from("/tmp/sess.jsonl"),
+            session_id: "s1".into(),
+            tool: ToolKind::ClaudeCode,
+            kind: aikit_session_capture::ActionKind::Read,
+            target: Some(target.into()),
+            input: None,
+            output: None,
+            status: aikit_session_capture::ActionStatus::Success,
+            error_message: None,
+            started_at_ms: Some(1000),
+            duration_ms: None,
+            git_root: None,
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn outbound_batches_drop_payloads_unless_opted_in() {
+        let local: Arc<dyn EventStore> = Arc::new(InMemoryEventStore::new());
+        let mut ev = read_event(
+            "1",
+            "curl -H 'Authorization: Bearer sk-ant-REDACTED'",
+        );
+        ev.input = Some("{\"command\":\"cat .env\"}".into());
+        ev.output = Some("AWS_SECRET_ACCESS_KEY=wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY".into());
+        ev.error_message = Some("exit 1".into());
+        ev.metadata = serde_json::json!({ "cwd": "/repo" });
+        let batch = EventBatch {
+            tool_events: vec![ev],
+            ..EventBatch::default()
+        };
+
+        let client = ForwardingEventStore::new(local.clone(), "http://team", "t".into()).unwrap();
+        let out = &client.outbound(&batch).tool_events[0];
+        assert!(out.input.is_none() && out.output.is_none() && out.error_message.is_none());
+        assert!(out.metadata.is_null());
+        assert!(!out.target.as_deref().unwrap().contains("sk-ant-api03"));
+        assert_eq!(out.source_file, PathBuf::from("sess.jsonl"));
+
+        let client = ForwardingEventStore::new(local, "http://team", "t".into())
+            .unwrap()
+            .with_full_payloads(true);
+        let out = &client.outbound(&batch).tool_events[0];
+        assert_eq!(out.input.as_deref(), Some("{\"command\":\"cat .env\"}"));
+        assert!(!out.output.as_deref().unwrap().contains("wJalrXUtnFEMI"));
+        assert_eq!(out.metadata["cwd"], "/repo");
+    }
+
+    #[test]
+    fn outbound_targets_are_relative_to_the_git_root() {
+        let local: Arc<dyn EventStore> = Arc::new(InMemoryEventStore::new());
+        let client = ForwardingEventStore::new(local, "http://team", "t".into()).unwrap();
+        let mut inside = read_event("1", "/home/alice/app/src/lib.rs");
+        inside.git_root = Some(PathBuf::from("/home/alice/app"));
+        let mut outside = read_event("2", "/etc/hosts");
+        outside.git_root = inside.git_root.clone();
+        let batch = EventBatch {
+            tool_events: vec![inside, outside, read_event("3", "/home/alice/notes.md")],
+            ..EventBatch::default()
+        };
+        let targets: Vec<_> = client
+            .outbound(&batch)
+            .tool_events
+            .into_iter()
+            .map(|ev| ev.target.unwrap())
+            .collect();
+        assert_eq!(
+            targets,
+            ["src/lib.rs", "/etc/hosts", "/home/alice/notes.md"]
+        );
+    }
+
+    #[tokio::test]
+    async fn forwarded_batches_land_in_the_owner_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let owners = BTreeMap::from([
+            ("alice".to_string(), "a-token".to_string()),
+            ("bob".to_string(), "b-token".to_string()),
+        ]);
+        let state = TeamState::open(dir.path(), owners, None).unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().nest("/api/v1", build_router(state.clone()));
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+
+        let local: Arc<dyn EventStore> = Arc::new(InMemoryEventStore::new());
+        let client = ForwardingEventStore::new(local.clone(), &base, "a-token".into()).unwrap();
+        let batch = EventBatch {
+            tool_events: vec![read_event("1", "src/lib.rs")],
+            ..EventBatch::default()
+        };
+        assert_eq!(client.upsert_events(batch.clone()).await.unwrap(), 1);
+        // Re-forwarding is deduplicated on both sides.
+        assert_eq!(client.upsert_events(batch).await.unwrap(), 0);
+
+        let alice = state.select(Some("alice")).unwrap();
+        let files = aggregate::hot_files(&alice, AggregateWindow::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].reads, 1);
+        let bob = state.select(Some("bob")).unwrap();
+        let sessions = aggregate::sessions_per_owner(&bob, AggregateWindow::default())
+            .await
+            .unwrap();
+        assert_eq!(sessions[0].sessions, 0);
+
+        let resp = reqwest::Client::new()
+            .get(format!("{base}/api/v1/team/files"))
+            .bearer_auth("b-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body[0]["owners"], serde_json::json!(["alice"]));
+
+        let bad = ForwardingEventStore::new(local, &base, "nope".into()).unwrap();
+        let batch = EventBatch {
+            tool_events: vec![read_event("2", "README.md")],
+            ..EventBatch::default()
+        };
+        let err = bad.upsert_events(batch).await.unwrap_err();
+        assert!(err.to_string().contains("401"), "{err}");
+    }
+}
@@ -1,26 +1,34 @@
 //! `aikit session` — interactive bidirectional agent sessions.
 //!
 //! Subcommands:
-//! - `new`  — open a live session and enter a multi-turn REPL
+//! - `new`  — open a live session in the full-screen chat UI (or a line REPL
+//!   when not on a terminal / with `--no-tui`)
 //! - `list` — list active live sessions (requires `AIKIT_SERVE_URL`)
+//! - `sync` — upload scrubbed session transcripts to S3-compatible storage
+//! - `pull` — download synced transcripts, verify them, and restore/ingest them
 
 use std::io::{self, BufRead, Write as IoWrite};
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use aikit_sdk::runner::Backend;
 use aikit_sdk::{
-    open_claude_session, open_codex_session, AgentEvent, AgentEventPayload, ClaudeSessionOptions,
-    CodexSessionOptions, LiveSession,
+    open_aikit_session, open_claude_session, open_codex_session, AgentEvent, AgentEventPayload,
+    AikitSessionOptions, ClaudeSessionOptions, CodexSessionOptions, LiveSession,
+    PermissionCallback,
 };
 
+use crate::tui::session_chat::{history_events, run_session_chat, ChatConfig};
+
 #[cfg(all(feature = "agent-adapters", feature = "watcher"))]
 use aikit_session_capture::watch::{find_adapter_for_path, NotifyWatchDriver, WatchDriver};
 #[cfg(feature = "agent-adapters")]
 use aikit_session_capture::{Registry, ToolKind};
 #[cfg(feature = "agent-adapters")]
 use aikit_session_sync::{
-    credential_owner_from_env, JsonSyncStateStore, OutputFormat, S3Sink, S3SinkConfig, SyncConfig,
-    SyncEngine, SyncSink,
+    credential_owner_from_env, pull_sessions, EncryptedSink, FsSink, JsonSyncStateStore, Keyring,
+    OutputFormat, PullConfig, PullDestination, S3Sink, S3SinkConfig, SyncBackend, SyncConfig,
+    SyncEngine, SyncSink, WebDavSink, WebDavSinkConfig,
 };
 
 // ── public args ───────────────────────────────────────────────────────────────
@@ -36,6 +44,11 @@ pub struct NewSessionArgs {
     pub sandbox: Option<String>,
     /// Print events as NDJSON instead of human-readable text.
     pub events: bool,
+    /// Resume this session id: its transcript is loaded into the chat UI and
+    /// the live session continues it (claude, aikit).
+    pub resume: Option<String>,
+    /// Use the line REPL even on a terminal.
+    pub no_tui: bool,
 }
 
 #[derive(Debug)]
@@ -46,6 +59,10 @@ pub struct ListSessionsArgs {
 
 #[derive(Debug)]
 pub struct SyncSessionsArgs {
+    /// Store objects under this directory instead of S3.
+    pub sink_dir: Option<PathBuf>,
+    /// Store objects in this WebDAV collection instead of S3.
+    pub webdav_url: Option<String>,
     pub bucket: Option<String>,
     pub endpoint: Option<String>,
     pub region: Option<String>,
@@ -54,6 +71,41 @@ pub struct SyncSessionsArgs {
     pub tools: Vec<String>,
     pub watch: bool,
     pub dry_run: bool,
+    /// Roll each session's chunks up into a full snapshot after syncing.
+    pub compact: bool,
+    /// Encrypt transcripts with the keys in this file before upload.
+    pub key_file: Option<PathBuf>,
+    /// TOML rule file extending the scrubber.
+    pub scrub_rules: Option<PathBuf>,
+    /// Report what scrubbing would redact instead of syncing.
+    pub scrub_audit: bool,
+    pub allow_http: bool,
+    pub format: String,
+    pub log_level: Option<String>,
+    pub log_format: String,
+}
+
+#[derive(Debug)]
+pub struct PullSessionsArgs {
+    pub sink_dir: Option<PathBuf>,
+    pub webdav_url: Option<String>,
+    pub bucket: Option<String>,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    /// Whose sessions to pull; may be a teammate's.
+    pub owner: Option<String>,
+    pub key_prefix: Option<String>,
+    pub tool: Option<String>,
+    pub session: Option<String>,
+    /// Directory to write transcripts under (default `./aikit-pull`).
+    pub out: Option<PathBuf>,
+    /// Write into the agent's own session directory instead of `out`.
+    pub restore: bool,
+    pub overwrite: bool,
+    /// Upsert pulled transcripts into the local capture DB.
+    pub ingest: bool,
+    /// Decrypt transcripts that were synced with `--key-file`.
+    pub key_file: Option<PathBuf>,
     pub allow_http: bool,
     pub format: String,
     pub log_level: Option<String>,
@@ -63,48 +115,131 @@ pub struct SyncSessionsArgs {
 // ── new session ───────────────────────────────────────────────────────────────
 
 pub fn execute_new(args: NewSessionArgs) -> anyhow::Result<()> {
-    type Events = std::sync::mpsc::Receiver<aikit_sdk::AgentEvent>;
+    if args.resume.is_some() && !matches!(args.agent.as_str(), "claude" | "aikit") {
+        anyhow::bail!("--resume is only supported for claude and aikit live sessions");
+    }
+    let use_tui = !args.events
+        && !args.no_tui
+        && atty::is(atty::Stream::Stdin)
+        && atty::is(atty::Stream::Stdout);
+    if use_tui {
+        return execute_new_tui(args);
+    }
+    if args.prompt.is_empty() {
+        anyhow::bail!("--prompt is required for session new without the chat UI");
+    }
+
+    let (session, events) = open_live_session(&args, &args.prompt, None)?;
+    let events_thread = std::thread::spawn({
+        let ndjson = args.events;
+        move || {
+            while let Ok(event) = events.recv() {
+                print_event(&event, ndjson);
+            }
+        }
+    });
 
-    // Both backends expose the same `LiveSession` control surface, so we box the
-    // concrete handle behind `dyn LiveSession` and drive the REPL identically.
-    let (session, events): (Box<dyn LiveSession>, Events) = match args.agent.as_str() {
+    run_repl(session.as_ref())?;
+    let _ = events_thread.join();
+    Ok(())
+}
+
+/// Run the session in the full-screen chat UI. With `--resume`, the stored
+/// transcript is replayed into the scrollback first.
+fn execute_new_tui(args: NewSessionArgs) -> anyhow::Result<()> {
+    let history = match &args.resume {
+        Some(id) => load_history(&args.agent, id)?,
+        None => Vec::new(),
+    };
+    let prompt = (!args.prompt.is_empty()).then(|| args.prompt.clone());
+    let config = ChatConfig {
+        agent: args.agent.clone(),
+        model: args.model.clone(),
+        prompt,
+        history,
+        open: Box::new(move |first_turn, on_permission| {
+            open_live_session(&args, &first_turn, Some(on_permission))
+        }),
+    };
+    run_session_chat(config)
+}
+
+/// Read a stored transcript through the agent's `HistoryReader`, as canonical
+/// events for the chat UI.
+fn load_history(agent: &str, session_id: &str) -> anyhow::Result<Vec<AgentEvent>> {
+    const PAGE: usize = 100;
+    let reader = Backend::from_key(agent)
+        .and_then(Backend::history_reader)
+        .ok_or_else(|| anyhow::anyhow!("{agent} has no history store to resume from"))?;
+    let mut messages = Vec::new();
+    loop {
+        let mut q = aikit_sdk::history::MessagesQuery::default();
+        q.limit = Some(PAGE);
+        q.offset = messages.len();
+        let page = reader
+            .messages(session_id, &q, None)
+            .map_err(|e| anyhow::anyhow!("Failed to read session {session_id}: {e}"))?;
+        let done = page.len() < PAGE;
+        messages.extend(page);
+        if done {
+            break;
+        }
+    }
+    Ok(history_events(agent, &messages))
+}
+
+type Events = std::sync::mpsc::Receiver<AgentEvent>;
+
+/// Open the live session for `args.agent` with `prompt` as its first turn.
+///
+/// Both backends expose the same `LiveSession` control surface, so the
+/// concrete handle is boxed behind `dyn LiveSession` and driven identically.
+fn open_live_session(
+    args: &NewSessionArgs,
+    prompt: &str,
+    on_permission: Option<PermissionCallback>,
+) -> anyhow::Result<(Box<dyn LiveSession>, Events)> {
+    match args.agent.as_str() {
         "claude" => {
             let opts = ClaudeSessionOptions {
                 model: args.model.clone(),
+                resume: args.resume.clone(),
+                on_tool_permission: on_permission,
                 ..ClaudeSessionOptions::default()
             };
-            let (ctrl, evts) = open_claude_session(&args.prompt, opts)
+            let (ctrl, evts) = open_claude_session(prompt, opts)
                 .map_err(|e| anyhow::anyhow!("Failed to open claude session: {e}"))?
                 .into_parts();
-            (Box::new(ctrl), evts)
+            Ok((Box::new(ctrl), evts))
         }
         "codex" => {
-            let opts = CodexSessionOptions::default()
+            let mut opts = CodexSessionOptions::default()
                 .with_approval_policy(args.approval_policy.clone())
                 .with_sandbox(args.sandbox.clone());
-            let (ctrl, evts) = open_codex_session(&args.prompt, opts)
+            opts.on_tool_permission = on_permission;
+            let (ctrl, evts) = open_codex_session(prompt, opts)
                 .map_err(|e| anyhow::anyhow!("Failed to open codex session: {e}"))?
                 .into_parts();
-            (Box::new(ctrl), evts)
+            Ok((Box::new(ctrl), evts))
+        }
+        "aikit" => {
+            // The built-in agent asks for no tool approvals; `on_permission`
+            // has nothing to answer.
+            let opts = AikitSessionOptions {
+                model: args.model.clone(),
+                session_id: args.resume.clone(),
+                ..AikitSessionOptions::default()
+            };
+            let (ctrl, evts) = open_aikit_session(prompt, opts)
+                .map_err(|e| anyhow::anyhow!("Failed to open aikit session: {e}"))?
+                .into_parts();
+            Ok((Box::new(ctrl), evts))
         }
         other => anyhow::bail!(
-            "Unknown agent '{}'. Live sessions support 'claude' or 'codex'.",
+            "Unknown agent '{}'. Live sessions support 'claude', 'codex' or 'aikit'.",
             other
         ),
-    };
-
-    let events_thread = std::thread::spawn({
-        let ndjson = args.events;
-        move || {
-            while let Ok(event) = events.recv() {
-                print_event(&event, ndjson);
-            }
-        }
-    });
-
-    run_repl(session.as_ref())?;
-    let _ = events_thread.join();
-    Ok(())
+    }
 }
 
 /// Drive a multi-turn REPL loop.  Reads lines from stdin; `/interrupt` sends
@@ -240,14 +375,36 @@ pub async fn execute_sync(args: SyncSessionsArgs) -> anyhow::Result<i32> {
             return Ok(2);
         }
     };
+    let backend = match backend_from(args.sink_dir, args.webdav_url) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Ok(2);
+        }
+    };
     let tools = parse_tools(&args.tools)?;
+    let scrubber = match scrubber_from(args.scrub_rules) {
+        Ok(scrubber) => scrubber,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Ok(2);
+        }
+    };
+    if args.scrub_audit {
+        let audit =
+            aikit_session_sync::scrub_audit(&default_registry(), tools.as_deref(), &scrubber).await;
+        print_scrub_audit(&audit, format)?;
+        return Ok(if audit.failed > 0 { 1 } else { 0 });
+    }
     let config = SyncConfig {
+        backend,
         bucket: bucket.clone(),
         endpoint: endpoint.clone(),
         region: region.clone(),
         allow_http,
         endpoint_ca_bundle: std::env::var_os("AIKIT_SYNC_ENDPOINT_CA_BUNDLE").map(PathBuf::from),
         path_style: endpoint.as_deref().map(default_path_style).unwrap_or(true),
+        key_file: key_file_from(args.key_file),
         owner,
         credential_owner: credential_owner_from_env(),
         key_prefix,
@@ -263,6 +420,7 @@ pub async fn execute_sync(args: SyncSessionsArgs) -> anyhow::Result<i32> {
     };
 
     if !config.dry_run
+        && config.backend == SyncBackend::S3
         && (config.bucket.as_deref().unwrap_or("").is_empty()
             || config.endpoint.as_deref().unwrap_or("").is_empty())
     {
@@ -283,18 +441,28 @@ pub async fn execute_sync(args: SyncSessionsArgs) -> anyhow::Result<i32> {
     let sink: Arc<dyn SyncSink> = if config.dry_run {
         Arc::new(aikit_session_sync::InMemorySink::new())
     } else {
-        Arc::new(S3Sink::new(S3SinkConfig {
-            bucket: config.bucket.clone().unwrap_or_default(),
-            endpoint: config.endpoint.clone().unwrap_or_default(),
-            region,
-            allow_http,
-            endpoint_ca_bundle: config.endpoint_ca_bundle.clone(),
-            path_style: config.path_style,
-        })?)
+        open_backend(
+            &config.backend,
+            S3SinkConfig {
+                bucket: config.bucket.clone().unwrap_or_default(),
+                endpoint: config.endpoint.clone().unwrap_or_default(),
+                region,
+                allow_http,
+                endpoint_ca_bundle: config.endpoint_ca_bundle.clone(),
+                path_style: config.path_style,
+            },
+        )?
+    };
+    let sink = match encrypt_with_key_file(sink, config.key_file.as_deref()) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Ok(2);
+        }
     };
     let state = Arc::new(JsonSyncStateStore::open()?);
     let engine = match SyncEngine::new(config.clone(), sink, state) {
-        Ok(engine) => engine,
+        Ok(engine) => engine.with_scrubber(scrubber),
         Err(aikit_session_sync::SyncError::Auth(e)) => {
             eprintln!("Error: auth: {e}");
             return Ok(2);
@@ -313,10 +481,31 @@ pub async fn execute_sync(args: SyncSessionsArgs) -> anyhow::Result<i32> {
         );
     }
 
+    let mut compact_failed = 0;
+    if args.compact && !config.dry_run {
+        let compacted = engine
+            .compact_all()
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        compact_failed = compacted.failed;
+        if matches!(config.format, OutputFormat::Json) {
+            println!("{}", serde_json::to_string(&compacted)?);
+        } else {
+            println!(
+                "compacted={} compact_skipped={} compact_failed={}",
+                compacted.compacted, compacted.skipped, compacted.failed
+            );
+        }
+    }
+
     if config.watch {
         watch_sync(&engine, &registry, config.tools.as_deref()).await?;
     }
-    Ok(if summary.failed == 0 { 0 } else { 1 })
+    Ok(if summary.failed == 0 && compact_failed == 0 {
+        0
+    } else {
+        1
+    })
 }
 
 #[cfg(not(feature = "agent-adapters"))]
@@ -325,6 +514,151 @@ pub async fn execute_sync(_args: SyncSessionsArgs) -> anyhow::Result<i32> {
     Ok(2)
 }
 
+#[cfg(feature = "agent-adapters")]
+pub async fn execute_pull(args: PullSessionsArgs) -> anyhow::Result<i32> {
+    {
+        let level = args
+            .log_level
+            .clone()
+            .or_else(|| std::env::var("RUST_LOG").ok())
+            .unwrap_or_else(|| "info".to_string());
+        init_sync_logging(&level, args.log_format.eq_ignore_ascii_case("json"));
+    }
+
+    let format = match args.format.as_str() {
+        "default" => OutputFormat::Default,
+        "json" => OutputFormat::Json,
+        other => {
+            eprintln!("Error: --format must be default or json, got {other}");
+            return Ok(2);
+        }
+    };
+    if args.restore && args.out.is_some() {
+        eprintln!("Error: --restore and --out are mutually exclusive");
+        return Ok(2);
+    }
+    let backend = match backend_from(args.sink_dir, args.webdav_url) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Ok(2);
+        }
+    };
+    // Pulling someone else's sessions is the point, so unlike sync the owner
+    // is not checked against the credential-derived one; it is only a default.
+    let Some(owner) = args
+        .owner
+        .or_else(|| std::env::var("AIKIT_SYNC_OWNER").ok())
+        .or_else(credential_owner_from_env)
+        .filter(|o| !o.is_empty())
+    else {
+        eprintln!("Error: --owner/AIKIT_SYNC_OWNER is required");
+        return Ok(2);
+    };
+    let bucket = args
+        .bucket
+        .or_else(|| std::env::var("AIKIT_SYNC_BUCKET").ok())
+        .unwrap_or_default();
+    let endpoint = args
+        .endpoint
+        .or_else(|| std::env::var("AIKIT_SYNC_ENDPOINT").ok())
+        .unwrap_or_default();
+    if backend == SyncBackend::S3 && (bucket.is_empty() || endpoint.is_empty()) {
+        eprintln!(
+            "Error: --bucket/AIKIT_SYNC_BUCKET and --endpoint/AIKIT_SYNC_ENDPOINT are required"
+        );
+        return Ok(2);
+    }
+    let tool = match &args.tool {
+        Some(tool) => parse_tools(std::slice::from_ref(tool))?.and_then(|t| t.first().copied()),
+        None => None,
+    };
+
+    let config = PullConfig {
+        key_prefix: args
+            .key_prefix
+            .or_else(|| std::env::var("AIKIT_SYNC_PREFIX").ok())
+            .unwrap_or_else(|| "sessions/".to_string()),
+        owner,
+        tool,
+        session_id: args.session,
+        destination: if args.restore {
+            PullDestination::Restore
+        } else {
+            PullDestination::Dir(args.out.unwrap_or_else(|| PathBuf::from("aikit-pull")))
+        },
+        overwrite: args.overwrite,
+        ingest: args.ingest,
+    };
+    let sink = open_backend(
+        &backend,
+        S3SinkConfig {
+            path_style: default_path_style(&endpoint),
+            bucket,
+            endpoint,
+            region: args
+                .region
+                .or_else(|| std::env::var("AIKIT_SYNC_REGION").ok())
+                .unwrap_or_else(|| "us-east-1".to_string()),
+            allow_http: args.allow_http || env_bool("AIKIT_SYNC_ALLOW_HTTP"),
+            endpoint_ca_bundle: std::env::var_os("AIKIT_SYNC_ENDPOINT_CA_BUNDLE")
+                .map(PathBuf::from),
+        },
+    )?;
+    let sink = match encrypt_with_key_file(sink, key_file_from(args.key_file).as_deref()) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return Ok(2);
+        }
+    };
+    let event_store = if config.ingest {
+        let conn = crate::cli::serve::storage::schema::open(&crate::cli::serve::capture_db_path())?;
+        Some(crate::cli::serve::storage::SqliteEventStore::new(conn))
+    } else {
+        None
+    };
+
+    let registry = default_registry();
+    let summary = match pull_sessions(
+        sink.as_ref(),
+        &registry,
+        event_store
+            .as_ref()
+            .map(|s| s as &dyn aikit_session_capture::EventStore),
+        &config,
+    )
+    .await
+    {
+        Ok(summary) => summary,
+        Err(aikit_session_sync::SyncError::Auth(e)) => {
+            eprintln!("Error: auth: {e}");
+            return Ok(2);
+        }
+        Err(e) => return Err(anyhow::anyhow!("{e}")),
+    };
+    if matches!(format, OutputFormat::Json) {
+        println!("{}", serde_json::to_string(&summary)?);
+    } else {
+        println!(
+            "pulled={} written={} unchanged={} skipped_existing={} failed={} events_ingested={}",
+            summary.pulled,
+            summary.written,
+            summary.unchanged,
+            summary.skipped_existing,
+            summary.failed,
+            summary.events_ingested
+        );
+    }
+    Ok(if summary.failed == 0 { 0 } else { 1 })
+}
+
+#[cfg(not(feature = "agent-adapters"))]
+pub async fn execute_pull(_args: PullSessionsArgs) -> anyhow::Result<i32> {
+    eprintln!("Error: session pull requires the agent-adapters feature");
+    Ok(2)
+}
+
 // ── event formatting ──────────────────────────────────────────────────────────
 
 fn print_event(event: &AgentEvent, ndjson: bool) {
@@ -377,6 +711,10 @@ fn default_registry() -> Registry {
     ));
     #[cfg(feature = "codex")]
     registry.register(Box::new(aikit_session_capture::codex::CodexAdapter::new()));
+    #[cfg(feature = "opencode")]
+    registry.register(Box::new(
+        aikit_session_capture::opencode::OpenCodeAdapter::new(),
+    ));
     registry
 }
 
@@ -386,11 +724,7 @@ async fn watch_sync(
     registry: &Registry,
     allow: Option<&[ToolKind]>,
 ) -> anyhow::Result<()> {
-    let adapters: Vec<_> = registry
-        .detected(allow)
-        .into_iter()
-        .filter(|adapter| matches!(adapter.kind(), ToolKind::ClaudeCode | ToolKind::Codex))
-        .collect();
+    let adapters = aikit_session_sync::engine::sync_adapters(registry, allow);
     let mut watcher =
         NotifyWatchDriver::new(adapters.clone(), std::time::Duration::from_millis(250))
             .map_err(|e| anyhow::anyhow!("watcher setup failed: {e}"))?;
@@ -450,3 +784,97 @@ fn env_bool(name: &str) -> bool {
 fn default_path_style(endpoint: &str) -> bool {
     !endpoint.contains(".amazonaws.com")
 }
+
+/// `--sink-dir` / `--webdav-url` (or `AIKIT_SYNC_DIR` / `AIKIT_SYNC_WEBDAV_URL`)
+/// select a non-S3 backend; at most one may be given.
+#[cfg(feature = "agent-adapters")]
+fn backend_from(
+    sink_dir: Option<PathBuf>,
+    webdav_url: Option<String>,
+) -> Result<SyncBackend, &'static str> {
+    let sink_dir = sink_dir.or_else(|| std::env::var_os("AIKIT_SYNC_DIR").map(PathBuf::from));
+    let webdav_url = webdav_url.or_else(|| std::env::var("AIKIT_SYNC_WEBDAV_URL").ok());
+    match (sink_dir, webdav_url) {
+        (Some(_), Some(_)) => Err("--sink-dir and --webdav-url are mutually exclusive"),
+        (Some(dir), None) => Ok(SyncBackend::Dir(dir)),
+        (None, Some(url)) => Ok(SyncBackend::WebDav(url)),
+        (None, None) => Ok(SyncBackend::S3),
+    }
+}
+
+/// Build the sink for `backend`. WebDAV reuses the S3 settings for plain-HTTP
+/// and custom-CA handling; its credentials, like S3's, come from the
+/// environment only.
+#[cfg(feature = "agent-adapters")]
+fn open_backend(backend: &SyncBackend, s3: S3SinkConfig) -> anyhow::Result<Arc<dyn SyncSink>> {
+    Ok(match backend {
+        SyncBackend::S3 => Arc::new(S3Sink::new(s3)?),
+        SyncBackend::Dir(root) => Arc::new(FsSink::new(root.clone())),
+        SyncBackend::WebDav(url) => Arc::new(WebDavSink::new(WebDavSinkConfig {
+            url: url.clone(),
+            username: std::env::var("AIKIT_SYNC_WEBDAV_USER").ok(),
+            password: std::env::var("AIKIT_SYNC_WEBDAV_PASSWORD").ok(),
+            allow_http: s3.allow_http,
+            endpoint_ca_bundle: s3.endpoint_ca_bundle,
+        })?),
+    })
+}
+
+#[cfg(feature = "agent-adapters")]
+fn key_file_from(flag: Option<PathBuf>) -> Option<PathBuf> {
+    flag.or_else(|| std::env::var_os("AIKIT_SYNC_KEY_FILE").map(PathBuf::from))
+}
+
+/// The default scrubber, extended by `--scrub-rules` / `AIKIT_SCRUB_RULES`.
+#[cfg(feature = "agent-adapters")]
+fn scrubber_from(
+    flag: Option<PathBuf>,
+) -> Result<aikit_session_capture::SecretScrubber, aikit_session_capture::ScrubConfigError> {
+    match flag.or_else(|| std::env::var_os("AIKIT_SCRUB_RULES").map(PathBuf::from)) {
+        Some(path) => aikit_session_capture::SecretScrubber::load(&path),
+        None => Ok(aikit_session_capture::SecretScrubber::default()),
+    }
+}
+
+#[cfg(feature = "agent-adapters")]
+fn print_scrub_audit(
+    audit: &aikit_session_sync::ScrubAudit,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    if matches!(format, OutputFormat::Json) {
+        println!("{}", serde_json::to_string(audit)?);
+        return Ok(());
+    }
+    let counts = |report: &aikit_session_capture::ScrubReport| {
+        report
+            .counts
+            .iter()
+            .map(|(label, n)| format!("{label}={n}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    for entry in &audit.files {
+        println!("{} {}", entry.file.display(), counts(&entry.report));
+    }
+    let line = format!(
+        "scanned={} redacted_files={} failed={} {}",
+        audit.scanned,
+        audit.files.len(),
+        audit.failed,
+        counts(&audit.totals)
+    );
+    println!("{}", line.trim_end());
+    Ok(())
+}
+
+/// Wrap `sink` in client-side encryption when a key file is configured.
+#[cfg(feature = "agent-adapters")]
+fn encrypt_with_key_file(
+    sink: Arc<dyn SyncSink>,
+    key_file: Option<&std::path::Path>,
+) -> Result<Arc<dyn SyncSink>, aikit_session_sync::SyncError> {
+    Ok(match key_file {
+        Some(path) => Arc::new(EncryptedSink::new(sink, Keyring::load(path)?)),
+        None => sink,
+    })
+}
@@ -45,6 +45,9 @@ pub struct AgentDefinition {
     pub tools: Option<Vec<String>>,
     pub disallowed_tools: Option<Vec<String>>,
     pub model: Option<String>,
+    /// Backend that runs this definition when the built-in agent spawns it as
+    /// a sub-agent (`codex`, `claude`, ...). `None` = the built-in loop.
+    pub agent: Option<String>,
     pub delegation: Option<DelegationAllowlist>,
 }
 
@@ -84,6 +87,8 @@ struct RawJsonAgent {
     #[serde(default)]
     model: Option<String>,
     #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
     agents: Option<JsonValue>,
 }
 
@@ -190,6 +195,10 @@ fn raw_json_to_def(id: &str, raw: RawJsonAgent) -> Result<AgentDefinition, Parse
             .model
             .map(|m| m.trim().to_string())
             .filter(|m| !m.is_empty()),
+        agent: raw
+            .agent
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty()),
         delegation: delegation_from_json(raw.agents)?,
     })
 }
@@ -227,6 +236,8 @@ struct YamlFrontmatter {
     #[serde(default)]
     model: Option<String>,
     #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
     agents: Option<serde_yaml::Value>,
 }
 
@@ -327,6 +338,7 @@ pub fn parse_agent_markdown(content: &str) -> Result<AgentDefinition, ParseError
             tools: None,
             disallowed_tools: None,
             model: None,
+            agent: None,
             agents: None,
         },
         Some(txt) => {
@@ -378,6 +390,10 @@ pub fn parse_agent_markdown(content: &str) -> Result<AgentDefinition, ParseError
             .model
             .map(|m| m.trim().to_string())
             .filter(|m| !m.is_empty()),
+        agent: ym
+            .agent
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty()),
         delegation: delegation_from_yaml(ym.agents)?,
     })
 }
@@ -627,6 +643,7 @@ You review code.";
         assert!(d.tools.is_none());
         assert!(d.disallowed_tools.is_none());
         assert!(d.model.is_none());
+        assert!(d.agent.is_none());
         assert!(d.delegation.is_none());
     }
 
@@ -637,6 +654,7 @@ name: coord
 description: Coordinates specialized workers
 tools: ['agent', 'read', 'search']
 model: inherit
+agent: codex
 disallowedTools: Write, Edit
 agents: '*'
 ---
@@ -657,6 +675,7 @@ Route work.";
             &vec!["Write".to_string(), "Edit".to_string()]
         );
         assert_eq!(d.model.as_deref(), Some("inherit"));
+        assert_eq!(d.agent.as_deref(), Some("codex"));
         assert_eq!(d.delegation, Some(DelegationAllowlist::All));
     }
 
@@ -784,6 +803,7 @@ description: y
     "tools": ["Read", "Edit"],
     "disallowedTools": ["Bash"],
     "model": "sonnet",
+    "agent": "claude",
     "agents": ["a", "b"]
   }
 }"#;
@@ -792,6 +812,7 @@ description: y
         assert_eq!(d.name, "worker");
         assert_eq!(d.description, "Does work");
         assert_eq!(d.prompt, "You are worker");
+        assert_eq!(d.agent.as_deref(), Some("claude"));
         assert_eq!(
             d.delegation,
             Some(DelegationAllowlist::Names(vec!["a".into(), "b".into()]))
@@ -916,6 +937,7 @@ x";
             tools: None,
             disallowed_tools: None,
             model: None,
+            agent: None,
             delegation: None,
         }
     }
@@ -3,3 +3,4 @@
 pub mod agent_select;
 pub mod output;
 pub mod progress_render;
+pub mod session_chat;
@@ -1,10 +1,12 @@
 //! Terminal rendering helper for `aikit run --progress`.
 //!
 //! Supports two modes:
-//! - **TTY mode**: redraws a viewport in-place using cursor manipulation.
-//! - **Non-TTY mode**: appends new lines to stderr without cursor movement.
+//! - **TTY mode**: redraws the structured step view (tool calls, results,
+//!   reasoning, file changes) plus the token/cost meter in-place using cursor
+//!   manipulation.
+//! - **Non-TTY mode**: appends each new flat line to stderr without cursor movement.
 
-use aikit_sdk::{ProgressSink, RunProgress};
+use aikit_sdk::{ProgressSink, ProgressViewConfig, RunProgress, ViewToggles};
 use crossterm::{
     cursor,
     terminal::{self, ClearType},
@@ -18,6 +20,13 @@ pub struct ProgressRenderer {
     stderr: Stderr,
     /// Number of lines rendered in the last TTY redraw (used for cursor positioning).
     last_render_lines: usize,
+    /// Rows already written in non-TTY mode (see [`RunProgress::rows_added`]).
+    rows_printed: u64,
+    toggles: ViewToggles,
+    /// File-change summary from the latest render, repeated by [`finalize`].
+    ///
+    /// [`finalize`]: ProgressRenderer::finalize
+    files_footer: Option<String>,
 }
 
 impl ProgressRenderer {
@@ -28,6 +37,9 @@ impl ProgressRenderer {
             is_tty,
             stderr: io::stderr(),
             last_render_lines: 0,
+            rows_printed: 0,
+            toggles: ViewToggles::default(),
+            files_footer: None,
         })
     }
 
@@ -37,11 +49,22 @@ impl ProgressRenderer {
             is_tty: false,
             stderr: io::stderr(),
             last_render_lines: 0,
+            rows_printed: 0,
+            toggles: ViewToggles::default(),
+            files_footer: None,
         }
     }
 
+    /// Set which collapsible parts of the step view (reasoning, tool results)
+    /// are expanded. Only affects TTY mode.
+    pub fn with_toggles(mut self, toggles: ViewToggles) -> Self {
+        self.toggles = toggles;
+        self
+    }
+
     /// Render the current progress state to stderr.
     pub fn render(&mut self, progress: &RunProgress) -> io::Result<()> {
+        self.files_footer = progress.files_footer();
         if self.is_tty {
             self.render_tty(progress)
         } else {
@@ -59,6 +82,9 @@ impl ProgressRenderer {
         if let Some(tokens) = final_tokens {
             writeln!(self.stderr, "{}", tokens)?;
         }
+        if let Some(files) = &self.files_footer {
+            writeln!(self.stderr, "{}", files)?;
+        }
         self.stderr.flush()
     }
 
@@ -69,37 +95,20 @@ impl ProgressRenderer {
     fn render_tty(&mut self, progress: &RunProgress) -> io::Result<()> {
         self.clear_viewport()?;
 
-        let lines: Vec<&str> = progress.formatted_lines().collect();
+        let lines = progress.view_lines(self.toggles);
+        let footer = progress.footer_lines();
         let (term_width, term_height) = terminal::size().unwrap_or((80, 24));
 
-        // Reserve space for token footer if present
-        let footer = progress.token_footer();
-        let footer_lines = if footer.is_some() { 1 } else { 0 };
+        // Reserve space for the footer (token meter, files, elapsed)
         let max_display = (term_height as usize)
-            .saturating_sub(footer_lines + 1)
+            .saturating_sub(footer.len() + 1)
             .max(1);
 
         let display_start = lines.len().saturating_sub(max_display);
-        let display = &lines[display_start..];
 
         let mut rendered = 0;
-        for line in display {
-            let truncated = if line.len() > term_width as usize {
-                &line[..term_width as usize]
-            } else {
-                line
-            };
-            writeln!(self.stderr, "{}", truncated)?;
-            rendered += 1;
-        }
-
-        if let Some(ref f) = footer {
-            let truncated = if f.len() > term_width as usize {
-                &f[..term_width as usize]
-            } else {
-                f.as_str()
-            };
-            writeln!(self.stderr, "{}", truncated)?;
+        for line in lines[display_start..].iter().chain(footer.iter()) {
+            writeln!(self.stderr, "{}", fit_width(line, term_width as usize))?;
             rendered += 1;
         }
 
@@ -108,10 +117,18 @@ impl ProgressRenderer {
     }
 
     fn render_append(&mut self, progress: &RunProgress) -> io::Result<()> {
-        // In non-TTY mode emit only the newest line (last in the ring buffer)
-        if let Some(last) = progress.formatted_lines().last() {
-            writeln!(self.stderr, "{}", last)?;
+        // In non-TTY mode emit each row once, as it is added
+        let added = progress.rows_added();
+        if added < self.rows_printed {
+            // The progress state was cleared; start counting again.
+            self.rows_printed = 0;
         }
+        let new_rows = (added - self.rows_printed) as usize;
+        let retained: Vec<&str> = progress.formatted_lines().collect();
+        for line in &retained[retained.len().saturating_sub(new_rows)..] {
+            writeln!(self.stderr, "{}", line)?;
+        }
+        self.rows_printed = added;
         self.stderr.flush()
     }
 
@@ -129,14 +146,32 @@ impl ProgressRenderer {
     }
 }
 
+/// Cut `line` to at most `width` characters (never splitting a UTF-8 sequence).
+pub(crate) fn fit_width(line: &str, width: usize) -> &str {
+    match line.char_indices().nth(width) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
 /// Adapter that implements `ProgressSink` using `ProgressRenderer`.
 pub struct ProgressRendererSink {
     renderer: ProgressRenderer,
+    config: ProgressViewConfig,
 }
 
 impl ProgressRendererSink {
     pub fn new(renderer: ProgressRenderer) -> Self {
-        Self { renderer }
+        Self {
+            renderer,
+            config: ProgressViewConfig::default(),
+        }
+    }
+
+    /// Build the progress this sink renders with `config`.
+    pub fn with_view_config(mut self, config: ProgressViewConfig) -> Self {
+        self.config = config;
+        self
     }
 }
 
@@ -148,4 +183,8 @@ impl ProgressSink for ProgressRendererSink {
     fn on_finalize(&mut self, exit_code: i32, token_footer: Option<String>) {
         let _ = self.renderer.finalize(exit_code, token_footer);
     }
+
+    fn view_config(&self) -> ProgressViewConfig {
+        self.config.clone()
+    }
 }
@@ -0,0 +1,838 @@
+//! Full-screen chat UI for `aikit session new`.
+//!
+//! Drives any [`LiveSession`] from a ratatui screen: a scrollback of the
+//! session's canonical events (rendered through [`RunProgress`], the same view
+//! `aikit agent run --progress` uses), a multi-line input box, keybindings for
+//! the `LiveSession` controls (`interrupt`, `set_model`, `get_context_usage`)
+//! and inline approval prompts answering the session's [`PermissionCallback`].
+//!
+//! The session is opened lazily on the first turn, so the UI can start with
+//! an empty input box (or a resumed transcript) when no `--prompt` is given.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aikit_sdk::runner::{LiveSession, PermissionCallback, ToolApprovalRequest, ToolDecision};
+use aikit_sdk::{
+    AgentEvent, AgentEventPayload, AgentEventStream, MessageKind, MessagePhase, MessageRole,
+    ProgressViewConfig, RunProgress, StreamMessage, ViewToggles,
+};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Position},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::tui::progress_render::fit_width;
+
+/// Opens the live session for the first turn. Receives the first turn's text
+/// and the UI's permission callback; returns the control handle and its
+/// event stream.
+pub type SessionOpener = Box<
+    dyn FnMut(
+        String,
+        PermissionCallback,
+    ) -> anyhow::Result<(Box<dyn LiveSession>, Receiver<AgentEvent>)>,
+>;
+
+/// What the chat UI needs to start.
+pub struct ChatConfig {
+    /// Agent key shown in the status bar.
+    pub agent: String,
+    /// Model shown in the status bar until changed with `set_model`.
+    pub model: Option<String>,
+    /// First turn to send on startup, if any.
+    pub prompt: Option<String>,
+    /// Events replayed into the scrollback before the session starts (a
+    /// resumed transcript).
+    pub history: Vec<AgentEvent>,
+    pub open: SessionOpener,
+}
+
+/// Run the chat UI until the user quits. Disconnects the session on exit.
+pub fn run_session_chat(config: ChatConfig) -> anyhow::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_chat_app(&mut terminal, config);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+/// Messages delivered to the UI thread from the session's threads.
+enum AppEvent {
+    Agent(AgentEvent),
+    Approval(ToolApprovalRequest, Sender<ToolDecision>),
+    /// The session's event stream ended.
+    Closed,
+}
+
+fn run_chat_app<B: Backend>(terminal: &mut Terminal<B>, config: ChatConfig) -> anyhow::Result<()>
+where
+    <B as Backend>::Error: Send + Sync + 'static,
+{
+    let ChatConfig {
+        agent,
+        model,
+        prompt,
+        history,
+        mut open,
+    } = config;
+    let mut state = ChatState::new(agent, model);
+    for event in &history {
+        state.push_event(event);
+    }
+
+    let (app_tx, app_rx) = mpsc::channel::<AppEvent>();
+    let mut session: Option<Box<dyn LiveSession>> = None;
+    if let Some(prompt) = prompt.filter(|p| !p.trim().is_empty()) {
+        state.perform(ChatAction::Send(prompt), &mut session, &mut open, &app_tx);
+    }
+
+    loop {
+        while let Ok(app_event) = app_rx.try_recv() {
+            state.apply(app_event);
+        }
+        terminal.draw(|f| draw(f, &state))?;
+
+        if !event::poll(Duration::from_millis(50))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match state.handle_key(key) {
+                Some(ChatAction::Quit) => break,
+                Some(action) => state.perform(action, &mut session, &mut open, &app_tx),
+                None => {}
+            }
+        }
+    }
+
+    if let Some(session) = session {
+        let _ = session.disconnect();
+    }
+    Ok(())
+}
+
+/// A control request produced by a keypress.
+#[derive(Debug, Clone, PartialEq)]
+enum ChatAction {
+    Send(String),
+    Interrupt,
+    SetModel(Option<String>),
+    ContextUsage,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputMode {
+    Message,
+    /// Editing the model name for `set_model`.
+    Model,
+}
+
+struct PendingApproval {
+    request: ToolApprovalRequest,
+    reply: Sender<ToolDecision>,
+}
+
+/// UI state, independent of the terminal so key handling can be tested.
+struct ChatState {
+    agent: String,
+    model: Option<String>,
+    progress: RunProgress,
+    toggles: ViewToggles,
+    input: String,
+    model_input: String,
+    mode: InputMode,
+    approvals: VecDeque<PendingApproval>,
+    /// Lines scrolled up from the bottom of the scrollback.
+    scroll_back: usize,
+    /// A turn is in flight (cleared by the turn's `Result`).
+    busy: bool,
+    /// Model chosen before the session was opened; applied once it is.
+    pending_model: Option<Option<String>>,
+    context: Option<String>,
+    notice: Option<String>,
+}
+
+impl ChatState {
+    fn new(agent: String, model: Option<String>) -> Self {
+        let progress = RunProgress::new(ProgressViewConfig {
+            max_rows: 5_000,
+            max_text_width: 4_000,
+            max_tool_output_chars: 200,
+            model: model.clone(),
+            ..ProgressViewConfig::default()
+        });
+        Self {
+            agent,
+            model,
+            progress,
+            toggles: ViewToggles::default(),
+            input: String::new(),
+            model_input: String::new(),
+            mode: InputMode::Message,
+            approvals: VecDeque::new(),
+            scroll_back: 0,
+            busy: false,
+            pending_model: None,
+            context: None,
+            notice: None,
+        }
+    }
+
+    fn push_event(&mut self, event: &AgentEvent) {
+        if matches!(event.payload, AgentEventPayload::Result { .. }) {
+            self.busy = false;
+        }
+        self.progress.push(&event.agent_key, event);
+    }
+
+    fn apply(&mut self, app_event: AppEvent) {
+        match app_event {
+            AppEvent::Agent(event) => self.push_event(&event),
+            AppEvent::Approval(request, reply) => {
+                self.approvals.push_back(PendingApproval { request, reply })
+            }
+            AppEvent::Closed => {
+                self.busy = false;
+                self.notice = Some("session ended".to_string());
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<ChatAction> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        if self.approvals.front().is_some() {
+            let decision = match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(ToolDecision::Allow),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    Some(ToolDecision::Deny {
+                        message: "Denied by the user".to_string(),
+                    })
+                }
+                _ => None,
+            };
+            if let Some(decision) = decision {
+                if let Some(pending) = self.approvals.pop_front() {
+                    let _ = pending.reply.send(decision);
+                }
+                return None;
+            }
+        }
+
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Some(ChatAction::Interrupt),
+            KeyCode::Char('q') if ctrl => return Some(ChatAction::Quit),
+            KeyCode::Char('d') if ctrl && self.input.is_empty() => return Some(ChatAction::Quit),
+            KeyCode::Char('u') if ctrl => return Some(ChatAction::ContextUsage),
+            KeyCode::Char('o') if ctrl => {
+                self.mode = InputMode::Model;
+                self.model_input = self.model.clone().unwrap_or_default();
+                return None;
+            }
+            KeyCode::Char('r') if ctrl => {
+                self.toggles.show_reasoning = !self.toggles.show_reasoning;
+                return None;
+            }
+            KeyCode::Char('t') if ctrl => {
+                self.toggles.expand_tool_results = !self.toggles.expand_tool_results;
+                return None;
+            }
+            KeyCode::PageUp => {
+                self.scroll_back = self.scroll_back.saturating_add(10);
+                return None;
+            }
+            KeyCode::PageDown => {
+                self.scroll_back = self.scroll_back.saturating_sub(10);
+                return None;
+            }
+            _ => {}
+        }
+
+        match self.mode {
+            InputMode::Model => match key.code {
+                KeyCode::Esc => self.mode = InputMode::Message,
+                KeyCode::Enter => {
+                    self.mode = InputMode::Message;
+                    let model = self.model_input.trim().to_string();
+                    return Some(ChatAction::SetModel((!model.is_empty()).then_some(model)));
+                }
+                KeyCode::Backspace => {
+                    self.model_input.pop();
+                }
+                KeyCode::Char(c) if !ctrl => self.model_input.push(c),
+                _ => {}
+            },
+            InputMode::Message => match key.code {
+                KeyCode::Enter if alt => self.input.push('\n'),
+                KeyCode::Char('j') if ctrl => self.input.push('\n'),
+                KeyCode::Enter => {
+                    let text = self.input.trim().to_string();
+                    if !text.is_empty() {
+                        self.input.clear();
+                        self.scroll_back = 0;
+                        return Some(ChatAction::Send(text));
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Tab => self.input.push_str("    "),
+                KeyCode::Char(c) if !ctrl => self.input.push(c),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Carry out `action` against the session, opening it on the first turn.
+    fn perform(
+        &mut self,
+        action: ChatAction,
+        session: &mut Option<Box<dyn LiveSession>>,
+        open: &mut SessionOpener,
+        app_tx: &Sender<AppEvent>,
+    ) {
+        match action {
+            ChatAction::Send(text) => {
+                self.push_event(&user_turn_event(&self.agent, &text));
+                let sent = match session {
+                    Some(session) => session.send_turn(text).map_err(|e| e.to_string()),
+                    None => match open(text, permission_callback(app_tx.clone())) {
+                        Ok((opened, events)) => {
+                            forward_events(events, app_tx.clone());
+                            if let Some(model) = self.pending_model.take() {
+                                self.notice = Some(control_notice(
+                                    "set_model",
+                                    opened.set_model(model).map(|_| ()),
+                                ));
+                            }
+                            *session = Some(opened);
+                            Ok(())
+                        }
+                        Err(e) => Err(e.to_string()),
+                    },
+                };
+                match sent {
+                    Ok(()) => self.busy = true,
+                    Err(e) => self.notice = Some(format!("send failed: {e}")),
+                }
+            }
+            ChatAction::Interrupt => match session {
+                Some(session) => {
+                    self.notice = Some(control_notice("interrupt", session.interrupt()));
+                    self.busy = false;
+                }
+                None => self.notice = Some("no turn to interrupt".to_string()),
+            },
+            ChatAction::SetModel(model) => {
+                self.model = model.clone();
+                self.progress.set_model(model.clone());
+                match session {
+                    Some(session) => {
+                        self.notice = Some(control_notice("set_model", session.set_model(model)))
+                    }
+                    None => {
+                        self.pending_model = Some(model);
+                        self.notice = Some("model applies once the session starts".to_string());
+                    }
+                }
+            }
+            ChatAction::ContextUsage => match session {
+                Some(session) => match session.get_context_usage() {
+                    Ok(usage) => self.context = Some(context_summary(&usage)),
+                    Err(e) => self.notice = Some(format!("get_context_usage: {e}")),
+                },
+                None => self.notice = Some("no session yet".to_string()),
+            },
+            ChatAction::Quit => {}
+        }
+    }
+}
+
+/// Permission callback that asks the UI thread and blocks until the user
+/// answers. Denies when the UI has gone away.
+fn permission_callback(app_tx: Sender<AppEvent>) -> PermissionCallback {
+    Arc::new(move |request: ToolApprovalRequest| {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let closed = || ToolDecision::Deny {
+            message: "The session UI closed before answering".to_string(),
+        };
+        if app_tx.send(AppEvent::Approval(request, reply_tx)).is_err() {
+            return closed();
+        }
+        reply_rx.recv().unwrap_or_else(|_| closed())
+    })
+}
+
+fn forward_events(events: Receiver<AgentEvent>, app_tx: Sender<AppEvent>) {
+    std::thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            if app_tx.send(AppEvent::Agent(event)).is_err() {
+                return;
+            }
+        }
+        let _ = app_tx.send(AppEvent::Closed);
+    });
+}
+
+fn control_notice(control: &str, result: Result<(), aikit_sdk::runner::ControlError>) -> String {
+    match result {
+        Ok(()) => format!("{control}: ok"),
+        Err(e) => format!("{control}: {e}"),
+    }
+}
+
+/// One-line digest of a `get_context_usage` response.
+fn context_summary(usage: &serde_json::Value) -> String {
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| usage.get(*k).and_then(|v| v.as_u64()))
+    };
+    match (
+        field(&["totalTokens", "total_tokens", "used"]),
+        field(&["maxTokens", "max_tokens", "contextWindow", "context_window"]),
+    ) {
+        (Some(used), Some(max)) if max > 0 => {
+            format!("ctx {used}/{max} ({}%)", used * 100 / max)
+        }
+        (Some(used), _) => format!("ctx {used} tokens"),
+        _ => {
+            let raw = usage.to_string();
+            format!("ctx {}", raw.chars().take(60).collect::<String>())
+        }
+    }
+}
+
+/// The user's own turn, as a canonical event so it lands in the scrollback.
+fn user_turn_event(agent: &str, text: &str) -> AgentEvent {
+    AgentEvent {
+        agent_key: agent.to_string(),
+        seq: 0,
+        stream: AgentEventStream::Stdout,
+        payload: AgentEventPayload::StreamMessage(StreamMessage {
+            text: text.to_string(),
+            phase: MessagePhase::Final,
+            role: MessageRole::User,
+            kind: MessageKind::Message,
+            source: AgentEventStream::Stdout,
+            raw_line_seq: 0,
+            turn_id: None,
+        }),
+    }
+}
+
+/// Canonical events for a resumed transcript, so a history session renders
+/// exactly like a live one.
+pub fn history_events(
+    agent: &str,
+    messages: &[aikit_sdk::history::HistoryMessage],
+) -> Vec<AgentEvent> {
+    use aikit_sdk::history::{HistoryBlock, HistoryContent};
+
+    let message = |role: MessageRole, kind: MessageKind, text: &str| {
+        AgentEventPayload::StreamMessage(StreamMessage {
+            text: text.to_string(),
+            phase: MessagePhase::Final,
+            role,
+            kind,
+            source: AgentEventStream::Stdout,
+            raw_line_seq: 0,
+            turn_id: None,
+        })
+    };
+    let mut payloads = Vec::new();
+    for msg in messages {
+        match &msg.content {
+            HistoryContent::Text(text) => {
+                payloads.push(message(msg.role, MessageKind::Message, text))
+            }
+            HistoryContent::Blocks(blocks) => {
+                for block in blocks {
+                    match block {
+                        HistoryBlock::Text { text } => {
+                            payloads.push(message(msg.role, MessageKind::Message, text))
+                        }
+                        HistoryBlock::Thinking { text } => payloads.push(message(
+                            MessageRole::Assistant,
+                            MessageKind::Reasoning,
+                            text,
+                        )),
+                        HistoryBlock::ToolUse { id, name, input }
+                        | HistoryBlock::ServerToolUse { id, name, input } => {
+                            payloads.push(AgentEventPayload::ToolUse {
+                                call_id: id.clone(),
+                                tool_name: name.clone(),
+                                input: input.clone(),
+                            })
+                        }
+                        HistoryBlock::ToolResult {
+                            tool_use_id,
+                            content,
+                            is_error,
+                        }
+                        | HistoryBlock::ServerToolResult {
+                            tool_use_id,
+                            content,
+                            is_error,
+                        } => payloads.push(AgentEventPayload::ToolResult {
+                            call_id: tool_use_id.clone(),
+                            output: content.clone(),
+                            is_error: *is_error,
+                        }),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    payloads
+        .into_iter()
+        .enumerate()
+        .map(|(seq, payload)| AgentEvent {
+            agent_key: agent.to_string(),
+            seq: seq as u64,
+            stream: AgentEventStream::Stdout,
+            payload,
+        })
+        .collect()
+}
+
+// ── rendering ─────────────────────────────────────────────────────────────────
+
+fn draw(f: &mut Frame, state: &ChatState) {
+    let input_lines = state.input.split('\n').count().clamp(1, 6) as u16;
+    let approval_height = if state.approvals.is_empty() { 0 } else { 4 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),                  // Scrollback
+            Constraint::Length(approval_height), // Pending approval
+            Constraint::Length(input_lines + 2), // Input
+            Constraint::Length(1),               // Status bar
+        ])
+        .split(f.area());
+
+    // Scrollback: newest at the bottom; lines are cut to width so the scroll
+    // offset is exact without measuring wrapped text.
+    let area = chunks[0];
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+    let lines = state.progress.view_lines(state.toggles);
+    let max_back = lines.len().saturating_sub(height);
+    let end = lines.len() - state.scroll_back.min(max_back);
+    let start = end.saturating_sub(height);
+    let shown: Vec<Line> = lines[start..end]
+        .iter()
+        .map(|l| Line::styled(fit_width(l, width).to_string(), line_style(l)))
+        .collect();
+    let title = if end < lines.len() {
+        format!(" {} (scrolled, PgDn to follow) ", state.agent)
+    } else {
+        format!(" {} ", state.agent)
+    };
+    f.render_widget(
+        Paragraph::new(shown).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+
+    if let Some(pending) = state.approvals.front() {
+        let more = match state.approvals.len() {
+            1 => String::new(),
+            n => format!(" (+{} queued)", n - 1),
+        };
+        let body = Paragraph::new(format!(
+            "{} {}",
+            pending.request.tool_name, pending.request.input
+        ))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!(" Approve tool call? [y]es / [n]o{more} ")),
+        );
+        f.render_widget(body, chunks[1]);
+    }
+
+    let (title, text) = match state.mode {
+        InputMode::Message => (" message · Enter send · Alt+Enter newline ", &state.input),
+        InputMode::Model => (
+            " model · Enter apply (empty = default) · Esc cancel ",
+            &state.model_input,
+        ),
+    };
+    let input_area = chunks[2];
+    let visible: Vec<&str> = text.split('\n').collect();
+    let skip = visible.len().saturating_sub(input_lines as usize);
+    let input = Paragraph::new(visible[skip..].join("\n"))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(input, input_area);
+    if state.approvals.is_empty() {
+        let last = visible.last().copied().unwrap_or("");
+        f.set_cursor_position(Position::new(
+            input_area.x
+                + 1
+                + (last.chars().count() as u16).min(input_area.width.saturating_sub(3)),
+            input_area.y + (visible.len() - skip) as u16,
+        ));
+    }
+
+    f.render_widget(Paragraph::new(status_line(state)), chunks[3]);
+}
+
+fn status_line(state: &ChatState) -> Line<'static> {
+    let key = |k: &'static str| Span::styled(k, Style::default().fg(Color::Cyan));
+    let mut spans = vec![
+        Span::styled(
+            if state.busy {
+                "● working "
+            } else {
+                "○ idle "
+            },
+            Style::default().fg(if state.busy {
+                Color::Green
+            } else {
+                Color::DarkGray
+            }),
+        ),
+        Span::raw(format!(
+            "model={} ",
+            state.model.as_deref().unwrap_or("default")
+        )),
+    ];
+    if let Some(tokens) = state.progress.token_footer() {
+        spans.push(Span::raw(format!(
+            "{} ",
+            tokens.trim_start_matches("[tokens] ")
+        )));
+    }
+    if let Some(context) = &state.context {
+        spans.push(Span::raw(format!("{context} ")));
+    }
+    if let Some(notice) = &state.notice {
+        spans.push(Span::styled(
+            format!("{notice} "),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    spans.extend([
+        Span::raw("· "),
+        key("^C"),
+        Span::raw(" interrupt "),
+        key("^O"),
+        Span::raw(" model "),
+        key("^U"),
+        Span::raw(" context "),
+        key("^R"),
+        Span::raw(" reasoning "),
+        key("^T"),
+        Span::raw(" tools "),
+        key("^Q"),
+        Span::raw(" quit"),
+    ]);
+    Line::from(spans)
+}
+
+fn line_style(line: &str) -> Style {
+    let body = line.split_once("] ").map(|(_, b)| b).unwrap_or(line);
+    if body.starts_with("user>") {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    } else if body.starts_with("reasoning>") {
+        Style::default().fg(Color::DarkGray)
+    } else if body.starts_with('✗') || body.starts_with("tool error>") {
+        Style::default().fg(Color::Red)
+    } else if body.starts_with('✓') || body.starts_with('…') {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn type_text(state: &mut ChatState, text: &str) {
+        for c in text.chars() {
+            assert_eq!(state.handle_key(key(KeyCode::Char(c))), None);
+        }
+    }
+
+    #[test]
+    fn enter_sends_and_alt_enter_adds_a_line() {
+        let mut state = ChatState::new("claude".into(), None);
+        type_text(&mut state, "line one");
+        state.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
+        type_text(&mut state, "line two");
+        assert_eq!(
+            state.handle_key(key(KeyCode::Enter)),
+            Some(ChatAction::Send("line one\nline two".into()))
+        );
+        assert!(state.input.is_empty());
+        // Empty input does not send.
+        assert_eq!(state.handle_key(key(KeyCode::Enter)), None);
+    }
+
+    #[test]
+    fn control_keys_map_to_live_session_controls() {
+        let mut state = ChatState::new("claude".into(), Some("sonnet".into()));
+        assert_eq!(state.handle_key(ctrl('c')), Some(ChatAction::Interrupt));
+        assert_eq!(state.handle_key(ctrl('u')), Some(ChatAction::ContextUsage));
+
+        assert_eq!(state.handle_key(ctrl('o')), None);
+        assert_eq!(state.mode, InputMode::Model);
+        for _ in 0.."sonnet".len() {
+            state.handle_key(key(KeyCode::Backspace));
+        }
+        type_text(&mut state, "opus");
+        assert_eq!(
+            state.handle_key(key(KeyCode::Enter)),
+            Some(ChatAction::SetModel(Some("opus".into())))
+        );
+        assert_eq!(state.mode, InputMode::Message);
+
+        state.handle_key(ctrl('r'));
+        state.handle_key(ctrl('t'));
+        assert!(state.toggles.show_reasoning && state.toggles.expand_tool_results);
+        assert_eq!(state.handle_key(ctrl('q')), Some(ChatAction::Quit));
+    }
+
+    #[test]
+    fn approval_prompt_answers_the_permission_callback() {
+        let (app_tx, app_rx) = mpsc::channel();
+        let callback = permission_callback(app_tx);
+        let asker = std::thread::spawn(move || {
+            callback(ToolApprovalRequest {
+                tool_name: "Bash".into(),
+                input: serde_json::json!({"command": "rm -rf build"}),
+                tool_use_id: Some("t1".into()),
+            })
+        });
+
+        let mut state = ChatState::new("claude".into(), None);
+        state.apply(app_rx.recv().unwrap());
+        assert_eq!(state.approvals.len(), 1);
+        // Typing is routed to the prompt, not the input box.
+        assert_eq!(state.handle_key(key(KeyCode::Char('n'))), None);
+        assert!(state.input.is_empty());
+        assert!(state.approvals.is_empty());
+        assert!(matches!(asker.join().unwrap(), ToolDecision::Deny { .. }));
+    }
+
+    #[test]
+    fn dropped_ui_denies_pending_approval() {
+        let (app_tx, app_rx) = mpsc::channel();
+        let callback = permission_callback(app_tx);
+        let asker = std::thread::spawn(move || {
+            callback(ToolApprovalRequest {
+                tool_name: "Write".into(),
+                input: serde_json::Value::Null,
+                tool_use_id: None,
+            })
+        });
+        let pending = app_rx.recv().unwrap();
+        drop(pending);
+        drop(app_rx);
+        assert!(matches!(asker.join().unwrap(), ToolDecision::Deny { .. }));
+    }
+
+    #[test]
+    fn turns_and_results_update_scrollback_and_busy_flag() {
+        let mut state = ChatState::new("codex".into(), None);
+        state.push_event(&user_turn_event("codex", "fix the build"));
+        state.busy = true;
+        state.push_event(&AgentEvent {
+            agent_key: "codex".into(),
+            seq: 1,
+            stream: AgentEventStream::Stdout,
+            payload: AgentEventPayload::Result {
+                text: "done".into(),
+                structured: None,
+                session_id: None,
+            },
+        });
+        assert!(!state.busy);
+        let lines = state.progress.view_lines(ViewToggles::default());
+        assert!(lines[0].ends_with("user> fix the build"), "{lines:?}");
+        assert!(lines[1].ends_with("result> done"), "{lines:?}");
+    }
+
+    #[test]
+    fn history_transcript_replays_as_canonical_events() {
+        let messages: Vec<aikit_sdk::history::HistoryMessage> =
+            serde_json::from_value(serde_json::json!([
+                {"role": "user", "uuid": "u1", "session_id": "s", "parent_tool_use_id": null,
+                 "content": {"text": "list files"}},
+                {"role": "assistant", "uuid": "a1", "session_id": "s", "parent_tool_use_id": null,
+                 "content": {"blocks": [
+                     {"thinking": {"text": "use ls"}},
+                     {"tool_use": {"id": "t1", "name": "Bash", "input": {"command": "ls"}}}
+                 ]}},
+                {"role": "tool", "uuid": "r1", "session_id": "s", "parent_tool_use_id": null,
+                 "content": {"blocks": [
+                     {"tool_result": {"tool_use_id": "t1", "content": "a.rs", "is_error": false}}
+                 ]}}
+            ]))
+            .unwrap();
+        let events = history_events("claude", &messages);
+        assert_eq!(events.len(), 4);
+
+        let mut state = ChatState::new("claude".into(), None);
+        for event in &events {
+            state.push_event(event);
+        }
+        let lines = state.progress.view_lines(ViewToggles::default());
+        assert!(lines[0].ends_with("user> list files"), "{lines:?}");
+        assert!(lines[1].contains("reasoning>"), "{lines:?}");
+        assert!(lines[2].ends_with("✓ Bash ls"), "{lines:?}");
+        assert!(lines[3].ends_with("└ a.rs"), "{lines:?}");
+    }
+
+    #[test]
+    fn context_summary_reads_common_shapes() {
+        assert_eq!(
+            context_summary(&serde_json::json!({"totalTokens": 5000, "maxTokens": 200000})),
+            "ctx 5000/200000 (2%)"
+        );
+        assert_eq!(
+            context_summary(&serde_json::json!({"used": 42})),
+            "ctx 42 tokens"
+        );
+    }
+}
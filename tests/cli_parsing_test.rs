@@ -224,6 +224,55 @@ mod tests {
         );
     }
 
+    /// --continue-from is accepted and echoed by dry-run
+    #[tokio::test]
+    async fn test_run_dry_run_with_continue_from() {
+        let mut h = harness();
+        let out = h
+            .run(&[
+                "aikit",
+                "agent",
+                "run",
+                "--agent",
+                "codex",
+                "--dry-run",
+                "-p",
+                "hello",
+                "--continue-from",
+                "claude:3f2c9a1e",
+            ])
+            .await;
+        assert_eq!(
+            out.exit_code, 0,
+            "dry-run with --continue-from should succeed; stderr: {}",
+            out.stderr
+        );
+        assert!(out.stdout.contains("Continue from: claude:3f2c9a1e"));
+    }
+
+    /// --continue-from starts a new session, so it conflicts with --resume
+    #[tokio::test]
+    async fn test_run_continue_from_conflicts_with_resume() {
+        let mut h = harness();
+        let out = h
+            .run(&[
+                "aikit",
+                "agent",
+                "run",
+                "--agent",
+                "codex",
+                "--dry-run",
+                "-p",
+                "hello",
+                "--resume",
+                "abc",
+                "--continue-from",
+                "claude:3f2c9a1e",
+            ])
+            .await;
+        assert_ne!(out.exit_code, 0, "--continue-from with --resume must fail");
+    }
+
     #[tokio::test]
     async fn test_session_sync_dry_run_json() {
         let tmp = tempfile::tempdir().unwrap();
@@ -249,6 +298,26 @@ mod tests {
         );
     }
 
+    /// cost rejects an unknown grouping and a missing capture store
+    #[tokio::test]
+    async fn test_cost_validates_arguments() {
+        let mut h = harness();
+        let out = h.run(&["aikit", "cost", "--by", "week"]).await;
+        assert_ne!(out.exit_code, 0, "--by week must fail");
+
+        let tmp = tempfile::tempdir().unwrap();
+        let db = tmp.path().join("missing.db");
+        let out = h
+            .run(&["aikit", "cost", "--db", db.to_str().unwrap()])
+            .await;
+        assert_ne!(out.exit_code, 0, "a missing --db must fail");
+
+        let out = h
+            .run(&["aikit", "cost", "--cache", "--format", "xml"])
+            .await;
+        assert_ne!(out.exit_code, 0, "--cache --format xml must fail");
+    }
+
     /// aikit agent run --dry-run works under new namespace
     #[tokio::test]
     async fn test_agent_run_dry_run() {
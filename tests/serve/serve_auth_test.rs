@@ -16,6 +16,7 @@ async fn start_auth_server(api_key: &str) -> u16 {
         max_sessions: 10,
         api_key: Some(api_key.to_string()),
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn_with_session(vec![], None);
 
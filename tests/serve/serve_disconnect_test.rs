@@ -16,6 +16,7 @@ async fn start_server() -> u16 {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_blocking_stub_run_fn(Duration::from_secs(3));
 
@@ -17,6 +17,7 @@ async fn test_non_loopback_without_api_key_fails_closed() {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn_with_session(vec![], None);
 
@@ -43,6 +44,7 @@ async fn test_non_loopback_with_insecure_flag_starts_anyway() {
         max_sessions: 10,
         api_key: None,
         insecure: true,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn_with_session(vec![], None);
 
@@ -77,6 +79,7 @@ async fn test_non_loopback_with_api_key_starts_fine() {
         max_sessions: 10,
         api_key: Some("mysecret".to_string()),
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn_with_session(vec![], None);
 
@@ -112,6 +115,7 @@ async fn test_loopback_without_api_key_starts_fine() {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn_with_session(vec![], None);
 
@@ -2,15 +2,16 @@
 //!
 //! Contract (see `specs/008-history-backend/contracts/history-api-contract.md`,
 //! gitignored): unknown `{backend}` key → 404 `unknown_backend`; a known
-//! Backend with no history store (e.g. `codex`) → 409 `history_unsupported`;
+//! Backend with no history store (e.g. `gemini`) → 409 `history_unsupported`;
+//! a read-only store (e.g. `codex`) → 409 `mutations_unsupported` on PATCH;
 //! `claude` → 200 against a fixture; `404` vs `200 []` for messages; the
 //! three-state PATCH semantics for `tag`.
 //!
 //! `CLAUDE_CONFIG_DIR`-touching assertions all live in ONE test function
 //! (`test_claude_history_full_flow`) run sequentially within itself, so no
 //! other test in this binary races the process-global env var. The other
-//! tests here (`codex`/unknown-key) never read `CLAUDE_CONFIG_DIR` and are
-//! safe to run in parallel with it.
+//! tests here (`gemini`/`codex`/unknown-key) never read `CLAUDE_CONFIG_DIR`
+//! and are safe to run in parallel with it.
 
 use std::path::Path;
 use std::time::Duration;
@@ -25,6 +26,7 @@ fn make_args(port: u16) -> ServeArgs {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     }
 }
 
@@ -95,41 +97,41 @@ async fn test_unknown_backend_returns_404() {
     assert_eq!(body["backend"], "not-a-real-backend");
 }
 
-// ── codex (known Backend, no history store) → 409 ────────────────────────────
+// ── gemini (known Backend, no history store) → 409 ───────────────────────────
 
 #[tokio::test]
-async fn test_codex_backend_history_routes_return_409() {
+async fn test_gemini_backend_history_routes_return_409() {
     let port = start_server().await;
     let client = reqwest::Client::new();
     let base = format!("http://127.0.0.1:{port}");
     let some_uuid = "550e8400-e29b-41d4-a716-446655440000";
 
     let resp = client
-        .get(format!("{base}/api/v1/history/codex"))
+        .get(format!("{base}/api/v1/history/gemini"))
         .send()
         .await
         .unwrap();
     assert_eq!(resp.status(), 409, "list must be 409, not 404 or empty 200");
     let body: serde_json::Value = resp.json().await.unwrap();
     assert_eq!(body["error"], "history_unsupported");
-    assert_eq!(body["backend"], "codex");
+    assert_eq!(body["backend"], "gemini");
 
     let resp = client
-        .get(format!("{base}/api/v1/history/codex/{some_uuid}"))
+        .get(format!("{base}/api/v1/history/gemini/{some_uuid}"))
         .send()
         .await
         .unwrap();
     assert_eq!(resp.status(), 409);
 
     let resp = client
-        .get(format!("{base}/api/v1/history/codex/{some_uuid}/messages"))
+        .get(format!("{base}/api/v1/history/gemini/{some_uuid}/messages"))
         .send()
         .await
         .unwrap();
     assert_eq!(resp.status(), 409);
 
     let resp = client
-        .patch(format!("{base}/api/v1/history/codex/{some_uuid}"))
+        .patch(format!("{base}/api/v1/history/gemini/{some_uuid}"))
         .json(&serde_json::json!({"rename": "x"}))
         .send()
         .await
@@ -139,6 +141,28 @@ async fn test_codex_backend_history_routes_return_409() {
     assert_eq!(body["error"], "history_unsupported");
 }
 
+// ── codex (read-only history store) → PATCH 409 ──────────────────────────────
+
+#[tokio::test]
+async fn test_codex_history_patch_returns_mutations_unsupported() {
+    let port = start_server().await;
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{port}");
+    let some_uuid = "550e8400-e29b-41d4-a716-446655440000";
+
+    // Gated on the capability before any store access, so this never reads
+    // the real `~/.codex`.
+    let resp = client
+        .patch(format!("{base}/api/v1/history/codex/{some_uuid}"))
+        .json(&serde_json::json!({"rename": "x"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"], "mutations_unsupported");
+}
+
 // ── claude: full flow against a fixture (single CLAUDE_CONFIG_DIR test) ──────
 
 #[tokio::test]
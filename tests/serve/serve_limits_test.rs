@@ -18,6 +18,7 @@ async fn start_server(run_fn: RunFn, max_sessions: usize) -> u16 {
         max_sessions,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
 
     tokio::spawn(async move {
@@ -22,6 +22,7 @@ async fn start_server(max_sessions: usize) -> u16 {
         max_sessions,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_stub_run_fn();
 
@@ -46,6 +46,7 @@ async fn test_signal_killed_run_is_not_reported_as_success() {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     tokio::spawn(async move {
         execute_with_run_fn(args, make_production_run_fn())
@@ -51,6 +51,7 @@ async fn start_server(timeout_secs: u64, path_dir: &std::path::Path) -> u16 {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let run_fn = make_production_run_fn();
 
@@ -16,6 +16,7 @@ async fn start_timeout_server(timeout_secs: u64) -> u16 {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     };
     let stub = make_timeout_stub_run_fn();
 
@@ -27,6 +27,7 @@ fn make_args(port: u16) -> ServeArgs {
         max_sessions: 10,
         api_key: None,
         insecure: false,
+        team: Default::default(),
     }
 }
 
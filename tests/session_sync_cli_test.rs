@@ -1,4 +1,5 @@
-//! CLI-level tests for `aikit session sync` (`execute_sync`).
+//! CLI-level tests for `aikit session sync` (`execute_sync`) and
+//! `aikit session pull` (`execute_pull`).
 //!
 //! Covers the command glue that the in-crate `aikit-session-sync` tests can't
 //! reach: env-var resolution, the fail-closed owner rule, `--format`
@@ -13,7 +14,7 @@
 
 use std::sync::OnceLock;
 
-use aikit::cli::session::{execute_sync, SyncSessionsArgs};
+use aikit::cli::session::{execute_pull, execute_sync, PullSessionsArgs, SyncSessionsArgs};
 use tokio::sync::{Mutex, MutexGuard};
 
 const VARS: &[&str] = &[
@@ -25,6 +26,10 @@ const VARS: &[&str] = &[
     "AIKIT_SYNC_ALLOW_HTTP",
     "AIKIT_SYNC_ENDPOINT_CA_BUNDLE",
     "AIKIT_SYNC_CREDENTIAL_OWNER",
+    "AIKIT_SYNC_KEY_FILE",
+    "AIKIT_SYNC_DIR",
+    "AIKIT_SYNC_WEBDAV_URL",
+    "AIKIT_SCRUB_RULES",
     "RUST_LOG",
 ];
 
@@ -42,6 +47,8 @@ async fn env_lock() -> MutexGuard<'static, ()> {
 /// Defaults: no flags set. Tests override the fields they exercise.
 fn args() -> SyncSessionsArgs {
     SyncSessionsArgs {
+        sink_dir: None,
+        webdav_url: None,
         bucket: None,
         endpoint: None,
         region: None,
@@ -50,6 +57,10 @@ fn args() -> SyncSessionsArgs {
         tools: vec![],
         watch: false,
         dry_run: false,
+        compact: false,
+        key_file: None,
+        scrub_rules: None,
+        scrub_audit: false,
         allow_http: false,
         format: "default".to_string(),
         log_level: None,
@@ -97,6 +108,96 @@ async fn owner_mismatch_fails_closed_returns_2() {
     assert_eq!(execute_sync(a).await.unwrap(), 2);
 }
 
+#[tokio::test]
+async fn unreadable_key_file_returns_2() {
+    let _g = env_lock().await;
+    let dir = tempfile::tempdir().unwrap();
+    std::env::set_var("AIKIT_SYNC_KEY_FILE", dir.path().join("missing.key"));
+    let mut a = args();
+    a.owner = Some("alice".into());
+    a.dry_run = true;
+    assert_eq!(execute_sync(a).await.unwrap(), 2);
+
+    let bad = dir.path().join("bad.key");
+    std::fs::write(&bad, "not-a-key\n").unwrap();
+    let mut a = pull_args();
+    a.owner = Some("alice".into());
+    a.bucket = Some("b".into());
+    a.endpoint = Some("http://127.0.0.1:9000".into());
+    a.allow_http = true;
+    a.key_file = Some(bad);
+    assert_eq!(execute_pull(a).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn invalid_scrub_rules_return_2() {
+    let _g = env_lock().await;
+    let dir = tempfile::tempdir().unwrap();
+    let rules = dir.path().join("scrub.toml");
+    std::fs::write(&rules, "[[rules]]\nlabel = \"x\"\nregex = \"(\"\n").unwrap();
+    std::env::set_var("AIKIT_SCRUB_RULES", &rules);
+    let mut a = args();
+    a.owner = Some("alice".into());
+    a.dry_run = true;
+    assert_eq!(execute_sync(a).await.unwrap(), 2);
+}
+
+/// `--scrub-audit` only reads local sessions: no owner, bucket or sink.
+/// Hermetic like the env fallback test below: `$HOME` points at a temp dir.
+#[cfg(unix)]
+#[tokio::test]
+async fn scrub_audit_needs_no_owner_or_bucket_and_exits_0() {
+    let _g = env_lock().await;
+    let home = tempfile::tempdir().unwrap();
+    let prev_home = std::env::var_os("HOME");
+    std::env::set_var("HOME", home.path());
+    let rules = home.path().join("scrub.toml");
+    std::fs::write(&rules, "[pii]\nemails = true\n").unwrap();
+
+    let mut a = args();
+    a.scrub_audit = true;
+    a.scrub_rules = Some(rules);
+    let code = execute_sync(a).await.unwrap();
+
+    match prev_home {
+        Some(v) => std::env::set_var("HOME", v),
+        None => std::env::remove_var("HOME"),
+    }
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn sink_dir_and_webdav_url_conflict_returns_2() {
+    let _g = env_lock().await;
+    std::env::set_var("AIKIT_SYNC_WEBDAV_URL", "https://dav.example.com/sessions");
+    let mut a = args();
+    a.owner = Some("alice".into());
+    a.sink_dir = Some(std::env::temp_dir());
+    assert_eq!(execute_sync(a).await.unwrap(), 2);
+}
+
+/// A directory backend needs no bucket or endpoint. Hermetic like the env
+/// fallback test below: `$HOME` points at a temp dir.
+#[cfg(unix)]
+#[tokio::test]
+async fn sink_dir_needs_no_bucket_and_exits_0() {
+    let _g = env_lock().await;
+    let home = tempfile::tempdir().unwrap();
+    let prev_home = std::env::var_os("HOME");
+    std::env::set_var("HOME", home.path());
+
+    let mut a = args();
+    a.owner = Some("alice".into());
+    a.sink_dir = Some(home.path().join("share"));
+    let code = execute_sync(a).await.unwrap();
+
+    match prev_home {
+        Some(v) => std::env::set_var("HOME", v),
+        None => std::env::remove_var("HOME"),
+    }
+    assert_eq!(code, 0);
+}
+
 /// Config supplied entirely through env (no flags) resolves and a dry run
 /// completes with exit 0 — proving the env fallbacks are wired. Unix-only and
 /// hermetic: `$HOME` is redirected to a temp dir so the state store and the
@@ -128,3 +229,54 @@ async fn env_fallbacks_resolve_and_dry_run_exits_0() {
         "env-resolved dry run over an empty home should succeed"
     );
 }
+
+/// Pull defaults: no flags set. Tests override the fields they exercise.
+fn pull_args() -> PullSessionsArgs {
+    PullSessionsArgs {
+        sink_dir: None,
+        webdav_url: None,
+        bucket: None,
+        endpoint: None,
+        region: None,
+        owner: None,
+        key_prefix: None,
+        tool: None,
+        session: None,
+        out: None,
+        restore: false,
+        overwrite: false,
+        ingest: false,
+        key_file: None,
+        allow_http: false,
+        format: "default".to_string(),
+        log_level: None,
+        log_format: "text".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn pull_without_owner_returns_2() {
+    let _g = env_lock().await;
+    let mut a = pull_args();
+    a.bucket = Some("b".into());
+    a.endpoint = Some("http://127.0.0.1:9000".into());
+    assert_eq!(execute_pull(a).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn pull_without_bucket_and_endpoint_returns_2() {
+    let _g = env_lock().await;
+    let mut a = pull_args();
+    a.owner = Some("alice".into());
+    assert_eq!(execute_pull(a).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn pull_restore_with_out_returns_2() {
+    let _g = env_lock().await;
+    let mut a = pull_args();
+    a.owner = Some("alice".into());
+    a.restore = true;
+    a.out = Some("elsewhere".into());
+    assert_eq!(execute_pull(a).await.unwrap(), 2);
+}
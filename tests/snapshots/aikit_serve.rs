@@ -21,6 +21,9 @@ mod tests {
             "--max-sessions",
             "--api-key",
             "--insecure",
+            "--team-tokens",
+            "--team-dir",
+            "--team-url",
         ] {
             assert!(
                 stdout.contains(flag),